@@ -0,0 +1,52 @@
+// Copyright 2020. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use tari_core::{base_node::comms_interface::InputCovenantEvaluation, covenants::CovenantFilterTrace};
+
+use crate::tari_rpc as grpc;
+
+impl From<InputCovenantEvaluation> for grpc::InputCovenantResult {
+    fn from(evaluation: InputCovenantEvaluation) -> Self {
+        let trace = evaluation.trace;
+        Self {
+            input_index: evaluation.input_index as u64,
+            output_hash: evaluation.output_hash,
+            covenant: evaluation.covenant.map(|c| c.to_string()).unwrap_or_default(),
+            passed: trace.is_pass(),
+            failure_reason: trace.error.unwrap_or_default(),
+            output_set_size: trace.output_set_size as u64,
+            filters: trace.filters.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<CovenantFilterTrace> for grpc::CovenantFilterResult {
+    fn from(trace: CovenantFilterTrace) -> Self {
+        Self {
+            filter: trace.filter.to_string(),
+            depth: trace.depth as u64,
+            input_set_size: trace.input_set_size as u64,
+            eliminated_outputs: trace.eliminated_outputs.into_iter().map(|i| i as u64).collect(),
+            output_set_size: trace.output_set_size as u64,
+        }
+    }
+}
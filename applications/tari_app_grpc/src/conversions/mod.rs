@@ -27,6 +27,7 @@ mod block_header;
 mod chain_metadata;
 mod com_signature;
 mod consensus_constants;
+mod covenant_trace;
 mod historical_block;
 mod new_block_template;
 mod output_features;
@@ -50,6 +51,7 @@ pub use self::{
     chain_metadata::*,
     com_signature::*,
     consensus_constants::*,
+    covenant_trace::*,
     historical_block::*,
     new_block_template::*,
     output_features::*,
@@ -47,12 +47,19 @@ pub fn init_configuration(
                 );
                 global_config.network = network;
                 global_config.data_dir = PathBuf::from(str);
-                if let DatabaseType::LMDB(_) = global_config.db_type {
-                    global_config.db_type = DatabaseType::LMDB(global_config.data_dir.join("db"));
+                match global_config.db_type {
+                    DatabaseType::LMDB(_) => {
+                        global_config.db_type = DatabaseType::LMDB(global_config.data_dir.join("db"));
+                    },
+                    DatabaseType::Sled(_) => {
+                        global_config.db_type = DatabaseType::Sled(global_config.data_dir.join("sled_db"));
+                    },
+                    DatabaseType::Memory => {},
                 }
                 global_config.peer_db_path = global_config.data_dir.join("peer_db");
                 global_config.wallet_peer_db_path = global_config.data_dir.join("wallet_peer_db");
                 global_config.console_wallet_peer_db_path = global_config.data_dir.join("console_wallet_peer_db");
+                global_config.mempool_persistence_file = global_config.data_dir.join("mempool.dat");
             },
             Err(e) => {
                 log::error!(target: LOG_TARGET, "Network selection was invalid, exiting.");
@@ -74,13 +81,22 @@ fn check_file_paths(config: &mut GlobalConfig, bootstrap: &ConfigBootstrap) {
     let prepend = bootstrap.base_path.clone();
     if !config.data_dir.is_absolute() {
         config.data_dir = concatenate_paths_normalized(prepend.clone(), config.data_dir.clone());
-        if let DatabaseType::LMDB(_) = config.db_type {
-            config.db_type = DatabaseType::LMDB(config.data_dir.join("db"));
+        match config.db_type {
+            DatabaseType::LMDB(_) => config.db_type = DatabaseType::LMDB(config.data_dir.join("db")),
+            DatabaseType::Sled(_) => config.db_type = DatabaseType::Sled(config.data_dir.join("sled_db")),
+            DatabaseType::Memory => {},
         }
     }
+    if let Some(path) = config.db_migration_source_path.as_ref().filter(|p| !p.is_absolute()) {
+        config.db_migration_source_path = Some(concatenate_paths_normalized(prepend.clone(), path.clone()));
+    }
     if !config.peer_db_path.is_absolute() {
         config.peer_db_path = concatenate_paths_normalized(prepend.clone(), config.peer_db_path.clone());
     }
+    if !config.mempool_persistence_file.is_absolute() {
+        config.mempool_persistence_file =
+            concatenate_paths_normalized(prepend.clone(), config.mempool_persistence_file.clone());
+    }
     if !config.base_node_identity_file.is_absolute() {
         config.base_node_identity_file =
             concatenate_paths_normalized(prepend.clone(), config.base_node_identity_file.clone());
@@ -20,10 +20,11 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{convert::TryFrom, str::FromStr, sync::Arc};
+use std::{convert::TryFrom, path::Path, str::FromStr, sync::Arc};
 
 use futures::future::Either;
 use log::*;
+use rpassword::prompt_password_stdout;
 use tari_common::{
     exit_codes::{ExitCode, ExitError},
     CommsTransport,
@@ -44,7 +45,10 @@ use tari_comms::{
     types::CommsPublicKey,
     utils::multiaddr::multiaddr_to_socketaddr,
 };
-use tari_p2p::transport::{TorConfig, TransportType};
+use tari_p2p::{
+    initialization::is_peer_database_encrypted,
+    transport::{TorConfig, TransportType},
+};
 use tari_utilities::hex::Hex;
 use thiserror::Error;
 use tokio::{runtime, runtime::Runtime};
@@ -131,6 +135,8 @@ pub fn create_transport_type(config: &GlobalConfig) -> TransportType {
             },
             listener_address,
         },
+        CommsTransport::WebSocket { listener_address } => TransportType::WebSocket { listener_address },
+        CommsTransport::Quic { listener_address } => TransportType::Quic { listener_address },
     }
 }
 
@@ -177,6 +183,47 @@ pub fn setup_runtime(config: &GlobalConfig) -> Result<Runtime, ExitError> {
     })
 }
 
+/// Returns the passphrase for the peer database, taken from the command line or env var, then the config file. If
+/// neither is set and the peer database at `peer_db_path` is encrypted, the user is prompted for it unless running in
+/// non-interactive mode.
+pub fn get_or_prompt_peer_db_passphrase(
+    arg_passphrase: Option<String>,
+    config_passphrase: Option<String>,
+    peer_db_path: &Path,
+    non_interactive: bool,
+) -> Result<Option<String>, ExitError> {
+    if arg_passphrase.is_some() {
+        return Ok(arg_passphrase);
+    }
+    if config_passphrase.is_some() {
+        return Ok(config_passphrase);
+    }
+
+    let is_encrypted = is_peer_database_encrypted(peer_db_path, "peers")
+        .map_err(|err| ExitError::new(ExitCode::DatabaseError, err))?;
+    if !is_encrypted {
+        return Ok(None);
+    }
+    if non_interactive {
+        return Err(ExitError::new(
+            ExitCode::IncorrectOrEmptyPassword,
+            "The peer database is encrypted, provide the passphrase with TARI_PEER_DB_PASSPHRASE",
+        ));
+    }
+
+    let passphrase = loop {
+        let passphrase = prompt_password_stdout("Peer database passphrase: ")
+            .map_err(|err| ExitError::new(ExitCode::IOError, err))?;
+        if passphrase.is_empty() {
+            println!("Passphrase cannot be empty!");
+            continue;
+        }
+        break passphrase;
+    };
+
+    Ok(Some(passphrase))
+}
+
 /// Returns a CommsPublicKey from either a emoji id or a public key
 pub fn parse_emoji_id_or_public_key(key: &str) -> Option<CommsPublicKey> {
     EmojiId::str_to_pubkey(&key.trim().replace('|', ""))
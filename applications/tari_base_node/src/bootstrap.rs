@@ -26,7 +26,12 @@ use anyhow::anyhow;
 use log::*;
 use tari_app_utilities::{consts, identity_management, utilities::create_transport_type};
 use tari_common::{configuration::bootstrap::ApplicationType, GlobalConfig};
-use tari_comms::{peer_manager::Peer, protocol::rpc::RpcServer, NodeIdentity, UnspawnedCommsNode};
+use tari_comms::{
+    peer_manager::Peer,
+    protocol::rpc::{RpcQuota, RpcServer},
+    NodeIdentity,
+    UnspawnedCommsNode,
+};
 use tari_comms_dht::{store_forward::SafConfig, DbConnectionUrl, Dht, DhtConfig};
 use tari_core::{
     base_node,
@@ -181,7 +186,8 @@ where B: BlockchainBackend + 'static
         // Save final node identity after comms has initialized. This is required because the public_address can be
         // changed by comms during initialization when using tor.
         match transport_type {
-            TransportType::Tcp { .. } => {}, // Do not overwrite TCP public_address in the base_node_id!
+            // Do not overwrite TCP public_address in the base_node_id!
+            TransportType::Tcp { .. } | TransportType::WebSocket { .. } | TransportType::Quic { .. } => {},
             _ => {
                 identity_management::save_as_json(&config.base_node_identity_file, &*comms.node_identity()).map_err(
                     |e| {
@@ -228,6 +234,14 @@ where B: BlockchainBackend + 'static
                 builder.with_unlimited_simultaneous_sessions()
             },
         };
+        let builder = match config.rpc_peer_quota_capacity {
+            0 => builder,
+            capacity => builder.with_peer_quota(RpcQuota::new(capacity, config.rpc_peer_quota_refill_per_sec)),
+        };
+        let builder = match config.rpc_method_quota_capacity {
+            0 => builder,
+            capacity => builder.with_method_quota(RpcQuota::new(capacity, config.rpc_method_quota_refill_per_sec)),
+        };
         let rpc_server = builder.finish();
         handles.register(rpc_server.get_handle());
 
@@ -258,6 +272,7 @@ where B: BlockchainBackend + 'static
             auxilary_tcp_listener_address: self.config.auxilary_tcp_listener_address.clone(),
             datastore_path: self.config.peer_db_path.clone(),
             peer_database_name: "peers".to_string(),
+            database_passphrase: self.config.peer_db_passphrase.clone(),
             max_concurrent_inbound_tasks: 50,
             max_concurrent_outbound_tasks: 100,
             outbound_buffer_size: 100,
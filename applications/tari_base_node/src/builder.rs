@@ -28,7 +28,15 @@ use tari_comms::{peer_manager::NodeIdentity, protocol::rpc::RpcServerHandle, Com
 use tari_comms_dht::Dht;
 use tari_core::{
     base_node::{state_machine_service::states::StatusInfo, LocalNodeCommsInterface, StateMachineHandle},
-    chain_storage::{create_lmdb_database, BlockchainDatabase, BlockchainDatabaseConfig, LMDBDatabase, Validators},
+    chain_storage::{
+        create_lmdb_database,
+        create_sled_database,
+        BlockchainBackend,
+        BlockchainDatabase,
+        BlockchainDatabaseConfig,
+        SledConfig,
+        Validators,
+    },
     consensus::ConsensusManager,
     mempool::{service::LocalMempoolService, Mempool, MempoolConfig},
     proof_of_work::randomx_factory::RandomXFactory,
@@ -60,10 +68,11 @@ const LOG_TARGET: &str = "c::bn::initialization";
 pub struct BaseNodeContext {
     config: Arc<GlobalConfig>,
     consensus_rules: ConsensusManager,
-    blockchain_db: BlockchainDatabase<LMDBDatabase>,
+    blockchain_db: BlockchainDatabase<Box<dyn BlockchainBackend>>,
     base_node_comms: CommsNode,
     base_node_dht: Dht,
     base_node_handles: ServiceHandles,
+    mempool: Mempool,
 }
 
 impl BaseNodeContext {
@@ -74,12 +83,32 @@ impl BaseNodeContext {
         info!(target: LOG_TARGET, "Tari base node has STARTED");
 
         self.state_machine().shutdown_signal().wait().await;
+        if self.config.mempool_persistence_enabled {
+            self.persist_mempool().await;
+        }
         info!(target: LOG_TARGET, "Waiting for communications stack shutdown");
 
         self.base_node_comms.wait_until_shutdown().await;
         info!(target: LOG_TARGET, "Communications stack has shutdown");
     }
 
+    async fn persist_mempool(&self) {
+        let path = self.config.mempool_persistence_file.clone();
+        match self
+            .mempool
+            .persist_to_file(path.clone(), self.config.mempool_persistence_max_transactions)
+            .await
+        {
+            Ok(num_txs) => info!(
+                target: LOG_TARGET,
+                "Persisted {} mempool transaction(s) to {}",
+                num_txs,
+                path.display()
+            ),
+            Err(err) => error!(target: LOG_TARGET, "Failed to persist mempool: {}", err),
+        }
+    }
+
     /// Return the node config
     pub fn config(&self) -> Arc<GlobalConfig> {
         self.config.clone()
@@ -131,7 +160,7 @@ impl BaseNodeContext {
     }
 
     /// Returns a BlockchainDatabase handle
-    pub fn blockchain_db(&self) -> BlockchainDatabase<LMDBDatabase> {
+    pub fn blockchain_db(&self) -> BlockchainDatabase<Box<dyn BlockchainBackend>> {
         self.blockchain_db.clone()
     }
 
@@ -167,7 +196,7 @@ pub async fn configure_and_initialize_node(
     interrupt_signal: ShutdownSignal,
     cleanup_orphans_at_startup: bool,
 ) -> Result<BaseNodeContext, anyhow::Error> {
-    let result = match &config.db_type {
+    let backend: Box<dyn BlockchainBackend> = match &config.db_type {
         DatabaseType::Memory => {
             // let backend = MemoryDatabase::<HashDigest>::default();
             // build_node_context(
@@ -181,19 +210,25 @@ pub async fn configure_and_initialize_node(
             // .await?
             unimplemented!();
         },
-        DatabaseType::LMDB(p) => {
-            let backend = create_lmdb_database(&p, config.db_config.clone())?;
-            build_node_context(
-                backend,
-                node_identity,
-                config,
-                interrupt_signal,
-                cleanup_orphans_at_startup,
-            )
-            .await?
-        },
+        DatabaseType::LMDB(p) => Box::new(create_lmdb_database(
+            &p,
+            config.db_config.clone(),
+            config.enable_utxo_search_indexes,
+        )?),
+        DatabaseType::Sled(p) => Box::new(create_sled_database(
+            &p,
+            SledConfig::new_from_mb(config.sled_cache_capacity_mb, config.sled_flush_every_ms),
+            config.enable_utxo_search_indexes,
+        )?),
     };
-    Ok(result)
+    build_node_context(
+        backend,
+        node_identity,
+        config,
+        interrupt_signal,
+        cleanup_orphans_at_startup,
+    )
+    .await
 }
 
 /// Constructs the base node context, this includes setting up the consensus manager, mempool, base node
@@ -208,7 +243,7 @@ pub async fn configure_and_initialize_node(
 /// ## Returns
 /// Result containing the BaseNodeContext, String will contain the reason on error
 async fn build_node_context(
-    backend: LMDBDatabase,
+    backend: Box<dyn BlockchainBackend>,
     base_node_identity: Arc<NodeIdentity>,
     config: Arc<GlobalConfig>,
     interrupt_signal: ShutdownSignal,
@@ -255,6 +290,15 @@ async fn build_node_context(
         Box::new(TxConsensusValidator::new(blockchain_db.clone())),
     ]);
     let mempool = Mempool::new(MempoolConfig::default(), rules.clone(), Box::new(mempool_validator));
+    if config.mempool_persistence_enabled {
+        match mempool.restore_from_file(config.mempool_persistence_file.clone()).await {
+            Ok(num_restored) => info!(
+                target: LOG_TARGET,
+                "Restored {} transaction(s) from the persisted mempool", num_restored
+            ),
+            Err(err) => warn!(target: LOG_TARGET, "Failed to restore persisted mempool: {}", err),
+        }
+    }
 
     //---------------------------------- Base Node  --------------------------------------------//
     debug!(target: LOG_TARGET, "Creating base node state machine.");
@@ -263,7 +307,7 @@ async fn build_node_context(
         config: &config,
         node_identity: base_node_identity,
         db: blockchain_db.clone(),
-        mempool,
+        mempool: mempool.clone(),
         rules: rules.clone(),
         factories: factories.clone(),
         interrupt_signal: interrupt_signal.clone(),
@@ -281,5 +325,6 @@ async fn build_node_context(
         base_node_comms,
         base_node_dht,
         base_node_handles,
+        mempool,
     })
 }
@@ -52,7 +52,7 @@ use tari_core::{
         LocalNodeCommsInterface,
     },
     blocks::{BlockHeader, ChainHeader},
-    chain_storage::{async_db::AsyncBlockchainDb, LMDBDatabase},
+    chain_storage::{async_db::AsyncBlockchainDb, BlockchainBackend},
     consensus::ConsensusManager,
     mempool::service::LocalMempoolService,
     proof_of_work::PowAlgorithm,
@@ -81,13 +81,14 @@ pub enum StatusOutput {
 pub struct CommandHandler {
     config: Arc<GlobalConfig>,
     consensus_rules: ConsensusManager,
-    blockchain_db: AsyncBlockchainDb<LMDBDatabase>,
+    blockchain_db: AsyncBlockchainDb<Box<dyn BlockchainBackend>>,
     discovery_service: DhtDiscoveryRequester,
     dht_metrics_collector: MetricsCollectorHandle,
     rpc_server: RpcServerHandle,
     base_node_identity: Arc<NodeIdentity>,
     peer_manager: Arc<PeerManager>,
     connectivity: ConnectivityRequester,
+    reputation_half_life: Duration,
     liveness: LivenessHandle,
     node_service: LocalNodeCommsInterface,
     mempool_service: LocalMempoolService,
@@ -108,6 +109,7 @@ impl CommandHandler {
             base_node_identity: ctx.base_node_identity(),
             peer_manager: ctx.base_node_comms().peer_manager(),
             connectivity: ctx.base_node_comms().connectivity(),
+            reputation_half_life: ctx.base_node_comms().connectivity_config().reputation_half_life,
             liveness: ctx.liveness(),
             node_service: ctx.local_node(),
             mempool_service: ctx.local_mempool(),
@@ -417,6 +419,14 @@ impl CommandHandler {
         if let Some(updated_at) = peer.identity_signature.map(|i| i.updated_at()) {
             println!("Last updated: {} (UTC)", updated_at);
         }
+        if peer.reputation.updated_at().is_some() {
+            println!(
+                "Reputation: {} ({} ban(s))",
+                peer.reputation
+                    .score_at(Utc::now().naive_utc(), self.reputation_half_life),
+                peer.reputation.num_bans()
+            );
+        }
     }
 
     pub async fn list_peers(&self, filter: Option<String>) -> Result<(), Error> {
@@ -479,6 +489,16 @@ impl CommandHandler {
                     s.push(format!("updated_at: {} (UTC)", updated_at));
                 }
 
+                if peer.reputation.updated_at().is_some() {
+                    let score = peer
+                        .reputation
+                        .score_at(Utc::now().naive_utc(), self.reputation_half_life);
+                    match peer.reputation.num_bans() {
+                        0 => s.push(format!("reputation: {}", score)),
+                        n => s.push(format!("reputation: {} ({} ban(s))", score, n)),
+                    }
+                }
+
                 if s.is_empty() {
                     "--".to_string()
                 } else {
@@ -716,7 +736,7 @@ impl CommandHandler {
 
     /// Function to process the get-headers command
     async fn get_chain_headers(
-        blockchain_db: &AsyncBlockchainDb<LMDBDatabase>,
+        blockchain_db: &AsyncBlockchainDb<Box<dyn BlockchainBackend>>,
         start: u64,
         end: Option<u64>,
     ) -> Result<Vec<ChainHeader>, anyhow::Error> {
@@ -1020,30 +1040,47 @@ impl CommandHandler {
             "Est. Size (MiB)",
             "% of total",
         ]);
-        let total_db_size = stats.db_stats().iter().map(|s| s.total_page_size()).sum::<usize>();
-        stats.db_stats().iter().for_each(|stat| {
-            table.add_row(row![
+        let total_db_size = stats
+            .db_stats()
+            .iter()
+            .map(|s| s.total_page_size())
+            .sum::<Option<usize>>()
+            .or_else(|| stats.size_on_disk().map(|size| size as usize))
+            .unwrap_or_default();
+        stats.db_stats().iter().for_each(|stat| match stat.pages {
+            Some(pages) => table.add_row(row![
                 stat.name,
                 stat.entries,
-                stat.depth,
-                stat.branch_pages,
-                stat.leaf_pages,
-                stat.overflow_pages,
-                format!("{:.2}", stat.total_page_size() as f32 / BYTES_PER_MB as f32),
-                format!("{:.2}%", (stat.total_page_size() as f32 / total_db_size as f32) * 100.0)
-            ]);
+                pages.depth,
+                pages.branch_pages,
+                pages.leaf_pages,
+                pages.overflow_pages,
+                format!("{:.2}", pages.total_page_size() as f32 / BYTES_PER_MB as f32),
+                format!(
+                    "{:.2}%",
+                    (pages.total_page_size() as f32 / total_db_size as f32) * 100.0
+                )
+            ]),
+            None => table.add_row(row![stat.name, stat.entries, "n/a", "n/a", "n/a", "n/a", "n/a", "n/a"]),
         });
 
         table.print_stdout();
         println!();
-        println!(
-            "{} databases, {:.2} MiB used ({:.2}%), page size: {} bytes, env_info = ({})",
-            stats.root().entries,
-            total_db_size as f32 / BYTES_PER_MB as f32,
-            (total_db_size as f32 / stats.env_info().mapsize as f32) * 100.0,
-            stats.root().psize as usize,
-            stats.env_info()
-        );
+        match (stats.root().pages, stats.env_info()) {
+            (Some(pages), Some(env_info)) => println!(
+                "{} databases, {:.2} MiB used ({:.2}%), page size: {} bytes, env_info = ({})",
+                stats.root().entries,
+                total_db_size as f32 / BYTES_PER_MB as f32,
+                (total_db_size as f32 / env_info.mapsize as f32) * 100.0,
+                pages.psize as usize,
+                env_info
+            ),
+            _ => println!(
+                "{} databases, {:.2} MiB on disk",
+                stats.root().entries,
+                total_db_size as f32 / BYTES_PER_MB as f32,
+            ),
+        }
 
         println!();
         println!("Totalling DB entry sizes. This may take a few seconds...");
@@ -1072,7 +1109,7 @@ impl CommandHandler {
         table.print_stdout();
         println!();
         println!(
-            "Total blockchain data size: {:.2} MiB ({:.2} % of LMDB map size)",
+            "Total blockchain data size: {:.2} MiB ({:.2} % of database size)",
             total_data_size as f32 / BYTES_PER_MB as f32,
             (total_data_size as f32 / total_db_size as f32) * 100.0
         );
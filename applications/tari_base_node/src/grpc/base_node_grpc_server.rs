@@ -23,6 +23,7 @@
 use std::{
     cmp,
     convert::{TryFrom, TryInto},
+    ops::Range,
 };
 
 use either::Either;
@@ -43,13 +44,14 @@ use tari_core::{
         StateMachineHandle,
     },
     blocks::{Block, BlockHeader, NewBlockTemplate},
-    chain_storage::{ChainStorageError, PrunedOutput},
+    chain_storage::{ChainStorageError, PrunedOutput, UtxoMinedInfo},
     consensus::{emission::Emission, ConsensusManager, NetworkConsensus},
     iterators::NonOverlappingIntegerPairIter,
     mempool::{service::LocalMempoolService, TxStorageResponse},
     proof_of_work::PowAlgorithm,
     transactions::transaction_components::Transaction,
 };
+use tari_crypto::script::TariScript;
 use tari_p2p::{auto_update::SoftwareUpdaterHandle, services::liveness::LivenessHandle};
 use tari_utilities::{hex::Hex, message_format::MessageFormat, ByteArray, Hashable};
 use tokio::task;
@@ -79,6 +81,10 @@ const LIST_HEADERS_MAX_NUM_HEADERS: u64 = 10_000;
 const LIST_HEADERS_PAGE_SIZE: usize = 10;
 // The `num_headers` value if none is provided.
 const LIST_HEADERS_DEFAULT_NUM_HEADERS: u64 = 10;
+// The maximum number of outputs a client can request per SearchUtxosByScript or SearchUtxosBySenderOffsetPublicKey
+// call. This is also the number of outputs returned if the client requests a count of zero.
+const SEARCH_UTXOS_MAX_COUNT: u64 = 1_000;
+const SEARCH_UTXOS_PAGE_SIZE: usize = 100;
 
 pub struct BaseNodeGrpcServer {
     node_service: LocalNodeCommsInterface,
@@ -89,6 +95,7 @@ pub struct BaseNodeGrpcServer {
     software_updater: SoftwareUpdaterHandle,
     comms: CommsNode,
     liveness: LivenessHandle,
+    utxo_search_indexes_enabled: bool,
 }
 
 impl BaseNodeGrpcServer {
@@ -102,6 +109,18 @@ impl BaseNodeGrpcServer {
             software_updater: ctx.software_updater(),
             comms: ctx.base_node_comms().clone(),
             liveness: ctx.liveness(),
+            utxo_search_indexes_enabled: ctx.config().enable_utxo_search_indexes,
+        }
+    }
+
+    fn check_utxo_search_indexes_enabled(&self) -> Result<(), Status> {
+        if self.utxo_search_indexes_enabled {
+            Ok(())
+        } else {
+            Err(Status::unimplemented(
+                "UTXO search indexes are disabled on this base node. Set enable_utxo_search_indexes to true to enable \
+                 them.",
+            ))
         }
     }
 }
@@ -125,6 +144,9 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
     type ListAssetRegistrationsStream = mpsc::Receiver<Result<tari_rpc::ListAssetRegistrationsResponse, Status>>;
     type ListHeadersStream = mpsc::Receiver<Result<tari_rpc::BlockHeader, Status>>;
     type SearchKernelsStream = mpsc::Receiver<Result<tari_rpc::HistoricalBlock, Status>>;
+    type SearchUtxosByScriptStream = mpsc::Receiver<Result<tari_rpc::SearchUtxosByScriptResponse, Status>>;
+    type SearchUtxosBySenderOffsetPublicKeyStream =
+        mpsc::Receiver<Result<tari_rpc::SearchUtxosByScriptResponse, Status>>;
     type SearchUtxosStream = mpsc::Receiver<Result<tari_rpc::HistoricalBlock, Status>>;
 
     async fn get_network_difficulty(
@@ -759,8 +781,10 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
             Status::internal(e.to_string())
         })?;
         let response = match res {
-            TxStorageResponse::UnconfirmedPool => tari_rpc::SubmitTransactionResponse {
-                result: tari_rpc::SubmitTransactionResult::Accepted.into(),
+            TxStorageResponse::UnconfirmedPool | TxStorageResponse::UnconfirmedPoolReplaced => {
+                tari_rpc::SubmitTransactionResponse {
+                    result: tari_rpc::SubmitTransactionResult::Accepted.into(),
+                }
             },
             TxStorageResponse::ReorgPool | TxStorageResponse::NotStoredAlreadySpent => {
                 tari_rpc::SubmitTransactionResponse {
@@ -770,6 +794,11 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
             TxStorageResponse::NotStored |
             TxStorageResponse::NotStoredOrphan |
             TxStorageResponse::NotStoredConsensus |
+            TxStorageResponse::NotStoredFeeTooLowForReplacement |
+            TxStorageResponse::NotStoredTooManyReplacements |
+            TxStorageResponse::NotStoredReplacementRejected |
+            TxStorageResponse::NotStoredFeeTooLow |
+            TxStorageResponse::NotStoredTooManyAncestors |
             TxStorageResponse::NotStoredTimeLocked => tari_rpc::SubmitTransactionResponse {
                 result: tari_rpc::SubmitTransactionResult::Rejected.into(),
             },
@@ -779,6 +808,42 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
         Ok(Response::new(response))
     }
 
+    async fn dry_run_covenants(
+        &self,
+        request: Request<tari_rpc::DryRunCovenantsRequest>,
+    ) -> Result<Response<tari_rpc::DryRunCovenantsResponse>, Status> {
+        let request = request.into_inner();
+        let txn: Transaction = request
+            .transaction
+            .ok_or_else(|| Status::invalid_argument("Transaction is empty"))?
+            .try_into()
+            .map_err(|e| Status::invalid_argument(format!("Failed to convert arguments. Invalid transaction.{}", e)))?;
+        debug!(
+            target: LOG_TARGET,
+            "Received DryRunCovenants request from client ({} inputs, {} outputs)",
+            txn.body.inputs().len(),
+            txn.body.outputs().len()
+        );
+
+        let mut handler = self.node_service.clone();
+        let (height, inputs) = handler.evaluate_covenants(txn).await.map_err(|e| {
+            error!(target: LOG_TARGET, "Error evaluating covenants: {}", e);
+            Status::internal(e.to_string())
+        })?;
+        let inputs = inputs
+            .into_iter()
+            .map(tari_rpc::InputCovenantResult::from)
+            .collect::<Vec<_>>();
+        let passed = inputs.iter().all(|input| input.passed);
+
+        debug!(target: LOG_TARGET, "Sending DryRunCovenants response to client");
+        Ok(Response::new(tari_rpc::DryRunCovenantsResponse {
+            height,
+            passed,
+            inputs,
+        }))
+    }
+
     async fn transaction_state(
         &self,
         request: Request<tari_rpc::TransactionStateRequest>,
@@ -827,8 +892,10 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
                 Status::internal(e.to_string())
             })?;
         let response = match res {
-            TxStorageResponse::UnconfirmedPool => tari_rpc::TransactionStateResponse {
-                result: tari_rpc::TransactionLocation::Mempool.into(),
+            TxStorageResponse::UnconfirmedPool | TxStorageResponse::UnconfirmedPoolReplaced => {
+                tari_rpc::TransactionStateResponse {
+                    result: tari_rpc::TransactionLocation::Mempool.into(),
+                }
             },
             TxStorageResponse::ReorgPool | TxStorageResponse::NotStoredAlreadySpent => {
                 tari_rpc::TransactionStateResponse {
@@ -839,6 +906,11 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
             },
             TxStorageResponse::NotStored |
             TxStorageResponse::NotStoredConsensus |
+            TxStorageResponse::NotStoredFeeTooLowForReplacement |
+            TxStorageResponse::NotStoredTooManyReplacements |
+            TxStorageResponse::NotStoredReplacementRejected |
+            TxStorageResponse::NotStoredFeeTooLow |
+            TxStorageResponse::NotStoredTooManyAncestors |
             TxStorageResponse::NotStoredOrphan |
             TxStorageResponse::NotStoredTimeLocked => tari_rpc::TransactionStateResponse {
                 result: tari_rpc::TransactionLocation::NotStored.into(),
@@ -1098,6 +1170,63 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
         Ok(Response::new(rx))
     }
 
+    async fn search_utxos_by_script(
+        &self,
+        request: Request<tari_rpc::SearchUtxosByScriptRequest>,
+    ) -> Result<Response<Self::SearchUtxosByScriptStream>, Status> {
+        debug!(target: LOG_TARGET, "Incoming GRPC request for SearchUtxosByScript");
+        self.check_utxo_search_indexes_enabled()?;
+        let request = request.into_inner();
+
+        let script = TariScript::from_bytes(&request.script)
+            .map_err(|err| Status::invalid_argument(format!("Malformed script: {}", err)))?;
+        let range = search_utxos_range(request.offset, request.count)?;
+
+        let mut handler = self.node_service.clone();
+        let (tx, rx) = mpsc::channel(SEARCH_UTXOS_PAGE_SIZE);
+        task::spawn(async move {
+            let outputs = handler.fetch_unspent_outputs_by_script(script, range).await;
+            send_unspent_outputs(outputs, tx).await;
+        });
+
+        debug!(
+            target: LOG_TARGET,
+            "Sending SearchUtxosByScript response stream to client"
+        );
+        Ok(Response::new(rx))
+    }
+
+    async fn search_utxos_by_sender_offset_public_key(
+        &self,
+        request: Request<tari_rpc::SearchUtxosBySenderOffsetPublicKeyRequest>,
+    ) -> Result<Response<Self::SearchUtxosBySenderOffsetPublicKeyStream>, Status> {
+        debug!(
+            target: LOG_TARGET,
+            "Incoming GRPC request for SearchUtxosBySenderOffsetPublicKey"
+        );
+        self.check_utxo_search_indexes_enabled()?;
+        let request = request.into_inner();
+
+        let sender_offset_public_key = PublicKey::from_bytes(&request.sender_offset_public_key)
+            .map_err(|err| Status::invalid_argument(format!("Malformed sender offset public key: {}", err)))?;
+        let range = search_utxos_range(request.offset, request.count)?;
+
+        let mut handler = self.node_service.clone();
+        let (tx, rx) = mpsc::channel(SEARCH_UTXOS_PAGE_SIZE);
+        task::spawn(async move {
+            let outputs = handler
+                .fetch_unspent_outputs_by_sender_offset_public_key(sender_offset_public_key, range)
+                .await;
+            send_unspent_outputs(outputs, tx).await;
+        });
+
+        debug!(
+            target: LOG_TARGET,
+            "Sending SearchUtxosBySenderOffsetPublicKey response stream to client"
+        );
+        Ok(Response::new(rx))
+    }
+
     #[allow(clippy::useless_conversion)]
     async fn fetch_matching_utxos(
         &self,
@@ -1494,6 +1623,29 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
 
         Ok(Response::new(response))
     }
+
+    async fn estimate_fee_per_gram(
+        &self,
+        request: Request<tari_rpc::EstimateFeePerGramRequest>,
+    ) -> Result<Response<tari_rpc::EstimateFeePerGramResponse>, Status> {
+        let request = request.into_inner();
+        let mut mempool_handle = self.mempool_service.clone();
+
+        let estimate = mempool_handle
+            .estimate_fee_per_gram(request.target_blocks)
+            .await
+            .map_err(|e| {
+                error!(target: LOG_TARGET, "Error submitting query:{}", e);
+                Status::internal(e.to_string())
+            })?;
+
+        let response = tari_rpc::EstimateFeePerGramResponse {
+            fee_per_gram: estimate.map(|fee| fee.as_u64()).unwrap_or_default(),
+            has_estimate: estimate.is_some(),
+        };
+
+        Ok(Response::new(response))
+    }
 }
 
 enum BlockGroupType {
@@ -1551,3 +1703,55 @@ async fn get_block_group(
         calc_type: calc_type_response,
     }))
 }
+
+/// Converts the requested page of a UTXO search into a range of matching outputs. A `count` of zero requests the
+/// maximum page size.
+fn search_utxos_range(offset: u64, count: u64) -> Result<Range<usize>, Status> {
+    let count = match count {
+        0 => SEARCH_UTXOS_MAX_COUNT,
+        count => cmp::min(count, SEARCH_UTXOS_MAX_COUNT),
+    };
+    let end = offset
+        .checked_add(count)
+        .ok_or_else(|| Status::invalid_argument("offset + count overflows"))?;
+    let start = usize::try_from(offset).map_err(|_| Status::invalid_argument("offset is too large"))?;
+    let end = usize::try_from(end).map_err(|_| Status::invalid_argument("offset + count is too large"))?;
+    Ok(start..end)
+}
+
+async fn send_unspent_outputs(
+    outputs: Result<Vec<UtxoMinedInfo>, CommsInterfaceError>,
+    mut tx: mpsc::Sender<Result<tari_rpc::SearchUtxosByScriptResponse, Status>>,
+) {
+    let outputs = match outputs {
+        Ok(outputs) => outputs,
+        Err(err) => {
+            warn!(target: LOG_TARGET, "Error communicating with base node: {:?}", err,);
+            let _ = tx.send(Err(Status::internal("Internal error"))).await;
+            return;
+        },
+    };
+
+    debug!(target: LOG_TARGET, "Found {} matching output(s)", outputs.len());
+
+    for output in outputs {
+        let mined_height = output.mined_height;
+        let mined_in_block = output.header_hash;
+        // Pruned outputs cannot be returned to the client
+        let output = match output.output.into_unpruned_output() {
+            Some(output) => output,
+            None => continue,
+        };
+        let response = tari_rpc::SearchUtxosByScriptResponse {
+            output: Some(output.into()),
+            mined_height,
+            mined_in_block,
+        };
+        if let Err(err) = tx.send(Ok(response)).await {
+            // This error can only happen if the Receiver has dropped, meaning the request was
+            // cancelled/disconnected
+            warn!(target: LOG_TARGET, "Error sending output via GRPC: {}", err);
+            return;
+        }
+    }
+}
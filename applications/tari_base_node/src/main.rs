@@ -115,7 +115,7 @@ use tari_app_utilities::{
     consts,
     identity_management::setup_node_identity,
     initialization::init_configuration,
-    utilities::setup_runtime,
+    utilities::{get_or_prompt_peer_db_passphrase, setup_runtime},
 };
 #[cfg(all(unix, feature = "libtor"))]
 use tari_common::CommsTransport;
@@ -156,7 +156,6 @@ fn main() {
 }
 
 fn main_inner() -> Result<(), ExitError> {
-    #[allow(unused_mut)] // config isn't mutated on windows
     let (bootstrap, mut config, _) = init_configuration(ApplicationType::BaseNode)?;
     debug!(target: LOG_TARGET, "Using configuration: {:?}", config);
 
@@ -183,6 +182,13 @@ fn main_inner() -> Result<(), ExitError> {
         return Ok(());
     }
 
+    config.peer_db_passphrase = get_or_prompt_peer_db_passphrase(
+        bootstrap.peer_db_passphrase.clone(),
+        config.peer_db_passphrase.clone(),
+        &config.peer_db_path,
+        bootstrap.non_interactive_mode,
+    )?;
+
     // The shutdown trigger for the system
     let shutdown = Shutdown::new();
 
@@ -245,6 +251,17 @@ async fn run_node(
         return Ok(());
     };
 
+    if bootstrap.migrate_db {
+        info!(
+            target: LOG_TARGET,
+            "Migrating the LMDB database to the configured backend"
+        );
+        recovery::run_migration(&config)
+            .await
+            .map_err(|e| ExitError::new(ExitCode::RecoveryError, e))?;
+        return Ok(());
+    };
+
     // Build, node, build!
     let ctx = builder::configure_and_initialize_node(
         config.clone(),
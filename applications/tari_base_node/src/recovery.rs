@@ -31,7 +31,6 @@ use std::{
 use anyhow::anyhow;
 use log::*;
 use tari_common::{
-    configuration::Network,
     exit_codes::{ExitCode, ExitError},
     DatabaseType,
     GlobalConfig,
@@ -41,9 +40,11 @@ use tari_core::{
         async_db::AsyncBlockchainDb,
         create_lmdb_database,
         create_recovery_lmdb_database,
+        create_sled_database,
         BlockchainBackend,
         BlockchainDatabase,
         BlockchainDatabaseConfig,
+        SledConfig,
         Validators,
     },
     consensus::ConsensusManager,
@@ -81,13 +82,23 @@ pub async fn run_recovery(node_config: &GlobalConfig) -> Result<(), anyhow::Erro
     println!("Starting recovery mode");
     let (temp_db, main_db) = match &node_config.db_type {
         DatabaseType::LMDB(p) => {
-            let backend = create_lmdb_database(&p, node_config.db_config.clone()).map_err(|e| {
+            let backend = create_lmdb_database(
+                &p,
+                node_config.db_config.clone(),
+                node_config.enable_utxo_search_indexes,
+            )
+            .map_err(|e| {
                 error!(target: LOG_TARGET, "Error opening db: {}", e);
                 anyhow!("Could not open DB: {}", e)
             })?;
             let new_path = Path::new(&p).join("temp_recovery");
 
-            let temp = create_lmdb_database(&new_path, node_config.db_config.clone()).map_err(|e| {
+            let temp = create_lmdb_database(
+                &new_path,
+                node_config.db_config.clone(),
+                node_config.enable_utxo_search_indexes,
+            )
+            .map_err(|e| {
                 error!(target: LOG_TARGET, "Error opening recovery db: {}", e);
                 anyhow!("Could not open recovery DB: {}", e)
             })?;
@@ -99,28 +110,12 @@ pub async fn run_recovery(node_config: &GlobalConfig) -> Result<(), anyhow::Erro
         },
     };
     let rules = ConsensusManager::builder(node_config.network).build();
-    let factories = CryptoFactories::default();
     let randomx_factory = RandomXFactory::new(node_config.max_randomx_vms);
-    let validators = Validators::new(
-        BodyOnlyValidator::new(rules.clone()),
-        HeaderValidator::new(rules.clone()),
-        OrphanBlockValidator::new(
-            rules.clone(),
-            node_config.base_node_bypass_range_proof_verification,
-            factories.clone(),
-        ),
-    );
-    let db_config = BlockchainDatabaseConfig {
-        orphan_storage_capacity: node_config.orphan_storage_capacity,
-        pruning_horizon: node_config.pruning_horizon,
-        pruning_interval: node_config.pruned_mode_cleanup_interval,
-        track_reorgs: false,
-    };
     let db = BlockchainDatabase::new(
         main_db,
         rules.clone(),
-        validators,
-        db_config,
+        create_validators(node_config, &rules),
+        create_db_config(node_config),
         DifficultyCalculator::new(rules, randomx_factory),
         true,
     )?;
@@ -145,13 +140,98 @@ pub async fn run_recovery(node_config: &GlobalConfig) -> Result<(), anyhow::Erro
     }
 }
 
-// Function to handle the recovery attempt of the db
-async fn do_recovery<D: BlockchainBackend + 'static>(
-    db: AsyncBlockchainDb<D>,
-    source_backend: D,
-) -> Result<(), anyhow::Error> {
-    // We dont care about the values, here, so we just use mock validators, and a mainnet CM.
-    let rules = ConsensusManager::builder(Network::LocalNet).build();
+/// Copies the chain stored in the LMDB database at `db_migration_source_path` (`<data_dir>/db` by default) into the
+/// sled database configured in `db_type`.
+/// Every block is validated again as it is added to the sled database, exactly as during recovery.
+pub async fn run_migration(node_config: &GlobalConfig) -> Result<(), anyhow::Error> {
+    println!("Starting database migration");
+    let target_path = match &node_config.db_type {
+        DatabaseType::Sled(p) => p,
+        _ => {
+            const MSG: &str = "Database migration requires db_type to be set to sled";
+            error!(target: LOG_TARGET, "{}", MSG);
+            return Err(anyhow!(MSG));
+        },
+    };
+    let source_path = node_config
+        .db_migration_source_path
+        .clone()
+        .unwrap_or_else(|| node_config.data_dir.join("db"));
+    if !source_path.join("data.mdb").exists() {
+        return Err(anyhow!("No LMDB database found at {}", source_path.display()));
+    }
+    let source_db = create_lmdb_database(
+        &source_path,
+        node_config.db_config.clone(),
+        node_config.enable_utxo_search_indexes,
+    )
+    .map_err(|e| {
+        error!(target: LOG_TARGET, "Error opening source db: {}", e);
+        anyhow!("Could not open source DB: {}", e)
+    })?;
+    let sled_config = SledConfig::new_from_mb(node_config.sled_cache_capacity_mb, node_config.sled_flush_every_ms);
+    let target_db =
+        create_sled_database(target_path, sled_config, node_config.enable_utxo_search_indexes).map_err(|e| {
+            error!(target: LOG_TARGET, "Error opening target db: {}", e);
+            anyhow!("Could not open target DB: {}", e)
+        })?;
+    if !target_db.is_empty()? {
+        return Err(anyhow!(
+            "The target database at {} is not empty. Remove it before migrating.",
+            target_path.display()
+        ));
+    }
+
+    let rules = ConsensusManager::builder(node_config.network).build();
+    let db = BlockchainDatabase::new(
+        target_db,
+        rules.clone(),
+        create_validators(node_config, &rules),
+        create_db_config(node_config),
+        DifficultyCalculator::new(rules, RandomXFactory::new(node_config.max_randomx_vms)),
+        false,
+    )?;
+    do_recovery(db.into(), source_db).await?;
+
+    info!(
+        target: LOG_TARGET,
+        "Database migration complete. The LMDB database at {} was left in place.",
+        source_path.display()
+    );
+    Ok(())
+}
+
+fn create_validators<B: BlockchainBackend>(node_config: &GlobalConfig, rules: &ConsensusManager) -> Validators<B> {
+    Validators::new(
+        BodyOnlyValidator::new(rules.clone()),
+        HeaderValidator::new(rules.clone()),
+        OrphanBlockValidator::new(
+            rules.clone(),
+            node_config.base_node_bypass_range_proof_verification,
+            CryptoFactories::default(),
+        ),
+    )
+}
+
+fn create_db_config(node_config: &GlobalConfig) -> BlockchainDatabaseConfig {
+    BlockchainDatabaseConfig {
+        orphan_storage_capacity: node_config.orphan_storage_capacity,
+        pruning_horizon: node_config.pruning_horizon,
+        pruning_interval: node_config.pruned_mode_cleanup_interval,
+        track_reorgs: false,
+    }
+}
+
+// Function to handle the recovery attempt of the db. Blocks are read from `source_backend` and added to `db`, which
+// validates them.
+async fn do_recovery<S, T>(db: AsyncBlockchainDb<T>, source_backend: S) -> Result<(), anyhow::Error>
+where
+    S: BlockchainBackend,
+    T: BlockchainBackend + 'static,
+{
+    // The source blocks are validated when they are added to `db`, so reading them back needs no validation. The
+    // consensus rules must match those of `db` for the source genesis block to be accepted.
+    let rules = db.inner().rules().clone();
     let validators = Validators::new(
         MockValidator::new(true),
         MockValidator::new(true),
@@ -193,3 +273,84 @@ async fn do_recovery<D: BlockchainBackend + 'static>(
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use tari_common::configuration::Network;
+    use tari_core::{
+        chain_storage::{create_lmdb_database, create_sled_database, MmrTree, SledConfig},
+        test_helpers::blockchain::create_chained_blocks,
+    };
+    use tempfile::tempdir;
+
+    use super::*;
+
+    fn create_db<B: BlockchainBackend>(backend: B, rules: &ConsensusManager) -> BlockchainDatabase<B> {
+        let validators = Validators::new(
+            MockValidator::new(true),
+            MockValidator::new(true),
+            MockValidator::new(true),
+        );
+        BlockchainDatabase::new(
+            backend,
+            rules.clone(),
+            validators,
+            BlockchainDatabaseConfig::default(),
+            DifficultyCalculator::new(rules.clone(), Default::default()),
+            false,
+        )
+        .unwrap()
+    }
+
+    fn count_utxos<B: BlockchainBackend>(db: &BlockchainDatabase<B>) -> u64 {
+        let deleted = db.fetch_deleted_bitmap_at_tip().unwrap().bitmap().cardinality();
+        db.fetch_mmr_size(MmrTree::Utxo).unwrap() - deleted
+    }
+
+    #[tokio::test]
+    async fn it_migrates_an_lmdb_chain_to_sled() {
+        let temp_dir = tempdir().unwrap();
+        let lmdb_path = temp_dir.path().join("lmdb");
+        let sled_path = temp_dir.path().join("sled");
+        let rules = ConsensusManager::builder(Network::LocalNet).build();
+
+        let source = create_db(
+            create_lmdb_database(&lmdb_path, Default::default(), true).unwrap(),
+            &rules,
+        );
+        let genesis_block = source
+            .fetch_block(0)
+            .unwrap()
+            .try_into_chain_block()
+            .map(Arc::new)
+            .unwrap();
+        let (names, chain) = create_chained_blocks(
+            &[("A->GB", 1u64, 120u64), ("B->A", 1, 120), ("C->B", 1, 120)],
+            genesis_block,
+        );
+        for name in &names {
+            source.add_block(chain[name].to_arc_block()).unwrap();
+        }
+        let source_metadata = source.get_chain_metadata().unwrap();
+        let source_utxos = count_utxos(&source);
+        let source_kernels = source.fetch_mmr_size(MmrTree::Kernel).unwrap();
+        drop(source);
+
+        let source_backend = create_lmdb_database(&lmdb_path, Default::default(), true).unwrap();
+        let target = create_db(
+            create_sled_database(&sled_path, SledConfig::default(), true).unwrap(),
+            &rules,
+        );
+        do_recovery(target.clone().into(), source_backend).await.unwrap();
+
+        let target_metadata = target.get_chain_metadata().unwrap();
+        assert_eq!(target_metadata.height_of_longest_chain(), 3);
+        assert_eq!(target_metadata.best_block(), source_metadata.best_block());
+        assert_eq!(
+            target_metadata.accumulated_difficulty(),
+            source_metadata.accumulated_difficulty()
+        );
+        assert_eq!(count_utxos(&target), source_utxos);
+        assert_eq!(target.fetch_mmr_size(MmrTree::Kernel).unwrap(), source_kernels);
+    }
+}
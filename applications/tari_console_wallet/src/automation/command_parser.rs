@@ -29,10 +29,11 @@ use std::{
 
 use chrono::{DateTime, Utc};
 use tari_app_utilities::utilities::{parse_emoji_id_or_public_key, parse_hash};
-use tari_common_types::types::PublicKey;
+use tari_common_types::types::{Commitment, PublicKey};
 use tari_comms::multiaddr::Multiaddr;
-use tari_core::transactions::tari_amount::MicroTari;
+use tari_core::{covenants::Covenant, transactions::tari_amount::MicroTari};
 use tari_crypto::tari_utilities::hex::Hex;
+use tari_wallet::output_manager_service::service::UTXOSelectionStrategy;
 
 use crate::automation::{commands::WalletCommand, error::ParseError};
 
@@ -49,8 +50,19 @@ impl Display for ParsedCommand {
             GetBalance => "get-balance",
             SendTari => "send-tari",
             SendOneSided => "send-one-sided",
+            SendBatch => "send-batch",
             MakeItRain => "make-it-rain",
             CoinSplit => "coin-split",
+            Consolidate => "consolidate",
+            ConsolidationHistory => "consolidation-history",
+            CreateAccount => "create-account",
+            ListAccounts => "list-accounts",
+            UseAccount => "use-account",
+            TransferToAccount => "transfer-to-account",
+            SendTimeLocked => "send-time-locked",
+            SchedulePayment => "schedule-payment",
+            ListScheduledPayments => "list-scheduled-payments",
+            CancelScheduledPayment => "cancel-scheduled-payment",
             DiscoverPeer => "discover-peer",
             Whois => "whois",
             ExportUtxos => "export-utxos",
@@ -66,6 +78,17 @@ impl Display for ParsedCommand {
             MintTokens => "mint-tokens",
             CreateInitialCheckpoint => "create-initial-checkpoint",
             CreateCommitteeDefinition => "create-committee-definition",
+            ExportWatchOnlyKeys => "export-watch-only-keys",
+            ExportUnsignedTransaction => "export-unsigned-transaction",
+            SignOfflineTransaction => "sign-offline-transaction",
+            BroadcastOfflineTransaction => "broadcast-offline-transaction",
+            CreateMultisig => "create-multisig",
+            ProposeMultisigSpend => "propose-multisig-spend",
+            ApproveMultisigSpend => "approve-multisig-spend",
+            ListMultisig => "list-multisig",
+            CreateInvoice => "create-invoice",
+            ListInvoices => "list-invoices",
+            CancelInvoice => "cancel-invoice",
         };
 
         let args = self
@@ -92,6 +115,8 @@ pub enum ParsedArgument {
     Address(Multiaddr),
     Negotiated(bool),
     Hash(Vec<u8>),
+    Covenant(Covenant),
+    SelectionStrategy(UTXOSelectionStrategy),
 }
 
 impl Display for ParsedArgument {
@@ -109,6 +134,13 @@ impl Display for ParsedArgument {
             Address(v) => write!(f, "{}", v),
             Negotiated(v) => write!(f, "{}", v),
             Hash(v) => write!(f, "{}", v.to_hex()),
+            Covenant(v) => write!(f, "--covenant {}", v),
+            SelectionStrategy(UTXOSelectionStrategy::Specific(commitments)) => write!(
+                f,
+                "--utxos {}",
+                commitments.iter().map(|c| c.to_hex()).collect::<Vec<_>>().join(",")
+            ),
+            SelectionStrategy(v) => write!(f, "--strategy {}", v),
         }
     }
 }
@@ -143,6 +175,40 @@ pub fn parse_command(command: &str) -> Result<ParsedCommand, ParseError> {
         MintTokens => parser_builder(args).pub_key().text_array().build()?,
         CreateInitialCheckpoint => parser_builder(args).pub_key().text().build()?,
         CreateCommitteeDefinition => parser_builder(args).pub_key().pub_key_array().build()?,
+        ExportWatchOnlyKeys => parser_builder(args).text().build()?,
+        // export-unsigned-transaction amount pub_key file_name message
+        ExportUnsignedTransaction => parser_builder(args).amount().pub_key().text().text_array().build()?,
+        // sign-offline-transaction unsigned_file_name signed_file_name
+        SignOfflineTransaction => parser_builder(args).text().text().build()?,
+        BroadcastOfflineTransaction => parser_builder(args).text().build()?,
+        // create-multisig amount threshold pub_key1 pub_key2
+        CreateMultisig => parser_builder(args).amount().int().pub_key().pub_key_array().build()?,
+        // propose-multisig-spend session_id pub_key message
+        ProposeMultisigSpend => parser_builder(args).int().pub_key().text_array().build()?,
+        ApproveMultisigSpend => parser_builder(args).int().build()?,
+        ListMultisig => Vec::new(),
+        // create-invoice amount expiry_minutes message
+        CreateInvoice => parser_builder(args).amount().int().text_array().build()?,
+        ListInvoices => Vec::new(),
+        CancelInvoice => parser_builder(args).int().build()?,
+        // send-batch csv_file_name message
+        SendBatch => parser_builder(args).text().text_array().build()?,
+        // consolidate [max_inputs]
+        Consolidate => match args.next() {
+            Some(max_inputs) => vec![ParsedArgument::Int(max_inputs.parse::<u64>()?)],
+            None => Vec::new(),
+        },
+        ConsolidationHistory => Vec::new(),
+        CreateAccount => parser_builder(args).text().build()?,
+        ListAccounts => Vec::new(),
+        UseAccount => parser_builder(args).text().build()?,
+        // transfer-to-account amount account_name message
+        TransferToAccount => parser_builder(args).amount().text().text_array().build()?,
+        // send-time-locked amount pub_key lock_height message
+        SendTimeLocked => parser_builder(args).amount().pub_key().int().text_array().build()?,
+        SchedulePayment => parse_schedule_payment(args)?,
+        ListScheduledPayments => Vec::new(),
+        CancelScheduledPayment => parser_builder(args).int().build()?,
     };
 
     Ok(ParsedCommand { command, args })
@@ -180,6 +246,26 @@ impl<'a> ArgParser<'a> {
         me
     }
 
+    fn amount(mut self) -> Self {
+        let amount_result = self
+            .args
+            .next()
+            .ok_or_else(|| ParseError::Empty("amount".to_string()))
+            .and_then(|a| Ok(ParsedArgument::Amount(MicroTari::from_str(a)?)));
+        self.result.push(amount_result);
+        self
+    }
+
+    fn int(mut self) -> Self {
+        let int_result = self
+            .args
+            .next()
+            .ok_or_else(|| ParseError::Empty("int".to_string()))
+            .and_then(|i| Ok(ParsedArgument::Int(u64::from_str(i)?)));
+        self.result.push(int_result);
+        self
+    }
+
     fn pub_key(mut self) -> Self {
         // public key/emoji id
         let pubkey = self
@@ -389,6 +475,79 @@ fn parse_make_it_rain(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>,
     Ok(parsed_args)
 }
 
+/// Parses `schedule-payment <amount> <pub_key> <interactive|one-sided> <height|time|now> [--every <interval>]
+/// [--count <payments>] [--lock-height <height>] <message>`. The optional values are always pushed, as zero when not
+/// given, so that the message is always the last argument.
+fn parse_schedule_payment(args: SplitWhitespace) -> Result<Vec<ParsedArgument>, ParseError> {
+    let mut parsed_args = Vec::new();
+    let mut args = args.peekable();
+
+    // amount
+    let amount = args.next().ok_or_else(|| ParseError::Empty("amount".to_string()))?;
+    parsed_args.push(ParsedArgument::Amount(MicroTari::from_str(amount)?));
+
+    // public key/emoji id
+    let pubkey = args
+        .next()
+        .ok_or_else(|| ParseError::Empty("public key or emoji id".to_string()))?;
+    let pubkey = parse_emoji_id_or_public_key(pubkey).ok_or(ParseError::PublicKey)?;
+    parsed_args.push(ParsedArgument::PublicKey(pubkey));
+
+    // transaction type
+    let negotiated = match args.next() {
+        Some("negotiated") | Some("interactive") => true,
+        Some("one_sided") | Some("one-sided") | Some("onesided") => false,
+        _ => {
+            return Err(ParseError::Invalid(
+                "Invalid data provided for <transaction type>, must be 'interactive' or 'one-sided'".to_string(),
+            ))
+        },
+    };
+    parsed_args.push(ParsedArgument::Negotiated(negotiated));
+
+    // a block height, or a utc time or 'now'
+    let start = args
+        .next()
+        .ok_or_else(|| ParseError::Empty("start height or time".to_string()))?;
+    let start = match start.parse::<u64>() {
+        Ok(height) => ParsedArgument::Int(height),
+        Err(_) if start == "now" => ParsedArgument::Date(Utc::now()),
+        Err(_) => ParsedArgument::Date(DateTime::parse_from_rfc3339(start)?.with_timezone(&Utc)),
+    };
+    parsed_args.push(start);
+
+    let mut every = 0;
+    let mut count = 0;
+    let mut lock_height = 0;
+    loop {
+        let value = match args.peek() {
+            Some(&"--every") => &mut every,
+            Some(&"--count") => &mut count,
+            Some(&"--lock-height") => &mut lock_height,
+            _ => break,
+        };
+        let flag = args.next().unwrap_or_default();
+        *value = args
+            .next()
+            .ok_or_else(|| ParseError::Empty(flag.trim_start_matches('-').to_string()))?
+            .parse::<u64>()?;
+    }
+    if lock_height > 0 && negotiated {
+        return Err(ParseError::Invalid(
+            "--lock-height is only supported for one-sided payments".to_string(),
+        ));
+    }
+    parsed_args.push(ParsedArgument::Int(every));
+    parsed_args.push(ParsedArgument::Int(count));
+    parsed_args.push(ParsedArgument::Int(lock_height));
+
+    // message
+    let message = args.collect::<Vec<&str>>().join(" ");
+    parsed_args.push(ParsedArgument::Text(message));
+
+    Ok(parsed_args)
+}
+
 fn parse_send_tari(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>, ParseError> {
     let mut parsed_args = Vec::new();
 
@@ -404,13 +563,86 @@ fn parse_send_tari(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>, Par
     let pubkey = parse_emoji_id_or_public_key(pubkey).ok_or(ParseError::PublicKey)?;
     parsed_args.push(ParsedArgument::PublicKey(pubkey));
 
+    // optional covenant and UTXO selection flags, followed by the message
+    let mut args = args.peekable();
+    let mut covenant = None;
+    let mut selection_strategy = None;
+    loop {
+        match args.peek() {
+            Some(&"--covenant") => {
+                args.next();
+                covenant = Some(parse_covenant(&mut args)?);
+            },
+            Some(&"--strategy") | Some(&"--utxos") => {
+                if selection_strategy.is_some() {
+                    return Err(ParseError::Invalid(
+                        "only one of --strategy or --utxos may be given".to_string(),
+                    ));
+                }
+                selection_strategy = Some(parse_selection_strategy(&mut args)?);
+            },
+            _ => break,
+        }
+    }
+
     // message
     let message = args.collect::<Vec<&str>>().join(" ");
     parsed_args.push(ParsedArgument::Text(message));
 
+    // The optional arguments are pushed last so that the positions of the other arguments do not change
+    if let Some(covenant) = covenant {
+        parsed_args.push(ParsedArgument::Covenant(covenant));
+    }
+    if let Some(selection_strategy) = selection_strategy {
+        parsed_args.push(ParsedArgument::SelectionStrategy(selection_strategy));
+    }
+
     Ok(parsed_args)
 }
 
+/// Parses either `--strategy <name>` or `--utxos <commitment>,<commitment>,...`, where the commitments are hex encoded
+fn parse_selection_strategy(args: &mut Peekable<SplitWhitespace>) -> Result<UTXOSelectionStrategy, ParseError> {
+    let flag = args
+        .next()
+        .ok_or_else(|| ParseError::Empty("selection strategy".to_string()))?;
+    let value = args
+        .next()
+        .ok_or_else(|| ParseError::Empty(flag.trim_start_matches('-').to_string()))?;
+    if flag == "--utxos" {
+        let commitments = value
+            .split(',')
+            .filter(|c| !c.is_empty())
+            .map(|c| {
+                Commitment::from_hex(c).map_err(|_| ParseError::SelectionStrategy(format!("bad commitment {}", c)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if commitments.is_empty() {
+            return Err(ParseError::Empty("utxos".to_string()));
+        }
+        return Ok(UTXOSelectionStrategy::Specific(commitments));
+    }
+    UTXOSelectionStrategy::from_str(value).map_err(ParseError::SelectionStrategy)
+}
+
+/// Parses a covenant in the textual covenant syntax. The covenant may contain whitespace, so words are consumed until
+/// all opening parentheses have been closed.
+fn parse_covenant(args: &mut Peekable<SplitWhitespace>) -> Result<Covenant, ParseError> {
+    let mut words = Vec::new();
+    let mut depth = 0i64;
+    for word in args.by_ref() {
+        words.push(word);
+        depth += word.matches('(').count() as i64;
+        depth -= word.matches(')').count() as i64;
+        if depth <= 0 {
+            break;
+        }
+    }
+    if words.is_empty() {
+        return Err(ParseError::Empty("covenant".to_string()));
+    }
+    Covenant::from_str(&words.join(" ")).map_err(|e| ParseError::Covenant(e.to_string()))
+}
+
 fn parse_export_utxos(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>, ParseError> {
     let mut parsed_args = Vec::new();
 
@@ -485,7 +717,8 @@ mod test {
     use rand::rngs::OsRng;
     use tari_common_types::types::PublicKey;
     use tari_core::transactions::tari_amount::MicroTari;
-    use tari_crypto::keys::PublicKey as PublicKeyTrait;
+    use tari_crypto::{keys::PublicKey as PublicKeyTrait, tari_utilities::hex::Hex};
+    use tari_wallet::output_manager_service::service::UTXOSelectionStrategy;
 
     use crate::automation::{
         command_parser::{parse_command, ParsedArgument},
@@ -531,6 +764,56 @@ mod test {
             panic!("Parsed message is not the same as provided.");
         }
 
+        let command_str = format!(
+            "send-tari 999T {} --covenant and(field_eq(@field::features_flags, @uint(0)), identity()) msg text",
+            public_key
+        );
+        let parsed = parse_command(&command_str).unwrap();
+
+        if let ParsedArgument::Text(msg) = parsed.args[2].clone() {
+            assert_eq!(msg, "msg text");
+        } else {
+            panic!("Parsed message is not the same as provided.");
+        }
+        if let ParsedArgument::Covenant(covenant) = parsed.args[3].clone() {
+            assert_eq!(
+                covenant.to_string(),
+                "and(field_eq(@field::features_flags, @uint(0)), identity())"
+            );
+        } else {
+            panic!("Parsed covenant is not the same as provided.");
+        }
+
+        let command_str = format!("send-one-sided 999T {} --covenant and(identity()) msg", public_key);
+        let parsed = parse_command(&command_str);
+        assert!(parsed.is_err());
+
+        let command_str = format!("send-tari 999T {} --strategy branch-and-bound msg", public_key);
+        let parsed = parse_command(&command_str).unwrap();
+        assert!(matches!(
+            parsed.args[3],
+            ParsedArgument::SelectionStrategy(UTXOSelectionStrategy::BranchAndBound)
+        ));
+
+        // A public key is a valid commitment encoding
+        let command_str = format!(
+            "send-one-sided 999T {} --utxos {},{} msg",
+            public_key,
+            public_key.to_hex(),
+            public_key.to_hex()
+        );
+        let parsed = parse_command(&command_str).unwrap();
+        if let ParsedArgument::SelectionStrategy(UTXOSelectionStrategy::Specific(commitments)) = parsed.args[3].clone()
+        {
+            assert_eq!(commitments.len(), 2);
+        } else {
+            panic!("Parsed UTXO commitments are not the same as provided.");
+        }
+
+        let command_str = format!("send-tari 999T {} --strategy largest --utxos abcd msg", public_key);
+        let parsed = parse_command(&command_str);
+        assert!(parsed.is_err());
+
         let command_str = format!("send-tari 999ut {}", public_key);
         let parsed = parse_command(&command_str).unwrap();
 
@@ -567,6 +850,24 @@ mod test {
             panic!("Parsed csv file name is not the same as provided.");
         }
 
+        let command_str = format!(
+            "export-unsigned-transaction 1T {} unsigned.json for the shop",
+            public_key
+        );
+        let parsed = parse_command(&command_str).unwrap();
+
+        if let ParsedArgument::Amount(amount) = parsed.args[0].clone() {
+            assert_eq!(amount, MicroTari::from_str("1T").unwrap());
+        } else {
+            panic!("Parsed MicroTari amount not the same as provided.");
+        }
+        if let ParsedArgument::Text(file) = parsed.args[2].clone() {
+            assert_eq!(file, "unsigned.json".to_string());
+        } else {
+            panic!("Parsed file name is not the same as provided.");
+        }
+        assert_eq!(parsed.args.len(), 6);
+
         let transaction_type = "negotiated";
         let message = "Testing the network!";
         let command_str = format!(
@@ -619,5 +920,34 @@ mod test {
                 _ => panic!("Expected parsing <transaction type> to return an error here"),
             },
         }
+
+        let command_str = format!(
+            "schedule-payment 1T {} one-sided 1000 --every 10 --lock-height 2000 rent",
+            public_key
+        );
+        let parsed = parse_command(&command_str).unwrap();
+        assert!(matches!(parsed.args[2], ParsedArgument::Negotiated(false)));
+        assert!(matches!(parsed.args[3], ParsedArgument::Int(1000)));
+        assert!(matches!(parsed.args[4], ParsedArgument::Int(10)));
+        assert!(matches!(parsed.args[5], ParsedArgument::Int(0)));
+        assert!(matches!(parsed.args[6], ParsedArgument::Int(2000)));
+        if let ParsedArgument::Text(msg) = parsed.args[7].clone() {
+            assert_eq!(msg, "rent");
+        } else {
+            panic!("Parsed message is not the same as provided.");
+        }
+
+        let command_str = format!(
+            "schedule-payment 1T {} interactive 2022-04-01T00:00:00Z rent",
+            public_key
+        );
+        let parsed = parse_command(&command_str).unwrap();
+        assert!(matches!(parsed.args[3], ParsedArgument::Date(_)));
+
+        let command_str = format!(
+            "schedule-payment 1T {} interactive now --lock-height 2000 rent",
+            public_key
+        );
+        assert!(matches!(parse_command(&command_str), Err(ParseError::Invalid(_))));
     }
 }
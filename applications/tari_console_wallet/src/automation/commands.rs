@@ -22,7 +22,7 @@
 
 use std::{
     convert::TryFrom,
-    fs::File,
+    fs::{self, File},
     io::{LineWriter, Write},
     str::FromStr,
     time::{Duration, Instant},
@@ -32,8 +32,10 @@ use chrono::Utc;
 use digest::Digest;
 use futures::FutureExt;
 use log::*;
+use qrcode::{render::unicode, QrCode};
 use sha2::Sha256;
 use strum_macros::{Display, EnumIter, EnumString};
+use tari_app_utilities::utilities::parse_emoji_id_or_public_key;
 use tari_common::GlobalConfig;
 use tari_common_types::{array::copy_into_fixed_array, emoji::EmojiId, transaction::TxId, types::PublicKey};
 use tari_comms::{
@@ -42,20 +44,31 @@ use tari_comms::{
     types::CommsPublicKey,
 };
 use tari_comms_dht::{envelope::NodeDestination, DhtDiscoveryRequester};
-use tari_core::transactions::{
-    tari_amount::{uT, MicroTari, Tari},
-    transaction_components::{TransactionOutput, UnblindedOutput},
+use tari_core::{
+    covenants::Covenant,
+    transactions::{
+        tari_amount::{MicroTari, Tari},
+        transaction_components::{TransactionOutput, UnblindedOutput},
+    },
 };
 use tari_crypto::{
     keys::PublicKey as PublicKeyTrait,
     ristretto::pedersen::PedersenCommitmentFactory,
     tari_utilities::{ByteArray, Hashable},
 };
-use tari_utilities::hex::Hex;
+use tari_utilities::{hex::Hex, message_format::MessageFormat};
 use tari_wallet::{
     error::WalletError,
-    output_manager_service::handle::OutputManagerHandle,
-    transaction_service::handle::{TransactionEvent, TransactionServiceHandle},
+    output_manager_service::{
+        handle::OutputManagerHandle,
+        service::UTXOSelectionStrategy,
+        storage::models::Account,
+        watch_only::{OfflineSignedTransaction, UnsignedTransactionTemplate},
+    },
+    transaction_service::{
+        handle::{BatchPaymentRecipient, ScheduledPaymentRequest, TransactionEvent, TransactionServiceHandle},
+        storage::models::{PaymentTrigger, ScheduledPaymentStatus},
+    },
     WalletSqlite,
 };
 use tokio::{
@@ -95,6 +108,28 @@ pub enum WalletCommand {
     MintTokens,
     CreateInitialCheckpoint,
     CreateCommitteeDefinition,
+    ExportWatchOnlyKeys,
+    ExportUnsignedTransaction,
+    SignOfflineTransaction,
+    BroadcastOfflineTransaction,
+    CreateMultisig,
+    ProposeMultisigSpend,
+    ApproveMultisigSpend,
+    ListMultisig,
+    CreateInvoice,
+    ListInvoices,
+    CancelInvoice,
+    SendBatch,
+    Consolidate,
+    ConsolidationHistory,
+    CreateAccount,
+    ListAccounts,
+    UseAccount,
+    TransferToAccount,
+    SendTimeLocked,
+    SchedulePayment,
+    ListScheduledPayments,
+    CancelScheduledPayment,
 }
 
 #[derive(Debug, EnumString, PartialEq, Clone)]
@@ -113,10 +148,7 @@ pub struct SentTransaction {}
 
 fn get_transaction_parameters(
     args: Vec<ParsedArgument>,
-) -> Result<(MicroTari, MicroTari, PublicKey, String), CommandError> {
-    // TODO: Consolidate "fee per gram" in codebase #LOGGED
-    let fee_per_gram = 25 * uT;
-
+) -> Result<(MicroTari, PublicKey, String, Covenant, Option<UTXOSelectionStrategy>), CommandError> {
     use ParsedArgument::*;
     let amount = match args[0].clone() {
         Amount(mtari) => Ok(mtari),
@@ -133,15 +165,22 @@ fn get_transaction_parameters(
         _ => Err(CommandError::Argument),
     }?;
 
-    Ok((fee_per_gram, amount, dest_pubkey, message))
+    let mut covenant = Default::default();
+    let mut selection_strategy = None;
+    for arg in args.into_iter().skip(3) {
+        match arg {
+            Covenant(v) => covenant = v,
+            SelectionStrategy(v) => selection_strategy = Some(v),
+            _ => return Err(CommandError::Argument),
+        }
+    }
+
+    Ok((amount, dest_pubkey, message, covenant, selection_strategy))
 }
 
 fn get_init_sha_atomic_swap_parameters(
     args: Vec<ParsedArgument>,
-) -> Result<(MicroTari, MicroTari, PublicKey, String), CommandError> {
-    // TODO: Consolidate "fee per gram" in codebase #LOGGED
-    let fee_per_gram = 25 * uT;
-
+) -> Result<(MicroTari, PublicKey, String), CommandError> {
     use ParsedArgument::*;
     let amount = match args[0].clone() {
         Amount(mtari) => Ok(mtari),
@@ -158,17 +197,27 @@ fn get_init_sha_atomic_swap_parameters(
         _ => Err(CommandError::Argument),
     }?;
 
-    Ok((fee_per_gram, amount, dest_pubkey, message))
+    Ok((amount, dest_pubkey, message))
 }
 
 /// Send a normal negotiated transaction to a recipient
 pub async fn send_tari(
     mut wallet_transaction_service: TransactionServiceHandle,
+    fee_per_gram: MicroTari,
     args: Vec<ParsedArgument>,
 ) -> Result<TxId, CommandError> {
-    let (fee_per_gram, amount, dest_pubkey, message) = get_transaction_parameters(args)?;
+    let (amount, dest_pubkey, message, covenant, selection_strategy) = get_transaction_parameters(args)?;
     wallet_transaction_service
-        .send_transaction(dest_pubkey, amount, fee_per_gram, message)
+        .send_transaction_or_token(
+            dest_pubkey,
+            amount,
+            None,
+            None,
+            fee_per_gram,
+            message,
+            covenant,
+            selection_strategy,
+        )
         .await
         .map_err(CommandError::TransactionServiceError)
 }
@@ -176,9 +225,10 @@ pub async fn send_tari(
 /// publishes a tari-SHA atomic swap HTLC transaction
 pub async fn init_sha_atomic_swap(
     mut wallet_transaction_service: TransactionServiceHandle,
+    fee_per_gram: MicroTari,
     args: Vec<ParsedArgument>,
 ) -> Result<(TxId, PublicKey, TransactionOutput), CommandError> {
-    let (fee_per_gram, amount, dest_pubkey, message) = get_init_sha_atomic_swap_parameters(args)?;
+    let (amount, dest_pubkey, message) = get_init_sha_atomic_swap_parameters(args)?;
 
     let (tx_id, pre_image, output) = wallet_transaction_service
         .send_sha_atomic_swap_transaction(dest_pubkey, amount, fee_per_gram, message)
@@ -203,8 +253,9 @@ pub async fn finalise_sha_atomic_swap(
         PublicKey(key) => Ok(key),
         _ => Err(CommandError::Argument),
     }?;
+    let fee_per_gram = output_service.estimate_fee_per_gram(None).await?;
     let (tx_id, _fee, amount, tx) = output_service
-        .create_claim_sha_atomic_swap_transaction(output, pre_image, MicroTari(25))
+        .create_claim_sha_atomic_swap_transaction(output, pre_image, fee_per_gram)
         .await?;
     transaction_service
         .submit_transaction(tx_id, tx, amount, "Claimed HTLC atomic swap".into())
@@ -224,8 +275,9 @@ pub async fn claim_htlc_refund(
         _ => Err(CommandError::Argument),
     }?;
 
+    let fee_per_gram = output_service.estimate_fee_per_gram(None).await?;
     let (tx_id, _fee, amount, tx) = output_service
-        .create_htlc_refund_transaction(output, MicroTari(25))
+        .create_htlc_refund_transaction(output, fee_per_gram)
         .await?;
     transaction_service
         .submit_transaction(tx_id, tx, amount, "Claimed HTLC refund".into())
@@ -236,11 +288,21 @@ pub async fn claim_htlc_refund(
 /// Send a one-sided transaction to a recipient
 pub async fn send_one_sided(
     mut wallet_transaction_service: TransactionServiceHandle,
+    fee_per_gram: MicroTari,
     args: Vec<ParsedArgument>,
 ) -> Result<TxId, CommandError> {
-    let (fee_per_gram, amount, dest_pubkey, message) = get_transaction_parameters(args)?;
+    let (amount, dest_pubkey, message, covenant, selection_strategy) = get_transaction_parameters(args)?;
     wallet_transaction_service
-        .send_one_sided_transaction(dest_pubkey, amount, fee_per_gram, message)
+        .send_one_sided_transaction_or_token(
+            dest_pubkey,
+            amount,
+            None,
+            None,
+            fee_per_gram,
+            message,
+            covenant,
+            selection_strategy,
+        )
         .await
         .map_err(CommandError::TransactionServiceError)
 }
@@ -351,6 +413,7 @@ pub async fn discover_peer(
 
 pub async fn make_it_rain(
     wallet_transaction_service: TransactionServiceHandle,
+    fee_per_gram: MicroTari,
     args: Vec<ParsedArgument>,
 ) -> Result<(), CommandError> {
     use ParsedArgument::*;
@@ -462,9 +525,9 @@ pub async fn make_it_rain(
                     let spawn_start = Instant::now();
                     // Send transaction
                     let tx_id = if negotiated {
-                        send_tari(tx_service, send_args).await
+                        send_tari(tx_service, fee_per_gram, send_args).await
                     } else {
-                        send_one_sided(tx_service, send_args).await
+                        send_one_sided(tx_service, fee_per_gram, send_args).await
                     };
                     let submit_time = Instant::now();
                     tokio::task::spawn(async move {
@@ -648,6 +711,7 @@ pub async fn command_runner(
 
     let mut transaction_service = wallet.transaction_service.clone();
     let mut output_service = wallet.output_manager_service.clone();
+    let mut invoice_service = wallet.invoice_service.clone();
     let dht_service = wallet.dht_service.discovery_service_requester().clone();
     let connectivity_requester = wallet.comms.connectivity();
     let mut online = false;
@@ -676,23 +740,220 @@ pub async fn command_runner(
                 discover_peer(dht_service.clone(), parsed.args).await?
             },
             SendTari => {
-                let tx_id = send_tari(transaction_service.clone(), parsed.args).await?;
+                let fee_per_gram = output_service.estimate_fee_per_gram(None).await?;
+                let tx_id = send_tari(transaction_service.clone(), fee_per_gram, parsed.args).await?;
                 debug!(target: LOG_TARGET, "send-tari tx_id {}", tx_id);
                 tx_ids.push(tx_id);
             },
             SendOneSided => {
-                let tx_id = send_one_sided(transaction_service.clone(), parsed.args).await?;
+                let fee_per_gram = output_service.estimate_fee_per_gram(None).await?;
+                let tx_id = send_one_sided(transaction_service.clone(), fee_per_gram, parsed.args).await?;
                 debug!(target: LOG_TARGET, "send-one-sided tx_id {}", tx_id);
                 tx_ids.push(tx_id);
             },
+            SendBatch => {
+                let file_name = match parsed.args[0] {
+                    ParsedArgument::Text(ref name) => Ok(name.clone()),
+                    _ => Err(CommandError::Argument),
+                }?;
+                let message = parsed.args[1..]
+                    .iter()
+                    .map(|arg| arg.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" ");
+                let recipients = read_batch_payments_csv(&file_name)?;
+                let total: MicroTari = recipients.iter().map(|r| r.amount).sum();
+                println!(
+                    "Sending {} to {} recipients in one transaction",
+                    total,
+                    recipients.len()
+                );
+                let fee_per_gram = output_service.estimate_fee_per_gram(None).await?;
+                let tx_id = transaction_service
+                    .send_one_sided_batch_transaction(recipients, fee_per_gram, message)
+                    .await?;
+                debug!(target: LOG_TARGET, "send-batch tx_id {}", tx_id);
+                tx_ids.push(tx_id);
+            },
             MakeItRain => {
-                make_it_rain(transaction_service.clone(), parsed.args).await?;
+                let fee_per_gram = output_service.estimate_fee_per_gram(None).await?;
+                make_it_rain(transaction_service.clone(), fee_per_gram, parsed.args).await?;
             },
             CoinSplit => {
                 let tx_id = coin_split(&parsed.args, &mut output_service, &mut transaction_service.clone()).await?;
                 tx_ids.push(tx_id);
                 println!("Coin split succeeded");
             },
+            Consolidate => {
+                let max_inputs = match parsed.args.get(0) {
+                    Some(ParsedArgument::Int(n)) => Ok(*n as usize),
+                    Some(_) => Err(CommandError::Argument),
+                    None => Ok(100),
+                }?;
+                let fee_per_gram = output_service.estimate_fee_per_gram(None).await?;
+                let (consolidation, tx) = output_service
+                    .create_consolidation_transaction(fee_per_gram, max_inputs, None)
+                    .await?;
+                transaction_service
+                    .submit_transaction(
+                        consolidation.tx_id,
+                        tx,
+                        consolidation.amount,
+                        format!("Consolidation of {} outputs", consolidation.num_inputs),
+                    )
+                    .await?;
+                tx_ids.push(consolidation.tx_id);
+                println!(
+                    "Consolidating {} outputs into one output of {} (fee {})",
+                    consolidation.num_inputs, consolidation.amount, consolidation.fee
+                );
+            },
+            ConsolidationHistory => {
+                let history = output_service.get_consolidation_history().await?;
+                if history.is_empty() {
+                    println!("No UTXO consolidations");
+                }
+                for consolidation in history {
+                    println!(
+                        "{}: {} outputs into {} (fee {}) in transaction {}",
+                        consolidation.timestamp,
+                        consolidation.num_inputs,
+                        consolidation.amount,
+                        consolidation.fee,
+                        consolidation.tx_id
+                    );
+                }
+            },
+            CreateAccount => {
+                let name = match parsed.args[0] {
+                    ParsedArgument::Text(ref name) => Ok(name.clone()),
+                    _ => Err(CommandError::Argument),
+                }?;
+                let account = output_service.create_account(name).await?;
+                println!("Created account `{}` with id {}", account.name, account.id);
+            },
+            ListAccounts => {
+                let accounts = output_service.get_accounts().await?;
+                for account in accounts {
+                    let balance = wallet
+                        .output_manager_service
+                        .for_account(account.id)
+                        .get_balance()
+                        .await?;
+                    println!(
+                        "{}. {} - available {}, pending incoming {}, pending outgoing {}",
+                        account.id,
+                        account.name,
+                        balance.available_balance,
+                        balance.pending_incoming_balance,
+                        balance.pending_outgoing_balance
+                    );
+                }
+            },
+            UseAccount => {
+                let name = match parsed.args[0] {
+                    ParsedArgument::Text(ref name) => Ok(name.clone()),
+                    _ => Err(CommandError::Argument),
+                }?;
+                let account = find_account(&mut output_service, &name).await?;
+                // Subsequent commands act on the selected account
+                output_service = wallet.output_manager_service.for_account(account.id);
+                transaction_service = wallet.transaction_service.for_account(account.id);
+                println!("Using account `{}`", account.name);
+            },
+            TransferToAccount => {
+                let amount = match parsed.args[0] {
+                    ParsedArgument::Amount(amount) => Ok(amount),
+                    _ => Err(CommandError::Argument),
+                }?;
+                let name = match parsed.args[1] {
+                    ParsedArgument::Text(ref name) => Ok(name.clone()),
+                    _ => Err(CommandError::Argument),
+                }?;
+                let message = parsed.args[2..]
+                    .iter()
+                    .map(|arg| arg.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" ");
+                let account = find_account(&mut output_service, &name).await?;
+                let fee_per_gram = output_service.estimate_fee_per_gram(None).await?;
+                let tx_id = transaction_service
+                    .transfer_between_accounts(account.id, amount, fee_per_gram, message)
+                    .await?;
+                tx_ids.push(tx_id);
+                println!(
+                    "Transferring {} to account `{}` in transaction {}",
+                    amount, account.name, tx_id
+                );
+            },
+            SendTimeLocked => {
+                let amount = match parsed.args[0] {
+                    ParsedArgument::Amount(amount) => Ok(amount),
+                    _ => Err(CommandError::Argument),
+                }?;
+                let dest_pubkey = match parsed.args[1] {
+                    ParsedArgument::PublicKey(ref key) => Ok(key.clone()),
+                    _ => Err(CommandError::Argument),
+                }?;
+                let lock_height = match parsed.args[2] {
+                    ParsedArgument::Int(height) => Ok(height),
+                    _ => Err(CommandError::Argument),
+                }?;
+                let message = parsed.args[3..]
+                    .iter()
+                    .map(|arg| arg.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" ");
+                let fee_per_gram = output_service.estimate_fee_per_gram(None).await?;
+                let tx_id = transaction_service
+                    .send_time_locked_transaction(dest_pubkey, amount, fee_per_gram, message, lock_height)
+                    .await?;
+                debug!(target: LOG_TARGET, "send-time-locked tx_id {}", tx_id);
+                tx_ids.push(tx_id);
+            },
+            SchedulePayment => {
+                let request = scheduled_payment_request(&parsed.args)?;
+                let first_payment = request.first_payment;
+                let id = transaction_service.schedule_payment(request).await?;
+                println!("Scheduled payment {} starting at {}", id, first_payment);
+            },
+            ListScheduledPayments => {
+                let payments = transaction_service.get_scheduled_payments().await?;
+                if payments.is_empty() {
+                    println!("No scheduled payments");
+                }
+                for payment in payments {
+                    println!(
+                        "{}: {} to {} ({}) {}",
+                        payment.id,
+                        payment.amount,
+                        payment.destination.to_hex(),
+                        payment.status,
+                        payment.message
+                    );
+                    if payment.status == ScheduledPaymentStatus::Scheduled {
+                        let remaining = payment
+                            .remaining_payments
+                            .map(|n| n.to_string())
+                            .unwrap_or_else(|| "unlimited".to_string());
+                        println!("    next payment at {}, {} remaining", payment.next_payment, remaining);
+                    }
+                    if let Some(height) = payment.recipient_lock_height {
+                        println!("    locked until height {}", height);
+                    }
+                    if let Some(tx_id) = payment.last_tx_id {
+                        println!("    last paid by transaction {}", tx_id);
+                    }
+                }
+            },
+            CancelScheduledPayment => {
+                let id = match parsed.args[0] {
+                    ParsedArgument::Int(id) => Ok(id),
+                    _ => Err(CommandError::Argument),
+                }?;
+                transaction_service.cancel_scheduled_payment(id).await?;
+                println!("Cancelled scheduled payment {}", id);
+            },
             Whois => {
                 let public_key = match parsed.args[0].clone() {
                     ParsedArgument::PublicKey(key) => Ok(Box::new(key)),
@@ -777,8 +1038,9 @@ pub async fn command_runner(
                 println!("Custom base node peer cleared from wallet database.");
             },
             InitShaAtomicSwap => {
+                let fee_per_gram = output_service.estimate_fee_per_gram(None).await?;
                 let (tx_id, pre_image, output) =
-                    init_sha_atomic_swap(transaction_service.clone(), parsed.clone().args).await?;
+                    init_sha_atomic_swap(transaction_service.clone(), fee_per_gram, parsed.clone().args).await?;
                 debug!(target: LOG_TARGET, "tari HTLC tx_id {}", tx_id);
                 let hash: [u8; 32] = Sha256::digest(pre_image.as_bytes()).into();
                 println!("pre_image hex: {}", pre_image.to_hex());
@@ -916,6 +1178,219 @@ pub async fn command_runner(
                     .await?;
                 println!("Done!");
             },
+            ExportWatchOnlyKeys => {
+                let file_name = match parsed.args[0] {
+                    ParsedArgument::Text(ref name) => Ok(name.clone()),
+                    _ => Err(CommandError::Argument),
+                }?;
+                let keys = output_service.get_watch_only_keys().await?;
+                fs::write(&file_name, keys.to_json()?).map_err(|e| CommandError::FileError(e.to_string()))?;
+                println!("Watch-only keys written to {}", file_name);
+                println!("Anyone holding these keys can see the balance and history of this wallet.");
+            },
+            ExportUnsignedTransaction => {
+                let amount = match parsed.args[0] {
+                    ParsedArgument::Amount(amount) => Ok(amount),
+                    _ => Err(CommandError::Argument),
+                }?;
+                let dest_pubkey = match parsed.args[1] {
+                    ParsedArgument::PublicKey(ref key) => Ok(key.clone()),
+                    _ => Err(CommandError::Argument),
+                }?;
+                let file_name = match parsed.args[2] {
+                    ParsedArgument::Text(ref name) => Ok(name.clone()),
+                    _ => Err(CommandError::Argument),
+                }?;
+                let message = parsed.args[3..]
+                    .iter()
+                    .map(|arg| arg.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" ");
+
+                let fee_per_gram = output_service.estimate_fee_per_gram(None).await?;
+                let template = output_service
+                    .create_unsigned_transaction_template(
+                        amount,
+                        fee_per_gram,
+                        None,
+                        message,
+                        dest_pubkey,
+                        Covenant::default(),
+                    )
+                    .await?;
+                fs::write(&file_name, template.to_json()?).map_err(|e| CommandError::FileError(e.to_string()))?;
+                println!(
+                    "Unsigned transaction {} for {} ({} inputs, fee {}) written to {}",
+                    template.tx_id,
+                    template.amount,
+                    template.inputs.len(),
+                    template.fee,
+                    file_name
+                );
+            },
+            SignOfflineTransaction => {
+                let (unsigned_file, signed_file) = match (&parsed.args[0], &parsed.args[1]) {
+                    (ParsedArgument::Text(unsigned_file), ParsedArgument::Text(signed_file)) => {
+                        Ok((unsigned_file.clone(), signed_file.clone()))
+                    },
+                    _ => Err(CommandError::Argument),
+                }?;
+                let json = fs::read_to_string(&unsigned_file).map_err(|e| CommandError::FileError(e.to_string()))?;
+                let template = UnsignedTransactionTemplate::from_json(&json)?;
+                println!(
+                    "Signing transaction {} sending {} (fee {}) to {}",
+                    template.tx_id,
+                    template.amount,
+                    template.fee,
+                    template.destination.to_hex()
+                );
+                let signed = transaction_service.sign_offline_transaction(template).await?;
+                fs::write(&signed_file, signed.to_json()?).map_err(|e| CommandError::FileError(e.to_string()))?;
+                println!("Signed transaction {} written to {}", signed.tx_id, signed_file);
+            },
+            BroadcastOfflineTransaction => {
+                let file_name = match parsed.args[0] {
+                    ParsedArgument::Text(ref name) => Ok(name.clone()),
+                    _ => Err(CommandError::Argument),
+                }?;
+                let json = fs::read_to_string(&file_name).map_err(|e| CommandError::FileError(e.to_string()))?;
+                let signed = OfflineSignedTransaction::from_json(&json)?;
+                let tx_id = transaction_service.submit_offline_transaction(signed).await?;
+                debug!(target: LOG_TARGET, "broadcast-offline-transaction tx_id {}", tx_id);
+                tx_ids.push(tx_id);
+            },
+            CreateMultisig => {
+                let amount = match parsed.args[0] {
+                    ParsedArgument::Amount(amount) => Ok(amount),
+                    _ => Err(CommandError::Argument),
+                }?;
+                let threshold = match parsed.args[1] {
+                    ParsedArgument::Int(threshold) => u8::try_from(threshold).map_err(|_| CommandError::Argument),
+                    _ => Err(CommandError::Argument),
+                }?;
+                let cosigners = parsed.args[2..]
+                    .iter()
+                    .map(|arg| match arg {
+                        ParsedArgument::PublicKey(key) => Ok(key.clone()),
+                        _ => Err(CommandError::Argument),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let num_members = cosigners.len() + 1;
+                let fee_per_gram = output_service.estimate_fee_per_gram(None).await?;
+                let session_id = transaction_service
+                    .create_multisig_output(cosigners, threshold, amount, fee_per_gram, String::new())
+                    .await?;
+                println!(
+                    "Started {}-of-{} multisig session {}. The output will be funded once all cosigners have joined.",
+                    threshold, num_members, session_id
+                );
+            },
+            ProposeMultisigSpend => {
+                let session_id = match parsed.args[0] {
+                    ParsedArgument::Int(session_id) => Ok(session_id),
+                    _ => Err(CommandError::Argument),
+                }?;
+                let destination = match parsed.args[1] {
+                    ParsedArgument::PublicKey(ref key) => Ok(key.clone()),
+                    _ => Err(CommandError::Argument),
+                }?;
+                let message = parsed.args[2..]
+                    .iter()
+                    .map(|arg| arg.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" ");
+                let fee_per_gram = output_service.estimate_fee_per_gram(None).await?;
+                transaction_service
+                    .propose_multisig_spend(session_id, destination, fee_per_gram, message)
+                    .await?;
+                println!("Proposed spending the output of multisig session {}", session_id);
+            },
+            ApproveMultisigSpend => {
+                let session_id = match parsed.args[0] {
+                    ParsedArgument::Int(session_id) => Ok(session_id),
+                    _ => Err(CommandError::Argument),
+                }?;
+                transaction_service.approve_multisig_spend(session_id).await?;
+                println!("Approved the pending spend of multisig session {}", session_id);
+            },
+            ListMultisig => {
+                let sessions = transaction_service.get_multisig_sessions().await?;
+                if sessions.is_empty() {
+                    println!("No multisig sessions");
+                }
+                for session in sessions {
+                    println!(
+                        "{}: {}-of-{} {} ({})",
+                        session.session_id,
+                        session.threshold,
+                        session.members.len(),
+                        session.amount,
+                        session.status
+                    );
+                    if let Some(spend) = session.spend {
+                        println!(
+                            "    spend to {} proposed by {} with {} of {} approvals",
+                            spend.destination.to_hex(),
+                            spend.proposer.to_hex(),
+                            spend.num_approvals(),
+                            session.threshold
+                        );
+                    }
+                }
+            },
+            CreateInvoice => {
+                let amount = match parsed.args[0] {
+                    ParsedArgument::Amount(amount) => Ok(amount),
+                    _ => Err(CommandError::Argument),
+                }?;
+                // An expiry of zero minutes creates an invoice that never expires
+                let expires_in = match parsed.args[1] {
+                    ParsedArgument::Int(0) => Ok(None),
+                    ParsedArgument::Int(minutes) => Ok(Some(Duration::from_secs(minutes * 60))),
+                    _ => Err(CommandError::Argument),
+                }?;
+                let message = parsed.args[2..]
+                    .iter()
+                    .map(|arg| arg.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" ");
+                let invoice = invoice_service
+                    .create_invoice(amount, None, message, expires_in)
+                    .await?;
+                let uri = invoice.payment_request(config.network).to_uri();
+                println!(
+                    "Created invoice {} ({}) for {}",
+                    invoice.id, invoice.reference, invoice.amount
+                );
+                if let Some(expires_at) = invoice.expires_at {
+                    println!("Expires at: {}", expires_at);
+                }
+                println!("Payment request: {}", uri);
+                println!("{}", render_qr_code(&uri)?);
+            },
+            ListInvoices => {
+                let invoices = invoice_service.get_invoices().await?;
+                if invoices.is_empty() {
+                    println!("No invoices");
+                }
+                for invoice in invoices {
+                    println!(
+                        "{}: {} {} ({}) {}",
+                        invoice.id, invoice.reference, invoice.amount, invoice.status, invoice.message
+                    );
+                    if let Some(tx_id) = invoice.tx_id {
+                        println!("    paid by transaction {}", tx_id);
+                    }
+                }
+            },
+            CancelInvoice => {
+                let id = match parsed.args[0] {
+                    ParsedArgument::Int(id) => Ok(id),
+                    _ => Err(CommandError::Argument),
+                }?;
+                let invoice = invoice_service.cancel_invoice(id).await?;
+                println!("Cancelled invoice {} ({})", invoice.id, invoice.reference);
+            },
         }
     }
 
@@ -957,6 +1432,102 @@ pub async fn command_runner(
     Ok(())
 }
 
+fn scheduled_payment_request(args: &[ParsedArgument]) -> Result<ScheduledPaymentRequest, CommandError> {
+    use ParsedArgument::*;
+    let optional = |arg: &ParsedArgument| match arg {
+        Int(0) => Ok(None),
+        Int(v) => Ok(Some(*v)),
+        _ => Err(CommandError::Argument),
+    };
+    let amount = match args[0] {
+        Amount(amount) => Ok(amount),
+        _ => Err(CommandError::Argument),
+    }?;
+    let destination = match args[1] {
+        ParsedArgument::PublicKey(ref key) => Ok(key.clone()),
+        _ => Err(CommandError::Argument),
+    }?;
+    let one_sided = match args[2] {
+        Negotiated(negotiated) => Ok(!negotiated),
+        _ => Err(CommandError::Argument),
+    }?;
+    let first_payment = match args[3] {
+        Int(height) => Ok(PaymentTrigger::Height(height)),
+        Date(time) => Ok(PaymentTrigger::Time(time.naive_utc())),
+        _ => Err(CommandError::Argument),
+    }?;
+    let message = match args[7] {
+        Text(ref message) => Ok(message.clone()),
+        _ => Err(CommandError::Argument),
+    }?;
+    Ok(ScheduledPaymentRequest {
+        destination,
+        amount,
+        // The fee is estimated when each payment is made
+        fee_per_gram: MicroTari::from(0),
+        message,
+        one_sided,
+        recipient_lock_height: optional(&args[6])?,
+        first_payment,
+        repeat_interval: optional(&args[4])?,
+        num_payments: optional(&args[5])?,
+    })
+}
+
+async fn find_account(output_service: &mut OutputManagerHandle, name: &str) -> Result<Account, CommandError> {
+    output_service
+        .get_accounts()
+        .await?
+        .into_iter()
+        .find(|account| account.name == name)
+        .ok_or_else(|| CommandError::UnknownAccount(name.to_string()))
+}
+
+fn render_qr_code(data: &str) -> Result<String, CommandError> {
+    let code = QrCode::new(data).map_err(|e| CommandError::Config(e.to_string()))?;
+    Ok(code
+        .render::<unicode::Dense1x2>()
+        .dark_color(unicode::Dense1x2::Dark)
+        .light_color(unicode::Dense1x2::Light)
+        .build())
+}
+
+/// Read the payments of a batch transaction from a CSV file with one `public key or emoji id,amount,message` row per
+/// payment. The message is optional and may contain commas. Blank lines and lines starting with `#` are ignored.
+fn read_batch_payments_csv(file_path: &str) -> Result<Vec<BatchPaymentRecipient>, CommandError> {
+    let contents = fs::read_to_string(file_path).map_err(|e| CommandError::CSVFile(e.to_string()))?;
+    let recipients = parse_batch_payments(&contents)?;
+    if recipients.is_empty() {
+        return Err(CommandError::CSVFile(format!("No payments found in {}", file_path)));
+    }
+    Ok(recipients)
+}
+
+fn parse_batch_payments(contents: &str) -> Result<Vec<BatchPaymentRecipient>, CommandError> {
+    contents
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(line_number, line)| {
+            let mut fields = line.splitn(3, ',').map(|field| field.trim().trim_matches('"'));
+            let destination = fields.next().and_then(parse_emoji_id_or_public_key).ok_or_else(|| {
+                CommandError::CSVFile(format!("Invalid public key or emoji id on line {}", line_number))
+            })?;
+            let amount = fields
+                .next()
+                .and_then(|amount| MicroTari::from_str(amount).ok())
+                .ok_or_else(|| CommandError::CSVFile(format!("Invalid amount on line {}", line_number)))?;
+            let message = fields.next().unwrap_or_default().to_string();
+            Ok(BatchPaymentRecipient {
+                destination,
+                amount,
+                message,
+            })
+        })
+        .collect()
+}
+
 fn write_utxos_to_csv_file(utxos: Vec<UnblindedOutput>, file_path: String) -> Result<(), CommandError> {
     let factory = PedersenCommitmentFactory::default();
     let file = File::create(file_path).map_err(|e| CommandError::CSVFile(e.to_string()))?;
@@ -991,3 +1562,36 @@ fn write_utxos_to_csv_file(utxos: Vec<UnblindedOutput>, file_path: String) -> Re
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::OsRng;
+    use tari_common_types::{emoji::EmojiId, types::PublicKey};
+    use tari_core::transactions::tari_amount::MicroTari;
+    use tari_crypto::keys::PublicKey as PublicKeyTrait;
+    use tari_utilities::hex::Hex;
+
+    use super::parse_batch_payments;
+
+    #[test]
+    fn it_parses_batch_payments() {
+        let (_, alice) = PublicKey::random_keypair(&mut OsRng);
+        let (_, bob) = PublicKey::random_keypair(&mut OsRng);
+        let csv = format!(
+            "# destination,amount,message\n{},1000,\"March salary, thanks\"\n\n{}, 2.5T\n",
+            alice.to_hex(),
+            EmojiId::from_pubkey(&bob)
+        );
+        let recipients = parse_batch_payments(&csv).unwrap();
+        assert_eq!(recipients.len(), 2);
+        assert_eq!(recipients[0].destination, alice);
+        assert_eq!(recipients[0].amount, MicroTari::from(1000));
+        assert_eq!(recipients[0].message, "March salary, thanks");
+        assert_eq!(recipients[1].destination, bob);
+        assert_eq!(recipients[1].amount, MicroTari::from(2_500_000));
+        assert!(recipients[1].message.is_empty());
+
+        assert!(parse_batch_payments(&format!("{},lots", alice.to_hex())).is_err());
+        assert!(parse_batch_payments("not a key,1000").is_err());
+    }
+}
@@ -28,9 +28,10 @@ use tari_core::transactions::{
     tari_amount::{MicroTariError, TariConversionError},
     transaction_components::TransactionError,
 };
-use tari_utilities::hex::HexError;
+use tari_utilities::{hex::HexError, message_format::MessageFormatError};
 use tari_wallet::{
     error::{WalletError, WalletStorageError},
+    invoice_service::error::InvoiceServiceError,
     output_manager_service::error::OutputManagerError,
     transaction_service::error::TransactionServiceError,
 };
@@ -52,6 +53,8 @@ pub enum CommandError {
     TransactionServiceError(#[from] TransactionServiceError),
     #[error("Output manager error: `{0}`")]
     OutputManagerError(#[from] OutputManagerError),
+    #[error("Invoice service error: `{0}`")]
+    InvoiceServiceError(#[from] InvoiceServiceError),
     #[error("Tokio join error `{0}`")]
     Join(#[from] JoinError),
     #[error("Config error `{0}`")]
@@ -66,8 +69,14 @@ pub enum CommandError {
     WalletStorageError(#[from] WalletStorageError),
     #[error("Hex error `{0}`")]
     HexError(#[from] HexError),
+    #[error("Serialization error `{0}`")]
+    MessageFormatError(#[from] MessageFormatError),
+    #[error("File error `{0}`")]
+    FileError(String),
     #[error("Error `{0}`")]
     ShaError(String),
+    #[error("No account named `{0}`")]
+    UnknownAccount(String),
 }
 
 impl From<CommandError> for ExitError {
@@ -97,6 +106,10 @@ pub enum ParseError {
     Date(#[from] chrono::ParseError),
     #[error("Failed to parse a net address.")]
     Address,
+    #[error("Failed to parse covenant. {0}")]
+    Covenant(String),
+    #[error("Failed to parse UTXO selection. {0}")]
+    SelectionStrategy(String),
     #[error("Invalid combination of arguments ({0}).")]
     Invalid(String),
     #[error("Parsing not yet implemented for {0}.")]
@@ -0,0 +1,229 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! An in-memory, sequenced log of transaction service events backing the streaming gRPC subscriptions.
+//!
+//! Every event is assigned a monotonically increasing sequence number and the most recent events are retained, so
+//! that a client which reconnects with the cursor of the last event it processed can be sent everything it missed.
+//! Cursors also carry the epoch of the log, a random value chosen when the wallet starts, because sequence numbers
+//! start again from one after a restart and events raised while the wallet was down cannot be replayed.
+
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use log::*;
+use rand::{rngs::OsRng, RngCore};
+use tari_shutdown::ShutdownSignal;
+use tari_wallet::transaction_service::handle::{TransactionEvent, TransactionEventReceiver};
+use thiserror::Error;
+use tokio::sync::broadcast;
+
+const LOG_TARGET: &str = "wallet::ui::grpc::event_log";
+
+/// The number of events retained for replay to reconnecting clients
+pub const EVENT_LOG_CAPACITY: usize = 10_000;
+const SUBSCRIBER_CHANNEL_SIZE: usize = 1_000;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum EventLogError {
+    #[error("Cursor belongs to a previous wallet session, resynchronise the transaction history")]
+    EpochMismatch,
+    #[error("Events after sequence {0} are no longer retained, resynchronise the transaction history")]
+    CursorExpired(u64),
+    #[error("Cursor sequence {0} is ahead of the latest event")]
+    CursorAhead(u64),
+}
+
+#[derive(Debug, Clone)]
+pub struct SequencedEvent {
+    pub sequence: u64,
+    pub event: Arc<TransactionEvent>,
+}
+
+struct EventLogInner {
+    next_sequence: u64,
+    events: VecDeque<SequencedEvent>,
+}
+
+#[derive(Clone)]
+pub struct TransactionEventLog {
+    epoch: u64,
+    capacity: usize,
+    inner: Arc<Mutex<EventLogInner>>,
+    sender: broadcast::Sender<SequencedEvent>,
+}
+
+impl TransactionEventLog {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(SUBSCRIBER_CHANNEL_SIZE);
+        Self {
+            epoch: OsRng.next_u64(),
+            capacity,
+            inner: Arc::new(Mutex::new(EventLogInner {
+                next_sequence: 1,
+                events: VecDeque::with_capacity(capacity),
+            })),
+            sender,
+        }
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// Assign the next sequence number to the event, retain it and publish it to live subscribers
+    pub fn record(&self, event: Arc<TransactionEvent>) -> u64 {
+        let mut inner = self.inner.lock().expect("Event log lock poisoned");
+        let sequence = inner.next_sequence;
+        inner.next_sequence += 1;
+        let sequenced = SequencedEvent { sequence, event };
+        inner.events.push_back(sequenced.clone());
+        while inner.events.len() > self.capacity {
+            inner.events.pop_front();
+        }
+        // Publishing while holding the lock keeps `subscribe` atomic with respect to new events. An error only means
+        // that there are no live subscribers.
+        let _ = self.sender.send(sequenced);
+        sequence
+    }
+
+    /// Record that `num_missed` events were lost before they could be sequenced. Their sequence numbers are skipped
+    /// and the retained events are discarded, so no cursor from before the gap can be resumed.
+    pub fn record_gap(&self, num_missed: u64) {
+        let mut inner = self.inner.lock().expect("Event log lock poisoned");
+        inner.next_sequence += num_missed;
+        inner.events.clear();
+    }
+
+    /// Subscribe to the log. When `resume_from` is the sequence of the last event a client processed in this epoch,
+    /// the retained events after it are returned for replay; the receiver yields every event recorded afterwards.
+    pub fn subscribe(
+        &self,
+        resume_from: Option<(u64, u64)>,
+    ) -> Result<(Vec<SequencedEvent>, broadcast::Receiver<SequencedEvent>), EventLogError> {
+        let inner = self.inner.lock().expect("Event log lock poisoned");
+        let replay = match resume_from {
+            None => Vec::new(),
+            Some((epoch, sequence)) => {
+                if epoch != self.epoch {
+                    return Err(EventLogError::EpochMismatch);
+                }
+                let latest = inner.next_sequence - 1;
+                if sequence > latest {
+                    return Err(EventLogError::CursorAhead(sequence));
+                }
+                let oldest_retained = inner.events.front().map(|e| e.sequence).unwrap_or(inner.next_sequence);
+                if sequence < latest && oldest_retained > sequence + 1 {
+                    return Err(EventLogError::CursorExpired(sequence));
+                }
+                inner.events.iter().filter(|e| e.sequence > sequence).cloned().collect()
+            },
+        };
+        Ok((replay, self.sender.subscribe()))
+    }
+
+    /// Record every event published by the transaction service until it closes or the wallet shuts down
+    pub async fn run(self, mut event_stream: TransactionEventReceiver, mut shutdown_signal: ShutdownSignal) {
+        loop {
+            tokio::select! {
+                result = event_stream.recv() => {
+                    match result {
+                        Ok(event) => {
+                            self.record(event);
+                        },
+                        Err(broadcast::error::RecvError::Lagged(n)) => {
+                            warn!(target: LOG_TARGET, "Event log missed {} transaction events", n);
+                            self.record_gap(n);
+                        },
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    }
+                },
+                _ = shutdown_signal.wait() => break,
+            }
+        }
+        debug!(target: LOG_TARGET, "Transaction event log stopped");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tari_common_types::transaction::TxId;
+
+    use super::*;
+
+    fn event(tx_id: u64) -> Arc<TransactionEvent> {
+        Arc::new(TransactionEvent::TransactionBroadcast(TxId::from(tx_id)))
+    }
+
+    #[test]
+    fn it_replays_events_after_the_cursor() {
+        let log = TransactionEventLog::new(3);
+        for i in 1..=4 {
+            log.record(event(i));
+        }
+        let epoch = log.epoch();
+
+        let (replay, _) = log.subscribe(Some((epoch, 2))).unwrap();
+        assert_eq!(replay.iter().map(|e| e.sequence).collect::<Vec<_>>(), vec![3, 4]);
+        assert_eq!(replay[0].event.tx_id(), Some(TxId::from(3u64)));
+
+        // Sequence 1 was evicted, but nothing after sequence 1 was
+        let (replay, _) = log.subscribe(Some((epoch, 1))).unwrap();
+        assert_eq!(replay.len(), 3);
+        assert!(log.subscribe(Some((epoch, 4))).unwrap().0.is_empty());
+
+        assert_eq!(
+            log.subscribe(Some((epoch, 0))).unwrap_err(),
+            EventLogError::CursorExpired(0)
+        );
+        assert_eq!(
+            log.subscribe(Some((epoch, 5))).unwrap_err(),
+            EventLogError::CursorAhead(5)
+        );
+        assert_eq!(
+            log.subscribe(Some((epoch.wrapping_add(1), 2))).unwrap_err(),
+            EventLogError::EpochMismatch
+        );
+    }
+
+    #[test]
+    fn it_forwards_new_events_and_invalidates_cursors_across_gaps() {
+        let log = TransactionEventLog::new(10);
+        log.record(event(1));
+        let (replay, mut receiver) = log.subscribe(None).unwrap();
+        assert!(replay.is_empty());
+
+        assert_eq!(log.record(event(2)), 2);
+        assert_eq!(receiver.try_recv().unwrap().sequence, 2);
+
+        log.record_gap(5);
+        assert_eq!(log.record(event(3)), 8);
+        assert_eq!(receiver.try_recv().unwrap().sequence, 8);
+        assert_eq!(
+            log.subscribe(Some((log.epoch(), 2))).unwrap_err(),
+            EventLogError::CursorExpired(2)
+        );
+        assert_eq!(log.subscribe(Some((log.epoch(), 7))).unwrap().0.len(), 1);
+    }
+}
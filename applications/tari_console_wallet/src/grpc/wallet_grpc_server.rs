@@ -20,15 +20,20 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::convert::{TryFrom, TryInto};
+use std::{
+    convert::{TryFrom, TryInto},
+    str::FromStr,
+    time::Duration,
+};
 
+use chrono::NaiveDateTime;
 use futures::{channel::mpsc, future, SinkExt};
 use log::*;
 use tari_app_grpc::{
     conversions::naive_datetime_to_timestamp,
     tari_rpc::{
         self,
-        payment_recipient::PaymentType,
+        payment_recipient::{PaymentType, UtxoSelectionStrategy},
         wallet_server,
         ClaimHtlcRefundRequest,
         ClaimHtlcRefundResponse,
@@ -78,32 +83,63 @@ use tari_app_grpc::{
 };
 use tari_common_types::{
     array::copy_into_fixed_array,
-    types::{BlockHash, PublicKey, Signature},
+    emoji::EmojiId,
+    types::{BlockHash, Commitment, PublicKey, Signature},
 };
 use tari_comms::{multiaddr::Multiaddr, types::CommsPublicKey, CommsNode};
-use tari_core::transactions::{
-    tari_amount::MicroTari,
-    transaction_components::{OutputFeatures, UnblindedOutput},
+use tari_core::{
+    covenants::Covenant,
+    transactions::{
+        tari_amount::MicroTari,
+        transaction_components::{OutputFeatures, UnblindedOutput},
+    },
 };
 use tari_crypto::{ristretto::RistrettoPublicKey, tari_utilities::Hashable};
 use tari_utilities::{hex::Hex, ByteArray};
 use tari_wallet::{
-    output_manager_service::handle::OutputManagerHandle,
-    transaction_service::{handle::TransactionServiceHandle, storage::models},
+    contacts_service::{
+        error::{ContactsServiceError, ContactsServiceStorageError},
+        handle::ContactsServiceHandle,
+        import_export,
+        storage::database::{Contact, SendMethod},
+    },
+    invoice_service::{error::InvoiceServiceError, handle::InvoiceServiceHandle, invoice::Invoice},
+    output_manager_service::{
+        error::OutputManagerError,
+        handle::OutputManagerHandle,
+        service::UTXOSelectionStrategy,
+        storage::models::Account,
+    },
+    transaction_service::{
+        error::TransactionServiceError,
+        handle::{BatchPaymentRecipient, ScheduledPaymentRequest, TransactionEvent, TransactionServiceHandle},
+        storage::models::{self, PaymentTrigger, ScheduledPayment},
+    },
     WalletSqlite,
 };
-use tokio::task;
+use tokio::{sync::broadcast, task};
 use tonic::{Request, Response, Status};
 
+use super::event_log::{SequencedEvent, TransactionEventLog, EVENT_LOG_CAPACITY};
+
 const LOG_TARGET: &str = "wallet::ui::grpc";
+const SUBSCRIPTION_STREAM_BUFFER: usize = 100;
 
 pub struct WalletGrpcServer {
     wallet: WalletSqlite,
+    event_log: TransactionEventLog,
 }
 
 impl WalletGrpcServer {
+    /// Create the server and start recording transaction events for the streaming subscriptions. Must be called from
+    /// within a tokio runtime.
     pub fn new(wallet: WalletSqlite) -> Self {
-        Self { wallet }
+        let event_log = TransactionEventLog::new(EVENT_LOG_CAPACITY);
+        task::spawn(event_log.clone().run(
+            wallet.transaction_service.get_event_stream(),
+            wallet.comms.shutdown_signal(),
+        ));
+        Self { wallet, event_log }
     }
 
     fn get_transaction_service(&self) -> TransactionServiceHandle {
@@ -114,6 +150,75 @@ impl WalletGrpcServer {
         self.wallet.output_manager_service.clone()
     }
 
+    /// Resolves the account named in a request to its id. An empty name does not select an account.
+    async fn find_account_id(&self, name: &str) -> Result<Option<u64>, Status> {
+        if name.is_empty() {
+            return Ok(None);
+        }
+        self.get_output_manager_service()
+            .get_accounts()
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .into_iter()
+            .find(|account| account.name == name)
+            .map(|account| Some(account.id))
+            .ok_or_else(|| Status::not_found(format!("No account named `{}`", name)))
+    }
+
+    fn get_account_transaction_service(&self, account_id: Option<u64>) -> TransactionServiceHandle {
+        match account_id {
+            Some(account_id) => self.wallet.transaction_service.for_account(account_id),
+            None => self.get_transaction_service(),
+        }
+    }
+
+    fn get_account_output_manager_service(&self, account_id: Option<u64>) -> OutputManagerHandle {
+        match account_id {
+            Some(account_id) => self.wallet.output_manager_service.for_account(account_id),
+            None => self.get_output_manager_service(),
+        }
+    }
+
+    async fn account_info(&self, account: Account) -> Result<tari_rpc::AccountInfo, Status> {
+        let balance = self
+            .get_account_output_manager_service(Some(account.id))
+            .get_balance()
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        Ok(tari_rpc::AccountInfo {
+            id: account.id,
+            name: account.name,
+            available_balance: balance.available_balance.0,
+            pending_incoming_balance: balance.pending_incoming_balance.0,
+            pending_outgoing_balance: balance.pending_outgoing_balance.0,
+        })
+    }
+
+    fn get_invoice_service(&self) -> InvoiceServiceHandle {
+        self.wallet.invoice_service.clone()
+    }
+
+    fn invoice_info(&self, invoice: Invoice) -> tari_rpc::InvoiceInfo {
+        let uri = invoice.payment_request(self.wallet.network.as_network()).to_uri();
+        tari_rpc::InvoiceInfo {
+            id: invoice.id,
+            reference: invoice.reference,
+            amount: invoice.amount.into(),
+            recipient: invoice.recipient.to_vec(),
+            message: invoice.message,
+            status: invoice.status.to_string(),
+            tx_id: invoice.tx_id.map(|tx_id| tx_id.as_u64()).unwrap_or_default(),
+            created_at: Some(naive_datetime_to_timestamp(invoice.created_at)),
+            expires_at: invoice.expires_at.map(naive_datetime_to_timestamp),
+            paid_at: invoice.paid_at.map(naive_datetime_to_timestamp),
+            uri,
+        }
+    }
+
+    fn get_contacts_service(&self) -> ContactsServiceHandle {
+        self.wallet.contacts_service.clone()
+    }
+
     fn comms(&self) -> &CommsNode {
         &self.wallet.comms
     }
@@ -122,6 +227,8 @@ impl WalletGrpcServer {
 #[tonic::async_trait]
 impl wallet_server::Wallet for WalletGrpcServer {
     type GetCompletedTransactionsStream = mpsc::Receiver<Result<GetCompletedTransactionsResponse, Status>>;
+    type SubscribeBalanceStream = mpsc::Receiver<Result<GetBalanceResponse, Status>>;
+    type SubscribeTransactionEventsStream = mpsc::Receiver<Result<tari_rpc::TransactionEventResponse, Status>>;
 
     async fn get_version(&self, _: Request<GetVersionRequest>) -> Result<Response<GetVersionResponse>, Status> {
         Ok(Response::new(GetVersionResponse {
@@ -177,8 +284,9 @@ impl wallet_server::Wallet for WalletGrpcServer {
         Ok(Response::new(SetBaseNodeResponse {}))
     }
 
-    async fn get_balance(&self, _request: Request<GetBalanceRequest>) -> Result<Response<GetBalanceResponse>, Status> {
-        let mut output_service = self.get_output_manager_service();
+    async fn get_balance(&self, request: Request<GetBalanceRequest>) -> Result<Response<GetBalanceResponse>, Status> {
+        let account_id = self.find_account_id(&request.into_inner().account).await?;
+        let mut output_service = self.get_account_output_manager_service(account_id);
         let balance;
         match output_service.get_balance().await {
             Ok(b) => balance = b,
@@ -408,6 +516,7 @@ impl wallet_server::Wallet for WalletGrpcServer {
 
     async fn transfer(&self, request: Request<TransferRequest>) -> Result<Response<TransferResponse>, Status> {
         let message = request.into_inner();
+        let account_id = self.find_account_id(&message.account).await?;
         let recipients = message
             .recipients
             .into_iter()
@@ -415,6 +524,20 @@ impl wallet_server::Wallet for WalletGrpcServer {
             .map(|(idx, dest)| -> Result<_, String> {
                 let pk = CommsPublicKey::from_hex(&dest.address)
                     .map_err(|_| format!("Destination address at index {} is malformed", idx))?;
+                let covenant = Covenant::from_str(&dest.covenant)
+                    .map_err(|e| format!("Covenant at index {} is malformed: {}", idx, e))?;
+                let selection_strategy = utxo_selection_strategy(dest.selection_strategy, &dest.utxo_commitments)
+                    .map_err(|e| format!("UTXO selection at index {} is invalid: {}", idx, e))?;
+                if dest.lock_height > 0 &&
+                    (dest.payment_type != PaymentType::OneSided as i32 ||
+                        !dest.covenant.trim().is_empty() ||
+                        selection_strategy.is_some())
+                {
+                    return Err(format!(
+                        "Lock height at index {} requires a one-sided payment without a covenant or UTXO selection",
+                        idx
+                    ));
+                }
                 Ok((
                     dest.address,
                     pk,
@@ -422,32 +545,76 @@ impl wallet_server::Wallet for WalletGrpcServer {
                     dest.fee_per_gram,
                     dest.message,
                     dest.payment_type,
+                    covenant,
+                    selection_strategy,
+                    dest.lock_height,
                 ))
             })
             .collect::<Result<Vec<_>, _>>()
             .map_err(Status::invalid_argument)?;
 
+        // Recipients without a fee per gram use the base node's estimate
+        let estimated_fee_per_gram = if recipients
+            .iter()
+            .any(|(_, _, _, fee_per_gram, _, _, _, _, _)| *fee_per_gram == 0)
+        {
+            self.get_output_manager_service()
+                .estimate_fee_per_gram(None)
+                .await
+                .map_err(|e| Status::internal(e.to_string()))?
+        } else {
+            MicroTari::from(0)
+        };
+
         let mut standard_transfers = Vec::new();
         let mut one_sided_transfers = Vec::new();
-        for (address, pk, amount, fee_per_gram, message, payment_type) in recipients.into_iter() {
-            let mut transaction_service = self.get_transaction_service();
+        for (address, pk, amount, fee_per_gram, message, payment_type, covenant, selection_strategy, lock_height) in
+            recipients.into_iter()
+        {
+            let fee_per_gram = match fee_per_gram {
+                0 => estimated_fee_per_gram,
+                fee_per_gram => fee_per_gram.into(),
+            };
+            let mut transaction_service = self.get_account_transaction_service(account_id);
             if payment_type == PaymentType::StandardMimblewimble as i32 {
                 standard_transfers.push(async move {
                     (
                         address,
                         transaction_service
-                            .send_transaction(pk, amount.into(), fee_per_gram.into(), message)
+                            .send_transaction_or_token(
+                                pk,
+                                amount.into(),
+                                None,
+                                None,
+                                fee_per_gram,
+                                message,
+                                covenant,
+                                selection_strategy,
+                            )
                             .await,
                     )
                 });
             } else if payment_type == PaymentType::OneSided as i32 {
                 one_sided_transfers.push(async move {
-                    (
-                        address,
+                    let result = if lock_height > 0 {
                         transaction_service
-                            .send_one_sided_transaction(pk, amount.into(), fee_per_gram.into(), message)
-                            .await,
-                    )
+                            .send_time_locked_transaction(pk, amount.into(), fee_per_gram, message, lock_height)
+                            .await
+                    } else {
+                        transaction_service
+                            .send_one_sided_transaction_or_token(
+                                pk,
+                                amount.into(),
+                                None,
+                                None,
+                                fee_per_gram,
+                                message,
+                                covenant,
+                                selection_strategy,
+                            )
+                            .await
+                    };
+                    (address, result)
                 });
             }
         }
@@ -519,13 +686,14 @@ impl wallet_server::Wallet for WalletGrpcServer {
 
     async fn get_completed_transactions(
         &self,
-        _request: Request<GetCompletedTransactionsRequest>,
+        request: Request<GetCompletedTransactionsRequest>,
     ) -> Result<Response<Self::GetCompletedTransactionsStream>, Status> {
         debug!(
             target: LOG_TARGET,
             "Incoming GRPC request for GetAllCompletedTransactions"
         );
-        let mut transaction_service = self.get_transaction_service();
+        let account_id = self.find_account_id(&request.into_inner().account).await?;
+        let mut transaction_service = self.get_account_transaction_service(account_id);
         let transactions = transaction_service
             .get_completed_transactions()
             .await
@@ -937,6 +1105,684 @@ impl wallet_server::Wallet for WalletGrpcServer {
             },
         }
     }
+
+    async fn create_multisig(
+        &self,
+        request: Request<tari_rpc::CreateMultisigRequest>,
+    ) -> Result<Response<tari_rpc::CreateMultisigResponse>, Status> {
+        let message = request.into_inner();
+        let cosigners = message
+            .cosigners
+            .iter()
+            .map(|c| CommsPublicKey::from_hex(c))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| Status::invalid_argument("Cosigner public key is malformed".to_string()))?;
+        let threshold = u8::try_from(message.threshold)
+            .map_err(|_| Status::invalid_argument("Threshold is too large".to_string()))?;
+        let fee_per_gram = if message.fee_per_gram == 0 {
+            self.get_output_manager_service()
+                .estimate_fee_per_gram(None)
+                .await
+                .map_err(|e| Status::internal(e.to_string()))?
+        } else {
+            message.fee_per_gram.into()
+        };
+
+        let session_id = self
+            .get_transaction_service()
+            .create_multisig_output(
+                cosigners,
+                threshold,
+                message.amount.into(),
+                fee_per_gram,
+                message.message,
+            )
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        Ok(Response::new(tari_rpc::CreateMultisigResponse { session_id }))
+    }
+
+    async fn propose_multisig_spend(
+        &self,
+        request: Request<tari_rpc::ProposeMultisigSpendRequest>,
+    ) -> Result<Response<tari_rpc::ProposeMultisigSpendResponse>, Status> {
+        let message = request.into_inner();
+        let destination = CommsPublicKey::from_hex(&message.destination)
+            .map_err(|_| Status::invalid_argument("Destination address is malformed".to_string()))?;
+        let fee_per_gram = if message.fee_per_gram == 0 {
+            self.get_output_manager_service()
+                .estimate_fee_per_gram(None)
+                .await
+                .map_err(|e| Status::internal(e.to_string()))?
+        } else {
+            message.fee_per_gram.into()
+        };
+
+        self.get_transaction_service()
+            .propose_multisig_spend(message.session_id, destination, fee_per_gram, message.message)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        Ok(Response::new(tari_rpc::ProposeMultisigSpendResponse {}))
+    }
+
+    async fn approve_multisig_spend(
+        &self,
+        request: Request<tari_rpc::ApproveMultisigSpendRequest>,
+    ) -> Result<Response<tari_rpc::ApproveMultisigSpendResponse>, Status> {
+        self.get_transaction_service()
+            .approve_multisig_spend(request.into_inner().session_id)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        Ok(Response::new(tari_rpc::ApproveMultisigSpendResponse {}))
+    }
+
+    async fn list_multisig_sessions(
+        &self,
+        _: Request<tari_rpc::Empty>,
+    ) -> Result<Response<tari_rpc::ListMultisigSessionsResponse>, Status> {
+        let sessions = self
+            .get_transaction_service()
+            .get_multisig_sessions()
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        let sessions = sessions
+            .into_iter()
+            .map(|session| tari_rpc::MultisigSessionInfo {
+                session_id: session.session_id,
+                leader: session.leader.to_vec(),
+                members: session.members.iter().map(|m| m.to_vec()).collect(),
+                threshold: session.threshold.into(),
+                amount: session.amount.into(),
+                status: session.status.to_string(),
+                funding_tx_id: session.funding_tx_id.map(|tx_id| tx_id.as_u64()).unwrap_or_default(),
+                spend: session.spend.map(|spend| tari_rpc::MultisigSpendInfo {
+                    proposer: spend.proposer.to_vec(),
+                    destination: spend.destination.to_vec(),
+                    num_approvals: spend.num_approvals() as u32,
+                    tx_id: spend.tx_id.map(|tx_id| tx_id.as_u64()).unwrap_or_default(),
+                }),
+            })
+            .collect();
+        Ok(Response::new(tari_rpc::ListMultisigSessionsResponse { sessions }))
+    }
+
+    async fn send_batch(
+        &self,
+        request: Request<tari_rpc::SendBatchRequest>,
+    ) -> Result<Response<tari_rpc::SendBatchResponse>, Status> {
+        let message = request.into_inner();
+        let recipients = message
+            .payments
+            .into_iter()
+            .enumerate()
+            .map(|(idx, payment)| {
+                let destination = CommsPublicKey::from_hex(&payment.address)
+                    .map_err(|_| format!("Destination address at index {} is malformed", idx))?;
+                Ok(BatchPaymentRecipient {
+                    destination,
+                    amount: payment.amount.into(),
+                    message: payment.message,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()
+            .map_err(Status::invalid_argument)?;
+        let fee_per_gram = if message.fee_per_gram == 0 {
+            self.get_output_manager_service()
+                .estimate_fee_per_gram(None)
+                .await
+                .map_err(|e| Status::internal(e.to_string()))?
+        } else {
+            message.fee_per_gram.into()
+        };
+
+        let tx_id = self
+            .get_transaction_service()
+            .send_one_sided_batch_transaction(recipients, fee_per_gram, message.message)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        Ok(Response::new(tari_rpc::SendBatchResponse {
+            transaction_id: tx_id.as_u64(),
+        }))
+    }
+
+    async fn create_invoice(
+        &self,
+        request: Request<tari_rpc::CreateInvoiceRequest>,
+    ) -> Result<Response<tari_rpc::CreateInvoiceResponse>, Status> {
+        let message = request.into_inner();
+        let reference = Some(message.reference).filter(|r| !r.is_empty());
+        let expires_in = Some(message.expires_in_seconds)
+            .filter(|s| *s > 0)
+            .map(Duration::from_secs);
+        let invoice = self
+            .get_invoice_service()
+            .create_invoice(message.amount.into(), reference, message.message, expires_in)
+            .await
+            .map_err(invoice_service_status)?;
+        Ok(Response::new(tari_rpc::CreateInvoiceResponse {
+            invoice: Some(self.invoice_info(invoice)),
+        }))
+    }
+
+    async fn list_invoices(
+        &self,
+        _: Request<tari_rpc::ListInvoicesRequest>,
+    ) -> Result<Response<tari_rpc::ListInvoicesResponse>, Status> {
+        let invoices = self
+            .get_invoice_service()
+            .get_invoices()
+            .await
+            .map_err(invoice_service_status)?;
+        Ok(Response::new(tari_rpc::ListInvoicesResponse {
+            invoices: invoices.into_iter().map(|i| self.invoice_info(i)).collect(),
+        }))
+    }
+
+    async fn cancel_invoice(
+        &self,
+        request: Request<tari_rpc::CancelInvoiceRequest>,
+    ) -> Result<Response<tari_rpc::CancelInvoiceResponse>, Status> {
+        let invoice = self
+            .get_invoice_service()
+            .cancel_invoice(request.into_inner().invoice_id)
+            .await
+            .map_err(invoice_service_status)?;
+        Ok(Response::new(tari_rpc::CancelInvoiceResponse {
+            invoice: Some(self.invoice_info(invoice)),
+        }))
+    }
+
+    async fn create_account(
+        &self,
+        request: Request<tari_rpc::CreateAccountRequest>,
+    ) -> Result<Response<tari_rpc::CreateAccountResponse>, Status> {
+        let name = request.into_inner().name;
+        if name.is_empty() {
+            return Err(Status::invalid_argument("Account name cannot be empty"));
+        }
+        let account = self
+            .get_output_manager_service()
+            .create_account(name)
+            .await
+            .map_err(|e| match e {
+                OutputManagerError::AccountAlreadyExists(_) => Status::already_exists(e.to_string()),
+                _ => Status::internal(e.to_string()),
+            })?;
+        Ok(Response::new(tari_rpc::CreateAccountResponse {
+            account: Some(self.account_info(account).await?),
+        }))
+    }
+
+    async fn list_accounts(
+        &self,
+        _: Request<tari_rpc::Empty>,
+    ) -> Result<Response<tari_rpc::ListAccountsResponse>, Status> {
+        let accounts = self
+            .get_output_manager_service()
+            .get_accounts()
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        let mut infos = Vec::with_capacity(accounts.len());
+        for account in accounts {
+            infos.push(self.account_info(account).await?);
+        }
+        Ok(Response::new(tari_rpc::ListAccountsResponse { accounts: infos }))
+    }
+
+    async fn transfer_between_accounts(
+        &self,
+        request: Request<tari_rpc::TransferBetweenAccountsRequest>,
+    ) -> Result<Response<tari_rpc::TransferBetweenAccountsResponse>, Status> {
+        let message = request.into_inner();
+        let from_account = self.find_account_id(&message.from_account).await?;
+        let to_account = self
+            .find_account_id(&message.to_account)
+            .await?
+            .ok_or_else(|| Status::invalid_argument("No destination account given"))?;
+        let fee_per_gram = if message.fee_per_gram == 0 {
+            self.get_output_manager_service()
+                .estimate_fee_per_gram(None)
+                .await
+                .map_err(|e| Status::internal(e.to_string()))?
+        } else {
+            message.fee_per_gram.into()
+        };
+        let tx_id = self
+            .get_account_transaction_service(from_account)
+            .transfer_between_accounts(to_account, message.amount.into(), fee_per_gram, message.message)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        Ok(Response::new(tari_rpc::TransferBetweenAccountsResponse {
+            transaction_id: tx_id.as_u64(),
+        }))
+    }
+
+    async fn list_contacts(
+        &self,
+        _: Request<tari_rpc::Empty>,
+    ) -> Result<Response<tari_rpc::ListContactsResponse>, Status> {
+        let contacts = self
+            .get_contacts_service()
+            .get_contacts()
+            .await
+            .map_err(contacts_service_status)?;
+        Ok(Response::new(tari_rpc::ListContactsResponse {
+            contacts: contacts.into_iter().map(contact_info).collect(),
+        }))
+    }
+
+    async fn upsert_contact(
+        &self,
+        request: Request<tari_rpc::UpsertContactRequest>,
+    ) -> Result<Response<tari_rpc::UpsertContactResponse>, Status> {
+        let info = request
+            .into_inner()
+            .contact
+            .ok_or_else(|| Status::invalid_argument("No contact given"))?;
+        let public_key =
+            CommsPublicKey::from_bytes(&info.public_key).map_err(|_| Status::invalid_argument("Invalid public key"))?;
+        if info.alias.is_empty() {
+            return Err(Status::invalid_argument("Contact alias cannot be empty"));
+        }
+        let contact = Contact {
+            alias: info.alias,
+            public_key,
+            tags: info.tags,
+            notes: info.notes,
+            preferred_send_method: match tari_rpc::ContactSendMethod::from_i32(info.preferred_send_method) {
+                Some(tari_rpc::ContactSendMethod::OneSided) => SendMethod::OneSided,
+                Some(tari_rpc::ContactSendMethod::Interactive) => SendMethod::Interactive,
+                None => return Err(Status::invalid_argument("Invalid preferred send method")),
+            },
+            last_seen: None,
+        };
+        self.get_contacts_service()
+            .upsert_contact(contact)
+            .await
+            .map_err(contacts_service_status)?;
+        Ok(Response::new(tari_rpc::UpsertContactResponse {}))
+    }
+
+    async fn remove_contact(
+        &self,
+        request: Request<tari_rpc::RemoveContactRequest>,
+    ) -> Result<Response<tari_rpc::RemoveContactResponse>, Status> {
+        let public_key = CommsPublicKey::from_bytes(&request.into_inner().public_key)
+            .map_err(|_| Status::invalid_argument("Invalid public key"))?;
+        let contact = self
+            .get_contacts_service()
+            .remove_contact(public_key)
+            .await
+            .map_err(contacts_service_status)?;
+        Ok(Response::new(tari_rpc::RemoveContactResponse {
+            contact: Some(contact_info(contact)),
+        }))
+    }
+
+    async fn get_contact_transactions(
+        &self,
+        request: Request<tari_rpc::GetContactTransactionsRequest>,
+    ) -> Result<Response<tari_rpc::GetContactTransactionsResponse>, Status> {
+        let public_key = CommsPublicKey::from_bytes(&request.into_inner().public_key)
+            .map_err(|_| Status::invalid_argument("Invalid public key"))?;
+        let tx_ids = self
+            .get_contacts_service()
+            .get_contact_transactions(public_key)
+            .await
+            .map_err(contacts_service_status)?;
+        Ok(Response::new(tari_rpc::GetContactTransactionsResponse {
+            transaction_ids: tx_ids.into_iter().map(|tx_id| tx_id.as_u64()).collect(),
+        }))
+    }
+
+    async fn export_contacts(
+        &self,
+        request: Request<tari_rpc::ExportContactsRequest>,
+    ) -> Result<Response<tari_rpc::ExportContactsResponse>, Status> {
+        let format = request.into_inner().format;
+        let contacts = self
+            .get_contacts_service()
+            .get_contacts()
+            .await
+            .map_err(contacts_service_status)?;
+        let data = match format.to_lowercase().as_str() {
+            "csv" => import_export::export_csv(&contacts),
+            "json" => import_export::export_json(&contacts).map_err(contacts_service_status)?,
+            _ => return Err(Status::invalid_argument(format!("Unsupported format '{}'", format))),
+        };
+        Ok(Response::new(tari_rpc::ExportContactsResponse { data }))
+    }
+
+    async fn import_contacts(
+        &self,
+        request: Request<tari_rpc::ImportContactsRequest>,
+    ) -> Result<Response<tari_rpc::ImportContactsResponse>, Status> {
+        let message = request.into_inner();
+        let contacts = match message.format.to_lowercase().as_str() {
+            "csv" => import_export::import_csv(&message.data),
+            "json" => import_export::import_json(&message.data),
+            _ => {
+                return Err(Status::invalid_argument(format!(
+                    "Unsupported format '{}'",
+                    message.format
+                )))
+            },
+        }
+        .map_err(contacts_service_status)?;
+        let num_imported = self
+            .get_contacts_service()
+            .import_contacts(contacts)
+            .await
+            .map_err(contacts_service_status)?;
+        Ok(Response::new(tari_rpc::ImportContactsResponse {
+            num_imported: num_imported as u64,
+        }))
+    }
+
+    async fn schedule_payment(
+        &self,
+        request: Request<tari_rpc::SchedulePaymentRequest>,
+    ) -> Result<Response<tari_rpc::SchedulePaymentResponse>, Status> {
+        let message = request.into_inner();
+        let destination = CommsPublicKey::from_hex(&message.address)
+            .map_err(|_| Status::invalid_argument("Destination address is malformed"))?;
+        let first_payment = match (message.start_height, message.start_time) {
+            (0, Some(time)) => PaymentTrigger::Time(NaiveDateTime::from_timestamp(time.seconds, time.nanos as u32)),
+            (height, None) if height > 0 => PaymentTrigger::Height(height),
+            _ => {
+                return Err(Status::invalid_argument(
+                    "Exactly one of the start height and start time must be set",
+                ))
+            },
+        };
+        let optional = |v: u64| if v == 0 { None } else { Some(v) };
+        let id = self
+            .get_transaction_service()
+            .schedule_payment(ScheduledPaymentRequest {
+                destination,
+                amount: message.amount.into(),
+                fee_per_gram: message.fee_per_gram.into(),
+                message: message.message,
+                one_sided: message.one_sided,
+                recipient_lock_height: optional(message.lock_height),
+                first_payment,
+                repeat_interval: optional(message.repeat_interval),
+                num_payments: optional(message.num_payments),
+            })
+            .await
+            .map_err(transaction_service_status)?;
+        Ok(Response::new(tari_rpc::SchedulePaymentResponse { id }))
+    }
+
+    async fn list_scheduled_payments(
+        &self,
+        _: Request<tari_rpc::Empty>,
+    ) -> Result<Response<tari_rpc::ListScheduledPaymentsResponse>, Status> {
+        let payments = self
+            .get_transaction_service()
+            .get_scheduled_payments()
+            .await
+            .map_err(transaction_service_status)?;
+        Ok(Response::new(tari_rpc::ListScheduledPaymentsResponse {
+            payments: payments.into_iter().map(scheduled_payment_info).collect(),
+        }))
+    }
+
+    async fn cancel_scheduled_payment(
+        &self,
+        request: Request<tari_rpc::CancelScheduledPaymentRequest>,
+    ) -> Result<Response<tari_rpc::CancelScheduledPaymentResponse>, Status> {
+        self.get_transaction_service()
+            .cancel_scheduled_payment(request.into_inner().id)
+            .await
+            .map_err(transaction_service_status)?;
+        Ok(Response::new(tari_rpc::CancelScheduledPaymentResponse {}))
+    }
+
+    async fn subscribe_transaction_events(
+        &self,
+        request: Request<tari_rpc::SubscribeTransactionEventsRequest>,
+    ) -> Result<Response<Self::SubscribeTransactionEventsStream>, Status> {
+        let resume_from = request.into_inner().resume_from.map(|c| (c.epoch, c.sequence));
+        let (replay, mut live_events) = self
+            .event_log
+            .subscribe(resume_from)
+            .map_err(|e| Status::out_of_range(e.to_string()))?;
+        debug!(
+            target: LOG_TARGET,
+            "Incoming GRPC request for SubscribeTransactionEvents, replaying {} event(s)",
+            replay.len()
+        );
+
+        let epoch = self.event_log.epoch();
+        let transaction_service = self.get_transaction_service();
+        let wallet_pk = self.wallet.comms.node_identity_ref().public_key().clone();
+        let mut shutdown_signal = self.wallet.comms.shutdown_signal();
+        let (mut sender, receiver) = mpsc::channel(SUBSCRIPTION_STREAM_BUFFER);
+        task::spawn(async move {
+            for event in replay {
+                let response = transaction_event_response(epoch, event, transaction_service.clone(), &wallet_pk).await;
+                if sender.send(Ok(response)).await.is_err() {
+                    return;
+                }
+            }
+            loop {
+                let event = tokio::select! {
+                    result = live_events.recv() => match result {
+                        Ok(event) => event,
+                        Err(broadcast::error::RecvError::Lagged(n)) => {
+                            warn!(target: LOG_TARGET, "Transaction event subscriber lagged by {} events", n);
+                            let status = Status::data_loss(format!(
+                                "Subscriber fell behind by {} events, resubscribe from the last cursor received",
+                                n
+                            ));
+                            let _ = sender.send(Err(status)).await;
+                            return;
+                        },
+                        Err(broadcast::error::RecvError::Closed) => return,
+                    },
+                    _ = shutdown_signal.wait() => return,
+                };
+                let response = transaction_event_response(epoch, event, transaction_service.clone(), &wallet_pk).await;
+                if sender.send(Ok(response)).await.is_err() {
+                    debug!(target: LOG_TARGET, "Transaction event subscriber disconnected");
+                    return;
+                }
+            }
+        });
+
+        Ok(Response::new(receiver))
+    }
+
+    async fn subscribe_balance(
+        &self,
+        _request: Request<tari_rpc::SubscribeBalanceRequest>,
+    ) -> Result<Response<Self::SubscribeBalanceStream>, Status> {
+        debug!(target: LOG_TARGET, "Incoming GRPC request for SubscribeBalance");
+        let mut output_service = self.get_output_manager_service();
+        // Subscribe before reading the initial balance so that no change can be missed in between
+        let mut output_manager_events = output_service.get_event_stream();
+        let mut transaction_events = self.get_transaction_service().get_event_stream();
+        let mut shutdown_signal = self.wallet.comms.shutdown_signal();
+
+        let (mut sender, receiver) = mpsc::channel(SUBSCRIPTION_STREAM_BUFFER);
+        task::spawn(async move {
+            let mut last_balance = None;
+            loop {
+                match output_service.get_balance().await {
+                    Ok(balance) => {
+                        let balance = GetBalanceResponse {
+                            available_balance: balance.available_balance.0,
+                            pending_incoming_balance: balance.pending_incoming_balance.0,
+                            pending_outgoing_balance: balance.pending_outgoing_balance.0,
+                        };
+                        if last_balance.as_ref() != Some(&balance) {
+                            if sender.send(Ok(balance.clone())).await.is_err() {
+                                debug!(target: LOG_TARGET, "Balance subscriber disconnected");
+                                return;
+                            }
+                            last_balance = Some(balance);
+                        }
+                    },
+                    Err(e) => {
+                        let _ = sender
+                            .send(Err(Status::internal(format!("GetBalance error! {}", e))))
+                            .await;
+                        return;
+                    },
+                }
+
+                // Wait for any event that may have changed the balance. A lagged receiver only means the balance
+                // must be read again, which happens regardless.
+                tokio::select! {
+                    result = output_manager_events.recv() => {
+                        if let Err(broadcast::error::RecvError::Closed) = result {
+                            return;
+                        }
+                    },
+                    result = transaction_events.recv() => {
+                        if let Err(broadcast::error::RecvError::Closed) = result {
+                            return;
+                        }
+                    },
+                    _ = shutdown_signal.wait() => return,
+                }
+            }
+        });
+
+        Ok(Response::new(receiver))
+    }
+}
+
+fn invoice_service_status(err: InvoiceServiceError) -> Status {
+    match err {
+        InvoiceServiceError::InvoiceNotFound(_) => Status::not_found(err.to_string()),
+        InvoiceServiceError::DuplicateReference(_) => Status::already_exists(err.to_string()),
+        InvoiceServiceError::ZeroAmount => Status::invalid_argument(err.to_string()),
+        InvoiceServiceError::InvalidInvoiceStatus(_, _) => Status::failed_precondition(err.to_string()),
+        _ => Status::internal(err.to_string()),
+    }
+}
+
+fn contacts_service_status(err: ContactsServiceError) -> Status {
+    match err {
+        ContactsServiceError::ContactNotFound |
+        ContactsServiceError::ContactsServiceStorageError(ContactsServiceStorageError::ValueNotFound(_)) => {
+            Status::not_found(err.to_string())
+        },
+        ContactsServiceError::InvalidContactData(_) => Status::invalid_argument(err.to_string()),
+        ContactsServiceError::TransactionServiceUnavailable => Status::unavailable(err.to_string()),
+        _ => Status::internal(err.to_string()),
+    }
+}
+
+fn contact_info(contact: Contact) -> tari_rpc::ContactInfo {
+    let preferred_send_method = match contact.preferred_send_method {
+        SendMethod::Interactive => tari_rpc::ContactSendMethod::Interactive,
+        SendMethod::OneSided => tari_rpc::ContactSendMethod::OneSided,
+    };
+    tari_rpc::ContactInfo {
+        public_key: contact.public_key.to_vec(),
+        emoji_id: EmojiId::from_pubkey(&contact.public_key).to_string(),
+        alias: contact.alias,
+        tags: contact.tags,
+        notes: contact.notes,
+        preferred_send_method: preferred_send_method as i32,
+        last_seen: contact.last_seen.map(naive_datetime_to_timestamp),
+    }
+}
+
+fn transaction_service_status(err: TransactionServiceError) -> Status {
+    match err {
+        TransactionServiceError::ScheduledPaymentNotFound(_) => Status::not_found(err.to_string()),
+        TransactionServiceError::ScheduledPaymentNotActive(_) => Status::failed_precondition(err.to_string()),
+        TransactionServiceError::InvalidScheduledPayment(_) => Status::invalid_argument(err.to_string()),
+        _ => Status::internal(err.to_string()),
+    }
+}
+
+fn scheduled_payment_info(payment: ScheduledPayment) -> tari_rpc::ScheduledPaymentInfo {
+    let (next_payment_height, next_payment_time) = match payment.next_payment {
+        PaymentTrigger::Height(height) => (height, None),
+        PaymentTrigger::Time(time) => (0, Some(naive_datetime_to_timestamp(time))),
+    };
+    tari_rpc::ScheduledPaymentInfo {
+        id: payment.id,
+        destination: payment.destination.to_vec(),
+        amount: payment.amount.into(),
+        fee_per_gram: payment.fee_per_gram.into(),
+        message: payment.message,
+        one_sided: payment.one_sided,
+        lock_height: payment.recipient_lock_height.unwrap_or_default(),
+        next_payment_height,
+        next_payment_time,
+        repeat_interval: payment.repeat_interval.unwrap_or_default(),
+        remaining_payments: payment.remaining_payments.unwrap_or_default(),
+        status: payment.status.to_string(),
+        last_tx_id: payment.last_tx_id.map(|tx_id| tx_id.as_u64()).unwrap_or_default(),
+        created_at: Some(naive_datetime_to_timestamp(payment.created_at)),
+    }
+}
+
+/// Converts the UTXO selection of a gRPC payment recipient. Explicit commitments take precedence over the strategy.
+fn utxo_selection_strategy(
+    strategy: i32,
+    utxo_commitments: &[Vec<u8>],
+) -> Result<Option<UTXOSelectionStrategy>, String> {
+    if !utxo_commitments.is_empty() {
+        let commitments = utxo_commitments
+            .iter()
+            .map(|c| Commitment::from_bytes(c).map_err(|_| "Malformed UTXO commitment".to_string()))
+            .collect::<Result<Vec<_>, _>>()?;
+        return Ok(Some(UTXOSelectionStrategy::Specific(commitments)));
+    }
+    match UtxoSelectionStrategy::from_i32(strategy) {
+        Some(UtxoSelectionStrategy::Default) => Ok(None),
+        Some(UtxoSelectionStrategy::Smallest) => Ok(Some(UTXOSelectionStrategy::Smallest)),
+        Some(UtxoSelectionStrategy::MaturityThenSmallest) => Ok(Some(UTXOSelectionStrategy::MaturityThenSmallest)),
+        Some(UtxoSelectionStrategy::Largest) => Ok(Some(UTXOSelectionStrategy::Largest)),
+        Some(UtxoSelectionStrategy::BranchAndBound) => Ok(Some(UTXOSelectionStrategy::BranchAndBound)),
+        Some(UtxoSelectionStrategy::Privacy) => Ok(Some(UTXOSelectionStrategy::Privacy)),
+        None => Err(format!("Unknown selection strategy {}", strategy)),
+    }
+}
+
+async fn transaction_event_response(
+    epoch: u64,
+    sequenced: SequencedEvent,
+    mut transaction_service: TransactionServiceHandle,
+    wallet_pk: &CommsPublicKey,
+) -> tari_rpc::TransactionEventResponse {
+    let event = &*sequenced.event;
+    let transaction = match event.tx_id() {
+        Some(tx_id) => match transaction_service.get_any_transaction(tx_id).await {
+            Ok(tx) => tx.map(|tx| convert_wallet_transaction_into_transaction_info(tx, wallet_pk)),
+            Err(e) => {
+                warn!(
+                    target: LOG_TARGET,
+                    "Could not fetch transaction {} for event: {}", tx_id, e
+                );
+                None
+            },
+        },
+        None => None,
+    };
+    let message = match event {
+        TransactionEvent::Error(e) => e.clone(),
+        _ => String::new(),
+    };
+    tari_rpc::TransactionEventResponse {
+        cursor: Some(tari_rpc::EventCursor {
+            epoch,
+            sequence: sequenced.sequence,
+        }),
+        event: event.name().to_string(),
+        tx_id: event.tx_id().map(|id| id.as_u64()).unwrap_or_default(),
+        num_confirmations: event.num_confirmations().unwrap_or_default(),
+        is_valid: event.is_valid().unwrap_or_default(),
+        message,
+        transaction,
+    }
 }
 
 fn convert_wallet_transaction_into_transaction_info(
@@ -27,6 +27,7 @@ use rpassword::prompt_password_stdout;
 use rustyline::Editor;
 use tari_app_utilities::utilities::create_transport_type;
 use tari_common::{
+    configuration::Network,
     exit_codes::{ExitCode, ExitError},
     ConfigBootstrap,
     GlobalConfig,
@@ -38,7 +39,7 @@ use tari_comms::{
     NodeIdentity,
 };
 use tari_comms_dht::{store_forward::SafConfig, DbConnectionUrl, DhtConfig};
-use tari_core::transactions::CryptoFactories;
+use tari_core::transactions::{tari_amount::MicroTari, CryptoFactories};
 use tari_crypto::keys::PublicKey;
 use tari_key_manager::cipher_seed::CipherSeed;
 use tari_p2p::{
@@ -52,10 +53,18 @@ use tari_shutdown::ShutdownSignal;
 use tari_wallet::{
     base_node_service::config::BaseNodeServiceConfig,
     error::{WalletError, WalletStorageError},
-    output_manager_service::config::OutputManagerServiceConfig,
+    output_manager_service::{
+        config::{ConsolidationPolicy, OutputManagerServiceConfig},
+        watch_only::{WalletKeySource, WatchOnlyKeys},
+    },
     storage::{database::WalletDatabase, sqlite_utilities::initialize_sqlite_database_backends},
     transaction_service::config::{TransactionRoutingMechanism, TransactionServiceConfig},
-    wallet::{derive_comms_secret_key, read_or_create_master_seed},
+    wallet::{
+        derive_comms_secret_key,
+        derive_watch_only_comms_secret_key,
+        read_or_create_master_seed,
+        read_or_import_watch_only_keys,
+    },
     Wallet,
     WalletConfig,
     WalletSqlite,
@@ -126,7 +135,7 @@ pub async fn change_password(
     arg_password: Option<String>,
     shutdown_signal: ShutdownSignal,
 ) -> Result<(), ExitError> {
-    let mut wallet = init_wallet(config, arg_password, None, None, shutdown_signal).await?;
+    let mut wallet = init_wallet(config, arg_password, None, None, None, shutdown_signal).await?;
 
     let passphrase = prompt_password("New wallet password: ")?;
     let confirmed = prompt_password("Confirm new password: ")?;
@@ -269,12 +278,26 @@ pub fn get_notify_script(bootstrap: &ConfigBootstrap, config: &GlobalConfig) ->
     Ok(notify_script)
 }
 
+/// The fee per gram to use when none is specified and the base node cannot provide an estimate
+pub fn get_default_fee_per_gram(config: &GlobalConfig) -> MicroTari {
+    use Network::*;
+    if let Some(fee_per_gram) = config.transaction_default_fee_per_gram {
+        return MicroTari(fee_per_gram);
+    }
+    // TODO: TBD #LOGGED
+    match config.network {
+        MainNet | LocalNet | Igor | Dibbler => MicroTari(5),
+        Ridcully | Stibbons | Weatherwax => MicroTari(25),
+    }
+}
+
 /// Set up the app environment and state for use by the UI
 pub async fn init_wallet(
     config: &GlobalConfig,
     arg_password: Option<String>,
     seed_words_file_name: Option<PathBuf>,
     recovery_seed: Option<CipherSeed>,
+    watch_only_keys: Option<WatchOnlyKeys>,
     shutdown_signal: ShutdownSignal,
 ) -> Result<WalletSqlite, ExitError> {
     fs::create_dir_all(
@@ -333,8 +356,17 @@ pub async fn init_wallet(
 
     let identity_sig = wallet_db.get_comms_identity_signature().await?;
 
-    let master_seed = read_or_create_master_seed(recovery_seed.clone(), &wallet_db).await?;
-    let comms_secret_key = derive_comms_secret_key(&master_seed)?;
+    let key_source: WalletKeySource = match read_or_import_watch_only_keys(watch_only_keys, &wallet_db).await? {
+        Some(keys) => keys.into(),
+        None => read_or_create_master_seed(recovery_seed.clone(), &wallet_db)
+            .await?
+            .into(),
+    };
+    let comms_secret_key = match &key_source {
+        WalletKeySource::Seed(master_seed) => derive_comms_secret_key(master_seed)?,
+        WalletKeySource::WatchOnly(keys) => derive_watch_only_comms_secret_key(keys)?,
+    };
+    let is_watch_only = key_source.is_watch_only();
 
     // This checks if anything has changed by validating the previous signature and if invalid, setting identity_sig to
     // None
@@ -381,6 +413,7 @@ pub async fn init_wallet(
         auxilary_tcp_listener_address: None,
         datastore_path: config.console_wallet_peer_db_path.clone(),
         peer_database_name: "peers".to_string(),
+        database_passphrase: config.peer_db_passphrase.clone(),
         max_concurrent_inbound_tasks: 10,
         max_concurrent_outbound_tasks: 10,
         outbound_buffer_size: 10,
@@ -442,6 +475,20 @@ pub async fn init_wallet(
             prevent_fee_gt_amount: config.prevent_fee_gt_amount,
             event_channel_size: config.output_manager_event_channel_size,
             num_confirmations_required: config.transaction_num_confirmations_required,
+            fee_estimate_target_blocks: config.transaction_fee_estimate_target_blocks,
+            default_fee_per_gram: get_default_fee_per_gram(config),
+            max_fee_per_gram: MicroTari::from(config.transaction_max_fee_per_gram),
+            consolidation_policy: config
+                .wallet_config
+                .as_ref()
+                .and_then(|c| c.utxo_consolidation.as_ref())
+                .map(|c| ConsolidationPolicy {
+                    max_utxo_count: c.max_utxo_count,
+                    dust_threshold: MicroTari(c.dust_threshold),
+                    max_fee_per_gram: MicroTari(c.max_fee_per_gram),
+                    max_inputs_per_transaction: c.max_inputs,
+                    interval: c.interval,
+                }),
             ..Default::default()
         }),
         config.network.into(),
@@ -462,7 +509,7 @@ pub async fn init_wallet(
         output_manager_backend,
         contacts_backend,
         shutdown_signal,
-        master_seed,
+        key_source,
     )
     .await
     .map_err(|e| {
@@ -504,7 +551,7 @@ pub async fn init_wallet(
 
         debug!(target: LOG_TARGET, "Wallet encrypted.");
 
-        if interactive && recovery_seed.is_none() {
+        if interactive && recovery_seed.is_none() && !is_watch_only {
             match confirm_seed_words(&mut wallet).await {
                 Ok(()) => {
                     print!("\x1Bc"); // Clear the screen
@@ -515,7 +562,7 @@ pub async fn init_wallet(
             };
         }
     }
-    if let Some(file_name) = seed_words_file_name {
+    if let Some(file_name) = seed_words_file_name.filter(|_| !is_watch_only) {
         let seed_words = wallet.output_manager_service.get_seed_words().await?.join(" ");
         let _ = fs::write(file_name, seed_words).map_err(|e| {
             ExitError::new(
@@ -641,7 +688,7 @@ pub(crate) fn boot(bootstrap: &ConfigBootstrap, config: &GlobalConfig) -> Result
     let wallet_exists = config.console_wallet_db_file.exists();
 
     // forced recovery
-    if bootstrap.recovery {
+    if bootstrap.recovery || bootstrap.watch_only_keys_file.is_some() {
         if wallet_exists {
             return Err(ExitError::new(
                 ExitCode::RecoveryError,
@@ -651,7 +698,11 @@ pub(crate) fn boot(bootstrap: &ConfigBootstrap, config: &GlobalConfig) -> Result
                 ),
             ));
         }
-        return Ok(WalletBoot::Recovery);
+        if bootstrap.recovery {
+            return Ok(WalletBoot::Recovery);
+        }
+        // a watch-only wallet is created from the imported keys and then finds its outputs by scanning
+        return Ok(WalletBoot::New);
     }
 
     if wallet_exists {
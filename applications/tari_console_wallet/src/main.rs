@@ -29,7 +29,7 @@
 #![deny(unknown_lints)]
 #![deny(clippy::redundant_clone)]
 #![recursion_limit = "1024"]
-use std::{env, process};
+use std::{env, fs, process};
 
 use init::{
     boot,
@@ -45,7 +45,7 @@ use init::{
 use log::*;
 use opentelemetry::{self, global, KeyValue};
 use recovery::prompt_private_key_from_seed_words;
-use tari_app_utilities::{consts, initialization::init_configuration};
+use tari_app_utilities::{consts, initialization::init_configuration, utilities::get_or_prompt_peer_db_passphrase};
 #[cfg(all(unix, feature = "libtor"))]
 use tari_common::CommsTransport;
 use tari_common::{
@@ -57,6 +57,8 @@ use tari_key_manager::cipher_seed::CipherSeed;
 #[cfg(all(unix, feature = "libtor"))]
 use tari_libtor::tor::Tor;
 use tari_shutdown::Shutdown;
+use tari_utilities::message_format::MessageFormat;
+use tari_wallet::output_manager_service::watch_only::WatchOnlyKeys;
 use tracing_subscriber::{layer::SubscriberExt, Registry};
 use wallet_modes::{command_mode, grpc_mode, recovery_mode, script_mode, tui_mode, WalletMode};
 
@@ -123,6 +125,7 @@ fn main_inner() -> Result<(), ExitError> {
     let mut boot_mode = boot(&bootstrap, &global_config)?;
 
     let recovery_seed: Option<CipherSeed> = get_recovery_seed(boot_mode, &bootstrap)?;
+    let watch_only_keys: Option<WatchOnlyKeys> = get_watch_only_keys(&bootstrap)?;
 
     if bootstrap.init {
         info!(target: LOG_TARGET, "Default configuration created. Done.");
@@ -131,6 +134,12 @@ fn main_inner() -> Result<(), ExitError> {
     // get command line password if provided
     let arg_password = bootstrap.password.clone();
     let seed_words_file_name = bootstrap.seed_words_file_name.clone();
+    global_config.peer_db_passphrase = get_or_prompt_peer_db_passphrase(
+        bootstrap.peer_db_passphrase.clone(),
+        global_config.peer_db_passphrase.clone(),
+        &global_config.console_wallet_peer_db_path,
+        bootstrap.non_interactive_mode,
+    )?;
 
     let mut shutdown = Shutdown::new();
     let shutdown_signal = shutdown.to_signal();
@@ -161,6 +170,7 @@ fn main_inner() -> Result<(), ExitError> {
         arg_password,
         seed_words_file_name,
         recovery_seed,
+        watch_only_keys,
         shutdown_signal,
     ))?;
 
@@ -234,6 +244,27 @@ fn get_recovery_seed(boot_mode: WalletBoot, bootstrap: &ConfigBootstrap) -> Resu
     }
 }
 
+fn get_watch_only_keys(bootstrap: &ConfigBootstrap) -> Result<Option<WatchOnlyKeys>, ExitError> {
+    match bootstrap.watch_only_keys_file {
+        Some(ref path) => {
+            let json = fs::read_to_string(path).map_err(|e| {
+                ExitError::new(
+                    ExitCode::IOError,
+                    format!("Could not read watch-only keys file {}: {}", path.display(), e),
+                )
+            })?;
+            let keys = WatchOnlyKeys::from_json(&json).map_err(|e| {
+                ExitError::new(
+                    ExitCode::InputError,
+                    format!("Invalid watch-only keys file {}: {}", path.display(), e),
+                )
+            })?;
+            Ok(Some(keys))
+        },
+        None => Ok(None),
+    }
+}
+
 fn enable_tracing() {
     // To run:
     // docker run -d -p6831:6831/udp -p6832:6832/udp -p16686:16686 -p14268:14268 jaegertracing/all-in-one:latest
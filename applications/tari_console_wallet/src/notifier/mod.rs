@@ -20,6 +20,7 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+mod webhook;
 use std::{
     io::Error,
     path::PathBuf,
@@ -39,6 +40,7 @@ use tari_wallet::{
     WalletSqlite,
 };
 use tokio::runtime::Handle;
+pub use webhook::WebhookNotifier;
 
 pub const LOG_TARGET: &str = "wallet::notifier";
 const RECEIVED: &str = "received";
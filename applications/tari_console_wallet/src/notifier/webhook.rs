@@ -0,0 +1,456 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Delivers every transaction service event to a set of configured urls as a signed JSON payload.
+//!
+//! Events are first written to the webhook outbox in the wallet database, one row per url, and a separate delivery
+//! task POSTs due rows concurrently, so slow receivers never hold up the reading of events. A row is removed once the
+//! receiver answers with a 2xx status; otherwise it is rescheduled with exponential backoff until the configured number
+//! of attempts is used up. Because the outbox is persistent, events that could not be delivered before the wallet shut
+//! down are retried on the next start.
+//!
+//! The hex encoded HMAC-SHA256 of the request body, keyed with the configured webhook secret, is sent in the
+//! `X-Tari-Signature` header so that receivers can authenticate the payload. Every payload carries a `delivery_id`
+//! that stays the same across retries, which receivers can use to discard duplicate deliveries.
+
+use std::time::Duration;
+
+use chrono::{NaiveDateTime, Utc};
+use futures::future;
+use hmac::{Hmac, Mac, NewMac};
+use log::*;
+use rand::{rngs::OsRng, RngCore};
+use reqwest::{header::CONTENT_TYPE, StatusCode};
+use serde::Serialize;
+use sha2::Sha256;
+use tari_common::configuration::WalletConfig;
+use tari_common_types::transaction::TxId;
+use tari_shutdown::ShutdownSignal;
+use tari_utilities::hex::to_hex;
+use tari_wallet::{
+    error::WalletStorageError,
+    storage::database::{WalletBackend, WalletDatabase, WebhookDelivery},
+    transaction_service::{
+        handle::{TransactionEvent, TransactionServiceHandle},
+        storage::models::WalletTransaction,
+    },
+    WalletSqlite,
+};
+use thiserror::Error;
+use tokio::{sync::broadcast, time};
+
+const LOG_TARGET: &str = "wallet::notifier::webhook";
+
+pub const SIGNATURE_HEADER: &str = "X-Tari-Signature";
+/// How often the outbox is checked for deliveries that are due
+const DELIVERY_INTERVAL: Duration = Duration::from_secs(5);
+/// The maximum number of deliveries attempted per interval
+const DELIVERY_BATCH_SIZE: usize = 50;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const BASE_RETRY_DELAY_SECS: i64 = 10;
+const MAX_RETRY_DELAY_SECS: i64 = 60 * 60;
+
+#[derive(Debug, Error)]
+pub enum WebhookError {
+    #[error("Wallet storage error: `{0}`")]
+    WalletStorageError(#[from] WalletStorageError),
+    #[error("Could not serialize webhook payload: `{0}`")]
+    SerializationError(#[from] serde_json::Error),
+    #[error("HTTP request failed: `{0}`")]
+    HttpError(#[from] reqwest::Error),
+    #[error("Webhook receiver responded with status {0}")]
+    UnexpectedStatus(StatusCode),
+}
+
+/// The JSON body POSTed to each webhook url
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookPayload {
+    /// Random id of the event, identical for every url and retry, so that receivers can make delivery idempotent
+    pub delivery_id: String,
+    pub event: String,
+    pub tx_id: Option<u64>,
+    /// Amount in MicroTari
+    pub amount: Option<u64>,
+    pub status: Option<String>,
+    pub direction: Option<String>,
+    pub confirmations: Option<u64>,
+    pub is_valid: Option<bool>,
+    pub message: Option<String>,
+    /// Unix timestamp of when the event was raised, useful for rejecting replayed payloads
+    pub timestamp: i64,
+}
+
+pub struct WebhookNotifier {
+    urls: Vec<String>,
+    secret: String,
+    max_attempts: u32,
+    wallet: WalletSqlite,
+    client: reqwest::Client,
+}
+
+impl WebhookNotifier {
+    /// Returns `None` when no webhook urls are configured, or an error if the http client could not be built
+    pub fn new(config: &WalletConfig, wallet: WalletSqlite) -> Result<Option<Self>, WebhookError> {
+        let secret = match config.webhook_secret.clone() {
+            Some(secret) if !config.webhook_urls.is_empty() => secret,
+            _ => return Ok(None),
+        };
+        let client = reqwest::Client::builder().timeout(REQUEST_TIMEOUT).build()?;
+        Ok(Some(Self {
+            urls: config.webhook_urls.clone(),
+            secret,
+            max_attempts: config.webhook_max_attempts,
+            wallet,
+            client,
+        }))
+    }
+
+    /// Queue every transaction event in the outbox until the wallet shuts down, while due payloads are delivered by a
+    /// separate task
+    pub async fn run(self) {
+        let mut event_stream = self.wallet.transaction_service.get_event_stream();
+        let mut shutdown_signal = self.wallet.comms.shutdown_signal();
+
+        info!(
+            target: LOG_TARGET,
+            "Webhook notifier starting for {} url(s)",
+            self.urls.len()
+        );
+        tokio::spawn(run_deliveries(
+            self.wallet.db.clone(),
+            self.client.clone(),
+            self.secret.clone(),
+            self.max_attempts,
+            shutdown_signal.clone(),
+        ));
+        loop {
+            tokio::select! {
+                result = event_stream.recv() => {
+                    match result {
+                        Ok(event) => {
+                            if let Err(e) = self.queue_event(&*event).await {
+                                error!(target: LOG_TARGET, "Could not queue webhook for event {}: {}", event, e);
+                            }
+                        },
+                        Err(broadcast::error::RecvError::Lagged(n)) => {
+                            warn!(target: LOG_TARGET, "Missed {} transaction events without queueing webhooks", n);
+                        },
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    }
+                },
+                _ = shutdown_signal.wait() => {
+                    break;
+                },
+            }
+        }
+        info!(target: LOG_TARGET, "Webhook notifier shutting down");
+    }
+
+    async fn queue_event(&self, event: &TransactionEvent) -> Result<(), WebhookError> {
+        let mut payload = payload_from_event(event);
+        if let Some(tx_id) = payload.tx_id {
+            let mut transaction_service = self.wallet.transaction_service.clone();
+            add_transaction_details(&mut transaction_service, TxId::from(tx_id), &mut payload).await;
+        }
+        let payload = serde_json::to_string(&payload)?;
+        for url in &self.urls {
+            self.wallet
+                .db
+                .queue_webhook_delivery(url.clone(), payload.clone())
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+/// Deliver due payloads from the outbox every `DELIVERY_INTERVAL` until the wallet shuts down
+async fn run_deliveries<T: WalletBackend + 'static>(
+    db: WalletDatabase<T>,
+    client: reqwest::Client,
+    secret: String,
+    max_attempts: u32,
+    mut shutdown_signal: ShutdownSignal,
+) {
+    let mut delivery_interval = time::interval(DELIVERY_INTERVAL);
+    delivery_interval.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
+    loop {
+        tokio::select! {
+            _ = delivery_interval.tick() => {
+                let now = Utc::now().naive_utc();
+                if let Err(e) = deliver_due(&db, &client, secret.as_bytes(), max_attempts, now).await {
+                    error!(target: LOG_TARGET, "Error delivering webhooks: {}", e);
+                }
+            },
+            _ = shutdown_signal.wait() => {
+                break;
+            },
+        }
+    }
+}
+
+/// Attempt every delivery in the outbox that is due at `now` concurrently, returning the number that were delivered
+pub async fn deliver_due<T: WalletBackend + 'static>(
+    db: &WalletDatabase<T>,
+    client: &reqwest::Client,
+    secret: &[u8],
+    max_attempts: u32,
+    now: NaiveDateTime,
+) -> Result<usize, WebhookError> {
+    let deliveries = db.get_due_webhook_deliveries(now, DELIVERY_BATCH_SIZE).await?;
+    let results = future::join_all(
+        deliveries
+            .iter()
+            .map(|delivery| post_delivery(client, secret, delivery)),
+    )
+    .await;
+    let mut num_delivered = 0;
+    for (delivery, result) in deliveries.into_iter().zip(results) {
+        match result {
+            Ok(()) => {
+                debug!(
+                    target: LOG_TARGET,
+                    "Webhook {} delivered to {}", delivery.id, delivery.url
+                );
+                db.remove_webhook_delivery(delivery.id).await?;
+                num_delivered += 1;
+            },
+            Err(e) => {
+                let attempts = delivery.attempts + 1;
+                if attempts >= max_attempts {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Giving up on webhook {} to {} after {} attempts: {}", delivery.id, delivery.url, attempts, e
+                    );
+                    db.remove_webhook_delivery(delivery.id).await?;
+                } else {
+                    let next_attempt_at = now + retry_delay(attempts);
+                    debug!(
+                        target: LOG_TARGET,
+                        "Webhook {} to {} failed ({}), retrying at {}", delivery.id, delivery.url, e, next_attempt_at
+                    );
+                    db.reschedule_webhook_delivery(delivery.id, attempts, next_attempt_at)
+                        .await?;
+                }
+            },
+        }
+    }
+    Ok(num_delivered)
+}
+
+async fn post_delivery(
+    client: &reqwest::Client,
+    secret: &[u8],
+    delivery: &WebhookDelivery,
+) -> Result<(), WebhookError> {
+    let response = client
+        .post(&delivery.url)
+        .header(CONTENT_TYPE, "application/json")
+        .header(SIGNATURE_HEADER, sign_payload(secret, delivery.payload.as_bytes()))
+        .body(delivery.payload.clone())
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        return Err(WebhookError::UnexpectedStatus(response.status()));
+    }
+    Ok(())
+}
+
+/// Hex encoded HMAC-SHA256 of the payload keyed with the webhook secret
+pub fn sign_payload(secret: &[u8], payload: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(payload);
+    to_hex(mac.finalize().into_bytes().as_slice())
+}
+
+/// Exponential backoff starting at `BASE_RETRY_DELAY_SECS`, capped at `MAX_RETRY_DELAY_SECS`
+fn retry_delay(attempts: u32) -> chrono::Duration {
+    let exponent = attempts.saturating_sub(1).min(16);
+    let secs = BASE_RETRY_DELAY_SECS
+        .saturating_mul(1i64 << exponent)
+        .min(MAX_RETRY_DELAY_SECS);
+    chrono::Duration::seconds(secs)
+}
+
+fn payload_from_event(event: &TransactionEvent) -> WebhookPayload {
+    let message = match event {
+        TransactionEvent::Error(e) => Some(e.clone()),
+        _ => None,
+    };
+    let mut delivery_id = [0u8; 16];
+    OsRng.fill_bytes(&mut delivery_id);
+    WebhookPayload {
+        delivery_id: to_hex(&delivery_id),
+        event: event.name().to_string(),
+        tx_id: event.tx_id().map(|id| id.as_u64()),
+        amount: None,
+        status: None,
+        direction: None,
+        confirmations: event.num_confirmations(),
+        is_valid: event.is_valid(),
+        message,
+        timestamp: Utc::now().timestamp(),
+    }
+}
+
+/// Fill in the amount, status, direction, message and confirmations from the stored transaction, if it can be found
+async fn add_transaction_details(
+    transaction_service: &mut TransactionServiceHandle,
+    tx_id: TxId,
+    payload: &mut WebhookPayload,
+) {
+    match transaction_service.get_any_transaction(tx_id).await {
+        Ok(Some(WalletTransaction::Completed(tx))) => {
+            payload.amount = Some(tx.amount.as_u64());
+            payload.status = Some(tx.status.to_string());
+            payload.direction = Some(tx.direction.to_string());
+            payload.message = Some(tx.message);
+            if payload.confirmations.is_none() {
+                payload.confirmations = tx.confirmations;
+            }
+        },
+        Ok(Some(WalletTransaction::PendingInbound(tx))) => {
+            payload.amount = Some(tx.amount.as_u64());
+            payload.status = Some(tx.status.to_string());
+            payload.direction = Some("inbound".to_string());
+            payload.message = Some(tx.message);
+        },
+        Ok(Some(WalletTransaction::PendingOutbound(tx))) => {
+            payload.amount = Some(tx.amount.as_u64());
+            payload.status = Some(tx.status.to_string());
+            payload.direction = Some("outbound".to_string());
+            payload.message = Some(tx.message);
+        },
+        Ok(None) => trace!(target: LOG_TARGET, "Transaction not found for webhook tx_id: {}", tx_id),
+        Err(e) => error!(target: LOG_TARGET, "Transaction service error: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tari_test_utils::random::string;
+    use tari_wallet::storage::{
+        database::WalletDatabase,
+        sqlite_db::wallet::WalletSqliteDatabase,
+        sqlite_utilities::run_migration_and_create_sqlite_connection,
+    };
+    use tempfile::tempdir;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        sync::mpsc,
+    };
+
+    use super::*;
+
+    /// A minimal HTTP server standing in for a webhook receiver. It answers each request with the next status in
+    /// `statuses` and forwards the signature header and body of each request it receives.
+    async fn spawn_webhook_receiver(statuses: Vec<u16>) -> (String, mpsc::UnboundedReceiver<(String, String)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/webhook", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            for status in statuses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = Vec::new();
+                let (head, body) = loop {
+                    let mut chunk = [0u8; 1024];
+                    let n = socket.read(&mut chunk).await.unwrap();
+                    buf.extend_from_slice(&chunk[..n]);
+                    let request = String::from_utf8_lossy(&buf).to_string();
+                    if let Some(pos) = request.find("\r\n\r\n") {
+                        let head = request[..pos].to_lowercase();
+                        let content_length = head
+                            .lines()
+                            .find_map(|l| l.strip_prefix("content-length:"))
+                            .map(|v| v.trim().parse::<usize>().unwrap())
+                            .unwrap_or(0);
+                        if buf.len() >= pos + 4 + content_length {
+                            break (head, request[pos + 4..].to_string());
+                        }
+                    }
+                };
+                let signature = head
+                    .lines()
+                    .find_map(|l| l.strip_prefix(&format!("{}:", SIGNATURE_HEADER.to_lowercase())))
+                    .map(|v| v.trim().to_string())
+                    .unwrap_or_default();
+                tx.send((signature, body)).unwrap();
+                let response = format!(
+                    "HTTP/1.1 {} Status\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+                    status
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        (url, rx)
+    }
+
+    #[tokio::test]
+    async fn it_retries_and_signs_webhook_deliveries() {
+        let db_name = format!("{}.sqlite3", string(8).as_str());
+        let db_folder = tempdir().unwrap();
+        let connection = run_migration_and_create_sqlite_connection(db_folder.path().join(db_name), 16).unwrap();
+        let db = WalletDatabase::new(WalletSqliteDatabase::new(connection, None).unwrap());
+
+        let (url, mut requests) = spawn_webhook_receiver(vec![500, 200]).await;
+        let secret = b"webhook secret";
+        let payload = r#"{"event":"transaction_mined","tx_id":1}"#.to_string();
+        db.queue_webhook_delivery(url, payload.clone()).await.unwrap();
+        let client = reqwest::Client::new();
+
+        // The first attempt fails and is rescheduled
+        let now = Utc::now().naive_utc() + chrono::Duration::seconds(1);
+        assert_eq!(deliver_due(&db, &client, secret, 3, now).await.unwrap(), 0);
+        let (_, body) = requests.recv().await.unwrap();
+        assert_eq!(body, payload);
+        assert!(db.get_due_webhook_deliveries(now, 10).await.unwrap().is_empty());
+
+        // Once the retry is due it is delivered, correctly signed, and removed from the outbox
+        let later = now + retry_delay(1);
+        let pending = db.get_due_webhook_deliveries(later, 10).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].attempts, 1);
+        assert_eq!(deliver_due(&db, &client, secret, 3, later).await.unwrap(), 1);
+        let (signature, body) = requests.recv().await.unwrap();
+        assert_eq!(body, payload);
+        assert_eq!(signature, sign_payload(secret, payload.as_bytes()));
+        assert!(db.get_due_webhook_deliveries(later, 10).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn it_gives_each_event_a_delivery_id() {
+        let event = TransactionEvent::TransactionBroadcast(TxId::from(1u64));
+        let first = payload_from_event(&event);
+        let second = payload_from_event(&event);
+        assert_eq!(first.delivery_id.len(), 32);
+        assert_ne!(first.delivery_id, second.delivery_id);
+        assert!(serde_json::to_string(&first).unwrap().contains(&first.delivery_id));
+    }
+
+    #[test]
+    fn it_backs_off_exponentially() {
+        assert_eq!(retry_delay(1).num_seconds(), BASE_RETRY_DELAY_SECS);
+        assert_eq!(retry_delay(2).num_seconds(), BASE_RETRY_DELAY_SECS * 2);
+        assert_eq!(retry_delay(3).num_seconds(), BASE_RETRY_DELAY_SECS * 4);
+        assert_eq!(retry_delay(100).num_seconds(), MAX_RETRY_DELAY_SECS);
+    }
+}
@@ -55,7 +55,10 @@ use tari_wallet::{
     base_node_service::{handle::BaseNodeEventReceiver, service::BaseNodeState},
     connectivity_service::{OnlineStatus, WalletConnectivityHandle, WalletConnectivityInterface},
     contacts_service::storage::database::Contact,
-    output_manager_service::{handle::OutputManagerEventReceiver, service::Balance},
+    output_manager_service::{
+        handle::{OutputManagerEventReceiver, OutputManagerHandle},
+        service::Balance,
+    },
     tokens::Token,
     transaction_service::{
         handle::TransactionEventReceiver,
@@ -69,6 +72,7 @@ use tokio::{
 };
 
 use crate::{
+    init::get_default_fee_per_gram,
     notifier::Notifier,
     ui::{
         state::{
@@ -239,7 +243,14 @@ impl AppState {
             },
         };
 
-        let contact = Contact { alias, public_key };
+        // Keep any tags, notes and send preferences already stored for this contact
+        let contact = match inner.wallet.contacts_service.get_contact(public_key.clone()).await {
+            Ok(mut contact) => {
+                contact.alias = alias;
+                contact
+            },
+            Err(_) => Contact::new(alias, public_key),
+        };
         inner.wallet.contacts_service.upsert_contact(contact).await?;
 
         inner.refresh_contacts_state().await?;
@@ -529,13 +540,12 @@ impl AppState {
         }
     }
 
+    /// The fee per gram most recently estimated by the base node, or the configured default if there is no estimate
+    /// yet
     pub fn get_default_fee_per_gram(&self) -> MicroTari {
-        use Network::*;
-        // TODO: TBD #LOGGED
-        match self.node_config.network {
-            MainNet | LocalNet | Igor | Dibbler => MicroTari(5),
-            Ridcully | Stibbons | Weatherwax => MicroTari(25),
-        }
+        self.cached_data
+            .fee_per_gram_estimate
+            .unwrap_or_else(|| get_default_fee_per_gram(&self.node_config))
     }
 
     pub fn get_network(&self) -> Network {
@@ -806,6 +816,13 @@ impl AppStateInner {
         Ok(())
     }
 
+    pub async fn refresh_fee_per_gram_estimate(&mut self, fee_per_gram: MicroTari) -> Result<(), UiError> {
+        self.data.fee_per_gram_estimate = Some(fee_per_gram);
+        self.updated = true;
+
+        Ok(())
+    }
+
     pub async fn refresh_base_node_peer(&mut self, peer: Peer) -> Result<(), UiError> {
         self.data.base_node_selected = peer;
         self.updated = true;
@@ -821,6 +838,10 @@ impl AppStateInner {
         self.wallet.transaction_service.get_event_stream()
     }
 
+    pub fn get_output_manager_service(&self) -> OutputManagerHandle {
+        self.wallet.output_manager_service.clone()
+    }
+
     pub fn get_output_manager_service_event_stream(&self) -> OutputManagerEventReceiver {
         self.wallet.output_manager_service.get_event_stream()
     }
@@ -1054,6 +1075,7 @@ struct AppStateData {
     connected_peers: Vec<Peer>,
     balance: Balance,
     base_node_state: BaseNodeState,
+    fee_per_gram_estimate: Option<MicroTari>,
     base_node_selected: Peer,
     base_node_previous: Peer,
     base_node_list: Vec<(String, Peer)>,
@@ -1129,6 +1151,7 @@ impl AppStateData {
             connected_peers: Vec::new(),
             balance: Balance::zero(),
             base_node_state: BaseNodeState::default(),
+            fee_per_gram_estimate: None,
             base_node_selected,
             base_node_previous,
             base_node_list,
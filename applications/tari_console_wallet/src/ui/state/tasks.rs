@@ -22,7 +22,7 @@
 
 use tari_common_types::types::PublicKey;
 use tari_comms::types::CommsPublicKey;
-use tari_core::transactions::tari_amount::MicroTari;
+use tari_core::{covenants::Covenant, transactions::tari_amount::MicroTari};
 use tari_wallet::transaction_service::handle::{TransactionEvent, TransactionServiceHandle};
 use tokio::sync::{broadcast, watch};
 
@@ -45,7 +45,16 @@ pub async fn send_transaction_task(
     let mut send_direct_received_result = (false, false);
     let mut send_saf_received_result = (false, false);
     match transaction_service_handle
-        .send_transaction_or_token(public_key, amount, unique_id, parent_public_key, fee_per_gram, message)
+        .send_transaction_or_token(
+            public_key,
+            amount,
+            unique_id,
+            parent_public_key,
+            fee_per_gram,
+            message,
+            Covenant::default(),
+            None,
+        )
         .await
     {
         Err(e) => {
@@ -120,7 +129,16 @@ pub async fn send_one_sided_transaction_task(
     let _ = result_tx.send(UiTransactionSendStatus::Initiated);
     let mut event_stream = transaction_service_handle.get_event_stream();
     match transaction_service_handle
-        .send_one_sided_transaction_or_token(public_key, amount, unique_id, parent_public_key, fee_per_gram, message)
+        .send_one_sided_transaction_or_token(
+            public_key,
+            amount,
+            unique_id,
+            parent_public_key,
+            fee_per_gram,
+            message,
+            Covenant::default(),
+            None,
+        )
         .await
     {
         Err(e) => {
@@ -262,12 +262,25 @@ impl WalletEventMonitor {
     }
 
     async fn trigger_base_node_state_refresh(&mut self, state: BaseNodeState) {
+        // Query the fee estimate before taking the write lock so that the UI is not blocked on the base node
+        let mut output_manager_service = self.app_state_inner.read().await.get_output_manager_service();
+        let fee_per_gram_estimate = output_manager_service.estimate_fee_per_gram(None).await;
+
         let mut inner = self.app_state_inner.write().await;
 
         if let Err(e) = inner.refresh_base_node_state(state).await {
             warn!(target: LOG_TARGET, "Error refresh app_state: {}", e);
         }
 
+        match fee_per_gram_estimate {
+            Ok(fee_per_gram) => {
+                if let Err(e) = inner.refresh_fee_per_gram_estimate(fee_per_gram).await {
+                    warn!(target: LOG_TARGET, "Error refresh app_state: {}", e);
+                }
+            },
+            Err(e) => warn!(target: LOG_TARGET, "Error getting fee per gram estimate: {}", e),
+        }
+
         if inner.has_time_locked_balance() {
             if let Err(e) = self.balance_enquiry_debounce_tx.send(()) {
                 warn!(target: LOG_TARGET, "Error refresh app_state: {}", e);
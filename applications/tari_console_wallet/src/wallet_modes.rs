@@ -37,7 +37,7 @@ use tui::backend::CrosstermBackend;
 use crate::{
     automation::{command_parser::parse_command, commands::command_runner},
     grpc::WalletGrpcServer,
-    notifier::Notifier,
+    notifier::{Notifier, WebhookNotifier},
     recovery::wallet_recovery,
     ui,
     ui::App,
@@ -241,10 +241,10 @@ pub fn tui_mode(config: WalletModeConfig, mut wallet: WalletSqlite) -> Result<()
         .as_ref()
         .and_then(|c| c.grpc_address.as_ref())
     {
-        let grpc = WalletGrpcServer::new(wallet.clone());
-        handle.spawn(run_grpc(grpc, grpc_address.clone()));
+        handle.spawn(run_grpc(wallet.clone(), grpc_address.clone()));
     }
 
+    spawn_webhook_notifier(&global_config, &handle, &wallet)?;
     let notifier = Notifier::new(notify_script, handle.clone(), wallet.clone());
 
     if let Some(peer) = base_node_config.base_node_custom.clone() {
@@ -334,11 +334,11 @@ pub fn grpc_mode(config: WalletModeConfig, wallet: WalletSqlite) -> Result<(), E
     let WalletModeConfig {
         global_config, handle, ..
     } = config;
+    spawn_webhook_notifier(&global_config, &handle, &wallet)?;
     info!(target: LOG_TARGET, "Starting grpc server");
     if let Some(grpc_address) = global_config.wallet_config.and_then(|c| c.grpc_address) {
-        let grpc = WalletGrpcServer::new(wallet);
         handle
-            .block_on(run_grpc(grpc, grpc_address))
+            .block_on(run_grpc(wallet, grpc_address))
             .map_err(|e| ExitError::new(ExitCode::GrpcError, e))?;
     } else {
         println!("No grpc address specified");
@@ -347,13 +347,29 @@ pub fn grpc_mode(config: WalletModeConfig, wallet: WalletSqlite) -> Result<(), E
     Ok(())
 }
 
-async fn run_grpc(grpc: WalletGrpcServer, grpc_console_wallet_address: Multiaddr) -> Result<(), String> {
+fn spawn_webhook_notifier(
+    global_config: &GlobalConfig,
+    handle: &Handle,
+    wallet: &WalletSqlite,
+) -> Result<(), ExitError> {
+    if let Some(wallet_config) = global_config.wallet_config.as_ref() {
+        let webhook_notifier = WebhookNotifier::new(wallet_config, wallet.clone())
+            .map_err(|e| ExitError::new(ExitCode::WalletError, e))?;
+        if let Some(webhook_notifier) = webhook_notifier {
+            handle.spawn(webhook_notifier.run());
+        }
+    }
+    Ok(())
+}
+
+async fn run_grpc(wallet: WalletSqlite, grpc_console_wallet_address: Multiaddr) -> Result<(), String> {
     // Do not remove this println!
     const CUCUMBER_TEST_MARKER_A: &str = "Tari Console Wallet running... (gRPC mode started)";
     println!("{}", CUCUMBER_TEST_MARKER_A);
 
     info!(target: LOG_TARGET, "Starting GRPC on {}", grpc_console_wallet_address);
     let socket = multiaddr_to_socketaddr(&grpc_console_wallet_address).map_err(|e| e.to_string())?;
+    let grpc = WalletGrpcServer::new(wallet);
     Server::builder()
         .add_service(tari_app_grpc::tari_rpc::wallet_server::WalletServer::new(grpc))
         .serve(socket)
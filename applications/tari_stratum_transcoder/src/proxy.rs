@@ -469,7 +469,10 @@ impl InnerService {
         let request = request.body();
         let request_id = request["id"].as_i64();
         let mut client = self.wallet_client.clone();
-        let balances = client.get_balance(grpc::GetBalanceRequest {}).await?.into_inner();
+        let balances = client
+            .get_balance(grpc::GetBalanceRequest::default())
+            .await?
+            .into_inner();
 
         let json_response = json!({
              "id": request_id.unwrap_or(-1),
@@ -580,12 +583,17 @@ impl InnerService {
                 fee_per_gram: recipient["fee_per_gram"].as_u64().unwrap(),
                 message: recipient["message"].as_str().unwrap().to_string(),
                 payment_type: 1,
+                covenant: Default::default(),
+                selection_strategy: Default::default(),
+                utxo_commitments: Default::default(),
+                lock_height: Default::default(),
             });
         }
 
         let transfer_results = client
             .transfer(grpc::TransferRequest {
                 recipients: grpc_payments,
+                account: Default::default(),
             })
             .await?
             .into_inner();
@@ -138,6 +138,7 @@ fn create_comms_config(config: &GlobalConfig, node_identity: Arc<NodeIdentity>)
         transport_type: create_transport_type(config),
         datastore_path: config.peer_db_path.clone(),
         peer_database_name: "peers".to_string(),
+        database_passphrase: config.peer_db_passphrase.clone(),
         max_concurrent_inbound_tasks: 50,
         max_concurrent_outbound_tasks: 100,
         outbound_buffer_size: 100,
@@ -247,5 +248,7 @@ fn create_transport_type(config: &GlobalConfig) -> TransportType {
             },
             listener_address,
         },
+        CommsTransport::WebSocket { listener_address } => TransportType::WebSocket { listener_address },
+        CommsTransport::Quic { listener_address } => TransportType::Quic { listener_address },
     }
 }
@@ -38,7 +38,11 @@ use std::{
 use futures::FutureExt;
 use log::*;
 use tari_app_grpc::tari_rpc::validator_node_server::ValidatorNodeServer;
-use tari_app_utilities::{identity_management::setup_node_identity, initialization::init_configuration};
+use tari_app_utilities::{
+    identity_management::setup_node_identity,
+    initialization::init_configuration,
+    utilities::get_or_prompt_peer_db_passphrase,
+};
 use tari_common::{
     configuration::bootstrap::ApplicationType,
     exit_codes::{ExitCode, ExitError},
@@ -77,7 +81,13 @@ fn main() {
 }
 
 fn main_inner() -> Result<(), ExitError> {
-    let (bootstrap, config, _) = init_configuration(ApplicationType::ValidatorNode)?;
+    let (bootstrap, mut config, _) = init_configuration(ApplicationType::ValidatorNode)?;
+    config.peer_db_passphrase = get_or_prompt_peer_db_passphrase(
+        bootstrap.peer_db_passphrase.clone(),
+        config.peer_db_passphrase.clone(),
+        &config.peer_db_path,
+        bootstrap.non_interactive_mode,
+    )?;
 
     // let _operation_mode = cmd_args::get_operation_mode();
     // match operation_mode {
@@ -22,14 +22,19 @@
 
 use std::{
     fmt::{Display, Error, Formatter},
-    ops::RangeInclusive,
+    ops::{Range, RangeInclusive},
 };
 
 use serde::{Deserialize, Serialize};
 use tari_common_types::types::{Commitment, HashOutput, PrivateKey, PublicKey, Signature};
-use tari_crypto::tari_utilities::hex::Hex;
+use tari_crypto::{script::TariScript, tari_utilities::hex::Hex};
 
-use crate::{blocks::NewBlockTemplate, chain_storage::MmrTree, proof_of_work::PowAlgorithm};
+use crate::{
+    blocks::NewBlockTemplate,
+    chain_storage::MmrTree,
+    proof_of_work::PowAlgorithm,
+    transactions::transaction_components::Transaction,
+};
 
 /// A container for the parameters required for a FetchMmrState request.
 #[derive(Debug, Serialize, Deserialize)]
@@ -70,6 +75,15 @@ pub enum NodeCommsRequest {
     FetchMempoolTransactionsByExcessSigs {
         excess_sigs: Vec<PrivateKey>,
     },
+    FetchUnspentOutputsByScript {
+        script: TariScript,
+        range: Range<usize>,
+    },
+    FetchUnspentOutputsBySenderOffsetPublicKey {
+        sender_offset_public_key: PublicKey,
+        range: Range<usize>,
+    },
+    EvaluateCovenants(Box<Transaction>),
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -118,6 +132,13 @@ impl Display for NodeCommsRequest {
             FetchMempoolTransactionsByExcessSigs { .. } => {
                 write!(f, "FetchMempoolTransactionsByExcessSigs")
             },
+            FetchUnspentOutputsByScript { range, .. } => {
+                write!(f, "FetchUnspentOutputsByScript ({:?})", range)
+            },
+            FetchUnspentOutputsBySenderOffsetPublicKey { range, .. } => {
+                write!(f, "FetchUnspentOutputsBySenderOffsetPublicKey ({:?})", range)
+            },
+            EvaluateCovenants(tx) => write!(f, "EvaluateCovenants ({} input(s))", tx.body.inputs().len()),
         }
     }
 }
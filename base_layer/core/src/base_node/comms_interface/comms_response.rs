@@ -33,6 +33,7 @@ use tari_common_types::{
 use crate::{
     blocks::{Block, BlockHeader, ChainHeader, HistoricalBlock, NewBlockTemplate},
     chain_storage::UtxoMinedInfo,
+    covenants::{Covenant, CovenantTrace},
     proof_of_work::Difficulty,
     transactions::transaction_components::{Transaction, TransactionKernel, TransactionOutput},
 };
@@ -66,6 +67,13 @@ pub enum NodeCommsResponse {
         output: Box<Option<UtxoMinedInfo>>,
     },
     FetchMempoolTransactionsByExcessSigsResponse(FetchMempoolTransactionsResponse),
+    FetchUnspentOutputsResponse {
+        outputs: Vec<UtxoMinedInfo>,
+    },
+    EvaluateCovenantsResponse {
+        height: u64,
+        inputs: Vec<InputCovenantEvaluation>,
+    },
 }
 
 impl Display for NodeCommsResponse {
@@ -102,6 +110,15 @@ impl Display for NodeCommsResponse {
                 resp.transactions.len(),
                 resp.not_found.len()
             ),
+            FetchUnspentOutputsResponse { outputs } => {
+                write!(f, "FetchUnspentOutputsResponse ({} output(s))", outputs.len())
+            },
+            EvaluateCovenantsResponse { height, inputs } => write!(
+                f,
+                "EvaluateCovenantsResponse (height {}, {} input(s))",
+                height,
+                inputs.len()
+            ),
         }
     }
 }
@@ -112,3 +129,15 @@ pub struct FetchMempoolTransactionsResponse {
     pub transactions: Vec<Arc<Transaction>>,
     pub not_found: Vec<PrivateKey>,
 }
+
+/// The result of evaluating the covenant of a single transaction input against the outputs of the transaction
+#[derive(Debug, Clone)]
+pub struct InputCovenantEvaluation {
+    /// The index of the input in the transaction
+    pub input_index: usize,
+    /// The hash of the output spent by the input
+    pub output_hash: HashOutput,
+    /// The covenant of the spent output, or None if the spent output could not be found
+    pub covenant: Option<Covenant>,
+    pub trace: CovenantTrace,
+}
@@ -29,7 +29,10 @@ use std::{
 use log::*;
 use strum_macros::Display;
 use tari_common_types::types::{BlockHash, HashOutput, PublicKey};
-use tari_comms::{connectivity::ConnectivityRequester, peer_manager::NodeId};
+use tari_comms::{
+    connectivity::{ConnectivityRequester, ReputationEvent},
+    peer_manager::NodeId,
+};
 use tari_crypto::tari_utilities::{hash::Hashable, hex::Hex};
 use tari_utilities::ByteArray;
 use tokio::sync::Semaphore;
@@ -40,6 +43,7 @@ use crate::{
             error::CommsInterfaceError,
             local_interface::BlockEventSender,
             FetchMempoolTransactionsResponse,
+            InputCovenantEvaluation,
             NodeCommsRequest,
             NodeCommsResponse,
             OutboundNodeCommsInterface,
@@ -49,8 +53,10 @@ use crate::{
     blocks::{Block, BlockBuilder, BlockHeader, ChainBlock, NewBlock, NewBlockTemplate},
     chain_storage::{async_db::AsyncBlockchainDb, BlockAddResult, BlockchainBackend, ChainStorageError, PrunedOutput},
     consensus::{ConsensusConstants, ConsensusManager},
+    covenants::CovenantTrace,
     mempool::Mempool,
     proof_of_work::{Difficulty, PowAlgorithm},
+    transactions::transaction_components::Transaction,
 };
 
 const LOG_TARGET: &str = "c::bn::comms_interface::inbound_handler";
@@ -487,9 +493,102 @@ where B: BlockchainBackend + 'static
                     },
                 ))
             },
+            NodeCommsRequest::FetchUnspentOutputsByScript { script, range } => {
+                let outputs = self
+                    .blockchain_db
+                    .fetch_unspent_outputs_by_script(script, range)
+                    .await?;
+                Ok(NodeCommsResponse::FetchUnspentOutputsResponse { outputs })
+            },
+            NodeCommsRequest::FetchUnspentOutputsBySenderOffsetPublicKey {
+                sender_offset_public_key,
+                range,
+            } => {
+                let outputs = self
+                    .blockchain_db
+                    .fetch_unspent_outputs_by_sender_offset_public_key(sender_offset_public_key, range)
+                    .await?;
+                Ok(NodeCommsResponse::FetchUnspentOutputsResponse { outputs })
+            },
+            NodeCommsRequest::EvaluateCovenants(transaction) => self.evaluate_covenants(*transaction).await,
         }
     }
 
+    /// Evaluates the covenant of every input against the outputs of the transaction at the next block height. The
+    /// spent output data of compact inputs and the height at which each spent output was mined are fetched from the
+    /// chain. As in block validation, spent outputs that are not yet in the chain are treated as mined at the next
+    /// height.
+    async fn evaluate_covenants(&self, transaction: Transaction) -> Result<NodeCommsResponse, CommsInterfaceError> {
+        let height = self.blockchain_db.get_chain_metadata().await?.height_of_longest_chain() + 1;
+        let output_hashes = transaction
+            .body
+            .inputs()
+            .iter()
+            .map(|input| input.output_hash())
+            .collect::<Vec<_>>();
+        let mined_infos = self
+            .blockchain_db
+            .fetch_utxos_and_mined_info(output_hashes.clone())
+            .await?;
+
+        let outputs = transaction.body.outputs();
+        let inputs = transaction
+            .body
+            .inputs()
+            .iter()
+            .zip(output_hashes)
+            .zip(mined_infos)
+            .enumerate()
+            .map(|(input_index, ((input, output_hash), mined_info))| {
+                let mined_height = mined_info.as_ref().map(|info| info.mined_height).unwrap_or(height);
+                let mut input = input.clone();
+                if input.is_compact() {
+                    match mined_info.and_then(|info| info.output.into_unpruned_output()) {
+                        Some(output) => input.add_output_data(
+                            output.version,
+                            output.features,
+                            output.commitment,
+                            output.script,
+                            output.sender_offset_public_key,
+                            output.covenant,
+                        ),
+                        None => {
+                            return InputCovenantEvaluation {
+                                input_index,
+                                output_hash,
+                                covenant: None,
+                                trace: CovenantTrace {
+                                    error: Some("The output spent by this input was not found".to_string()),
+                                    ..Default::default()
+                                },
+                            };
+                        },
+                    }
+                }
+
+                match input.covenant() {
+                    Ok(covenant) => InputCovenantEvaluation {
+                        input_index,
+                        output_hash,
+                        covenant: Some(covenant.clone()),
+                        trace: covenant.execute_with_trace(height, &input, Some(mined_height), outputs),
+                    },
+                    Err(err) => InputCovenantEvaluation {
+                        input_index,
+                        output_hash,
+                        covenant: None,
+                        trace: CovenantTrace {
+                            error: Some(err.to_string()),
+                            ..Default::default()
+                        },
+                    },
+                }
+            })
+            .collect();
+
+        Ok(NodeCommsResponse::EvaluateCovenantsResponse { height, inputs })
+    }
+
     /// Handles a `NewBlock` message. Only a single `NewBlock` message can be handled at once to prevent extraneous
     /// requests for the full block.
     /// This may (asynchronously) block until the other request(s) complete or time out and so should typically be
@@ -649,6 +748,13 @@ where B: BlockchainBackend + 'static
                 {
                     error!(target: LOG_TARGET, "Failed to ban peer: {}", e);
                 }
+                if let Err(e) = self
+                    .connectivity
+                    .report_peer(source_peer.clone(), ReputationEvent::BadRpcResponse)
+                    .await
+                {
+                    error!(target: LOG_TARGET, "Failed to report peer: {}", e);
+                }
 
                 debug!(
                     target: LOG_TARGET,
@@ -738,11 +844,18 @@ where B: BlockchainBackend + 'static
                 if let Some(source_peer) = source_peer {
                     if let Err(e) = self
                         .connectivity
-                        .ban_peer(source_peer, format!("Peer propagated invalid block: {}", e))
+                        .ban_peer(source_peer.clone(), format!("Peer propagated invalid block: {}", e))
                         .await
                     {
                         error!(target: LOG_TARGET, "Failed to ban peer: {}", e);
                     }
+                    if let Err(e) = self
+                        .connectivity
+                        .report_peer(source_peer, ReputationEvent::InvalidBlock)
+                        .await
+                    {
+                        error!(target: LOG_TARGET, "Failed to report peer: {}", e);
+                    }
                 }
                 self.publish_block_event(BlockEvent::AddBlockFailed(block));
                 Err(e.into())
@@ -20,12 +20,16 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{ops::RangeInclusive, sync::Arc};
+use std::{
+    ops::{Range, RangeInclusive},
+    sync::Arc,
+};
 
 use tari_common_types::{
     chain_metadata::ChainMetadata,
     types::{BlockHash, Commitment, HashOutput, PublicKey, Signature},
 };
+use tari_crypto::script::TariScript;
 use tari_service_framework::{reply_channel::SenderService, Service};
 use tokio::sync::broadcast;
 
@@ -34,13 +38,14 @@ use crate::{
         comms_request::GetNewBlockTemplateRequest,
         error::CommsInterfaceError,
         BlockEvent,
+        InputCovenantEvaluation,
         NodeCommsRequest,
         NodeCommsResponse,
     },
     blocks::{Block, ChainHeader, HistoricalBlock, NewBlockTemplate},
     chain_storage::UtxoMinedInfo,
     proof_of_work::PowAlgorithm,
-    transactions::transaction_components::{TransactionKernel, TransactionOutput},
+    transactions::transaction_components::{Transaction, TransactionKernel, TransactionOutput},
 };
 
 pub type BlockEventSender = broadcast::Sender<Arc<BlockEvent>>;
@@ -318,4 +323,57 @@ impl LocalNodeCommsInterface {
             _ => Err(CommsInterfaceError::UnexpectedApiResponse),
         }
     }
+
+    /// Fetches the unspent outputs locked with the given script. `range` selects a page of the matching outputs.
+    pub async fn fetch_unspent_outputs_by_script(
+        &mut self,
+        script: TariScript,
+        range: Range<usize>,
+    ) -> Result<Vec<UtxoMinedInfo>, CommsInterfaceError> {
+        match self
+            .request_sender
+            .call(NodeCommsRequest::FetchUnspentOutputsByScript { script, range })
+            .await??
+        {
+            NodeCommsResponse::FetchUnspentOutputsResponse { outputs } => Ok(outputs),
+            _ => Err(CommsInterfaceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Fetches the unspent outputs with the given sender offset public key. `range` selects a page of the matching
+    /// outputs.
+    pub async fn fetch_unspent_outputs_by_sender_offset_public_key(
+        &mut self,
+        sender_offset_public_key: PublicKey,
+        range: Range<usize>,
+    ) -> Result<Vec<UtxoMinedInfo>, CommsInterfaceError> {
+        match self
+            .request_sender
+            .call(NodeCommsRequest::FetchUnspentOutputsBySenderOffsetPublicKey {
+                sender_offset_public_key,
+                range,
+            })
+            .await??
+        {
+            NodeCommsResponse::FetchUnspentOutputsResponse { outputs } => Ok(outputs),
+            _ => Err(CommsInterfaceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Evaluates the covenant of every input of `transaction` against its outputs at the next block height, without
+    /// submitting the transaction. Returns the height at which the covenants were evaluated and the result for each
+    /// input.
+    pub async fn evaluate_covenants(
+        &mut self,
+        transaction: Transaction,
+    ) -> Result<(u64, Vec<InputCovenantEvaluation>), CommsInterfaceError> {
+        match self
+            .request_sender
+            .call(NodeCommsRequest::EvaluateCovenants(Box::new(transaction)))
+            .await??
+        {
+            NodeCommsResponse::EvaluateCovenantsResponse { height, inputs } => Ok((height, inputs)),
+            _ => Err(CommsInterfaceError::UnexpectedApiResponse),
+        }
+    }
 }
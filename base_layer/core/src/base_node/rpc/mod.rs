@@ -45,6 +45,8 @@ use crate::{
             FetchUtxosResponse,
             QueryDeletedRequest,
             QueryDeletedResponse,
+            SearchUtxosByScriptRequest,
+            SearchUtxosBySenderOffsetPublicKeyRequest,
             Signatures,
             SyncUtxosByBlockRequest,
             SyncUtxosByBlockResponse,
@@ -76,7 +78,7 @@ pub trait BaseNodeWalletService: Send + Sync + 'static {
         request: Request<Signatures>,
     ) -> Result<Response<TxQueryBatchResponses>, RpcStatus>;
 
-    #[rpc(method = 4)]
+    #[rpc(method = 4, cost = 10)]
     async fn fetch_matching_utxos(
         &self,
         request: Request<FetchMatchingUtxos>,
@@ -106,11 +108,29 @@ pub trait BaseNodeWalletService: Send + Sync + 'static {
     #[rpc(method = 10)]
     async fn get_height_at_time(&self, request: Request<u64>) -> Result<Response<u64>, RpcStatus>;
 
-    #[rpc(method = 11)]
+    #[rpc(method = 11, cost = 20)]
     async fn sync_utxos_by_block(
         &self,
         request: Request<SyncUtxosByBlockRequest>,
     ) -> Result<Streaming<SyncUtxosByBlockResponse>, RpcStatus>;
+
+    #[rpc(method = 12, cost = 20)]
+    async fn search_utxos_by_script(
+        &self,
+        request: Request<SearchUtxosByScriptRequest>,
+    ) -> Result<Response<UtxoQueryResponses>, RpcStatus>;
+
+    #[rpc(method = 13)]
+    async fn estimate_fee_per_gram(
+        &self,
+        request: Request<u64>,
+    ) -> Result<Response<proto::mempool::FeePerGramEstimateResponse>, RpcStatus>;
+
+    #[rpc(method = 14, cost = 20)]
+    async fn search_utxos_by_sender_offset_public_key(
+        &self,
+        request: Request<SearchUtxosBySenderOffsetPublicKeyRequest>,
+    ) -> Result<Response<UtxoQueryResponses>, RpcStatus>;
 }
 
 #[cfg(feature = "base_node")]
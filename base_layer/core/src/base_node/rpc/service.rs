@@ -21,12 +21,15 @@
 // OTHERWISE) ARISING IN ANY WAY OUT OF THE  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH
 // DAMAGE.
 
-use std::convert::TryFrom;
+use std::{convert::TryFrom, ops::Range};
 
 use log::*;
-use tari_common_types::types::Signature;
+use tari_common_types::types::{PublicKey, Signature};
 use tari_comms::protocol::rpc::{Request, Response, RpcStatus, Streaming};
-use tari_crypto::tari_utilities::hex::Hex;
+use tari_crypto::{
+    script::TariScript,
+    tari_utilities::{hex::Hex, ByteArray},
+};
 use tokio::sync::mpsc;
 
 use crate::{
@@ -35,7 +38,7 @@ use crate::{
         state_machine_service::states::StateInfo,
         StateMachineHandle,
     },
-    chain_storage::{async_db::AsyncBlockchainDb, BlockchainBackend, PrunedOutput},
+    chain_storage::{async_db::AsyncBlockchainDb, BlockchainBackend, ChainStorageError, PrunedOutput, UtxoMinedInfo},
     mempool::{service::MempoolHandle, TxStorageResponse},
     proto,
     proto::{
@@ -44,6 +47,8 @@ use crate::{
             FetchUtxosResponse,
             QueryDeletedRequest,
             QueryDeletedResponse,
+            SearchUtxosByScriptRequest,
+            SearchUtxosBySenderOffsetPublicKeyRequest,
             Signatures as SignaturesProto,
             SyncUtxosByBlockRequest,
             SyncUtxosByBlockResponse,
@@ -65,6 +70,32 @@ use crate::{
 
 const LOG_TARGET: &str = "c::base_node::rpc";
 
+/// The maximum number of outputs returned by a single UTXO search
+const MAX_ALLOWED_QUERY_SIZE: u64 = 512;
+
+/// Converts a requested page of search results into a range of matching outputs. A zero `limit` requests the maximum
+/// page size.
+fn utxo_search_range(offset: u64, limit: u64) -> Result<Range<usize>, RpcStatus> {
+    let limit = match limit {
+        0 => MAX_ALLOWED_QUERY_SIZE,
+        limit => limit.min(MAX_ALLOWED_QUERY_SIZE),
+    };
+    let end = offset
+        .checked_add(limit)
+        .ok_or_else(|| RpcStatus::bad_request("offset + limit overflows"))?;
+    let start = usize::try_from(offset).map_err(|_| RpcStatus::bad_request("offset is too large"))?;
+    let end = usize::try_from(end).map_err(|_| RpcStatus::bad_request("offset + limit is too large"))?;
+    Ok(start..end)
+}
+
+fn utxo_search_error_to_status(err: ChainStorageError) -> RpcStatus {
+    if err.is_output_search_index_disabled() {
+        RpcStatus::not_implemented("UTXO search indexes are disabled on this base node")
+    } else {
+        RpcStatus::log_internal_error(LOG_TARGET)(err)
+    }
+}
+
 pub struct BaseNodeWalletRpcService<B> {
     db: AsyncBlockchainDb<B>,
     mempool: MempoolHandle,
@@ -95,6 +126,32 @@ impl<B: BlockchainBackend + 'static> BaseNodeWalletRpcService<B> {
         self.state_machine.clone()
     }
 
+    async fn to_utxo_query_responses(&self, outputs: Vec<UtxoMinedInfo>) -> Result<UtxoQueryResponses, RpcStatus> {
+        let metadata = self
+            .db
+            .get_chain_metadata()
+            .await
+            .map_err(RpcStatus::log_internal_error(LOG_TARGET))?;
+
+        Ok(UtxoQueryResponses {
+            height_of_longest_chain: metadata.height_of_longest_chain(),
+            best_block: metadata.best_block().clone(),
+            responses: outputs
+                .into_iter()
+                .map(|utxo| UtxoQueryResponse {
+                    mmr_position: utxo.mmr_position.into(),
+                    mined_height: utxo.mined_height,
+                    mined_in_block: utxo.header_hash,
+                    output_hash: utxo.output.hash(),
+                    output: match utxo.output {
+                        PrunedOutput::Pruned { .. } => None,
+                        PrunedOutput::NotPruned { output } => Some(output.into()),
+                    },
+                })
+                .collect(),
+        })
+    }
+
     async fn fetch_kernel(&self, signature: Signature) -> Result<TxQueryResponse, RpcStatus> {
         let db = self.db();
         let chain_metadata = db
@@ -136,7 +193,7 @@ impl<B: BlockchainBackend + 'static> BaseNodeWalletRpcService<B> {
             .await
             .map_err(RpcStatus::log_internal_error(LOG_TARGET))?
         {
-            TxStorageResponse::UnconfirmedPool => TxQueryResponse {
+            TxStorageResponse::UnconfirmedPool | TxStorageResponse::UnconfirmedPoolReplaced => TxQueryResponse {
                 location: TxLocation::InMempool as i32,
                 block_hash: None,
                 confirmations: 0,
@@ -148,6 +205,11 @@ impl<B: BlockchainBackend + 'static> BaseNodeWalletRpcService<B> {
             TxStorageResponse::NotStoredTimeLocked |
             TxStorageResponse::NotStoredAlreadySpent |
             TxStorageResponse::NotStoredConsensus |
+            TxStorageResponse::NotStoredFeeTooLowForReplacement |
+            TxStorageResponse::NotStoredTooManyReplacements |
+            TxStorageResponse::NotStoredReplacementRejected |
+            TxStorageResponse::NotStoredFeeTooLow |
+            TxStorageResponse::NotStoredTooManyAncestors |
             TxStorageResponse::NotStored => TxQueryResponse {
                 location: TxLocation::NotStored as i32,
                 block_hash: None,
@@ -184,7 +246,7 @@ impl<B: BlockchainBackend + 'static> BaseNodeWalletService for BaseNodeWalletRpc
             .await
             .map_err(RpcStatus::log_internal_error(LOG_TARGET))?
         {
-            TxStorageResponse::UnconfirmedPool => TxSubmissionResponse {
+            TxStorageResponse::UnconfirmedPool | TxStorageResponse::UnconfirmedPoolReplaced => TxSubmissionResponse {
                 accepted: true,
                 rejection_reason: TxSubmissionRejectionReason::None.into(),
                 is_synced,
@@ -200,7 +262,18 @@ impl<B: BlockchainBackend + 'static> BaseNodeWalletService for BaseNodeWalletRpc
                 rejection_reason: TxSubmissionRejectionReason::TimeLocked.into(),
                 is_synced,
             },
-            TxStorageResponse::NotStoredConsensus | TxStorageResponse::NotStored => TxSubmissionResponse {
+            // The transaction conflicts with transactions in the mempool that it is not allowed to replace
+            TxStorageResponse::NotStoredFeeTooLowForReplacement |
+            TxStorageResponse::NotStoredTooManyReplacements |
+            TxStorageResponse::NotStoredReplacementRejected => TxSubmissionResponse {
+                accepted: false,
+                rejection_reason: TxSubmissionRejectionReason::DoubleSpend.into(),
+                is_synced,
+            },
+            TxStorageResponse::NotStoredConsensus |
+            TxStorageResponse::NotStoredFeeTooLow |
+            TxStorageResponse::NotStoredTooManyAncestors |
+            TxStorageResponse::NotStored => TxSubmissionResponse {
                 accepted: false,
                 rejection_reason: TxSubmissionRejectionReason::ValidationFailed.into(),
                 is_synced,
@@ -600,4 +673,60 @@ impl<B: BlockchainBackend + 'static> BaseNodeWalletService for BaseNodeWalletRpc
 
         Ok(Streaming::new(rx))
     }
+
+    async fn search_utxos_by_script(
+        &self,
+        request: Request<SearchUtxosByScriptRequest>,
+    ) -> Result<Response<UtxoQueryResponses>, RpcStatus> {
+        let message = request.into_message();
+        let script = TariScript::from_bytes(&message.script)
+            .map_err(|err| RpcStatus::bad_request(format!("Malformed script: {}", err)))?;
+        let range = utxo_search_range(message.offset, message.limit)?;
+
+        debug!(target: LOG_TARGET, "Searching for UTXO(s) {:?} by script hash", range);
+
+        let outputs = self
+            .db()
+            .fetch_unspent_outputs_by_script(script, range)
+            .await
+            .map_err(utxo_search_error_to_status)?;
+
+        Ok(Response::new(self.to_utxo_query_responses(outputs).await?))
+    }
+
+    async fn search_utxos_by_sender_offset_public_key(
+        &self,
+        request: Request<SearchUtxosBySenderOffsetPublicKeyRequest>,
+    ) -> Result<Response<UtxoQueryResponses>, RpcStatus> {
+        let message = request.into_message();
+        let sender_offset_public_key = PublicKey::from_bytes(&message.sender_offset_public_key)
+            .map_err(|err| RpcStatus::bad_request(format!("Malformed sender offset public key: {}", err)))?;
+        let range = utxo_search_range(message.offset, message.limit)?;
+
+        debug!(
+            target: LOG_TARGET,
+            "Searching for UTXO(s) {:?} by sender offset public key", range
+        );
+
+        let outputs = self
+            .db()
+            .fetch_unspent_outputs_by_sender_offset_public_key(sender_offset_public_key, range)
+            .await
+            .map_err(utxo_search_error_to_status)?;
+
+        Ok(Response::new(self.to_utxo_query_responses(outputs).await?))
+    }
+
+    async fn estimate_fee_per_gram(
+        &self,
+        request: Request<u64>,
+    ) -> Result<Response<proto::mempool::FeePerGramEstimateResponse>, RpcStatus> {
+        let target_blocks = request.into_message();
+        let estimate = self
+            .mempool()
+            .estimate_fee_per_gram(target_blocks)
+            .await
+            .map_err(RpcStatus::log_internal_error(LOG_TARGET))?;
+        Ok(Response::new(estimate.into()))
+    }
 }
@@ -29,7 +29,11 @@ use std::{
 use futures::StreamExt;
 use log::*;
 use num_format::{Locale, ToFormattedString};
-use tari_comms::{connectivity::ConnectivityRequester, peer_manager::NodeId, PeerConnection};
+use tari_comms::{
+    connectivity::{ConnectivityRequester, ReputationEvent},
+    peer_manager::NodeId,
+    PeerConnection,
+};
 use tari_utilities::{hex::Hex, Hashable};
 use tracing;
 
@@ -132,6 +136,7 @@ impl<B: BlockchainBackend + 'static> BlockSynchronizer<B> {
             );
             match self.synchronize_blocks(sync_peer, client, max_latency).await {
                 Ok(_) => {
+                    self.report_peer(node_id, ReputationEvent::UsefulSync).await;
                     self.db.cleanup_orphans().await?;
                     return Ok(());
                 },
@@ -154,11 +159,12 @@ impl<B: BlockchainBackend + 'static> BlockSynchronizer<B> {
                         target: LOG_TARGET,
                         "Banning peer because provided block failed validation: {}", err
                     );
-                    self.ban_peer(node_id, &err).await?;
+                    self.ban_peer(node_id, ReputationEvent::InvalidBlock, &err).await?;
                     return Err(err.into());
                 },
                 Err(err @ BlockSyncError::MaxLatencyExceeded { .. }) => {
                     warn!(target: LOG_TARGET, "{}", err);
+                    self.report_peer(node_id, ReputationEvent::Timeout).await;
                     if i == self.sync_peers.len() - 1 {
                         return Err(BlockSyncError::AllSyncPeersExceedLatency);
                     }
@@ -166,7 +172,7 @@ impl<B: BlockchainBackend + 'static> BlockSynchronizer<B> {
                 },
                 Err(err @ BlockSyncError::ProtocolViolation(_)) => {
                     warn!(target: LOG_TARGET, "Banning peer: {}", err);
-                    self.ban_peer(node_id, &err).await?;
+                    self.ban_peer(node_id, ReputationEvent::BadRpcResponse, &err).await?;
                     return Err(err);
                 },
                 Err(err) => return Err(err),
@@ -176,6 +182,15 @@ impl<B: BlockchainBackend + 'static> BlockSynchronizer<B> {
         Err(BlockSyncError::NoSyncPeers)
     }
 
+    async fn report_peer(&mut self, node_id: &NodeId, event: ReputationEvent) {
+        if let Err(err) = self.connectivity.report_peer(node_id.clone(), event).await {
+            warn!(
+                target: LOG_TARGET,
+                "Failed to report {} for peer {}: {}", event, node_id, err
+            );
+        }
+    }
+
     async fn connect_to_sync_peer(&self, peer: NodeId) -> Result<PeerConnection, BlockSyncError> {
         let connection = self.connectivity.dial_peer(peer).await?;
         Ok(connection)
@@ -355,7 +370,15 @@ impl<B: BlockchainBackend + 'static> BlockSynchronizer<B> {
         Ok(())
     }
 
-    async fn ban_peer<T: ToString>(&mut self, node_id: &NodeId, reason: T) -> Result<(), BlockSyncError> {
+    /// Removes a misbehaving peer from the sync peers, bans it and records the misbehaviour against its reputation.
+    /// The peer is banned regardless of its reputation score, so that credit built up by previous syncs cannot
+    /// outweigh invalid data.
+    async fn ban_peer<T: ToString>(
+        &mut self,
+        node_id: &NodeId,
+        event: ReputationEvent,
+        reason: T,
+    ) -> Result<(), BlockSyncError> {
         let reason = reason.to_string();
         if self.config.forced_sync_peers.contains(node_id) {
             debug!(
@@ -364,12 +387,6 @@ impl<B: BlockchainBackend + 'static> BlockSynchronizer<B> {
             );
             return Ok(());
         }
-        if let Some(pos) = self.sync_peers.iter().position(|p| p.node_id() == node_id) {
-            self.sync_peers.remove(pos);
-            if self.sync_peers.is_empty() {
-                return Err(BlockSyncError::NoSyncPeers);
-            }
-        }
         warn!(target: LOG_TARGET, "Banned sync peer because {}", reason);
         if let Err(err) = self
             .connectivity
@@ -378,6 +395,13 @@ impl<B: BlockchainBackend + 'static> BlockSynchronizer<B> {
         {
             error!(target: LOG_TARGET, "Failed to ban peer: {}", err);
         }
+        self.report_peer(node_id, event).await;
+        if let Some(pos) = self.sync_peers.iter().position(|p| p.node_id() == node_id) {
+            self.sync_peers.remove(pos);
+            if self.sync_peers.is_empty() {
+                return Err(BlockSyncError::NoSyncPeers);
+            }
+        }
         Ok(())
     }
 }
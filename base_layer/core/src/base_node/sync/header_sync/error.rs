@@ -48,6 +48,8 @@ pub enum BlockHeaderSyncError {
     FoundHashIndexOutOfRange(u64, u64),
     #[error("Failed to ban peer: {0}")]
     FailedToBan(ConnectivityError),
+    #[error("Failed to report peer: {0}")]
+    FailedToReportPeer(ConnectivityError),
     #[error("Connectivity Error: {0}")]
     ConnectivityError(#[from] ConnectivityError),
     #[error(
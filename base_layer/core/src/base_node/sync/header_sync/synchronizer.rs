@@ -29,7 +29,7 @@ use futures::StreamExt;
 use log::*;
 use tari_common_types::{chain_metadata::ChainMetadata, types::HashOutput};
 use tari_comms::{
-    connectivity::ConnectivityRequester,
+    connectivity::{ConnectivityRequester, ReputationEvent},
     peer_manager::NodeId,
     protocol::rpc::{RpcError, RpcHandshakeError},
     PeerConnection,
@@ -153,7 +153,10 @@ impl<'a, B: BlockchainBackend + 'static> HeaderSynchronizer<'a, B> {
             debug!(target: LOG_TARGET, "Sync peer latency is {:.2?}", latency);
 
             match self.attempt_sync(&sync_peer, client, max_latency).await {
-                Ok(()) => return Ok(sync_peer),
+                Ok(()) => {
+                    self.report_peer(node_id, ReputationEvent::UsefulSync).await;
+                    return Ok(sync_peer);
+                },
                 // Try another peer
                 Err(err @ BlockHeaderSyncError::NotInSync) => {
                     warn!(target: LOG_TARGET, "{}", err);
@@ -189,13 +192,23 @@ impl<'a, B: BlockchainBackend + 'static> HeaderSynchronizer<'a, B> {
                     })
                     .await?;
                 },
-                Err(err @ BlockHeaderSyncError::InvalidBlockHeight { .. }) => {
+                Err(
+                    err @ BlockHeaderSyncError::InvalidBlockHeight { .. } |
+                    err @ BlockHeaderSyncError::ChainLinkBroken { .. } |
+                    err @ BlockHeaderSyncError::ReceivedInvalidHeader(_),
+                ) => {
                     warn!(target: LOG_TARGET, "{}", err);
                     self.ban_peer_long(node_id, BanReason::GeneralHeaderSyncFailure(err))
                         .await?;
                 },
+                Err(err @ BlockHeaderSyncError::InvalidProtocolResponse(_)) => {
+                    warn!(target: LOG_TARGET, "{}", err);
+                    self.report_bad_peer(node_id, BanReason::GeneralHeaderSyncFailure(err))
+                        .await?;
+                },
                 Err(err @ BlockHeaderSyncError::MaxLatencyExceeded { .. }) => {
                     warn!(target: LOG_TARGET, "{}", err);
+                    self.report_peer(node_id, ReputationEvent::Timeout).await;
                     if i == self.sync_peers.len() - 1 {
                         return Err(BlockHeaderSyncError::AllSyncPeersExceedLatency);
                     }
@@ -235,6 +248,8 @@ impl<'a, B: BlockchainBackend + 'static> HeaderSynchronizer<'a, B> {
         self.ban_peer_for(node_id, reason, self.config.short_ban_period).await
     }
 
+    /// Bans a sync peer that sent invalid data and records the misbehaviour against its reputation. The peer is banned
+    /// regardless of its reputation score, so that credit built up by previous syncs cannot outweigh invalid data.
     async fn ban_peer_for(
         &mut self,
         node_id: &NodeId,
@@ -253,9 +268,41 @@ impl<'a, B: BlockchainBackend + 'static> HeaderSynchronizer<'a, B> {
             .ban_peer_until(node_id.clone(), duration, reason.to_string())
             .await
             .map_err(BlockHeaderSyncError::FailedToBan)?;
+        self.report_peer(node_id, reason.reputation_event()).await;
+        Ok(())
+    }
+
+    /// Lowers the reputation of a sync peer that misbehaved without sending invalid chain data. The connectivity
+    /// manager bans the peer once its reputation falls far enough.
+    async fn report_bad_peer(&mut self, node_id: &NodeId, reason: BanReason) -> Result<(), BlockHeaderSyncError> {
+        if self.config.forced_sync_peers.contains(node_id) {
+            debug!(
+                target: LOG_TARGET,
+                "Not reporting peer that is allowlisted for sync. Reason = {}", reason
+            );
+            return Ok(());
+        }
+        let event = reason.reputation_event();
+        warn!(
+            target: LOG_TARGET,
+            "Reporting {} for sync peer {} because {}", event, node_id, reason
+        );
+        self.connectivity
+            .report_peer(node_id.clone(), event)
+            .await
+            .map_err(BlockHeaderSyncError::FailedToReportPeer)?;
         Ok(())
     }
 
+    async fn report_peer(&mut self, node_id: &NodeId, event: ReputationEvent) {
+        if let Err(err) = self.connectivity.report_peer(node_id.clone(), event).await {
+            warn!(
+                target: LOG_TARGET,
+                "Failed to report {} for peer {}: {}", event, node_id, err
+            );
+        }
+    }
+
     #[tracing::instrument(skip(self, client), err)]
     async fn attempt_sync(
         &mut self,
@@ -796,6 +843,26 @@ enum BanReason {
     PeerCouldNotProvideStrongerChain { claimed: u128, actual: u128, local: u128 },
 }
 
+impl BanReason {
+    /// The reputation event reported to the connectivity manager for this misbehaviour
+    fn reputation_event(&self) -> ReputationEvent {
+        use BanReason::*;
+        match self {
+            ValidationFailed(_) |
+            ChainSplitNotFound |
+            GeneralHeaderSyncFailure(BlockHeaderSyncError::InvalidBlockHeight { .. }) |
+            GeneralHeaderSyncFailure(BlockHeaderSyncError::ChainLinkBroken { .. }) |
+            GeneralHeaderSyncFailure(BlockHeaderSyncError::ReceivedInvalidHeader(_)) => ReputationEvent::InvalidBlock,
+            RpcNegotiationTimedOut => ReputationEvent::Timeout,
+            PeerSentTooManyHeaders(_) |
+            PeerSentInvalidTipHeight { .. } |
+            SplitHashGreaterThanHashes { .. } |
+            GeneralHeaderSyncFailure(_) |
+            PeerCouldNotProvideStrongerChain { .. } => ReputationEvent::BadRpcResponse,
+        }
+    }
+}
+
 struct ChainSplitInfo {
     local_tip_header: ChainHeader,
     remote_tip_height: u64,
@@ -55,13 +55,14 @@ use crate::{
 
 #[tari_rpc(protocol_name = b"t/blksync/1", server_struct = BaseNodeSyncRpcServer, client_struct = BaseNodeSyncRpcClient)]
 pub trait BaseNodeSyncService: Send + Sync + 'static {
-    #[rpc(method = 1)]
+    /// In addition to the cost of the call, the peer is charged one quota token for each block streamed.
+    #[rpc(method = 1, cost = 5)]
     async fn sync_blocks(
         &self,
         request: Request<SyncBlocksRequest>,
     ) -> Result<Streaming<proto::base_node::BlockBodyResponse>, RpcStatus>;
 
-    #[rpc(method = 2)]
+    #[rpc(method = 2, cost = 10)]
     async fn sync_headers(
         &self,
         request: Request<SyncHeadersRequest>,
@@ -85,13 +86,15 @@ pub trait BaseNodeSyncService: Send + Sync + 'static {
         request: Request<()>,
     ) -> Result<Response<proto::base_node::ChainMetadata>, RpcStatus>;
 
-    #[rpc(method = 6)]
+    #[rpc(method = 6, cost = 20)]
     async fn sync_kernels(
         &self,
         request: Request<SyncKernelsRequest>,
     ) -> Result<Streaming<proto::types::TransactionKernel>, RpcStatus>;
 
-    #[rpc(method = 8)]
+    /// In addition to the cost of the call, the peer is charged one quota token for each UTXO and deleted bitmap
+    /// streamed.
+    #[rpc(method = 8, cost = 5)]
     async fn sync_utxos(&self, request: Request<SyncUtxosRequest>) -> Result<Streaming<SyncUtxosResponse>, RpcStatus>;
 }
 
@@ -109,6 +109,7 @@ impl<B: BlockchainBackend + 'static> BaseNodeSyncService for BaseNodeSyncRpcServ
         request: Request<SyncBlocksRequest>,
     ) -> Result<Streaming<proto::base_node::BlockBodyResponse>, RpcStatus> {
         let peer_node_id = request.context().peer_node_id().clone();
+        let quota = request.quota();
         let message = request.into_message();
         let mut block_event_stream = self.base_node_service.get_block_event_stream();
 
@@ -197,6 +198,19 @@ impl<B: BlockchainBackend + 'static> BaseNodeSyncService for BaseNodeSyncRpcServ
                         }
                     }
 
+                    // Charge the peer for each block in the batch
+                    if let Some(ref quota) = quota {
+                        let cost = u32::try_from(end - start + 1).unwrap_or(u32::MAX);
+                        if let Err(err) = quota.consume(cost) {
+                            debug!(
+                                target: LOG_TARGET,
+                                "Peer '{}' exceeded its quota at block #{}", peer_node_id, start
+                            );
+                            let _ = tx.send(Err(err)).await;
+                            break;
+                        }
+                    }
+
                     debug!(
                         target: LOG_TARGET,
                         "Sending blocks #{} - #{} to '{}'", start, end, peer_node_id
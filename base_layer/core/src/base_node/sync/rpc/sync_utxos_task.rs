@@ -20,11 +20,11 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{sync::Arc, time::Instant};
+use std::{convert::TryFrom, sync::Arc, time::Instant};
 
 use log::*;
 use tari_comms::{
-    protocol::rpc::{Request, RpcStatus},
+    protocol::rpc::{Request, RequestQuota, RpcStatus},
     utils,
 };
 use tari_crypto::tari_utilities::{hex::Hex, Hashable};
@@ -57,6 +57,7 @@ where B: BlockchainBackend + 'static
         mut tx: mpsc::Sender<Result<SyncUtxosResponse, RpcStatus>>,
     ) -> Result<(), RpcStatus> {
         let peer = request.context().peer_node_id().clone();
+        let quota = request.quota();
         let msg = request.into_message();
         let start_header = self
             .db
@@ -109,6 +110,7 @@ where B: BlockchainBackend + 'static
             if let Err(err) = self
                 .start_streaming(
                     &mut tx,
+                    quota,
                     start_header.into_header(),
                     skip_outputs,
                     prev_utxo_mmr_size,
@@ -131,6 +133,7 @@ where B: BlockchainBackend + 'static
     async fn start_streaming(
         &self,
         tx: &mut mpsc::Sender<Result<SyncUtxosResponse, RpcStatus>>,
+        quota: Option<RequestQuota>,
         mut current_header: BlockHeader,
         mut skip_outputs: u64,
         mut prev_utxo_mmr_size: u64,
@@ -196,6 +199,14 @@ where B: BlockchainBackend + 'static
                 current_header.height,
                 deleted_diff.cardinality(),
             );
+
+            // Charge the peer for each UTXO and deleted bitmap in this block
+            if let Some(ref quota) = quota {
+                let num_items =
+                    utxos.len().saturating_sub(skip_outputs as usize) + usize::from(include_deleted_bitmaps);
+                quota.consume(u32::try_from(num_items).unwrap_or(u32::MAX))?;
+            }
+
             let utxos = utxos
                 .into_iter()
                 .skip(skip_outputs as usize)
@@ -33,6 +33,7 @@ use tari_common_types::{
     chain_metadata::ChainMetadata,
     types::{BlockHash, Commitment, HashOutput, PublicKey, Signature},
 };
+use tari_crypto::script::TariScript;
 use tari_utilities::epoch_time::EpochTime;
 
 use crate::{
@@ -174,6 +175,12 @@ impl<B: BlockchainBackend + 'static> AsyncBlockchainDb<B> {
         parent_public_key: PublicKey,
         range: Range<usize>) -> Vec<UtxoMinedInfo>, "fetch_all_unspent_by_parent_public_key");
 
+    make_async_fn!(fetch_unspent_outputs_by_script(script: TariScript, range: Range<usize>) -> Vec<UtxoMinedInfo>, "fetch_unspent_outputs_by_script");
+
+    make_async_fn!(fetch_unspent_outputs_by_sender_offset_public_key(
+        sender_offset_public_key: PublicKey,
+        range: Range<usize>) -> Vec<UtxoMinedInfo>, "fetch_unspent_outputs_by_sender_offset_public_key");
+
     //---------------------------------- Kernel --------------------------------------------//
     make_async_fn!(fetch_kernel_by_excess_sig(excess_sig: Signature) -> Option<(TransactionKernel, HashOutput)>, "fetch_kernel_by_excess_sig");
 
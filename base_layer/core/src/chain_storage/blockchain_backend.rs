@@ -5,6 +5,7 @@ use tari_common_types::{
     chain_metadata::ChainMetadata,
     types::{Commitment, HashOutput, PublicKey, Signature},
 };
+use tari_crypto::script::TariScript;
 use tari_mmr::Hash;
 
 use crate::{
@@ -135,6 +136,22 @@ pub trait BlockchainBackend: Send + Sync {
         range: Range<usize>,
     ) -> Result<Vec<UtxoMinedInfo>, ChainStorageError>;
 
+    /// Fetch unspent outputs locked with the given script. `range` selects a page of the matching outputs. Returns
+    /// `OutputSearchIndexDisabled` if the backend was opened without the output search indexes.
+    fn fetch_unspent_outputs_by_script(
+        &self,
+        script: &TariScript,
+        range: Range<usize>,
+    ) -> Result<Vec<UtxoMinedInfo>, ChainStorageError>;
+
+    /// Fetch unspent outputs with the given sender offset public key. `range` selects a page of the matching outputs.
+    /// Returns `OutputSearchIndexDisabled` if the backend was opened without the output search indexes.
+    fn fetch_unspent_outputs_by_sender_offset_public_key(
+        &self,
+        sender_offset_public_key: &PublicKey,
+        range: Range<usize>,
+    ) -> Result<Vec<UtxoMinedInfo>, ChainStorageError>;
+
     /// Fetch all outputs in a block
     fn fetch_outputs_in_block(&self, header_hash: &HashOutput) -> Result<Vec<PrunedOutput>, ChainStorageError>;
 
@@ -208,3 +225,232 @@ pub trait BlockchainBackend: Send + Sync {
     /// Fetches all tracked reorgs
     fn fetch_all_reorgs(&self) -> Result<Vec<Reorg>, ChainStorageError>;
 }
+
+/// Allows the backend to be selected at runtime, e.g. `Box<dyn BlockchainBackend>`
+impl<T: BlockchainBackend + ?Sized> BlockchainBackend for Box<T> {
+    fn write(&mut self, tx: DbTransaction) -> Result<(), ChainStorageError> {
+        (**self).write(tx)
+    }
+
+    fn fetch(&self, key: &DbKey) -> Result<Option<DbValue>, ChainStorageError> {
+        (**self).fetch(key)
+    }
+
+    fn contains(&self, key: &DbKey) -> Result<bool, ChainStorageError> {
+        (**self).contains(key)
+    }
+
+    fn fetch_chain_header_by_height(&self, height: u64) -> Result<ChainHeader, ChainStorageError> {
+        (**self).fetch_chain_header_by_height(height)
+    }
+
+    fn fetch_header_accumulated_data(
+        &self,
+        hash: &HashOutput,
+    ) -> Result<Option<BlockHeaderAccumulatedData>, ChainStorageError> {
+        (**self).fetch_header_accumulated_data(hash)
+    }
+
+    fn fetch_chain_header_in_all_chains(&self, hash: &HashOutput) -> Result<ChainHeader, ChainStorageError> {
+        (**self).fetch_chain_header_in_all_chains(hash)
+    }
+
+    fn fetch_header_containing_kernel_mmr(&self, mmr_position: u64) -> Result<ChainHeader, ChainStorageError> {
+        (**self).fetch_header_containing_kernel_mmr(mmr_position)
+    }
+
+    fn fetch_header_containing_utxo_mmr(&self, mmr_position: u64) -> Result<ChainHeader, ChainStorageError> {
+        (**self).fetch_header_containing_utxo_mmr(mmr_position)
+    }
+
+    fn is_empty(&self) -> Result<bool, ChainStorageError> {
+        (**self).is_empty()
+    }
+
+    fn fetch_block_accumulated_data(
+        &self,
+        header_hash: &HashOutput,
+    ) -> Result<Option<BlockAccumulatedData>, ChainStorageError> {
+        (**self).fetch_block_accumulated_data(header_hash)
+    }
+
+    fn fetch_block_accumulated_data_by_height(
+        &self,
+        height: u64,
+    ) -> Result<Option<BlockAccumulatedData>, ChainStorageError> {
+        (**self).fetch_block_accumulated_data_by_height(height)
+    }
+
+    fn fetch_kernels_in_block(&self, header_hash: &HashOutput) -> Result<Vec<TransactionKernel>, ChainStorageError> {
+        (**self).fetch_kernels_in_block(header_hash)
+    }
+
+    fn fetch_kernel_by_excess(
+        &self,
+        excess: &[u8],
+    ) -> Result<Option<(TransactionKernel, HashOutput)>, ChainStorageError> {
+        (**self).fetch_kernel_by_excess(excess)
+    }
+
+    fn fetch_kernel_by_excess_sig(
+        &self,
+        excess_sig: &Signature,
+    ) -> Result<Option<(TransactionKernel, HashOutput)>, ChainStorageError> {
+        (**self).fetch_kernel_by_excess_sig(excess_sig)
+    }
+
+    fn fetch_utxos_in_block(
+        &self,
+        header_hash: &HashOutput,
+        deleted: Option<&Bitmap>,
+    ) -> Result<(Vec<PrunedOutput>, Bitmap), ChainStorageError> {
+        (**self).fetch_utxos_in_block(header_hash, deleted)
+    }
+
+    fn fetch_output(&self, output_hash: &HashOutput) -> Result<Option<UtxoMinedInfo>, ChainStorageError> {
+        (**self).fetch_output(output_hash)
+    }
+
+    fn fetch_unspent_output_hash_by_commitment(
+        &self,
+        commitment: &Commitment,
+    ) -> Result<Option<HashOutput>, ChainStorageError> {
+        (**self).fetch_unspent_output_hash_by_commitment(commitment)
+    }
+
+    fn fetch_utxo_by_unique_id(
+        &self,
+        parent_public_key: Option<&PublicKey>,
+        unique_id: &[u8],
+        deleted_at: Option<u64>,
+    ) -> Result<Option<UtxoMinedInfo>, ChainStorageError> {
+        (**self).fetch_utxo_by_unique_id(parent_public_key, unique_id, deleted_at)
+    }
+
+    fn fetch_all_unspent_by_parent_public_key(
+        &self,
+        parent_public_key: &PublicKey,
+        range: Range<usize>,
+    ) -> Result<Vec<UtxoMinedInfo>, ChainStorageError> {
+        (**self).fetch_all_unspent_by_parent_public_key(parent_public_key, range)
+    }
+
+    fn fetch_unspent_outputs_by_script(
+        &self,
+        script: &TariScript,
+        range: Range<usize>,
+    ) -> Result<Vec<UtxoMinedInfo>, ChainStorageError> {
+        (**self).fetch_unspent_outputs_by_script(script, range)
+    }
+
+    fn fetch_unspent_outputs_by_sender_offset_public_key(
+        &self,
+        sender_offset_public_key: &PublicKey,
+        range: Range<usize>,
+    ) -> Result<Vec<UtxoMinedInfo>, ChainStorageError> {
+        (**self).fetch_unspent_outputs_by_sender_offset_public_key(sender_offset_public_key, range)
+    }
+
+    fn fetch_outputs_in_block(&self, header_hash: &HashOutput) -> Result<Vec<PrunedOutput>, ChainStorageError> {
+        (**self).fetch_outputs_in_block(header_hash)
+    }
+
+    fn fetch_inputs_in_block(&self, header_hash: &HashOutput) -> Result<Vec<TransactionInput>, ChainStorageError> {
+        (**self).fetch_inputs_in_block(header_hash)
+    }
+
+    fn fetch_mmr_size(&self, tree: MmrTree) -> Result<u64, ChainStorageError> {
+        (**self).fetch_mmr_size(tree)
+    }
+
+    fn fetch_mmr_leaf_index(&self, tree: MmrTree, hash: &HashOutput) -> Result<Option<u32>, ChainStorageError> {
+        (**self).fetch_mmr_leaf_index(tree, hash)
+    }
+
+    fn orphan_count(&self) -> Result<usize, ChainStorageError> {
+        (**self).orphan_count()
+    }
+
+    fn fetch_last_header(&self) -> Result<BlockHeader, ChainStorageError> {
+        (**self).fetch_last_header()
+    }
+
+    fn clear_all_pending_headers(&self) -> Result<usize, ChainStorageError> {
+        (**self).clear_all_pending_headers()
+    }
+
+    fn fetch_last_chain_header(&self) -> Result<ChainHeader, ChainStorageError> {
+        (**self).fetch_last_chain_header()
+    }
+
+    fn fetch_tip_header(&self) -> Result<ChainHeader, ChainStorageError> {
+        (**self).fetch_tip_header()
+    }
+
+    fn fetch_chain_metadata(&self) -> Result<ChainMetadata, ChainStorageError> {
+        (**self).fetch_chain_metadata()
+    }
+
+    fn utxo_count(&self) -> Result<usize, ChainStorageError> {
+        (**self).utxo_count()
+    }
+
+    fn kernel_count(&self) -> Result<usize, ChainStorageError> {
+        (**self).kernel_count()
+    }
+
+    fn fetch_orphan_chain_tip_by_hash(&self, hash: &HashOutput) -> Result<Option<ChainHeader>, ChainStorageError> {
+        (**self).fetch_orphan_chain_tip_by_hash(hash)
+    }
+
+    fn fetch_orphan_children_of(&self, hash: HashOutput) -> Result<Vec<Block>, ChainStorageError> {
+        (**self).fetch_orphan_children_of(hash)
+    }
+
+    fn fetch_orphan_chain_block(&self, hash: HashOutput) -> Result<Option<ChainBlock>, ChainStorageError> {
+        (**self).fetch_orphan_chain_block(hash)
+    }
+
+    fn fetch_deleted_bitmap(&self) -> Result<DeletedBitmap, ChainStorageError> {
+        (**self).fetch_deleted_bitmap()
+    }
+
+    fn delete_oldest_orphans(
+        &mut self,
+        horizon_height: u64,
+        orphan_storage_capacity: usize,
+    ) -> Result<(), ChainStorageError> {
+        (**self).delete_oldest_orphans(horizon_height, orphan_storage_capacity)
+    }
+
+    fn fetch_monero_seed_first_seen_height(&self, seed: &[u8]) -> Result<u64, ChainStorageError> {
+        (**self).fetch_monero_seed_first_seen_height(seed)
+    }
+
+    fn fetch_horizon_data(&self) -> Result<Option<HorizonData>, ChainStorageError> {
+        (**self).fetch_horizon_data()
+    }
+
+    fn get_stats(&self) -> Result<DbBasicStats, ChainStorageError> {
+        (**self).get_stats()
+    }
+
+    fn fetch_total_size_stats(&self) -> Result<DbTotalSizeStats, ChainStorageError> {
+        (**self).fetch_total_size_stats()
+    }
+
+    fn fetch_header_hash_by_deleted_mmr_positions(
+        &self,
+        mmr_positions: Vec<u32>,
+    ) -> Result<Vec<Option<(u64, HashOutput)>>, ChainStorageError> {
+        (**self).fetch_header_hash_by_deleted_mmr_positions(mmr_positions)
+    }
+
+    fn bad_block_exists(&self, block_hash: HashOutput) -> Result<bool, ChainStorageError> {
+        (**self).bad_block_exists(block_hash)
+    }
+
+    fn fetch_all_reorgs(&self) -> Result<Vec<Reorg>, ChainStorageError> {
+        (**self).fetch_all_reorgs()
+    }
+}
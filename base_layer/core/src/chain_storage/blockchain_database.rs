@@ -37,7 +37,10 @@ use tari_common_types::{
     chain_metadata::ChainMetadata,
     types::{BlockHash, Commitment, HashDigest, HashOutput, PublicKey, Signature},
 };
-use tari_crypto::tari_utilities::{hex::Hex, ByteArray, Hashable};
+use tari_crypto::{
+    script::TariScript,
+    tari_utilities::{hex::Hex, ByteArray, Hashable},
+};
 use tari_mmr::{pruned_hashset::PrunedHashSet, MerkleMountainRange, MutableMmr};
 use tari_utilities::epoch_time::EpochTime;
 
@@ -390,6 +393,24 @@ where B: BlockchainBackend
         db.fetch_all_unspent_by_parent_public_key(&parent_public_key, range)
     }
 
+    pub fn fetch_unspent_outputs_by_script(
+        &self,
+        script: TariScript,
+        range: Range<usize>,
+    ) -> Result<Vec<UtxoMinedInfo>, ChainStorageError> {
+        let db = self.db_read_access()?;
+        db.fetch_unspent_outputs_by_script(&script, range)
+    }
+
+    pub fn fetch_unspent_outputs_by_sender_offset_public_key(
+        &self,
+        sender_offset_public_key: PublicKey,
+        range: Range<usize>,
+    ) -> Result<Vec<UtxoMinedInfo>, ChainStorageError> {
+        let db = self.db_read_access()?;
+        db.fetch_unspent_outputs_by_sender_offset_public_key(&sender_offset_public_key, range)
+    }
+
     /// Return a list of matching utxos, with each being `None` if not found. If found, the transaction
     /// output, and a boolean indicating if the UTXO was spent as of the block hash specified or the tip if not
     /// specified.
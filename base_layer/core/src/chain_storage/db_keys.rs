@@ -0,0 +1,238 @@
+//  Copyright 2022, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Key and metadata types that define the on-disk layout shared by the blockchain database backends.
+
+use std::{fmt, fmt::Formatter, mem};
+
+use blake2::Digest;
+use serde::{Deserialize, Serialize};
+use tari_common_types::types::{BlockHash, HashDigest, HashOutput, PublicKey};
+use tari_crypto::{
+    script::TariScript,
+    tari_utilities::{hex::Hex, ByteArray},
+};
+
+use crate::{
+    blocks::DeletedBitmap,
+    chain_storage::{ChainStorageError, HorizonData},
+    transactions::transaction_components::TransactionError,
+};
+
+#[derive(Debug, Clone, PartialEq, Copy)]
+pub(crate) enum MetadataKey {
+    ChainHeight,
+    BestBlock,
+    AccumulatedWork,
+    PruningHorizon,
+    PrunedHeight,
+    HorizonData,
+    DeletedBitmap,
+}
+
+impl MetadataKey {
+    #[inline]
+    pub fn as_u32(self) -> u32 {
+        self as u32
+    }
+}
+
+impl fmt::Display for MetadataKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MetadataKey::ChainHeight => f.write_str("Current chain height"),
+            MetadataKey::AccumulatedWork => f.write_str("Total accumulated work"),
+            MetadataKey::PruningHorizon => f.write_str("Pruning horizon"),
+            MetadataKey::PrunedHeight => f.write_str("Effective pruned height"),
+            MetadataKey::BestBlock => f.write_str("Chain tip block hash"),
+            MetadataKey::HorizonData => f.write_str("Database info"),
+            MetadataKey::DeletedBitmap => f.write_str("Deleted bitmap"),
+        }
+    }
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub(crate) enum MetadataValue {
+    ChainHeight(u64),
+    BestBlock(BlockHash),
+    AccumulatedWork(u128),
+    PruningHorizon(u64),
+    PrunedHeight(u64),
+    HorizonData(HorizonData),
+    DeletedBitmap(DeletedBitmap),
+}
+
+impl fmt::Display for MetadataValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MetadataValue::ChainHeight(h) => write!(f, "Chain height is {}", h),
+            MetadataValue::AccumulatedWork(d) => write!(f, "Total accumulated work is {}", d),
+            MetadataValue::PruningHorizon(h) => write!(f, "Pruning horizon is {}", h),
+            MetadataValue::PrunedHeight(height) => write!(f, "Effective pruned height is {}", height),
+            MetadataValue::BestBlock(hash) => write!(f, "Chain tip block hash is {}", hash.to_hex()),
+            MetadataValue::HorizonData(_) => write!(f, "Horizon data"),
+            MetadataValue::DeletedBitmap(deleted) => {
+                write!(f, "Deleted Bitmap ({} indexes)", deleted.bitmap().cardinality())
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct UniqueIdIndexKey {
+    inner: Vec<u8>,
+    prefix_len: usize,
+}
+
+impl UniqueIdIndexKey {
+    /// Construct a key for the unique_id_index db.
+    ///
+    /// # Arguments
+    /// `parent_public_key` - the parent asset public key to which the token is assigned
+    /// `unique_id` - a series of bytes representing the token uniquely for the asset
+    pub fn new(parent_public_key: Option<&PublicKey>, unique_id: &[u8]) -> Self {
+        let unique_id_hash = HashDigest::default().chain(unique_id).finalize();
+        Self::from_raw_parts(
+            parent_public_key.map(|p| p.as_bytes()).unwrap_or(&[0; 32][..]),
+            &unique_id_hash,
+            // u64::MAX
+            &[0xff; 8][..],
+        )
+    }
+
+    /// Convert the key to a deleted at height key
+    /// `deleted_height` - The height that the UTXO was deleted
+    pub fn set_deleted_height(&mut self, deleted_height: u64) -> &mut Self {
+        let n = self.inner.len() - mem::size_of::<u64>();
+        self.inner[n..].copy_from_slice(&deleted_height.to_be_bytes());
+        self
+    }
+
+    fn from_raw_parts(parent_public_key_bytes: &[u8], unique_id_bytes: &[u8], deleted_height_bytes: &[u8]) -> Self {
+        let prefix_len = parent_public_key_bytes.len() + unique_id_bytes.len();
+        let mut key = Vec::with_capacity(prefix_len + deleted_height_bytes.len());
+
+        key.extend_from_slice(parent_public_key_bytes);
+        key.extend_from_slice(unique_id_bytes);
+        key.extend_from_slice(deleted_height_bytes);
+
+        Self { inner: key, prefix_len }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        self.inner.as_slice()
+    }
+
+    pub fn as_prefix_bytes(&self) -> &[u8] {
+        &self.inner[..self.prefix_len][..]
+    }
+
+    pub fn to_hex(&self) -> String {
+        self.inner.to_hex()
+    }
+}
+
+impl fmt::Display for UniqueIdIndexKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_hex())
+    }
+}
+
+/// Key for the script_hash_index db: `script_hash || output_hash`. All unspent outputs locked with the same script
+/// share the script hash prefix.
+#[derive(Debug, Clone)]
+pub(crate) struct ScriptHashIndexKey {
+    inner: Vec<u8>,
+}
+
+impl ScriptHashIndexKey {
+    pub fn new(script_hash: &[u8], output_hash: &[u8]) -> Self {
+        let mut key = Vec::with_capacity(script_hash.len() + output_hash.len());
+        key.extend_from_slice(script_hash);
+        key.extend_from_slice(output_hash);
+        Self { inner: key }
+    }
+
+    pub fn from_script(script: &TariScript, output_hash: &[u8]) -> Result<Self, ChainStorageError> {
+        Ok(Self::new(&script_hash(script)?, output_hash))
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        self.inner.as_slice()
+    }
+}
+
+/// Key for the sender_offset_index db: `sender_offset_public_key || output_hash`
+#[derive(Debug, Clone)]
+pub(crate) struct SenderOffsetIndexKey {
+    inner: Vec<u8>,
+}
+
+impl SenderOffsetIndexKey {
+    pub fn new(sender_offset_public_key: &PublicKey, output_hash: &[u8]) -> Self {
+        let public_key = sender_offset_public_key.as_bytes();
+        let mut key = Vec::with_capacity(public_key.len() + output_hash.len());
+        key.extend_from_slice(public_key);
+        key.extend_from_slice(output_hash);
+        Self { inner: key }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        self.inner.as_slice()
+    }
+}
+
+/// The hash used to index outputs by script. This is the same hash the wallet uses to identify known scripts.
+pub(crate) fn script_hash(script: &TariScript) -> Result<HashOutput, ChainStorageError> {
+    let hash = script
+        .as_hash::<HashDigest>()
+        .map_err(|err| ChainStorageError::TransactionError(TransactionError::ScriptError(err)))?;
+    Ok(hash.to_vec())
+}
+
+pub(crate) struct CompositeKey {
+    key: Vec<u8>,
+}
+
+impl CompositeKey {
+    pub fn new(header_hash: &[u8], mmr_position: u32, hash: &[u8]) -> OutputKey {
+        let mut key = Vec::with_capacity(header_hash.len() + mem::size_of::<u32>() + hash.len());
+        key.extend_from_slice(header_hash);
+        key.extend_from_slice(&mmr_position.to_be_bytes());
+        key.extend_from_slice(hash);
+
+        OutputKey { key }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.key
+    }
+
+    pub fn to_hex(&self) -> String {
+        self.key.to_hex()
+    }
+}
+
+pub(crate) type InputKey = CompositeKey;
+pub(crate) type KernelKey = CompositeKey;
+pub(crate) type OutputKey = CompositeKey;
@@ -102,6 +102,11 @@ pub enum ChainStorageError {
         #[from]
         source: LMDBError,
     },
+    #[error("Sled error: {source}")]
+    SledError {
+        #[from]
+        source: sled::Error,
+    },
     #[error("Invalid proof of work: {source}")]
     ProofOfWorkError {
         #[from]
@@ -129,6 +134,8 @@ pub enum ChainStorageError {
     TransactionError(#[from] TransactionError),
     #[error("Could not convert data:{0}")]
     ConversionError(String),
+    #[error("The UTXO search indexes are disabled on this node")]
+    OutputSearchIndexDisabled,
 }
 
 impl ChainStorageError {
@@ -139,6 +146,10 @@ impl ChainStorageError {
     pub fn is_key_exist_error(&self) -> bool {
         matches!(self, ChainStorageError::KeyExists { .. })
     }
+
+    pub fn is_output_search_index_disabled(&self) -> bool {
+        matches!(self, ChainStorageError::OutputSearchIndexDisabled)
+    }
 }
 
 impl From<task::JoinError> for ChainStorageError {
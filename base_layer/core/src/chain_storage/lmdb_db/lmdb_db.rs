@@ -26,28 +26,26 @@
 #![allow(clippy::ptr_arg)]
 
 use std::{
-    fmt,
-    fmt::Formatter,
     fs,
     fs::File,
-    mem,
     ops::{Deref, Range},
     path::Path,
     sync::Arc,
     time::Instant,
 };
 
-use blake2::Digest;
 use croaring::Bitmap;
 use fs2::FileExt;
 use lmdb_zero::{open, ConstTransaction, Database, Environment, ReadTransaction, WriteTransaction};
 use log::*;
-use serde::{Deserialize, Serialize};
 use tari_common_types::{
     chain_metadata::ChainMetadata,
     types::{BlockHash, Commitment, HashDigest, HashOutput, PublicKey, Signature, BLOCK_HASH_LENGTH},
 };
-use tari_crypto::tari_utilities::{hash::Hashable, hex::Hex, ByteArray};
+use tari_crypto::{
+    script::TariScript,
+    tari_utilities::{hash::Hashable, hex::Hex, ByteArray},
+};
 use tari_mmr::{Hash, MerkleMountainRange, MutableMmr};
 use tari_storage::lmdb_store::{db, LMDBBuilder, LMDBConfig, LMDBStore};
 use tari_utilities::hex::to_hex;
@@ -64,6 +62,17 @@ use crate::{
         UpdateBlockAccumulatedData,
     },
     chain_storage::{
+        db_keys::{
+            script_hash,
+            InputKey,
+            KernelKey,
+            MetadataKey,
+            MetadataValue,
+            OutputKey,
+            ScriptHashIndexKey,
+            SenderOffsetIndexKey,
+            UniqueIdIndexKey,
+        },
         db_transaction::{DbKey, DbTransaction, DbValue, WriteOperation},
         error::{ChainStorageError, OrNotFound},
         lmdb_db::{
@@ -128,6 +137,8 @@ const LMDB_DB_UTXO_MMR_SIZE_INDEX: &str = "utxo_mmr_size_index";
 const LMDB_DB_DELETED_TXO_MMR_POSITION_TO_HEIGHT_INDEX: &str = "deleted_txo_mmr_position_to_height_index";
 const LMDB_DB_UTXO_COMMITMENT_INDEX: &str = "utxo_commitment_index";
 const LMDB_DB_UNIQUE_ID_INDEX: &str = "unique_id_index";
+const LMDB_DB_SCRIPT_HASH_INDEX: &str = "script_hash_index";
+const LMDB_DB_SENDER_OFFSET_INDEX: &str = "sender_offset_index";
 const LMDB_DB_ORPHANS: &str = "orphans";
 const LMDB_DB_MONERO_SEED_HEIGHT: &str = "monero_seed_height";
 const LMDB_DB_ORPHAN_HEADER_ACCUMULATED_DATA: &str = "orphan_accumulated_data";
@@ -136,7 +147,13 @@ const LMDB_DB_ORPHAN_PARENT_MAP_INDEX: &str = "orphan_parent_map_index";
 const LMDB_DB_BAD_BLOCK_LIST: &str = "bad_blocks";
 const LMDB_DB_REORGS: &str = "reorgs";
 
-pub fn create_lmdb_database<P: AsRef<Path>>(path: P, config: LMDBConfig) -> Result<LMDBDatabase, ChainStorageError> {
+/// Creates or opens the LMDB blockchain database at `path`. The script hash and sender offset public key indexes used
+/// to search the UTXO set are only maintained if `output_search_indexes` is true.
+pub fn create_lmdb_database<P: AsRef<Path>>(
+    path: P,
+    config: LMDBConfig,
+    output_search_indexes: bool,
+) -> Result<LMDBDatabase, ChainStorageError> {
     let flags = db::CREATE;
     debug!(target: LOG_TARGET, "Creating LMDB database at {:?}", path.as_ref());
     std::fs::create_dir_all(&path)?;
@@ -164,6 +181,8 @@ pub fn create_lmdb_database<P: AsRef<Path>>(path: P, config: LMDBConfig) -> Resu
         .add_database(LMDB_DB_UTXO_MMR_SIZE_INDEX, flags)
         .add_database(LMDB_DB_UTXO_COMMITMENT_INDEX, flags)
         .add_database(LMDB_DB_UNIQUE_ID_INDEX, flags)
+        .add_database(LMDB_DB_SCRIPT_HASH_INDEX, flags)
+        .add_database(LMDB_DB_SENDER_OFFSET_INDEX, flags)
         .add_database(LMDB_DB_DELETED_TXO_MMR_POSITION_TO_HEIGHT_INDEX, flags | db::INTEGERKEY)
         .add_database(LMDB_DB_ORPHANS, flags)
         .add_database(LMDB_DB_ORPHAN_HEADER_ACCUMULATED_DATA, flags)
@@ -175,7 +194,7 @@ pub fn create_lmdb_database<P: AsRef<Path>>(path: P, config: LMDBConfig) -> Resu
         .build()
         .map_err(|err| ChainStorageError::CriticalError(format!("Could not create LMDB store:{}", err)))?;
     debug!(target: LOG_TARGET, "LMDB database creation successful");
-    LMDBDatabase::new(lmdb_store, file_lock)
+    LMDBDatabase::new(lmdb_store, file_lock, output_search_indexes)
 }
 
 /// This is a lmdb-based blockchain database for persistent storage of the chain state.
@@ -197,6 +216,8 @@ pub struct LMDBDatabase {
     output_mmr_size_index: DatabaseRef,
     utxo_commitment_index: DatabaseRef,
     unique_id_index: DatabaseRef,
+    script_hash_index: DatabaseRef,
+    sender_offset_index: DatabaseRef,
     deleted_txo_mmr_position_to_height_index: DatabaseRef,
     orphans_db: DatabaseRef,
     monero_seed_height_db: DatabaseRef,
@@ -205,11 +226,12 @@ pub struct LMDBDatabase {
     orphan_parent_map_index: DatabaseRef,
     bad_blocks: DatabaseRef,
     reorgs: DatabaseRef,
+    output_search_indexes: bool,
     _file_lock: Arc<File>,
 }
 
 impl LMDBDatabase {
-    pub fn new(store: LMDBStore, file_lock: File) -> Result<Self, ChainStorageError> {
+    pub fn new(store: LMDBStore, file_lock: File, output_search_indexes: bool) -> Result<Self, ChainStorageError> {
         let env = store.env();
 
         let db = Self {
@@ -228,6 +250,8 @@ impl LMDBDatabase {
             output_mmr_size_index: get_database(&store, LMDB_DB_UTXO_MMR_SIZE_INDEX)?,
             utxo_commitment_index: get_database(&store, LMDB_DB_UTXO_COMMITMENT_INDEX)?,
             unique_id_index: get_database(&store, LMDB_DB_UNIQUE_ID_INDEX)?,
+            script_hash_index: get_database(&store, LMDB_DB_SCRIPT_HASH_INDEX)?,
+            sender_offset_index: get_database(&store, LMDB_DB_SENDER_OFFSET_INDEX)?,
             deleted_txo_mmr_position_to_height_index: get_database(
                 &store,
                 LMDB_DB_DELETED_TXO_MMR_POSITION_TO_HEIGHT_INDEX,
@@ -239,14 +263,134 @@ impl LMDBDatabase {
             orphan_parent_map_index: get_database(&store, LMDB_DB_ORPHAN_PARENT_MAP_INDEX)?,
             bad_blocks: get_database(&store, LMDB_DB_BAD_BLOCK_LIST)?,
             reorgs: get_database(&store, LMDB_DB_REORGS)?,
+            output_search_indexes,
             env,
             env_config: store.env_config(),
             _file_lock: Arc::new(file_lock),
         };
 
+        if db.output_search_indexes {
+            db.build_output_indexes_if_required()?;
+        } else {
+            db.clear_output_indexes()?;
+        }
+
         Ok(db)
     }
 
+    /// The script hash and sender offset indexes are optional. Databases created before they were added, or opened
+    /// while they were disabled, have empty indexes, so they are populated from the current UTXO set once.
+    fn build_output_indexes_if_required(&self) -> Result<(), ChainStorageError> {
+        let txn = self.write_transaction()?;
+        if lmdb_len(&txn, &self.utxo_commitment_index)? == 0 {
+            return Ok(());
+        }
+        let build_script_index = lmdb_len(&txn, &self.script_hash_index)? == 0;
+        let build_sender_offset_index = lmdb_len(&txn, &self.sender_offset_index)? == 0;
+        if !build_script_index && !build_sender_offset_index {
+            return Ok(());
+        }
+        let output_hashes: Vec<HashOutput> = lmdb_filter_map_values(&txn, &self.utxo_commitment_index, Some)?;
+        info!(
+            target: LOG_TARGET,
+            "Building output indexes for {} unspent output(s)",
+            output_hashes.len()
+        );
+        for output_hash in output_hashes {
+            if let Some(UtxoMinedInfo {
+                output: PrunedOutput::NotPruned { output },
+                ..
+            }) = self.fetch_output_in_txn(&txn, &output_hash)?
+            {
+                if build_script_index {
+                    lmdb_insert(
+                        &txn,
+                        &self.script_hash_index,
+                        ScriptHashIndexKey::from_script(&output.script, &output_hash)?.as_bytes(),
+                        &output_hash,
+                        "script_hash_index",
+                    )?;
+                }
+                if build_sender_offset_index {
+                    lmdb_insert(
+                        &txn,
+                        &self.sender_offset_index,
+                        SenderOffsetIndexKey::new(&output.sender_offset_public_key, &output_hash).as_bytes(),
+                        &output_hash,
+                        "sender_offset_index",
+                    )?;
+                }
+            }
+        }
+        txn.commit()?;
+        Ok(())
+    }
+
+    /// Removes the script hash and sender offset indexes. They are not updated while disabled, so they would be stale
+    /// if they were enabled again later. Clearing them ensures that they are rebuilt.
+    fn clear_output_indexes(&self) -> Result<(), ChainStorageError> {
+        let txn = self.write_transaction()?;
+        let num_deleted = lmdb_clear(&txn, &self.script_hash_index)? + lmdb_clear(&txn, &self.sender_offset_index)?;
+        txn.commit()?;
+        if num_deleted > 0 {
+            info!(
+                target: LOG_TARGET,
+                "Cleared {} disabled output index entries", num_deleted
+            );
+        }
+        Ok(())
+    }
+
+    fn insert_output_search_indexes(
+        &self,
+        txn: &WriteTransaction<'_>,
+        script: &TariScript,
+        sender_offset_public_key: &PublicKey,
+        output_hash: &HashOutput,
+    ) -> Result<(), ChainStorageError> {
+        if !self.output_search_indexes {
+            return Ok(());
+        }
+        lmdb_insert(
+            txn,
+            &*self.script_hash_index,
+            ScriptHashIndexKey::from_script(script, output_hash)?.as_bytes(),
+            output_hash,
+            "script_hash_index",
+        )?;
+        lmdb_insert(
+            txn,
+            &*self.sender_offset_index,
+            SenderOffsetIndexKey::new(sender_offset_public_key, output_hash).as_bytes(),
+            output_hash,
+            "sender_offset_index",
+        )
+    }
+
+    fn delete_output_search_indexes(
+        &self,
+        txn: &WriteTransaction<'_>,
+        script: &TariScript,
+        sender_offset_public_key: &PublicKey,
+        output_hash: &HashOutput,
+    ) -> Result<(), ChainStorageError> {
+        if !self.output_search_indexes {
+            return Ok(());
+        }
+        lmdb_delete(
+            txn,
+            &*self.script_hash_index,
+            ScriptHashIndexKey::from_script(script, output_hash)?.as_bytes(),
+            "script_hash_index",
+        )?;
+        lmdb_delete(
+            txn,
+            &*self.sender_offset_index,
+            SenderOffsetIndexKey::new(sender_offset_public_key, output_hash).as_bytes(),
+            "sender_offset_index",
+        )
+    }
+
     /// Try to establish a read lock on the LMDB database. If an exclusive write lock has been previously acquired, this
     /// method will block until that lock is released.
     fn read_transaction(&self) -> Result<ReadTransaction<'_>, ChainStorageError> {
@@ -428,7 +572,7 @@ impl LMDBDatabase {
         Ok(())
     }
 
-    fn all_dbs(&self) -> [(&'static str, &DatabaseRef); 23] {
+    fn all_dbs(&self) -> [(&'static str, &DatabaseRef); 25] {
         [
             ("metadata_db", &self.metadata_db),
             ("headers_db", &self.headers_db),
@@ -445,6 +589,8 @@ impl LMDBDatabase {
             ("output_mmr_size_index", &self.output_mmr_size_index),
             ("utxo_commitment_index", &self.utxo_commitment_index),
             ("unique_id_index", &self.unique_id_index),
+            ("script_hash_index", &self.script_hash_index),
+            ("sender_offset_index", &self.sender_offset_index),
             (
                 "deleted_txo_mmr_position_to_height_index",
                 &self.deleted_txo_mmr_position_to_height_index,
@@ -481,6 +627,22 @@ impl LMDBDatabase {
             })?;
         // output.output is None
         lmdb_replace(txn, &self.utxos_db, key.as_bytes(), &output)?;
+        // Pruned outputs are spent and should already have been removed from the script and sender offset indexes
+        if self.output_search_indexes {
+            let script_key = ScriptHashIndexKey::from_script(&pruned_output.script, &output.hash)?;
+            if lmdb_exists(txn, &self.script_hash_index, script_key.as_bytes())? {
+                lmdb_delete(txn, &self.script_hash_index, script_key.as_bytes(), "script_hash_index")?;
+            }
+            let sender_offset_key = SenderOffsetIndexKey::new(&pruned_output.sender_offset_public_key, &output.hash);
+            if lmdb_exists(txn, &self.sender_offset_index, sender_offset_key.as_bytes())? {
+                lmdb_delete(
+                    txn,
+                    &self.sender_offset_index,
+                    sender_offset_key.as_bytes(),
+                    "sender_offset_index",
+                )?;
+            }
+        }
         Ok(pruned_output)
     }
 
@@ -527,6 +689,8 @@ impl LMDBDatabase {
             )?;
         }
 
+        self.insert_output_search_indexes(txn, &output.script, &output.sender_offset_public_key, &output_hash)?;
+
         lmdb_insert(
             txn,
             &*self.txos_hash_to_index_db,
@@ -653,6 +817,17 @@ impl LMDBDatabase {
             ChainStorageError::ValueNotFound { .. } => Ok(()),
             _ => Err(err),
         })?;
+        self.delete_output_search_indexes(
+            txn,
+            input.script()?,
+            input.sender_offset_public_key()?,
+            &input.output_hash(),
+        )
+        .or_else(|err| match err {
+            // As above, a 0-conf output is only indexed once the block's outputs are inserted
+            ChainStorageError::ValueNotFound { .. } => Ok(()),
+            _ => Err(err),
+        })?;
         lmdb_insert(
             txn,
             &self.deleted_txo_mmr_position_to_height_index,
@@ -980,6 +1155,7 @@ impl LMDBDatabase {
                     output.commitment.as_bytes(),
                     "utxo_commitment_index",
                 )?;
+                self.delete_output_search_indexes(txn, &output.script, &output.sender_offset_public_key, &output_hash)?;
                 if let Some(unique_id) = output.features.unique_asset_id() {
                     let key = UniqueIdIndexKey::new(output.features.parent_public_key.as_ref(), unique_id);
                     lmdb_delete(txn, &self.unique_id_index, key.as_bytes(), "unique_id_index")?;
@@ -1031,6 +1207,7 @@ impl LMDBDatabase {
                 &input.output_hash(),
                 "utxo_commitment_index",
             )?;
+            self.insert_output_search_indexes(txn, input.script()?, input.sender_offset_public_key()?, &output_hash)?;
             lmdb_delete(
                 txn,
                 &self.deleted_txo_mmr_position_to_height_index,
@@ -1219,7 +1396,7 @@ impl LMDBDatabase {
             })
             .collect::<Result<Vec<_>, ChainStorageError>>()?;
 
-        let mut spent_zero_conf_commitments = Vec::new();
+        let mut spent_zero_conf_inputs = Vec::new();
         // unique_id_index expects inputs to be inserted before outputs
         for input in &inputs {
             let output_hash = input.output_hash();
@@ -1231,7 +1408,7 @@ impl LMDBDatabase {
                             target: LOG_TARGET,
                             "Input {} spends output from current block (0-conf)", input
                         );
-                        spent_zero_conf_commitments.push(input.commitment()?);
+                        spent_zero_conf_inputs.push(input);
                         index
                     },
                     None => return Err(ChainStorageError::UnspendableInput),
@@ -1252,13 +1429,19 @@ impl LMDBDatabase {
             self.insert_output(txn, &block_hash, header.height, &output, mmr_count as u32 - 1)?;
         }
 
-        for commitment in spent_zero_conf_commitments {
+        for input in spent_zero_conf_inputs {
             lmdb_delete(
                 txn,
                 &self.utxo_commitment_index,
-                commitment.as_bytes(),
+                input.commitment()?.as_bytes(),
                 "utxo_commitment_index",
             )?;
+            self.delete_output_search_indexes(
+                txn,
+                input.script()?,
+                input.sender_offset_public_key()?,
+                &input.output_hash(),
+            )?;
         }
         // Merge current deletions with the tip bitmap
         let deleted_at_current_height = output_mmr.deleted().clone();
@@ -1540,7 +1723,7 @@ pub fn create_recovery_lmdb_database<P: AsRef<Path>>(path: P) -> Result<(), Chai
     Ok(())
 }
 
-fn acquire_exclusive_file_lock(db_path: &Path) -> Result<File, ChainStorageError> {
+pub(crate) fn acquire_exclusive_file_lock(db_path: &Path) -> Result<File, ChainStorageError> {
     let lock_file_path = db_path.join(".chain_storage_file.lock");
 
     let file = File::create(lock_file_path)?;
@@ -1995,6 +2178,45 @@ impl BlockchainBackend for LMDBDatabase {
         Ok(result)
     }
 
+    fn fetch_unspent_outputs_by_script(
+        &self,
+        script: &TariScript,
+        range: Range<usize>,
+    ) -> Result<Vec<UtxoMinedInfo>, ChainStorageError> {
+        if !self.output_search_indexes {
+            return Err(ChainStorageError::OutputSearchIndexDisabled);
+        }
+        let txn = self.read_transaction()?;
+        let values: Vec<HashOutput> = lmdb_fetch_matching_after(&txn, &self.script_hash_index, &script_hash(script)?)?;
+        let mut result = Vec::with_capacity(range.len().min(values.len()));
+        for hash in values.into_iter().skip(range.start).take(range.len()) {
+            if let Some(s) = self.fetch_output_in_txn(&txn, &hash)? {
+                result.push(s);
+            }
+        }
+        Ok(result)
+    }
+
+    fn fetch_unspent_outputs_by_sender_offset_public_key(
+        &self,
+        sender_offset_public_key: &PublicKey,
+        range: Range<usize>,
+    ) -> Result<Vec<UtxoMinedInfo>, ChainStorageError> {
+        if !self.output_search_indexes {
+            return Err(ChainStorageError::OutputSearchIndexDisabled);
+        }
+        let txn = self.read_transaction()?;
+        let values: Vec<HashOutput> =
+            lmdb_fetch_matching_after(&txn, &self.sender_offset_index, sender_offset_public_key.as_bytes())?;
+        let mut result = Vec::with_capacity(range.len().min(values.len()));
+        for hash in values.into_iter().skip(range.start).take(range.len()) {
+            if let Some(s) = self.fetch_output_in_txn(&txn, &hash)? {
+                result.push(s);
+            }
+        }
+        Ok(result)
+    }
+
     fn fetch_outputs_in_block(&self, header_hash: &HashOutput) -> Result<Vec<PrunedOutput>, ChainStorageError> {
         let txn = self.read_transaction()?;
         Ok(lmdb_fetch_matching_after(&txn, &self.utxos_db, header_hash)?
@@ -2445,126 +2667,6 @@ fn get_database(store: &LMDBStore, name: &str) -> Result<DatabaseRef, ChainStora
     Ok(handle.db())
 }
 
-#[derive(Debug, Clone, PartialEq, Copy)]
-enum MetadataKey {
-    ChainHeight,
-    BestBlock,
-    AccumulatedWork,
-    PruningHorizon,
-    PrunedHeight,
-    HorizonData,
-    DeletedBitmap,
-}
-
-impl MetadataKey {
-    #[inline]
-    pub fn as_u32(self) -> u32 {
-        self as u32
-    }
-}
-
-impl fmt::Display for MetadataKey {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            MetadataKey::ChainHeight => f.write_str("Current chain height"),
-            MetadataKey::AccumulatedWork => f.write_str("Total accumulated work"),
-            MetadataKey::PruningHorizon => f.write_str("Pruning horizon"),
-            MetadataKey::PrunedHeight => f.write_str("Effective pruned height"),
-            MetadataKey::BestBlock => f.write_str("Chain tip block hash"),
-            MetadataKey::HorizonData => f.write_str("Database info"),
-            MetadataKey::DeletedBitmap => f.write_str("Deleted bitmap"),
-        }
-    }
-}
-
-#[allow(clippy::large_enum_variant)]
-#[derive(Debug, Clone, Deserialize, Serialize)]
-enum MetadataValue {
-    ChainHeight(u64),
-    BestBlock(BlockHash),
-    AccumulatedWork(u128),
-    PruningHorizon(u64),
-    PrunedHeight(u64),
-    HorizonData(HorizonData),
-    DeletedBitmap(DeletedBitmap),
-}
-
-impl fmt::Display for MetadataValue {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            MetadataValue::ChainHeight(h) => write!(f, "Chain height is {}", h),
-            MetadataValue::AccumulatedWork(d) => write!(f, "Total accumulated work is {}", d),
-            MetadataValue::PruningHorizon(h) => write!(f, "Pruning horizon is {}", h),
-            MetadataValue::PrunedHeight(height) => write!(f, "Effective pruned height is {}", height),
-            MetadataValue::BestBlock(hash) => write!(f, "Chain tip block hash is {}", hash.to_hex()),
-            MetadataValue::HorizonData(_) => write!(f, "Horizon data"),
-            MetadataValue::DeletedBitmap(deleted) => {
-                write!(f, "Deleted Bitmap ({} indexes)", deleted.bitmap().cardinality())
-            },
-        }
-    }
-}
-
-#[derive(Debug, Clone)]
-struct UniqueIdIndexKey {
-    inner: Vec<u8>,
-    prefix_len: usize,
-}
-
-impl UniqueIdIndexKey {
-    /// Construct a key for the unique_id_index db.
-    ///
-    /// # Arguments
-    /// `parent_public_key` - the parent asset public key to which the token is assigned
-    /// `unique_id` - a series of bytes representing the token uniquely for the asset
-    pub fn new(parent_public_key: Option<&PublicKey>, unique_id: &[u8]) -> Self {
-        let unique_id_hash = HashDigest::default().chain(unique_id).finalize();
-        Self::from_raw_parts(
-            parent_public_key.map(|p| p.as_bytes()).unwrap_or(&[0; 32][..]),
-            &unique_id_hash,
-            // u64::MAX
-            &[0xff; 8][..],
-        )
-    }
-
-    /// Convert the key to a deleted at height key
-    /// `deleted_height` - The height that the UTXO was deleted
-    pub fn set_deleted_height(&mut self, deleted_height: u64) -> &mut Self {
-        let n = self.inner.len() - mem::size_of::<u64>();
-        self.inner[n..].copy_from_slice(&deleted_height.to_be_bytes());
-        self
-    }
-
-    fn from_raw_parts(parent_public_key_bytes: &[u8], unique_id_bytes: &[u8], deleted_height_bytes: &[u8]) -> Self {
-        let prefix_len = parent_public_key_bytes.len() + unique_id_bytes.len();
-        let mut key = Vec::with_capacity(prefix_len + deleted_height_bytes.len());
-
-        key.extend_from_slice(parent_public_key_bytes);
-        key.extend_from_slice(unique_id_bytes);
-        key.extend_from_slice(deleted_height_bytes);
-
-        Self { inner: key, prefix_len }
-    }
-
-    pub fn as_bytes(&self) -> &[u8] {
-        self.inner.as_slice()
-    }
-
-    pub fn as_prefix_bytes(&self) -> &[u8] {
-        &self.inner[..self.prefix_len][..]
-    }
-
-    pub fn to_hex(&self) -> String {
-        self.inner.to_hex()
-    }
-}
-
-impl fmt::Display for UniqueIdIndexKey {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.to_hex())
-    }
-}
-
 /// A struct that wraps a LMDB transaction and provides an interface to valid operations that can be performed
 /// on the current deleted bitmap state of the blockchain.
 /// A deleted bitmap contains the MMR leaf indexes of spent TXOs.
@@ -2627,30 +2729,3 @@ impl<'a, 'b> DeletedBitmapModel<'a, WriteTransaction<'b>> {
         Ok(())
     }
 }
-
-struct CompositeKey {
-    key: Vec<u8>,
-}
-
-impl CompositeKey {
-    pub fn new(header_hash: &[u8], mmr_position: u32, hash: &[u8]) -> OutputKey {
-        let mut key = Vec::with_capacity(header_hash.len() + mem::size_of::<u32>() + hash.len());
-        key.extend_from_slice(header_hash);
-        key.extend_from_slice(&mmr_position.to_be_bytes());
-        key.extend_from_slice(hash);
-
-        OutputKey { key }
-    }
-
-    pub fn as_bytes(&self) -> &[u8] {
-        &self.key
-    }
-
-    pub fn to_hex(&self) -> String {
-        self.key.to_hex()
-    }
-}
-
-type InputKey = CompositeKey;
-type KernelKey = CompositeKey;
-type OutputKey = CompositeKey;
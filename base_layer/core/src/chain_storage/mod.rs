@@ -51,6 +51,8 @@ pub use blockchain_backend::BlockchainBackend;
 
 mod consts;
 
+mod db_keys;
+
 mod db_transaction;
 pub use db_transaction::{DbKey, DbTransaction, DbValue, WriteOperation};
 
@@ -72,8 +74,11 @@ pub use reorg::Reorg;
 mod lmdb_db;
 pub use lmdb_db::{create_lmdb_database, create_recovery_lmdb_database, LMDBDatabase};
 
+mod sled_db;
+pub use sled_db::{create_sled_database, SledConfig, SledDatabase};
+
 mod stats;
-pub use stats::{DbBasicStats, DbSize, DbStat, DbTotalSizeStats};
+pub use stats::{DbBasicStats, DbPageStats, DbSize, DbStat, DbTotalSizeStats};
 
 mod target_difficulties;
 mod utxo_mined_info;
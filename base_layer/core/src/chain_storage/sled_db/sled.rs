@@ -0,0 +1,573 @@
+//  Copyright 2022, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{cell::RefCell, cmp::Ordering, collections::BTreeMap, convert::TryInto, iter::Peekable, ops::Bound, vec};
+
+use log::*;
+use serde::{de::DeserializeOwned, Serialize};
+use sled::{
+    transaction::{TransactionError, TransactionResult},
+    IVec,
+    Transactional,
+    Tree,
+};
+use tari_crypto::tari_utilities::hex::to_hex;
+
+use crate::chain_storage::ChainStorageError;
+
+pub const LOG_TARGET: &str = "c::cs::sled_db::sled";
+
+type PendingWrites = BTreeMap<Vec<u8>, Option<Vec<u8>>>;
+type BaseIter = Box<dyn Iterator<Item = sled::Result<(IVec, IVec)>>>;
+type PendingWrite = (Vec<u8>, Option<Vec<u8>>);
+
+/// A read/write view over a set of sled trees.
+///
+/// sled only supports point reads inside multi-tree transactions, but the chain storage logic needs range scans
+/// that observe its own uncommitted writes. All writes are therefore buffered here and every read consults the
+/// buffer before falling through to the trees. `commit` applies the buffered writes to all affected trees in a
+/// single atomic sled transaction and keeps the entry counts in the `lengths` tree up to date.
+pub struct SledTransaction<'a> {
+    lengths: &'a Tree,
+    pending: RefCell<BTreeMap<IVec, (Tree, PendingWrites)>>,
+}
+
+impl<'a> SledTransaction<'a> {
+    pub fn new(lengths: &'a Tree) -> Self {
+        Self {
+            lengths,
+            pending: RefCell::new(BTreeMap::new()),
+        }
+    }
+
+    fn get(&self, tree: &Tree, key: &[u8]) -> Result<Option<IVec>, ChainStorageError> {
+        if let Some((_, writes)) = self.pending.borrow().get(&tree.name()) {
+            if let Some(value) = writes.get(key) {
+                return Ok(value.as_deref().map(IVec::from));
+            }
+        }
+        Ok(tree.get(key)?)
+    }
+
+    fn put(&self, tree: &Tree, key: &[u8], value: Option<Vec<u8>>) {
+        self.pending
+            .borrow_mut()
+            .entry(tree.name())
+            .or_insert_with(|| (tree.clone(), BTreeMap::new()))
+            .1
+            .insert(key.to_vec(), value);
+    }
+
+    fn len(&self, tree: &Tree) -> Result<usize, ChainStorageError> {
+        let mut len = self.lengths.get(tree.name())?.map(|v| decode_len(&v)).unwrap_or(0) as i64;
+        if let Some((_, writes)) = self.pending.borrow().get(&tree.name()) {
+            for (key, value) in writes {
+                len += i64::from(value.is_some()) - i64::from(tree.contains_key(key)?);
+            }
+        }
+        Ok(len.max(0) as usize)
+    }
+
+    /// Iterate over all entries with a key greater than or equal to `start` in ascending key order
+    fn iter_from(&self, tree: &Tree, start: &[u8]) -> MergedIter {
+        let pending = self
+            .pending
+            .borrow()
+            .get(&tree.name())
+            .map(|(_, writes)| {
+                writes
+                    .range::<[u8], _>((Bound::Included(start), Bound::Unbounded))
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        MergedIter::new(Box::new(tree.range(start..)), pending, false)
+    }
+
+    /// Iterate over all entries with a key less than or equal to `end` (or all entries if `end` is None) in descending
+    /// key order
+    fn iter_rev_to(&self, tree: &Tree, end: Option<&[u8]>) -> MergedIter {
+        let upper = end.map(Bound::Included).unwrap_or(Bound::Unbounded);
+        let pending = self
+            .pending
+            .borrow()
+            .get(&tree.name())
+            .map(|(_, writes)| {
+                writes
+                    .range::<[u8], _>((Bound::Unbounded, upper))
+                    .rev()
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let base: BaseIter = match end {
+            Some(end) => Box::new(tree.range(..=end).rev()),
+            None => Box::new(tree.iter().rev()),
+        };
+        MergedIter::new(base, pending, true)
+    }
+
+    /// Atomically apply all buffered writes. If this fails, none of the writes are applied.
+    pub fn commit(self) -> Result<(), ChainStorageError> {
+        let pending = self.pending.into_inner();
+        if pending.is_empty() {
+            return Ok(());
+        }
+        let (mut trees, writes): (Vec<_>, Vec<_>) = pending.into_iter().map(|(_, entry)| entry).unzip();
+        trees.push(self.lengths.clone());
+
+        let result: TransactionResult<(), ()> = trees.as_slice().transaction(|tx_trees| {
+            let (lengths, tx_trees) = tx_trees.split_last().expect("lengths tree is always included");
+            for ((tx_tree, writes), tree) in tx_trees.iter().zip(&writes).zip(&trees) {
+                let mut delta = 0i64;
+                for (key, value) in writes {
+                    let existed = match value {
+                        Some(value) => tx_tree.insert(key.as_slice(), value.as_slice())?.is_some(),
+                        None => tx_tree.remove(key.as_slice())?.is_some(),
+                    };
+                    delta += i64::from(value.is_some()) - i64::from(existed);
+                }
+                if delta != 0 {
+                    let current = lengths.get(tree.name())?.map(|v| decode_len(&v)).unwrap_or(0) as i64;
+                    let new_len = (current + delta).max(0) as u64;
+                    lengths.insert(tree.name(), &new_len.to_be_bytes()[..])?;
+                }
+            }
+            Ok(())
+        });
+
+        result.map_err(|err| match err {
+            TransactionError::Abort(()) => {
+                ChainStorageError::AccessError("sled transaction was unexpectedly aborted".to_string())
+            },
+            TransactionError::Storage(err) => err.into(),
+        })
+    }
+}
+
+fn decode_len(bytes: &[u8]) -> u64 {
+    bytes.try_into().map(u64::from_be_bytes).unwrap_or(0)
+}
+
+/// Merges an iterator over committed tree entries with the (identically ordered) pending writes. Pending writes
+/// shadow committed entries with the same key, and pending deletes remove them.
+pub struct MergedIter {
+    base: Peekable<BaseIter>,
+    pending: Peekable<vec::IntoIter<PendingWrite>>,
+    reverse: bool,
+}
+
+impl MergedIter {
+    fn new(base: BaseIter, pending: Vec<PendingWrite>, reverse: bool) -> Self {
+        Self {
+            base: base.peekable(),
+            pending: pending.into_iter().peekable(),
+            reverse,
+        }
+    }
+}
+
+impl Iterator for MergedIter {
+    type Item = Result<(IVec, IVec), ChainStorageError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let ordering = match (self.base.peek(), self.pending.peek()) {
+                (None, None) => return None,
+                (Some(Err(_)), _) | (Some(Ok(_)), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(Ok((base_key, _))), Some((pending_key, _))) => {
+                    let ordering = base_key.as_ref().cmp(pending_key.as_slice());
+                    if self.reverse {
+                        ordering.reverse()
+                    } else {
+                        ordering
+                    }
+                },
+            };
+
+            match ordering {
+                Ordering::Less => return self.base.next().map(|r| r.map_err(Into::into)),
+                Ordering::Equal | Ordering::Greater => {
+                    if ordering == Ordering::Equal {
+                        // The pending write shadows the committed value
+                        let _ = self.base.next();
+                    }
+                    let (key, value) = self.pending.next()?;
+                    if let Some(value) = value {
+                        return Some(Ok((IVec::from(key), IVec::from(value))));
+                    }
+                },
+            }
+        }
+    }
+}
+
+pub fn serialize<T>(data: &T) -> Result<Vec<u8>, ChainStorageError>
+where T: Serialize {
+    bincode::serialize(data).map_err(|e| {
+        error!(target: LOG_TARGET, "Could not serialize sled value: {:?}", e);
+        ChainStorageError::AccessError(e.to_string())
+    })
+}
+
+pub fn deserialize<T>(buf_bytes: &[u8]) -> Result<T, ChainStorageError>
+where T: DeserializeOwned {
+    bincode::deserialize(buf_bytes).map_err(|e| {
+        error!(target: LOG_TARGET, "Could not deserialize sled value: {:?}", e);
+        ChainStorageError::AccessError(e.to_string())
+    })
+}
+
+/// Inserts a new value. An error is returned if the key already exists.
+pub fn sled_insert<V>(
+    txn: &SledTransaction<'_>,
+    tree: &Tree,
+    key: &[u8],
+    val: &V,
+    table_name: &'static str,
+) -> Result<(), ChainStorageError>
+where
+    V: Serialize,
+{
+    if txn.get(tree, key)?.is_some() {
+        return Err(ChainStorageError::KeyExists {
+            table_name,
+            key: to_hex(key),
+        });
+    }
+    let val_buf = serialize(val)?;
+    trace!(target: LOG_TARGET, "sled: {} bytes inserted", val_buf.len());
+    txn.put(tree, key, Some(val_buf));
+    Ok(())
+}
+
+/// Inserts or replaces the item at the given key. If the key does not exist, a new entry is created
+pub fn sled_replace<V>(txn: &SledTransaction<'_>, tree: &Tree, key: &[u8], val: &V) -> Result<(), ChainStorageError>
+where V: Serialize {
+    let val_buf = serialize(val)?;
+    txn.put(tree, key, Some(val_buf));
+    Ok(())
+}
+
+/// Deletes the given key. An error is returned if the key does not exist
+pub fn sled_delete(
+    txn: &SledTransaction<'_>,
+    tree: &Tree,
+    key: &[u8],
+    table_name: &'static str,
+) -> Result<(), ChainStorageError> {
+    if txn.get(tree, key)?.is_none() {
+        return Err(ChainStorageError::ValueNotFound {
+            entity: table_name,
+            field: "<unknown>",
+            value: to_hex(key),
+        });
+    }
+    txn.put(tree, key, None);
+    Ok(())
+}
+
+/// sled has no native duplicate key support, so duplicate values are stored under `key || value` and retrieved with a
+/// prefix scan. This requires all keys in the tree to be the same length.
+pub fn sled_insert_dup<V>(
+    txn: &SledTransaction<'_>,
+    tree: &Tree,
+    key: &[u8],
+    val: &V,
+) -> Result<(), ChainStorageError>
+where
+    V: Serialize,
+{
+    let val_buf = serialize(val)?;
+    let mut dup_key = Vec::with_capacity(key.len() + val_buf.len());
+    dup_key.extend_from_slice(key);
+    dup_key.extend_from_slice(&val_buf);
+    txn.put(tree, &dup_key, Some(val_buf));
+    Ok(())
+}
+
+/// Removes a single value previously added with `sled_insert_dup`
+pub fn sled_delete_key_value<V>(
+    txn: &SledTransaction<'_>,
+    tree: &Tree,
+    key: &[u8],
+    value: &V,
+) -> Result<(), ChainStorageError>
+where
+    V: Serialize,
+{
+    let mut dup_key = key.to_vec();
+    dup_key.extend_from_slice(&serialize(value)?);
+    txn.put(tree, &dup_key, None);
+    Ok(())
+}
+
+/// Returns all values added with `sled_insert_dup` for the given key
+pub fn sled_get_multiple<V>(txn: &SledTransaction<'_>, tree: &Tree, key: &[u8]) -> Result<Vec<V>, ChainStorageError>
+where V: DeserializeOwned {
+    sled_fetch_matching_after(txn, tree, key)
+}
+
+pub fn sled_get<V>(txn: &SledTransaction<'_>, tree: &Tree, key: &[u8]) -> Result<Option<V>, ChainStorageError>
+where V: DeserializeOwned {
+    match txn.get(tree, key)? {
+        Some(v) => deserialize(&v).map(Some),
+        None => Ok(None),
+    }
+}
+
+pub fn sled_exists(txn: &SledTransaction<'_>, tree: &Tree, key: &[u8]) -> Result<bool, ChainStorageError> {
+    Ok(txn.get(tree, key)?.is_some())
+}
+
+pub fn sled_len(txn: &SledTransaction<'_>, tree: &Tree) -> Result<usize, ChainStorageError> {
+    txn.len(tree)
+}
+
+/// Returns the value with the greatest key in the tree
+pub fn sled_last<V>(txn: &SledTransaction<'_>, tree: &Tree) -> Result<Option<V>, ChainStorageError>
+where V: DeserializeOwned {
+    match txn.iter_rev_to(tree, None).next() {
+        Some(row) => deserialize(&row?.1).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Returns the first value with a key greater than or equal to the given key
+pub fn sled_first_after<V>(txn: &SledTransaction<'_>, tree: &Tree, key: &[u8]) -> Result<Option<V>, ChainStorageError>
+where V: DeserializeOwned {
+    match txn.iter_from(tree, key).next() {
+        Some(row) => deserialize(&row?.1).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Returns the entry with the greatest key that is less than or equal to `key` and starts with `prefix`
+pub fn sled_last_at_or_before<V>(
+    txn: &SledTransaction<'_>,
+    tree: &Tree,
+    prefix: &[u8],
+    key: &[u8],
+) -> Result<Option<(Vec<u8>, V)>, ChainStorageError>
+where
+    V: DeserializeOwned,
+{
+    match txn.iter_rev_to(tree, Some(key)).next() {
+        Some(row) => {
+            let (k, v) = row?;
+            if k.starts_with(prefix) {
+                Ok(Some((k.to_vec(), deserialize(&v)?)))
+            } else {
+                Ok(None)
+            }
+        },
+        None => Ok(None),
+    }
+}
+
+/// Returns all values whose keys start with the given prefix, in key order
+pub fn sled_fetch_matching_after<V>(
+    txn: &SledTransaction<'_>,
+    tree: &Tree,
+    key_prefix: &[u8],
+) -> Result<Vec<V>, ChainStorageError>
+where
+    V: DeserializeOwned,
+{
+    let mut result = vec![];
+    for row in txn.iter_from(tree, key_prefix) {
+        let (k, v) = row?;
+        if !k.starts_with(key_prefix) {
+            break;
+        }
+        result.push(deserialize(&v)?);
+    }
+    Ok(result)
+}
+
+pub fn sled_delete_keys_starting_with<V>(
+    txn: &SledTransaction<'_>,
+    tree: &Tree,
+    key_prefix: &[u8],
+) -> Result<Vec<V>, ChainStorageError>
+where
+    V: DeserializeOwned,
+{
+    let mut rows = vec![];
+    for row in txn.iter_from(tree, key_prefix) {
+        let (k, v) = row?;
+        if !k.starts_with(key_prefix) {
+            break;
+        }
+        rows.push((k, v));
+    }
+    let mut result = Vec::with_capacity(rows.len());
+    for (k, v) in rows {
+        result.push(deserialize(&v)?);
+        txn.put(tree, &k, None);
+    }
+    Ok(result)
+}
+
+pub fn sled_filter_map_values<F, V, R>(
+    txn: &SledTransaction<'_>,
+    tree: &Tree,
+    f: F,
+) -> Result<Vec<R>, ChainStorageError>
+where
+    F: Fn(V) -> Option<R>,
+    V: DeserializeOwned,
+{
+    let mut result = vec![];
+    for row in txn.iter_from(tree, &[]) {
+        let val = deserialize::<V>(&row?.1)?;
+        if let Some(r) = f(val) {
+            result.push(r);
+        }
+    }
+    Ok(result)
+}
+
+/// Deletes every entry for which the predicate returns `Some(true)`. Iteration stops when the predicate returns None.
+pub fn sled_delete_each_where<V, F>(
+    txn: &SledTransaction<'_>,
+    tree: &Tree,
+    mut predicate: F,
+) -> Result<usize, ChainStorageError>
+where
+    V: DeserializeOwned,
+    F: FnMut(&[u8], V) -> Option<bool>,
+{
+    let mut to_delete = vec![];
+    for row in txn.iter_from(tree, &[]) {
+        let (k, v) = row?;
+        match predicate(&k, deserialize(&v)?) {
+            Some(true) => to_delete.push(k),
+            Some(false) => continue,
+            None => break,
+        }
+    }
+    let num_deleted = to_delete.len();
+    for k in to_delete {
+        txn.put(tree, &k, None);
+    }
+    Ok(num_deleted)
+}
+
+pub fn sled_clear(txn: &SledTransaction<'_>, tree: &Tree) -> Result<usize, ChainStorageError> {
+    let keys = txn
+        .iter_from(tree, &[])
+        .map(|row| row.map(|(k, _)| k))
+        .collect::<Result<Vec<_>, _>>()?;
+    let num_deleted = keys.len();
+    for k in keys {
+        txn.put(tree, &k, None);
+    }
+    Ok(num_deleted)
+}
+
+/// Fetches the size of all key/values in the given tree. Returns the number of entries, the total size of all the
+/// keys and values in bytes.
+pub fn fetch_tree_entry_sizes(tree: &Tree) -> Result<(u64, u64, u64), ChainStorageError> {
+    let mut num_entries = 0;
+    let mut total_key_size = 0;
+    let mut total_value_size = 0;
+    for row in tree.iter() {
+        let (key, value) = row?;
+        num_entries += 1;
+        total_key_size += key.len() as u64;
+        total_value_size += value.len() as u64;
+    }
+    Ok((num_entries, total_key_size, total_value_size))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn open_temp() -> (sled::Db, Tree, Tree) {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        let lengths = db.open_tree("lengths").unwrap();
+        let tree = db.open_tree("test").unwrap();
+        (db, lengths, tree)
+    }
+
+    #[test]
+    fn it_reads_uncommitted_writes() {
+        let (_db, lengths, tree) = open_temp();
+        let txn = SledTransaction::new(&lengths);
+        sled_insert(&txn, &tree, &1u64.to_be_bytes(), &"a", "test").unwrap();
+        sled_insert(&txn, &tree, &2u64.to_be_bytes(), &"b", "test").unwrap();
+        assert_eq!(
+            sled_get::<String>(&txn, &tree, &1u64.to_be_bytes()).unwrap().unwrap(),
+            "a"
+        );
+        assert_eq!(sled_last::<String>(&txn, &tree).unwrap().unwrap(), "b");
+        assert_eq!(sled_len(&txn, &tree).unwrap(), 2);
+        assert!(tree.is_empty());
+
+        txn.commit().unwrap();
+        assert_eq!(tree.len(), 2);
+    }
+
+    #[test]
+    fn it_shadows_committed_entries() {
+        let (_db, lengths, tree) = open_temp();
+        let txn = SledTransaction::new(&lengths);
+        for i in 0u64..5 {
+            sled_insert(&txn, &tree, &i.to_be_bytes(), &i, "test").unwrap();
+        }
+        txn.commit().unwrap();
+
+        let txn = SledTransaction::new(&lengths);
+        sled_delete(&txn, &tree, &4u64.to_be_bytes(), "test").unwrap();
+        sled_replace(&txn, &tree, &1u64.to_be_bytes(), &10u64).unwrap();
+        assert_eq!(sled_last::<u64>(&txn, &tree).unwrap(), Some(3));
+        assert_eq!(sled_filter_map_values(&txn, &tree, Some::<u64>).unwrap(), vec![0u64, 10, 2, 3]);
+        assert_eq!(sled_len(&txn, &tree).unwrap(), 4);
+        let err = sled_insert(&txn, &tree, &2u64.to_be_bytes(), &2u64, "test").unwrap_err();
+        assert!(err.is_key_exist_error());
+        txn.commit().unwrap();
+
+        let txn = SledTransaction::new(&lengths);
+        assert_eq!(sled_len(&txn, &tree).unwrap(), 4);
+        assert_eq!(sled_first_after::<u64>(&txn, &tree, &4u64.to_be_bytes()).unwrap(), None);
+    }
+
+    #[test]
+    fn it_stores_duplicate_values() {
+        let (_db, lengths, tree) = open_temp();
+        let txn = SledTransaction::new(&lengths);
+        sled_insert_dup(&txn, &tree, b"parent", &vec![1u8; 4]).unwrap();
+        sled_insert_dup(&txn, &tree, b"parent", &vec![2u8; 4]).unwrap();
+        sled_insert_dup(&txn, &tree, b"other", &vec![3u8; 4]).unwrap();
+        txn.commit().unwrap();
+
+        let txn = SledTransaction::new(&lengths);
+        let values: Vec<Vec<u8>> = sled_get_multiple(&txn, &tree, b"parent").unwrap();
+        assert_eq!(values, vec![vec![1u8; 4], vec![2u8; 4]]);
+        sled_delete_key_value(&txn, &tree, b"parent", &vec![1u8; 4]).unwrap();
+        let values: Vec<Vec<u8>> = sled_get_multiple(&txn, &tree, b"parent").unwrap();
+        assert_eq!(values, vec![vec![2u8; 4]]);
+    }
+}
@@ -0,0 +1,2466 @@
+//  Copyright 2022, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+// Because we use dynamically sized u8 vectors for hash types through the type alias HashOutput,
+// let's ignore this clippy error in this module
+
+#![allow(clippy::ptr_arg)]
+
+use std::{fs::File, ops::Range, path::Path, sync::Arc, time::Instant};
+
+use croaring::Bitmap;
+use log::*;
+use sled::{Db, Tree};
+use tari_common_types::{
+    chain_metadata::ChainMetadata,
+    types::{BlockHash, Commitment, HashDigest, HashOutput, PublicKey, Signature, BLOCK_HASH_LENGTH},
+};
+use tari_crypto::{
+    script::TariScript,
+    tari_utilities::{hash::Hashable, hex::Hex, ByteArray},
+};
+use tari_mmr::{Hash, MerkleMountainRange, MutableMmr};
+use tari_utilities::hex::to_hex;
+
+use crate::{
+    blocks::{
+        Block,
+        BlockAccumulatedData,
+        BlockHeader,
+        BlockHeaderAccumulatedData,
+        ChainBlock,
+        ChainHeader,
+        DeletedBitmap,
+        UpdateBlockAccumulatedData,
+    },
+    chain_storage::{
+        db_keys::{
+            script_hash,
+            InputKey,
+            KernelKey,
+            MetadataKey,
+            MetadataValue,
+            OutputKey,
+            ScriptHashIndexKey,
+            SenderOffsetIndexKey,
+            UniqueIdIndexKey,
+        },
+        db_transaction::{DbKey, DbTransaction, DbValue, WriteOperation},
+        error::{ChainStorageError, OrNotFound},
+        lmdb_db::{
+            acquire_exclusive_file_lock,
+            TransactionInputRowData,
+            TransactionInputRowDataRef,
+            TransactionKernelRowData,
+            TransactionOutputRowData,
+        },
+        sled_db::sled::{
+            fetch_tree_entry_sizes,
+            sled_clear,
+            sled_delete,
+            sled_delete_each_where,
+            sled_delete_key_value,
+            sled_delete_keys_starting_with,
+            sled_exists,
+            sled_fetch_matching_after,
+            sled_filter_map_values,
+            sled_first_after,
+            sled_get,
+            sled_get_multiple,
+            sled_insert,
+            sled_insert_dup,
+            sled_last,
+            sled_last_at_or_before,
+            sled_len,
+            sled_replace,
+            SledTransaction,
+        },
+        stats::DbTotalSizeStats,
+        utxo_mined_info::UtxoMinedInfo,
+        BlockchainBackend,
+        DbBasicStats,
+        DbSize,
+        HorizonData,
+        MmrTree,
+        PrunedOutput,
+        Reorg,
+    },
+    transactions::{
+        aggregated_body::AggregateBody,
+        transaction_components::{TransactionError, TransactionInput, TransactionKernel, TransactionOutput},
+    },
+};
+
+pub const LOG_TARGET: &str = "c::cs::sled_db::sled_db";
+
+const SLED_DB_LENGTHS: &str = "__lengths";
+const SLED_DB_METADATA: &str = "metadata";
+const SLED_DB_HEADERS: &str = "headers";
+const SLED_DB_HEADER_ACCUMULATED_DATA: &str = "header_accumulated_data";
+const SLED_DB_BLOCK_ACCUMULATED_DATA: &str = "mmr_peak_data";
+const SLED_DB_BLOCK_HASHES: &str = "block_hashes";
+const SLED_DB_UTXOS: &str = "utxos";
+const SLED_DB_INPUTS: &str = "inputs";
+const SLED_DB_TXOS_HASH_TO_INDEX: &str = "txos_hash_to_index";
+const SLED_DB_KERNELS: &str = "kernels";
+const SLED_DB_KERNEL_EXCESS_INDEX: &str = "kernel_excess_index";
+const SLED_DB_KERNEL_EXCESS_SIG_INDEX: &str = "kernel_excess_sig_index";
+const SLED_DB_KERNEL_MMR_SIZE_INDEX: &str = "kernel_mmr_size_index";
+const SLED_DB_UTXO_MMR_SIZE_INDEX: &str = "utxo_mmr_size_index";
+const SLED_DB_DELETED_TXO_MMR_POSITION_TO_HEIGHT_INDEX: &str = "deleted_txo_mmr_position_to_height_index";
+const SLED_DB_UTXO_COMMITMENT_INDEX: &str = "utxo_commitment_index";
+const SLED_DB_UNIQUE_ID_INDEX: &str = "unique_id_index";
+const SLED_DB_SCRIPT_HASH_INDEX: &str = "script_hash_index";
+const SLED_DB_SENDER_OFFSET_INDEX: &str = "sender_offset_index";
+const SLED_DB_ORPHANS: &str = "orphans";
+const SLED_DB_MONERO_SEED_HEIGHT: &str = "monero_seed_height";
+const SLED_DB_ORPHAN_HEADER_ACCUMULATED_DATA: &str = "orphan_accumulated_data";
+const SLED_DB_ORPHAN_CHAIN_TIPS: &str = "orphan_chain_tips";
+const SLED_DB_ORPHAN_PARENT_MAP_INDEX: &str = "orphan_parent_map_index";
+const SLED_DB_BAD_BLOCK_LIST: &str = "bad_blocks";
+const SLED_DB_REORGS: &str = "reorgs";
+
+/// Configuration for the sled blockchain backend
+#[derive(Debug, Clone, Copy)]
+pub struct SledConfig {
+    /// The maximum size of the in-memory page cache in bytes
+    pub cache_capacity_bytes: u64,
+    /// The interval at which sled flushes dirty pages in the background. Writes are always flushed on commit.
+    pub flush_every_ms: Option<u64>,
+}
+
+impl SledConfig {
+    /// Specify the cache capacity in megabytes. A `flush_every_ms` of `None` disables background flushing.
+    pub fn new_from_mb(cache_capacity_mb: u64, flush_every_ms: Option<u64>) -> Self {
+        Self {
+            cache_capacity_bytes: cache_capacity_mb * 1024 * 1024,
+            flush_every_ms,
+        }
+    }
+}
+
+impl Default for SledConfig {
+    fn default() -> Self {
+        Self::new_from_mb(512, Some(1000))
+    }
+}
+
+/// Creates or opens the sled blockchain database at `path`. The script hash and sender offset public key indexes used
+/// to search the UTXO set are only maintained if `output_search_indexes` is true.
+pub fn create_sled_database<P: AsRef<Path>>(
+    path: P,
+    config: SledConfig,
+    output_search_indexes: bool,
+) -> Result<SledDatabase, ChainStorageError> {
+    debug!(target: LOG_TARGET, "Creating sled database at {:?}", path.as_ref());
+    std::fs::create_dir_all(&path)?;
+
+    let file_lock = acquire_exclusive_file_lock(path.as_ref())?;
+
+    let db = sled::Config::new()
+        .path(path.as_ref())
+        .cache_capacity(config.cache_capacity_bytes)
+        .flush_every_ms(config.flush_every_ms)
+        .mode(sled::Mode::HighThroughput)
+        .open()
+        .map_err(|err| ChainStorageError::CriticalError(format!("Could not create sled store:{}", err)))?;
+    debug!(target: LOG_TARGET, "sled database creation successful");
+    SledDatabase::new(db, file_lock, output_search_indexes)
+}
+
+/// This is a sled-based blockchain database for persistent storage of the chain state. It stores the same
+/// logical tables as [LMDBDatabase](crate::chain_storage::LMDBDatabase), with each table held in its own sled tree.
+///
+/// Integer keys are stored big-endian so that sled's lexicographic key ordering matches numeric ordering.
+pub struct SledDatabase {
+    db: Arc<Db>,
+    lengths: Tree,
+    metadata_db: Tree,
+    headers_db: Tree,
+    header_accumulated_data_db: Tree,
+    block_accumulated_data_db: Tree,
+    block_hashes_db: Tree,
+    utxos_db: Tree,
+    inputs_db: Tree,
+    txos_hash_to_index_db: Tree,
+    kernels_db: Tree,
+    kernel_excess_index: Tree,
+    kernel_excess_sig_index: Tree,
+    kernel_mmr_size_index: Tree,
+    output_mmr_size_index: Tree,
+    utxo_commitment_index: Tree,
+    unique_id_index: Tree,
+    script_hash_index: Tree,
+    sender_offset_index: Tree,
+    deleted_txo_mmr_position_to_height_index: Tree,
+    orphans_db: Tree,
+    monero_seed_height_db: Tree,
+    orphan_header_accumulated_data_db: Tree,
+    orphan_chain_tips_db: Tree,
+    orphan_parent_map_index: Tree,
+    bad_blocks: Tree,
+    reorgs: Tree,
+    output_search_indexes: bool,
+    _file_lock: Arc<File>,
+}
+
+impl SledDatabase {
+    pub fn new(db: Db, file_lock: File, output_search_indexes: bool) -> Result<Self, ChainStorageError> {
+        let db = Self {
+            lengths: db.open_tree(SLED_DB_LENGTHS)?,
+            metadata_db: db.open_tree(SLED_DB_METADATA)?,
+            headers_db: db.open_tree(SLED_DB_HEADERS)?,
+            header_accumulated_data_db: db.open_tree(SLED_DB_HEADER_ACCUMULATED_DATA)?,
+            block_accumulated_data_db: db.open_tree(SLED_DB_BLOCK_ACCUMULATED_DATA)?,
+            block_hashes_db: db.open_tree(SLED_DB_BLOCK_HASHES)?,
+            utxos_db: db.open_tree(SLED_DB_UTXOS)?,
+            inputs_db: db.open_tree(SLED_DB_INPUTS)?,
+            txos_hash_to_index_db: db.open_tree(SLED_DB_TXOS_HASH_TO_INDEX)?,
+            kernels_db: db.open_tree(SLED_DB_KERNELS)?,
+            kernel_excess_index: db.open_tree(SLED_DB_KERNEL_EXCESS_INDEX)?,
+            kernel_excess_sig_index: db.open_tree(SLED_DB_KERNEL_EXCESS_SIG_INDEX)?,
+            kernel_mmr_size_index: db.open_tree(SLED_DB_KERNEL_MMR_SIZE_INDEX)?,
+            output_mmr_size_index: db.open_tree(SLED_DB_UTXO_MMR_SIZE_INDEX)?,
+            utxo_commitment_index: db.open_tree(SLED_DB_UTXO_COMMITMENT_INDEX)?,
+            unique_id_index: db.open_tree(SLED_DB_UNIQUE_ID_INDEX)?,
+            script_hash_index: db.open_tree(SLED_DB_SCRIPT_HASH_INDEX)?,
+            sender_offset_index: db.open_tree(SLED_DB_SENDER_OFFSET_INDEX)?,
+            deleted_txo_mmr_position_to_height_index: db.open_tree(SLED_DB_DELETED_TXO_MMR_POSITION_TO_HEIGHT_INDEX)?,
+            orphans_db: db.open_tree(SLED_DB_ORPHANS)?,
+            monero_seed_height_db: db.open_tree(SLED_DB_MONERO_SEED_HEIGHT)?,
+            orphan_header_accumulated_data_db: db.open_tree(SLED_DB_ORPHAN_HEADER_ACCUMULATED_DATA)?,
+            orphan_chain_tips_db: db.open_tree(SLED_DB_ORPHAN_CHAIN_TIPS)?,
+            orphan_parent_map_index: db.open_tree(SLED_DB_ORPHAN_PARENT_MAP_INDEX)?,
+            bad_blocks: db.open_tree(SLED_DB_BAD_BLOCK_LIST)?,
+            reorgs: db.open_tree(SLED_DB_REORGS)?,
+            output_search_indexes,
+            db: Arc::new(db),
+            _file_lock: Arc::new(file_lock),
+        };
+
+        if db.output_search_indexes {
+            db.build_output_indexes_if_required()?;
+        } else {
+            db.clear_output_indexes()?;
+        }
+
+        Ok(db)
+    }
+
+    /// The script hash and sender offset indexes are optional. Databases opened while they were disabled have empty
+    /// indexes, so they are populated from the current UTXO set once.
+    fn build_output_indexes_if_required(&self) -> Result<(), ChainStorageError> {
+        let txn = self.transaction();
+        if sled_len(&txn, &self.utxo_commitment_index)? == 0 {
+            return Ok(());
+        }
+        let build_script_index = sled_len(&txn, &self.script_hash_index)? == 0;
+        let build_sender_offset_index = sled_len(&txn, &self.sender_offset_index)? == 0;
+        if !build_script_index && !build_sender_offset_index {
+            return Ok(());
+        }
+        let output_hashes: Vec<HashOutput> = sled_filter_map_values(&txn, &self.utxo_commitment_index, Some)?;
+        info!(
+            target: LOG_TARGET,
+            "Building output indexes for {} unspent output(s)",
+            output_hashes.len()
+        );
+        for output_hash in output_hashes {
+            if let Some(UtxoMinedInfo {
+                output: PrunedOutput::NotPruned { output },
+                ..
+            }) = self.fetch_output_in_txn(&txn, &output_hash)?
+            {
+                if build_script_index {
+                    sled_insert(
+                        &txn,
+                        &self.script_hash_index,
+                        ScriptHashIndexKey::from_script(&output.script, &output_hash)?.as_bytes(),
+                        &output_hash,
+                        "script_hash_index",
+                    )?;
+                }
+                if build_sender_offset_index {
+                    sled_insert(
+                        &txn,
+                        &self.sender_offset_index,
+                        SenderOffsetIndexKey::new(&output.sender_offset_public_key, &output_hash).as_bytes(),
+                        &output_hash,
+                        "sender_offset_index",
+                    )?;
+                }
+            }
+        }
+        txn.commit()
+    }
+
+    /// Removes the script hash and sender offset indexes. They are not updated while disabled, so they would be stale
+    /// if they were enabled again later. Clearing them ensures that they are rebuilt.
+    fn clear_output_indexes(&self) -> Result<(), ChainStorageError> {
+        let txn = self.transaction();
+        let num_deleted = sled_clear(&txn, &self.script_hash_index)? + sled_clear(&txn, &self.sender_offset_index)?;
+        txn.commit()?;
+        if num_deleted > 0 {
+            info!(
+                target: LOG_TARGET,
+                "Cleared {} disabled output index entries", num_deleted
+            );
+        }
+        Ok(())
+    }
+
+    fn insert_output_search_indexes(
+        &self,
+        txn: &SledTransaction<'_>,
+        script: &TariScript,
+        sender_offset_public_key: &PublicKey,
+        output_hash: &HashOutput,
+    ) -> Result<(), ChainStorageError> {
+        if !self.output_search_indexes {
+            return Ok(());
+        }
+        sled_insert(
+            txn,
+            &self.script_hash_index,
+            ScriptHashIndexKey::from_script(script, output_hash)?.as_bytes(),
+            output_hash,
+            "script_hash_index",
+        )?;
+        sled_insert(
+            txn,
+            &self.sender_offset_index,
+            SenderOffsetIndexKey::new(sender_offset_public_key, output_hash).as_bytes(),
+            output_hash,
+            "sender_offset_index",
+        )
+    }
+
+    fn delete_output_search_indexes(
+        &self,
+        txn: &SledTransaction<'_>,
+        script: &TariScript,
+        sender_offset_public_key: &PublicKey,
+        output_hash: &HashOutput,
+    ) -> Result<(), ChainStorageError> {
+        if !self.output_search_indexes {
+            return Ok(());
+        }
+        sled_delete(
+            txn,
+            &self.script_hash_index,
+            ScriptHashIndexKey::from_script(script, output_hash)?.as_bytes(),
+            "script_hash_index",
+        )?;
+        sled_delete(
+            txn,
+            &self.sender_offset_index,
+            SenderOffsetIndexKey::new(sender_offset_public_key, output_hash).as_bytes(),
+            "sender_offset_index",
+        )
+    }
+
+    fn transaction(&self) -> SledTransaction<'_> {
+        SledTransaction::new(&self.lengths)
+    }
+
+    fn apply_db_transaction(&mut self, txn: &DbTransaction) -> Result<(), ChainStorageError> {
+        use WriteOperation::*;
+        let write_txn = self.transaction();
+        for op in txn.operations() {
+            trace!(target: LOG_TARGET, "[apply_db_transaction] WriteOperation: {}", op);
+            match op {
+                InsertOrphanBlock(block) => self.insert_orphan_block(&write_txn, block)?,
+                InsertChainHeader { header } => {
+                    self.insert_header(&write_txn, header.header(), header.accumulated_data())?;
+                },
+                InsertBlockBody { block } => {
+                    self.insert_block_body(&write_txn, block.header(), block.block().body.clone())?;
+                },
+                InsertKernel {
+                    header_hash,
+                    kernel,
+                    mmr_position,
+                } => {
+                    self.insert_kernel(&write_txn, header_hash, &**kernel, *mmr_position)?;
+                },
+                InsertOutput {
+                    header_hash,
+                    header_height,
+                    output,
+                    mmr_position,
+                } => {
+                    self.insert_output(&write_txn, header_hash, *header_height, &*output, *mmr_position)?;
+                },
+                InsertPrunedOutput {
+                    header_hash,
+                    header_height,
+                    output_hash,
+                    witness_hash,
+                    mmr_position,
+                } => {
+                    self.insert_pruned_output(
+                        &write_txn,
+                        header_hash,
+                        *header_height,
+                        output_hash,
+                        witness_hash,
+                        *mmr_position,
+                    )?;
+                },
+                DeleteHeader(height) => {
+                    self.delete_header(&write_txn, *height)?;
+                },
+                DeleteOrphan(hash) => {
+                    self.delete_orphan(&write_txn, hash)?;
+                },
+                DeleteOrphanChainTip(hash) => {
+                    sled_delete(&write_txn, &self.orphan_chain_tips_db, hash, "orphan_chain_tips_db")?;
+                },
+                InsertOrphanChainTip(hash) => {
+                    sled_insert(
+                        &write_txn,
+                        &self.orphan_chain_tips_db,
+                        hash,
+                        hash,
+                        "orphan_chain_tips_db",
+                    )?;
+                },
+                DeleteBlock(hash) => {
+                    self.delete_block_body(&write_txn, hash)?;
+                },
+                InsertMoneroSeedHeight(data, height) => {
+                    self.insert_monero_seed_height(&write_txn, data, *height)?;
+                },
+                SetAccumulatedDataForOrphan(accumulated_data) => {
+                    self.set_accumulated_data_for_orphan(&write_txn, accumulated_data)?;
+                },
+                InsertChainOrphanBlock(chain_block) => {
+                    self.insert_orphan_block(&write_txn, chain_block.block())?;
+                    self.set_accumulated_data_for_orphan(&write_txn, chain_block.accumulated_data())?;
+                },
+                UpdateBlockAccumulatedData { header_hash, values } => {
+                    self.update_block_accumulated_data(&write_txn, header_hash, values.clone())?;
+                },
+                UpdateDeletedBitmap { deleted } => {
+                    let mut bitmap = fetch_deleted_bitmap(&write_txn, &self.metadata_db)?;
+                    bitmap.bitmap_mut().or_inplace(deleted);
+                    self.save_deleted_bitmap(&write_txn, bitmap)?;
+                },
+                PruneOutputsAtMmrPositions { output_positions } => {
+                    self.prune_outputs_at_positions(&write_txn, output_positions)?;
+                },
+                DeleteAllInputsInBlock { block_hash } => {
+                    self.delete_all_inputs_in_block(&write_txn, block_hash)?;
+                },
+                SetBestBlock {
+                    height,
+                    hash,
+                    accumulated_difficulty,
+                    expected_prev_best_block,
+                } => {
+                    // for security we check that the best block does exist, and we check the previous value
+                    // we dont want to check this if the prev block has never been set, this means a empty hash of 32
+                    // bytes.
+                    if *height > 0 {
+                        let prev = fetch_best_block(&write_txn, &self.metadata_db)?;
+                        if *expected_prev_best_block != prev {
+                            return Err(ChainStorageError::InvalidOperation(format!(
+                                "There was a change in best_block, the best block is suppose to be: ({}), but it \
+                                 currently is: ({})",
+                                expected_prev_best_block.to_hex(),
+                                prev.to_hex(),
+                            )));
+                        };
+                    }
+                    if !sled_exists(&write_txn, &self.block_hashes_db, hash.as_slice())? {
+                        // we dont care about the header or the height, we just want to know its there.
+                        return Err(ChainStorageError::InvalidOperation(format!(
+                            "There is no Blockheader hash ({}) in db",
+                            expected_prev_best_block.to_hex(),
+                        )));
+                    };
+                    self.set_metadata(
+                        &write_txn,
+                        MetadataKey::ChainHeight,
+                        MetadataValue::ChainHeight(*height),
+                    )?;
+                    self.set_metadata(
+                        &write_txn,
+                        MetadataKey::BestBlock,
+                        MetadataValue::BestBlock(hash.clone()),
+                    )?;
+                    self.set_metadata(
+                        &write_txn,
+                        MetadataKey::AccumulatedWork,
+                        MetadataValue::AccumulatedWork(*accumulated_difficulty),
+                    )?;
+                },
+                SetPruningHorizonConfig(pruning_horizon) => {
+                    self.set_metadata(
+                        &write_txn,
+                        MetadataKey::PruningHorizon,
+                        MetadataValue::PruningHorizon(*pruning_horizon),
+                    )?;
+                },
+                SetPrunedHeight { height } => {
+                    self.set_metadata(
+                        &write_txn,
+                        MetadataKey::PrunedHeight,
+                        MetadataValue::PrunedHeight(*height),
+                    )?;
+                },
+                SetHorizonData { horizon_data } => {
+                    self.set_metadata(
+                        &write_txn,
+                        MetadataKey::HorizonData,
+                        MetadataValue::HorizonData(horizon_data.clone()),
+                    )?;
+                },
+                InsertBadBlock { hash, height } => {
+                    self.insert_bad_block_and_cleanup(&write_txn, hash, *height)?;
+                },
+                InsertReorg { reorg } => {
+                    sled_replace(
+                        &write_txn,
+                        &self.reorgs,
+                        &reorg.local_time.timestamp().to_be_bytes(),
+                        &reorg,
+                    )?;
+                },
+                ClearAllReorgs => {
+                    sled_clear(&write_txn, &self.reorgs)?;
+                },
+            }
+        }
+        write_txn.commit()?;
+
+        Ok(())
+    }
+
+    fn all_dbs(&self) -> [(&'static str, &Tree); 25] {
+        [
+            ("metadata_db", &self.metadata_db),
+            ("headers_db", &self.headers_db),
+            ("header_accumulated_data_db", &self.header_accumulated_data_db),
+            ("block_accumulated_data_db", &self.block_accumulated_data_db),
+            ("block_hashes_db", &self.block_hashes_db),
+            ("utxos_db", &self.utxos_db),
+            ("inputs_db", &self.inputs_db),
+            ("txos_hash_to_index_db", &self.txos_hash_to_index_db),
+            ("kernels_db", &self.kernels_db),
+            ("kernel_excess_index", &self.kernel_excess_index),
+            ("kernel_excess_sig_index", &self.kernel_excess_sig_index),
+            ("kernel_mmr_size_index", &self.kernel_mmr_size_index),
+            ("output_mmr_size_index", &self.output_mmr_size_index),
+            ("utxo_commitment_index", &self.utxo_commitment_index),
+            ("unique_id_index", &self.unique_id_index),
+            ("script_hash_index", &self.script_hash_index),
+            ("sender_offset_index", &self.sender_offset_index),
+            (
+                "deleted_txo_mmr_position_to_height_index",
+                &self.deleted_txo_mmr_position_to_height_index,
+            ),
+            ("orphans_db", &self.orphans_db),
+            (
+                "orphan_header_accumulated_data_db",
+                &self.orphan_header_accumulated_data_db,
+            ),
+            ("monero_seed_height_db", &self.monero_seed_height_db),
+            ("orphan_chain_tips_db", &self.orphan_chain_tips_db),
+            ("orphan_parent_map_index", &self.orphan_parent_map_index),
+            ("bad_blocks", &self.bad_blocks),
+            ("reorgs", &self.reorgs),
+        ]
+    }
+
+    fn prune_output(&self, txn: &SledTransaction<'_>, key: &OutputKey) -> Result<TransactionOutput, ChainStorageError> {
+        let mut output: TransactionOutputRowData =
+            sled_get(txn, &self.utxos_db, key.as_bytes()).or_not_found("TransactionOutput", "key", key.to_hex())?;
+        let pruned_output = output
+            .output
+            .take()
+            .ok_or_else(|| ChainStorageError::DataInconsistencyDetected {
+                function: "prune_output",
+                details: format!(
+                    "Attempt to prune output that has already been pruned for key {}",
+                    key.to_hex()
+                ),
+            })?;
+        // output.output is None
+        sled_replace(txn, &self.utxos_db, key.as_bytes(), &output)?;
+        // Pruned outputs are spent and should already have been removed from the script and sender offset indexes
+        if self.output_search_indexes {
+            let script_key = ScriptHashIndexKey::from_script(&pruned_output.script, &output.hash)?;
+            if sled_exists(txn, &self.script_hash_index, script_key.as_bytes())? {
+                sled_delete(txn, &self.script_hash_index, script_key.as_bytes(), "script_hash_index")?;
+            }
+            let sender_offset_key = SenderOffsetIndexKey::new(&pruned_output.sender_offset_public_key, &output.hash);
+            if sled_exists(txn, &self.sender_offset_index, sender_offset_key.as_bytes())? {
+                sled_delete(
+                    txn,
+                    &self.sender_offset_index,
+                    sender_offset_key.as_bytes(),
+                    "sender_offset_index",
+                )?;
+            }
+        }
+        Ok(pruned_output)
+    }
+
+    fn insert_output(
+        &self,
+        txn: &SledTransaction<'_>,
+        header_hash: &HashOutput,
+        header_height: u64,
+        output: &TransactionOutput,
+        mmr_position: u32,
+    ) -> Result<(), ChainStorageError> {
+        let output_hash = output.hash();
+        let witness_hash = output.witness_hash();
+
+        let key = OutputKey::new(header_hash, mmr_position, &[]);
+
+        sled_insert(
+            txn,
+            &self.utxo_commitment_index,
+            output.commitment.as_bytes(),
+            &output_hash,
+            "utxo_commitment_index",
+        )?;
+
+        if let Some(ref unique_id) = output.features.unique_id {
+            let parent_public_key = output.features.parent_public_key.as_ref();
+            let key = UniqueIdIndexKey::new(parent_public_key, unique_id.as_slice());
+            debug!(
+                target: LOG_TARGET,
+                "inserting index for unique_id <{}, {}> in output {}. Key is {}",
+                parent_public_key
+                    .map(|p| p.to_hex())
+                    .unwrap_or_else(|| "<none>".to_string()),
+                unique_id.to_hex(),
+                output_hash.to_hex(),
+                key,
+            );
+            sled_insert(
+                txn,
+                &self.unique_id_index,
+                key.as_bytes(),
+                &output_hash,
+                "unique_id_index",
+            )?;
+        }
+
+        self.insert_output_search_indexes(txn, &output.script, &output.sender_offset_public_key, &output_hash)?;
+
+        sled_insert(
+            txn,
+            &self.txos_hash_to_index_db,
+            output_hash.as_slice(),
+            &(mmr_position, key.as_bytes()),
+            "txos_hash_to_index_db",
+        )?;
+        sled_insert(
+            txn,
+            &self.utxos_db,
+            key.as_bytes(),
+            &TransactionOutputRowData {
+                output: Some(output.clone()),
+                header_hash: header_hash.clone(),
+                mmr_position,
+                hash: output_hash,
+                witness_hash,
+                mined_height: header_height,
+            },
+            "utxos_db",
+        )?;
+
+        Ok(())
+    }
+
+    fn insert_pruned_output(
+        &self,
+        txn: &SledTransaction<'_>,
+        header_hash: &HashOutput,
+        header_height: u64,
+        output_hash: &HashOutput,
+        witness_hash: &HashOutput,
+        mmr_position: u32,
+    ) -> Result<(), ChainStorageError> {
+        if !sled_exists(txn, &self.block_hashes_db, header_hash.as_slice())? {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "Unable to insert pruned output because header {} does not exist",
+                header_hash.to_hex(),
+            )));
+        }
+        let key = OutputKey::new(header_hash, mmr_position, &[]);
+        sled_insert(
+            txn,
+            &self.txos_hash_to_index_db,
+            output_hash.as_slice(),
+            &(mmr_position, key.as_bytes()),
+            "txos_hash_to_index_db",
+        )?;
+        sled_insert(
+            txn,
+            &self.utxos_db,
+            key.as_bytes(),
+            &TransactionOutputRowData {
+                output: None,
+                header_hash: header_hash.clone(),
+                mmr_position,
+                hash: output_hash.clone(),
+                witness_hash: witness_hash.clone(),
+                mined_height: header_height,
+            },
+            "utxos_db",
+        )
+    }
+
+    fn insert_kernel(
+        &self,
+        txn: &SledTransaction<'_>,
+        header_hash: &HashOutput,
+        kernel: &TransactionKernel,
+        mmr_position: u32,
+    ) -> Result<(), ChainStorageError> {
+        let hash = kernel.hash();
+        let key = KernelKey::new(header_hash, mmr_position, &hash);
+
+        sled_insert(
+            txn,
+            &self.kernel_excess_index,
+            kernel.excess.as_bytes(),
+            &(header_hash.clone(), mmr_position, hash.clone()),
+            "kernel_excess_index",
+        )?;
+
+        sled_insert(
+            txn,
+            &self.kernel_excess_sig_index,
+            excess_sig_key(&kernel.excess_sig).as_slice(),
+            &(header_hash.clone(), mmr_position, hash.clone()),
+            "kernel_excess_sig_index",
+        )?;
+
+        sled_insert(
+            txn,
+            &self.kernels_db,
+            key.as_bytes(),
+            &TransactionKernelRowData {
+                kernel: kernel.clone(),
+                header_hash: header_hash.clone(),
+                mmr_position,
+                hash,
+            },
+            "kernels_db",
+        )
+    }
+
+    fn insert_input(
+        &self,
+        txn: &SledTransaction<'_>,
+        height: u64,
+        header_hash: &HashOutput,
+        input: &TransactionInput,
+        mmr_position: u32,
+    ) -> Result<(), ChainStorageError> {
+        sled_delete(
+            txn,
+            &self.utxo_commitment_index,
+            input.commitment()?.as_bytes(),
+            "utxo_commitment_index",
+        )
+        .or_else(|err| match err {
+            // The commitment may not yet be included in the DB in the 0-conf transaction case
+            ChainStorageError::ValueNotFound { .. } => Ok(()),
+            _ => Err(err),
+        })?;
+        self.delete_output_search_indexes(
+            txn,
+            input.script()?,
+            input.sender_offset_public_key()?,
+            &input.output_hash(),
+        )
+        .or_else(|err| match err {
+            // As above, a 0-conf output is only indexed once the block's outputs are inserted
+            ChainStorageError::ValueNotFound { .. } => Ok(()),
+            _ => Err(err),
+        })?;
+        sled_insert(
+            txn,
+            &self.deleted_txo_mmr_position_to_height_index,
+            &mmr_position.to_be_bytes(),
+            &(height, header_hash),
+            "deleted_txo_mmr_position_to_height_index",
+        )?;
+
+        if let Some(ref unique_id) = input.features()?.unique_id {
+            let parent_public_key = input.features()?.parent_public_key.as_ref();
+            // Move the "current" UTXO entry to a key containing the spend height
+            let mut key = UniqueIdIndexKey::new(parent_public_key, unique_id.as_slice());
+            let expected_output_hash =
+                sled_get::<HashOutput>(txn, &self.unique_id_index, key.as_bytes())?.ok_or_else(|| {
+                    ChainStorageError::DataInconsistencyDetected {
+                        function: "insert_input",
+                        details: format!("unique token ID with key {} does not exist in index", key.to_hex()),
+                    }
+                })?;
+
+            let output_hash = input.output_hash();
+            if expected_output_hash != output_hash {
+                // This should have been checked by an upstream validator
+                return Err(ChainStorageError::DataInconsistencyDetected {
+                    function: "insert_input",
+                    details: format!(
+                        "output hash for unique id key {} did not match the output hash this input is spending. \
+                         output hash in index {}, hash of spent output: {}",
+                        key.to_hex(),
+                        expected_output_hash.to_hex(),
+                        output_hash.to_hex()
+                    ),
+                });
+            }
+
+            sled_delete(txn, &self.unique_id_index, key.as_bytes(), "unique_id_index")?;
+            key.set_deleted_height(height);
+            debug!(
+                target: LOG_TARGET,
+                "moving index for unique_id <{}, {}> in output {} to key {}",
+                parent_public_key
+                    .map(|p| p.to_hex())
+                    .unwrap_or_else(|| "<none>".to_string()),
+                unique_id.to_hex(),
+                output_hash.to_hex(),
+                key,
+            );
+            sled_insert(
+                txn,
+                &self.unique_id_index,
+                key.as_bytes(),
+                &output_hash,
+                "unique_id_index",
+            )?;
+        }
+
+        let hash = input.canonical_hash()?;
+        let key = InputKey::new(header_hash, mmr_position, &hash);
+        sled_insert(
+            txn,
+            &self.inputs_db,
+            key.as_bytes(),
+            &TransactionInputRowDataRef {
+                input: &input.to_compact(),
+                header_hash,
+                mmr_position,
+                hash: &hash,
+            },
+            "inputs_db",
+        )
+    }
+
+    fn set_metadata(
+        &self,
+        txn: &SledTransaction<'_>,
+        k: MetadataKey,
+        v: MetadataValue,
+    ) -> Result<(), ChainStorageError> {
+        sled_replace(txn, &self.metadata_db, &k.as_u32().to_be_bytes(), &v)?;
+        Ok(())
+    }
+
+    fn insert_orphan_block(&self, txn: &SledTransaction<'_>, block: &Block) -> Result<(), ChainStorageError> {
+        let k = block.hash();
+        sled_insert_dup(txn, &self.orphan_parent_map_index, &block.header.prev_hash, &k)?;
+        sled_insert(txn, &self.orphans_db, k.as_slice(), &block, "orphans_db")?;
+
+        Ok(())
+    }
+
+    fn set_accumulated_data_for_orphan(
+        &self,
+        txn: &SledTransaction<'_>,
+        accumulated_data: &BlockHeaderAccumulatedData,
+    ) -> Result<(), ChainStorageError> {
+        if !sled_exists(txn, &self.orphans_db, accumulated_data.hash.as_slice())? {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "set_accumulated_data_for_orphan: orphan {} does not exist",
+                accumulated_data.hash.to_hex()
+            )));
+        }
+
+        sled_insert(
+            txn,
+            &self.orphan_header_accumulated_data_db,
+            accumulated_data.hash.as_slice(),
+            &accumulated_data,
+            "orphan_header_accumulated_data_db",
+        )?;
+
+        Ok(())
+    }
+
+    /// Inserts the header and header accumulated data.
+    fn insert_header(
+        &self,
+        txn: &SledTransaction<'_>,
+        header: &BlockHeader,
+        accum_data: &BlockHeaderAccumulatedData,
+    ) -> Result<(), ChainStorageError> {
+        if let Some(current_header_at_height) =
+            sled_get::<BlockHeader>(txn, &self.headers_db, &header.height.to_be_bytes())?
+        {
+            let hash = current_header_at_height.hash();
+            if hash != accum_data.hash {
+                return Err(ChainStorageError::InvalidOperation(format!(
+                    "There is a different header stored at height {} already. New header ({}), current header: ({})",
+                    header.height,
+                    accum_data.hash.to_hex(),
+                    current_header_at_height.hash().to_hex(),
+                )));
+            }
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "The header at height {} already exists. Existing header hash: {}",
+                header.height,
+                hash.to_hex()
+            )));
+        }
+
+        // Check that the current height is still header.height - 1 and that no other threads have inserted
+        if let Some(ref last_header) = self.fetch_last_header_in_txn(txn)? {
+            if last_header.height != header.height.saturating_sub(1) {
+                return Err(ChainStorageError::InvalidOperation(format!(
+                    "Attempted to insert a header out of order. The last header height is {} but attempted to insert \
+                     a header with height {}",
+                    last_header.height, header.height,
+                )));
+            }
+
+            // Possibly remove this check later
+            let hash = last_header.hash();
+            if hash != header.prev_hash {
+                return Err(ChainStorageError::InvalidOperation(format!(
+                    "Attempted to insert a block header at height {} that didn't form a chain. Previous block \
+                     hash:{}, new block's previous hash:{}",
+                    header.height,
+                    hash.to_hex(),
+                    header.prev_hash.to_hex()
+                )));
+            }
+        } else if header.height != 0 {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "The first header inserted must have height 0. Height provided: {}",
+                header.height
+            )));
+        }
+
+        let height_key = header.height.to_be_bytes();
+        sled_insert(
+            txn,
+            &self.header_accumulated_data_db,
+            &height_key,
+            &accum_data,
+            "header_accumulated_data_db",
+        )?;
+        sled_insert(
+            txn,
+            &self.block_hashes_db,
+            header.hash().as_slice(),
+            &header.height,
+            "block_hashes_db",
+        )?;
+        sled_insert(txn, &self.headers_db, &height_key, header, "headers_db")?;
+        sled_insert(
+            txn,
+            &self.kernel_mmr_size_index,
+            &header.kernel_mmr_size.to_be_bytes(),
+            &header.height,
+            "kernel_mmr_size_index",
+        )?;
+        sled_insert(
+            txn,
+            &self.output_mmr_size_index,
+            &header.output_mmr_size.to_be_bytes(),
+            &(header.height, header.hash().as_slice()),
+            "output_mmr_size_index",
+        )?;
+        Ok(())
+    }
+
+    fn delete_header(&self, txn: &SledTransaction<'_>, height: u64) -> Result<(), ChainStorageError> {
+        if self.fetch_block_accumulated_data_in_txn(txn, height)?.is_some() {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "Attempted to delete header at height {} while block accumulated data still exists",
+                height
+            )));
+        }
+
+        let header =
+            self.fetch_last_header_in_txn(txn)
+                .or_not_found("BlockHeader", "height", "last_header".to_string())?;
+        if header.height != height {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "Attempted to delete a header at height {} that was not the last header (which is at height {}). \
+                 Headers must be deleted in reverse order.",
+                height, header.height
+            )));
+        }
+
+        let hash = header.hash();
+
+        // Check that there are no utxos or kernels linked to this.
+        if !sled_fetch_matching_after::<TransactionKernelRowData>(txn, &self.kernels_db, &hash)?.is_empty() {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "Cannot delete header {} ({}) because there are kernels linked to it",
+                header.height,
+                hash.to_hex()
+            )));
+        }
+        if !sled_fetch_matching_after::<TransactionOutputRowData>(txn, &self.utxos_db, &hash)?.is_empty() {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "Cannot delete header at height {} ({}) because there are UTXOs linked to it",
+                height,
+                hash.to_hex()
+            )));
+        }
+
+        let height_key = height.to_be_bytes();
+        sled_delete(txn, &self.block_hashes_db, &hash, "block_hashes_db")?;
+        sled_delete(txn, &self.headers_db, &height_key, "headers_db")?;
+        sled_delete(
+            txn,
+            &self.header_accumulated_data_db,
+            &height_key,
+            "header_accumulated_data_db",
+        )?;
+        sled_delete(
+            txn,
+            &self.kernel_mmr_size_index,
+            &header.kernel_mmr_size.to_be_bytes(),
+            "kernel_mmr_size_index",
+        )?;
+        sled_delete(
+            txn,
+            &self.output_mmr_size_index,
+            &header.output_mmr_size.to_be_bytes(),
+            "output_mmr_size_index",
+        )?;
+
+        Ok(())
+    }
+
+    fn delete_block_body(
+        &self,
+        write_txn: &SledTransaction<'_>,
+        block_hash: &HashOutput,
+    ) -> Result<(), ChainStorageError> {
+        let hash_hex = block_hash.to_hex();
+        debug!(target: LOG_TARGET, "Deleting block `{}`", hash_hex);
+        debug!(target: LOG_TARGET, "Deleting UTXOs...");
+        let height = self
+            .fetch_height_from_hash(write_txn, block_hash)
+            .or_not_found("Block", "hash", hash_hex)?;
+        let block_accum_data = self
+            .fetch_block_accumulated_data_in_txn(write_txn, height)?
+            .ok_or_else(|| ChainStorageError::ValueNotFound {
+                entity: "BlockAccumulatedData",
+                field: "height",
+                value: height.to_string(),
+            })?;
+        let mut bitmap = fetch_deleted_bitmap(write_txn, &self.metadata_db)?;
+        bitmap.bitmap_mut().andnot_inplace(block_accum_data.deleted());
+        self.save_deleted_bitmap(write_txn, bitmap)?;
+
+        sled_delete(
+            write_txn,
+            &self.block_accumulated_data_db,
+            &height.to_be_bytes(),
+            "block_accumulated_data_db",
+        )?;
+
+        self.delete_block_inputs_outputs(write_txn, height, block_hash)?;
+        self.delete_block_kernels(write_txn, block_hash)?;
+
+        Ok(())
+    }
+
+    fn delete_block_inputs_outputs(
+        &self,
+        txn: &SledTransaction<'_>,
+        height: u64,
+        hash: &[u8],
+    ) -> Result<(), ChainStorageError> {
+        let output_rows = sled_delete_keys_starting_with::<TransactionOutputRowData>(txn, &self.utxos_db, hash)?;
+        debug!(target: LOG_TARGET, "Deleted {} outputs...", output_rows.len());
+        let inputs = sled_delete_keys_starting_with::<TransactionInputRowData>(txn, &self.inputs_db, hash)?;
+        debug!(target: LOG_TARGET, "Deleted {} input(s)...", inputs.len());
+
+        for utxo in &output_rows {
+            trace!(target: LOG_TARGET, "Deleting UTXO `{}`", to_hex(&utxo.hash));
+            sled_delete(
+                txn,
+                &self.txos_hash_to_index_db,
+                utxo.hash.as_slice(),
+                "txos_hash_to_index_db",
+            )?;
+            if let Some(ref output) = utxo.output {
+                let output_hash = output.hash();
+                // if an output was already spent in the block, it was never created as unspent, so dont delete it as it
+                // does not exist here
+                if inputs.iter().any(|r| r.input.output_hash() == output_hash) {
+                    continue;
+                }
+                sled_delete(
+                    txn,
+                    &self.utxo_commitment_index,
+                    output.commitment.as_bytes(),
+                    "utxo_commitment_index",
+                )?;
+                self.delete_output_search_indexes(txn, &output.script, &output.sender_offset_public_key, &output_hash)?;
+                if let Some(unique_id) = output.features.unique_asset_id() {
+                    let key = UniqueIdIndexKey::new(output.features.parent_public_key.as_ref(), unique_id);
+                    sled_delete(txn, &self.unique_id_index, key.as_bytes(), "unique_id_index")?;
+                }
+            }
+        }
+        // Move inputs in this block back into the unspent set, any outputs spent within this block they will be removed
+        // by deleting all the block's outputs below
+        for row in inputs {
+            // If input spends an output in this block, don't add it to the utxo set
+            let output_hash = row.input.output_hash();
+            if output_rows.iter().any(|r| r.hash == output_hash) {
+                continue;
+            }
+            let mut input = row.input.clone();
+
+            let utxo_mined_info =
+                self.fetch_output_in_txn(txn, &output_hash)?
+                    .ok_or_else(|| ChainStorageError::ValueNotFound {
+                        entity: "UTXO",
+                        field: "hash",
+                        value: output_hash.to_hex(),
+                    })?;
+
+            match utxo_mined_info.output {
+                PrunedOutput::Pruned { .. } => {
+                    debug!(target: LOG_TARGET, "Output Transaction Input is spending is pruned");
+                    return Err(ChainStorageError::TransactionError(
+                        TransactionError::MissingTransactionInputData,
+                    ));
+                },
+                PrunedOutput::NotPruned { output } => {
+                    input.add_output_data(
+                        output.version,
+                        output.features,
+                        output.commitment,
+                        output.script,
+                        output.sender_offset_public_key,
+                        output.covenant,
+                    );
+                },
+            }
+
+            trace!(target: LOG_TARGET, "Input moved to UTXO set: {}", input);
+            sled_insert(
+                txn,
+                &self.utxo_commitment_index,
+                input.commitment()?.as_bytes(),
+                &input.output_hash(),
+                "utxo_commitment_index",
+            )?;
+            self.insert_output_search_indexes(txn, input.script()?, input.sender_offset_public_key()?, &output_hash)?;
+            sled_delete(
+                txn,
+                &self.deleted_txo_mmr_position_to_height_index,
+                &row.mmr_position.to_be_bytes(),
+                "deleted_txo_mmr_position_to_height_index",
+            )?;
+
+            if let Some(unique_id) = input.features()?.unique_asset_id() {
+                let mut key = UniqueIdIndexKey::new(input.features()?.parent_public_key.as_ref(), unique_id);
+                // The output that made this input that is being unspent is now at the head
+                sled_replace(txn, &self.unique_id_index, key.as_bytes(), &output_hash)?;
+
+                // Remove the checkpoint key at current height
+                key.set_deleted_height(height);
+                sled_delete(txn, &self.unique_id_index, key.as_bytes(), "unique_id_index")?;
+            }
+        }
+        Ok(())
+    }
+
+    fn delete_block_kernels(&self, txn: &SledTransaction<'_>, block_hash: &[u8]) -> Result<(), ChainStorageError> {
+        let kernels = sled_delete_keys_starting_with::<TransactionKernelRowData>(txn, &self.kernels_db, block_hash)?;
+        debug!(target: LOG_TARGET, "Deleted {} kernels...", kernels.len());
+        for kernel in kernels {
+            trace!(
+                target: LOG_TARGET,
+                "Deleting excess `{}`",
+                kernel.kernel.excess.to_hex()
+            );
+            sled_delete(
+                txn,
+                &self.kernel_excess_index,
+                kernel.kernel.excess.as_bytes(),
+                "kernel_excess_index",
+            )?;
+            let excess_sig_key = excess_sig_key(&kernel.kernel.excess_sig);
+            trace!(
+                target: LOG_TARGET,
+                "Deleting excess signature `{}`",
+                to_hex(&excess_sig_key)
+            );
+            sled_delete(
+                txn,
+                &self.kernel_excess_sig_index,
+                excess_sig_key.as_slice(),
+                "kernel_excess_sig_index",
+            )?;
+        }
+        Ok(())
+    }
+
+    fn delete_orphan(&self, txn: &SledTransaction<'_>, hash: &HashOutput) -> Result<(), ChainStorageError> {
+        let orphan = match sled_get::<Block>(txn, &self.orphans_db, hash.as_slice())? {
+            Some(orphan) => orphan,
+            None => {
+                // delete_orphan is idempotent
+                debug!(
+                    target: LOG_TARGET,
+                    "delete_orphan: request to delete orphan block {} that was not found.",
+                    hash.to_hex()
+                );
+                return Ok(());
+            },
+        };
+
+        let parent_hash = orphan.header.prev_hash;
+        sled_delete_key_value(txn, &self.orphan_parent_map_index, parent_hash.as_slice(), &hash)?;
+
+        // Orphan is a tip hash
+        if sled_exists(txn, &self.orphan_chain_tips_db, hash.as_slice())? {
+            sled_delete(txn, &self.orphan_chain_tips_db, hash.as_slice(), "orphan_chain_tips_db")?;
+
+            // Parent becomes a tip hash
+            if sled_exists(txn, &self.orphans_db, parent_hash.as_slice())? {
+                sled_insert(
+                    txn,
+                    &self.orphan_chain_tips_db,
+                    parent_hash.as_slice(),
+                    &parent_hash,
+                    "orphan_chain_tips_db",
+                )?;
+            }
+        }
+
+        if sled_exists(txn, &self.orphan_header_accumulated_data_db, hash.as_slice())? {
+            sled_delete(
+                txn,
+                &self.orphan_header_accumulated_data_db,
+                hash.as_slice(),
+                "orphan_header_accumulated_data_db",
+            )?;
+        }
+        sled_delete(txn, &self.orphans_db, hash.as_slice(), "orphans_db")?;
+        Ok(())
+    }
+
+    fn insert_block_body(
+        &self,
+        txn: &SledTransaction<'_>,
+        header: &BlockHeader,
+        body: AggregateBody,
+    ) -> Result<(), ChainStorageError> {
+        let block_hash = header.hash();
+        debug!(
+            target: LOG_TARGET,
+            "Inserting block body for header `{}`: {}",
+            block_hash.to_hex(),
+            body.to_counts_string()
+        );
+
+        // Check that the database has not been changed by another thread
+        // 1. The header we are inserting for matches the header at that height
+        let current_header_at_height = sled_get::<BlockHeader>(txn, &self.headers_db, &header.height.to_be_bytes())
+            .or_not_found("BlockHeader", "height", header.height.to_string())?;
+        let hash = current_header_at_height.hash();
+        if hash != block_hash {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "Could not insert this block body because there is a different header stored at height {}. New header \
+                 ({}), current header: ({})",
+                header.height,
+                hash.to_hex(),
+                block_hash.to_hex()
+            )));
+        }
+
+        let (inputs, outputs, kernels) = body.dissolve();
+
+        let data = if header.height == 0 {
+            BlockAccumulatedData::default()
+        } else {
+            self.fetch_block_accumulated_data_in_txn(txn, header.height - 1)?
+                .ok_or_else(|| ChainStorageError::ValueNotFound {
+                    entity: "BlockAccumulatedData",
+                    field: "height",
+                    value: (header.height - 1).to_string(),
+                })?
+        };
+
+        let mut total_kernel_sum = Commitment::default();
+        let BlockAccumulatedData {
+            kernels: pruned_kernel_set,
+            outputs: pruned_output_set,
+            witness: pruned_proof_set,
+            ..
+        } = data;
+
+        let mut kernel_mmr = MerkleMountainRange::<HashDigest, _>::new(pruned_kernel_set);
+
+        for kernel in kernels {
+            total_kernel_sum = &total_kernel_sum + &kernel.excess;
+            let pos = kernel_mmr.push(kernel.hash())?;
+            trace!(
+                target: LOG_TARGET,
+                "Inserting kernel `{}`",
+                kernel.excess_sig.get_signature().to_hex()
+            );
+            self.insert_kernel(txn, &block_hash, &kernel, pos as u32)?;
+        }
+        let mut output_mmr = MutableMmr::<HashDigest, _>::new(pruned_output_set, Bitmap::create())?;
+        let mut witness_mmr = MerkleMountainRange::<HashDigest, _>::new(pruned_proof_set);
+
+        let leaf_count = witness_mmr.get_leaf_count()?;
+
+        // Output hashes added before inputs so that inputs can spend outputs in this transaction (0-conf and combined)
+        let outputs = outputs
+            .into_iter()
+            .enumerate()
+            .map(|(i, output)| {
+                output_mmr.push(output.hash())?;
+                witness_mmr.push(output.witness_hash())?;
+                Ok((output, leaf_count + i + 1))
+            })
+            .collect::<Result<Vec<_>, ChainStorageError>>()?;
+
+        let mut spent_zero_conf_inputs = Vec::new();
+        // unique_id_index expects inputs to be inserted before outputs
+        for input in &inputs {
+            let output_hash = input.output_hash();
+            let index = match self.fetch_mmr_leaf_index_in_txn(txn, MmrTree::Utxo, &output_hash)? {
+                Some(index) => index,
+                None => match output_mmr.find_leaf_index(&output_hash)? {
+                    Some(index) => {
+                        debug!(
+                            target: LOG_TARGET,
+                            "Input {} spends output from current block (0-conf)", input
+                        );
+                        spent_zero_conf_inputs.push(input);
+                        index
+                    },
+                    None => return Err(ChainStorageError::UnspendableInput),
+                },
+            };
+            if !output_mmr.delete(index) {
+                return Err(ChainStorageError::InvalidOperation(format!(
+                    "Could not delete index {} from the output MMR",
+                    index
+                )));
+            }
+            trace!(target: LOG_TARGET, "Inserting input `{}`", input.commitment()?.to_hex());
+            self.insert_input(txn, current_header_at_height.height, &block_hash, input, index)?;
+        }
+
+        for (output, mmr_count) in outputs {
+            trace!(target: LOG_TARGET, "Inserting output `{}`", output.commitment.to_hex());
+            self.insert_output(txn, &block_hash, header.height, &output, mmr_count as u32 - 1)?;
+        }
+
+        for input in spent_zero_conf_inputs {
+            sled_delete(
+                txn,
+                &self.utxo_commitment_index,
+                input.commitment()?.as_bytes(),
+                "utxo_commitment_index",
+            )?;
+            self.delete_output_search_indexes(
+                txn,
+                input.script()?,
+                input.sender_offset_public_key()?,
+                &input.output_hash(),
+            )?;
+        }
+        // Merge current deletions with the tip bitmap
+        let deleted_at_current_height = output_mmr.deleted().clone();
+        // Merge the new indexes with the blockchain deleted bitmap
+        let mut deleted_bitmap = fetch_deleted_bitmap(txn, &self.metadata_db)?;
+        deleted_bitmap.bitmap_mut().or_inplace(&deleted_at_current_height);
+
+        // Set the output MMR to the complete map so that the complete state can be committed to in the final MR
+        output_mmr.set_deleted(deleted_bitmap.bitmap().clone());
+        output_mmr.compress();
+
+        // Save the bitmap
+        self.save_deleted_bitmap(txn, deleted_bitmap)?;
+
+        sled_insert(
+            txn,
+            &self.block_accumulated_data_db,
+            &header.height.to_be_bytes(),
+            &BlockAccumulatedData::new(
+                kernel_mmr.get_pruned_hash_set()?,
+                output_mmr.mmr().get_pruned_hash_set()?,
+                witness_mmr.get_pruned_hash_set()?,
+                deleted_at_current_height,
+                total_kernel_sum,
+            ),
+            "block_accumulated_data_db",
+        )?;
+
+        Ok(())
+    }
+
+    fn update_block_accumulated_data(
+        &self,
+        write_txn: &SledTransaction<'_>,
+        header_hash: &HashOutput,
+        values: UpdateBlockAccumulatedData,
+    ) -> Result<(), ChainStorageError> {
+        let height = self.fetch_height_from_hash(write_txn, header_hash).or_not_found(
+            "BlockHash",
+            "hash",
+            header_hash.to_hex(),
+        )?;
+
+        let mut block_accum_data = self
+            .fetch_block_accumulated_data_in_txn(write_txn, height)?
+            .unwrap_or_default();
+
+        if let Some(deleted_diff) = values.deleted_diff {
+            block_accum_data.deleted = deleted_diff;
+        }
+        if let Some(kernel_sum) = values.kernel_sum {
+            block_accum_data.kernel_sum = kernel_sum;
+        }
+        if let Some(kernel_hash_set) = values.kernel_hash_set {
+            block_accum_data.kernels = kernel_hash_set;
+        }
+        if let Some(utxo_hash_set) = values.utxo_hash_set {
+            block_accum_data.outputs = utxo_hash_set;
+        }
+        if let Some(witness_hash_set) = values.witness_hash_set {
+            block_accum_data.witness = witness_hash_set;
+        }
+
+        sled_replace(
+            write_txn,
+            &self.block_accumulated_data_db,
+            &height.to_be_bytes(),
+            &block_accum_data,
+        )?;
+        Ok(())
+    }
+
+    fn save_deleted_bitmap(
+        &self,
+        txn: &SledTransaction<'_>,
+        mut bitmap: DeletedBitmap,
+    ) -> Result<(), ChainStorageError> {
+        bitmap.bitmap_mut().run_optimize();
+        self.set_metadata(txn, MetadataKey::DeletedBitmap, MetadataValue::DeletedBitmap(bitmap))
+    }
+
+    fn insert_monero_seed_height(
+        &self,
+        write_txn: &SledTransaction<'_>,
+        seed: &[u8],
+        height: u64,
+    ) -> Result<(), ChainStorageError> {
+        let current_height = sled_get(write_txn, &self.monero_seed_height_db, seed)?.unwrap_or(std::u64::MAX);
+        if height < current_height {
+            sled_replace(write_txn, &self.monero_seed_height_db, seed, &height)?;
+        };
+        Ok(())
+    }
+
+    fn delete_all_inputs_in_block(
+        &self,
+        txn: &SledTransaction<'_>,
+        block_hash: &BlockHash,
+    ) -> Result<(), ChainStorageError> {
+        let inputs = sled_delete_keys_starting_with::<TransactionInputRowData>(txn, &self.inputs_db, block_hash)?;
+        debug!(target: LOG_TARGET, "Deleted {} input(s)", inputs.len());
+        Ok(())
+    }
+
+    fn prune_outputs_at_positions(
+        &self,
+        write_txn: &SledTransaction<'_>,
+        output_positions: &[u32],
+    ) -> Result<(), ChainStorageError> {
+        for pos in output_positions {
+            let (_height, hash) = sled_first_after::<(u64, Vec<u8>)>(
+                write_txn,
+                &self.output_mmr_size_index,
+                &((pos + 1) as u64).to_be_bytes(),
+            )
+            .or_not_found("BlockHeader", "mmr_position", pos.to_string())?;
+            let key = OutputKey::new(&hash, *pos, &[]);
+            debug!(target: LOG_TARGET, "Pruning output: {}", key.to_hex());
+            self.prune_output(write_txn, &key)?;
+        }
+
+        Ok(())
+    }
+
+    fn fetch_mmr_leaf_index_in_txn(
+        &self,
+        txn: &SledTransaction<'_>,
+        tree: MmrTree,
+        hash: &Hash,
+    ) -> Result<Option<u32>, ChainStorageError> {
+        match tree {
+            MmrTree::Utxo => {
+                Ok(sled_get::<(u32, Vec<u8>)>(txn, &self.txos_hash_to_index_db, hash)?.map(|(index, _)| index))
+            },
+            _ => Err(ChainStorageError::InvalidArguments {
+                func: "fetch_mmr_leaf_index",
+                arg: "tree",
+                message: format!("Leaf indexes are not stored for the {} tree", tree),
+            }),
+        }
+    }
+
+    fn fetch_orphan(&self, txn: &SledTransaction<'_>, hash: &HashOutput) -> Result<Option<Block>, ChainStorageError> {
+        sled_get(txn, &self.orphans_db, hash)
+    }
+
+    fn fetch_block_accumulated_data_in_txn(
+        &self,
+        txn: &SledTransaction<'_>,
+        height: u64,
+    ) -> Result<Option<BlockAccumulatedData>, ChainStorageError> {
+        sled_get(txn, &self.block_accumulated_data_db, &height.to_be_bytes())
+    }
+
+    fn fetch_height_from_hash(
+        &self,
+        txn: &SledTransaction<'_>,
+        header_hash: &HashOutput,
+    ) -> Result<Option<u64>, ChainStorageError> {
+        sled_get(txn, &self.block_hashes_db, header_hash.as_slice())
+    }
+
+    fn fetch_header_by_height(
+        &self,
+        txn: &SledTransaction<'_>,
+        height: u64,
+    ) -> Result<Option<BlockHeader>, ChainStorageError> {
+        sled_get(txn, &self.headers_db, &height.to_be_bytes())
+    }
+
+    fn fetch_header_accumulated_data_by_height(
+        &self,
+        txn: &SledTransaction<'_>,
+        height: u64,
+    ) -> Result<Option<BlockHeaderAccumulatedData>, ChainStorageError> {
+        sled_get(txn, &self.header_accumulated_data_db, &height.to_be_bytes())
+    }
+
+    fn fetch_last_header_in_txn(&self, txn: &SledTransaction<'_>) -> Result<Option<BlockHeader>, ChainStorageError> {
+        sled_last(txn, &self.headers_db)
+    }
+
+    /// Constructs a `ChainHeader` for the header at the given height, returning a `ValueNotFound` error if the header
+    /// or its accumulated data does not exist.
+    fn fetch_chain_header_in_txn(
+        &self,
+        txn: &SledTransaction<'_>,
+        height: u64,
+        function: &'static str,
+    ) -> Result<ChainHeader, ChainStorageError> {
+        let header = self
+            .fetch_header_by_height(txn, height)?
+            .ok_or_else(|| ChainStorageError::ValueNotFound {
+                entity: "BlockHeader",
+                field: "height",
+                value: height.to_string(),
+            })?;
+        let accum_data = self
+            .fetch_header_accumulated_data_by_height(txn, height)?
+            .ok_or_else(|| ChainStorageError::ValueNotFound {
+                entity: "BlockHeaderAccumulatedData",
+                field: "height",
+                value: height.to_string(),
+            })?;
+
+        ChainHeader::try_construct(header, accum_data).ok_or_else(|| ChainStorageError::DataInconsistencyDetected {
+            function,
+            details: format!("Accumulated data mismatch at height #{}", height),
+        })
+    }
+
+    fn insert_bad_block_and_cleanup(
+        &self,
+        txn: &SledTransaction<'_>,
+        hash: &HashOutput,
+        height: u64,
+    ) -> Result<(), ChainStorageError> {
+        const CLEAN_BAD_BLOCKS_BEFORE_REL_HEIGHT: u64 = 10000;
+
+        sled_replace(txn, &self.bad_blocks, hash, &height)?;
+        // Clean up bad blocks that are far from the tip
+        let metadata = fetch_metadata(txn, &self.metadata_db)?;
+        let deleted_before_height = metadata
+            .height_of_longest_chain()
+            .saturating_sub(CLEAN_BAD_BLOCKS_BEFORE_REL_HEIGHT);
+        if deleted_before_height == 0 {
+            return Ok(());
+        }
+
+        let num_deleted =
+            sled_delete_each_where::<u64, _>(txn, &self.bad_blocks, |_, v| Some(v < deleted_before_height))?;
+        debug!(target: LOG_TARGET, "Cleaned out {} stale bad blocks", num_deleted);
+
+        Ok(())
+    }
+
+    fn fetch_output_in_txn(
+        &self,
+        txn: &SledTransaction<'_>,
+        output_hash: &HashOutput,
+    ) -> Result<Option<UtxoMinedInfo>, ChainStorageError> {
+        if let Some((index, key)) =
+            sled_get::<(u32, Vec<u8>)>(txn, &self.txos_hash_to_index_db, output_hash.as_slice())?
+        {
+            debug!(
+                target: LOG_TARGET,
+                "Fetch output: {} Found ({}, {})",
+                output_hash.to_hex(),
+                index,
+                key.to_hex()
+            );
+            match sled_get::<TransactionOutputRowData>(txn, &self.utxos_db, &key)? {
+                Some(TransactionOutputRowData {
+                    output: Some(o),
+                    mmr_position,
+                    mined_height,
+                    header_hash,
+                    ..
+                }) => Ok(Some(UtxoMinedInfo {
+                    output: PrunedOutput::NotPruned { output: o },
+                    mmr_position,
+                    mined_height,
+                    header_hash,
+                })),
+                Some(TransactionOutputRowData {
+                    output: None,
+                    mmr_position,
+                    mined_height,
+                    hash,
+                    witness_hash,
+                    header_hash,
+                    ..
+                }) => Ok(Some(UtxoMinedInfo {
+                    output: PrunedOutput::Pruned {
+                        output_hash: hash,
+                        witness_hash,
+                    },
+                    mmr_position,
+                    mined_height,
+                    header_hash,
+                })),
+                _ => Ok(None),
+            }
+        } else {
+            debug!(
+                target: LOG_TARGET,
+                "Fetch output: {} NOT found in index",
+                output_hash.to_hex()
+            );
+            Ok(None)
+        }
+    }
+}
+
+impl BlockchainBackend for SledDatabase {
+    fn write(&mut self, txn: DbTransaction) -> Result<(), ChainStorageError> {
+        if txn.operations().is_empty() {
+            return Ok(());
+        }
+
+        let mark = Instant::now();
+        let num_operations = txn.operations().len();
+        if let Err(e) = self.apply_db_transaction(&txn) {
+            error!(target: LOG_TARGET, "Failed to apply DB transaction: {:?}", e);
+            return Err(e);
+        }
+        // sled commits are atomic but only durable once flushed
+        self.db.flush()?;
+        trace!(
+            target: LOG_TARGET,
+            "Database completed {} operation(s) in {:.0?}",
+            num_operations,
+            mark.elapsed()
+        );
+        Ok(())
+    }
+
+    fn fetch(&self, key: &DbKey) -> Result<Option<DbValue>, ChainStorageError> {
+        let txn = self.transaction();
+        let res = match key {
+            DbKey::BlockHeader(k) => self
+                .fetch_header_by_height(&txn, *k)?
+                .map(|val| DbValue::BlockHeader(Box::new(val))),
+            DbKey::BlockHash(hash) => {
+                if hash.len() != BLOCK_HASH_LENGTH {
+                    return Err(ChainStorageError::InvalidQuery(format!(
+                        "Invalid block hash length. Expected length: {} Got: {}",
+                        BLOCK_HASH_LENGTH,
+                        hash.len()
+                    )));
+                }
+                match self.fetch_height_from_hash(&txn, hash)? {
+                    Some(k) => {
+                        trace!(
+                            target: LOG_TARGET,
+                            "Header with hash:{} found at height:{}",
+                            hash.to_hex(),
+                            k
+                        );
+                        self.fetch_header_by_height(&txn, k)?
+                            .map(|val| DbValue::BlockHash(Box::new(val)))
+                    },
+                    None => {
+                        trace!(
+                            target: LOG_TARGET,
+                            "Header with hash:{} not found in block_hashes_db",
+                            hash.to_hex()
+                        );
+                        None
+                    },
+                }
+            },
+            DbKey::OrphanBlock(k) => self
+                .fetch_orphan(&txn, k)?
+                .map(|val| DbValue::OrphanBlock(Box::new(val))),
+        };
+        Ok(res)
+    }
+
+    fn contains(&self, key: &DbKey) -> Result<bool, ChainStorageError> {
+        let txn = self.transaction();
+        Ok(match key {
+            DbKey::BlockHeader(k) => sled_exists(&txn, &self.headers_db, &k.to_be_bytes())?,
+            DbKey::BlockHash(h) => sled_exists(&txn, &self.block_hashes_db, h)?,
+            DbKey::OrphanBlock(k) => sled_exists(&txn, &self.orphans_db, k)?,
+        })
+    }
+
+    fn fetch_chain_header_by_height(&self, height: u64) -> Result<ChainHeader, ChainStorageError> {
+        let txn = self.transaction();
+        self.fetch_chain_header_in_txn(&txn, height, "fetch_chain_header_by_height")
+    }
+
+    fn fetch_header_accumulated_data(
+        &self,
+        hash: &HashOutput,
+    ) -> Result<Option<BlockHeaderAccumulatedData>, ChainStorageError> {
+        let txn = self.transaction();
+        match self.fetch_height_from_hash(&txn, hash)? {
+            Some(h) => self.fetch_header_accumulated_data_by_height(&txn, h),
+            None => Ok(None),
+        }
+    }
+
+    fn fetch_chain_header_in_all_chains(&self, hash: &HashOutput) -> Result<ChainHeader, ChainStorageError> {
+        let txn = self.transaction();
+
+        if let Some(h) = self.fetch_height_from_hash(&txn, hash)? {
+            return self.fetch_chain_header_in_txn(&txn, h, "fetch_chain_header_in_all_chains");
+        }
+
+        let orphan_accum: Option<BlockHeaderAccumulatedData> =
+            sled_get(&txn, &self.orphan_header_accumulated_data_db, hash.as_slice())?;
+
+        if let Some(accum) = orphan_accum {
+            let orphan =
+                self.fetch_orphan(&txn, hash)?
+                    .ok_or_else(|| ChainStorageError::DataInconsistencyDetected {
+                        function: "fetch_chain_header_in_all_chains",
+                        details: format!(
+                            "Orphan accumulated data exists but the corresponding orphan header {} does not",
+                            hash.to_hex()
+                        ),
+                    })?;
+            let chain_header = ChainHeader::try_construct(orphan.header, accum).ok_or_else(|| {
+                ChainStorageError::DataInconsistencyDetected {
+                    function: "fetch_chain_header_in_all_chains",
+                    details: format!("accumulated data mismatch for orphan header {}", hash.to_hex()),
+                }
+            })?;
+            return Ok(chain_header);
+        }
+
+        Err(ChainStorageError::ValueNotFound {
+            entity: "chain header (in chain_header_in_all_chains)",
+            field: "hash",
+            value: hash.to_hex(),
+        })
+    }
+
+    fn fetch_header_containing_kernel_mmr(&self, mmr_position: u64) -> Result<ChainHeader, ChainStorageError> {
+        let txn = self.transaction();
+        // The index is keyed by the MMR size at each height, so we have to offset the position by 1 so that the
+        // mmr_position arg is an index starting from 0
+        let mmr_position = mmr_position + 1;
+
+        let height = sled_first_after::<u64>(&txn, &self.kernel_mmr_size_index, &mmr_position.to_be_bytes())?
+            .ok_or_else(|| ChainStorageError::ValueNotFound {
+                entity: "kernel_mmr_size_index",
+                field: "mmr_position",
+                value: mmr_position.to_string(),
+            })?;
+
+        self.fetch_chain_header_in_txn(&txn, height, "fetch_header_containing_kernel_mmr")
+    }
+
+    fn fetch_header_containing_utxo_mmr(&self, mmr_position: u64) -> Result<ChainHeader, ChainStorageError> {
+        let txn = self.transaction();
+        // The index is keyed by the MMR size at each height, so we have to offset the position by 1 so that the
+        // mmr_position arg is an index starting from 0
+        let mmr_position = mmr_position + 1;
+
+        let (height, _hash) =
+            sled_first_after::<(u64, Vec<u8>)>(&txn, &self.output_mmr_size_index, &mmr_position.to_be_bytes())?
+                .ok_or_else(|| ChainStorageError::ValueNotFound {
+                    entity: "output_mmr_size_index",
+                    field: "mmr_position",
+                    value: mmr_position.to_string(),
+                })?;
+
+        self.fetch_chain_header_in_txn(&txn, height, "fetch_header_containing_utxo_mmr")
+    }
+
+    fn is_empty(&self) -> Result<bool, ChainStorageError> {
+        let txn = self.transaction();
+        Ok(sled_len(&txn, &self.headers_db)? == 0)
+    }
+
+    fn fetch_block_accumulated_data(
+        &self,
+        header_hash: &HashOutput,
+    ) -> Result<Option<BlockAccumulatedData>, ChainStorageError> {
+        let txn = self.transaction();
+        match self.fetch_height_from_hash(&txn, header_hash)? {
+            Some(height) => self.fetch_block_accumulated_data_in_txn(&txn, height),
+            None => Ok(None),
+        }
+    }
+
+    fn fetch_block_accumulated_data_by_height(
+        &self,
+        height: u64,
+    ) -> Result<Option<BlockAccumulatedData>, ChainStorageError> {
+        let txn = self.transaction();
+        self.fetch_block_accumulated_data_in_txn(&txn, height)
+    }
+
+    fn fetch_kernels_in_block(&self, header_hash: &HashOutput) -> Result<Vec<TransactionKernel>, ChainStorageError> {
+        let txn = self.transaction();
+        Ok(sled_fetch_matching_after(&txn, &self.kernels_db, header_hash)?
+            .into_iter()
+            .map(|f: TransactionKernelRowData| f.kernel)
+            .collect())
+    }
+
+    fn fetch_kernel_by_excess(
+        &self,
+        excess: &[u8],
+    ) -> Result<Option<(TransactionKernel, HashOutput)>, ChainStorageError> {
+        let txn = self.transaction();
+        match sled_get::<(HashOutput, u32, HashOutput)>(&txn, &self.kernel_excess_index, excess)? {
+            Some((header_hash, mmr_position, hash)) => {
+                let key = KernelKey::new(&header_hash, mmr_position, &hash);
+                Ok(sled_get(&txn, &self.kernels_db, key.as_bytes())?
+                    .map(|kernel: TransactionKernelRowData| (kernel.kernel, header_hash)))
+            },
+            None => Ok(None),
+        }
+    }
+
+    fn fetch_kernel_by_excess_sig(
+        &self,
+        excess_sig: &Signature,
+    ) -> Result<Option<(TransactionKernel, HashOutput)>, ChainStorageError> {
+        let txn = self.transaction();
+        let key = excess_sig_key(excess_sig);
+        match sled_get::<(HashOutput, u32, HashOutput)>(&txn, &self.kernel_excess_sig_index, key.as_slice())? {
+            Some((header_hash, mmr_position, hash)) => {
+                let key = KernelKey::new(&header_hash, mmr_position, &hash);
+                Ok(sled_get(&txn, &self.kernels_db, key.as_bytes())?
+                    .map(|kernel: TransactionKernelRowData| (kernel.kernel, header_hash)))
+            },
+            None => Ok(None),
+        }
+    }
+
+    fn fetch_utxos_in_block(
+        &self,
+        header_hash: &HashOutput,
+        deleted: Option<&Bitmap>,
+    ) -> Result<(Vec<PrunedOutput>, Bitmap), ChainStorageError> {
+        let txn = self.transaction();
+
+        let utxos = sled_fetch_matching_after::<TransactionOutputRowData>(&txn, &self.utxos_db, header_hash)?
+            .into_iter()
+            .map(|row| {
+                if deleted.map(|b| b.contains(row.mmr_position)).unwrap_or(false) {
+                    return PrunedOutput::Pruned {
+                        output_hash: row.hash,
+                        witness_hash: row.witness_hash,
+                    };
+                }
+                match row.output {
+                    Some(output) => PrunedOutput::NotPruned { output },
+                    None => PrunedOutput::Pruned {
+                        output_hash: row.hash,
+                        witness_hash: row.witness_hash,
+                    },
+                }
+            })
+            .collect();
+
+        let height =
+            self.fetch_height_from_hash(&txn, header_hash)?
+                .ok_or_else(|| ChainStorageError::ValueNotFound {
+                    entity: "BlockHeader",
+                    field: "hash",
+                    value: header_hash.to_hex(),
+                })?;
+
+        // Builds a BitMap of the deleted UTXO MMR indexes that occurred at the current height
+        let acc_data = self.fetch_block_accumulated_data_in_txn(&txn, height)?.ok_or_else(|| {
+            ChainStorageError::ValueNotFound {
+                entity: "BlockAccumulatedData",
+                field: "height",
+                value: height.to_string(),
+            }
+        })?;
+
+        let mut difference_bitmap = Bitmap::create();
+        difference_bitmap.or_inplace(acc_data.deleted());
+
+        Ok((utxos, difference_bitmap))
+    }
+
+    fn fetch_output(&self, output_hash: &HashOutput) -> Result<Option<UtxoMinedInfo>, ChainStorageError> {
+        debug!(target: LOG_TARGET, "Fetch output: {}", output_hash.to_hex());
+        let txn = self.transaction();
+        self.fetch_output_in_txn(&txn, output_hash)
+    }
+
+    fn fetch_unspent_output_hash_by_commitment(
+        &self,
+        commitment: &Commitment,
+    ) -> Result<Option<HashOutput>, ChainStorageError> {
+        let txn = self.transaction();
+        sled_get::<HashOutput>(&txn, &self.utxo_commitment_index, commitment.as_bytes())
+    }
+
+    fn fetch_utxo_by_unique_id(
+        &self,
+        parent_public_key: Option<&PublicKey>,
+        unique_id: &[u8],
+        deleted_height: Option<u64>,
+    ) -> Result<Option<UtxoMinedInfo>, ChainStorageError> {
+        let txn = self.transaction();
+
+        let mut key = UniqueIdIndexKey::new(parent_public_key, unique_id);
+        if let Some(height) = deleted_height {
+            key.set_deleted_height(height);
+        }
+
+        // Either return the output hash that matches the key exactly, or the key before the given key.
+        // Since a key without a deleted height is `0xFF x 8 (u64::MAX)` that key will always return the
+        // output in the UTxO set or the last TxO matching the <parent_pk, unique_id> tuple
+        let output_hash =
+            sled_last_at_or_before::<HashOutput>(&txn, &self.unique_id_index, key.as_prefix_bytes(), key.as_bytes())?
+                .map(|(_, v)| v);
+
+        match output_hash {
+            Some(output_hash) => self.fetch_output_in_txn(&txn, &output_hash),
+            None => Ok(None),
+        }
+    }
+
+    fn fetch_all_unspent_by_parent_public_key(
+        &self,
+        parent_public_key: &PublicKey,
+        range: Range<usize>,
+    ) -> Result<Vec<UtxoMinedInfo>, ChainStorageError> {
+        let txn = self.transaction();
+        let key = parent_public_key.as_bytes();
+        let values: Vec<HashOutput> = sled_fetch_matching_after(&txn, &self.unique_id_index, key)?;
+        let mut result = vec![];
+        for hash in values.into_iter().skip(range.start).take(range.len()) {
+            if let Some(s) = self.fetch_output_in_txn(&txn, &hash)? {
+                result.push(s);
+            }
+        }
+        Ok(result)
+    }
+
+    fn fetch_unspent_outputs_by_script(
+        &self,
+        script: &TariScript,
+        range: Range<usize>,
+    ) -> Result<Vec<UtxoMinedInfo>, ChainStorageError> {
+        if !self.output_search_indexes {
+            return Err(ChainStorageError::OutputSearchIndexDisabled);
+        }
+        let txn = self.transaction();
+        let values: Vec<HashOutput> = sled_fetch_matching_after(&txn, &self.script_hash_index, &script_hash(script)?)?;
+        let mut result = Vec::with_capacity(range.len().min(values.len()));
+        for hash in values.into_iter().skip(range.start).take(range.len()) {
+            if let Some(s) = self.fetch_output_in_txn(&txn, &hash)? {
+                result.push(s);
+            }
+        }
+        Ok(result)
+    }
+
+    fn fetch_unspent_outputs_by_sender_offset_public_key(
+        &self,
+        sender_offset_public_key: &PublicKey,
+        range: Range<usize>,
+    ) -> Result<Vec<UtxoMinedInfo>, ChainStorageError> {
+        if !self.output_search_indexes {
+            return Err(ChainStorageError::OutputSearchIndexDisabled);
+        }
+        let txn = self.transaction();
+        let values: Vec<HashOutput> =
+            sled_fetch_matching_after(&txn, &self.sender_offset_index, sender_offset_public_key.as_bytes())?;
+        let mut result = Vec::with_capacity(range.len().min(values.len()));
+        for hash in values.into_iter().skip(range.start).take(range.len()) {
+            if let Some(s) = self.fetch_output_in_txn(&txn, &hash)? {
+                result.push(s);
+            }
+        }
+        Ok(result)
+    }
+
+    fn fetch_outputs_in_block(&self, header_hash: &HashOutput) -> Result<Vec<PrunedOutput>, ChainStorageError> {
+        let txn = self.transaction();
+        Ok(sled_fetch_matching_after(&txn, &self.utxos_db, header_hash)?
+            .into_iter()
+            .map(|f: TransactionOutputRowData| match f.output {
+                Some(o) => PrunedOutput::NotPruned { output: o },
+                None => PrunedOutput::Pruned {
+                    output_hash: f.hash,
+                    witness_hash: f.witness_hash,
+                },
+            })
+            .collect())
+    }
+
+    fn fetch_inputs_in_block(&self, header_hash: &HashOutput) -> Result<Vec<TransactionInput>, ChainStorageError> {
+        let txn = self.transaction();
+        Ok(sled_fetch_matching_after(&txn, &self.inputs_db, header_hash)?
+            .into_iter()
+            .map(|f: TransactionInputRowData| f.input)
+            .collect())
+    }
+
+    fn fetch_mmr_size(&self, tree: MmrTree) -> Result<u64, ChainStorageError> {
+        let txn = self.transaction();
+        match tree {
+            MmrTree::Kernel => Ok(sled_len(&txn, &self.kernels_db)? as u64),
+            MmrTree::Witness | MmrTree::Utxo => Ok(sled_len(&txn, &self.utxos_db)? as u64),
+        }
+    }
+
+    fn fetch_mmr_leaf_index(&self, tree: MmrTree, hash: &Hash) -> Result<Option<u32>, ChainStorageError> {
+        let txn = self.transaction();
+        self.fetch_mmr_leaf_index_in_txn(&txn, tree, hash)
+    }
+
+    /// Returns the number of blocks in the block orphan pool.
+    fn orphan_count(&self) -> Result<usize, ChainStorageError> {
+        trace!(target: LOG_TARGET, "Get orphan count");
+        let txn = self.transaction();
+        sled_len(&txn, &self.orphans_db)
+    }
+
+    /// Finds and returns the last stored header.
+    fn fetch_last_header(&self) -> Result<BlockHeader, ChainStorageError> {
+        let txn = self.transaction();
+        self.fetch_last_header_in_txn(&txn)?.ok_or_else(|| {
+            ChainStorageError::InvalidOperation("Cannot fetch last header because database is empty".to_string())
+        })
+    }
+
+    /// Finds and returns the last stored header.
+    fn fetch_last_chain_header(&self) -> Result<ChainHeader, ChainStorageError> {
+        let txn = self.transaction();
+        let header = self.fetch_last_header_in_txn(&txn)?.ok_or_else(|| {
+            ChainStorageError::InvalidOperation("Cannot fetch last header because database is empty".to_string())
+        })?;
+        self.fetch_chain_header_in_txn(&txn, header.height, "fetch_last_chain_header")
+    }
+
+    fn fetch_tip_header(&self) -> Result<ChainHeader, ChainStorageError> {
+        let txn = self.transaction();
+        let metadata = fetch_metadata(&txn, &self.metadata_db)?;
+        self.fetch_chain_header_in_txn(&txn, metadata.height_of_longest_chain(), "fetch_tip_header")
+    }
+
+    /// Returns the metadata of the chain.
+    fn fetch_chain_metadata(&self) -> Result<ChainMetadata, ChainStorageError> {
+        let txn = self.transaction();
+        fetch_metadata(&txn, &self.metadata_db)
+    }
+
+    fn utxo_count(&self) -> Result<usize, ChainStorageError> {
+        let txn = self.transaction();
+        sled_len(&txn, &self.utxos_db)
+    }
+
+    fn kernel_count(&self) -> Result<usize, ChainStorageError> {
+        let txn = self.transaction();
+        sled_len(&txn, &self.kernels_db)
+    }
+
+    fn fetch_orphan_chain_tip_by_hash(&self, hash: &HashOutput) -> Result<Option<ChainHeader>, ChainStorageError> {
+        trace!(target: LOG_TARGET, "Call to fetch_orphan_chain_tips()");
+        let txn = self.transaction();
+        if !sled_exists(&txn, &self.orphan_chain_tips_db, hash.as_slice())? {
+            return Ok(None);
+        }
+
+        let orphan = self
+            .fetch_orphan(&txn, hash)?
+            .ok_or_else(|| ChainStorageError::ValueNotFound {
+                entity: "Orphan",
+                field: "hash",
+                value: hash.to_hex(),
+            })?;
+
+        let accumulated_data =
+            sled_get(&txn, &self.orphan_header_accumulated_data_db, hash.as_slice())?.ok_or_else(|| {
+                ChainStorageError::ValueNotFound {
+                    entity: "Orphan accumulated data",
+                    field: "hash",
+                    value: hash.to_hex(),
+                }
+            })?;
+
+        let height = orphan.header.height;
+        let chain_header = ChainHeader::try_construct(orphan.header, accumulated_data).ok_or_else(|| {
+            ChainStorageError::DataInconsistencyDetected {
+                function: "fetch_orphan_chain_tip_by_hash",
+                details: format!("Accumulated data mismatch at height #{}", height),
+            }
+        })?;
+        Ok(Some(chain_header))
+    }
+
+    fn fetch_orphan_children_of(&self, parent_hash: HashOutput) -> Result<Vec<Block>, ChainStorageError> {
+        trace!(
+            target: LOG_TARGET,
+            "Call to fetch_orphan_children_of({})",
+            parent_hash.to_hex()
+        );
+        let txn = self.transaction();
+        let orphan_hashes: Vec<HashOutput> =
+            sled_get_multiple(&txn, &self.orphan_parent_map_index, parent_hash.as_slice())?;
+        let mut res = Vec::with_capacity(orphan_hashes.len());
+        for hash in orphan_hashes {
+            res.push(
+                self.fetch_orphan(&txn, &hash)?
+                    .ok_or_else(|| ChainStorageError::ValueNotFound {
+                        entity: "Orphan",
+                        field: "hash",
+                        value: hash.to_hex(),
+                    })?,
+            )
+        }
+        Ok(res)
+    }
+
+    fn fetch_orphan_chain_block(&self, hash: HashOutput) -> Result<Option<ChainBlock>, ChainStorageError> {
+        let txn = self.transaction();
+        let block = match self.fetch_orphan(&txn, &hash)? {
+            Some(block) => block,
+            None => return Ok(None),
+        };
+        match sled_get::<BlockHeaderAccumulatedData>(&txn, &self.orphan_header_accumulated_data_db, hash.as_slice())? {
+            Some(accumulated_data) => {
+                let chain_block = ChainBlock::try_construct(Arc::new(block), accumulated_data).ok_or_else(|| {
+                    ChainStorageError::DataInconsistencyDetected {
+                        function: "fetch_orphan_chain_block",
+                        details: format!("Accumulated data mismatch for hash {}", hash.to_hex()),
+                    }
+                })?;
+                Ok(Some(chain_block))
+            },
+            None => Ok(None),
+        }
+    }
+
+    fn fetch_deleted_bitmap(&self) -> Result<DeletedBitmap, ChainStorageError> {
+        let txn = self.transaction();
+        fetch_deleted_bitmap(&txn, &self.metadata_db)
+    }
+
+    fn delete_oldest_orphans(
+        &mut self,
+        horizon_height: u64,
+        orphan_storage_capacity: usize,
+    ) -> Result<(), ChainStorageError> {
+        let orphan_count = self.orphan_count()?;
+        let num_over_limit = orphan_count.saturating_sub(orphan_storage_capacity);
+        if num_over_limit == 0 {
+            return Ok(());
+        }
+        debug!(
+            target: LOG_TARGET,
+            "Orphan block storage limit of {} reached, performing cleanup of {} entries.",
+            orphan_storage_capacity,
+            num_over_limit,
+        );
+
+        let mut orphans = {
+            let read_txn = self.transaction();
+            sled_filter_map_values(&read_txn, &self.orphans_db, |block: Block| {
+                Some((block.header.height, block.hash()))
+            })?
+        };
+
+        orphans.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut txn = DbTransaction::new();
+        for (removed_count, (height, block_hash)) in orphans.into_iter().enumerate() {
+            if height > horizon_height && removed_count >= num_over_limit {
+                break;
+            }
+            debug!(
+                target: LOG_TARGET,
+                "Discarding orphan block #{} ({}).",
+                height,
+                block_hash.to_hex()
+            );
+            txn.delete_orphan(block_hash.clone());
+        }
+        self.write(txn)?;
+
+        Ok(())
+    }
+
+    fn fetch_monero_seed_first_seen_height(&self, seed: &[u8]) -> Result<u64, ChainStorageError> {
+        let txn = self.transaction();
+        Ok(sled_get(&txn, &self.monero_seed_height_db, seed)?.unwrap_or(0))
+    }
+
+    fn fetch_horizon_data(&self) -> Result<Option<HorizonData>, ChainStorageError> {
+        let txn = self.transaction();
+        Ok(Some(fetch_horizon_data(&txn, &self.metadata_db)?))
+    }
+
+    fn get_stats(&self) -> Result<DbBasicStats, ChainStorageError> {
+        let txn = self.transaction();
+        let db_stats = self
+            .all_dbs()
+            .iter()
+            .map(|(name, tree)| sled_len(&txn, tree).map(|entries| (*name, entries)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(DbBasicStats::from_entry_counts(self.db.size_on_disk()?, db_stats))
+    }
+
+    fn fetch_total_size_stats(&self) -> Result<DbTotalSizeStats, ChainStorageError> {
+        self.all_dbs()
+            .iter()
+            .map(|(name, tree)| {
+                fetch_tree_entry_sizes(tree).map(|(num_entries, total_key_size, total_value_size)| DbSize {
+                    name,
+                    num_entries,
+                    total_key_size,
+                    total_value_size,
+                })
+            })
+            .collect()
+    }
+
+    fn fetch_header_hash_by_deleted_mmr_positions(
+        &self,
+        mmr_positions: Vec<u32>,
+    ) -> Result<Vec<Option<(u64, HashOutput)>>, ChainStorageError> {
+        let txn = self.transaction();
+
+        mmr_positions
+            .iter()
+            .map(|pos| sled_get(&txn, &self.deleted_txo_mmr_position_to_height_index, &pos.to_be_bytes()))
+            .collect()
+    }
+
+    fn bad_block_exists(&self, block_hash: HashOutput) -> Result<bool, ChainStorageError> {
+        let txn = self.transaction();
+        sled_exists(&txn, &self.bad_blocks, &block_hash)
+    }
+
+    fn clear_all_pending_headers(&self) -> Result<usize, ChainStorageError> {
+        let txn = self.transaction();
+        let last_header = match self.fetch_last_header_in_txn(&txn)? {
+            Some(h) => h,
+            None => {
+                return Ok(0);
+            },
+        };
+        let metadata = fetch_metadata(&txn, &self.metadata_db)?;
+
+        if metadata.height_of_longest_chain() == last_header.height {
+            return Ok(0);
+        }
+
+        let start = metadata.height_of_longest_chain() + 1;
+        let end = last_header.height;
+
+        let mut num_deleted = 0;
+        for h in (start..=end).rev() {
+            self.delete_header(&txn, h)?;
+            num_deleted += 1;
+        }
+        txn.commit()?;
+        self.db.flush()?;
+        Ok(num_deleted)
+    }
+
+    fn fetch_all_reorgs(&self) -> Result<Vec<Reorg>, ChainStorageError> {
+        let txn = self.transaction();
+        sled_filter_map_values(&txn, &self.reorgs, Some)
+    }
+}
+
+fn excess_sig_key(excess_sig: &Signature) -> Vec<u8> {
+    let mut key = Vec::<u8>::with_capacity(32 * 2);
+    key.extend(excess_sig.get_public_nonce().as_bytes());
+    key.extend(excess_sig.get_signature().as_bytes());
+    key
+}
+
+fn fetch_metadata_value(
+    txn: &SledTransaction<'_>,
+    db: &Tree,
+    k: MetadataKey,
+) -> Result<Option<MetadataValue>, ChainStorageError> {
+    sled_get(txn, db, &k.as_u32().to_be_bytes())
+}
+
+// Fetch the chain metadata
+fn fetch_metadata(txn: &SledTransaction<'_>, db: &Tree) -> Result<ChainMetadata, ChainStorageError> {
+    Ok(ChainMetadata::new(
+        fetch_chain_height(txn, db)?,
+        fetch_best_block(txn, db)?,
+        fetch_pruning_horizon(txn, db)?,
+        fetch_pruned_height(txn, db)?,
+        fetch_accumulated_work(txn, db)?,
+    ))
+}
+
+// Fetches the chain height from the provided metadata db.
+fn fetch_chain_height(txn: &SledTransaction<'_>, db: &Tree) -> Result<u64, ChainStorageError> {
+    match fetch_metadata_value(txn, db, MetadataKey::ChainHeight)? {
+        Some(MetadataValue::ChainHeight(height)) => Ok(height),
+        _ => Err(ChainStorageError::ValueNotFound {
+            entity: "ChainMetadata",
+            field: "ChainHeight",
+            value: "".to_string(),
+        }),
+    }
+}
+
+/// Fetches the effective pruned height from the provided metadata db.
+fn fetch_pruned_height(txn: &SledTransaction<'_>, db: &Tree) -> Result<u64, ChainStorageError> {
+    match fetch_metadata_value(txn, db, MetadataKey::PrunedHeight)? {
+        Some(MetadataValue::PrunedHeight(height)) => Ok(height),
+        _ => Ok(0),
+    }
+}
+
+/// Fetches the horizon data from the provided metadata db.
+fn fetch_horizon_data(txn: &SledTransaction<'_>, db: &Tree) -> Result<HorizonData, ChainStorageError> {
+    match fetch_metadata_value(txn, db, MetadataKey::HorizonData)? {
+        Some(MetadataValue::HorizonData(data)) => Ok(data),
+        None => Err(ChainStorageError::ValueNotFound {
+            entity: "HorizonData",
+            field: "metadata",
+            value: "".to_string(),
+        }),
+        Some(k) => Err(ChainStorageError::DataInconsistencyDetected {
+            function: "fetch_horizon_data",
+            details: format!("Received incorrect value {:?} for key horizon data", k),
+        }),
+    }
+}
+
+// Fetches the best block hash from the provided metadata db.
+fn fetch_best_block(txn: &SledTransaction<'_>, db: &Tree) -> Result<BlockHash, ChainStorageError> {
+    match fetch_metadata_value(txn, db, MetadataKey::BestBlock)? {
+        Some(MetadataValue::BestBlock(best_block)) => Ok(best_block),
+        _ => Err(ChainStorageError::ValueNotFound {
+            entity: "ChainMetadata",
+            field: "BestBlock",
+            value: "".to_string(),
+        }),
+    }
+}
+
+// Fetches the accumulated work from the provided metadata db.
+fn fetch_accumulated_work(txn: &SledTransaction<'_>, db: &Tree) -> Result<u128, ChainStorageError> {
+    match fetch_metadata_value(txn, db, MetadataKey::AccumulatedWork)? {
+        Some(MetadataValue::AccumulatedWork(accumulated_difficulty)) => Ok(accumulated_difficulty),
+        _ => Err(ChainStorageError::ValueNotFound {
+            entity: "ChainMetadata",
+            field: "AccumulatedWork",
+            value: "".to_string(),
+        }),
+    }
+}
+
+// Fetches the deleted bitmap from the provided metadata db.
+fn fetch_deleted_bitmap(txn: &SledTransaction<'_>, db: &Tree) -> Result<DeletedBitmap, ChainStorageError> {
+    match fetch_metadata_value(txn, db, MetadataKey::DeletedBitmap)? {
+        Some(MetadataValue::DeletedBitmap(bitmap)) => Ok(bitmap),
+        None => Ok(Bitmap::create().into()),
+        _ => Err(ChainStorageError::ValueNotFound {
+            entity: "ChainMetadata",
+            field: "DeletedBitmap",
+            value: "".to_string(),
+        }),
+    }
+}
+
+// Fetches the pruning horizon from the provided metadata db.
+fn fetch_pruning_horizon(txn: &SledTransaction<'_>, db: &Tree) -> Result<u64, ChainStorageError> {
+    match fetch_metadata_value(txn, db, MetadataKey::PruningHorizon)? {
+        Some(MetadataValue::PruningHorizon(pruning_horizon)) => Ok(pruning_horizon),
+        _ => Ok(0),
+    }
+}
@@ -30,7 +30,8 @@ use lmdb_zero as lmdb;
 #[derive(Debug, Clone)]
 pub struct DbBasicStats {
     root: DbStat,
-    env_info: EnvInfo,
+    env_info: Option<EnvInfo>,
+    size_on_disk: Option<u64>,
     db_stats: Vec<DbStat>,
 }
 
@@ -42,17 +43,50 @@ impl DbBasicStats {
     ) -> Self {
         Self {
             root: ("[root]", global).into(),
-            env_info: env_info.into(),
+            env_info: Some(env_info.into()),
+            size_on_disk: None,
             db_stats: db_stats.into_iter().map(Into::into).collect(),
         }
     }
 
+    /// Builds stats for backends that do not expose page-level or environment information. Only the entry counts and
+    /// the total size on disk are available.
+    pub(super) fn from_entry_counts<I: IntoIterator<Item = (&'static str, usize)>>(
+        size_on_disk: u64,
+        db_stats: I,
+    ) -> Self {
+        let db_stats = db_stats
+            .into_iter()
+            .map(|(name, entries)| DbStat {
+                name,
+                pages: None,
+                entries,
+            })
+            .collect::<Vec<_>>();
+        Self {
+            root: DbStat {
+                name: "[root]",
+                pages: None,
+                entries: db_stats.len(),
+            },
+            env_info: None,
+            size_on_disk: Some(size_on_disk),
+            db_stats,
+        }
+    }
+
     pub fn root(&self) -> &DbStat {
         &self.root
     }
 
-    pub fn env_info(&self) -> &EnvInfo {
-        &self.env_info
+    /// Returns the environment information, if the backend exposes it
+    pub fn env_info(&self) -> Option<&EnvInfo> {
+        self.env_info.as_ref()
+    }
+
+    /// Returns the total size of the database on disk in bytes, if the backend reports it
+    pub fn size_on_disk(&self) -> Option<u64> {
+        self.size_on_disk
     }
 
     pub fn db_stats(&self) -> &[DbStat] {
@@ -62,7 +96,10 @@ impl DbBasicStats {
 
 impl Display for DbBasicStats {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "Root: psize = {}, {}", self.root.psize, self.root)?;
+        match self.root.pages {
+            Some(pages) => writeln!(f, "Root: psize = {}, {}", pages.psize, self.root)?,
+            None => writeln!(f, "Root: {}", self.root)?,
+        }
         for stat in &self.db_stats {
             writeln!(f, "{}", stat)?;
         }
@@ -75,24 +112,16 @@ impl Display for DbBasicStats {
 pub struct DbStat {
     /// Name of the db
     pub name: &'static str,
-    /// Size of a database page. This is currently the same for all databases.
-    pub psize: u32,
-    /// Depth (height) of the B-tree
-    pub depth: u32,
-    /// Number of internal (non-leaf) pages
-    pub branch_pages: usize,
-    /// Number of leaf pages
-    pub leaf_pages: usize,
-    /// Number of overflow pages
-    pub overflow_pages: usize,
+    /// B-tree page statistics, if the backend exposes them
+    pub pages: Option<DbPageStats>,
     /// Number of data items
     pub entries: usize,
 }
 
 impl DbStat {
-    /// Returns the total size in bytes of all pages
-    pub fn total_page_size(&self) -> usize {
-        self.psize as usize * (self.leaf_pages + self.branch_pages + self.overflow_pages)
+    /// Returns the total size in bytes of all pages, if page statistics are available
+    pub fn total_page_size(&self) -> Option<usize> {
+        self.pages.map(|pages| pages.total_page_size())
     }
 }
 
@@ -100,11 +129,13 @@ impl From<(&'static str, lmdb::Stat)> for DbStat {
     fn from((name, stat): (&'static str, lmdb::Stat)) -> Self {
         Self {
             name,
-            psize: stat.psize,
-            depth: stat.depth,
-            branch_pages: stat.branch_pages,
-            leaf_pages: stat.leaf_pages,
-            overflow_pages: stat.overflow_pages,
+            pages: Some(DbPageStats {
+                psize: stat.psize,
+                depth: stat.depth,
+                branch_pages: stat.branch_pages,
+                leaf_pages: stat.leaf_pages,
+                overflow_pages: stat.overflow_pages,
+            }),
             entries: stat.entries,
         }
     }
@@ -112,18 +143,43 @@ impl From<(&'static str, lmdb::Stat)> for DbStat {
 
 impl Display for DbStat {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "name: {}, Total page size: {}, entries: {}, depth: {}, branch_pages: {}, leaf_pages: {}, overflow_pages: \
-             {}",
-            self.name,
-            self.total_page_size(),
-            self.entries,
-            self.depth,
-            self.branch_pages,
-            self.leaf_pages,
-            self.overflow_pages,
-        )
+        match self.pages {
+            Some(pages) => write!(
+                f,
+                "name: {}, Total page size: {}, entries: {}, depth: {}, branch_pages: {}, leaf_pages: {}, \
+                 overflow_pages: {}",
+                self.name,
+                pages.total_page_size(),
+                self.entries,
+                pages.depth,
+                pages.branch_pages,
+                pages.leaf_pages,
+                pages.overflow_pages,
+            ),
+            None => write!(f, "name: {}, entries: {}", self.name, self.entries),
+        }
+    }
+}
+
+/// Page statistics of a B-tree database.
+#[derive(Debug, Clone, Copy)]
+pub struct DbPageStats {
+    /// Size of a database page. This is currently the same for all databases.
+    pub psize: u32,
+    /// Depth (height) of the B-tree
+    pub depth: u32,
+    /// Number of internal (non-leaf) pages
+    pub branch_pages: usize,
+    /// Number of leaf pages
+    pub leaf_pages: usize,
+    /// Number of overflow pages
+    pub overflow_pages: usize,
+}
+
+impl DbPageStats {
+    /// Returns the total size in bytes of all pages
+    pub fn total_page_size(&self) -> usize {
+        self.psize as usize * (self.leaf_pages + self.branch_pages + self.overflow_pages)
     }
 }
 
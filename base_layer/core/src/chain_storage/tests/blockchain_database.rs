@@ -501,6 +501,176 @@ mod add_block {
     }
 }
 
+mod fetch_unspent_outputs_by_script {
+    use tari_crypto::{script, script::TariScript};
+    use tari_test_utils::paths::create_temporary_data_path;
+
+    use super::*;
+    use crate::{chain_storage::BlockchainBackend, test_helpers::blockchain::create_new_blockchain_with_backend};
+
+    fn create_scripted_outputs(
+        db: &BlockchainDatabase<TempDatabase>,
+    ) -> (Vec<Arc<Block>>, Vec<UnblindedOutput>, TariScript) {
+        let (mut blocks, outputs) = add_many_chained_blocks(2, db);
+        let script = script!(Nop Nop);
+        let (txns, tx_outputs) = schema_to_transaction(&[TransactionSchema {
+            from: vec![outputs[0].clone()],
+            to: vec![10 * T, 20 * T],
+            to_outputs: vec![],
+            fee: 5.into(),
+            lock_height: 0,
+            features: Default::default(),
+            script: script.clone(),
+            covenant: Default::default(),
+            input_data: None,
+            input_version: None,
+            output_version: None,
+        }]);
+        let (block, _) = create_next_block(db, blocks.last().unwrap(), txns);
+        db.add_block(block.clone()).unwrap().assert_added();
+        blocks.push(block);
+        let scripted = tx_outputs.into_iter().filter(|o| o.script == script).collect();
+        (blocks, scripted, script)
+    }
+
+    #[test]
+    fn it_returns_outputs_locked_with_the_script() {
+        let db = setup();
+        let (_, scripted, script) = create_scripted_outputs(&db);
+        assert_eq!(scripted.len(), 2);
+
+        let found = db.fetch_unspent_outputs_by_script(script.clone(), 0..10).unwrap();
+        assert_eq!(found.len(), 2);
+        assert!(found
+            .iter()
+            .all(|utxo| utxo.output.as_transaction_output().unwrap().script == script));
+
+        let found = db.fetch_unspent_outputs_by_script(script![Nop Nop Nop], 0..10).unwrap();
+        assert!(found.is_empty());
+    }
+
+    #[test]
+    fn it_pages_through_the_matching_outputs() {
+        let db = setup();
+        let (_, _, script) = create_scripted_outputs(&db);
+
+        let first = db.fetch_unspent_outputs_by_script(script.clone(), 0..1).unwrap();
+        let second = db.fetch_unspent_outputs_by_script(script.clone(), 1..10).unwrap();
+        assert_eq!(first.len(), 1);
+        assert_eq!(second.len(), 1);
+        assert_ne!(first[0].output.hash(), second[0].output.hash());
+        assert!(db.fetch_unspent_outputs_by_script(script, 2..10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn it_tracks_spends_and_reorgs() {
+        let db = setup();
+        let (blocks, scripted, script) = create_scripted_outputs(&db);
+
+        let (txns, _) = schema_to_transaction(&[txn_schema!(from: vec![scripted[0].clone()], to: vec![5 * T])]);
+        let (block, _) = create_next_block(&db, blocks.last().unwrap(), txns);
+        db.add_block(block).unwrap().assert_added();
+        let found = db.fetch_unspent_outputs_by_script(script.clone(), 0..10).unwrap();
+        assert_eq!(found.len(), 1);
+
+        // Rewinding the spend restores the spent output to the index
+        db.rewind_to_height(blocks.last().unwrap().header.height).unwrap();
+        let found = db.fetch_unspent_outputs_by_script(script.clone(), 0..10).unwrap();
+        assert_eq!(found.len(), 2);
+
+        // Rewinding the block that created the outputs removes them from the index
+        db.rewind_to_height(blocks.last().unwrap().header.height - 1).unwrap();
+        assert!(db.fetch_unspent_outputs_by_script(script, 0..10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn it_is_rebuilt_after_being_disabled() {
+        let path = create_temporary_data_path();
+        let mut backend = TempDatabase::from_path(&path);
+        backend.disable_delete_on_drop();
+        let db = create_new_blockchain_with_backend(backend);
+        let (_, _, script) = create_scripted_outputs(&db);
+        drop(db);
+
+        let mut backend = TempDatabase::from_path_with_output_search_indexes(&path, false);
+        backend.disable_delete_on_drop();
+        let err = backend.fetch_unspent_outputs_by_script(&script, 0..10).unwrap_err();
+        assert!(err.is_output_search_index_disabled());
+        let err = backend
+            .fetch_unspent_outputs_by_sender_offset_public_key(&PublicKey::default(), 0..10)
+            .unwrap_err();
+        assert!(err.is_output_search_index_disabled());
+        drop(backend);
+
+        let backend = TempDatabase::from_path(&path);
+        let found = backend.fetch_unspent_outputs_by_script(&script, 0..10).unwrap();
+        assert_eq!(found.len(), 2);
+    }
+}
+
+mod fetch_unspent_outputs_by_sender_offset_public_key {
+    use super::*;
+
+    #[test]
+    fn it_returns_the_output_with_the_sender_offset_public_key() {
+        let db = setup();
+        let (blocks, outputs) = add_many_chained_blocks(1, &db);
+        let (txns, tx_outputs) = schema_to_transaction(&[txn_schema!(from: vec![outputs[0].clone()], to: vec![5 * T])]);
+        let (block, _) = create_next_block(&db, blocks.last().unwrap(), txns);
+        db.add_block(block).unwrap().assert_added();
+        let output = &tx_outputs[0];
+
+        let found = db
+            .fetch_unspent_outputs_by_sender_offset_public_key(output.sender_offset_public_key.clone(), 0..10)
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(
+            found[0]
+                .output
+                .as_transaction_output()
+                .unwrap()
+                .sender_offset_public_key,
+            output.sender_offset_public_key
+        );
+        assert!(db
+            .fetch_unspent_outputs_by_sender_offset_public_key(output.sender_offset_public_key.clone(), 1..10)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn it_tracks_spends_and_reorgs() {
+        let db = setup();
+        let (blocks, outputs) = add_many_chained_blocks(1, &db);
+        let (txns, tx_outputs) = schema_to_transaction(&[txn_schema!(from: vec![outputs[0].clone()], to: vec![5 * T])]);
+        let (block, _) = create_next_block(&db, blocks.last().unwrap(), txns);
+        db.add_block(block.clone()).unwrap().assert_added();
+        let key = tx_outputs[0].sender_offset_public_key.clone();
+
+        let (txns, _) = schema_to_transaction(&[txn_schema!(from: vec![tx_outputs[0].clone()], to: vec![T])]);
+        let (spend_block, _) = create_next_block(&db, &block, txns);
+        db.add_block(spend_block).unwrap().assert_added();
+        assert!(db
+            .fetch_unspent_outputs_by_sender_offset_public_key(key.clone(), 0..10)
+            .unwrap()
+            .is_empty());
+
+        // Rewinding the spend restores the spent output to the index
+        db.rewind_to_height(block.header.height).unwrap();
+        let found = db
+            .fetch_unspent_outputs_by_sender_offset_public_key(key.clone(), 0..10)
+            .unwrap();
+        assert_eq!(found.len(), 1);
+
+        // Rewinding the block that created the output removes it from the index
+        db.rewind_to_height(block.header.height - 1).unwrap();
+        assert!(db
+            .fetch_unspent_outputs_by_sender_offset_public_key(key, 0..10)
+            .unwrap()
+            .is_empty());
+    }
+}
+
 mod get_stats {
     use super::*;
 
@@ -508,7 +678,10 @@ mod get_stats {
     fn it_works_when_db_is_empty() {
         let db = setup();
         let stats = db.get_stats().unwrap();
-        assert_eq!(stats.root().depth, 1);
+        match stats.root().pages {
+            Some(pages) => assert_eq!(pages.depth, 1),
+            None => assert!(stats.size_on_disk().is_some()),
+        }
     }
 }
 
@@ -219,6 +219,33 @@ impl CovenantArg {
     }
 }
 
+impl CovenantArg {
+    /// Writes the argument in the textual covenant syntax, e.g. `@uint(42)`
+    pub(super) fn fmt_text(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use CovenantArg::*;
+        match self {
+            Hash(hash) => write!(f, "@hash({})", to_hex(&hash[..])),
+            PublicKey(public_key) => write!(f, "@public_key({})", public_key.to_hex()),
+            Commitment(commitment) => write!(f, "@commitment({})", commitment.to_hex()),
+            TariScript(script) => write!(f, "@script({})", to_hex(&script.as_bytes())),
+            Covenant(covenant) => write!(f, "@covenant({})", covenant),
+            Uint(v) => write!(f, "@uint({})", v),
+            OutputField(field) => write!(f, "@{}", field),
+            OutputFields(fields) => {
+                write!(f, "@fields(")?;
+                for (i, field) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "@{}", field)?;
+                }
+                write!(f, ")")
+            },
+            Bytes(bytes) => write!(f, "@bytes({})", to_hex(bytes)),
+        }
+    }
+}
+
 impl Display for CovenantArg {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         use CovenantArg::*;
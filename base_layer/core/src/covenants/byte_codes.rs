@@ -37,7 +37,7 @@ pub const ARG_BYTES: u8 = 0x09;
 //---------------------------------- FILTER byte codes --------------------------------------------//
 
 pub(super) fn is_valid_filter_code(code: u8) -> bool {
-    (0x20..=0x24).contains(&code) || (0x30..=0x34).contains(&code)
+    (0x20..=0x24).contains(&code) || (0x30..=0x38).contains(&code)
 }
 
 pub const FILTER_IDENTITY: u8 = 0x20;
@@ -51,6 +51,10 @@ pub const FILTER_FIELDS_PRESERVED: u8 = 0x31;
 pub const FILTER_FIELDS_HASHED_EQ: u8 = 0x32;
 pub const FILTER_FIELD_EQ: u8 = 0x33;
 pub const FILTER_ABSOLUTE_HEIGHT: u8 = 0x34;
+pub const FILTER_RELATIVE_HEIGHT: u8 = 0x35;
+pub const FILTER_FIELD_GREATER_THAN: u8 = 0x36;
+pub const FILTER_FIELD_LESS_THAN: u8 = 0x37;
+pub const FILTER_METADATA_PREFIX: u8 = 0x38;
 
 //---------------------------------- FIELD byte codes --------------------------------------------//
 pub const FIELD_COMMITMENT: u8 = 0x00;
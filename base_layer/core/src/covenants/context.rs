@@ -25,7 +25,9 @@ use crate::{
         arguments::CovenantArg,
         error::CovenantError,
         filters::CovenantFilter,
+        output_set::OutputSet,
         token::{CovenantToken, CovenantTokenCollection},
+        trace::CovenantFilterTrace,
     },
     transactions::transaction_components::TransactionInput,
 };
@@ -34,6 +36,9 @@ pub struct CovenantContext<'a> {
     input: &'a TransactionInput,
     tokens: CovenantTokenCollection,
     block_height: u64,
+    input_mined_height: Option<u64>,
+    trace: Option<Vec<CovenantFilterTrace>>,
+    depth: usize,
 }
 
 impl<'a> CovenantContext<'a> {
@@ -42,9 +47,60 @@ impl<'a> CovenantContext<'a> {
             input,
             tokens,
             block_height,
+            input_mined_height: None,
+            trace: None,
+            depth: 0,
         }
     }
 
+    /// Sets the height at which the output spent by the input was mined. This is required by filters that are relative
+    /// to the input, and is only known when the covenant is executed against the chain.
+    pub fn with_input_mined_height(mut self, height: u64) -> Self {
+        self.input_mined_height = Some(height);
+        self
+    }
+
+    /// Records the effect of every filter on the output set. The recorded filters are returned by `take_trace`.
+    pub fn with_trace(mut self) -> Self {
+        self.trace = Some(Vec::new());
+        self
+    }
+
+    /// Starts tracing a filter that is about to be applied to `output_set`. Returns the position of the trace entry and
+    /// the output indexes before the filter was applied, which must be passed to `end_filter_trace`.
+    pub(super) fn start_filter_trace(
+        &mut self,
+        filter: &CovenantFilter,
+        output_set: &OutputSet<'_>,
+    ) -> Option<(usize, Vec<usize>)> {
+        let depth = self.depth;
+        let trace = self.trace.as_mut()?;
+        let before = output_set.get_selected_indexes();
+        // The entry is added before the filter is applied so that nested filters are recorded after their parent
+        trace.push(CovenantFilterTrace {
+            filter: filter.name(),
+            depth,
+            input_set_size: before.len(),
+            eliminated_outputs: Vec::new(),
+            output_set_size: 0,
+        });
+        self.depth += 1;
+        Some((trace.len() - 1, before))
+    }
+
+    pub(super) fn end_filter_trace(&mut self, (pos, before): (usize, Vec<usize>), output_set: &OutputSet<'_>) {
+        self.depth -= 1;
+        if let Some(entry) = self.trace.as_mut().and_then(|trace| trace.get_mut(pos)) {
+            let after = output_set.get_selected_indexes();
+            entry.eliminated_outputs = before.into_iter().filter(|i| !after.contains(i)).collect();
+            entry.output_set_size = after.len();
+        }
+    }
+
+    pub fn take_trace(&mut self) -> Vec<CovenantFilterTrace> {
+        self.trace.take().unwrap_or_default()
+    }
+
     pub fn has_more_tokens(&self) -> bool {
         !self.tokens.is_empty()
     }
@@ -76,6 +132,10 @@ impl<'a> CovenantContext<'a> {
         self.block_height
     }
 
+    pub fn input_mined_height(&self) -> Option<u64> {
+        self.input_mined_height
+    }
+
     pub fn input(&self) -> &TransactionInput {
         self.input
     }
@@ -20,7 +20,14 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{io, iter::FromIterator};
+use std::{
+    fmt,
+    fmt::{Display, Formatter},
+    io,
+    iter::FromIterator,
+    slice,
+    str::FromStr,
+};
 
 use integer_encoding::{VarInt, VarIntReader, VarIntWriter};
 
@@ -34,7 +41,9 @@ use crate::{
         error::CovenantError,
         filters::Filter,
         output_set::OutputSet,
+        parser::{CovenantParseError, CovenantParser},
         token::{CovenantToken, CovenantTokenCollection},
+        trace::CovenantTrace,
     },
     transactions::transaction_components::{TransactionInput, TransactionOutput},
 };
@@ -77,22 +86,78 @@ impl Covenant {
         counter.get()
     }
 
+    /// Executes the covenant against the given outputs. Filters that are relative to the height at which the input was
+    /// mined (e.g. `relative_height`) cannot be evaluated without the chain and fail with
+    /// `CovenantError::InputMinedHeightUnknown`. Use `execute_with_input_mined_height` where the mined height of the
+    /// input is known.
     pub fn execute<'a>(
         &self,
         block_height: u64,
         input: &TransactionInput,
         outputs: &'a [TransactionOutput],
+    ) -> Result<usize, CovenantError> {
+        let tokens = CovenantTokenCollection::from_iter(self.tokens.clone());
+        self.execute_in_context(&mut CovenantContext::new(tokens, input, block_height), outputs)
+    }
+
+    /// Executes the covenant against the given outputs, where `input_mined_height` is the height at which the output
+    /// spent by `input` was mined.
+    pub fn execute_with_input_mined_height(
+        &self,
+        block_height: u64,
+        input: &TransactionInput,
+        input_mined_height: u64,
+        outputs: &[TransactionOutput],
+    ) -> Result<usize, CovenantError> {
+        let tokens = CovenantTokenCollection::from_iter(self.tokens.clone());
+        let mut cx = CovenantContext::new(tokens, input, block_height).with_input_mined_height(input_mined_height);
+        self.execute_in_context(&mut cx, outputs)
+    }
+
+    /// Executes the covenant against the given outputs and records the effect that each filter had on the output set.
+    /// A failing covenant is not an error here, the reason for the failure is included in the returned trace. If
+    /// `input_mined_height` is None, height-relative filters fail as in `execute`.
+    pub fn execute_with_trace(
+        &self,
+        block_height: u64,
+        input: &TransactionInput,
+        input_mined_height: Option<u64>,
+        outputs: &[TransactionOutput],
+    ) -> CovenantTrace {
+        let tokens = CovenantTokenCollection::from_iter(self.tokens.clone());
+        let mut cx = CovenantContext::new(tokens, input, block_height).with_trace();
+        if let Some(height) = input_mined_height {
+            cx = cx.with_input_mined_height(height);
+        }
+        let result = self.execute_in_context(&mut cx, outputs);
+        let filters = cx.take_trace();
+        match result {
+            Ok(output_set_size) => CovenantTrace {
+                filters,
+                output_set_size,
+                error: None,
+            },
+            Err(err) => CovenantTrace {
+                output_set_size: filters.first().map(|root| root.output_set_size).unwrap_or_default(),
+                filters,
+                error: Some(err.to_string()),
+            },
+        }
+    }
+
+    fn execute_in_context(
+        &self,
+        cx: &mut CovenantContext<'_>,
+        outputs: &[TransactionOutput],
     ) -> Result<usize, CovenantError> {
         if self.tokens.is_empty() {
             // Empty covenants always pass
             return Ok(outputs.len());
         }
 
-        let tokens = CovenantTokenCollection::from_iter(self.tokens.clone());
-        let mut cx = CovenantContext::new(tokens, input, block_height);
         let root = cx.require_next_filter()?;
         let mut output_set = OutputSet::new(outputs);
-        root.filter(&mut cx, &mut output_set)?;
+        root.filter(cx, &mut output_set)?;
         if cx.has_more_tokens() {
             return Err(CovenantError::RemainingTokens);
         }
@@ -147,6 +212,54 @@ impl ConsensusDecoding for Covenant {
     }
 }
 
+/// Writes the covenant in the textual covenant syntax, e.g.
+/// `and(field_eq(@field::features_flags, @uint(1)), absolute_height(@uint(100)))`. The output can be parsed back into
+/// an identical covenant with `Covenant::from_str`.
+impl Display for Covenant {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        // A well-formed covenant has a single root filter but every token is written so that malformed covenants can
+        // be inspected
+        let mut tokens = self.tokens.iter();
+        let mut is_first = true;
+        while tokens.len() > 0 {
+            if !is_first {
+                write!(f, ", ")?;
+            }
+            write_next_token(f, &mut tokens)?;
+            is_first = false;
+        }
+        Ok(())
+    }
+}
+
+fn write_next_token(f: &mut Formatter<'_>, tokens: &mut slice::Iter<'_, CovenantToken>) -> fmt::Result {
+    match tokens.next() {
+        Some(CovenantToken::Filter(filter)) => {
+            write!(f, "{}(", filter.name())?;
+            for i in 0..filter.arity() {
+                if tokens.len() == 0 {
+                    break;
+                }
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write_next_token(f, tokens)?;
+            }
+            write!(f, ")")
+        },
+        Some(CovenantToken::Arg(arg)) => arg.fmt_text(f),
+        None => Ok(()),
+    }
+}
+
+impl FromStr for Covenant {
+    type Err = CovenantParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CovenantParser::new(s).parse()
+    }
+}
+
 impl FromIterator<CovenantToken> for Covenant {
     fn from_iter<T: IntoIterator<Item = CovenantToken>>(iter: T) -> Self {
         Self {
@@ -186,6 +299,79 @@ mod test {
         assert_eq!(num_matching_outputs, 3);
     }
 
+    #[test]
+    fn it_executes_relative_height_filters_with_the_input_mined_height() {
+        let outputs = create_outputs(10, Default::default());
+        let input = create_input();
+        let covenant = covenant!(relative_height(@uint(10)));
+        let err = covenant.execute(110, &input, &outputs).unwrap_err();
+        assert!(matches!(err, CovenantError::InputMinedHeightUnknown { .. }));
+        let err = covenant
+            .execute_with_input_mined_height(105, &input, 100, &outputs)
+            .unwrap_err();
+        assert!(matches!(err, CovenantError::NoMatchingOutputs));
+        let num_matching_outputs = covenant
+            .execute_with_input_mined_height(110, &input, 100, &outputs)
+            .unwrap();
+        assert_eq!(num_matching_outputs, 10);
+    }
+
+    #[test]
+    fn it_traces_the_outputs_eliminated_by_each_filter() {
+        let bytes = vec![0xab, 0xcd, 0xef];
+        let mut outputs = create_outputs(10, Default::default());
+        outputs[4].features.maturity = 42;
+        outputs[5].features.maturity = 42;
+        outputs[7].features.maturity = 42;
+        outputs[7].features.unique_id = Some(bytes.clone());
+        let input = create_input();
+        let covenant = covenant!(and(
+            field_eq(@field::features_maturity, @uint(42)),
+            not(field_eq(@field::features_unique_id, @bytes(bytes)))
+        ));
+        let trace = covenant.execute_with_trace(0, &input, None, &outputs);
+        assert!(trace.is_pass());
+        assert_eq!(trace.output_set_size, 2);
+
+        let summary = trace
+            .filters
+            .iter()
+            .map(|f| {
+                (
+                    f.filter,
+                    f.depth,
+                    f.input_set_size,
+                    f.eliminated_outputs.clone(),
+                    f.output_set_size,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(summary, vec![
+            ("and", 0, 10, vec![0, 1, 2, 3, 6, 7, 8, 9], 2),
+            ("field_eq", 1, 10, vec![0, 1, 2, 3, 6, 8, 9], 3),
+            ("not", 1, 3, vec![7], 2),
+            ("field_eq", 2, 3, vec![4, 5], 1),
+        ]);
+    }
+
+    #[test]
+    fn it_traces_a_failing_covenant() {
+        let outputs = create_outputs(3, Default::default());
+        let input = create_input();
+        let covenant = covenant!(absolute_height(@uint(100)));
+        let trace = covenant.execute_with_trace(50, &input, None, &outputs);
+        assert!(!trace.is_pass());
+        assert_eq!(trace.output_set_size, 0);
+        assert_eq!(trace.filters.len(), 1);
+        assert_eq!(trace.filters[0].eliminated_outputs, vec![0, 1, 2]);
+        assert_eq!(trace.error.unwrap(), CovenantError::NoMatchingOutputs.to_string());
+
+        let trace = covenant!().execute_with_trace(50, &input, None, &outputs);
+        assert!(trace.is_pass());
+        assert!(trace.filters.is_empty());
+        assert_eq!(trace.output_set_size, 3);
+    }
+
     mod consensus_encoding {
         use super::*;
 
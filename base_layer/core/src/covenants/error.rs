@@ -42,4 +42,6 @@ pub enum CovenantError {
     InvalidArgument { filter: &'static str, details: String },
     #[error("Unsupported argument {arg}: {details}")]
     UnsupportedArgument { arg: &'static str, details: String },
+    #[error("The height at which the input was mined is required by filter {filter} but is not known")]
+    InputMinedHeightUnknown { filter: &'static str },
 }
@@ -39,7 +39,7 @@ use crate::{
         encoder::CovenentWriteExt,
         error::CovenantError,
     },
-    transactions::transaction_components::{TransactionInput, TransactionOutput},
+    transactions::transaction_components::{OutputFlags, TransactionInput, TransactionOutput},
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -81,6 +81,24 @@ impl OutputField {
         *self as u8
     }
 
+    /// Returns the field with the given name, as used in `@field::<name>`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        use OutputField::*;
+        match name {
+            "commitment" => Some(Commitment),
+            "script" => Some(Script),
+            "sender_offset_public_key" => Some(SenderOffsetPublicKey),
+            "covenant" => Some(Covenant),
+            "features" => Some(Features),
+            "features_flags" => Some(FeaturesFlags),
+            "features_maturity" => Some(FeaturesMaturity),
+            "features_unique_id" => Some(FeaturesUniqueId),
+            "features_parent_public_key" => Some(FeaturesParentPublicKey),
+            "features_metadata" => Some(FeaturesMetadata),
+            _ => None,
+        }
+    }
+
     pub fn get_field_value_ref<'a, T: 'static>(&self, output: &'a TransactionOutput) -> Option<&'a T> {
         use OutputField::*;
         let val = match self {
@@ -98,6 +116,17 @@ impl OutputField {
         val.downcast_ref::<T>()
     }
 
+    /// Returns the value of a numeric field as a u64, or None if the field is not numeric.
+    pub fn get_field_value_as_u64(&self, output: &TransactionOutput) -> Option<u64> {
+        self.get_field_value_ref::<u64>(output)
+            .copied()
+            .or_else(|| self.get_field_value_ref::<u32>(output).map(|v| u64::from(*v)))
+            .or_else(|| {
+                self.get_field_value_ref::<OutputFlags>(output)
+                    .map(|flags| u64::from(flags.bits()))
+            })
+    }
+
     pub fn get_field_value_bytes(&self, output: &TransactionOutput) -> Vec<u8> {
         use OutputField::*;
         match self {
@@ -44,10 +44,7 @@ impl Filter for FieldEqFilter {
                 TariScript(script) => field.is_eq(output, script),
                 Covenant(covenant) => field.is_eq(output, covenant),
                 Uint(int) => {
-                    let val = field
-                        .get_field_value_ref::<u64>(output)
-                        .copied()
-                        .or_else(|| field.get_field_value_ref::<u32>(output).map(|v| *v as u64));
+                    let val = field.get_field_value_as_u64(output);
 
                     match val {
                         Some(val) => Ok(val == *int),
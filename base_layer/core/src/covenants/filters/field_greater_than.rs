@@ -0,0 +1,81 @@
+//  Copyright 2022, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::covenants::{context::CovenantContext, error::CovenantError, filters::Filter, output_set::OutputSet};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldGreaterThanFilter;
+
+impl Filter for FieldGreaterThanFilter {
+    fn filter(&self, context: &mut CovenantContext<'_>, output_set: &mut OutputSet<'_>) -> Result<(), CovenantError> {
+        let field = context.next_arg()?.require_outputfield()?;
+        let val = context.next_arg()?.require_uint()?;
+        output_set.retain(|output| {
+            let field_val = field
+                .get_field_value_as_u64(output)
+                .ok_or_else(|| CovenantError::InvalidArgument {
+                    filter: "field_greater_than",
+                    details: format!("Uint argument cannot be compared to non-numeric field {}", field),
+                })?;
+            Ok(field_val > val)
+        })?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tari_test_utils::unpack_enum;
+
+    use super::*;
+    use crate::{
+        covenant,
+        covenants::{filters::test::setup_filter_test, test::create_input},
+    };
+
+    #[test]
+    fn it_filters_outputs_with_a_greater_maturity() {
+        let covenant = covenant!(field_greater_than(@field::features_maturity, @uint(42)));
+        let input = create_input();
+        let (mut context, outputs) = setup_filter_test(&covenant, &input, 0, |outputs| {
+            outputs[5].features.maturity = 42;
+            outputs[7].features.maturity = 43;
+            outputs[9].features.maturity = 100;
+        });
+        let mut output_set = OutputSet::new(&outputs);
+        FieldGreaterThanFilter.filter(&mut context, &mut output_set).unwrap();
+
+        assert_eq!(output_set.get_selected_indexes(), vec![7, 9]);
+    }
+
+    #[test]
+    fn it_errors_for_a_non_numeric_field() {
+        let covenant = covenant!(field_greater_than(@field::commitment, @uint(42)));
+        let input = create_input();
+        let (mut context, outputs) = setup_filter_test(&covenant, &input, 0, |_| {});
+        let mut output_set = OutputSet::new(&outputs);
+        let err = FieldGreaterThanFilter
+            .filter(&mut context, &mut output_set)
+            .unwrap_err();
+        unpack_enum!(CovenantError::InvalidArgument { .. } = err);
+    }
+}
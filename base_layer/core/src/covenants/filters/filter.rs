@@ -26,12 +26,16 @@ use super::{
     absolute_height::AbsoluteHeightFilter,
     and::AndFilter,
     field_eq::FieldEqFilter,
+    field_greater_than::FieldGreaterThanFilter,
+    field_less_than::FieldLessThanFilter,
     fields_hashed_eq::FieldsHashedEqFilter,
     fields_preserved::FieldsPreservedFilter,
     identity::IdentityFilter,
+    metadata_prefix::MetadataPrefixFilter,
     not::NotFilter,
     or::OrFilter,
     output_hash_eq::OutputHashEqFilter,
+    relative_height::RelativeHeightFilter,
     xor::XorFilter,
 };
 use crate::covenants::{
@@ -59,6 +63,10 @@ pub enum CovenantFilter {
     FieldEq(FieldEqFilter),
     FieldsHashedEq(FieldsHashedEqFilter),
     AbsoluteHeight(AbsoluteHeightFilter),
+    RelativeHeight(RelativeHeightFilter),
+    FieldGreaterThan(FieldGreaterThanFilter),
+    FieldLessThan(FieldLessThanFilter),
+    MetadataPrefix(MetadataPrefixFilter),
 }
 
 impl CovenantFilter {
@@ -85,6 +93,10 @@ impl CovenantFilter {
             FieldEq(_) => FILTER_FIELD_EQ,
             FieldsHashedEq(_) => FILTER_FIELDS_HASHED_EQ,
             AbsoluteHeight(_) => FILTER_ABSOLUTE_HEIGHT,
+            RelativeHeight(_) => FILTER_RELATIVE_HEIGHT,
+            FieldGreaterThan(_) => FILTER_FIELD_GREATER_THAN,
+            FieldLessThan(_) => FILTER_FIELD_LESS_THAN,
+            MetadataPrefix(_) => FILTER_METADATA_PREFIX,
         }
     }
 
@@ -101,10 +113,67 @@ impl CovenantFilter {
             FILTER_FIELD_EQ => Ok(Self::field_eq()),
             FILTER_FIELDS_HASHED_EQ => Ok(Self::fields_hashed_eq()),
             FILTER_ABSOLUTE_HEIGHT => Ok(Self::absolute_height()),
+            FILTER_RELATIVE_HEIGHT => Ok(Self::relative_height()),
+            FILTER_FIELD_GREATER_THAN => Ok(Self::field_greater_than()),
+            FILTER_FIELD_LESS_THAN => Ok(Self::field_less_than()),
+            FILTER_METADATA_PREFIX => Ok(Self::metadata_prefix()),
             _ => Err(CovenantDecodeError::UnknownFilterByteCode { code }),
         }
     }
 
+    /// Returns the name of the filter as used in the `covenant!` macro and the textual covenant syntax.
+    pub fn name(&self) -> &'static str {
+        use CovenantFilter::*;
+        match self {
+            Identity(_) => "identity",
+            And(_) => "and",
+            Or(_) => "or",
+            Xor(_) => "xor",
+            Not(_) => "not",
+            OutputHashEq(_) => "output_hash_eq",
+            FieldsPreserved(_) => "fields_preserved",
+            FieldEq(_) => "field_eq",
+            FieldsHashedEq(_) => "fields_hashed_eq",
+            AbsoluteHeight(_) => "absolute_height",
+            RelativeHeight(_) => "relative_height",
+            FieldGreaterThan(_) => "field_greater_than",
+            FieldLessThan(_) => "field_less_than",
+            MetadataPrefix(_) => "metadata_prefix",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "identity" => Some(Self::identity()),
+            "and" => Some(Self::and()),
+            "or" => Some(Self::or()),
+            "xor" => Some(Self::xor()),
+            "not" => Some(Self::not()),
+            "output_hash_eq" => Some(Self::output_hash_eq()),
+            "fields_preserved" => Some(Self::fields_preserved()),
+            "field_eq" => Some(Self::field_eq()),
+            "fields_hashed_eq" => Some(Self::fields_hashed_eq()),
+            "absolute_height" => Some(Self::absolute_height()),
+            "relative_height" => Some(Self::relative_height()),
+            "field_greater_than" => Some(Self::field_greater_than()),
+            "field_less_than" => Some(Self::field_less_than()),
+            "metadata_prefix" => Some(Self::metadata_prefix()),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of tokens (filters or arguments) that directly follow this filter.
+    pub fn arity(&self) -> usize {
+        use CovenantFilter::*;
+        match self {
+            Identity(_) => 0,
+            And(_) | Or(_) | Xor(_) => 2,
+            Not(_) => 1,
+            OutputHashEq(_) | FieldsPreserved(_) | AbsoluteHeight(_) | RelativeHeight(_) | MetadataPrefix(_) => 1,
+            FieldEq(_) | FieldsHashedEq(_) | FieldGreaterThan(_) | FieldLessThan(_) => 2,
+        }
+    }
+
     pub fn identity() -> Self {
         CovenantFilter::Identity(IdentityFilter)
     }
@@ -144,10 +213,37 @@ impl CovenantFilter {
     pub fn absolute_height() -> Self {
         CovenantFilter::AbsoluteHeight(AbsoluteHeightFilter)
     }
+
+    pub fn relative_height() -> Self {
+        CovenantFilter::RelativeHeight(RelativeHeightFilter)
+    }
+
+    pub fn field_greater_than() -> Self {
+        CovenantFilter::FieldGreaterThan(FieldGreaterThanFilter)
+    }
+
+    pub fn field_less_than() -> Self {
+        CovenantFilter::FieldLessThan(FieldLessThanFilter)
+    }
+
+    pub fn metadata_prefix() -> Self {
+        CovenantFilter::MetadataPrefix(MetadataPrefixFilter)
+    }
 }
 
 impl Filter for CovenantFilter {
     fn filter(&self, context: &mut CovenantContext<'_>, output_set: &mut OutputSet<'_>) -> Result<(), CovenantError> {
+        let trace = context.start_filter_trace(self, output_set);
+        let result = self.apply(context, output_set);
+        if let Some(trace) = trace {
+            context.end_filter_trace(trace, output_set);
+        }
+        result
+    }
+}
+
+impl CovenantFilter {
+    fn apply(&self, context: &mut CovenantContext<'_>, output_set: &mut OutputSet<'_>) -> Result<(), CovenantError> {
         use CovenantFilter::*;
         match self {
             Identity(identity) => identity.filter(context, output_set),
@@ -160,6 +256,10 @@ impl Filter for CovenantFilter {
             FieldEq(fields_eq) => fields_eq.filter(context, output_set),
             FieldsHashedEq(fields_hashed_eq) => fields_hashed_eq.filter(context, output_set),
             AbsoluteHeight(abs_height) => abs_height.filter(context, output_set),
+            RelativeHeight(rel_height) => rel_height.filter(context, output_set),
+            FieldGreaterThan(field_gt) => field_gt.filter(context, output_set),
+            FieldLessThan(field_lt) => field_lt.filter(context, output_set),
+            MetadataPrefix(metadata_prefix) => metadata_prefix.filter(context, output_set),
         }
     }
 }
@@ -23,23 +23,31 @@
 mod absolute_height;
 mod and;
 mod field_eq;
+mod field_greater_than;
+mod field_less_than;
 mod fields_hashed_eq;
 mod fields_preserved;
 mod identity;
+mod metadata_prefix;
 mod not;
 mod or;
 mod output_hash_eq;
+mod relative_height;
 mod xor;
 
 pub use absolute_height::AbsoluteHeightFilter;
 pub use and::AndFilter;
 pub use field_eq::FieldEqFilter;
+pub use field_greater_than::FieldGreaterThanFilter;
+pub use field_less_than::FieldLessThanFilter;
 pub use fields_hashed_eq::FieldsHashedEqFilter;
 pub use fields_preserved::FieldsPreservedFilter;
 pub use identity::IdentityFilter;
+pub use metadata_prefix::MetadataPrefixFilter;
 pub use not::NotFilter;
 pub use or::OrFilter;
 pub use output_hash_eq::OutputHashEqFilter;
+pub use relative_height::RelativeHeightFilter;
 pub use xor::XorFilter;
 
 mod filter;
@@ -0,0 +1,91 @@
+//  Copyright 2022, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::covenants::{context::CovenantContext, error::CovenantError, filters::Filter, output_set::OutputSet};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelativeHeightFilter;
+
+impl Filter for RelativeHeightFilter {
+    fn filter(&self, context: &mut CovenantContext<'_>, output_set: &mut OutputSet<'_>) -> Result<(), CovenantError> {
+        let rel_height = context.next_arg()?.require_uint()?;
+        // The mined height of the input is only known when the covenant is executed against the chain
+        let mined_height = context
+            .input_mined_height()
+            .ok_or(CovenantError::InputMinedHeightUnknown {
+                filter: "relative_height",
+            })?;
+        if context.block_height() < mined_height.saturating_add(rel_height) {
+            output_set.clear();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        covenant,
+        covenants::{filters::test::setup_filter_test, test::create_input},
+    };
+
+    #[test]
+    fn it_filters_all_out_if_relative_height_not_reached() {
+        let covenant = covenant!(relative_height(@uint(10)));
+        let input = create_input();
+        let (context, outputs) = setup_filter_test(&covenant, &input, 109, |_| {});
+        let mut context = context.with_input_mined_height(100);
+
+        let mut output_set = OutputSet::new(&outputs);
+        RelativeHeightFilter.filter(&mut context, &mut output_set).unwrap();
+
+        assert!(output_set.is_empty());
+    }
+
+    #[test]
+    fn it_filters_all_in_if_relative_height_reached() {
+        let covenant = covenant!(relative_height(@uint(10)));
+        let input = create_input();
+        let (context, outputs) = setup_filter_test(&covenant, &input, 110, |_| {});
+        let mut context = context.with_input_mined_height(100);
+
+        let mut output_set = OutputSet::new(&outputs);
+        RelativeHeightFilter.filter(&mut context, &mut output_set).unwrap();
+
+        assert_eq!(output_set.len(), 10);
+    }
+
+    #[test]
+    fn it_errors_if_mined_height_unknown() {
+        let covenant = covenant!(relative_height(@uint(10)));
+        let input = create_input();
+        let (mut context, outputs) = setup_filter_test(&covenant, &input, 110, |_| {});
+
+        let mut output_set = OutputSet::new(&outputs);
+        let err = RelativeHeightFilter.filter(&mut context, &mut output_set).unwrap_err();
+
+        assert!(matches!(err, CovenantError::InputMinedHeightUnknown {
+            filter: "relative_height"
+        }));
+    }
+}
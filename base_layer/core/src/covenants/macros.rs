@@ -113,7 +113,8 @@ macro_rules! __covenant_inner {
     // token(filter1, filter2, ...), ...
     (@ { $covenant:ident } $token:ident($($args:tt)+), $($tail:tt)*) => {
         $covenant.push_token($crate::covenants::CovenantToken::$token());
-        $crate::__covenant_inner!(@ { $covenant } $($args)+ $($tail)*)
+        $crate::__covenant_inner!(@ { $covenant } $($args)+);
+        $crate::__covenant_inner!(@ { $covenant } $($tail)*)
     };
 
     // token(...)
@@ -204,6 +205,23 @@ mod test {
         assert_eq!(covenant.to_bytes().to_hex(), "330703050a213307070903bada5520");
     }
 
+    #[test]
+    fn height_comparison_and_prefix_filters() {
+        let covenant = covenant!(and(
+            relative_height(@uint(10)),
+            or(
+                field_greater_than(@field::features_maturity, @uint(42)),
+                metadata_prefix(@bytes(vec![0xca, 0xfe]))
+            )
+        ));
+        assert_eq!(covenant.to_bytes().to_hex(), "2135060a22360706062a380902cafe");
+
+        let covenant = covenant!(field_less_than(@field::features_flags, @uint(1)));
+        assert_eq!(covenant.to_bytes().to_hex(), "3707050601");
+        let decoded = Covenant::from_bytes(&covenant.to_bytes()).unwrap();
+        assert_eq!(covenant, decoded);
+    }
+
     #[test]
     fn script() {
         let hash = "53563b674ba8e5166adb57afa8355bcf2ee759941eef8f8959b802367c2558bd";
@@ -36,16 +36,20 @@ mod error;
 mod fields;
 mod filters;
 mod output_set;
+mod parser;
 mod serde;
 mod token;
+mod trace;
 
 pub use covenant::Covenant;
 pub use error::CovenantError;
 // Used in macro
 #[allow(unused_imports)]
 pub(crate) use fields::OutputField;
+pub use parser::CovenantParseError;
 #[allow(unused_imports)]
 pub(crate) use token::CovenantToken;
+pub use trace::{CovenantFilterTrace, CovenantTrace};
 
 #[macro_use]
 mod macros;
@@ -106,7 +106,6 @@ impl<'a> OutputSet<'a> {
             .map(|output| **output)
     }
 
-    #[cfg(test)]
     pub(super) fn get_selected_indexes(&self) -> Vec<usize> {
         self.0.iter().map(|idx| idx.index).collect()
     }
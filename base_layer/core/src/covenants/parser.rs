@@ -0,0 +1,422 @@
+//  Copyright 2022, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use tari_common_types::types::{Commitment, PublicKey};
+use tari_crypto::script::TariScript;
+use tari_utilities::hex::{from_hex, Hex};
+
+use crate::covenants::{
+    arguments::{CovenantArg, Hash},
+    fields::{OutputField, OutputFields},
+    filters::CovenantFilter,
+    token::CovenantToken,
+    Covenant,
+};
+
+/// Error returned when the textual representation of a covenant cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Covenant parse error at position {position}: {details}")]
+pub struct CovenantParseError {
+    position: usize,
+    details: String,
+}
+
+impl CovenantParseError {
+    fn new<T: Into<String>>(position: usize, details: T) -> Self {
+        Self {
+            position,
+            details: details.into(),
+        }
+    }
+
+    /// The byte offset into the input at which the error occurred
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn details(&self) -> &str {
+        &self.details
+    }
+}
+
+/// Parses the textual covenant syntax, e.g. `and(field_eq(@field::features_flags, @uint(1)),
+/// absolute_height(@uint(100)))`. Filters are written as `name(arg, ...)` and arguments as `@type(value)`, where
+/// hashes, keys, commitments, scripts and bytes are hex encoded.
+pub(super) struct CovenantParser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> CovenantParser<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self { input, pos: 0 }
+    }
+
+    pub fn parse(mut self) -> Result<Covenant, CovenantParseError> {
+        let covenant = self.parse_covenant()?;
+        self.skip_whitespace();
+        if !self.is_at_end() {
+            return Err(CovenantParseError::new(self.pos, "unexpected trailing input"));
+        }
+        Ok(covenant)
+    }
+
+    fn parse_covenant(&mut self) -> Result<Covenant, CovenantParseError> {
+        let mut covenant = Covenant::new();
+        self.skip_whitespace();
+        // An empty covenant has no tokens
+        if self.is_at_end() || self.peek() == Some(')') {
+            return Ok(covenant);
+        }
+        self.parse_filter(&mut covenant)?;
+        Ok(covenant)
+    }
+
+    fn parse_filter(&mut self, covenant: &mut Covenant) -> Result<(), CovenantParseError> {
+        self.skip_whitespace();
+        let (start, name) = self.read_identifier("filter")?;
+        let filter = CovenantFilter::from_name(name)
+            .ok_or_else(|| CovenantParseError::new(start, format!("unknown filter `{}`", name)))?;
+        let arity = filter.arity();
+        covenant.push_token(CovenantToken::Filter(filter));
+
+        self.skip_whitespace();
+        self.expect('(')?;
+        let mut num_args = 0;
+        self.skip_whitespace();
+        if !self.consume(')') {
+            loop {
+                self.parse_filter_arg(covenant)?;
+                num_args += 1;
+                self.skip_whitespace();
+                if self.consume(',') {
+                    self.skip_whitespace();
+                    // Allow a trailing comma
+                    if self.consume(')') {
+                        break;
+                    }
+                    continue;
+                }
+                self.expect(')')?;
+                break;
+            }
+        }
+
+        if num_args != arity {
+            return Err(CovenantParseError::new(
+                start,
+                format!("`{}` expects {} argument(s) but got {}", name, arity, num_args),
+            ));
+        }
+        Ok(())
+    }
+
+    fn parse_filter_arg(&mut self, covenant: &mut Covenant) -> Result<(), CovenantParseError> {
+        self.skip_whitespace();
+        if self.consume('@') {
+            let arg = self.parse_arg()?;
+            covenant.push_token(CovenantToken::Arg(arg));
+            Ok(())
+        } else {
+            self.parse_filter(covenant)
+        }
+    }
+
+    fn parse_arg(&mut self) -> Result<CovenantArg, CovenantParseError> {
+        let (start, name) = self.read_identifier("argument type")?;
+        match name {
+            "field" => Ok(CovenantArg::OutputField(self.parse_field_name()?)),
+            "fields" => {
+                self.expect('(')?;
+                let mut fields = OutputFields::new();
+                loop {
+                    self.skip_whitespace();
+                    if self.consume(')') {
+                        break;
+                    }
+                    self.expect('@')?;
+                    let (pos, name) = self.read_identifier("`field`")?;
+                    if name != "field" {
+                        return Err(CovenantParseError::new(
+                            pos,
+                            format!("expected `field` but got `{}`", name),
+                        ));
+                    }
+                    fields.push(self.parse_field_name()?);
+                    self.skip_whitespace();
+                    if !self.consume(',') {
+                        self.expect(')')?;
+                        break;
+                    }
+                }
+                Ok(CovenantArg::OutputFields(fields))
+            },
+            "covenant" => {
+                self.expect('(')?;
+                let covenant = self.parse_covenant()?;
+                self.skip_whitespace();
+                self.expect(')')?;
+                Ok(CovenantArg::Covenant(covenant))
+            },
+            "uint" => {
+                let (pos, value) = self.read_value()?;
+                let v = value
+                    .parse::<u64>()
+                    .map_err(|e| CovenantParseError::new(pos, format!("invalid uint `{}`: {}", value, e)))?;
+                Ok(CovenantArg::Uint(v))
+            },
+            "hash" => {
+                let (pos, bytes) = self.read_hex_value()?;
+                if bytes.len() != 32 {
+                    return Err(CovenantParseError::new(
+                        pos,
+                        format!("hash must be 32 bytes but got {} byte(s)", bytes.len()),
+                    ));
+                }
+                let mut hash: Hash = [0u8; 32];
+                hash.copy_from_slice(&bytes);
+                Ok(CovenantArg::Hash(hash))
+            },
+            "public_key" => {
+                let (pos, value) = self.read_value()?;
+                let public_key = PublicKey::from_hex(value)
+                    .map_err(|e| CovenantParseError::new(pos, format!("invalid public key: {}", e)))?;
+                Ok(CovenantArg::PublicKey(public_key))
+            },
+            "commitment" => {
+                let (pos, value) = self.read_value()?;
+                let commitment = Commitment::from_hex(value)
+                    .map_err(|e| CovenantParseError::new(pos, format!("invalid commitment: {}", e)))?;
+                Ok(CovenantArg::Commitment(commitment))
+            },
+            "script" => {
+                let (pos, bytes) = self.read_hex_value()?;
+                let script = TariScript::from_bytes(&bytes)
+                    .map_err(|e| CovenantParseError::new(pos, format!("invalid script: {}", e)))?;
+                Ok(CovenantArg::TariScript(script))
+            },
+            "bytes" => {
+                let (_, bytes) = self.read_hex_value()?;
+                Ok(CovenantArg::Bytes(bytes))
+            },
+            _ => Err(CovenantParseError::new(
+                start,
+                format!("unknown argument type `@{}`", name),
+            )),
+        }
+    }
+
+    /// Parses the `::name` part of `@field::name`
+    fn parse_field_name(&mut self) -> Result<OutputField, CovenantParseError> {
+        self.expect(':')?;
+        self.expect(':')?;
+        let (pos, name) = self.read_identifier("field name")?;
+        OutputField::from_name(name).ok_or_else(|| CovenantParseError::new(pos, format!("unknown field `{}`", name)))
+    }
+
+    /// Reads the `(value)` part of an argument, returning the position and (possibly empty) value
+    fn read_value(&mut self) -> Result<(usize, &'a str), CovenantParseError> {
+        self.expect('(')?;
+        self.skip_whitespace();
+        let start = self.pos;
+        let value = self.take_while(is_identifier_char);
+        self.skip_whitespace();
+        self.expect(')')?;
+        Ok((start, value))
+    }
+
+    fn read_hex_value(&mut self) -> Result<(usize, Vec<u8>), CovenantParseError> {
+        let (pos, value) = self.read_value()?;
+        let bytes = from_hex(value).map_err(|e| CovenantParseError::new(pos, format!("invalid hex: {}", e)))?;
+        Ok((pos, bytes))
+    }
+
+    fn read_identifier(&mut self, expected: &str) -> Result<(usize, &'a str), CovenantParseError> {
+        let start = self.pos;
+        let ident = self.take_while(is_identifier_char);
+        if ident.is_empty() {
+            return Err(self.unexpected(expected));
+        }
+        Ok((start, ident))
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), CovenantParseError> {
+        if self.consume(c) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("'{}'", c)))
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> CovenantParseError {
+        match self.peek() {
+            Some(c) => CovenantParseError::new(self.pos, format!("expected {} but got '{}'", expected, c)),
+            None => CovenantParseError::new(self.pos, format!("expected {} but reached the end of input", expected)),
+        }
+    }
+
+    fn consume(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn take_while<F: Fn(char) -> bool>(&mut self, predicate: F) -> &'a str {
+        let start = self.pos;
+        let len = self.input[start..]
+            .find(|c| !predicate(c))
+            .unwrap_or_else(|| self.input.len() - start);
+        self.pos += len;
+        &self.input[start..self.pos]
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.take_while(char::is_whitespace);
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn is_at_end(&self) -> bool {
+        self.pos >= self.input.len()
+    }
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use tari_crypto::script;
+
+    use super::*;
+    use crate::covenant;
+
+    fn parse_err(s: &str) -> CovenantParseError {
+        Covenant::from_str(s).unwrap_err()
+    }
+
+    #[test]
+    fn it_parses_the_empty_covenant() {
+        assert_eq!(Covenant::from_str("").unwrap(), covenant!());
+        assert_eq!(Covenant::from_str("  ").unwrap(), covenant!());
+        assert_eq!(covenant!().to_string(), "");
+    }
+
+    #[test]
+    fn it_parses_a_covenant() {
+        let covenant =
+            Covenant::from_str("and(field_eq(@field::features_flags, @uint(1)), absolute_height(@uint(100)))").unwrap();
+        assert_eq!(
+            covenant,
+            covenant!(and(
+                field_eq(@field::features_flags, @uint(1)),
+                absolute_height(@uint(100))
+            ))
+        );
+    }
+
+    #[test]
+    fn it_ignores_whitespace_and_trailing_commas() {
+        let covenant = Covenant::from_str(" or ( identity( ) ,\n\tnot(identity(),), ) ").unwrap();
+        assert_eq!(covenant, covenant!(or(identity(), not(identity()))));
+    }
+
+    #[test]
+    fn it_round_trips_all_argument_types() {
+        let hash = [7u8; 32];
+        let public_key =
+            PublicKey::from_hex("5615a327e1d19da34e5aa8bbd2ecc97addf29b158844b885bfc4efa0dab17052").unwrap();
+        let commitment =
+            Commitment::from_hex("7ca31ba517d8b563609ed6707fedde5a2be64ac1d67b254cb5348bc2f680557f").unwrap();
+        let covenants = vec![
+            covenant!(output_hash_eq(@hash(hash))),
+            covenant!(field_eq(@field::features_parent_public_key, @public_key(public_key))),
+            covenant!(field_eq(@field::commitment, @commitment(commitment))),
+            covenant!(field_eq(@field::script, @script(script!(CheckHeight(100) Drop Nop)))),
+            covenant!(fields_preserved(@fields(@field::features, @field::covenant, @field::script))),
+            covenant!(fields_hashed_eq(@fields(@field::commitment), @hash(hash))),
+            covenant!(field_eq(@field::covenant, @covenant(and(identity(), relative_height(@uint(10)))))),
+            covenant!(field_eq(@field::covenant, @covenant())),
+            covenant!(xor(
+                metadata_prefix(@bytes(vec![0xca, 0xfe])),
+                field_less_than(@field::features_maturity, @uint(u64::MAX))
+            )),
+            covenant!(field_eq(@field::features_unique_id, @bytes(vec![]))),
+        ];
+
+        for covenant in covenants {
+            let text = covenant.to_string();
+            let parsed = Covenant::from_str(&text).unwrap();
+            assert_eq!(parsed, covenant, "round trip failed for {}", text);
+        }
+    }
+
+    #[test]
+    fn it_displays_the_textual_syntax() {
+        let covenant = covenant!(and(
+            field_eq(@field::features_flags, @uint(1)),
+            fields_preserved(@fields(@field::features, @field::script))
+        ));
+        assert_eq!(
+            covenant.to_string(),
+            "and(field_eq(@field::features_flags, @uint(1)), fields_preserved(@fields(@field::features, \
+             @field::script)))"
+        );
+    }
+
+    #[test]
+    fn it_reports_the_position_of_errors() {
+        let err = parse_err("and(identity(), foo())");
+        assert_eq!(err.position(), 16);
+        assert_eq!(err.details(), "unknown filter `foo`");
+
+        let err = parse_err("field_eq(@field::nope, @uint(1))");
+        assert_eq!(err.position(), 17);
+
+        let err = parse_err("field_eq(@field::features_maturity, @uint(abc))");
+        assert_eq!(err.position(), 42);
+
+        let err = parse_err("absolute_height(@int(1))");
+        assert_eq!(err.position(), 17);
+
+        let err = parse_err("and(identity())");
+        assert_eq!(err.position(), 0);
+        assert_eq!(err.details(), "`and` expects 2 argument(s) but got 1");
+
+        let err = parse_err("identity() identity()");
+        assert_eq!(err.position(), 11);
+
+        let err = parse_err("not(identity()");
+        assert_eq!(err.position(), 14);
+
+        let err = parse_err("output_hash_eq(@hash(0102))");
+        assert_eq!(err.position(), 21);
+    }
+}
@@ -34,12 +34,16 @@ use crate::covenants::{
         AndFilter,
         CovenantFilter,
         FieldEqFilter,
+        FieldGreaterThanFilter,
+        FieldLessThanFilter,
         FieldsHashedEqFilter,
         FieldsPreservedFilter,
         IdentityFilter,
+        MetadataPrefixFilter,
         NotFilter,
         OrFilter,
         OutputHashEqFilter,
+        RelativeHeightFilter,
         XorFilter,
     },
     Covenant,
@@ -144,6 +148,26 @@ impl CovenantToken {
         CovenantToken::Filter(CovenantFilter::AbsoluteHeight(AbsoluteHeightFilter))
     }
 
+    #[allow(dead_code)]
+    pub fn relative_height() -> Self {
+        CovenantToken::Filter(CovenantFilter::RelativeHeight(RelativeHeightFilter))
+    }
+
+    #[allow(dead_code)]
+    pub fn field_greater_than() -> Self {
+        CovenantToken::Filter(CovenantFilter::FieldGreaterThan(FieldGreaterThanFilter))
+    }
+
+    #[allow(dead_code)]
+    pub fn field_less_than() -> Self {
+        CovenantToken::Filter(CovenantFilter::FieldLessThan(FieldLessThanFilter))
+    }
+
+    #[allow(dead_code)]
+    pub fn metadata_prefix() -> Self {
+        CovenantToken::Filter(CovenantFilter::MetadataPrefix(MetadataPrefixFilter))
+    }
+
     #[allow(dead_code)]
     pub fn hash(hash: Hash) -> Self {
         CovenantToken::Arg(CovenantArg::Hash(hash))
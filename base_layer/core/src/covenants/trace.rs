@@ -0,0 +1,54 @@
+//  Copyright 2022, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+/// The result of executing a covenant with tracing enabled. Every filter that was evaluated is recorded in the order it
+/// was encountered, which is the order the filters appear in the covenant.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CovenantTrace {
+    /// The filters that were evaluated. Filters that were not reached because of an earlier error are not included.
+    pub filters: Vec<CovenantFilterTrace>,
+    /// The number of outputs remaining after the covenant was executed
+    pub output_set_size: usize,
+    /// The reason the covenant failed, or None if it passed
+    pub error: Option<String>,
+}
+
+impl CovenantTrace {
+    pub fn is_pass(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// The effect that a single filter had on the output set it was given
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CovenantFilterTrace {
+    /// The name of the filter in the textual covenant syntax e.g. `field_eq`
+    pub filter: &'static str,
+    /// The nesting depth of the filter, where the root filter has a depth of 0
+    pub depth: usize,
+    /// The number of outputs in the set before the filter was applied
+    pub input_set_size: usize,
+    /// The indexes (into the transaction outputs) of the outputs removed by this filter
+    pub eliminated_outputs: Vec<usize>,
+    /// The number of outputs in the set after the filter was applied
+    pub output_set_size: usize,
+}
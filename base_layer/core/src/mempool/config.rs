@@ -23,13 +23,18 @@
 use serde::{Deserialize, Serialize};
 use tari_common::NetworkConfigPath;
 
-use crate::mempool::{reorg_pool::ReorgPoolConfig, unconfirmed_pool::UnconfirmedPoolConfig};
+use crate::mempool::{
+    fee_estimator::FeeEstimatorConfig,
+    reorg_pool::ReorgPoolConfig,
+    unconfirmed_pool::UnconfirmedPoolConfig,
+};
 
 /// Configuration for the Mempool.
 #[derive(Clone, Copy, Deserialize, Serialize, Default)]
 pub struct MempoolConfig {
     pub unconfirmed_pool: UnconfirmedPoolConfig,
     pub reorg_pool: ReorgPoolConfig,
+    pub fee_estimator: FeeEstimatorConfig,
 }
 
 impl NetworkConfigPath for MempoolConfig {
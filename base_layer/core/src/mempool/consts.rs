@@ -26,3 +26,25 @@ pub const MEMPOOL_UNCONFIRMED_POOL_STORAGE_CAPACITY: usize = 40_000;
 /// The maximum number of transactions that can be skipped when compiling a set of highest priority transactions,
 /// skipping over large transactions are performed in an attempt to fit more transactions into the remaining space.
 pub const MEMPOOL_UNCONFIRMED_POOL_WEIGHT_TRANSACTION_SKIP_COUNT: usize = 20;
+
+/// The maximum number of transactions that a single replace-by-fee transaction may evict from the Unconfirmed
+/// Transaction pool, counting the conflicting transactions and all of their descendants.
+pub const MEMPOOL_UNCONFIRMED_POOL_MAX_REPLACEMENT_EVICTIONS: usize = 100;
+
+/// The maximum number of transactions in the Unconfirmed Transaction pool that a transaction and its unconfirmed
+/// ancestors may form, counting the transaction itself.
+pub const MEMPOOL_UNCONFIRMED_POOL_MAX_ANCESTOR_COUNT: usize = 25;
+
+/// The maximum number of transactions in the Unconfirmed Transaction pool that a transaction and its unconfirmed
+/// descendants may form, counting the transaction itself.
+pub const MEMPOOL_UNCONFIRMED_POOL_MAX_DESCENDANT_COUNT: usize = 25;
+
+/// The largest confirmation target, in blocks, for which the fee estimator keeps statistics
+pub const MEMPOOL_FEE_ESTIMATOR_MAX_TARGET_BLOCKS: u64 = 30;
+
+/// The factor by which the fee estimator scales down its historical observations with every published block
+pub const MEMPOOL_FEE_ESTIMATOR_DECAY: f64 = 0.998;
+
+/// The fraction of transactions in a fee bucket that must have been mined within the target for the fee estimator to
+/// consider the bucket sufficient for that target
+pub const MEMPOOL_FEE_ESTIMATOR_SUCCESS_THRESHOLD: f64 = 0.85;
@@ -40,4 +40,6 @@ pub enum MempoolError {
     RwLockPoisonError,
     #[error(transparent)]
     BlockingTaskError(#[from] JoinError),
+    #[error("Mempool persistence error: `{0}`")]
+    PersistenceError(String),
 }
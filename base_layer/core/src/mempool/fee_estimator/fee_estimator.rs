@@ -0,0 +1,359 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{collections::HashMap, sync::Arc};
+
+use log::*;
+use serde::{Deserialize, Serialize};
+use tari_common_types::types::PrivateKey;
+
+use crate::{
+    mempool::consts::{
+        MEMPOOL_FEE_ESTIMATOR_DECAY,
+        MEMPOOL_FEE_ESTIMATOR_MAX_TARGET_BLOCKS,
+        MEMPOOL_FEE_ESTIMATOR_SUCCESS_THRESHOLD,
+    },
+    transactions::{tari_amount::MicroTari, transaction_components::Transaction, weight::TransactionWeight},
+};
+
+pub const LOG_TARGET: &str = "c::mp::fee_estimator";
+
+/// The lower bound, in µT per gram, of the cheapest fee bucket
+const MIN_BUCKET_FEE_PER_GRAM: f64 = 1.0;
+/// The lower bound, in µT per gram, above which no further fee buckets are created
+const MAX_BUCKET_FEE_PER_GRAM: f64 = 100_000.0;
+/// The ratio between the lower bounds of consecutive fee buckets
+const BUCKET_SPACING: f64 = 1.1;
+
+/// Configuration for the FeeEstimator
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct FeeEstimatorConfig {
+    /// The largest confirmation target, in blocks, that estimates can be requested for. Transactions that stay in the
+    /// mempool for longer than this are counted as not having been mined in time.
+    pub max_target_blocks: u64,
+    /// The factor by which historical observations are scaled down with every published block, so that estimates
+    /// follow recent fee market conditions.
+    pub decay: f64,
+    /// The fraction of transactions in a fee bucket that must have been mined within the target for the bucket to be
+    /// considered sufficient for that target.
+    pub success_threshold: f64,
+    /// The minimum (decayed) number of observations required before a group of fee buckets is evaluated.
+    pub min_samples: f64,
+}
+
+impl Default for FeeEstimatorConfig {
+    fn default() -> Self {
+        Self {
+            max_target_blocks: MEMPOOL_FEE_ESTIMATOR_MAX_TARGET_BLOCKS,
+            decay: MEMPOOL_FEE_ESTIMATOR_DECAY,
+            success_threshold: MEMPOOL_FEE_ESTIMATOR_SUCCESS_THRESHOLD,
+            min_samples: 1.0,
+        }
+    }
+}
+
+struct TrackedTransaction {
+    entry_height: u64,
+    bucket: usize,
+}
+
+/// The FeeEstimator groups transactions entering the unconfirmed pool into fee per gram buckets and records how many
+/// blocks it took for them to be mined. An estimate for a confirmation target is the lowest fee bucket for which
+/// (together with all higher buckets) at least `success_threshold` of the observed transactions were mined within the
+/// target.
+pub struct FeeEstimator {
+    config: FeeEstimatorConfig,
+    bucket_bounds: Vec<u64>,
+    /// Decayed count of transactions per bucket that were mined within `target` blocks, indexed by `[target - 1]`.
+    confirmed: Vec<Vec<f64>>,
+    /// Decayed count of transactions per bucket that were either mined or expired.
+    totals: Vec<f64>,
+    tracked: HashMap<PrivateKey, TrackedTransaction>,
+    best_height: Option<u64>,
+}
+
+impl FeeEstimator {
+    /// Create a new FeeEstimator with the specified configuration
+    pub fn new(config: FeeEstimatorConfig) -> Self {
+        let mut bucket_bounds = Vec::new();
+        let mut bound = MIN_BUCKET_FEE_PER_GRAM;
+        while bound <= MAX_BUCKET_FEE_PER_GRAM {
+            let rounded = bound.round() as u64;
+            if bucket_bounds.last() != Some(&rounded) {
+                bucket_bounds.push(rounded);
+            }
+            bound *= BUCKET_SPACING;
+        }
+        let max_target_blocks = config.max_target_blocks.max(1) as usize;
+        Self {
+            config,
+            confirmed: vec![vec![0.0; bucket_bounds.len()]; max_target_blocks],
+            totals: vec![0.0; bucket_bounds.len()],
+            bucket_bounds,
+            tracked: HashMap::new(),
+            best_height: None,
+        }
+    }
+
+    /// Start tracking a transaction that was accepted into the unconfirmed pool. Transactions are only tracked once the
+    /// estimator has seen a published block, as the height at which they entered the mempool is not known before
+    /// then. A transaction that is already tracked keeps its original entry height.
+    pub fn track_transaction(&mut self, transaction: &Transaction, weighting: &TransactionWeight) {
+        let height = match self.best_height {
+            Some(height) => height,
+            None => return,
+        };
+        let excess_sig = match transaction.first_kernel_excess_sig() {
+            Some(sig) => sig.get_signature().clone(),
+            None => return,
+        };
+        let weight = transaction.calculate_weight(weighting);
+        if weight == 0 {
+            return;
+        }
+        let fee_per_gram = transaction.body.get_total_fee().as_u64() as f64 / weight as f64;
+        let bucket = self.bucket_index(fee_per_gram);
+        self.tracked.entry(excess_sig).or_insert(TrackedTransaction {
+            entry_height: height,
+            bucket,
+        });
+    }
+
+    /// Record the number of blocks each of the `published` transactions took to be mined and expire tracked
+    /// transactions that have been waiting for longer than the maximum target.
+    pub fn process_published_block(&mut self, height: u64, published: &[Arc<Transaction>]) {
+        self.decay();
+
+        for tx in published {
+            let tracked = tx
+                .first_kernel_excess_sig()
+                .and_then(|sig| self.tracked.remove(sig.get_signature()));
+            if let Some(tracked) = tracked {
+                let blocks_to_confirm = height.saturating_sub(tracked.entry_height).max(1);
+                self.record(tracked.bucket, Some(blocks_to_confirm));
+            }
+        }
+
+        let max_target_blocks = self.max_target_blocks();
+        let expired = self
+            .tracked
+            .iter()
+            .filter(|(_, tracked)| height.saturating_sub(tracked.entry_height) > max_target_blocks)
+            .map(|(sig, _)| sig.clone())
+            .collect::<Vec<_>>();
+        for sig in expired {
+            if let Some(tracked) = self.tracked.remove(&sig) {
+                self.record(tracked.bucket, None);
+            }
+        }
+
+        self.best_height = Some(height);
+        trace!(
+            target: LOG_TARGET,
+            "Fee estimator processed block #{}, tracking {} transaction(s)",
+            height,
+            self.tracked.len()
+        );
+    }
+
+    /// Stop tracking transactions that left the unconfirmed pool without being mined, such as double spends of
+    /// transactions in a published block or transactions that were replaced or evicted from a full pool. They are
+    /// neither counted as mined nor as expired.
+    pub fn untrack_transactions(&mut self, transactions: &[Arc<Transaction>]) {
+        for tx in transactions {
+            if let Some(sig) = tx.first_kernel_excess_sig() {
+                self.tracked.remove(sig.get_signature());
+            }
+        }
+    }
+
+    /// Returns the lowest fee per gram that is expected to get a transaction mined within `target_blocks` blocks, or
+    /// None if there is not yet enough data to make an estimate. Targets are clamped to `[1, max_target_blocks]`.
+    pub fn estimate_fee_per_gram(&self, target_blocks: u64) -> Option<MicroTari> {
+        let target = target_blocks.clamp(1, self.max_target_blocks()) as usize;
+        let confirmed = &self.confirmed[target - 1];
+
+        let mut estimate = None;
+        let mut group_confirmed = 0.0;
+        let mut group_total = 0.0;
+        // Walk down from the most expensive bucket, merging sparse buckets until there are enough samples to judge
+        for bucket in (0..self.bucket_bounds.len()).rev() {
+            group_confirmed += confirmed[bucket];
+            group_total += self.totals[bucket];
+            if group_total < self.config.min_samples {
+                continue;
+            }
+            if group_confirmed / group_total < self.config.success_threshold {
+                break;
+            }
+            estimate = Some(bucket);
+            group_confirmed = 0.0;
+            group_total = 0.0;
+        }
+
+        estimate.map(|bucket| MicroTari::from(self.bucket_bounds[bucket]))
+    }
+
+    /// Returns the number of transactions currently being tracked
+    #[cfg(test)]
+    pub fn num_tracked(&self) -> usize {
+        self.tracked.len()
+    }
+
+    fn max_target_blocks(&self) -> u64 {
+        self.confirmed.len() as u64
+    }
+
+    fn bucket_index(&self, fee_per_gram: f64) -> usize {
+        let fee_per_gram = fee_per_gram.floor() as u64;
+        match self.bucket_bounds.binary_search(&fee_per_gram) {
+            Ok(index) => index,
+            Err(0) => 0,
+            Err(index) => index - 1,
+        }
+    }
+
+    fn record(&mut self, bucket: usize, blocks_to_confirm: Option<u64>) {
+        self.totals[bucket] += 1.0;
+        if let Some(blocks) = blocks_to_confirm {
+            for confirmed in self.confirmed.iter_mut().skip(blocks as usize - 1) {
+                confirmed[bucket] += 1.0;
+            }
+        }
+    }
+
+    fn decay(&mut self) {
+        let decay = self.config.decay;
+        self.totals.iter_mut().for_each(|total| *total *= decay);
+        self.confirmed
+            .iter_mut()
+            .flat_map(|confirmed| confirmed.iter_mut())
+            .for_each(|count| *count *= decay);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tx;
+
+    fn create_txs(fee_per_gram: u64, count: usize) -> Vec<Arc<Transaction>> {
+        (0..count)
+            .map(|_| Arc::new(tx!(MicroTari(10_000), fee: MicroTari(fee_per_gram), inputs: 1, outputs: 1).0))
+            .collect()
+    }
+
+    fn track_all(estimator: &mut FeeEstimator, txs: &[Arc<Transaction>]) {
+        let weighting = TransactionWeight::latest();
+        txs.iter().for_each(|tx| estimator.track_transaction(tx, &weighting));
+    }
+
+    #[test]
+    fn it_does_not_estimate_without_data() {
+        let mut estimator = FeeEstimator::new(FeeEstimatorConfig::default());
+        assert!(estimator.estimate_fee_per_gram(1).is_none());
+
+        // Transactions seen before the first block have no known entry height
+        let txs = create_txs(10, 3);
+        track_all(&mut estimator, &txs);
+        assert_eq!(estimator.num_tracked(), 0);
+        estimator.process_published_block(1, &txs);
+        assert!(estimator.estimate_fee_per_gram(1).is_none());
+    }
+
+    #[test]
+    fn it_estimates_lower_fees_for_longer_targets() {
+        let mut estimator = FeeEstimator::new(FeeEstimatorConfig::default());
+        estimator.process_published_block(10, &[]);
+
+        let expensive = create_txs(100, 5);
+        let cheap = create_txs(5, 5);
+        track_all(&mut estimator, &expensive);
+        track_all(&mut estimator, &cheap);
+        assert_eq!(estimator.num_tracked(), 10);
+
+        // Expensive transactions are mined in the next block, cheap ones take 5 blocks
+        estimator.process_published_block(11, &expensive);
+        for height in 12..15 {
+            estimator.process_published_block(height, &[]);
+        }
+        estimator.process_published_block(15, &cheap);
+        assert_eq!(estimator.num_tracked(), 0);
+
+        let fast = estimator.estimate_fee_per_gram(1).unwrap();
+        let slow = estimator.estimate_fee_per_gram(5).unwrap();
+        assert!(fast > MicroTari(50) && fast <= MicroTari(100));
+        assert!(slow <= MicroTari(6));
+        // Targets beyond the maximum are clamped
+        assert_eq!(
+            estimator.estimate_fee_per_gram(1_000),
+            estimator.estimate_fee_per_gram(30)
+        );
+        assert_eq!(estimator.estimate_fee_per_gram(0), Some(fast));
+    }
+
+    #[test]
+    fn it_counts_expired_transactions_as_failures() {
+        let config = FeeEstimatorConfig {
+            max_target_blocks: 3,
+            ..Default::default()
+        };
+        let mut estimator = FeeEstimator::new(config);
+        estimator.process_published_block(1, &[]);
+
+        let mined = create_txs(100, 5);
+        let stuck = create_txs(5, 20);
+        track_all(&mut estimator, &mined);
+        track_all(&mut estimator, &stuck);
+        estimator.process_published_block(2, &mined);
+        for height in 3..=5 {
+            estimator.process_published_block(height, &[]);
+        }
+        assert_eq!(estimator.num_tracked(), 0);
+
+        // The stuck transactions should not pull the estimate down to their fee rate
+        let estimate = estimator.estimate_fee_per_gram(3).unwrap();
+        assert!(estimate > MicroTari(50));
+    }
+
+    #[test]
+    fn it_ignores_untracked_transactions() {
+        let config = FeeEstimatorConfig {
+            max_target_blocks: 3,
+            ..Default::default()
+        };
+        let mut estimator = FeeEstimator::new(config);
+        estimator.process_published_block(1, &[]);
+
+        let double_spent = create_txs(5, 5);
+        track_all(&mut estimator, &double_spent);
+        assert_eq!(estimator.num_tracked(), 5);
+        estimator.untrack_transactions(&double_spent);
+        assert_eq!(estimator.num_tracked(), 0);
+
+        // Untracked transactions are not recorded as expired either
+        for height in 2..=5 {
+            estimator.process_published_block(height, &[]);
+        }
+        assert!(estimator.estimate_fee_per_gram(3).is_none());
+    }
+}
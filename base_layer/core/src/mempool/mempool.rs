@@ -20,8 +20,12 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::sync::{Arc, RwLock};
+use std::{
+    path::PathBuf,
+    sync::{Arc, RwLock},
+};
 
+use log::*;
 use tari_common_types::types::{PrivateKey, Signature};
 use tokio::task;
 
@@ -31,15 +35,18 @@ use crate::{
     mempool::{
         error::MempoolError,
         mempool_storage::MempoolStorage,
+        persistence::MempoolSnapshot,
         MempoolConfig,
         StateResponse,
         StatsResponse,
         TxStorageResponse,
     },
-    transactions::transaction_components::Transaction,
+    transactions::{tari_amount::MicroTari, transaction_components::Transaction},
     validation::MempoolTransactionValidation,
 };
 
+const LOG_TARGET: &str = "c::mp::mempool";
+
 /// The Mempool consists of an Unconfirmed Transaction Pool, Pending Pool, Orphan Pool and Reorg Pool and is responsible
 /// for managing and maintaining all unconfirmed transactions that have not yet been included in a block, and
 /// transactions that have recently been included in a block.
@@ -125,6 +132,13 @@ impl Mempool {
         self.do_read_task(move |storage| storage.has_transaction(&tx)).await
     }
 
+    /// Returns the estimated fee per gram required for a transaction to be mined within `target_blocks` blocks, or
+    /// None if there is not enough data to make an estimate.
+    pub async fn estimate_fee_per_gram(&self, target_blocks: u64) -> Result<Option<MicroTari>, MempoolError> {
+        self.do_read_task(move |storage| Ok(storage.estimate_fee_per_gram(target_blocks)))
+            .await
+    }
+
     /// Gathers and returns the stats of the Mempool.
     pub async fn stats(&self) -> Result<StatsResponse, MempoolError> {
         self.do_read_task(|storage| Ok(storage.stats())).await
@@ -135,6 +149,34 @@ impl Mempool {
         self.do_read_task(|storage| Ok(storage.state())).await
     }
 
+    /// Writes the unconfirmed and reorg pools to the file at `path`, keeping at most `max_transactions` transactions.
+    /// Returns the number of transactions that were written.
+    pub async fn persist_to_file(&self, path: PathBuf, max_transactions: usize) -> Result<usize, MempoolError> {
+        let snapshot = self
+            .do_read_task(move |storage| Ok(storage.persistence_snapshot(max_transactions)))
+            .await?;
+        let num_txs = snapshot.len();
+        task::spawn_blocking(move || snapshot.write_to_file(&path)).await??;
+        Ok(num_txs)
+    }
+
+    /// Restores the transactions previously written to the file at `path` with `persist_to_file`. Unconfirmed
+    /// transactions are re-validated before being inserted. Returns the number of unconfirmed transactions that were
+    /// restored, or zero if there was no file to restore from.
+    pub async fn restore_from_file(&self, path: PathBuf) -> Result<usize, MempoolError> {
+        let snapshot = match task::spawn_blocking(move || MempoolSnapshot::read_from_file(&path)).await?? {
+            Some(snapshot) => snapshot,
+            None => return Ok(0),
+        };
+        let num_unconfirmed = snapshot.unconfirmed.len();
+        let num_restored = self.do_write_task(move |storage| storage.restore(snapshot)).await?;
+        debug!(
+            target: LOG_TARGET,
+            "Restored {} of {} persisted unconfirmed transaction(s)", num_restored, num_unconfirmed
+        );
+        Ok(num_restored)
+    }
+
     async fn do_read_task<F, T>(&self, callback: F) -> Result<T, MempoolError>
     where
         F: FnOnce(&MempoolStorage) -> Result<T, MempoolError> + Send + 'static,
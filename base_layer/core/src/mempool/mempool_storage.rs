@@ -20,10 +20,10 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 
 use log::*;
-use tari_common_types::types::{PrivateKey, Signature};
+use tari_common_types::types::{HashOutput, PrivateKey, Signature};
 use tari_utilities::{hex::Hex, Hashable};
 
 use crate::{
@@ -31,6 +31,8 @@ use crate::{
     consensus::ConsensusManager,
     mempool::{
         error::MempoolError,
+        fee_estimator::FeeEstimator,
+        persistence::MempoolSnapshot,
         reorg_pool::ReorgPool,
         unconfirmed_pool::UnconfirmedPool,
         MempoolConfig,
@@ -38,7 +40,7 @@ use crate::{
         StatsResponse,
         TxStorageResponse,
     },
-    transactions::{transaction_components::Transaction, weight::TransactionWeight},
+    transactions::{tari_amount::MicroTari, transaction_components::Transaction, weight::TransactionWeight},
     validation::{MempoolTransactionValidation, ValidationError},
 };
 
@@ -50,6 +52,7 @@ pub const LOG_TARGET: &str = "c::mp::mempool_storage";
 pub struct MempoolStorage {
     unconfirmed_pool: UnconfirmedPool,
     reorg_pool: ReorgPool,
+    fee_estimator: FeeEstimator,
     validator: Box<dyn MempoolTransactionValidation>,
     rules: ConsensusManager,
 }
@@ -64,6 +67,7 @@ impl MempoolStorage {
         Self {
             unconfirmed_pool: UnconfirmedPool::new(config.unconfirmed_pool),
             reorg_pool: ReorgPool::new(config.reorg_pool),
+            fee_estimator: FeeEstimator::new(config.fee_estimator),
             validator,
             rules,
         }
@@ -86,14 +90,12 @@ impl MempoolStorage {
                     "Transaction {} is VALID, inserting in unconfirmed pool", tx_id
                 );
                 let weight = self.get_transaction_weighting(0);
-                self.unconfirmed_pool.insert(tx, None, &weight)?;
-                Ok(TxStorageResponse::UnconfirmedPool)
+                self.insert_unconfirmed(tx, None, &weight)
             },
             Err(ValidationError::UnknownInputs(dependent_outputs)) => {
                 if self.unconfirmed_pool.contains_all_outputs(&dependent_outputs) {
                     let weight = self.get_transaction_weighting(0);
-                    self.unconfirmed_pool.insert(tx, Some(dependent_outputs), &weight)?;
-                    Ok(TxStorageResponse::UnconfirmedPool)
+                    self.insert_unconfirmed(tx, Some(dependent_outputs), &weight)
                 } else {
                     warn!(target: LOG_TARGET, "Validation failed due to unknown inputs");
                     Ok(TxStorageResponse::NotStoredOrphan)
@@ -118,6 +120,23 @@ impl MempoolStorage {
         }
     }
 
+    fn insert_unconfirmed(
+        &mut self,
+        tx: Arc<Transaction>,
+        dependent_outputs: Option<Vec<HashOutput>>,
+        weight: &TransactionWeight,
+    ) -> Result<TxStorageResponse, MempoolError> {
+        let response = self.unconfirmed_pool.insert(tx.clone(), dependent_outputs, weight)?;
+        // Replaced and evicted transactions will not be mined, so they must not be counted as expired
+        self.fee_estimator
+            .untrack_transactions(&self.unconfirmed_pool.take_evicted_transactions());
+        // A transaction that would be evicted from a full pool straight away is reported as not stored
+        if response.is_unconfirmed() {
+            self.fee_estimator.track_transaction(&tx, weight);
+        }
+        Ok(response)
+    }
+
     fn get_transaction_weighting(&self, height: u64) -> TransactionWeight {
         *self.rules.consensus_constants(height).transaction_weight()
     }
@@ -143,6 +162,23 @@ impl MempoolStorage {
         let removed_transactions = self
             .unconfirmed_pool
             .remove_published_and_discard_deprecated_transactions(published_block);
+        // Only the transactions whose kernels are in the block were mined, the others were discarded as double spends
+        let published_kernels = published_block
+            .body
+            .kernels()
+            .iter()
+            .map(|kernel| kernel.excess_sig.get_signature())
+            .collect::<HashSet<_>>();
+        let (mined_transactions, discarded_transactions): (Vec<_>, Vec<_>) =
+            removed_transactions.iter().cloned().partition(|tx| {
+                tx.body
+                    .kernels()
+                    .iter()
+                    .any(|kernel| published_kernels.contains(kernel.excess_sig.get_signature()))
+            });
+        self.fee_estimator.untrack_transactions(&discarded_transactions);
+        self.fee_estimator
+            .process_published_block(published_block.header.height, &mined_transactions);
         self.reorg_pool
             .insert_all(published_block.header.height, removed_transactions);
 
@@ -188,7 +224,8 @@ impl MempoolStorage {
                     previous_tip_height,
                     new_tip_height,
                 );
-                self.unconfirmed_pool.remove_timelocked(new_tip_height);
+                let removed_txs = self.unconfirmed_pool.remove_timelocked(new_tip_height);
+                self.fee_estimator.untrack_transactions(&removed_txs);
             } else {
                 debug!(
                     target: LOG_TARGET,
@@ -209,6 +246,55 @@ impl MempoolStorage {
         self.unconfirmed_pool.snapshot()
     }
 
+    /// Returns a snapshot of the Mempool that can be persisted to disk. At most `max_transactions` transactions are
+    /// included, preferring the highest priority unconfirmed transactions and then the most recently published
+    /// transactions in the ReorgPool.
+    pub fn persistence_snapshot(&self, max_transactions: usize) -> MempoolSnapshot {
+        let unconfirmed = self
+            .unconfirmed_pool
+            .snapshot_by_priority(max_transactions)
+            .into_iter()
+            .map(|tx| (*tx).clone())
+            .collect::<Vec<_>>();
+
+        let mut remaining = max_transactions.saturating_sub(unconfirmed.len());
+        let mut reorg = Vec::new();
+        for (height, txs) in self.reorg_pool.snapshot_by_height().into_iter().rev() {
+            if remaining == 0 {
+                break;
+            }
+            let txs = txs
+                .into_iter()
+                .take(remaining)
+                .map(|tx| (*tx).clone())
+                .collect::<Vec<_>>();
+            remaining -= txs.len();
+            reorg.push((height, txs));
+        }
+        reorg.reverse();
+
+        MempoolSnapshot::new(unconfirmed, reorg)
+    }
+
+    /// Restores a previously persisted snapshot. Unconfirmed transactions are re-validated and only inserted if they
+    /// are still valid against the current chain. Transactions in the ReorgPool have already been published and are
+    /// restored as is; they are re-validated if a reorg returns them to the unconfirmed pool. Returns the number of
+    /// unconfirmed transactions that were restored.
+    pub fn restore(&mut self, snapshot: MempoolSnapshot) -> Result<usize, MempoolError> {
+        for (height, txs) in snapshot.reorg {
+            self.reorg_pool
+                .insert_all(height, txs.into_iter().map(Arc::new).collect());
+        }
+
+        let mut num_restored = 0;
+        for tx in snapshot.unconfirmed {
+            if self.insert(Arc::new(tx))?.is_unconfirmed() {
+                num_restored += 1;
+            }
+        }
+        Ok(num_restored)
+    }
+
     /// Returns a list of transaction ranked by transaction priority up to a given weight.
     /// Will only return transactions that will fit into the given weight
     pub fn retrieve_and_revalidate(&mut self, total_weight: u64) -> Result<Vec<Arc<Transaction>>, MempoolError> {
@@ -275,6 +361,12 @@ impl MempoolStorage {
         self.unconfirmed_pool.len() + self.reorg_pool.len()
     }
 
+    /// Returns the estimated fee per gram required for a transaction to be mined within `target_blocks` blocks, or
+    /// None if the mempool has not yet observed enough transactions being mined to make an estimate.
+    pub fn estimate_fee_per_gram(&self, target_blocks: u64) -> Option<MicroTari> {
+        self.fee_estimator.estimate_fee_per_gram(target_blocks)
+    }
+
     /// Gathers and returns the stats of the Mempool.
     pub fn stats(&self) -> StatsResponse {
         let weighting = self.get_transaction_weighting(0);
@@ -30,11 +30,15 @@ mod consts;
 #[cfg(feature = "base_node")]
 mod error;
 #[cfg(feature = "base_node")]
+mod fee_estimator;
+#[cfg(feature = "base_node")]
 #[allow(clippy::module_inception)]
 mod mempool;
 #[cfg(feature = "base_node")]
 mod mempool_storage;
 #[cfg(feature = "base_node")]
+mod persistence;
+#[cfg(feature = "base_node")]
 mod priority;
 #[cfg(feature = "base_node")]
 mod reorg_pool;
@@ -54,6 +58,8 @@ mod unconfirmed_pool;
 pub use error::MempoolError;
 #[cfg(feature = "base_node")]
 pub use mempool::Mempool;
+#[cfg(feature = "base_node")]
+pub use persistence::MempoolSnapshot;
 
 #[cfg(feature = "base_node")]
 pub use self::config::{MempoolConfig, MempoolServiceConfig};
@@ -105,17 +111,37 @@ pub struct StateResponse {
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum TxStorageResponse {
     UnconfirmedPool,
+    /// Stored in the unconfirmed pool, replacing one or more conflicting transactions and their descendants
+    UnconfirmedPoolReplaced,
     ReorgPool,
     NotStoredOrphan,
     NotStoredTimeLocked,
     NotStoredAlreadySpent,
     NotStoredConsensus,
+    /// Conflicts with transactions in the unconfirmed pool and does not pay enough to replace them
+    NotStoredFeeTooLowForReplacement,
+    /// Replacing the conflicting transactions would evict more transactions than permitted
+    NotStoredTooManyReplacements,
+    /// The replacement spends an output of one of the transactions it would evict
+    NotStoredReplacementRejected,
+    /// The unconfirmed pool is full and the transaction does not pay more than the lowest priority transaction in it
+    NotStoredFeeTooLow,
+    /// The transaction would exceed the ancestor or descendant limit of the unconfirmed pool
+    NotStoredTooManyAncestors,
     NotStored,
 }
 
 impl TxStorageResponse {
     pub fn is_stored(&self) -> bool {
-        matches!(self, Self::UnconfirmedPool | Self::ReorgPool)
+        matches!(
+            self,
+            Self::UnconfirmedPool | Self::UnconfirmedPoolReplaced | Self::ReorgPool
+        )
+    }
+
+    /// Returns true if the transaction was stored in the unconfirmed pool
+    pub fn is_unconfirmed(&self) -> bool {
+        matches!(self, Self::UnconfirmedPool | Self::UnconfirmedPoolReplaced)
     }
 }
 
@@ -123,11 +149,23 @@ impl Display for TxStorageResponse {
     fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
         let storage = match self {
             TxStorageResponse::UnconfirmedPool => "Unconfirmed pool",
+            TxStorageResponse::UnconfirmedPoolReplaced => "Unconfirmed pool, replaced conflicting transactions",
             TxStorageResponse::ReorgPool => "Reorg pool",
             TxStorageResponse::NotStoredOrphan => "Not stored orphan transaction",
             TxStorageResponse::NotStoredTimeLocked => "Not stored time locked transaction",
             TxStorageResponse::NotStoredAlreadySpent => "Not stored output already spent",
             TxStorageResponse::NotStoredConsensus => "Not stored due to consensus rule",
+            TxStorageResponse::NotStoredFeeTooLowForReplacement => {
+                "Not stored fee too low to replace conflicting transactions"
+            },
+            TxStorageResponse::NotStoredTooManyReplacements => {
+                "Not stored too many conflicting transactions to replace"
+            },
+            TxStorageResponse::NotStoredReplacementRejected => {
+                "Not stored replacement spends an output of a conflicting transaction"
+            },
+            TxStorageResponse::NotStoredFeeTooLow => "Not stored fee too low for a full mempool",
+            TxStorageResponse::NotStoredTooManyAncestors => "Not stored too many unconfirmed ancestors or descendants",
             TxStorageResponse::NotStored => "Not stored",
         };
         fmt.write_str(storage)
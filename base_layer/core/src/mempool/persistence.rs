@@ -0,0 +1,137 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{
+    fs,
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{mempool::error::MempoolError, transactions::transaction_components::Transaction};
+
+const SNAPSHOT_VERSION: u8 = 0;
+
+/// A serializable copy of the unconfirmed and reorg pools, used to carry locally known transactions across base node
+/// restarts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MempoolSnapshot {
+    version: u8,
+    /// Unconfirmed transactions in the order in which they were inserted, so that parents are restored before the
+    /// children that spend them.
+    pub unconfirmed: Vec<Transaction>,
+    /// Recently published transactions grouped by the height of the block that included them, ordered by height.
+    pub reorg: Vec<(u64, Vec<Transaction>)>,
+}
+
+impl MempoolSnapshot {
+    pub fn new(unconfirmed: Vec<Transaction>, reorg: Vec<(u64, Vec<Transaction>)>) -> Self {
+        Self {
+            version: SNAPSHOT_VERSION,
+            unconfirmed,
+            reorg,
+        }
+    }
+
+    /// Returns the total number of transactions contained in the snapshot.
+    pub fn len(&self) -> usize {
+        self.unconfirmed.len() + self.reorg.iter().map(|(_, txs)| txs.len()).sum::<usize>()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Writes the snapshot to `path`. The snapshot is first written to a temporary file alongside `path` and then
+    /// renamed, so that an interrupted write never leaves a truncated snapshot behind.
+    pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), MempoolError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| MempoolError::PersistenceError(e.to_string()))?;
+        }
+        let tmp_path = path.with_extension("tmp");
+        let file = File::create(&tmp_path).map_err(|e| MempoolError::PersistenceError(e.to_string()))?;
+        let mut writer = BufWriter::new(file);
+        bincode::serialize_into(&mut writer, self).map_err(|e| MempoolError::PersistenceError(e.to_string()))?;
+        // Make sure the snapshot is on disk before it replaces the previous one, otherwise a crash could leave an
+        // empty or truncated file in its place
+        let file = writer
+            .into_inner()
+            .map_err(|e| MempoolError::PersistenceError(e.to_string()))?;
+        file.sync_all()
+            .map_err(|e| MempoolError::PersistenceError(e.to_string()))?;
+        fs::rename(&tmp_path, path).map_err(|e| MempoolError::PersistenceError(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Reads a snapshot from `path`. Returns None if no snapshot exists at `path`.
+    pub fn read_from_file<P: AsRef<Path>>(path: P) -> Result<Option<Self>, MempoolError> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(None);
+        }
+        let file = File::open(path).map_err(|e| MempoolError::PersistenceError(e.to_string()))?;
+        let snapshot: Self = bincode::deserialize_from(BufReader::new(file))
+            .map_err(|e| MempoolError::PersistenceError(e.to_string()))?;
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(MempoolError::PersistenceError(format!(
+                "Unsupported mempool snapshot version {} (expected {})",
+                snapshot.version, SNAPSHOT_VERSION
+            )));
+        }
+        Ok(Some(snapshot))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::{transactions::tari_amount::uT, tx};
+
+    #[test]
+    fn write_and_read_snapshot() {
+        let (tx1, _, _) = tx!(10_000 * uT, fee: 5 * uT, inputs: 1, outputs: 1);
+        let (tx2, _, _) = tx!(10_000 * uT, fee: 10 * uT, inputs: 1, outputs: 1);
+        let snapshot = MempoolSnapshot::new(vec![tx1.clone()], vec![(7, vec![tx2.clone()])]);
+        assert_eq!(snapshot.len(), 2);
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("mempool").join("snapshot.dat");
+        snapshot.write_to_file(&path).unwrap();
+        assert!(!path.with_extension("tmp").exists());
+
+        let restored = MempoolSnapshot::read_from_file(&path).unwrap().unwrap();
+        assert_eq!(restored.unconfirmed, vec![tx1]);
+        assert_eq!(restored.reorg, vec![(7, vec![tx2])]);
+    }
+
+    #[test]
+    fn read_missing_snapshot() {
+        let dir = tempdir().unwrap();
+        let snapshot = MempoolSnapshot::read_from_file(dir.path().join("missing.dat")).unwrap();
+        assert!(snapshot.is_none());
+    }
+}
@@ -38,9 +38,16 @@ pub struct FeePriority(Vec<u8>);
 
 impl FeePriority {
     pub fn new(transaction: &Transaction, weight: u64) -> Self {
+        Self::with_package(transaction, transaction.body.get_total_fee().as_u64(), weight)
+    }
+
+    /// Create the priority for a transaction that is mined as part of a package (e.g. together with its unconfirmed
+    /// ancestors). The fee rate is taken from the combined fee and weight of the package rather than the transaction
+    /// alone.
+    pub fn with_package(transaction: &Transaction, package_fee: u64, package_weight: u64) -> Self {
         // The weights have been normalised, so the fee priority is now equal to the fee per gram ± a few pct points
         // Include 3 decimal places before flooring
-        let fee_per_byte = ((transaction.body.get_total_fee().as_u64() as f64 / weight as f64) * 1000.0) as u64;
+        let fee_per_byte = fee_rate(package_fee, package_weight);
         // Big-endian used here, the MSB is in the starting index. The ordering for Vec<u8> is big-endian and the
         // unconfirmed pool expects the lowest priority to be sorted lowest to highest in the BTreeMap
         let fee_priority = fee_per_byte.to_be_bytes();
@@ -64,6 +71,18 @@ impl FeePriority {
         priority[48..80].copy_from_slice(agg_nonce.as_bytes());
         Self(priority)
     }
+
+    /// The fee rate component of this priority, in thousandths of a µT per gram
+    pub fn fee_rate(&self) -> u64 {
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(&self.0[..8]);
+        u64::from_be_bytes(buf)
+    }
+}
+
+/// Returns the fee per gram, in thousandths of a µT per gram
+fn fee_rate(fee: u64, weight: u64) -> u64 {
+    ((fee as f64 / weight as f64) * 1000.0) as u64
 }
 
 /// A prioritized transaction includes a transaction and the calculated priority of the transaction.
@@ -73,7 +92,16 @@ pub struct PrioritizedTransaction {
     pub transaction: Arc<Transaction>,
     pub priority: FeePriority,
     pub weight: u64,
+    pub fee: u64,
     pub dependent_output_hashes: Vec<HashOutput>,
+    /// The number of transactions in the package formed by this transaction and its unconfirmed ancestors
+    pub ancestor_count: usize,
+    /// The combined fee of this transaction and its unconfirmed ancestors
+    pub ancestor_fee: u64,
+    /// The combined weight of this transaction and its unconfirmed ancestors
+    pub ancestor_weight: u64,
+    /// The number of transactions formed by this transaction and its unconfirmed descendants
+    pub descendant_count: usize,
 }
 
 impl PrioritizedTransaction {
@@ -84,14 +112,31 @@ impl PrioritizedTransaction {
         dependent_outputs: Option<Vec<HashOutput>>,
     ) -> PrioritizedTransaction {
         let weight = transaction.calculate_weight(weighting);
+        let fee = transaction.body.get_total_fee().as_u64();
         Self {
             key,
             priority: FeePriority::new(&transaction, weight),
             weight,
+            fee,
             transaction,
             dependent_output_hashes: dependent_outputs.unwrap_or_default(),
+            ancestor_count: 1,
+            ancestor_fee: fee,
+            ancestor_weight: weight,
+            descendant_count: 1,
         }
     }
+
+    /// The fee rate of this transaction on its own, in thousandths of a µT per gram
+    pub fn fee_rate(&self) -> u64 {
+        fee_rate(self.fee, self.weight)
+    }
+
+    /// The fee rate of the package formed by this transaction and its unconfirmed ancestors, in thousandths of a µT
+    /// per gram
+    pub fn ancestor_fee_rate(&self) -> u64 {
+        fee_rate(self.ancestor_fee, self.ancestor_weight)
+    }
 }
 
 impl Display for PrioritizedTransaction {
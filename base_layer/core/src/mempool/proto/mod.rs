@@ -24,6 +24,7 @@ pub use mempool::{InventoryIndexes, TransactionInventory, TransactionItem};
 
 use crate::proto::mempool;
 
+mod fee_estimate;
 mod state_response;
 mod stats_response;
 mod sync_protocol;
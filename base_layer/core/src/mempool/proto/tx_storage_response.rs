@@ -34,6 +34,12 @@ impl TryFrom<proto::TxStorageResponse> for TxStorageResponse {
             UnconfirmedPool => TxStorageResponse::UnconfirmedPool,
             ReorgPool => TxStorageResponse::ReorgPool,
             NotStored => TxStorageResponse::NotStored,
+            UnconfirmedPoolReplaced => TxStorageResponse::UnconfirmedPoolReplaced,
+            NotStoredFeeTooLowForReplacement => TxStorageResponse::NotStoredFeeTooLowForReplacement,
+            NotStoredTooManyReplacements => TxStorageResponse::NotStoredTooManyReplacements,
+            NotStoredReplacementRejected => TxStorageResponse::NotStoredReplacementRejected,
+            NotStoredFeeTooLow => TxStorageResponse::NotStoredFeeTooLow,
+            NotStoredTooManyAncestors => TxStorageResponse::NotStoredTooManyAncestors,
         })
     }
 }
@@ -43,12 +49,18 @@ impl From<TxStorageResponse> for proto::TxStorageResponse {
         use TxStorageResponse::*;
         match response {
             UnconfirmedPool => proto::TxStorageResponse::UnconfirmedPool,
+            UnconfirmedPoolReplaced => proto::TxStorageResponse::UnconfirmedPoolReplaced,
             ReorgPool => proto::TxStorageResponse::ReorgPool,
             NotStored => proto::TxStorageResponse::NotStored,
             NotStoredOrphan => proto::TxStorageResponse::NotStored,
             NotStoredTimeLocked => proto::TxStorageResponse::NotStored,
             NotStoredAlreadySpent => proto::TxStorageResponse::NotStored,
             NotStoredConsensus => proto::TxStorageResponse::NotStored,
+            NotStoredFeeTooLowForReplacement => proto::TxStorageResponse::NotStoredFeeTooLowForReplacement,
+            NotStoredTooManyReplacements => proto::TxStorageResponse::NotStoredTooManyReplacements,
+            NotStoredReplacementRejected => proto::TxStorageResponse::NotStoredReplacementRejected,
+            NotStoredFeeTooLow => proto::TxStorageResponse::NotStoredFeeTooLow,
+            NotStoredTooManyAncestors => proto::TxStorageResponse::NotStoredTooManyAncestors,
         }
     }
 }
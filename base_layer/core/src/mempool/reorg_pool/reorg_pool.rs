@@ -263,6 +263,23 @@ impl ReorgPool {
         self.tx_by_key.values().cloned().collect()
     }
 
+    /// Returns all transactions stored in the ReorgPool grouped by the height at which they were published, ordered by
+    /// height.
+    pub fn snapshot_by_height(&self) -> Vec<(u64, Vec<Arc<Transaction>>)> {
+        let mut heights = self.txs_by_height.keys().copied().collect::<Vec<_>>();
+        heights.sort_unstable();
+        heights
+            .into_iter()
+            .map(|height| {
+                let txs = self.txs_by_height[&height]
+                    .iter()
+                    .filter_map(|key| self.tx_by_key.get(key).cloned())
+                    .collect();
+                (height, txs)
+            })
+            .collect()
+    }
+
     fn get_next_key(&mut self) -> usize {
         let key = self.key_counter;
         self.key_counter = (self.key_counter + 1) % usize::MAX;
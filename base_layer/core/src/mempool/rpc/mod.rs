@@ -32,7 +32,7 @@ use tari_comms_rpc_macros::tari_rpc;
 use crate::{
     mempool::service::MempoolHandle,
     proto::{
-        mempool::{StateResponse, StatsResponse, TxStorage},
+        mempool::{FeePerGramEstimateResponse, StateResponse, StatsResponse, TxStorage},
         types::{Signature, Transaction},
     },
 };
@@ -53,6 +53,12 @@ pub trait MempoolService: Send + Sync + 'static {
 
     #[rpc(method = 4)]
     async fn submit_transaction(&self, request: Request<Transaction>) -> Result<Response<TxStorage>, RpcStatus>;
+
+    #[rpc(method = 5)]
+    async fn estimate_fee_per_gram(
+        &self,
+        request: Request<u64>,
+    ) -> Result<Response<FeePerGramEstimateResponse>, RpcStatus>;
 }
 
 pub fn create_mempool_rpc_service(mempool: MempoolHandle) -> MempoolRpcServer<MempoolRpcService> {
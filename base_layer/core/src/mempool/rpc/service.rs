@@ -107,4 +107,17 @@ impl MempoolService for MempoolRpcService {
         let tx_storage = self.mempool().submit_transaction(tx).await.map_err(to_internal_error)?;
         Ok(Response::new(tx_storage.into()))
     }
+
+    async fn estimate_fee_per_gram(
+        &self,
+        request: Request<u64>,
+    ) -> Result<Response<proto::mempool::FeePerGramEstimateResponse>, RpcStatus> {
+        let target_blocks = request.into_message();
+        let estimate = self
+            .mempool()
+            .estimate_fee_per_gram(target_blocks)
+            .await
+            .map_err(to_internal_error)?;
+        Ok(Response::new(estimate.into()))
+    }
 }
@@ -182,3 +182,36 @@ mod submit_transaction {
         unpack_enum!(RpcStatusCode::BadRequest = status.as_status_code());
     }
 }
+
+mod estimate_fee_per_gram {
+    use super::*;
+    use crate::{mempool::MempoolService, transactions::tari_amount::MicroTari};
+
+    #[tokio::test]
+    async fn it_returns_the_estimate() {
+        let (service, mempool, req_mock, _tmpdir) = setup();
+        mempool.set_estimate_fee_per_gram_response(Some(MicroTari(42))).await;
+
+        let resp = service
+            .estimate_fee_per_gram(req_mock.request_no_context(3))
+            .await
+            .unwrap()
+            .into_message();
+        assert_eq!(resp.fee_per_gram, 42);
+        assert_eq!(resp.estimate(), Some(MicroTari(42)));
+        assert_eq!(mempool.get_call_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn it_returns_zero_without_an_estimate() {
+        let (service, _mempool, req_mock, _tmpdir) = setup();
+
+        let resp = service
+            .estimate_fee_per_gram(req_mock.request_no_context(3))
+            .await
+            .unwrap()
+            .into_message();
+        assert_eq!(resp.fee_per_gram, 0);
+        assert!(resp.estimate().is_none());
+    }
+}
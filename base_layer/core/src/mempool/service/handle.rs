@@ -31,7 +31,7 @@ use crate::{
         StatsResponse,
         TxStorageResponse,
     },
-    transactions::transaction_components::Transaction,
+    transactions::{tari_amount::MicroTari, transaction_components::Transaction},
 };
 
 #[derive(Clone)]
@@ -81,4 +81,18 @@ impl MempoolHandle {
             _ => panic!("Incorrect response"),
         }
     }
+
+    pub async fn estimate_fee_per_gram(
+        &mut self,
+        target_blocks: u64,
+    ) -> Result<Option<MicroTari>, MempoolServiceError> {
+        match self
+            .inner
+            .call(MempoolRequest::EstimateFeePerGram(target_blocks))
+            .await??
+        {
+            MempoolResponse::FeePerGramEstimate(resp) => Ok(resp),
+            _ => panic!("Incorrect response"),
+        }
+    }
 }
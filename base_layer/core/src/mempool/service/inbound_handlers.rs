@@ -72,6 +72,9 @@ impl MempoolInboundHandlers {
                 );
                 Ok(MempoolResponse::TxStorage(self.submit_transaction(tx, None).await?))
             },
+            EstimateFeePerGram(target_blocks) => Ok(MempoolResponse::FeePerGramEstimate(
+                self.mempool.estimate_fee_per_gram(target_blocks).await?,
+            )),
         }
     }
 
@@ -130,7 +133,7 @@ impl MempoolInboundHandlers {
                     "Transaction inserted into mempool: {}, pool: {}.", kernel_excess_sig, tx_storage
                 );
                 // propagate the tx if it was accepted to the unconfirmed pool
-                if matches!(tx_storage, TxStorageResponse::UnconfirmedPool) {
+                if tx_storage.is_unconfirmed() {
                     debug!(
                         target: LOG_TARGET,
                         "Propagate transaction ({}) to network.", kernel_excess_sig,
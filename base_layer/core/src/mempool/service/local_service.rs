@@ -30,7 +30,7 @@ use crate::{
         StatsResponse,
         TxStorageResponse,
     },
-    transactions::transaction_components::Transaction,
+    transactions::{tari_amount::MicroTari, transaction_components::Transaction},
 };
 
 pub type LocalMempoolRequester = SenderService<MempoolRequest, Result<MempoolResponse, MempoolServiceError>>;
@@ -100,6 +100,22 @@ impl LocalMempoolService {
             _ => Err(MempoolServiceError::UnexpectedApiResponse),
         }
     }
+
+    /// Returns a future that resolves to the estimated fee per gram for a transaction to be mined within
+    /// `target_blocks` blocks, or None if the mempool cannot make an estimate yet
+    pub async fn estimate_fee_per_gram(
+        &mut self,
+        target_blocks: u64,
+    ) -> Result<Option<MicroTari>, MempoolServiceError> {
+        match self
+            .request_sender
+            .call(MempoolRequest::EstimateFeePerGram(target_blocks))
+            .await??
+        {
+            MempoolResponse::FeePerGramEstimate(s) => Ok(s),
+            _ => Err(MempoolServiceError::UnexpectedApiResponse),
+        }
+    }
 }
 
 #[cfg(test)]
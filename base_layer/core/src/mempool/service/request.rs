@@ -36,6 +36,7 @@ pub enum MempoolRequest {
     GetState,
     GetTxStateByExcessSig(Signature),
     SubmitTransaction(Transaction),
+    EstimateFeePerGram(u64),
 }
 
 impl Display for MempoolRequest {
@@ -50,6 +51,9 @@ impl Display for MempoolRequest {
                 "SubmitTransaction ({})",
                 tx.body.kernels()[0].excess_sig.get_signature().to_hex()
             )),
+            MempoolRequest::EstimateFeePerGram(target_blocks) => {
+                f.write_str(&format!("EstimateFeePerGram ({} blocks)", target_blocks))
+            },
         }
     }
 }
@@ -24,7 +24,10 @@ use std::{fmt, fmt::Formatter};
 
 use tari_common_types::waiting_requests::RequestKey;
 
-use crate::mempool::{StateResponse, StatsResponse, TxStorageResponse};
+use crate::{
+    mempool::{StateResponse, StatsResponse, TxStorageResponse},
+    transactions::tari_amount::MicroTari,
+};
 
 /// API Response enum for Mempool responses.
 #[derive(Clone, Debug)]
@@ -32,6 +35,7 @@ pub enum MempoolResponse {
     Stats(StatsResponse),
     State(StateResponse),
     TxStorage(TxStorageResponse),
+    FeePerGramEstimate(Option<MicroTari>),
 }
 
 impl fmt::Display for MempoolResponse {
@@ -41,6 +45,7 @@ impl fmt::Display for MempoolResponse {
             Stats(_) => write!(f, "Stats"),
             State(_) => write!(f, "State"),
             TxStorage(_) => write!(f, "TxStorage"),
+            FeePerGramEstimate(_) => write!(f, "FeePerGramEstimate"),
         }
     }
 }
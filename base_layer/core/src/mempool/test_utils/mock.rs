@@ -29,12 +29,15 @@ use futures::StreamExt;
 use tari_service_framework::reply_channel;
 use tokio::{sync::Mutex, task};
 
-use crate::mempool::{
-    service::{MempoolHandle, MempoolRequest, MempoolResponse},
-    MempoolServiceError,
-    StateResponse,
-    StatsResponse,
-    TxStorageResponse,
+use crate::{
+    mempool::{
+        service::{MempoolHandle, MempoolRequest, MempoolResponse},
+        MempoolServiceError,
+        StateResponse,
+        StatsResponse,
+        TxStorageResponse,
+    },
+    transactions::tari_amount::MicroTari,
 };
 
 pub fn create_mempool_service_mock() -> (MempoolHandle, MempoolMockState) {
@@ -51,6 +54,7 @@ pub struct MempoolMockState {
     get_state: Arc<Mutex<StateResponse>>,
     get_tx_state_by_excess_sig: Arc<Mutex<TxStorageResponse>>,
     submit_transaction: Arc<Mutex<TxStorageResponse>>,
+    estimate_fee_per_gram: Arc<Mutex<Option<MicroTari>>>,
     calls: Arc<AtomicUsize>,
 }
 
@@ -69,6 +73,7 @@ impl Default for MempoolMockState {
             })),
             get_tx_state_by_excess_sig: Arc::new(Mutex::new(TxStorageResponse::NotStored)),
             submit_transaction: Arc::new(Mutex::new(TxStorageResponse::NotStored)),
+            estimate_fee_per_gram: Arc::new(Mutex::new(None)),
             calls: Arc::new(Default::default()),
         }
     }
@@ -91,6 +96,10 @@ impl MempoolMockState {
         *self.submit_transaction.lock().await = resp;
     }
 
+    pub async fn set_estimate_fee_per_gram_response(&self, resp: Option<MicroTari>) {
+        *self.estimate_fee_per_gram.lock().await = resp;
+    }
+
     fn inc_call_count(&self) {
         self.calls.fetch_add(1, Ordering::SeqCst);
     }
@@ -137,6 +146,9 @@ impl MempoolServiceMock {
             SubmitTransaction(_) => Ok(MempoolResponse::TxStorage(
                 self.state.submit_transaction.lock().await.clone(),
             )),
+            EstimateFeePerGram(_) => Ok(MempoolResponse::FeePerGramEstimate(
+                *self.state.estimate_fee_per_gram.lock().await,
+            )),
         }
     }
 }
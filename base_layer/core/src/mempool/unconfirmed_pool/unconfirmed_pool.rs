@@ -23,6 +23,7 @@
 use std::{
     collections::{BTreeMap, HashMap, HashSet},
     hash::Hash,
+    iter,
     sync::Arc,
 };
 
@@ -36,9 +37,16 @@ use tari_utilities::ByteArray;
 use crate::{
     blocks::Block,
     mempool::{
-        consts::{MEMPOOL_UNCONFIRMED_POOL_STORAGE_CAPACITY, MEMPOOL_UNCONFIRMED_POOL_WEIGHT_TRANSACTION_SKIP_COUNT},
+        consts::{
+            MEMPOOL_UNCONFIRMED_POOL_MAX_ANCESTOR_COUNT,
+            MEMPOOL_UNCONFIRMED_POOL_MAX_DESCENDANT_COUNT,
+            MEMPOOL_UNCONFIRMED_POOL_MAX_REPLACEMENT_EVICTIONS,
+            MEMPOOL_UNCONFIRMED_POOL_STORAGE_CAPACITY,
+            MEMPOOL_UNCONFIRMED_POOL_WEIGHT_TRANSACTION_SKIP_COUNT,
+        },
         priority::{FeePriority, PrioritizedTransaction},
         unconfirmed_pool::UnconfirmedPoolError,
+        TxStorageResponse,
     },
     transactions::{
         transaction_components::{Transaction, TransactionOutput},
@@ -58,6 +66,13 @@ pub struct UnconfirmedPoolConfig {
     /// The maximum number of transactions that can be skipped when compiling a set of highest priority transactions,
     /// skipping over large transactions are performed in an attempt to fit more transactions into the remaining space.
     pub weight_tx_skip_count: usize,
+    /// The maximum number of transactions (conflicting transactions and their descendants) that a single
+    /// replace-by-fee transaction may evict from the pool.
+    pub max_replacement_evictions: usize,
+    /// The maximum number of transactions that a transaction and its unconfirmed ancestors in the pool may form
+    pub max_ancestor_count: usize,
+    /// The maximum number of transactions that a transaction and its unconfirmed descendants in the pool may form
+    pub max_descendant_count: usize,
 }
 
 impl Default for UnconfirmedPoolConfig {
@@ -65,6 +80,9 @@ impl Default for UnconfirmedPoolConfig {
         Self {
             storage_capacity: MEMPOOL_UNCONFIRMED_POOL_STORAGE_CAPACITY,
             weight_tx_skip_count: MEMPOOL_UNCONFIRMED_POOL_WEIGHT_TRANSACTION_SKIP_COUNT,
+            max_replacement_evictions: MEMPOOL_UNCONFIRMED_POOL_MAX_REPLACEMENT_EVICTIONS,
+            max_ancestor_count: MEMPOOL_UNCONFIRMED_POOL_MAX_ANCESTOR_COUNT,
+            max_descendant_count: MEMPOOL_UNCONFIRMED_POOL_MAX_DESCENDANT_COUNT,
         }
     }
 }
@@ -77,6 +95,13 @@ impl Default for UnconfirmedPoolConfig {
 /// priority. The txs_by_priority BTreeMap makes it easier to select the set of highest priority transactions that can
 /// be included in a block. The excess_sig of a transaction is used a key to uniquely identify a specific transaction in
 /// these containers.
+///
+/// Only one transaction in the pool may spend a given output. A transaction that spends an output already spent by a
+/// pooled transaction replaces it (and all of its descendants) if it pays a higher fee per gram than each conflicting
+/// transaction and a higher absolute fee than all evicted transactions combined. A transaction that spends outputs of
+/// other pooled transactions is prioritised by the fee rate of the package it forms with its ancestors, and raises the
+/// priority of those ancestors so that a child can pay for its parents. The number of unconfirmed ancestors and
+/// descendants of a transaction is limited, so that the package totals kept on each transaction stay cheap to update.
 pub struct UnconfirmedPool {
     config: UnconfirmedPoolConfig,
     key_counter: usize,
@@ -85,6 +110,9 @@ pub struct UnconfirmedPool {
     tx_by_priority: BTreeMap<FeePriority, TransactionKey>,
     txs_by_output: HashMap<HashOutput, Vec<TransactionKey>>,
     txs_by_unique_id: HashMap<[u8; 32], Vec<TransactionKey>>,
+    tx_by_spent_output: HashMap<HashOutput, TransactionKey>,
+    /// The transactions that the most recent insert replaced or evicted to make space
+    evicted: Vec<Arc<Transaction>>,
 }
 
 // helper class to reduce type complexity
@@ -104,37 +132,108 @@ impl UnconfirmedPool {
             tx_by_priority: BTreeMap::new(),
             txs_by_output: HashMap::new(),
             txs_by_unique_id: HashMap::new(),
+            tx_by_spent_output: HashMap::new(),
+            evicted: Vec::new(),
         }
     }
 
     /// Insert a new transaction into the UnconfirmedPool. Low priority transactions will be removed to make space for
-    /// higher priority transactions. The lowest priority transactions will be removed when the maximum capacity is
-    /// reached and the new transaction has a higher priority than the currently stored lowest priority transaction.
+    /// higher priority transactions. When the maximum capacity is exceeded, the lowest priority transaction is removed
+    /// after the package fee rate of the new transaction has been applied to it and its ancestors. A transaction that
+    /// would itself be the lowest priority transaction in a full pool is not stored.
+    /// Transactions that spend the same outputs as pooled transactions are subject to the replace-by-fee rules.
     pub fn insert(
         &mut self,
         tx: Arc<Transaction>,
         dependent_outputs: Option<Vec<HashOutput>>,
         transaction_weighting: &TransactionWeight,
-    ) -> Result<(), UnconfirmedPoolError> {
+    ) -> Result<TxStorageResponse, UnconfirmedPoolError> {
+        self.evicted.clear();
         if tx
             .body
             .kernels()
             .iter()
             .all(|k| self.txs_by_signature.contains_key(k.excess_sig.get_signature()))
         {
-            return Ok(());
+            return Ok(TxStorageResponse::UnconfirmedPool);
         }
 
         let new_key = self.get_next_key();
-        let prioritized_tx = PrioritizedTransaction::new(new_key, transaction_weighting, tx, dependent_outputs);
-        if self.tx_by_key.len() >= self.config.storage_capacity {
-            if prioritized_tx.priority < *self.lowest_priority() {
-                return Ok(());
+        let mut prioritized_tx = PrioritizedTransaction::new(new_key, transaction_weighting, tx, dependent_outputs);
+
+        let conflicts = self.find_conflicting_transactions(&prioritized_tx.transaction);
+        let to_evict = self.get_descendants(&conflicts);
+        if !conflicts.is_empty() {
+            if let Some(rejection) = self.check_replacement(&prioritized_tx, &conflicts, &to_evict) {
+                debug!(
+                    target: LOG_TARGET,
+                    "Transaction {} conflicts with {} transaction(s) in the unconfirmed pool: {}",
+                    prioritized_tx,
+                    conflicts.len(),
+                    rejection
+                );
+                return Ok(rejection);
             }
-            self.remove_lowest_priority_tx();
         }
 
+        let ancestors = self.get_ancestors(&prioritized_tx.dependent_output_hashes);
+        if let Some(rejection) = self.check_package_limits(&ancestors) {
+            debug!(
+                target: LOG_TARGET,
+                "Transaction {} exceeds the package limits of the unconfirmed pool ({} ancestor(s))",
+                prioritized_tx,
+                ancestors.len()
+            );
+            return Ok(rejection);
+        }
+        for ancestor in ancestors.iter().filter_map(|key| self.tx_by_key.get(key)) {
+            prioritized_tx.ancestor_count += 1;
+            prioritized_tx.ancestor_fee += ancestor.fee;
+            prioritized_tx.ancestor_weight += ancestor.weight;
+        }
+        if prioritized_tx.ancestor_fee_rate() < prioritized_tx.fee_rate() {
+            prioritized_tx.priority = FeePriority::with_package(
+                &prioritized_tx.transaction,
+                prioritized_tx.ancestor_fee,
+                prioritized_tx.ancestor_weight,
+            );
+        }
+
+        if self.tx_by_key.len() - to_evict.len() >= self.config.storage_capacity &&
+            !self.outranks_lowest_priority_tx(&prioritized_tx.priority, &ancestors, &to_evict)
+        {
+            debug!(
+                target: LOG_TARGET,
+                "Transaction {} would be the lowest priority transaction in a full unconfirmed pool", prioritized_tx
+            );
+            return Ok(TxStorageResponse::NotStoredFeeTooLow);
+        }
+
+        let mut response = TxStorageResponse::UnconfirmedPool;
+        if !to_evict.is_empty() {
+            debug!(
+                target: LOG_TARGET,
+                "Transaction {} replaces {} transaction(s) in the unconfirmed pool",
+                prioritized_tx,
+                to_evict.len()
+            );
+            for key in to_evict {
+                if let Some(evicted) = self.remove_transaction(key) {
+                    self.evicted.push(evicted);
+                }
+            }
+            response = TxStorageResponse::UnconfirmedPoolReplaced;
+        }
+
+        for key in &ancestors {
+            if let Some(ancestor) = self.tx_by_key.get_mut(key) {
+                ancestor.descendant_count += 1;
+            }
+        }
         self.tx_by_priority.insert(prioritized_tx.priority.clone(), new_key);
+        for input in prioritized_tx.transaction.body.inputs() {
+            self.tx_by_spent_output.insert(input.output_hash(), new_key);
+        }
         for output in prioritized_tx.transaction.body.outputs() {
             self.txs_by_output.entry(output.hash()).or_default().push(new_key);
 
@@ -153,7 +252,210 @@ impl UnconfirmedPool {
         );
         self.tx_by_key.insert(new_key, prioritized_tx);
 
-        Ok(())
+        // Child-pays-for-parent: the package fee rate applies to the new transaction and its ancestors before the
+        // lowest priority transaction is chosen for eviction
+        self.recompute_priority(new_key);
+        for key in ancestors {
+            self.recompute_priority(key);
+        }
+
+        if self.tx_by_key.len() > self.config.storage_capacity {
+            self.remove_lowest_priority_tx();
+        }
+        if !self.tx_by_key.contains_key(&new_key) {
+            return Ok(TxStorageResponse::NotStoredFeeTooLow);
+        }
+
+        Ok(response)
+    }
+
+    /// Returns the reason for rejecting a transaction with the given unconfirmed ancestors if it would exceed the
+    /// ancestor limit, or push any of its ancestors over the descendant limit
+    fn check_package_limits(&self, ancestors: &HashSet<TransactionKey>) -> Option<TxStorageResponse> {
+        if ancestors.len() + 1 > self.config.max_ancestor_count {
+            return Some(TxStorageResponse::NotStoredTooManyAncestors);
+        }
+        let exceeds_descendant_limit = ancestors
+            .iter()
+            .filter_map(|key| self.tx_by_key.get(key))
+            .any(|ancestor| ancestor.descendant_count + 1 > self.config.max_descendant_count);
+        if exceeds_descendant_limit {
+            return Some(TxStorageResponse::NotStoredTooManyAncestors);
+        }
+        None
+    }
+
+    /// Returns true if a new transaction with the given priority would not be the lowest priority transaction in the
+    /// pool once `to_evict` has been removed. The ancestors of the new transaction are raised to at least its package
+    /// fee rate when it is inserted, so only the remaining transactions are compared.
+    fn outranks_lowest_priority_tx(
+        &self,
+        priority: &FeePriority,
+        ancestors: &HashSet<TransactionKey>,
+        to_evict: &HashSet<TransactionKey>,
+    ) -> bool {
+        self.tx_by_priority
+            .iter()
+            .find(|(_, key)| !ancestors.contains(*key) && !to_evict.contains(*key))
+            .map(|(lowest, _)| priority > lowest)
+            .unwrap_or(true)
+    }
+
+    /// Returns the keys of the transactions in the pool that spend any of the inputs of the given transaction
+    fn find_conflicting_transactions(&self, transaction: &Transaction) -> HashSet<TransactionKey> {
+        transaction
+            .body
+            .inputs()
+            .iter()
+            .filter_map(|input| self.tx_by_spent_output.get(&input.output_hash()))
+            .copied()
+            .collect()
+    }
+
+    /// Returns the given transactions together with all transactions in the pool that (transitively) spend their
+    /// outputs
+    fn get_descendants(&self, keys: &HashSet<TransactionKey>) -> HashSet<TransactionKey> {
+        let mut descendants = keys.clone();
+        let mut pending = keys.iter().copied().collect::<Vec<_>>();
+        while let Some(key) = pending.pop() {
+            let ptx = match self.tx_by_key.get(&key) {
+                Some(ptx) => ptx,
+                None => continue,
+            };
+            for output in ptx.transaction.body.outputs() {
+                if let Some(child) = self.tx_by_spent_output.get(&output.hash()) {
+                    if descendants.insert(*child) {
+                        pending.push(*child);
+                    }
+                }
+            }
+        }
+        descendants
+    }
+
+    /// Returns all transactions in the pool that create the given outputs, and (transitively) the outputs that those
+    /// transactions depend on
+    fn get_ancestors(&self, dependent_outputs: &[HashOutput]) -> HashSet<TransactionKey> {
+        let mut ancestors = HashSet::new();
+        let mut pending = dependent_outputs.iter().collect::<Vec<_>>();
+        while let Some(output_hash) = pending.pop() {
+            for key in self.txs_by_output.get(output_hash).into_iter().flatten() {
+                if ancestors.insert(*key) {
+                    if let Some(ptx) = self.tx_by_key.get(key) {
+                        pending.extend(ptx.dependent_output_hashes.iter());
+                    }
+                }
+            }
+        }
+        ancestors
+    }
+
+    /// Checks the replace-by-fee rules for a transaction that conflicts with `conflicts`, evicting `to_evict` (the
+    /// conflicts and their descendants). Returns the reason for rejecting the replacement, if any.
+    fn check_replacement(
+        &self,
+        replacement: &PrioritizedTransaction,
+        conflicts: &HashSet<TransactionKey>,
+        to_evict: &HashSet<TransactionKey>,
+    ) -> Option<TxStorageResponse> {
+        if to_evict.len() > self.config.max_replacement_evictions {
+            return Some(TxStorageResponse::NotStoredTooManyReplacements);
+        }
+
+        // A replacement cannot spend the outputs of a transaction it evicts
+        let spends_evicted_output = replacement.dependent_output_hashes.iter().any(|hash| {
+            self.txs_by_output
+                .get(hash)
+                .map(|keys| keys.iter().any(|key| to_evict.contains(key)))
+                .unwrap_or(false)
+        });
+        if spends_evicted_output {
+            return Some(TxStorageResponse::NotStoredReplacementRejected);
+        }
+
+        let max_conflicting_fee_rate = conflicts
+            .iter()
+            .filter_map(|key| self.tx_by_key.get(key))
+            .map(|ptx| ptx.fee_rate())
+            .max()
+            .unwrap_or(0);
+        if replacement.fee_rate() <= max_conflicting_fee_rate {
+            return Some(TxStorageResponse::NotStoredFeeTooLowForReplacement);
+        }
+
+        let evicted_fee = to_evict
+            .iter()
+            .filter_map(|key| self.tx_by_key.get(key))
+            .map(|ptx| ptx.fee)
+            .sum::<u64>();
+        if replacement.fee <= evicted_fee {
+            return Some(TxStorageResponse::NotStoredFeeTooLowForReplacement);
+        }
+
+        None
+    }
+
+    /// Recalculates the package totals of the given transaction from the transactions currently in the pool. Removing a
+    /// transaction can disconnect a descendant from more than one of its ancestors, so the totals of the relatives of a
+    /// removed transaction are refreshed rather than adjusted. The package limits bound the cost of doing so.
+    fn refresh_package_totals(&mut self, tx_key: TransactionKey) {
+        let ptx = match self.tx_by_key.get(&tx_key) {
+            Some(ptx) => ptx,
+            None => return,
+        };
+        let (ancestor_count, ancestor_fee, ancestor_weight) = self
+            .get_ancestors(&ptx.dependent_output_hashes)
+            .iter()
+            .filter_map(|key| self.tx_by_key.get(key))
+            .fold((1, ptx.fee, ptx.weight), |(count, fee, weight), ancestor| {
+                (count + 1, fee + ancestor.fee, weight + ancestor.weight)
+            });
+        let descendant_count = self.get_descendants(&iter::once(tx_key).collect()).len();
+        if let Some(ptx) = self.tx_by_key.get_mut(&tx_key) {
+            ptx.ancestor_count = ancestor_count;
+            ptx.ancestor_fee = ancestor_fee;
+            ptx.ancestor_weight = ancestor_weight;
+            ptx.descendant_count = descendant_count;
+        }
+    }
+
+    /// Recalculates the priority of the given transaction from the transactions currently in the pool. A transaction
+    /// that spends unconfirmed outputs can only be mined together with its ancestors, so it is ranked by the fee rate
+    /// of that package when it is lower than its own. A transaction is raised to the highest package fee rate of any
+    /// of its descendants, so that a child can pay for its parent. The package totals are kept on each transaction and
+    /// the number of descendants is limited, so this does not walk the ancestors of every descendant.
+    fn recompute_priority(&mut self, tx_key: TransactionKey) {
+        let ptx = match self.tx_by_key.get(&tx_key) {
+            Some(ptx) => ptx,
+            None => return,
+        };
+        let mut priority = FeePriority::new(&ptx.transaction, ptx.weight);
+        if ptx.ancestor_fee_rate() < priority.fee_rate() {
+            priority = FeePriority::with_package(&ptx.transaction, ptx.ancestor_fee, ptx.ancestor_weight);
+        }
+
+        let descendants = self.get_descendants(&iter::once(tx_key).collect());
+        for descendant in descendants.iter().filter(|key| **key != tx_key) {
+            if let Some(descendant) = self.tx_by_key.get(descendant) {
+                if descendant.ancestor_fee_rate() > priority.fee_rate() {
+                    priority = FeePriority::with_package(
+                        &ptx.transaction,
+                        descendant.ancestor_fee,
+                        descendant.ancestor_weight,
+                    );
+                }
+            }
+        }
+
+        if priority == ptx.priority {
+            return;
+        }
+        let previous = ptx.priority.clone();
+        self.tx_by_priority.remove(&previous);
+        self.tx_by_priority.insert(priority.clone(), tx_key);
+        if let Some(ptx) = self.tx_by_key.get_mut(&tx_key) {
+            ptx.priority = priority;
+        }
     }
 
     /// TThis will search the unconfirmed pool for the set of outputs and return true if all of them are found
@@ -372,24 +674,26 @@ impl UnconfirmedPool {
         false
     }
 
-    fn lowest_priority(&self) -> &FeePriority {
-        self.tx_by_priority
-            .keys()
-            .next()
-            .expect("lowest_priority called on empty mempool")
-    }
-
     fn remove_lowest_priority_tx(&mut self) {
         if let Some(tx_key) = self.tx_by_priority.values().next().copied() {
-            self.remove_transaction(tx_key);
+            if let Some(evicted) = self.remove_transaction(tx_key) {
+                self.evicted.push(evicted);
+            }
         }
     }
 
+    /// Returns the transactions that the most recent insert replaced or evicted to make space. They left the pool
+    /// without being mined.
+    pub fn take_evicted_transactions(&mut self) -> Vec<Arc<Transaction>> {
+        std::mem::take(&mut self.evicted)
+    }
+
     /// Remove all current mempool transactions from the UnconfirmedPoolStorage, returning that which have been removed
     pub fn drain_all_mempool_transactions(&mut self) -> Vec<Arc<Transaction>> {
         self.txs_by_signature.clear();
         self.tx_by_priority.clear();
         self.txs_by_output.clear();
+        self.tx_by_spent_output.clear();
         self.tx_by_key.drain().map(|(_, val)| val.transaction).collect()
     }
 
@@ -480,6 +784,13 @@ impl UnconfirmedPool {
             }
         }
 
+        for input in prioritized_transaction.transaction.body.inputs() {
+            let output_hash = input.output_hash();
+            if self.tx_by_spent_output.get(&output_hash) == Some(&tx_key) {
+                self.tx_by_spent_output.remove(&output_hash);
+            }
+        }
+
         for output in prioritized_transaction.transaction.body.outputs() {
             let output_hash = output.hash();
             if let Some(keys) = self.txs_by_output.get_mut(&output_hash) {
@@ -503,6 +814,27 @@ impl UnconfirmedPool {
             }
         }
 
+        // Any package fee rate the transaction contributed to its ancestors, or took from them, no longer applies
+        let children = prioritized_transaction
+            .transaction
+            .body
+            .outputs()
+            .iter()
+            .filter_map(|output| self.tx_by_spent_output.get(&output.hash()))
+            .copied()
+            .collect();
+        let relatives = self
+            .get_ancestors(&prioritized_transaction.dependent_output_hashes)
+            .into_iter()
+            .chain(self.get_descendants(&children))
+            .collect::<Vec<_>>();
+        for key in &relatives {
+            self.refresh_package_totals(*key);
+        }
+        for key in relatives {
+            self.recompute_priority(key);
+        }
+
         trace!(
             target: LOG_TARGET,
             "Deleted transaction: {}",
@@ -511,9 +843,9 @@ impl UnconfirmedPool {
         Some(prioritized_transaction.transaction)
     }
 
-    /// Remove all unconfirmed transactions that have become time locked. This can happen when the chain height was
-    /// reduced on some reorgs.
-    pub fn remove_timelocked(&mut self, tip_height: u64) {
+    /// Remove all unconfirmed transactions that have become time locked, returning the removed transactions. This can
+    /// happen when the chain height was reduced on some reorgs.
+    pub fn remove_timelocked(&mut self, tip_height: u64) -> Vec<Arc<Transaction>> {
         debug!(target: LOG_TARGET, "Removing time-locked inputs from unconfirmed pool");
         let to_remove = self
             .tx_by_key
@@ -521,9 +853,11 @@ impl UnconfirmedPool {
             .filter(|(_, ptx)| ptx.transaction.min_spendable_height() > tip_height + 1)
             .map(|(k, _)| *k)
             .collect::<Vec<_>>();
+        let mut removed_transactions = Vec::with_capacity(to_remove.len());
         for tx_key in to_remove {
-            self.remove_transaction(tx_key);
+            removed_transactions.extend(self.remove_transaction(tx_key));
         }
+        removed_transactions
     }
 
     /// Returns the total number of unconfirmed transactions stored in the UnconfirmedPool.
@@ -536,6 +870,22 @@ impl UnconfirmedPool {
         self.tx_by_key.iter().map(|(_, ptx)| ptx.transaction.clone()).collect()
     }
 
+    /// Returns up to `max_transactions` of the highest priority transactions stored in the UnconfirmedPool, in the
+    /// order in which they were inserted so that parent transactions precede their children.
+    pub fn snapshot_by_priority(&self, max_transactions: usize) -> Vec<Arc<Transaction>> {
+        let mut keys = self
+            .tx_by_priority
+            .values()
+            .rev()
+            .take(max_transactions)
+            .copied()
+            .collect::<Vec<_>>();
+        keys.sort_unstable();
+        keys.into_iter()
+            .filter_map(|key| self.tx_by_key.get(&key).map(|ptx| ptx.transaction.clone()))
+            .collect()
+    }
+
     /// Returns the total weight of all transactions stored in the pool.
     pub fn calculate_weight(&self, transaction_weight: &TransactionWeight) -> u64 {
         self.tx_by_key.values().fold(0, |weight, ptx| {
@@ -558,7 +908,19 @@ impl UnconfirmedPool {
                 .all(|tx_keys| tx_keys.iter().all(|tx_key| self.tx_by_key.contains_key(tx_key))) &&
             self.txs_by_unique_id
                 .values()
-                .all(|tx_keys| tx_keys.iter().all(|tx_key| self.tx_by_key.contains_key(tx_key)))
+                .all(|tx_keys| tx_keys.iter().all(|tx_key| self.tx_by_key.contains_key(tx_key))) &&
+            self.tx_by_spent_output
+                .values()
+                .all(|tx_key| self.tx_by_key.contains_key(tx_key)) &&
+            self.tx_by_key.iter().all(|(tx_key, ptx)| {
+                let ancestors = self.get_ancestors(&ptx.dependent_output_hashes);
+                let ancestor_fee = ancestors.iter().map(|key| self.tx_by_key[key].fee).sum::<u64>();
+                let ancestor_weight = ancestors.iter().map(|key| self.tx_by_key[key].weight).sum::<u64>();
+                ptx.ancestor_count == ancestors.len() + 1 &&
+                    ptx.ancestor_fee == ptx.fee + ancestor_fee &&
+                    ptx.ancestor_weight == ptx.weight + ancestor_weight &&
+                    ptx.descendant_count == self.get_descendants(&iter::once(*tx_key).collect()).len()
+            })
     }
 
     fn get_next_key(&mut self) -> usize {
@@ -582,6 +944,7 @@ impl UnconfirmedPool {
         shrink_hashmap(&mut self.txs_by_signature);
         shrink_hashmap(&mut self.txs_by_output);
         shrink_hashmap(&mut self.txs_by_unique_id);
+        shrink_hashmap(&mut self.tx_by_spent_output);
 
         if old - new > 0 {
             debug!(
@@ -628,7 +991,7 @@ mod test {
             fee::Fee,
             tari_amount::MicroTari,
             test_helpers::{TestParams, UtxoTestParams},
-            transaction_components::{KernelFeatures, OutputFeatures},
+            transaction_components::{KernelFeatures, OutputFeatures, UnblindedOutput},
             weight::TransactionWeight,
             CryptoFactories,
             SenderTransactionProtocol,
@@ -667,6 +1030,7 @@ mod test {
         let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
             storage_capacity: 4,
             weight_tx_skip_count: 3,
+            ..Default::default()
         });
 
         let tx_weight = TransactionWeight::latest();
@@ -696,28 +1060,21 @@ mod test {
         assert!(unconfirmed_pool.check_data_consistency());
     }
 
-    #[test]
-    fn test_double_spend_inputs() {
-        let (tx1, _, _) = tx!(MicroTari(5_000), fee: MicroTari(10), inputs: 1, outputs: 1);
-        const INPUT_AMOUNT: MicroTari = MicroTari(5_000);
-        let (tx2, inputs, _) = tx!(INPUT_AMOUNT, fee: MicroTari(5), inputs: 1, outputs: 1);
-
+    /// Creates a transaction that spends `input` into a single output, paying the given fee per gram
+    fn spend_with_fee(input: &UnblindedOutput, fee_per_gram: MicroTari) -> Transaction {
+        let factories = CryptoFactories::default();
         let test_params = TestParams::new();
 
         let mut stx_builder = SenderTransactionProtocol::builder(0, create_consensus_constants(0));
         stx_builder
             .with_lock_height(0)
-            .with_fee_per_gram(5.into())
+            .with_fee_per_gram(fee_per_gram)
             .with_offset(Default::default())
             .with_private_nonce(test_params.nonce.clone())
             .with_change_secret(test_params.change_spend_key.clone());
 
-        // Double spend the input from tx2 in tx3
-        let double_spend_utxo = tx2.body.inputs().first().unwrap().clone();
-        let double_spend_input = inputs.first().unwrap().clone();
-
         let estimated_fee = Fee::new(TransactionWeight::latest()).calculate(
-            5.into(),
+            fee_per_gram,
             1,
             1,
             1,
@@ -725,46 +1082,337 @@ mod test {
         );
 
         let utxo = test_params.create_unblinded_output(UtxoTestParams {
-            value: INPUT_AMOUNT - estimated_fee,
+            value: input.value - estimated_fee,
             ..Default::default()
         });
         stx_builder
-            .with_input(double_spend_utxo, double_spend_input)
+            .with_input(
+                input.as_transaction_input(&factories.commitment).unwrap(),
+                input.clone(),
+            )
             .with_output(utxo, test_params.sender_offset_private_key)
             .unwrap();
 
-        let factories = CryptoFactories::default();
         let mut stx_protocol = stx_builder.build::<HashDigest>(&factories, None, u64::MAX).unwrap();
         stx_protocol
             .finalize(KernelFeatures::empty(), &factories, None, u64::MAX)
             .unwrap();
 
-        let tx3 = stx_protocol.get_transaction().unwrap().clone();
+        stx_protocol.get_transaction().unwrap().clone()
+    }
 
-        let tx1 = Arc::new(tx1);
+    fn priority_of<'a>(unconfirmed_pool: &'a UnconfirmedPool, tx: &Transaction) -> &'a PrioritizedTransaction {
+        let key = unconfirmed_pool
+            .txs_by_signature
+            .get(tx.first_kernel_excess_sig().unwrap().get_signature())
+            .unwrap()[0];
+        unconfirmed_pool.tx_by_key.get(&key).unwrap()
+    }
+
+    #[test]
+    fn test_double_spend_inputs() {
+        let tx1 = Arc::new(tx!(MicroTari(5_000), fee: MicroTari(10), inputs: 1, outputs: 1).0);
+        let (tx2, inputs, _) = tx!(MicroTari(5_000), fee: MicroTari(5), inputs: 1, outputs: 1);
         let tx2 = Arc::new(tx2);
-        let tx3 = Arc::new(tx3);
+        // Double spend the input from tx2 in tx3 and tx4. The pool only ever holds one spend of an output, so instead
+        // of storing every double spend and picking one of them for a block, the replace-by-fee rules decide
+        // which spend is kept when it is inserted.
+        let tx3 = Arc::new(spend_with_fee(&inputs[0], MicroTari(2)));
+        let tx4 = Arc::new(spend_with_fee(&inputs[0], MicroTari(20)));
 
         let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
             storage_capacity: 4,
             weight_tx_skip_count: 3,
+            ..Default::default()
         });
 
         let tx_weight = TransactionWeight::latest();
         unconfirmed_pool
-            .insert_many(vec![tx1.clone(), tx2.clone(), tx3.clone()], &tx_weight)
+            .insert_many(vec![tx1.clone(), tx2.clone()], &tx_weight)
             .unwrap();
-        assert_eq!(unconfirmed_pool.len(), 3);
 
-        let desired_weight = tx1.calculate_weight(&tx_weight) +
-            tx2.calculate_weight(&tx_weight) +
-            tx3.calculate_weight(&tx_weight) +
-            1000;
+        // A double spend paying a lower fee does not replace the pooled transaction
+        let response = unconfirmed_pool.insert(tx3.clone(), None, &tx_weight).unwrap();
+        assert_eq!(response, TxStorageResponse::NotStoredFeeTooLowForReplacement);
+        assert_eq!(unconfirmed_pool.len(), 2);
+        assert!(unconfirmed_pool.has_tx_with_excess_sig(&tx2.body.kernels()[0].excess_sig));
+        assert!(!unconfirmed_pool.has_tx_with_excess_sig(&tx3.body.kernels()[0].excess_sig));
+        assert!(unconfirmed_pool.take_evicted_transactions().is_empty());
+
+        // A double spend paying a higher fee replaces it
+        let response = unconfirmed_pool.insert(tx4.clone(), None, &tx_weight).unwrap();
+        assert_eq!(response, TxStorageResponse::UnconfirmedPoolReplaced);
+        assert_eq!(unconfirmed_pool.len(), 2);
+        assert!(!unconfirmed_pool.has_tx_with_excess_sig(&tx2.body.kernels()[0].excess_sig));
+        assert!(unconfirmed_pool.has_tx_with_excess_sig(&tx4.body.kernels()[0].excess_sig));
+        assert_eq!(unconfirmed_pool.take_evicted_transactions(), vec![tx2]);
+
+        let desired_weight = tx1.calculate_weight(&tx_weight) + tx4.calculate_weight(&tx_weight) + 1000;
         let results = unconfirmed_pool.fetch_highest_priority_txs(desired_weight).unwrap();
         assert!(results.retrieved_transactions.contains(&tx1));
-        // Whether tx2 or tx3 is selected is non-deterministic
-        assert!(results.retrieved_transactions.contains(&tx2) ^ results.retrieved_transactions.contains(&tx3));
+        assert!(results.retrieved_transactions.contains(&tx4));
         assert_eq!(results.retrieved_transactions.len(), 2);
+
+        assert!(unconfirmed_pool.check_data_consistency());
+    }
+
+    #[test]
+    fn test_replacement_evicts_descendants() {
+        let (parent, parent_inputs, parent_outputs) = tx!(MicroTari(10_000), fee: MicroTari(5), inputs: 1, outputs: 1);
+        let parent = Arc::new(parent);
+        let child = Arc::new(spend_with_fee(&parent_outputs[0], MicroTari(5)));
+        let replacement = Arc::new(spend_with_fee(&parent_inputs[0], MicroTari(50)));
+        let dependent_outputs = vec![parent.body.outputs()[0].hash()];
+
+        let tx_weight = TransactionWeight::latest();
+        let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
+            storage_capacity: 10,
+            weight_tx_skip_count: 3,
+            max_replacement_evictions: 1,
+            ..Default::default()
+        });
+        unconfirmed_pool.insert(parent.clone(), None, &tx_weight).unwrap();
+        unconfirmed_pool
+            .insert(child.clone(), Some(dependent_outputs.clone()), &tx_weight)
+            .unwrap();
+
+        // Replacing the parent would also evict the child
+        let response = unconfirmed_pool.insert(replacement.clone(), None, &tx_weight).unwrap();
+        assert_eq!(response, TxStorageResponse::NotStoredTooManyReplacements);
+        assert_eq!(unconfirmed_pool.len(), 2);
+
+        let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
+            storage_capacity: 10,
+            weight_tx_skip_count: 3,
+            ..Default::default()
+        });
+        unconfirmed_pool.insert(parent.clone(), None, &tx_weight).unwrap();
+        unconfirmed_pool
+            .insert(child.clone(), Some(dependent_outputs), &tx_weight)
+            .unwrap();
+
+        let response = unconfirmed_pool.insert(replacement.clone(), None, &tx_weight).unwrap();
+        assert_eq!(response, TxStorageResponse::UnconfirmedPoolReplaced);
+        assert_eq!(unconfirmed_pool.len(), 1);
+        assert!(!unconfirmed_pool.has_tx_with_excess_sig(&parent.body.kernels()[0].excess_sig));
+        assert!(!unconfirmed_pool.has_tx_with_excess_sig(&child.body.kernels()[0].excess_sig));
+        assert!(unconfirmed_pool.has_tx_with_excess_sig(&replacement.body.kernels()[0].excess_sig));
+
+        assert!(unconfirmed_pool.check_data_consistency());
+    }
+
+    #[test]
+    fn test_replacement_cannot_spend_evicted_outputs() {
+        let (parent, parent_inputs, _) = tx!(MicroTari(10_000), fee: MicroTari(5), inputs: 1, outputs: 1);
+        let parent = Arc::new(parent);
+        // The replacement double spends the input of the parent while depending on the output of the parent
+        let replacement = Arc::new(spend_with_fee(&parent_inputs[0], MicroTari(50)));
+        let dependent_outputs = vec![parent.body.outputs()[0].hash()];
+
+        let tx_weight = TransactionWeight::latest();
+        let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
+            storage_capacity: 10,
+            weight_tx_skip_count: 3,
+            ..Default::default()
+        });
+        unconfirmed_pool.insert(parent.clone(), None, &tx_weight).unwrap();
+
+        let response = unconfirmed_pool
+            .insert(replacement.clone(), Some(dependent_outputs), &tx_weight)
+            .unwrap();
+        assert_eq!(response, TxStorageResponse::NotStoredReplacementRejected);
+        assert!(!response.is_stored());
+        assert_eq!(unconfirmed_pool.len(), 1);
+        assert!(unconfirmed_pool.has_tx_with_excess_sig(&parent.body.kernels()[0].excess_sig));
+        assert!(!unconfirmed_pool.has_tx_with_excess_sig(&replacement.body.kernels()[0].excess_sig));
+
+        assert!(unconfirmed_pool.check_data_consistency());
+    }
+
+    #[test]
+    fn test_full_pool_rejects_lowest_priority_tx() {
+        let tx1 = Arc::new(tx!(MicroTari(10_000), fee: MicroTari(50), inputs: 1, outputs: 1).0);
+        let tx2 = Arc::new(tx!(MicroTari(10_000), fee: MicroTari(20), inputs: 1, outputs: 1).0);
+        // tx3 pays the lowest fee per gram, but enough to cover the minimum transaction fee so that it is only rejected
+        // for its priority
+        let tx3 = Arc::new(tx!(MicroTari(10_000), fee: MicroTari(5), inputs: 1, outputs: 1).0);
+        let tx4 = Arc::new(tx!(MicroTari(10_000), fee: MicroTari(30), inputs: 1, outputs: 1).0);
+
+        let tx_weight = TransactionWeight::latest();
+        let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
+            storage_capacity: 2,
+            weight_tx_skip_count: 3,
+            ..Default::default()
+        });
+        unconfirmed_pool
+            .insert_many(vec![tx1.clone(), tx2.clone()], &tx_weight)
+            .unwrap();
+
+        // The new transaction would be the first to be evicted, so the pool is left as it was
+        let response = unconfirmed_pool.insert(tx3.clone(), None, &tx_weight).unwrap();
+        assert_eq!(response, TxStorageResponse::NotStoredFeeTooLow);
+        assert_eq!(unconfirmed_pool.len(), 2);
+        assert!(unconfirmed_pool.has_tx_with_excess_sig(&tx1.body.kernels()[0].excess_sig));
+        assert!(unconfirmed_pool.has_tx_with_excess_sig(&tx2.body.kernels()[0].excess_sig));
+        assert!(!unconfirmed_pool.has_tx_with_excess_sig(&tx3.body.kernels()[0].excess_sig));
+
+        // A transaction that outranks the lowest priority transaction takes its place
+        let response = unconfirmed_pool.insert(tx4.clone(), None, &tx_weight).unwrap();
+        assert_eq!(response, TxStorageResponse::UnconfirmedPool);
+        assert_eq!(unconfirmed_pool.len(), 2);
+        assert!(unconfirmed_pool.has_tx_with_excess_sig(&tx1.body.kernels()[0].excess_sig));
+        assert!(!unconfirmed_pool.has_tx_with_excess_sig(&tx2.body.kernels()[0].excess_sig));
+        assert!(unconfirmed_pool.has_tx_with_excess_sig(&tx4.body.kernels()[0].excess_sig));
+
+        assert!(unconfirmed_pool.check_data_consistency());
+    }
+
+    #[test]
+    fn test_package_limits() {
+        let (parent, _, parent_outputs) = tx!(MicroTari(20_000), fee: MicroTari(5), inputs: 1, outputs: 2);
+        let parent = Arc::new(parent);
+        let child1 = Arc::new(spend_with_fee(&parent_outputs[0], MicroTari(10)));
+        let child2 = Arc::new(spend_with_fee(&parent_outputs[1], MicroTari(10)));
+        let dependent_outputs = parent.body.outputs().iter().map(|o| o.hash()).collect::<Vec<_>>();
+
+        let tx_weight = TransactionWeight::latest();
+        let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
+            storage_capacity: 10,
+            weight_tx_skip_count: 3,
+            max_ancestor_count: 1,
+            ..Default::default()
+        });
+        unconfirmed_pool.insert(parent.clone(), None, &tx_weight).unwrap();
+        let response = unconfirmed_pool
+            .insert(child1.clone(), Some(dependent_outputs.clone()), &tx_weight)
+            .unwrap();
+        assert_eq!(response, TxStorageResponse::NotStoredTooManyAncestors);
+        assert_eq!(unconfirmed_pool.len(), 1);
+
+        let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
+            storage_capacity: 10,
+            weight_tx_skip_count: 3,
+            max_descendant_count: 2,
+            ..Default::default()
+        });
+        unconfirmed_pool.insert(parent.clone(), None, &tx_weight).unwrap();
+        let response = unconfirmed_pool
+            .insert(child1.clone(), Some(dependent_outputs.clone()), &tx_weight)
+            .unwrap();
+        assert_eq!(response, TxStorageResponse::UnconfirmedPool);
+        // The parent already has as many descendants as it may have
+        let response = unconfirmed_pool
+            .insert(child2, Some(dependent_outputs), &tx_weight)
+            .unwrap();
+        assert_eq!(response, TxStorageResponse::NotStoredTooManyAncestors);
+        assert_eq!(unconfirmed_pool.len(), 2);
+        assert_eq!(priority_of(&unconfirmed_pool, &parent).descendant_count, 2);
+        assert_eq!(priority_of(&unconfirmed_pool, &child1).ancestor_count, 2);
+
+        assert!(unconfirmed_pool.check_data_consistency());
+    }
+
+    #[test]
+    fn test_child_pays_for_parent() {
+        // The parent pays less per gram than the other transaction, but enough to cover the minimum transaction fee
+        let (parent, _, parent_outputs) = tx!(MicroTari(10_000), fee: MicroTari(5), inputs: 1, outputs: 1);
+        let parent = Arc::new(parent);
+        let other = Arc::new(tx!(MicroTari(10_000), fee: MicroTari(10), inputs: 1, outputs: 1).0);
+        let child = Arc::new(spend_with_fee(&parent_outputs[0], MicroTari(50)));
+
+        let tx_weight = TransactionWeight::latest();
+        let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
+            storage_capacity: 10,
+            weight_tx_skip_count: 3,
+            ..Default::default()
+        });
+        unconfirmed_pool
+            .insert_many(vec![parent.clone(), other.clone()], &tx_weight)
+            .unwrap();
+        assert!(priority_of(&unconfirmed_pool, &parent).priority < priority_of(&unconfirmed_pool, &other).priority);
+
+        let response = unconfirmed_pool
+            .insert(child.clone(), Some(vec![parent.body.outputs()[0].hash()]), &tx_weight)
+            .unwrap();
+        assert_eq!(response, TxStorageResponse::UnconfirmedPool);
+
+        // The child pays for its parent, which now outranks the other transaction
+        assert!(priority_of(&unconfirmed_pool, &parent).priority > priority_of(&unconfirmed_pool, &other).priority);
+        // The child is ranked by the fee rate of the package, which is lower than its own
+        let child_ptx = priority_of(&unconfirmed_pool, &child);
+        assert!(child_ptx.priority.fee_rate() < child_ptx.fee_rate());
+
+        // Only the parent and child fit
+        let desired_weight = parent.calculate_weight(&tx_weight) + child.calculate_weight(&tx_weight);
+        let results = unconfirmed_pool.fetch_highest_priority_txs(desired_weight).unwrap();
+        assert_eq!(results.retrieved_transactions.len(), 2);
+        assert!(results.retrieved_transactions.contains(&parent));
+        assert!(results.retrieved_transactions.contains(&child));
+
+        assert!(unconfirmed_pool.check_data_consistency());
+    }
+
+    #[test]
+    fn test_child_pays_for_parent_before_eviction() {
+        let (parent, _, parent_outputs) = tx!(MicroTari(10_000), fee: MicroTari(5), inputs: 1, outputs: 1);
+        let parent = Arc::new(parent);
+        let other = Arc::new(tx!(MicroTari(10_000), fee: MicroTari(10), inputs: 1, outputs: 1).0);
+        let child = Arc::new(spend_with_fee(&parent_outputs[0], MicroTari(50)));
+
+        let tx_weight = TransactionWeight::latest();
+        let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
+            storage_capacity: 2,
+            weight_tx_skip_count: 3,
+            ..Default::default()
+        });
+        unconfirmed_pool
+            .insert_many(vec![parent.clone(), other.clone()], &tx_weight)
+            .unwrap();
+        unconfirmed_pool
+            .insert(child.clone(), Some(vec![parent.body.outputs()[0].hash()]), &tx_weight)
+            .unwrap();
+
+        // The parent is boosted by the child before choosing what to evict, so the other transaction makes way. Ranked
+        // by its own fee rate the parent would have been evicted and the child left without its input.
+        assert_eq!(unconfirmed_pool.len(), 2);
+        assert!(unconfirmed_pool.has_tx_with_excess_sig(&parent.body.kernels()[0].excess_sig));
+        assert!(unconfirmed_pool.has_tx_with_excess_sig(&child.body.kernels()[0].excess_sig));
+        assert!(!unconfirmed_pool.has_tx_with_excess_sig(&other.body.kernels()[0].excess_sig));
+        assert_eq!(unconfirmed_pool.take_evicted_transactions(), vec![other]);
+
+        assert!(unconfirmed_pool.check_data_consistency());
+    }
+
+    #[test]
+    fn test_parent_priority_restored_when_child_leaves() {
+        let (parent, _, parent_outputs) = tx!(MicroTari(10_000), fee: MicroTari(5), inputs: 1, outputs: 1);
+        let parent = Arc::new(parent);
+        let other = Arc::new(tx!(MicroTari(10_000), fee: MicroTari(10), inputs: 1, outputs: 1).0);
+        let child = Arc::new(spend_with_fee(&parent_outputs[0], MicroTari(50)));
+
+        let tx_weight = TransactionWeight::latest();
+        let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
+            storage_capacity: 10,
+            weight_tx_skip_count: 3,
+            ..Default::default()
+        });
+        unconfirmed_pool
+            .insert_many(vec![parent.clone(), other.clone()], &tx_weight)
+            .unwrap();
+        unconfirmed_pool
+            .insert(child.clone(), Some(vec![parent.body.outputs()[0].hash()]), &tx_weight)
+            .unwrap();
+        assert!(priority_of(&unconfirmed_pool, &parent).priority > priority_of(&unconfirmed_pool, &other).priority);
+
+        let child_key = priority_of(&unconfirmed_pool, &child).key;
+        unconfirmed_pool.remove_transaction(child_key).unwrap();
+
+        // Without the child the parent falls back to its own fee rate
+        let parent_ptx = priority_of(&unconfirmed_pool, &parent);
+        assert_eq!(parent_ptx.priority.fee_rate(), parent_ptx.fee_rate());
+        assert!(parent_ptx.priority < priority_of(&unconfirmed_pool, &other).priority);
+
+        assert!(unconfirmed_pool.check_data_consistency());
     }
 
     #[test]
@@ -782,6 +1430,7 @@ mod test {
         let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
             storage_capacity: 10,
             weight_tx_skip_count: 3,
+            ..Default::default()
         });
         unconfirmed_pool
             .insert_many(
@@ -822,7 +1471,9 @@ mod test {
         let tx4 = Arc::new(tx!(MicroTari(5_000), fee: MicroTari(6), inputs:2, outputs:1).0);
         let mut tx5 = tx!(MicroTari(5_000), fee:MicroTari(5), inputs:3, outputs:1).0;
         let mut tx6 = tx!(MicroTari(5_000), fee:MicroTari(13), inputs: 2, outputs: 1).0;
-        // tx1 and tx5 have a shared input. Also, tx3 and tx6 have a shared input
+        // tx1 and tx5 have a shared input. Also, tx3 and tx6 have a shared input. Under the replace-by-fee rules tx5 is
+        // not stored as it pays no more per gram than tx1, while tx6 pays more than tx3 and replaces it. Either way
+        // neither double spend is left in the pool once the block is published.
         tx5.body.inputs_mut()[0] = tx1.body.inputs()[0].clone();
         tx6.body.inputs_mut()[1] = tx3.body.inputs()[1].clone();
         let tx5 = Arc::new(tx5);
@@ -832,6 +1483,7 @@ mod test {
         let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
             storage_capacity: 10,
             weight_tx_skip_count: 3,
+            ..Default::default()
         });
         unconfirmed_pool
             .insert_many(
@@ -874,6 +1526,11 @@ mod test {
         let (tx6, _, _) = tx!(MicroTari(450_000), fee: MicroTari(50), inputs:5, outputs:5);
         tx3.body.set_kernel(tx5.body.kernels()[0].clone());
         tx4.body.set_kernel(tx6.body.kernels()[0].clone());
+        // Spend different inputs so that the transactions do not replace each other. As copies of tx1 and tx2 they
+        // would conflict with them and, paying the same fee, be rejected by the replace-by-fee rules instead of
+        // being stored.
+        *tx3.body.inputs_mut() = tx5.body.inputs().clone();
+        *tx4.body.inputs_mut() = tx6.body.inputs().clone();
 
         // Insert multiple transactions with the same outputs into the mempool
 
@@ -881,6 +1538,7 @@ mod test {
         let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
             storage_capacity: 10,
             weight_tx_skip_count: 3,
+            ..Default::default()
         });
         let txns = vec![
             Arc::new(tx1.clone()),
@@ -968,6 +1626,7 @@ mod test {
         let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
             storage_capacity: 10,
             weight_tx_skip_count: 3,
+            ..Default::default()
         });
 
         let tx1 = Arc::new(tx1);
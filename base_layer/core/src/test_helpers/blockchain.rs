@@ -22,6 +22,7 @@
 
 use std::{
     collections::HashMap,
+    env,
     fs,
     ops::{Deref, Range},
     path::{Path, PathBuf},
@@ -34,6 +35,7 @@ use tari_common_types::{
     chain_metadata::ChainMetadata,
     types::{Commitment, HashOutput, PublicKey, Signature},
 };
+use tari_crypto::script::TariScript;
 use tari_storage::lmdb_store::LMDBConfig;
 use tari_test_utils::paths::create_temporary_data_path;
 use tari_utilities::Hashable;
@@ -52,6 +54,7 @@ use crate::{
     },
     chain_storage::{
         create_lmdb_database,
+        create_sled_database,
         BlockchainBackend,
         BlockchainDatabase,
         BlockchainDatabaseConfig,
@@ -62,10 +65,10 @@ use crate::{
         DbTransaction,
         DbValue,
         HorizonData,
-        LMDBDatabase,
         MmrTree,
         PrunedOutput,
         Reorg,
+        SledConfig,
         UtxoMinedInfo,
         Validators,
     },
@@ -89,14 +92,36 @@ pub fn create_new_blockchain() -> BlockchainDatabase<TempDatabase> {
 }
 
 pub fn create_new_blockchain_with_network(network: Network) -> BlockchainDatabase<TempDatabase> {
+    create_custom_blockchain(create_test_consensus_manager(network))
+}
+
+/// Create a new blockchain database for the local network that uses the given backend.
+pub fn create_new_blockchain_with_backend(backend: TempDatabase) -> BlockchainDatabase<TempDatabase> {
+    let rules = create_test_consensus_manager(Network::LocalNet);
+    let validators = Validators::new(
+        MockValidator::new(true),
+        MockValidator::new(true),
+        MockValidator::new(true),
+    );
+    BlockchainDatabase::new(
+        backend,
+        rules.clone(),
+        validators,
+        BlockchainDatabaseConfig::default(),
+        DifficultyCalculator::new(rules, Default::default()),
+        false,
+    )
+    .unwrap()
+}
+
+fn create_test_consensus_manager(network: Network) -> ConsensusManager {
     let consensus_constants = ConsensusConstantsBuilder::new(network).build();
     let genesis = get_genesis_block(network);
-    let consensus_manager = ConsensusManager::builder(network)
+    ConsensusManager::builder(network)
         .add_consensus_constants(consensus_constants)
         .with_block(genesis)
         .on_ties(ChainStrengthComparerBuilder::new().by_height().build())
-        .build();
-    create_custom_blockchain(consensus_manager)
+        .build()
 }
 
 /// Create a new custom blockchain database containing no blocks.
@@ -151,9 +176,20 @@ pub fn create_test_db() -> TempDatabase {
     TempDatabase::new()
 }
 
+/// Set this environment variable to `sled` to run the test suites against the sled backend instead of LMDB. CI runs
+/// the `tari_core` tests against both backends.
+pub const TEST_DB_BACKEND_ENV_VAR: &str = "TARI_TEST_DB_BACKEND";
+
+fn create_backend_at_path<P: AsRef<Path>>(path: P, output_search_indexes: bool) -> Box<dyn BlockchainBackend> {
+    match env::var(TEST_DB_BACKEND_ENV_VAR).as_deref() {
+        Ok("sled") => Box::new(create_sled_database(path, SledConfig::default(), output_search_indexes).unwrap()),
+        _ => Box::new(create_lmdb_database(path, LMDBConfig::default(), output_search_indexes).unwrap()),
+    }
+}
+
 pub struct TempDatabase {
     path: PathBuf,
-    db: Option<LMDBDatabase>,
+    db: Option<Box<dyn BlockchainBackend>>,
     delete_on_drop: bool,
 }
 
@@ -162,15 +198,20 @@ impl TempDatabase {
         let temp_path = create_temporary_data_path();
 
         Self {
-            db: Some(create_lmdb_database(&temp_path, LMDBConfig::default()).unwrap()),
+            db: Some(create_backend_at_path(&temp_path, true)),
             path: temp_path,
             delete_on_drop: true,
         }
     }
 
     pub fn from_path<P: AsRef<Path>>(temp_path: P) -> Self {
+        Self::from_path_with_output_search_indexes(temp_path, true)
+    }
+
+    /// Opens the database at the given path, with or without the UTXO search indexes.
+    pub fn from_path_with_output_search_indexes<P: AsRef<Path>>(temp_path: P, output_search_indexes: bool) -> Self {
         Self {
-            db: Some(create_lmdb_database(&temp_path, LMDBConfig::default()).unwrap()),
+            db: Some(create_backend_at_path(&temp_path, output_search_indexes)),
             path: temp_path.as_ref().to_path_buf(),
             delete_on_drop: true,
         }
@@ -189,16 +230,16 @@ impl Default for TempDatabase {
 }
 
 impl Deref for TempDatabase {
-    type Target = LMDBDatabase;
+    type Target = dyn BlockchainBackend;
 
     fn deref(&self) -> &Self::Target {
-        self.db.as_ref().unwrap()
+        &**self.db.as_ref().unwrap()
     }
 }
 
 impl Drop for TempDatabase {
     fn drop(&mut self) {
-        // force a drop on the LMDB db
+        // force a drop on the backend so that its files are released
         self.db = None;
         if self.delete_on_drop && Path::new(&self.path).exists() {
             fs::remove_dir_all(&self.path).expect("Could not delete temporary file");
@@ -326,6 +367,25 @@ impl BlockchainBackend for TempDatabase {
             .fetch_all_unspent_by_parent_public_key(parent_public_key, range)
     }
 
+    fn fetch_unspent_outputs_by_script(
+        &self,
+        script: &TariScript,
+        range: Range<usize>,
+    ) -> Result<Vec<UtxoMinedInfo>, ChainStorageError> {
+        self.db.as_ref().unwrap().fetch_unspent_outputs_by_script(script, range)
+    }
+
+    fn fetch_unspent_outputs_by_sender_offset_public_key(
+        &self,
+        sender_offset_public_key: &PublicKey,
+        range: Range<usize>,
+    ) -> Result<Vec<UtxoMinedInfo>, ChainStorageError> {
+        self.db
+            .as_ref()
+            .unwrap()
+            .fetch_unspent_outputs_by_sender_offset_public_key(sender_offset_public_key, range)
+    }
+
     fn fetch_outputs_in_block(&self, header_hash: &HashOutput) -> Result<Vec<PrunedOutput>, ChainStorageError> {
         self.db.as_ref().unwrap().fetch_outputs_in_block(header_hash)
     }
@@ -44,20 +44,23 @@ use tari_crypto::{
     tari_utilities::hex::Hex,
 };
 
-use crate::transactions::{
-    crypto_factories::CryptoFactories,
-    tari_amount::MicroTari,
-    transaction_components::{
-        KernelFeatures,
-        KernelSum,
-        OutputFlags,
-        Transaction,
-        TransactionError,
-        TransactionInput,
-        TransactionKernel,
-        TransactionOutput,
+use crate::{
+    covenants::CovenantError,
+    transactions::{
+        crypto_factories::CryptoFactories,
+        tari_amount::MicroTari,
+        transaction_components::{
+            KernelFeatures,
+            KernelSum,
+            OutputFlags,
+            Transaction,
+            TransactionError,
+            TransactionInput,
+            TransactionKernel,
+            TransactionOutput,
+        },
+        weight::TransactionWeight,
     },
-    weight::TransactionWeight,
 };
 
 pub const LOG_TARGET: &str = "c::tx::aggregated_body";
@@ -460,9 +463,15 @@ impl AggregateBody {
         Ok(())
     }
 
+    /// Executes the covenant of every input without the chain. Covenants that depend on the height at which the input
+    /// was mined cannot be checked here and are left to `validation::helpers::validate_covenants`, which every block
+    /// and mempool transaction passes through.
     fn validate_covenants(&self, height: u64) -> Result<(), TransactionError> {
         for input in self.inputs.iter() {
-            input.covenant()?.execute(height, input, &self.outputs)?;
+            match input.covenant()?.execute(height, input, &self.outputs) {
+                Ok(_) | Err(CovenantError::InputMinedHeightUnknown { .. }) => {},
+                Err(err) => return Err(err.into()),
+            }
         }
         Ok(())
     }
@@ -151,7 +151,8 @@ impl TransactionInput {
         };
     }
 
-    pub(super) fn build_script_challenge(
+    /// Convenience function that calculates the challenge for the script signature
+    pub fn build_script_challenge(
         nonce_commitment: &Commitment,
         script: &TariScript,
         input_data: &ExecutionStack,
@@ -83,6 +83,7 @@ pub struct SenderTransactionInitializer {
     inputs: Vec<TransactionInput>,
     unblinded_inputs: Vec<UnblindedOutput>,
     sender_custom_outputs: Vec<UnblindedOutput>,
+    sender_custom_outputs_rewind_data: Vec<Option<RewindData>>,
     sender_offset_private_keys: Vec<PrivateKey>,
     change_secret: Option<BlindingFactor>,
     change_script: Option<TariScript>,
@@ -127,6 +128,7 @@ impl SenderTransactionInitializer {
             inputs: Vec::new(),
             unblinded_inputs: Vec::new(),
             sender_custom_outputs: Vec::new(),
+            sender_custom_outputs_rewind_data: Vec::new(),
             sender_offset_private_keys: vec![],
             change_secret: None,
             change_script: None,
@@ -211,6 +213,27 @@ impl SenderTransactionInitializer {
         &mut self,
         output: UnblindedOutput,
         sender_offset_private_key: PrivateKey,
+    ) -> Result<&mut Self, BuildError> {
+        self.add_sender_output(output, sender_offset_private_key, None)
+    }
+
+    /// Adds an output as the sender, like `with_output`, but with its own rewind data instead of the rewind data
+    /// provided with `with_rewindable_outputs`. This allows the sender to create outputs that are rewindable by
+    /// another wallet, such as one-sided payments.
+    pub fn with_rewindable_output(
+        &mut self,
+        output: UnblindedOutput,
+        sender_offset_private_key: PrivateKey,
+        rewind_data: RewindData,
+    ) -> Result<&mut Self, BuildError> {
+        self.add_sender_output(output, sender_offset_private_key, Some(rewind_data))
+    }
+
+    fn add_sender_output(
+        &mut self,
+        output: UnblindedOutput,
+        sender_offset_private_key: PrivateKey,
+        rewind_data: Option<RewindData>,
     ) -> Result<&mut Self, BuildError> {
         let commitment_factory = PedersenCommitmentFactory::default();
         let commitment = commitment_factory.commit(&output.spending_key, &PrivateKey::from(output.value));
@@ -234,6 +257,7 @@ impl SenderTransactionInitializer {
         }
         self.excess_blinding_factor = &self.excess_blinding_factor + &output.spending_key;
         self.sender_custom_outputs.push(output);
+        self.sender_custom_outputs_rewind_data.push(rewind_data);
         self.sender_offset_private_keys.push(sender_offset_private_key);
         Ok(self)
     }
@@ -499,8 +523,9 @@ impl SenderTransactionInitializer {
         let mut outputs = match self
             .sender_custom_outputs
             .iter()
-            .map(|o| {
-                if let Some(rewind_data) = self.rewind_data.as_ref() {
+            .zip(self.sender_custom_outputs_rewind_data.iter())
+            .map(|(o, output_rewind_data)| {
+                if let Some(rewind_data) = output_rewind_data.as_ref().or_else(|| self.rewind_data.as_ref()) {
                     o.as_rewindable_transaction_output(factories, rewind_data, None)
                 } else {
                     o.as_transaction_output(factories)
@@ -680,6 +705,7 @@ mod test {
             transaction_protocol::{
                 sender::SenderState,
                 transaction_initializer::SenderTransactionInitializer,
+                RewindData,
                 TransactionProtocolError,
             },
         },
@@ -801,6 +827,70 @@ mod test {
         }
     }
 
+    /// A sender output with its own rewind data can only be rewound with that data
+    #[test]
+    fn rewindable_sender_output() {
+        let factories = CryptoFactories::default();
+        let p = TestParams::new();
+        let (utxo, input) = create_test_input(MicroTari(5000), 0, &factories.commitment);
+        let output = create_unblinded_output(
+            TariScript::default(),
+            OutputFeatures::default(),
+            p.clone(),
+            MicroTari(1000),
+        );
+        let output_rewind_data = RewindData {
+            rewind_key: PrivateKey::random(&mut OsRng),
+            rewind_blinding_key: PrivateKey::random(&mut OsRng),
+            proof_message: [0u8; 21],
+        };
+        let change_rewind_data = RewindData {
+            rewind_key: PrivateKey::random(&mut OsRng),
+            rewind_blinding_key: PrivateKey::random(&mut OsRng),
+            proof_message: [1u8; 21],
+        };
+        let mut builder = SenderTransactionInitializer::new(0, create_consensus_constants(0));
+        builder
+            .with_lock_height(0)
+            .with_offset(p.offset)
+            .with_private_nonce(p.nonce)
+            .with_rewindable_output(output, p.sender_offset_private_key, output_rewind_data.clone())
+            .unwrap()
+            .with_input(utxo, input)
+            .with_fee_per_gram(MicroTari(4))
+            .with_change_secret(p.change_spend_key)
+            .with_change_script(TariScript::default(), ExecutionStack::default(), PrivateKey::default())
+            .with_rewindable_outputs(change_rewind_data.clone());
+        let result = builder.build::<Blake256>(&factories, None, u64::MAX).unwrap();
+        if let SenderState::Finalizing(info) = result.into_state() {
+            assert_eq!(info.outputs.len(), 2, "There should be 2 outputs");
+            let rewound = info.outputs[0]
+                .full_rewind_range_proof(
+                    &factories.range_proof,
+                    &output_rewind_data.rewind_key,
+                    &output_rewind_data.rewind_blinding_key,
+                )
+                .unwrap();
+            assert_eq!(rewound.committed_value, MicroTari(1000));
+            assert!(info.outputs[0]
+                .full_rewind_range_proof(
+                    &factories.range_proof,
+                    &change_rewind_data.rewind_key,
+                    &change_rewind_data.rewind_blinding_key,
+                )
+                .is_err());
+            assert!(info.outputs[1]
+                .full_rewind_range_proof(
+                    &factories.range_proof,
+                    &change_rewind_data.rewind_key,
+                    &change_rewind_data.rewind_blinding_key,
+                )
+                .is_ok());
+        } else {
+            panic!("There were no recipients, so we should be finalizing");
+        }
+    }
+
     /// Hit the edge case where our change isn't enough to cover the cost of an extra output
     #[test]
     #[allow(clippy::identity_op)]
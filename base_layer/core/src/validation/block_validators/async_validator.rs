@@ -155,7 +155,13 @@ impl<B: BlockchainBackend + 'static> BlockValidator<B> {
             AggregateBody::new_sorted_unchecked(inputs_result.inputs, outputs_result.outputs, kernels_result.kernels),
         );
 
-        helpers::validate_covenants(&block)?;
+        let db = self.db.inner().clone();
+        let block = task::spawn_blocking(move || {
+            let db = db.db_read_access()?;
+            helpers::validate_covenants(&*db, &block.body, block.header.height)?;
+            Ok::<_, ValidationError>(block)
+        })
+        .await??;
 
         Ok(block)
     }
@@ -93,7 +93,7 @@ impl<B: BlockchainBackend> PostOrphanBodyValidation<B> for BodyOnlyValidator {
             block_id
         );
         helpers::check_not_bad_block(backend, block.hash())?;
-        helpers::validate_covenants(block.block())?;
+        helpers::validate_covenants(backend, &block.block().body, block.height())?;
 
         debug!(target: LOG_TARGET, "Block validation: Block is VALID for {}", block_id);
         Ok(())
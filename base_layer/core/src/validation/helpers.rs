@@ -670,11 +670,23 @@ pub fn check_not_bad_block<B: BlockchainBackend>(db: &B, hash: &[u8]) -> Result<
     Ok(())
 }
 
-pub fn validate_covenants(block: &Block) -> Result<(), ValidationError> {
-    for input in block.body.inputs() {
-        let output_set_size = input
-            .covenant()?
-            .execute(block.header.height, input, block.body.outputs())?;
+/// Executes the covenant of every input in `body` against its outputs at `height`. Height-relative covenant filters are
+/// evaluated using the height at which the spent output was mined. Outputs that are not yet in the chain (i.e. spent
+/// in the same block or zero-conf) are treated as mined at `height`.
+pub fn validate_covenants<B: BlockchainBackend>(
+    db: &B,
+    body: &AggregateBody,
+    height: u64,
+) -> Result<(), ValidationError> {
+    for input in body.inputs() {
+        let mined_height = db
+            .fetch_output(&input.output_hash())?
+            .map(|info| info.mined_height)
+            .unwrap_or(height);
+        let output_set_size =
+            input
+                .covenant()?
+                .execute_with_input_mined_height(height, input, mined_height, body.outputs())?;
         trace!(target: LOG_TARGET, "{} output(s) passed covenant", output_set_size);
     }
     Ok(())
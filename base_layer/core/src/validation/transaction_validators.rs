@@ -31,7 +31,7 @@ use crate::{
         CryptoFactories,
     },
     validation::{
-        helpers::{check_inputs_are_utxos, check_outputs},
+        helpers::{check_inputs_are_utxos, check_outputs, validate_covenants},
         MempoolTransactionValidation,
         ValidationError,
     },
@@ -250,7 +250,9 @@ impl<B: BlockchainBackend> MempoolTransactionValidation for TxInputAndMaturityVa
             let db = self.db.db_read_access()?;
             check_inputs_are_utxos(&*db, tx.body())?;
             check_outputs(&*db, constants, tx.body())?;
-            db.fetch_chain_metadata()?.height_of_longest_chain()
+            let tip_height = db.fetch_chain_metadata()?.height_of_longest_chain();
+            validate_covenants(&*db, tx.body(), tip_height + 1)?;
+            tip_height
         };
 
         verify_timelocks(tx, tip_height)?;
@@ -22,7 +22,17 @@
 
 use tari_common::configuration::Network;
 use tari_core::{
-    chain_storage::{create_lmdb_database, BlockchainBackend, ChainStorageError, DbKey, DbTransaction, DbValue},
+    chain_storage::{
+        create_lmdb_database,
+        create_sled_database,
+        BlockchainBackend,
+        ChainStorageError,
+        DbKey,
+        DbTransaction,
+        DbValue,
+        MmrTree,
+        SledConfig,
+    },
     consensus::ConsensusManagerBuilder,
     test_helpers::blockchain::create_test_db,
     tx,
@@ -35,9 +45,23 @@ use crate::helpers::database::create_orphan_block;
 
 #[test]
 fn lmdb_insert_contains_delete_and_fetch_orphan() {
+    let mut db = create_test_db();
+    insert_contains_delete_and_fetch_orphan(&mut db);
+}
+
+#[test]
+fn sled_insert_contains_delete_and_fetch_orphan() {
+    let temp_path = create_temporary_data_path();
+    {
+        let mut db = create_sled_database(&temp_path, SledConfig::default(), true).unwrap();
+        insert_contains_delete_and_fetch_orphan(&mut db);
+    }
+    std::fs::remove_dir_all(&temp_path).expect("Could not clear temp storage for db");
+}
+
+fn insert_contains_delete_and_fetch_orphan<T: BlockchainBackend>(db: &mut T) {
     let network = Network::LocalNet;
     let consensus = ConsensusManagerBuilder::new(network).build();
-    let mut db = create_test_db();
     let txs = vec![
         (tx!(1000.into(), fee: 4.into(), inputs: 2, outputs: 1)).0,
         (tx!(2000.into(), fee: 6.into(), inputs: 1, outputs: 1)).0,
@@ -70,17 +94,17 @@ fn lmdb_file_lock() {
 
     // Perform test
     {
-        let db = create_lmdb_database(&temp_path, LMDBConfig::default()).unwrap();
+        let db = create_lmdb_database(&temp_path, LMDBConfig::default(), true).unwrap();
 
-        match create_lmdb_database(&temp_path, LMDBConfig::default()) {
+        match create_lmdb_database(&temp_path, LMDBConfig::default(), true) {
             Err(ChainStorageError::CannotAcquireFileLock) => {},
             _ => panic!("Should not be able to make this db"),
         }
 
         drop(db);
 
-        let _db2 =
-            create_lmdb_database(&temp_path, LMDBConfig::default()).expect("Should be able to make a new lmdb now");
+        let _db2 = create_lmdb_database(&temp_path, LMDBConfig::default(), true)
+            .expect("Should be able to make a new lmdb now");
     }
 
     // Cleanup test data - in Windows the LMBD `set_mapsize` sets file size equals to map size; Linux use sparse files
@@ -88,3 +112,43 @@ fn lmdb_file_lock() {
         std::fs::remove_dir_all(&temp_path).expect("Could not clear temp storage for db");
     }
 }
+
+#[test]
+fn sled_file_lock() {
+    // Create temporary test folder
+    let temp_path = create_temporary_data_path();
+
+    // Perform test
+    {
+        let db = create_sled_database(&temp_path, SledConfig::default(), true).unwrap();
+
+        match create_sled_database(&temp_path, SledConfig::default(), true) {
+            Err(ChainStorageError::CannotAcquireFileLock) => {},
+            _ => panic!("Should not be able to make this db"),
+        }
+
+        drop(db);
+
+        let _db2 = create_sled_database(&temp_path, SledConfig::default(), true)
+            .expect("Should be able to make a new sled db now");
+    }
+
+    std::fs::remove_dir_all(&temp_path).expect("Could not clear temp storage for db");
+}
+
+#[test]
+fn sled_fetch_mmr_leaf_index_rejects_unsupported_trees() {
+    let temp_path = create_temporary_data_path();
+    {
+        let db = create_sled_database(&temp_path, SledConfig::default(), true).unwrap();
+        let hash = vec![0u8; 32];
+        assert_eq!(db.fetch_mmr_leaf_index(MmrTree::Utxo, &hash).unwrap(), None);
+        for tree in [MmrTree::Kernel, MmrTree::Witness] {
+            assert!(matches!(
+                db.fetch_mmr_leaf_index(tree, &hash),
+                Err(ChainStorageError::InvalidArguments { .. })
+            ));
+        }
+    }
+    std::fs::remove_dir_all(&temp_path).expect("Could not clear temp storage for db");
+}
@@ -1676,7 +1676,7 @@ fn orphan_cleanup_delete_all_orphans() {
     };
     // Test cleanup during runtime
     {
-        let db = create_lmdb_database(&path, LMDBConfig::default()).unwrap();
+        let db = create_lmdb_database(&path, LMDBConfig::default(), true).unwrap();
         let store = BlockchainDatabase::new(
             db,
             consensus_manager.clone(),
@@ -1730,7 +1730,7 @@ fn orphan_cleanup_delete_all_orphans() {
 
     // Test orphans are present on open
     {
-        let db = create_lmdb_database(&path, LMDBConfig::default()).unwrap();
+        let db = create_lmdb_database(&path, LMDBConfig::default(), true).unwrap();
         let store = BlockchainDatabase::new(
             db,
             consensus_manager.clone(),
@@ -1745,7 +1745,7 @@ fn orphan_cleanup_delete_all_orphans() {
 
     // Test orphans cleanup on open
     {
-        let db = create_lmdb_database(&path, LMDBConfig::default()).unwrap();
+        let db = create_lmdb_database(&path, LMDBConfig::default(), true).unwrap();
         let store = BlockchainDatabase::new(
             db,
             consensus_manager.clone(),
@@ -659,7 +659,10 @@ async fn test_zero_conf() {
     assert!(retrieved_txs.contains(&Arc::new(tx34.clone())));
 
     // Verify that a higher priority transaction is not retrieved due to its zero-conf dependency instead of the lowest
-    // priority transaction
+    // priority transaction. Transactions are ranked by the fee rate of the package they form with their unconfirmed
+    // ancestors rather than by their own fee rate, which would make tx31 the lowest priority. tx31's package (tx01,
+    // tx11 and tx21) pays about 70 µT per gram, while tx32 also pulls in the low paying tx02 and tx12 along with tx01,
+    // tx11 and tx22, bringing its package down to about 63 µT per gram. tx32 is left out instead.
     let weight = mempool.stats().await.unwrap().total_weight - 1;
     let retrieved_txs = mempool.retrieve(weight).await.unwrap();
     assert_eq!(retrieved_txs.len(), 15);
@@ -675,8 +678,8 @@ async fn test_zero_conf() {
     assert!(retrieved_txs.contains(&Arc::new(tx22)));
     assert!(retrieved_txs.contains(&Arc::new(tx23)));
     assert!(retrieved_txs.contains(&Arc::new(tx24)));
-    assert!(!retrieved_txs.contains(&Arc::new(tx31))); // Missing
-    assert!(retrieved_txs.contains(&Arc::new(tx32)));
+    assert!(retrieved_txs.contains(&Arc::new(tx31)));
+    assert!(!retrieved_txs.contains(&Arc::new(tx32))); // Missing
     assert!(retrieved_txs.contains(&Arc::new(tx33)));
     assert!(retrieved_txs.contains(&Arc::new(tx34)));
 }
@@ -767,6 +770,179 @@ async fn test_reorg() {
     mempool.process_reorg(vec![], vec![reorg_block4.into()]).await.unwrap();
 }
 
+#[tokio::test]
+#[allow(clippy::identity_op)]
+async fn test_fee_estimates_ignore_double_spends() {
+    let network = Network::LocalNet;
+    let (mut db, mut blocks, mut outputs, consensus_manager) = create_new_blockchain(network);
+    let mempool_validator = TxInputAndMaturityValidator::new(db.clone());
+    let mempool = Mempool::new(
+        MempoolConfig::default(),
+        consensus_manager.clone(),
+        Box::new(mempool_validator),
+    );
+
+    // "Mine" Block 1
+    let txs = vec![
+        txn_schema!(from: vec![outputs[0][0].clone()], to: vec![1 * T, 1 * T], fee: 25*uT, lock: 0, features: OutputFeatures::default()),
+    ];
+    generate_new_block(&mut db, &mut blocks, &mut outputs, txs, &consensus_manager).unwrap();
+    mempool.process_published_block(blocks[1].to_arc_block()).await.unwrap();
+
+    // A well paying transaction enters the mempool, but a double spend of its input paying a low fee is mined
+    let schemas = vec![
+        txn_schema!(from: vec![outputs[1][0].clone()], to: vec![], fee: 100*uT, lock: 0, features: OutputFeatures::default()),
+        txn_schema!(from: vec![outputs[1][0].clone()], to: vec![], fee: 5*uT, lock: 0, features: OutputFeatures::default()),
+    ];
+    let (txns, _) = schema_to_transaction(&schemas);
+    assert_eq!(
+        mempool.insert(txns[0].clone()).await.unwrap(),
+        TxStorageResponse::UnconfirmedPool
+    );
+    generate_block(&db, &mut blocks, vec![txns[1].deref().clone()], &consensus_manager).unwrap();
+    mempool.process_published_block(blocks[2].to_arc_block()).await.unwrap();
+
+    // The discarded double spend was not mined, so it must not be counted as confirmed at its fee rate
+    let stats = mempool.stats().await.unwrap();
+    assert_eq!(stats.unconfirmed_txs, 0);
+    assert!(mempool.estimate_fee_per_gram(1).await.unwrap().is_none());
+}
+
+#[tokio::test]
+#[allow(clippy::identity_op)]
+async fn test_fee_estimates_ignore_replaced_transactions() {
+    let network = Network::LocalNet;
+    let (mut db, mut blocks, mut outputs, consensus_manager) = create_new_blockchain(network);
+    let mempool_validator = TxInputAndMaturityValidator::new(db.clone());
+    let mut config = MempoolConfig::default();
+    config.fee_estimator.max_target_blocks = 1;
+    let mempool = Mempool::new(config, consensus_manager.clone(), Box::new(mempool_validator));
+
+    // "Mine" Block 1
+    let txs = vec![
+        txn_schema!(from: vec![outputs[0][0].clone()], to: vec![1 * T, 1 * T], fee: 25*uT, lock: 0, features: OutputFeatures::default()),
+    ];
+    generate_new_block(&mut db, &mut blocks, &mut outputs, txs, &consensus_manager).unwrap();
+    mempool.process_published_block(blocks[1].to_arc_block()).await.unwrap();
+
+    // The first transaction is replaced by a double spend paying a higher fee, the other pays the same fee as it did
+    let schemas = vec![
+        txn_schema!(from: vec![outputs[1][0].clone()], to: vec![], fee: 10*uT, lock: 0, features: OutputFeatures::default()),
+        txn_schema!(from: vec![outputs[1][0].clone()], to: vec![], fee: 100*uT, lock: 0, features: OutputFeatures::default()),
+        txn_schema!(from: vec![outputs[1][1].clone()], to: vec![], fee: 10*uT, lock: 0, features: OutputFeatures::default()),
+    ];
+    let (txns, _) = schema_to_transaction(&schemas);
+    assert_eq!(
+        mempool.insert(txns[0].clone()).await.unwrap(),
+        TxStorageResponse::UnconfirmedPool
+    );
+    assert_eq!(
+        mempool.insert(txns[1].clone()).await.unwrap(),
+        TxStorageResponse::UnconfirmedPoolReplaced
+    );
+    assert_eq!(
+        mempool.insert(txns[2].clone()).await.unwrap(),
+        TxStorageResponse::UnconfirmedPool
+    );
+    let mined = vec![txns[1].deref().clone(), txns[2].deref().clone()];
+    generate_block(&db, &mut blocks, mined, &consensus_manager).unwrap();
+    mempool.process_published_block(blocks[2].to_arc_block()).await.unwrap();
+    // The replaced transaction would have expired by now had it still been tracked
+    generate_block(&db, &mut blocks, vec![], &consensus_manager).unwrap();
+    mempool.process_published_block(blocks[3].to_arc_block()).await.unwrap();
+
+    // Every transaction paying 10 µT per gram that could be mined was mined in the next block
+    let estimate = mempool.estimate_fee_per_gram(1).await.unwrap().unwrap();
+    assert!(estimate <= 10 * uT);
+}
+
+#[tokio::test]
+#[allow(clippy::identity_op)]
+async fn test_persist_and_restore() {
+    let network = Network::LocalNet;
+    let (mut db, mut blocks, mut outputs, consensus_manager) = create_new_blockchain(network);
+    let validator_db = db.clone();
+    let new_mempool = || {
+        Mempool::new(
+            MempoolConfig::default(),
+            consensus_manager.clone(),
+            Box::new(TxInputAndMaturityValidator::new(validator_db.clone())),
+        )
+    };
+    let mempool = new_mempool();
+
+    // "Mine" Block 1
+    let txs = vec![
+        txn_schema!(from: vec![outputs[0][0].clone()], to: vec![1 * T, 1 * T], fee: 25*uT, lock: 0, features: OutputFeatures::default()),
+    ];
+    generate_new_block(&mut db, &mut blocks, &mut outputs, txs, &consensus_manager).unwrap();
+    mempool.process_published_block(blocks[1].to_arc_block()).await.unwrap();
+
+    let schemas = vec![
+        txn_schema!(from: vec![outputs[1][0].clone()], to: vec![], fee: 25*uT, lock: 0, features: OutputFeatures::default()),
+        txn_schema!(from: vec![outputs[1][1].clone()], to: vec![], fee: 30*uT, lock: 0, features: OutputFeatures::default()),
+        txn_schema!(from: vec![outputs[1][2].clone()], to: vec![], fee: 35*uT, lock: 0, features: OutputFeatures::default()),
+    ];
+    let (txns, _) = schema_to_transaction(&schemas);
+    for tx in &txns {
+        mempool.insert(tx.clone()).await.unwrap();
+    }
+
+    // "Mine" block 2, moving the first transaction into the reorg pool
+    generate_block(&db, &mut blocks, vec![txns[0].deref().clone()], &consensus_manager).unwrap();
+    mempool.process_published_block(blocks[2].to_arc_block()).await.unwrap();
+    let stats = mempool.stats().await.unwrap();
+    assert_eq!(stats.unconfirmed_txs, 2);
+    assert_eq!(stats.reorg_txs, 1);
+
+    let temp_dir = tempdir().unwrap();
+    let path = temp_dir.path().join("mempool.dat");
+    let num_persisted = mempool.persist_to_file(path.clone(), 100).await.unwrap();
+    assert_eq!(num_persisted, 3);
+
+    let restored = new_mempool();
+    let num_restored = restored.restore_from_file(path.clone()).await.unwrap();
+    assert_eq!(num_restored, 2);
+    let stats = restored.stats().await.unwrap();
+    assert_eq!(stats.unconfirmed_txs, 2);
+    assert_eq!(stats.reorg_txs, 1);
+    assert_eq!(
+        restored.has_transaction(txns[0].clone()).await.unwrap(),
+        TxStorageResponse::ReorgPool
+    );
+
+    // "Mine" block 3 while the node is down. The mined transaction is no longer valid and is not restored.
+    generate_block(&db, &mut blocks, vec![txns[1].deref().clone()], &consensus_manager).unwrap();
+    let restored = new_mempool();
+    let num_restored = restored.restore_from_file(path.clone()).await.unwrap();
+    assert_eq!(num_restored, 1);
+    assert_eq!(
+        restored.has_transaction(txns[2].clone()).await.unwrap(),
+        TxStorageResponse::UnconfirmedPool
+    );
+
+    // Only the highest priority transaction is persisted when capped
+    let num_persisted = mempool.persist_to_file(path.clone(), 1).await.unwrap();
+    assert_eq!(num_persisted, 1);
+    let restored = new_mempool();
+    restored.restore_from_file(path).await.unwrap();
+    let stats = restored.stats().await.unwrap();
+    assert_eq!(stats.unconfirmed_txs, 1);
+    assert_eq!(stats.reorg_txs, 0);
+    assert_eq!(
+        restored.has_transaction(txns[2].clone()).await.unwrap(),
+        TxStorageResponse::UnconfirmedPool
+    );
+
+    // Restoring from a missing file is a no-op
+    let restored = new_mempool();
+    let num_restored = restored
+        .restore_from_file(temp_dir.path().join("missing.dat"))
+        .await
+        .unwrap();
+    assert_eq!(num_restored, 0);
+}
+
 static EMISSION: [u64; 2] = [10, 10];
 #[tokio::test]
 #[allow(clippy::identity_op)]
@@ -1268,7 +1444,7 @@ async fn block_event_and_reorg_event_handling() {
     // There are 5 transactions created
     // TX1 the base transaction and then TX2A and TX3A that spend it
     // Double spends TX2B and TX3B are also created spending TX1
-    // Both nodes have all transactions in their mempools
+    // Both nodes have TX2A and TX3A in their mempools, TX2B and TX3B do not pay enough to replace them
     // When block B2A is submitted, then both nodes have TX2A and TX3A in their reorg pools
     // When block B2B is submitted with TX2B, TX3B, then TX2A, TX3A are discarded (Not Stored)
     let factories = CryptoFactories::default();
@@ -1349,8 +1525,16 @@ async fn block_event_and_reorg_event_handling() {
     );
     alice.mempool.insert(Arc::new(tx2a.clone())).await.unwrap();
     alice.mempool.insert(Arc::new(tx3a.clone())).await.unwrap();
-    alice.mempool.insert(Arc::new(tx2b.clone())).await.unwrap();
-    alice.mempool.insert(Arc::new(tx3b.clone())).await.unwrap();
+    assert_eq!(
+        alice.mempool.insert(Arc::new(tx2b.clone())).await.unwrap(),
+        TxStorageResponse::NotStoredFeeTooLowForReplacement
+    );
+    assert_eq!(
+        alice.mempool.insert(Arc::new(tx3b.clone())).await.unwrap(),
+        TxStorageResponse::NotStoredFeeTooLowForReplacement
+    );
+    // The double spends pay the same fee per gram as TX2A and TX3A. Replacing a pooled transaction requires paying
+    // more, so unlike before replace-by-fee they are not stored alongside the transactions they conflict with.
     bob.mempool.insert(Arc::new(tx2a.clone())).await.unwrap();
     bob.mempool.insert(Arc::new(tx3a.clone())).await.unwrap();
     bob.mempool.insert(Arc::new(tx2b.clone())).await.unwrap();
@@ -1368,7 +1552,7 @@ async fn block_event_and_reorg_event_handling() {
         .unwrap();
     find_header_with_achieved_difficulty(&mut block2b.header, Difficulty::from(10));
 
-    // Add Block2a - tx2b and tx3b will be discarded as double spends.
+    // Add Block2a - tx2a and tx3a will be moved to the ReorgPool.
     assert!(bob.local_nci.submit_block(block2a.clone(),).await.is_ok());
 
     async_assert_eventually!(
@@ -1398,13 +1582,14 @@ async fn block_event_and_reorg_event_handling() {
             .unwrap(),
         TxStorageResponse::ReorgPool
     );
+    // TX2B and TX3B were never stored, so they cannot have been moved into the reorg pool with TX2A and TX3A
     assert_eq!(
         alice
             .mempool
             .has_tx_with_excess_sig(tx2b_excess_sig.clone())
             .await
             .unwrap(),
-        TxStorageResponse::ReorgPool
+        TxStorageResponse::NotStored
     );
     assert_eq!(
         alice
@@ -1412,6 +1597,6 @@ async fn block_event_and_reorg_event_handling() {
             .has_tx_with_excess_sig(tx3b_excess_sig.clone())
             .await
             .unwrap(),
-        TxStorageResponse::ReorgPool
+        TxStorageResponse::NotStored
     );
 }
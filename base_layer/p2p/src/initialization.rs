@@ -39,7 +39,7 @@ use tari_common::{configuration::Network, DnsNameServer};
 use tari_comms::{
     backoff::ConstantBackoff,
     multiaddr::Multiaddr,
-    peer_manager::{NodeIdentity, Peer, PeerFeatures, PeerManagerError},
+    peer_manager::{EncryptedPeerDatabase, NodeIdentity, Peer, PeerFeatures, PeerManagerError},
     pipeline,
     protocol::{
         messaging::{MessagingEventSender, MessagingProtocolExtension},
@@ -48,7 +48,7 @@ use tari_comms::{
     },
     tor,
     tor::HiddenServiceControllerError,
-    transports::{MemoryTransport, SocksTransport, TcpWithTorTransport},
+    transports::{MemoryTransport, QuicTransport, SocksTransport, TcpWithTorTransport, WebSocketTransport},
     utils::cidr::parse_cidrs,
     CommsBuilder,
     CommsBuilderError,
@@ -95,6 +95,8 @@ pub enum CommsInitializationError {
     CannotAcquireFileLock,
     #[error("IO Error: `{0}`")]
     IoError(#[from] std::io::Error),
+    #[error("Failed to open peer database: {0}")]
+    PeerDatabaseError(String),
 }
 
 impl CommsInitializationError {
@@ -119,6 +121,9 @@ pub struct P2pConfig {
     pub datastore_path: PathBuf,
     /// Name to use for the peer database
     pub peer_database_name: String,
+    /// If set, the peer database and the DHT store and forward database are encrypted at rest using a key derived
+    /// from this passphrase. Existing unencrypted databases are encrypted on startup.
+    pub database_passphrase: Option<String>,
     /// The maximum number of concurrent Inbound tasks allowed before back-pressure is applied to peers
     pub max_concurrent_inbound_tasks: usize,
     /// The maximum number of concurrent outbound tasks allowed before back-pressure is applied to outbound messaging
@@ -296,6 +301,20 @@ pub async fn spawn_comms_using_transport(
                 .spawn_with_transport(transport)
                 .await?
         },
+        TransportType::WebSocket { listener_address } => {
+            debug!(target: LOG_TARGET, "Building WebSocket comms stack");
+            comms
+                .with_listener_address(listener_address)
+                .spawn_with_transport(WebSocketTransport::new())
+                .await?
+        },
+        TransportType::Quic { listener_address } => {
+            debug!(target: LOG_TARGET, "Building QUIC comms stack");
+            comms
+                .with_listener_address(listener_address)
+                .spawn_with_transport(QuicTransport::new()?)
+                .await?
+        },
     };
 
     Ok(comms)
@@ -350,6 +369,10 @@ async fn configure_comms_and_dht(
         .with_listener_liveness_allowlist_cidrs(listener_liveness_allowlist_cidrs)
         .with_dial_backoff(ConstantBackoff::new(Duration::from_millis(500)))
         .with_peer_storage(peer_database, Some(file_lock));
+    let builder = match config.database_passphrase {
+        Some(ref passphrase) => builder.with_peer_storage_passphrase(passphrase.clone()),
+        None => builder,
+    };
 
     let mut comms = match config.auxilary_tcp_listener_address {
         Some(ref addr) => builder.with_auxilary_tcp_listener_address(addr.clone()).build()?,
@@ -365,6 +388,9 @@ async fn configure_comms_and_dht(
 
     let mut dht = Dht::builder();
     dht.with_config(config.dht.clone()).with_outbound_sender(outbound_tx);
+    if let Some(ref passphrase) = config.database_passphrase {
+        dht.with_database_passphrase(passphrase.clone());
+    }
     // TODO: remove this once enough weatherwax nodes have upgraded
     if config.network == Network::Weatherwax {
         dht.with_protocol_version(DhtProtocolVersion::v1());
@@ -428,6 +454,32 @@ fn acquire_exclusive_file_lock(db_path: &Path) -> Result<File, CommsInitializati
     Ok(file)
 }
 
+/// Returns true if the peer database at the given path exists and has been encrypted. This allows applications to
+/// decide whether to prompt for a passphrase before comms is initialized.
+pub fn is_peer_database_encrypted(
+    datastore_path: &Path,
+    peer_database_name: &str,
+) -> Result<bool, CommsInitializationError> {
+    if !datastore_path.join("data.mdb").exists() {
+        return Ok(false);
+    }
+
+    let datastore = LMDBBuilder::new()
+        .set_path(datastore_path)
+        .set_env_flags(open::NOLOCK)
+        .set_env_config(LMDBConfig::default())
+        .set_max_number_of_databases(1)
+        .add_database(peer_database_name, lmdb_zero::db::CREATE)
+        .build()
+        .map_err(|err| CommsInitializationError::PeerDatabaseError(err.to_string()))?;
+    let peer_database = datastore.get_handle(peer_database_name).ok_or_else(|| {
+        CommsInitializationError::PeerDatabaseError(format!("No database named '{}'", peer_database_name))
+    })?;
+
+    EncryptedPeerDatabase::is_encrypted(&peer_database)
+        .map_err(|err| CommsInitializationError::PeerDatabaseError(err.to_string()))
+}
+
 /// Adds a new peer to the base node
 /// ## Parameters
 /// `comms_node` - A reference to the comms node. This is the communications stack
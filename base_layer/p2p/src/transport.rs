@@ -42,6 +42,12 @@ pub enum TransportType {
         socks_config: SocksConfig,
         listener_address: Multiaddr,
     },
+    /// Use a WebSocketTransport. This transport listens on and connects to TCP/IP and DNS addresses ending in `/ws`,
+    /// e.g. `/ip4/0.0.0.0/tcp/18189/ws`, for nodes that can only make HTTP connections.
+    WebSocket { listener_address: Multiaddr },
+    /// Use a QuicTransport. This transport listens on and connects to UDP/IP and DNS addresses ending in `/quic`, e.g.
+    /// `/ip4/0.0.0.0/udp/18189/quic`. Substreams are QUIC streams rather than yamux substreams.
+    Quic { listener_address: Multiaddr },
 }
 
 #[derive(Debug, Clone)]
@@ -0,0 +1,7 @@
+CREATE TABLE multisig_sessions (
+    session_id        BIGINT PRIMARY KEY NOT NULL,
+    leader_public_key BLOB               NOT NULL,
+    status            INTEGER            NOT NULL,
+    session_data      TEXT               NOT NULL,
+    timestamp         DATETIME           NOT NULL
+);
@@ -0,0 +1,8 @@
+CREATE TABLE webhook_outbox (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    url             TEXT     NOT NULL,
+    payload         TEXT     NOT NULL,
+    attempts        INTEGER  NOT NULL DEFAULT 0,
+    next_attempt_at DATETIME NOT NULL,
+    created_at      DATETIME NOT NULL
+);
@@ -0,0 +1,12 @@
+CREATE TABLE invoices (
+    id         BIGINT PRIMARY KEY NOT NULL,
+    reference  TEXT UNIQUE        NOT NULL,
+    amount     BIGINT             NOT NULL,
+    recipient  BLOB               NOT NULL,
+    message    TEXT               NOT NULL,
+    status     INTEGER            NOT NULL,
+    tx_id      BIGINT             NULL,
+    created_at DATETIME           NOT NULL,
+    expires_at DATETIME           NULL,
+    paid_at    DATETIME           NULL
+);
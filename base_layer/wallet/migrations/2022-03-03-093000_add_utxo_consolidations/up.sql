@@ -0,0 +1,7 @@
+CREATE TABLE utxo_consolidations (
+    tx_id      BIGINT PRIMARY KEY NOT NULL,
+    num_inputs BIGINT             NOT NULL,
+    amount     BIGINT             NOT NULL,
+    fee        BIGINT             NOT NULL,
+    timestamp  DATETIME           NOT NULL
+);
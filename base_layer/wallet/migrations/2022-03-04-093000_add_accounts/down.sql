@@ -0,0 +1,4 @@
+ALTER TABLE outputs
+    DROP COLUMN account_id;
+
+DROP TABLE IF EXISTS accounts;
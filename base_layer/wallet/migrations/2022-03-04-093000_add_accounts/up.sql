@@ -0,0 +1,13 @@
+CREATE TABLE accounts (
+    id         BIGINT PRIMARY KEY NOT NULL,
+    name       TEXT UNIQUE        NOT NULL,
+    key_index  BIGINT             NOT NULL DEFAULT 0,
+    created_at DATETIME           NOT NULL
+);
+
+-- The default account uses the wallet's original key manager branch and owns all existing outputs
+INSERT INTO accounts (id, name, key_index, created_at)
+VALUES (0, 'default', 0, CURRENT_TIMESTAMP);
+
+ALTER TABLE outputs
+    ADD account_id BIGINT NOT NULL DEFAULT 0;
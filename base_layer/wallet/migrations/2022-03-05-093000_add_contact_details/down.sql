@@ -0,0 +1,11 @@
+ALTER TABLE contacts
+    DROP COLUMN tags;
+
+ALTER TABLE contacts
+    DROP COLUMN notes;
+
+ALTER TABLE contacts
+    DROP COLUMN preferred_send_method;
+
+ALTER TABLE contacts
+    DROP COLUMN last_seen;
@@ -0,0 +1,11 @@
+ALTER TABLE contacts
+    ADD COLUMN tags TEXT NOT NULL DEFAULT '[]';
+
+ALTER TABLE contacts
+    ADD COLUMN notes TEXT NOT NULL DEFAULT '';
+
+ALTER TABLE contacts
+    ADD COLUMN preferred_send_method INTEGER NOT NULL DEFAULT 0;
+
+ALTER TABLE contacts
+    ADD COLUMN last_seen DATETIME NULL;
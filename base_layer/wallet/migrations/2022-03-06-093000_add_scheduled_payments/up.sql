@@ -0,0 +1,16 @@
+CREATE TABLE scheduled_payments (
+    id                     BIGINT PRIMARY KEY NOT NULL,
+    destination_public_key BLOB               NOT NULL,
+    amount                 BIGINT             NOT NULL,
+    fee_per_gram           BIGINT             NOT NULL,
+    message                TEXT               NOT NULL,
+    one_sided              INTEGER            NOT NULL,
+    recipient_lock_height  BIGINT             NULL,
+    trigger_height         BIGINT             NULL,
+    trigger_time           DATETIME           NULL,
+    repeat_interval        BIGINT             NULL,
+    remaining_payments     BIGINT             NULL,
+    status                 INTEGER            NOT NULL,
+    last_tx_id             BIGINT             NULL,
+    created_at             DATETIME           NOT NULL
+);
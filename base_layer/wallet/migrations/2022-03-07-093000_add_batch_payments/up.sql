@@ -0,0 +1,9 @@
+DROP TABLE IF EXISTS batch_payments;
+CREATE TABLE batch_payments (
+    tx_id                  BIGINT  NOT NULL,
+    payment_index          INTEGER NOT NULL,
+    destination_public_key BLOB    NOT NULL,
+    amount                 BIGINT  NOT NULL,
+    message                TEXT    NOT NULL,
+    PRIMARY KEY (tx_id, payment_index)
+);
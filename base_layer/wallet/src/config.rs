@@ -27,6 +27,7 @@ use tari_p2p::{auto_update::AutoUpdateConfig, initialization::P2pConfig};
 
 use crate::{
     base_node_service::config::BaseNodeServiceConfig,
+    contacts_service::config::ContactsServiceConfig,
     output_manager_service::config::OutputManagerServiceConfig,
     transaction_service::config::TransactionServiceConfig,
 };
@@ -45,6 +46,7 @@ pub struct WalletConfig {
     pub base_node_service_config: BaseNodeServiceConfig,
     pub updater_config: Option<AutoUpdateConfig>,
     pub autoupdate_check_interval: Option<Duration>,
+    pub contacts_service_config: ContactsServiceConfig,
 }
 
 impl WalletConfig {
@@ -71,6 +73,7 @@ impl WalletConfig {
             base_node_service_config: base_node_service_config.unwrap_or_default(),
             updater_config,
             autoupdate_check_interval,
+            contacts_service_config: ContactsServiceConfig::default(),
         }
     }
 }
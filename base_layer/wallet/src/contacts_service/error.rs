@@ -24,7 +24,11 @@ use diesel::result::Error as DieselError;
 use tari_service_framework::reply_channel::TransportChannelError;
 use thiserror::Error;
 
-use crate::{contacts_service::storage::database::DbKey, error::WalletStorageError};
+use crate::{
+    contacts_service::storage::database::DbKey,
+    error::WalletStorageError,
+    transaction_service::error::TransactionServiceError,
+};
 
 #[derive(Debug, Error)]
 #[allow(clippy::large_enum_variant)]
@@ -37,6 +41,12 @@ pub enum ContactsServiceError {
     ContactsServiceStorageError(#[from] ContactsServiceStorageError),
     #[error("Transport channel error: `{0}`")]
     TransportChannelError(#[from] TransportChannelError),
+    #[error("Invalid contact data: `{0}`")]
+    InvalidContactData(String),
+    #[error("The transaction service is not available to the contacts service")]
+    TransactionServiceUnavailable,
+    #[error("Transaction service error: `{0}`")]
+    TransactionServiceError(#[from] TransactionServiceError),
 }
 
 #[derive(Debug, Error)]
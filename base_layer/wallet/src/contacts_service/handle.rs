@@ -20,6 +20,7 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use tari_common_types::transaction::TxId;
 use tari_comms::types::CommsPublicKey;
 use tari_service_framework::reply_channel::SenderService;
 use tower::Service;
@@ -32,6 +33,8 @@ pub enum ContactsServiceRequest {
     UpsertContact(Contact),
     RemoveContact(CommsPublicKey),
     GetContacts,
+    ImportContacts(Vec<Contact>),
+    GetContactTransactions(CommsPublicKey),
 }
 
 #[derive(Debug)]
@@ -40,6 +43,8 @@ pub enum ContactsServiceResponse {
     ContactRemoved(Contact),
     Contact(Contact),
     Contacts(Vec<Contact>),
+    ContactsImported(usize),
+    ContactTransactions(Vec<TxId>),
 }
 
 #[derive(Clone)]
@@ -88,4 +93,31 @@ impl ContactsServiceHandle {
             _ => Err(ContactsServiceError::UnexpectedApiResponse),
         }
     }
+
+    /// Upsert all the provided contacts, returning the number of contacts imported
+    pub async fn import_contacts(&mut self, contacts: Vec<Contact>) -> Result<usize, ContactsServiceError> {
+        match self
+            .handle
+            .call(ContactsServiceRequest::ImportContacts(contacts))
+            .await??
+        {
+            ContactsServiceResponse::ContactsImported(n) => Ok(n),
+            _ => Err(ContactsServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Returns the ids of all transactions sent to or received from the contact, most recent first
+    pub async fn get_contact_transactions(
+        &mut self,
+        pub_key: CommsPublicKey,
+    ) -> Result<Vec<TxId>, ContactsServiceError> {
+        match self
+            .handle
+            .call(ContactsServiceRequest::GetContactTransactions(pub_key))
+            .await??
+        {
+            ContactsServiceResponse::ContactTransactions(t) => Ok(t),
+            _ => Err(ContactsServiceError::UnexpectedApiResponse),
+        }
+    }
 }
@@ -0,0 +1,254 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Import and export of the contact book in JSON and CSV formats.
+//!
+//! The CSV format has one contact per record with the columns
+//! `public_key,alias,preferred_send_method,tags,notes`. Fields are quoted as described in RFC 4180 when they contain a
+//! comma, quote, line break or surrounding whitespace. Tags are separated by `;`, with `;` and `\` in a tag escaped by
+//! a `\`. Blank lines and lines starting with `#` are ignored.
+
+use std::{borrow::Cow, fmt::Write, mem, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use tari_comms::types::CommsPublicKey;
+use tari_utilities::hex::Hex;
+
+use crate::contacts_service::{
+    error::ContactsServiceError,
+    storage::database::{Contact, SendMethod},
+};
+
+const CSV_HEADER: &str = "public_key,alias,preferred_send_method,tags,notes";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ContactRecord {
+    public_key: String,
+    alias: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    notes: String,
+    #[serde(default)]
+    preferred_send_method: String,
+}
+
+impl From<&Contact> for ContactRecord {
+    fn from(c: &Contact) -> Self {
+        Self {
+            public_key: c.public_key.to_hex(),
+            alias: c.alias.clone(),
+            tags: c.tags.clone(),
+            notes: c.notes.clone(),
+            preferred_send_method: c.preferred_send_method.to_string(),
+        }
+    }
+}
+
+impl ContactRecord {
+    fn into_contact(self) -> Result<Contact, ContactsServiceError> {
+        let public_key = CommsPublicKey::from_hex(self.public_key.trim())
+            .map_err(|_| ContactsServiceError::InvalidContactData(format!("Invalid public key {}", self.public_key)))?;
+        let preferred_send_method = SendMethod::from_str(&self.preferred_send_method).map_err(|_| {
+            ContactsServiceError::InvalidContactData(format!("Invalid send method {}", self.preferred_send_method))
+        })?;
+        Ok(Contact {
+            alias: self.alias,
+            public_key,
+            tags: self.tags,
+            notes: self.notes,
+            preferred_send_method,
+            last_seen: None,
+        })
+    }
+}
+
+/// Serialize the contacts into a JSON array
+pub fn export_json(contacts: &[Contact]) -> Result<String, ContactsServiceError> {
+    let records = contacts.iter().map(ContactRecord::from).collect::<Vec<_>>();
+    serde_json::to_string_pretty(&records).map_err(|e| ContactsServiceError::InvalidContactData(e.to_string()))
+}
+
+/// Parse contacts from a JSON array as produced by [export_json]
+pub fn import_json(data: &str) -> Result<Vec<Contact>, ContactsServiceError> {
+    let records: Vec<ContactRecord> =
+        serde_json::from_str(data).map_err(|e| ContactsServiceError::InvalidContactData(e.to_string()))?;
+    records.into_iter().map(ContactRecord::into_contact).collect()
+}
+
+/// Serialize the contacts into CSV, including a header line
+pub fn export_csv(contacts: &[Contact]) -> String {
+    let mut csv = format!("{}\n", CSV_HEADER);
+    for c in contacts {
+        let _ = writeln!(
+            csv,
+            "{},{},{},{},{}",
+            c.public_key.to_hex(),
+            quote_csv_field(&c.alias),
+            c.preferred_send_method,
+            quote_csv_field(&join_tags(&c.tags)),
+            quote_csv_field(&c.notes)
+        );
+    }
+    csv
+}
+
+/// Parse contacts from CSV as produced by [export_csv]. The header line is optional.
+pub fn import_csv(data: &str) -> Result<Vec<Contact>, ContactsServiceError> {
+    let mut contacts = Vec::new();
+    for (line, fields) in parse_csv(data)? {
+        if (fields.len() == 1 && fields[0].trim().is_empty()) || fields.join(",") == CSV_HEADER {
+            continue;
+        }
+        if fields.len() < 2 || fields.len() > 5 {
+            return Err(ContactsServiceError::InvalidContactData(format!(
+                "Line {}: expected a public key, alias and up to 3 optional fields but got {} field(s)",
+                line,
+                fields.len()
+            )));
+        }
+        let mut fields = fields.into_iter();
+        let public_key = fields.next().unwrap_or_default();
+        let alias = fields.next().unwrap_or_default();
+        let preferred_send_method = fields.next().unwrap_or_default();
+        let tags = split_tags(&fields.next().unwrap_or_default());
+        let notes = fields.next().unwrap_or_default();
+        let record = ContactRecord {
+            public_key,
+            alias,
+            tags,
+            notes,
+            preferred_send_method,
+        };
+        contacts.push(
+            record
+                .into_contact()
+                .map_err(|e| ContactsServiceError::InvalidContactData(format!("Line {}: {}", line, e)))?,
+        );
+    }
+    Ok(contacts)
+}
+
+fn quote_csv_field(field: &str) -> Cow<'_, str> {
+    if field.contains(&[',', '"', '\r', '\n'][..]) || field.trim() != field {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+fn join_tags(tags: &[String]) -> String {
+    tags.iter()
+        .map(|t| t.replace('\\', "\\\\").replace(';', "\\;"))
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+fn split_tags(field: &str) -> Vec<String> {
+    let mut tags = Vec::new();
+    let mut tag = String::new();
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => tag.extend(chars.next()),
+            ';' => tags.push(mem::take(&mut tag)),
+            c => tag.push(c),
+        }
+    }
+    tags.push(tag);
+    tags.into_iter().filter(|t| !t.is_empty()).collect()
+}
+
+/// Split the data into records of fields, returning each record with the line number that it starts on. Quoted fields
+/// may contain commas, line breaks and quotes escaped as `""`.
+fn parse_csv(data: &str) -> Result<Vec<(usize, Vec<String>)>, ContactsServiceError> {
+    let invalid = |line: usize, msg: &str| ContactsServiceError::InvalidContactData(format!("Line {}: {}", line, msg));
+    let mut records = Vec::new();
+    let mut chars = data.chars().peekable();
+    let mut line = 1;
+    while let Some(&first) = chars.peek() {
+        match first {
+            '#' => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+                line += 1;
+                continue;
+            },
+            '\r' | '\n' => {
+                if chars.next() == Some('\n') {
+                    line += 1;
+                }
+                continue;
+            },
+            _ => {},
+        }
+
+        let start_line = line;
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let mut in_quotes = false;
+        let mut quoted = false;
+        loop {
+            let c = chars.next();
+            if in_quotes {
+                match c {
+                    Some('"') if chars.peek() == Some(&'"') => {
+                        chars.next();
+                        field.push('"');
+                    },
+                    Some('"') => in_quotes = false,
+                    Some(c) => {
+                        if c == '\n' {
+                            line += 1;
+                        }
+                        field.push(c);
+                    },
+                    None => return Err(invalid(start_line, "unterminated quoted field")),
+                }
+                continue;
+            }
+            match c {
+                Some(',') => {
+                    fields.push(mem::take(&mut field));
+                    quoted = false;
+                },
+                Some('\r') if chars.peek() == Some(&'\n') => {},
+                Some('\n') | None => {
+                    fields.push(mem::take(&mut field));
+                    line += 1;
+                    break;
+                },
+                Some('"') if field.is_empty() && !quoted => {
+                    in_quotes = true;
+                    quoted = true;
+                },
+                Some(_) if quoted => return Err(invalid(line, "unexpected character after a quoted field")),
+                Some(c) => field.push(c),
+            }
+        }
+        records.push((start_line, fields));
+    }
+    Ok(records)
+}
@@ -20,13 +20,16 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+pub mod config;
 pub mod error;
 pub mod handle;
+pub mod import_export;
 pub mod service;
 pub mod storage;
 
 use futures::future;
 use log::*;
+use tari_p2p::services::liveness::LivenessHandle;
 use tari_service_framework::{
     async_trait,
     reply_channel,
@@ -35,10 +38,14 @@ use tari_service_framework::{
     ServiceInitializerContext,
 };
 
-use crate::contacts_service::{
-    handle::ContactsServiceHandle,
-    service::ContactsService,
-    storage::database::{ContactsBackend, ContactsDatabase},
+use crate::{
+    contacts_service::{
+        config::ContactsServiceConfig,
+        handle::ContactsServiceHandle,
+        service::ContactsService,
+        storage::database::{ContactsBackend, ContactsDatabase},
+    },
+    transaction_service::handle::TransactionServiceHandle,
 };
 
 const LOG_TARGET: &str = "wallet::contacts_service::initializer";
@@ -46,14 +53,18 @@ const LOG_TARGET: &str = "wallet::contacts_service::initializer";
 pub struct ContactsServiceInitializer<T>
 where T: ContactsBackend
 {
+    config: ContactsServiceConfig,
     backend: Option<T>,
 }
 
 impl<T> ContactsServiceInitializer<T>
 where T: ContactsBackend
 {
-    pub fn new(backend: T) -> Self {
-        Self { backend: Some(backend) }
+    pub fn new(config: ContactsServiceConfig, backend: T) -> Self {
+        Self {
+            config,
+            backend: Some(backend),
+        }
     }
 }
 
@@ -74,11 +85,23 @@ where T: ContactsBackend + 'static
             .take()
             .expect("Cannot start Contacts Service without setting a storage backend");
 
+        let config = self.config.clone();
         let shutdown_signal = context.get_shutdown_signal();
 
         context.spawn_when_ready(move |handles| async move {
-            let service =
-                ContactsService::new(receiver, ContactsDatabase::new(backend), handles.get_shutdown_signal()).start();
+            // Liveness and the transaction service are optional so that the contacts service can run standalone
+            let liveness = handles.get_handle::<LivenessHandle>();
+            let transaction_service = handles.get_handle::<TransactionServiceHandle>();
+
+            let service = ContactsService::new(
+                config,
+                receiver,
+                ContactsDatabase::new(backend),
+                liveness,
+                transaction_service,
+                handles.get_shutdown_signal(),
+            )
+            .start();
             futures::pin_mut!(service);
             future::select(service, shutdown_signal).await;
             info!(target: LOG_TARGET, "Contacts service shutdown");
@@ -20,15 +20,26 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use std::sync::Arc;
+
+use chrono::{NaiveDateTime, Utc};
 use futures::{pin_mut, StreamExt};
 use log::*;
+use tari_common_types::transaction::TxId;
+use tari_comms::{peer_manager::NodeId, types::CommsPublicKey};
+use tari_p2p::services::liveness::{LivenessEvent, LivenessHandle};
 use tari_service_framework::reply_channel;
 use tari_shutdown::ShutdownSignal;
+use tokio::time::{self, MissedTickBehavior};
 
-use crate::contacts_service::{
-    error::ContactsServiceError,
-    handle::{ContactsServiceRequest, ContactsServiceResponse},
-    storage::database::{ContactsBackend, ContactsDatabase},
+use crate::{
+    contacts_service::{
+        config::ContactsServiceConfig,
+        error::ContactsServiceError,
+        handle::{ContactsServiceRequest, ContactsServiceResponse},
+        storage::database::{ContactsBackend, ContactsDatabase},
+    },
+    transaction_service::handle::TransactionServiceHandle,
 };
 
 const LOG_TARGET: &str = "wallet:contacts_service";
@@ -36,9 +47,12 @@ const LOG_TARGET: &str = "wallet:contacts_service";
 pub struct ContactsService<T>
 where T: ContactsBackend + 'static
 {
+    config: ContactsServiceConfig,
     db: ContactsDatabase<T>,
     request_stream:
         Option<reply_channel::Receiver<ContactsServiceRequest, Result<ContactsServiceResponse, ContactsServiceError>>>,
+    liveness: Option<LivenessHandle>,
+    transaction_service: Option<TransactionServiceHandle>,
     shutdown_signal: Option<ShutdownSignal>,
 }
 
@@ -46,17 +60,23 @@ impl<T> ContactsService<T>
 where T: ContactsBackend + 'static
 {
     pub fn new(
+        config: ContactsServiceConfig,
         request_stream: reply_channel::Receiver<
             ContactsServiceRequest,
             Result<ContactsServiceResponse, ContactsServiceError>,
         >,
 
         db: ContactsDatabase<T>,
+        liveness: Option<LivenessHandle>,
+        transaction_service: Option<TransactionServiceHandle>,
         shutdown_signal: ShutdownSignal,
     ) -> Self {
         Self {
+            config,
             db,
             request_stream: Some(request_stream),
+            liveness,
+            transaction_service,
             shutdown_signal: Some(shutdown_signal),
         }
     }
@@ -75,9 +95,25 @@ where T: ContactsBackend + 'static
             .expect("Output Manager Service initialized without shutdown signal");
         pin_mut!(shutdown);
 
+        let mut liveness_events = self.liveness.as_ref().map(|l| l.get_event_stream());
+        let ping_interval = self.config.liveness_ping_interval;
+        let mut ping_contacts = time::interval_at(time::Instant::now() + ping_interval, ping_interval);
+        ping_contacts.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
         debug!(target: LOG_TARGET, "Contacts Service started");
         loop {
             tokio::select! {
+                _ = ping_contacts.tick(), if self.liveness.is_some() => {
+                    if let Err(e) = self.ping_contacts().await {
+                        warn!(target: LOG_TARGET, "Failed to ping contacts: {}", e);
+                    }
+                },
+                event = async { liveness_events.as_mut().unwrap().recv().await }, if liveness_events.is_some() => {
+                    match event {
+                        Ok(e) => self.handle_liveness_event(e).await,
+                        Err(e) => debug!(target: LOG_TARGET, "Lagging read on liveness event broadcast channel: {}", e),
+                    };
+                },
                 Some(request_context) = request_stream.next() => {
                     let (request, reply_tx) = request_context.split();
                     let response = self.handle_request(request).await.map_err(|e| {
@@ -126,6 +162,95 @@ where T: ContactsBackend + 'static
             ContactsServiceRequest::GetContacts => {
                 Ok(self.db.get_contacts().await.map(ContactsServiceResponse::Contacts)?)
             },
+            ContactsServiceRequest::ImportContacts(contacts) => {
+                let num_contacts = contacts.len();
+                for c in contacts {
+                    self.db.upsert_contact(c).await?;
+                }
+                info!(target: LOG_TARGET, "{} contacts imported", num_contacts);
+                Ok(ContactsServiceResponse::ContactsImported(num_contacts))
+            },
+            ContactsServiceRequest::GetContactTransactions(pk) => Ok(self
+                .get_contact_transactions(pk)
+                .await
+                .map(ContactsServiceResponse::ContactTransactions)?),
         }
     }
+
+    /// Send a liveness ping to every contact so that their last seen time can be updated when they respond
+    async fn ping_contacts(&mut self) -> Result<(), ContactsServiceError> {
+        let contacts = self.db.get_contacts().await?;
+        let liveness = match self.liveness.as_mut() {
+            Some(l) => l,
+            None => return Ok(()),
+        };
+        for c in contacts {
+            let node_id = NodeId::from_public_key(&c.public_key);
+            if let Err(e) = liveness.send_ping(node_id.clone()).await {
+                debug!(target: LOG_TARGET, "Failed to send ping to contact {}: {}", node_id, e);
+            }
+        }
+        Ok(())
+    }
+
+    async fn handle_liveness_event(&mut self, event: Arc<LivenessEvent>) {
+        let node_id = match &*event {
+            LivenessEvent::ReceivedPing(e) | LivenessEvent::ReceivedPong(e) => &e.node_id,
+            LivenessEvent::PingRoundBroadcast(_) => return,
+        };
+        let contacts = match self.db.get_contacts().await {
+            Ok(c) => c,
+            Err(e) => {
+                warn!(target: LOG_TARGET, "Could not fetch contacts: {}", e);
+                return;
+            },
+        };
+        let now = Utc::now().naive_utc();
+        for c in contacts {
+            if &NodeId::from_public_key(&c.public_key) == node_id {
+                if let Err(e) = self.db.update_last_seen(c.public_key, now).await {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Could not update last seen for contact {}: {}", c.alias, e
+                    );
+                }
+                break;
+            }
+        }
+    }
+
+    /// Returns the ids of all transactions to or from the given contact, most recent first
+    async fn get_contact_transactions(&mut self, pub_key: CommsPublicKey) -> Result<Vec<TxId>, ContactsServiceError> {
+        let transaction_service = self
+            .transaction_service
+            .as_mut()
+            .ok_or(ContactsServiceError::TransactionServiceUnavailable)?;
+
+        let mut transactions: Vec<(TxId, NaiveDateTime)> = transaction_service
+            .get_completed_transactions()
+            .await?
+            .into_values()
+            .filter(|tx| tx.source_public_key == pub_key || tx.destination_public_key == pub_key)
+            .map(|tx| (tx.tx_id, tx.timestamp))
+            .collect();
+        transactions.extend(
+            transaction_service
+                .get_pending_inbound_transactions()
+                .await?
+                .into_values()
+                .filter(|tx| tx.source_public_key == pub_key)
+                .map(|tx| (tx.tx_id, tx.timestamp)),
+        );
+        transactions.extend(
+            transaction_service
+                .get_pending_outbound_transactions()
+                .await?
+                .into_values()
+                .filter(|tx| tx.destination_public_key == pub_key)
+                .map(|tx| (tx.tx_id, tx.timestamp)),
+        );
+        transactions.sort_by(|a, b| b.1.cmp(&a.1));
+
+        Ok(transactions.into_iter().map(|(tx_id, _)| tx_id).collect())
+    }
 }
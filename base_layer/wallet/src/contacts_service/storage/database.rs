@@ -21,10 +21,13 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use std::{
+    convert::TryFrom,
     fmt::{Display, Error, Formatter},
+    str::FromStr,
     sync::Arc,
 };
 
+use chrono::NaiveDateTime;
 use log::*;
 use tari_comms::types::CommsPublicKey;
 
@@ -32,10 +35,74 @@ use crate::contacts_service::error::ContactsServiceStorageError;
 
 const LOG_TARGET: &str = "wallet::contacts_service::database";
 
+/// The method that should be used by default when sending funds to a contact
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendMethod {
+    Interactive = 0,
+    OneSided = 1,
+}
+
+impl Default for SendMethod {
+    fn default() -> Self {
+        SendMethod::Interactive
+    }
+}
+
+impl TryFrom<i32> for SendMethod {
+    type Error = ContactsServiceStorageError;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(SendMethod::Interactive),
+            1 => Ok(SendMethod::OneSided),
+            _ => Err(ContactsServiceStorageError::ConversionError),
+        }
+    }
+}
+
+impl Display for SendMethod {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        match self {
+            SendMethod::Interactive => f.write_str("interactive"),
+            SendMethod::OneSided => f.write_str("one-sided"),
+        }
+    }
+}
+
+impl FromStr for SendMethod {
+    type Err = ContactsServiceStorageError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "" | "interactive" => Ok(SendMethod::Interactive),
+            "one-sided" | "one_sided" | "onesided" => Ok(SendMethod::OneSided),
+            _ => Err(ContactsServiceStorageError::ConversionError),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Contact {
     pub alias: String,
     pub public_key: CommsPublicKey,
+    pub tags: Vec<String>,
+    pub notes: String,
+    pub preferred_send_method: SendMethod,
+    /// The last time a liveness ping or pong was received from this contact
+    pub last_seen: Option<NaiveDateTime>,
+}
+
+impl Contact {
+    pub fn new(alias: String, public_key: CommsPublicKey) -> Self {
+        Self {
+            alias,
+            public_key,
+            tags: Vec::new(),
+            notes: String::new(),
+            preferred_send_method: SendMethod::default(),
+            last_seen: None,
+        }
+    }
 }
 
 /// This trait defines the functionality that a database backend need to provide for the Contacts Service
@@ -58,7 +125,8 @@ pub enum DbValue {
 }
 
 pub enum DbKeyValuePair {
-    Contact(CommsPublicKey, Contact),
+    Contact(CommsPublicKey, Box<Contact>),
+    LastSeen(CommsPublicKey, NaiveDateTime),
 }
 
 pub enum WriteOperation {
@@ -123,7 +191,24 @@ where T: ContactsBackend + 'static
         tokio::task::spawn_blocking(move || {
             db_clone.write(WriteOperation::Upsert(Box::new(DbKeyValuePair::Contact(
                 contact.public_key.clone(),
-                contact,
+                Box::new(contact),
+            ))))
+        })
+        .await
+        .map_err(|err| ContactsServiceStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(())
+    }
+
+    pub async fn update_last_seen(
+        &self,
+        pub_key: CommsPublicKey,
+        last_seen: NaiveDateTime,
+    ) -> Result<(), ContactsServiceStorageError> {
+        let db_clone = self.db.clone();
+
+        tokio::task::spawn_blocking(move || {
+            db_clone.write(WriteOperation::Upsert(Box::new(DbKeyValuePair::LastSeen(
+                pub_key, last_seen,
             ))))
         })
         .await
@@ -22,6 +22,7 @@
 
 use std::convert::TryFrom;
 
+use chrono::NaiveDateTime;
 use diesel::{prelude::*, result::Error as DieselError, SqliteConnection};
 use tari_common_types::types::PublicKey;
 use tari_crypto::tari_utilities::ByteArray;
@@ -29,7 +30,7 @@ use tari_crypto::tari_utilities::ByteArray;
 use crate::{
     contacts_service::{
         error::ContactsServiceStorageError,
-        storage::database::{Contact, ContactsBackend, DbKey, DbKeyValuePair, DbValue, WriteOperation},
+        storage::database::{Contact, ContactsBackend, DbKey, DbKeyValuePair, DbValue, SendMethod, WriteOperation},
     },
     schema::contacts,
     storage::sqlite_utilities::wallet_db_connection::WalletDbConnection,
@@ -75,12 +76,30 @@ impl ContactsBackend for ContactsServiceSqliteDatabase {
             WriteOperation::Upsert(kvp) => match *kvp {
                 DbKeyValuePair::Contact(k, c) => match ContactSql::find(&k.to_vec(), &conn) {
                     Ok(found_c) => {
-                        let _ = found_c.update(UpdateContact { alias: Some(c.alias) }, &conn)?;
+                        let _ = found_c.update(
+                            UpdateContact {
+                                alias: Some(c.alias),
+                                tags: Some(serialize_tags(&c.tags)),
+                                notes: Some(c.notes),
+                                preferred_send_method: Some(c.preferred_send_method as i32),
+                                ..Default::default()
+                            },
+                            &conn,
+                        )?;
                     },
                     Err(_) => {
-                        ContactSql::from(c).commit(&conn)?;
+                        ContactSql::from(*c).commit(&conn)?;
                     },
                 },
+                DbKeyValuePair::LastSeen(k, last_seen) => {
+                    let _ = ContactSql::find(&k.to_vec(), &conn)?.update(
+                        UpdateContact {
+                            last_seen: Some(Some(last_seen)),
+                            ..Default::default()
+                        },
+                        &conn,
+                    )?;
+                },
             },
             WriteOperation::Remove(k) => match k {
                 DbKey::Contact(k) => match ContactSql::find(&k.to_vec(), &conn) {
@@ -105,6 +124,10 @@ impl ContactsBackend for ContactsServiceSqliteDatabase {
 struct ContactSql {
     public_key: Vec<u8>,
     alias: String,
+    tags: String,
+    notes: String,
+    preferred_send_method: i32,
+    last_seen: Option<NaiveDateTime>,
 }
 
 impl ContactSql {
@@ -161,6 +184,10 @@ impl TryFrom<ContactSql> for Contact {
         Ok(Self {
             public_key: PublicKey::from_vec(&o.public_key).map_err(|_| ContactsServiceStorageError::ConversionError)?,
             alias: o.alias,
+            tags: serde_json::from_str(&o.tags).map_err(|_| ContactsServiceStorageError::ConversionError)?,
+            notes: o.notes,
+            preferred_send_method: SendMethod::try_from(o.preferred_send_method)?,
+            last_seen: o.last_seen,
         })
     }
 }
@@ -171,14 +198,26 @@ impl From<Contact> for ContactSql {
         Self {
             public_key: o.public_key.to_vec(),
             alias: o.alias,
+            tags: serialize_tags(&o.tags),
+            notes: o.notes,
+            preferred_send_method: o.preferred_send_method as i32,
+            last_seen: o.last_seen,
         }
     }
 }
 
-#[derive(AsChangeset)]
+fn serialize_tags(tags: &[String]) -> String {
+    serde_json::to_string(tags).unwrap_or_else(|_| "[]".to_string())
+}
+
+#[derive(AsChangeset, Default)]
 #[table_name = "contacts"]
 pub struct UpdateContact {
     alias: Option<String>,
+    tags: Option<String>,
+    notes: Option<String>,
+    preferred_send_method: Option<i32>,
+    last_seen: Option<Option<NaiveDateTime>>,
 }
 
 #[cfg(test)]
@@ -218,10 +257,7 @@ mod test {
             let mut contacts = Vec::new();
             for i in 0..names.len() {
                 let pub_key = PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng));
-                contacts.push(Contact {
-                    alias: names[i].clone(),
-                    public_key: pub_key,
-                });
+                contacts.push(Contact::new(names[i].clone(), pub_key));
                 ContactSql::from(contacts[i].clone()).commit(&conn).unwrap();
             }
 
@@ -249,6 +285,7 @@ mod test {
             c.update(
                 UpdateContact {
                     alias: Some("Fred".to_string()),
+                    ..Default::default()
                 },
                 &conn,
             )
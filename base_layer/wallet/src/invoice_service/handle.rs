@@ -0,0 +1,105 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::time::Duration;
+
+use tari_core::transactions::tari_amount::MicroTari;
+use tari_service_framework::reply_channel::SenderService;
+use tower::Service;
+
+use crate::invoice_service::{error::InvoiceServiceError, invoice::Invoice};
+
+#[derive(Debug)]
+pub enum InvoiceServiceRequest {
+    CreateInvoice {
+        amount: MicroTari,
+        reference: Option<String>,
+        message: String,
+        expires_in: Option<Duration>,
+    },
+    GetInvoice(u64),
+    GetInvoices,
+    CancelInvoice(u64),
+}
+
+#[derive(Debug)]
+pub enum InvoiceServiceResponse {
+    Invoice(Box<Invoice>),
+    Invoices(Vec<Invoice>),
+}
+
+#[derive(Clone)]
+pub struct InvoiceServiceHandle {
+    handle: SenderService<InvoiceServiceRequest, Result<InvoiceServiceResponse, InvoiceServiceError>>,
+}
+
+impl InvoiceServiceHandle {
+    pub fn new(
+        handle: SenderService<InvoiceServiceRequest, Result<InvoiceServiceResponse, InvoiceServiceError>>,
+    ) -> Self {
+        Self { handle }
+    }
+
+    /// Create an invoice for `amount` payable to this wallet. A random reference is generated if none is provided.
+    pub async fn create_invoice(
+        &mut self,
+        amount: MicroTari,
+        reference: Option<String>,
+        message: String,
+        expires_in: Option<Duration>,
+    ) -> Result<Invoice, InvoiceServiceError> {
+        match self
+            .handle
+            .call(InvoiceServiceRequest::CreateInvoice {
+                amount,
+                reference,
+                message,
+                expires_in,
+            })
+            .await??
+        {
+            InvoiceServiceResponse::Invoice(invoice) => Ok(*invoice),
+            _ => Err(InvoiceServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    pub async fn get_invoice(&mut self, id: u64) -> Result<Invoice, InvoiceServiceError> {
+        match self.handle.call(InvoiceServiceRequest::GetInvoice(id)).await?? {
+            InvoiceServiceResponse::Invoice(invoice) => Ok(*invoice),
+            _ => Err(InvoiceServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    pub async fn get_invoices(&mut self) -> Result<Vec<Invoice>, InvoiceServiceError> {
+        match self.handle.call(InvoiceServiceRequest::GetInvoices).await?? {
+            InvoiceServiceResponse::Invoices(invoices) => Ok(invoices),
+            _ => Err(InvoiceServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    pub async fn cancel_invoice(&mut self, id: u64) -> Result<Invoice, InvoiceServiceError> {
+        match self.handle.call(InvoiceServiceRequest::CancelInvoice(id)).await?? {
+            InvoiceServiceResponse::Invoice(invoice) => Ok(*invoice),
+            _ => Err(InvoiceServiceError::UnexpectedApiResponse),
+        }
+    }
+}
@@ -0,0 +1,398 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{
+    convert::TryFrom,
+    fmt::{Display, Error, Formatter},
+    str::FromStr,
+};
+
+use chrono::{NaiveDateTime, Utc};
+use tari_common::configuration::Network;
+use tari_common_types::{
+    transaction::{TransactionDirection, TxId},
+    types::PublicKey,
+};
+use tari_core::transactions::tari_amount::MicroTari;
+use tari_crypto::tari_utilities::hex::Hex;
+
+use crate::{invoice_service::error::InvoiceServiceError, transaction_service::storage::models::CompletedTransaction};
+
+const URI_SCHEME: &str = "tari://";
+const URI_INVOICE_PATH: &str = "invoice";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvoiceStatus {
+    /// Waiting for a payment
+    Pending,
+    /// A matching payment has been received but not yet confirmed on chain
+    Paid,
+    /// The matching payment has been mined and confirmed
+    Confirmed,
+    /// The invoice expired before a payment was received
+    Expired,
+    /// The invoice was cancelled by the wallet owner
+    Cancelled,
+}
+
+impl InvoiceStatus {
+    /// Whether a payment received now can still be matched to the invoice. Payments to expired invoices are still
+    /// matched so that late payments are attributed rather than lost.
+    pub fn is_open(&self) -> bool {
+        matches!(self, InvoiceStatus::Pending | InvoiceStatus::Expired)
+    }
+}
+
+impl Display for InvoiceStatus {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        match self {
+            InvoiceStatus::Pending => write!(f, "Pending"),
+            InvoiceStatus::Paid => write!(f, "Paid"),
+            InvoiceStatus::Confirmed => write!(f, "Confirmed"),
+            InvoiceStatus::Expired => write!(f, "Expired"),
+            InvoiceStatus::Cancelled => write!(f, "Cancelled"),
+        }
+    }
+}
+
+impl TryFrom<i32> for InvoiceStatus {
+    type Error = InvoiceServiceError;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(InvoiceStatus::Pending),
+            1 => Ok(InvoiceStatus::Paid),
+            2 => Ok(InvoiceStatus::Confirmed),
+            3 => Ok(InvoiceStatus::Expired),
+            4 => Ok(InvoiceStatus::Cancelled),
+            _ => Err(InvoiceServiceError::ConversionError(format!(
+                "Invalid invoice status: {}",
+                value
+            ))),
+        }
+    }
+}
+
+impl From<InvoiceStatus> for i32 {
+    fn from(status: InvoiceStatus) -> Self {
+        match status {
+            InvoiceStatus::Pending => 0,
+            InvoiceStatus::Paid => 1,
+            InvoiceStatus::Confirmed => 2,
+            InvoiceStatus::Expired => 3,
+            InvoiceStatus::Cancelled => 4,
+        }
+    }
+}
+
+/// A request for payment that can be handed to a payer, typically as a URI or QR code
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaymentRequest {
+    pub network: Network,
+    pub recipient: PublicKey,
+    pub amount: MicroTari,
+    /// The payer should include the reference in the transaction message so the payment can be matched
+    pub reference: String,
+    pub message: String,
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+impl PaymentRequest {
+    /// Encode as `tari://<network>/invoice/<recipient>?amount=<uT>&reference=<ref>[&expires=<unix>][&message=<msg>]`
+    pub fn to_uri(&self) -> String {
+        let mut uri = format!(
+            "{}{}/{}/{}?amount={}&reference={}",
+            URI_SCHEME,
+            self.network,
+            URI_INVOICE_PATH,
+            self.recipient.to_hex(),
+            self.amount.as_u64(),
+            percent_encode(&self.reference)
+        );
+        if let Some(expires_at) = self.expires_at {
+            uri.push_str(&format!("&expires={}", expires_at.timestamp()));
+        }
+        if !self.message.is_empty() {
+            uri.push_str(&format!("&message={}", percent_encode(&self.message)));
+        }
+        uri
+    }
+}
+
+impl FromStr for PaymentRequest {
+    type Err = InvoiceServiceError;
+
+    fn from_str(uri: &str) -> Result<Self, Self::Err> {
+        let invalid = |msg: &str| InvoiceServiceError::InvalidPaymentRequest(msg.to_string());
+        let rest = uri.strip_prefix(URI_SCHEME).ok_or_else(|| invalid("not a tari uri"))?;
+        let (path, query) = rest.split_once('?').ok_or_else(|| invalid("missing query"))?;
+        let mut segments = path.split('/');
+        let network = segments
+            .next()
+            .and_then(|n| Network::from_str(n).ok())
+            .ok_or_else(|| invalid("invalid network"))?;
+        if segments.next() != Some(URI_INVOICE_PATH) {
+            return Err(invalid("not an invoice"));
+        }
+        let recipient = segments
+            .next()
+            .and_then(|pk| PublicKey::from_hex(pk).ok())
+            .ok_or_else(|| invalid("invalid recipient public key"))?;
+
+        let mut amount = None;
+        let mut reference = None;
+        let mut expires_at = None;
+        let mut message = String::new();
+        for pair in query.split('&') {
+            let (key, value) = pair.split_once('=').ok_or_else(|| invalid("malformed query"))?;
+            match key {
+                "amount" => {
+                    amount = Some(MicroTari::from(
+                        value.parse::<u64>().map_err(|_| invalid("invalid amount"))?,
+                    ))
+                },
+                "reference" => reference = Some(percent_decode(value).ok_or_else(|| invalid("invalid reference"))?),
+                "expires" => {
+                    let timestamp = value.parse::<i64>().map_err(|_| invalid("invalid expiry"))?;
+                    expires_at = Some(NaiveDateTime::from_timestamp(timestamp, 0));
+                },
+                "message" => message = percent_decode(value).ok_or_else(|| invalid("invalid message"))?,
+                // Ignore unknown parameters so that the format can be extended
+                _ => {},
+            }
+        }
+
+        Ok(Self {
+            network,
+            recipient,
+            amount: amount.ok_or_else(|| invalid("missing amount"))?,
+            reference: reference.ok_or_else(|| invalid("missing reference"))?,
+            message,
+            expires_at,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Invoice {
+    pub id: u64,
+    pub reference: String,
+    pub amount: MicroTari,
+    pub recipient: PublicKey,
+    pub message: String,
+    pub status: InvoiceStatus,
+    /// The transaction that paid the invoice
+    pub tx_id: Option<TxId>,
+    pub created_at: NaiveDateTime,
+    pub expires_at: Option<NaiveDateTime>,
+    pub paid_at: Option<NaiveDateTime>,
+}
+
+impl Invoice {
+    pub fn payment_request(&self, network: Network) -> PaymentRequest {
+        PaymentRequest {
+            network,
+            recipient: self.recipient.clone(),
+            amount: self.amount,
+            reference: self.reference.clone(),
+            message: self.message.clone(),
+            expires_at: self.expires_at,
+        }
+    }
+
+    pub fn is_expired_at(&self, now: NaiveDateTime) -> bool {
+        self.status == InvoiceStatus::Pending && self.expires_at.map(|e| e <= now).unwrap_or(false)
+    }
+}
+
+/// Find the invoice paid by an inbound transaction.
+///
+/// A transaction whose message contains the reference of an open invoice as a separate word, and that pays at least
+/// the invoiced amount, pays that invoice. Payments without a reference, such as one-sided payments that carry no
+/// message, are never matched on their amount alone because an unrelated payment could settle the invoice.
+pub fn find_paid_invoice<'a>(invoices: &'a [Invoice], tx: &CompletedTransaction) -> Option<&'a Invoice> {
+    if tx.direction != TransactionDirection::Inbound {
+        return None;
+    }
+    invoices
+        .iter()
+        .filter(|i| i.status.is_open())
+        .find(|i| contains_reference(&tx.message, &i.reference) && tx.amount >= i.amount)
+}
+
+/// Whether `message` contains `reference` as a whole word, ignoring punctuation around it (e.g. `(INV-1234).`)
+fn contains_reference(message: &str, reference: &str) -> bool {
+    message
+        .split_whitespace()
+        .any(|word| word == reference || word.trim_matches(|c: char| c.is_ascii_punctuation()) == reference)
+}
+
+/// Mark pending invoices whose expiry has passed as expired, returning the ones that changed
+pub fn expire_invoices(invoices: &mut [Invoice], now: NaiveDateTime) -> Vec<Invoice> {
+    invoices
+        .iter_mut()
+        .filter(|i| i.is_expired_at(now))
+        .map(|i| {
+            i.status = InvoiceStatus::Expired;
+            i.clone()
+        })
+        .collect()
+}
+
+pub(crate) fn now() -> NaiveDateTime {
+    Utc::now().naive_utc()
+}
+
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn percent_decode(value: &str) -> Option<String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = value.get(i + 1..i + 3)?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use tari_common_types::{transaction::TransactionStatus, types::PrivateKey};
+    use tari_core::transactions::transaction_components::Transaction;
+    use tari_crypto::keys::PublicKey as PublicKeyTrait;
+    use tari_test_utils::random;
+
+    use super::*;
+
+    fn completed_tx(amount: u64, message: &str) -> CompletedTransaction {
+        CompletedTransaction::new(
+            TxId::new_random(),
+            PublicKey::default(),
+            PublicKey::default(),
+            MicroTari::from(amount),
+            MicroTari::from(0),
+            Transaction::new(vec![], vec![], vec![], PrivateKey::default(), PrivateKey::default()),
+            TransactionStatus::Completed,
+            message.to_string(),
+            now(),
+            TransactionDirection::Inbound,
+            None,
+            None,
+        )
+    }
+
+    fn invoice(reference: &str, amount: u64) -> Invoice {
+        let (_, recipient) = PublicKey::random_keypair(&mut rand::rngs::OsRng);
+        Invoice {
+            id: rand::random(),
+            reference: reference.to_string(),
+            amount: MicroTari::from(amount),
+            recipient,
+            message: String::new(),
+            status: InvoiceStatus::Pending,
+            tx_id: None,
+            created_at: now(),
+            expires_at: None,
+            paid_at: None,
+        }
+    }
+
+    #[test]
+    fn payment_request_uri_round_trip() {
+        let invoice = Invoice {
+            message: "Order #42 & more".to_string(),
+            expires_at: Some(NaiveDateTime::from_timestamp(1_700_000_000, 0)),
+            ..invoice(&random::string(10), 1_000_000)
+        };
+        let request = invoice.payment_request(Network::Dibbler);
+        let uri = request.to_uri();
+        assert!(uri.starts_with("tari://dibbler/invoice/"));
+        assert!(uri.contains("message=Order%20%2342%20%26%20more"));
+        assert_eq!(PaymentRequest::from_str(&uri).unwrap(), request);
+
+        assert!(PaymentRequest::from_str("tari://dibbler/pubkey/00").is_err());
+        assert!(PaymentRequest::from_str(&uri.replace("amount=1000000", "amount=lots")).is_err());
+    }
+
+    #[test]
+    fn it_matches_payments_to_invoices() {
+        let invoices = vec![
+            invoice("INV-AAAA", 500),
+            invoice("INV-BBBB", 1000),
+            invoice("INV-CCCC", 1000),
+        ];
+
+        let mut tx = completed_tx(1000, "Payment for INV-BBBB");
+        assert_eq!(find_paid_invoice(&invoices, &tx).unwrap().reference, "INV-BBBB");
+
+        // Underpayment does not pay the invoice
+        tx.amount = MicroTari::from(999);
+        assert!(find_paid_invoice(&invoices, &tx).is_none());
+
+        // Outbound transactions never pay an invoice
+        tx.amount = MicroTari::from(1000);
+        tx.direction = TransactionDirection::Outbound;
+        assert!(find_paid_invoice(&invoices, &tx).is_none());
+
+        // The reference must be a separate word, but may be surrounded by punctuation
+        tx.direction = TransactionDirection::Inbound;
+        tx.message = "Payment for INV-BBBBX".to_string();
+        assert!(find_paid_invoice(&invoices, &tx).is_none());
+        tx.message = "XINV-BBBB".to_string();
+        assert!(find_paid_invoice(&invoices, &tx).is_none());
+        tx.message = "Payment (INV-BBBB).".to_string();
+        assert_eq!(find_paid_invoice(&invoices, &tx).unwrap().reference, "INV-BBBB");
+
+        // A one-sided payment without a reference is not matched on its amount alone
+        let mut one_sided = completed_tx(500, "");
+        one_sided.status = TransactionStatus::FauxUnconfirmed;
+        assert!(find_paid_invoice(&invoices, &one_sided).is_none());
+        one_sided.message = "INV-AAAA".to_string();
+        assert_eq!(find_paid_invoice(&invoices, &one_sided).unwrap().reference, "INV-AAAA");
+    }
+
+    #[test]
+    fn it_expires_pending_invoices() {
+        let mut invoices = vec![invoice("INV-AAAA", 500), invoice("INV-BBBB", 1000)];
+        invoices[0].expires_at = Some(now() - chrono::Duration::seconds(1));
+        invoices[1].expires_at = Some(now() + chrono::Duration::hours(1));
+        let expired = expire_invoices(&mut invoices, now());
+        assert_eq!(expired.len(), 1);
+        assert_eq!(invoices[0].status, InvoiceStatus::Expired);
+        assert_eq!(invoices[1].status, InvoiceStatus::Pending);
+    }
+}
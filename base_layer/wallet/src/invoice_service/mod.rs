@@ -0,0 +1,99 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Payment requests ("invoices") for incoming payments. An invoice is encoded as a `tari://` URI that the payer can
+//! scan or paste, and the service watches incoming transactions to mark invoices as paid and then confirmed.
+
+pub mod error;
+pub mod handle;
+pub mod invoice;
+pub mod service;
+
+use std::sync::Arc;
+
+use futures::future;
+use log::*;
+use tari_comms::NodeIdentity;
+use tari_service_framework::{
+    async_trait,
+    reply_channel,
+    ServiceInitializationError,
+    ServiceInitializer,
+    ServiceInitializerContext,
+};
+
+use crate::{
+    invoice_service::{handle::InvoiceServiceHandle, service::InvoiceService},
+    storage::database::{WalletBackend, WalletDatabase},
+    transaction_service::handle::TransactionServiceHandle,
+};
+
+const LOG_TARGET: &str = "wallet::invoice_service::initializer";
+
+pub struct InvoiceServiceInitializer<T>
+where T: WalletBackend
+{
+    db: WalletDatabase<T>,
+    node_identity: Arc<NodeIdentity>,
+}
+
+impl<T> InvoiceServiceInitializer<T>
+where T: WalletBackend
+{
+    pub fn new(db: WalletDatabase<T>, node_identity: Arc<NodeIdentity>) -> Self {
+        Self { db, node_identity }
+    }
+}
+
+#[async_trait]
+impl<T> ServiceInitializer for InvoiceServiceInitializer<T>
+where T: WalletBackend + 'static
+{
+    async fn initialize(&mut self, context: ServiceInitializerContext) -> Result<(), ServiceInitializationError> {
+        let (sender, receiver) = reply_channel::unbounded();
+
+        let invoice_handle = InvoiceServiceHandle::new(sender);
+
+        // Register handle before waiting for handles to be ready
+        context.register_handle(invoice_handle);
+
+        let db = self.db.clone();
+        let recipient = self.node_identity.public_key().clone();
+        let shutdown_signal = context.get_shutdown_signal();
+
+        context.spawn_when_ready(move |handles| async move {
+            let transaction_service = handles.expect_handle::<TransactionServiceHandle>();
+            let service = InvoiceService::new(
+                receiver,
+                db,
+                recipient,
+                transaction_service,
+                handles.get_shutdown_signal(),
+            )
+            .start();
+            futures::pin_mut!(service);
+            future::select(service, shutdown_signal).await;
+            info!(target: LOG_TARGET, "Invoice service shutdown");
+        });
+        Ok(())
+    }
+}
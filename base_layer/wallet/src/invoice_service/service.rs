@@ -0,0 +1,397 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::time::Duration;
+
+use chrono::Duration as ChronoDuration;
+use futures::{pin_mut, StreamExt};
+use log::*;
+use rand::{distributions::Alphanumeric, rngs::OsRng, Rng, RngCore};
+use tari_common_types::{
+    transaction::{TransactionStatus, TxId},
+    types::PublicKey,
+};
+use tari_core::transactions::tari_amount::MicroTari;
+use tari_service_framework::reply_channel;
+use tari_shutdown::ShutdownSignal;
+use tokio::sync::broadcast;
+
+use crate::{
+    invoice_service::{
+        error::InvoiceServiceError,
+        handle::{InvoiceServiceRequest, InvoiceServiceResponse},
+        invoice::{expire_invoices, find_paid_invoice, now, Invoice, InvoiceStatus},
+    },
+    storage::database::{WalletBackend, WalletDatabase},
+    transaction_service::handle::{TransactionEvent, TransactionEventReceiver, TransactionServiceHandle},
+};
+
+const LOG_TARGET: &str = "wallet::invoice_service";
+const REFERENCE_PREFIX: &str = "INV-";
+const REFERENCE_LENGTH: usize = 10;
+
+pub struct InvoiceService<T>
+where T: WalletBackend + 'static
+{
+    db: WalletDatabase<T>,
+    recipient: PublicKey,
+    transaction_service: TransactionServiceHandle,
+    request_stream:
+        Option<reply_channel::Receiver<InvoiceServiceRequest, Result<InvoiceServiceResponse, InvoiceServiceError>>>,
+    shutdown_signal: Option<ShutdownSignal>,
+}
+
+impl<T> InvoiceService<T>
+where T: WalletBackend + 'static
+{
+    pub fn new(
+        request_stream: reply_channel::Receiver<
+            InvoiceServiceRequest,
+            Result<InvoiceServiceResponse, InvoiceServiceError>,
+        >,
+        db: WalletDatabase<T>,
+        recipient: PublicKey,
+        transaction_service: TransactionServiceHandle,
+        shutdown_signal: ShutdownSignal,
+    ) -> Self {
+        Self {
+            db,
+            recipient,
+            transaction_service,
+            request_stream: Some(request_stream),
+            shutdown_signal: Some(shutdown_signal),
+        }
+    }
+
+    pub async fn start(mut self) -> Result<(), InvoiceServiceError> {
+        let request_stream = self
+            .request_stream
+            .take()
+            .expect("Invoice Service initialized without request_stream")
+            .fuse();
+        pin_mut!(request_stream);
+
+        let shutdown = self
+            .shutdown_signal
+            .take()
+            .expect("Invoice Service initialized without shutdown signal");
+        pin_mut!(shutdown);
+
+        let mut transaction_events: TransactionEventReceiver = self.transaction_service.get_event_stream();
+
+        debug!(target: LOG_TARGET, "Invoice Service started");
+        // Payments may have been received while the service was not running
+        if let Err(e) = self.reconcile_payments().await {
+            error!(
+                target: LOG_TARGET,
+                "Error reconciling invoices with the transaction history: {}", e
+            );
+        }
+        loop {
+            tokio::select! {
+                Some(request_context) = request_stream.next() => {
+                    let (request, reply_tx) = request_context.split();
+                    let response = self.handle_request(request).await.map_err(|e| {
+                        error!(target: LOG_TARGET, "Error handling request: {:?}", e);
+                        e
+                    });
+                    let _ = reply_tx.send(response).map_err(|e| {
+                        error!(target: LOG_TARGET, "Failed to send reply");
+                        e
+                    });
+                },
+                result = transaction_events.recv() => {
+                    match result {
+                        Ok(event) => {
+                            if let Err(e) = self.handle_transaction_event(&*event).await {
+                                error!(target: LOG_TARGET, "Error matching event {} to invoices: {}", event, e);
+                            }
+                        },
+                        Err(broadcast::error::RecvError::Lagged(n)) => {
+                            warn!(
+                                target: LOG_TARGET,
+                                "Invoice service missed {} transaction events, reconciling invoices", n
+                            );
+                            if let Err(e) = self.reconcile_payments().await {
+                                error!(
+                                    target: LOG_TARGET,
+                                    "Error reconciling invoices with the transaction history: {}", e
+                                );
+                            }
+                        },
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    }
+                },
+                _ = shutdown.wait() => {
+                    info!(target: LOG_TARGET, "Invoice service shutting down because it received the shutdown signal");
+                    break;
+                }
+            }
+        }
+        info!(target: LOG_TARGET, "Invoice Service ended");
+        Ok(())
+    }
+
+    async fn handle_request(
+        &mut self,
+        request: InvoiceServiceRequest,
+    ) -> Result<InvoiceServiceResponse, InvoiceServiceError> {
+        match request {
+            InvoiceServiceRequest::CreateInvoice {
+                amount,
+                reference,
+                message,
+                expires_in,
+            } => self
+                .create_invoice(amount, reference, message, expires_in)
+                .await
+                .map(|i| InvoiceServiceResponse::Invoice(Box::new(i))),
+            InvoiceServiceRequest::GetInvoice(id) => self
+                .fetch_invoice(id)
+                .await
+                .map(|i| InvoiceServiceResponse::Invoice(Box::new(i))),
+            InvoiceServiceRequest::GetInvoices => self.get_invoices().await.map(InvoiceServiceResponse::Invoices),
+            InvoiceServiceRequest::CancelInvoice(id) => self
+                .cancel_invoice(id)
+                .await
+                .map(|i| InvoiceServiceResponse::Invoice(Box::new(i))),
+        }
+    }
+
+    async fn create_invoice(
+        &mut self,
+        amount: MicroTari,
+        reference: Option<String>,
+        message: String,
+        expires_in: Option<Duration>,
+    ) -> Result<Invoice, InvoiceServiceError> {
+        if amount == MicroTari::from(0) {
+            return Err(InvoiceServiceError::ZeroAmount);
+        }
+        let existing = self.db.get_invoices().await?;
+        let reference = match reference {
+            Some(reference) => {
+                if existing.iter().any(|i| i.reference == reference) {
+                    return Err(InvoiceServiceError::DuplicateReference(reference));
+                }
+                reference
+            },
+            None => loop {
+                let reference = generate_reference();
+                if !existing.iter().any(|i| i.reference == reference) {
+                    break reference;
+                }
+            },
+        };
+
+        let created_at = now();
+        let expires_at = match expires_in {
+            Some(expires_in) => Some(
+                created_at +
+                    ChronoDuration::from_std(expires_in)
+                        .map_err(|e| InvoiceServiceError::ConversionError(e.to_string()))?,
+            ),
+            None => None,
+        };
+        let invoice = Invoice {
+            id: OsRng.next_u64(),
+            reference,
+            amount,
+            recipient: self.recipient.clone(),
+            message,
+            status: InvoiceStatus::Pending,
+            tx_id: None,
+            created_at,
+            expires_at,
+            paid_at: None,
+        };
+        self.db.save_invoice(invoice.clone()).await?;
+        info!(
+            target: LOG_TARGET,
+            "Created invoice {} for {} with reference {}", invoice.id, invoice.amount, invoice.reference
+        );
+        Ok(invoice)
+    }
+
+    async fn fetch_invoice(&mut self, id: u64) -> Result<Invoice, InvoiceServiceError> {
+        self.get_invoices()
+            .await?
+            .into_iter()
+            .find(|i| i.id == id)
+            .ok_or(InvoiceServiceError::InvoiceNotFound(id))
+    }
+
+    /// Fetch all invoices, marking those that have passed their expiry as expired
+    async fn get_invoices(&mut self) -> Result<Vec<Invoice>, InvoiceServiceError> {
+        let mut invoices = self.db.get_invoices().await?;
+        for invoice in expire_invoices(&mut invoices, now()) {
+            debug!(target: LOG_TARGET, "Invoice {} expired", invoice.id);
+            self.db.save_invoice(invoice).await?;
+        }
+        Ok(invoices)
+    }
+
+    async fn cancel_invoice(&mut self, id: u64) -> Result<Invoice, InvoiceServiceError> {
+        let mut invoice = self.fetch_invoice(id).await?;
+        if !invoice.status.is_open() {
+            return Err(InvoiceServiceError::InvalidInvoiceStatus(
+                id,
+                invoice.status.to_string(),
+            ));
+        }
+        invoice.status = InvoiceStatus::Cancelled;
+        self.db.save_invoice(invoice.clone()).await?;
+        info!(target: LOG_TARGET, "Cancelled invoice {}", id);
+        Ok(invoice)
+    }
+
+    async fn handle_transaction_event(&mut self, event: &TransactionEvent) -> Result<(), InvoiceServiceError> {
+        match event {
+            TransactionEvent::ReceivedFinalizedTransaction(tx_id) |
+            TransactionEvent::TransactionImported(tx_id) |
+            TransactionEvent::FauxTransactionUnconfirmed { tx_id, .. } => self.match_payment(*tx_id).await,
+            TransactionEvent::TransactionMined { tx_id, is_valid: true } |
+            TransactionEvent::FauxTransactionConfirmed { tx_id, is_valid: true } => {
+                // A faux transaction may be confirmed without an unconfirmed event having been seen first
+                self.match_payment(*tx_id).await?;
+                self.confirm_payment(*tx_id).await
+            },
+            TransactionEvent::TransactionCancelled(tx_id, _) => self.revert_payment(*tx_id).await,
+            _ => Ok(()),
+        }
+    }
+
+    /// Mark the invoice paid by the transaction, if any, as paid
+    async fn match_payment(&mut self, tx_id: TxId) -> Result<(), InvoiceServiceError> {
+        let invoices = self.get_invoices().await?;
+        if invoices.iter().any(|i| i.tx_id == Some(tx_id)) {
+            return Ok(());
+        }
+        let tx = match self.transaction_service.get_completed_transaction(tx_id).await {
+            Ok(tx) => tx,
+            Err(e) => {
+                trace!(
+                    target: LOG_TARGET,
+                    "Transaction {} is not a completed transaction: {}",
+                    tx_id,
+                    e
+                );
+                return Ok(());
+            },
+        };
+        if let Some(invoice) = find_paid_invoice(&invoices, &tx) {
+            let mut invoice = invoice.clone();
+            invoice.status = InvoiceStatus::Paid;
+            invoice.tx_id = Some(tx_id);
+            invoice.paid_at = Some(now());
+            info!(
+                target: LOG_TARGET,
+                "Invoice {} ({}) paid by transaction {} for {}", invoice.id, invoice.reference, tx_id, tx.amount
+            );
+            self.db.save_invoice(invoice).await?;
+        }
+        Ok(())
+    }
+
+    /// Match every completed transaction against the open invoices and confirm payments that have been mined, so that
+    /// no payment is missed when transaction events were not seen
+    async fn reconcile_payments(&mut self) -> Result<(), InvoiceServiceError> {
+        let transactions = self.transaction_service.get_completed_transactions().await?;
+        let mut invoices = self.get_invoices().await?;
+        let mut transactions = transactions.into_values().collect::<Vec<_>>();
+        transactions.sort_by_key(|tx| tx.timestamp);
+        for tx in transactions {
+            let tx_id = tx.tx_id;
+            if !invoices.iter().any(|i| i.tx_id == Some(tx_id)) {
+                let invoice = match find_paid_invoice(&invoices, &tx) {
+                    Some(invoice) => invoice.id,
+                    None => continue,
+                };
+                let invoice = invoices
+                    .iter_mut()
+                    .find(|i| i.id == invoice)
+                    .expect("invoice was just found");
+                invoice.status = InvoiceStatus::Paid;
+                invoice.tx_id = Some(tx_id);
+                invoice.paid_at = Some(now());
+                info!(
+                    target: LOG_TARGET,
+                    "Invoice {} ({}) paid by transaction {} for {}", invoice.id, invoice.reference, tx_id, tx.amount
+                );
+                self.db.save_invoice(invoice.clone()).await?;
+            }
+            if matches!(
+                tx.status,
+                TransactionStatus::MinedConfirmed | TransactionStatus::FauxConfirmed
+            ) {
+                self.confirm_payment(tx_id).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn confirm_payment(&mut self, tx_id: TxId) -> Result<(), InvoiceServiceError> {
+        let invoices = self.db.get_invoices().await?;
+        if let Some(invoice) = invoices
+            .into_iter()
+            .find(|i| i.tx_id == Some(tx_id) && i.status == InvoiceStatus::Paid)
+        {
+            info!(target: LOG_TARGET, "Payment of invoice {} confirmed", invoice.id);
+            self.db
+                .save_invoice(Invoice {
+                    status: InvoiceStatus::Confirmed,
+                    ..invoice
+                })
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Reopen an invoice whose payment was cancelled
+    async fn revert_payment(&mut self, tx_id: TxId) -> Result<(), InvoiceServiceError> {
+        let invoices = self.db.get_invoices().await?;
+        if let Some(mut invoice) = invoices.into_iter().find(|i| i.tx_id == Some(tx_id)) {
+            warn!(
+                target: LOG_TARGET,
+                "Payment {} of invoice {} was cancelled, reopening the invoice", tx_id, invoice.id
+            );
+            invoice.status = InvoiceStatus::Pending;
+            invoice.tx_id = None;
+            invoice.paid_at = None;
+            if invoice.is_expired_at(now()) {
+                invoice.status = InvoiceStatus::Expired;
+            }
+            self.db.save_invoice(invoice).await?;
+        }
+        Ok(())
+    }
+}
+
+fn generate_reference() -> String {
+    let suffix: String = OsRng
+        .sample_iter(&Alphanumeric)
+        .take(REFERENCE_LENGTH)
+        .map(char::from)
+        .collect::<String>()
+        .to_uppercase();
+    format!("{}{}", REFERENCE_PREFIX, suffix)
+}
@@ -18,6 +18,7 @@ pub mod base_node_service;
 pub mod connectivity_service;
 pub mod contacts_service;
 pub mod error;
+pub mod invoice_service;
 mod operation_id;
 pub mod output_manager_service;
 pub mod storage;
@@ -0,0 +1,220 @@
+//  Copyright 2022, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Pure UTXO selection algorithms used by the output manager. The algorithms work on output values only so that they
+//! can be reasoned about (and tested) independently of the wallet database.
+
+use std::{cmp::Ordering, collections::HashMap, hash::Hash};
+
+/// Upper bound on the number of search nodes visited by [branch_and_bound] before giving up.
+const BNB_MAX_TRIES: usize = 100_000;
+
+/// Searches for a subset of `values` that covers `target`, plus `input_cost` for every selected value, without
+/// exceeding it by more than `cost_of_change`. Such a subset can be spent without creating a change output because
+/// the excess is cheaper to donate to the fee than to return as change. The subset with the least excess is
+/// returned as indices into `values`, or `None` if no such subset was found within the search limit.
+pub fn branch_and_bound(values: &[u64], target: u64, input_cost: u64, cost_of_change: u64) -> Option<Vec<usize>> {
+    // Work with effective values (the value less the cost of spending it), largest first so that the search reaches
+    // the target quickly and prunes early
+    let mut candidates = values
+        .iter()
+        .enumerate()
+        .filter_map(|(i, v)| v.checked_sub(input_cost).filter(|v| *v > 0).map(|v| (i, v)))
+        .collect::<Vec<_>>();
+    candidates.sort_by(|a, b| b.1.cmp(&a.1));
+
+    let remaining = candidates.iter().map(|(_, v)| *v).sum::<u64>();
+    if remaining < target {
+        return None;
+    }
+
+    let mut search = BranchAndBound {
+        candidates: &candidates,
+        target,
+        upper_bound: target.saturating_add(cost_of_change),
+        selection: Vec::new(),
+        best: None,
+        tries: 0,
+    };
+    search.explore(0, 0, remaining);
+    search.best.map(|(_, selection)| selection)
+}
+
+struct BranchAndBound<'a> {
+    candidates: &'a [(usize, u64)],
+    target: u64,
+    upper_bound: u64,
+    selection: Vec<usize>,
+    best: Option<(u64, Vec<usize>)>,
+    tries: usize,
+}
+
+impl BranchAndBound<'_> {
+    fn explore(&mut self, pos: usize, current: u64, remaining: u64) {
+        if self.tries >= BNB_MAX_TRIES || self.best.as_ref().map(|(waste, _)| *waste == 0).unwrap_or(false) {
+            return;
+        }
+        self.tries += 1;
+
+        if current > self.upper_bound {
+            return;
+        }
+        if current >= self.target {
+            let waste = current - self.target;
+            if self.best.as_ref().map(|(best, _)| waste < *best).unwrap_or(true) {
+                self.best = Some((waste, self.selection.clone()));
+            }
+            return;
+        }
+        if pos == self.candidates.len() || current + remaining < self.target {
+            return;
+        }
+
+        let (index, value) = self.candidates[pos];
+        let remaining = remaining - value;
+        // Inclusion branch first, then the omission branch
+        self.selection.push(index);
+        self.explore(pos + 1, current + value, remaining);
+        self.selection.pop();
+        self.explore(pos + 1, current, remaining);
+    }
+}
+
+/// Orders candidate outputs so that outputs received from the same counterparty are spent together. Each candidate
+/// is a `(counterparty, value)` pair; outputs with an unknown counterparty are treated as their own group.
+///
+/// If a single counterparty's outputs can cover `target`, plus `input_cost` for every output used, the group needing
+/// the fewest inputs is placed first and `true` is returned. Otherwise groups are ordered from the largest total to
+/// the smallest and `false` is returned, meaning that spending enough of them will link counterparties. Within a
+/// group, outputs are ordered from largest to smallest. The returned indices cover every candidate.
+pub fn order_by_counterparty<K>(candidates: &[(Option<K>, u64)], target: u64, input_cost: u64) -> (Vec<usize>, bool)
+where K: Eq + Hash {
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut group_index = HashMap::new();
+    for (i, (counterparty, _)) in candidates.iter().enumerate() {
+        match counterparty {
+            Some(k) => {
+                let g = *group_index.entry(k).or_insert_with(|| {
+                    groups.push(Vec::new());
+                    groups.len() - 1
+                });
+                groups[g].push(i);
+            },
+            None => groups.push(vec![i]),
+        }
+    }
+    for group in groups.iter_mut() {
+        group.sort_by(|a, b| candidates[*b].1.cmp(&candidates[*a].1));
+    }
+
+    // The number of inputs each group needs to cover the target on its own, if it can
+    let inputs_needed = |group: &Vec<usize>| -> Option<usize> {
+        let mut total = 0u64;
+        for (n, i) in group.iter().enumerate() {
+            total = total.saturating_add(candidates[*i].1);
+            if total >= target.saturating_add(input_cost.saturating_mul(n as u64 + 1)) {
+                return Some(n + 1);
+            }
+        }
+        None
+    };
+    let group_total = |group: &Vec<usize>| group.iter().map(|i| candidates[*i].1).sum::<u64>();
+
+    let best = groups
+        .iter()
+        .enumerate()
+        .filter_map(|(g, group)| inputs_needed(group).map(|n| (g, n)))
+        .min_by(|(a, a_inputs), (b, b_inputs)| match a_inputs.cmp(b_inputs) {
+            Ordering::Equal => group_total(&groups[*a]).cmp(&group_total(&groups[*b])),
+            ord => ord,
+        })
+        .map(|(g, _)| g);
+
+    let mut ordered = match best {
+        Some(g) => vec![groups.remove(g)],
+        None => Vec::new(),
+    };
+    groups.sort_by_key(|group| std::cmp::Reverse(group_total(group)));
+    ordered.extend(groups);
+
+    (ordered.into_iter().flatten().collect(), best.is_some())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_finds_an_exact_match() {
+        let values = [500, 300, 200, 100, 50];
+        let selection = branch_and_bound(&values, 350, 0, 0).unwrap();
+        let total = selection.iter().map(|i| values[*i]).sum::<u64>();
+        assert_eq!(total, 350);
+    }
+
+    #[test]
+    fn it_accounts_for_input_cost_and_cost_of_change() {
+        let values = [1_000, 610, 420];
+        // 610 + 420 - 2 * 10 = 1010 which is within the cost of change of the target
+        let mut selection = branch_and_bound(&values, 1_005, 10, 5).unwrap();
+        selection.sort_unstable();
+        assert_eq!(selection, vec![1, 2]);
+        // The single 1000 output does not cover the cost of spending it
+        assert!(branch_and_bound(&values, 995, 10, 0).is_none());
+    }
+
+    #[test]
+    fn it_gives_up_when_no_match_exists() {
+        assert!(branch_and_bound(&[100, 100, 100], 150, 0, 10).is_none());
+        assert!(branch_and_bound(&[100, 100], 250, 0, 10).is_none());
+        assert!(branch_and_bound(&[], 1, 0, 10).is_none());
+    }
+
+    #[test]
+    fn it_prefers_a_single_counterparty() {
+        let candidates = [
+            (Some("alice"), 100),
+            (Some("bob"), 300),
+            (None, 200),
+            (Some("alice"), 250),
+            (Some("bob"), 20),
+        ];
+        let (ordered, unlinked) = order_by_counterparty(&candidates, 320, 5);
+        assert!(unlinked);
+        // Bob's outputs only cover the amount before the cost of spending them is included
+        assert_eq!(&ordered[..2], &[3, 0]);
+        assert_eq!(ordered.len(), candidates.len());
+
+        // Bob can cover this amount with a single input
+        let (ordered, unlinked) = order_by_counterparty(&candidates, 300, 0);
+        assert!(unlinked);
+        assert_eq!(ordered[0], 1);
+    }
+
+    #[test]
+    fn it_orders_groups_by_total_when_linking_is_unavoidable() {
+        let candidates = [(Some(1), 100), (Some(2), 300), (Some(1), 250), (None, 200)];
+        let (ordered, unlinked) = order_by_counterparty(&candidates, 1_000, 0);
+        assert!(!unlinked);
+        assert_eq!(ordered, vec![2, 0, 1, 3]);
+    }
+}
@@ -22,6 +22,7 @@
 
 use std::time::Duration;
 
+use tari_core::transactions::tari_amount::MicroTari;
 use tari_key_manager::mnemonic::MnemonicLanguage;
 
 #[derive(Clone, Debug)]
@@ -34,6 +35,15 @@ pub struct OutputManagerServiceConfig {
     pub event_channel_size: usize,
     pub num_confirmations_required: u64,
     pub tx_validator_batch_size: usize,
+    /// The number of blocks within which transactions should be mined when the fee per gram is estimated by the base
+    /// node
+    pub fee_estimate_target_blocks: u64,
+    /// The fee per gram used when no fee per gram is specified and the base node cannot provide an estimate
+    pub default_fee_per_gram: MicroTari,
+    /// Base node estimates above this fee per gram are capped at this fee per gram
+    pub max_fee_per_gram: MicroTari,
+    /// If set, unspent outputs are periodically consolidated according to this policy
+    pub consolidation_policy: Option<ConsolidationPolicy>,
 }
 
 impl Default for OutputManagerServiceConfig {
@@ -47,6 +57,37 @@ impl Default for OutputManagerServiceConfig {
             event_channel_size: 250,
             num_confirmations_required: 3,
             tx_validator_batch_size: 100,
+            fee_estimate_target_blocks: 3,
+            default_fee_per_gram: MicroTari(5),
+            max_fee_per_gram: MicroTari(1_000),
+            consolidation_policy: None,
+        }
+    }
+}
+
+/// Governs when the wallet combines its unspent outputs into a single output with a pay-to-self transaction.
+#[derive(Clone, Debug)]
+pub struct ConsolidationPolicy {
+    /// Consolidate the smallest outputs whenever the wallet holds more than this many unspent outputs
+    pub max_utxo_count: usize,
+    /// Outputs worth at most this amount are swept together even when the wallet is under `max_utxo_count`
+    pub dust_threshold: MicroTari,
+    /// Consolidation is postponed while the estimated fee per gram is above this amount
+    pub max_fee_per_gram: MicroTari,
+    /// The maximum number of inputs spent by a single consolidation transaction
+    pub max_inputs_per_transaction: usize,
+    /// How often the policy is checked
+    pub interval: Duration,
+}
+
+impl Default for ConsolidationPolicy {
+    fn default() -> Self {
+        Self {
+            max_utxo_count: 500,
+            dust_threshold: MicroTari(0),
+            max_fee_per_gram: MicroTari(25),
+            max_inputs_per_transaction: 100,
+            interval: Duration::from_secs(60 * 60),
         }
     }
 }
@@ -67,6 +67,16 @@ pub enum OutputManagerError {
     NotEnoughFunds,
     #[error("Funds are still pending. Unable to fulfil transaction right now.")]
     FundsPending,
+    #[error("Not all of the specified outputs are available to spend")]
+    SpecifiedOutputsUnavailable,
+    #[error("There are not enough outputs worth consolidating")]
+    NoUtxosToConsolidate,
+    #[error("Account `{0}` does not exist")]
+    AccountNotFound(u64),
+    #[error("An account named `{0}` already exists")]
+    AccountAlreadyExists(String),
+    #[error("Account requests cannot be nested")]
+    NestedAccountRequest,
     #[error("Output already exists")]
     DuplicateOutput,
     #[error("Error sending a message to the public API")]
@@ -122,6 +132,12 @@ pub enum OutputManagerError {
     InvalidMessageError(String),
     #[error("Operation not support on this Key Manager branch")]
     KeyManagerBranchNotSupported,
+    #[error("This is a watch-only wallet and cannot sign or derive spending keys")]
+    WatchOnlyWallet,
+    #[error("The transaction template was prepared by a different wallet")]
+    TemplateWalletMismatch,
+    #[error("Transaction template input `{0}` cannot be spent by this wallet")]
+    UnspendableTemplateInput(String),
 }
 
 #[derive(Debug, Error)]
@@ -25,7 +25,7 @@ use std::{fmt, fmt::Formatter, sync::Arc};
 use aes_gcm::Aes256Gcm;
 use tari_common_types::{
     transaction::TxId,
-    types::{BlockHash, HashOutput, PublicKey},
+    types::{BlockHash, HashOutput, PrivateKey, PublicKey},
 };
 use tari_core::{
     covenants::Covenant,
@@ -50,11 +50,12 @@ use tower::Service;
 
 use crate::output_manager_service::{
     error::OutputManagerError,
-    service::Balance,
+    service::{Balance, UTXOSelectionStrategy},
     storage::{
-        models::{KnownOneSidedPaymentScript, SpendingPriority},
+        models::{Account, KnownOneSidedPaymentScript, SpendingPriority, UtxoConsolidation},
         OutputStatus,
     },
+    watch_only::{UnsignedTransactionTemplate, WatchOnlyKeys},
 };
 
 /// API Request enum
@@ -79,6 +80,9 @@ pub enum OutputManagerRequest {
         message: String,
         script: TariScript,
         covenant: Covenant,
+        selection_strategy: Option<UTXOSelectionStrategy>,
+        /// The height before which the recipient's output cannot be spent
+        recipient_maturity: Option<u64>,
     },
     CreatePayToSelfTransaction {
         tx_id: TxId,
@@ -88,6 +92,7 @@ pub enum OutputManagerRequest {
         fee_per_gram: MicroTari,
         lock_height: Option<u64>,
         message: String,
+        selection_strategy: Option<UTXOSelectionStrategy>,
     },
     CreatePayToSelfWithOutputs {
         outputs: Vec<UnblindedOutputBuilder>,
@@ -95,6 +100,12 @@ pub enum OutputManagerRequest {
         spending_unique_id: Option<Vec<u8>>,
         spending_parent_public_key: Option<PublicKey>,
     },
+    CreateOneSidedBatchTransaction {
+        tx_id: TxId,
+        recipients: Vec<(PublicKey, MicroTari)>,
+        fee_per_gram: MicroTari,
+        message: String,
+    },
     CancelTransaction(TxId),
     GetSpentOutputs,
     GetUnspentOutputs,
@@ -103,15 +114,52 @@ pub enum OutputManagerRequest {
     ValidateUtxos,
     RevalidateTxos,
     CreateCoinSplit((MicroTari, usize, MicroTari, Option<u64>)),
+    CreateConsolidationTransaction {
+        fee_per_gram: MicroTari,
+        max_inputs: usize,
+        max_value: Option<MicroTari>,
+    },
+    GetConsolidationHistory,
+    CreateAccount(String),
+    GetAccounts,
+    GetAccountTxIds,
+    CreateAccountTransfer {
+        tx_id: TxId,
+        to_account: u64,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+    },
+    /// Handle the wrapped request on behalf of the given account rather than the default account
+    ForAccount(u64, Box<OutputManagerRequest>),
     ApplyEncryption(Box<Aes256Gcm>),
     RemoveEncryption,
     GetPublicRewindKeys,
-    FeeEstimate {
+    GetWatchOnlyKeys,
+    PrepareOfflineTransaction(Box<UnsignedTransactionTemplate>),
+    EncumberOfflineTransaction {
+        tx_id: TxId,
+        input_hashes: Vec<HashOutput>,
+        outputs: Vec<TransactionOutput>,
+    },
+    GetMultisigKeys(u64),
+    CreateUnsignedTransactionTemplate {
         amount: MicroTari,
         fee_per_gram: MicroTari,
+        lock_height: Option<u64>,
+        message: String,
+        destination: PublicKey,
+        covenant: Covenant,
+    },
+    FeeEstimate {
+        amount: MicroTari,
+        fee_per_gram: Option<MicroTari>,
         num_kernels: usize,
         num_outputs: usize,
     },
+    EstimateFeePerGram {
+        target_blocks: Option<u64>,
+    },
     ScanForRecoverableOutputs {
         outputs: Vec<TransactionOutput>,
         tx_id: TxId,
@@ -155,6 +203,12 @@ impl fmt::Display for OutputManagerRequest {
             ConfirmPendingTransaction(v) => write!(f, "ConfirmPendingTransaction ({})", v),
             PrepareToSendTransaction { message, .. } => write!(f, "PrepareToSendTransaction ({})", message),
             CreatePayToSelfTransaction { message, .. } => write!(f, "CreatePayToSelfTransaction ({})", message),
+            CreateOneSidedBatchTransaction { tx_id, recipients, .. } => write!(
+                f,
+                "CreateOneSidedBatchTransaction ({}, {} recipients)",
+                tx_id,
+                recipients.len()
+            ),
             CancelTransaction(v) => write!(f, "CancelTransaction ({})", v),
             GetSpentOutputs => write!(f, "GetSpentOutputs"),
             GetUnspentOutputs => write!(f, "GetUnspentOutputs"),
@@ -163,10 +217,45 @@ impl fmt::Display for OutputManagerRequest {
             ValidateUtxos => write!(f, "ValidateUtxos"),
             RevalidateTxos => write!(f, "RevalidateTxos"),
             CreateCoinSplit(v) => write!(f, "CreateCoinSplit ({})", v.0),
+            CreateConsolidationTransaction { max_inputs, .. } => {
+                write!(f, "CreateConsolidationTransaction (up to {} inputs)", max_inputs)
+            },
+            GetConsolidationHistory => write!(f, "GetConsolidationHistory"),
+            CreateAccount(name) => write!(f, "CreateAccount ({})", name),
+            GetAccounts => write!(f, "GetAccounts"),
+            GetAccountTxIds => write!(f, "GetAccountTxIds"),
+            CreateAccountTransfer {
+                tx_id,
+                to_account,
+                amount,
+                ..
+            } => write!(
+                f,
+                "CreateAccountTransfer ({}, {} to account {})",
+                tx_id, amount, to_account
+            ),
+            ForAccount(account_id, request) => write!(f, "{} (account {})", request, account_id),
             ApplyEncryption(_) => write!(f, "ApplyEncryption"),
             RemoveEncryption => write!(f, "RemoveEncryption"),
             GetCoinbaseTransaction(_) => write!(f, "GetCoinbaseTransaction"),
             GetPublicRewindKeys => write!(f, "GetPublicRewindKeys"),
+            GetWatchOnlyKeys => write!(f, "GetWatchOnlyKeys"),
+            PrepareOfflineTransaction(template) => write!(f, "PrepareOfflineTransaction ({})", template.tx_id),
+            EncumberOfflineTransaction {
+                tx_id,
+                input_hashes,
+                outputs,
+            } => write!(
+                f,
+                "EncumberOfflineTransaction ({}, {} inputs, {} outputs)",
+                tx_id,
+                input_hashes.len(),
+                outputs.len()
+            ),
+            GetMultisigKeys(session_id) => write!(f, "GetMultisigKeys ({})", session_id),
+            CreateUnsignedTransactionTemplate { amount, message, .. } => {
+                write!(f, "CreateUnsignedTransactionTemplate ({}, {})", amount, message)
+            },
             FeeEstimate {
                 amount,
                 fee_per_gram,
@@ -175,8 +264,14 @@ impl fmt::Display for OutputManagerRequest {
             } => write!(
                 f,
                 "FeeEstimate(amount: {}, fee_per_gram: {}, num_kernels: {}, num_outputs: {})",
-                amount, fee_per_gram, num_kernels, num_outputs
+                amount,
+                fee_per_gram
+                    .map(|fee| fee.to_string())
+                    .unwrap_or_else(|| "estimated".to_string()),
+                num_kernels,
+                num_outputs
             ),
+            EstimateFeePerGram { target_blocks } => write!(f, "EstimateFeePerGram({:?})", target_blocks),
             ScanForRecoverableOutputs { .. } => write!(f, "ScanForRecoverableOutputs"),
             ScanOutputs { .. } => write!(f, "ScanOutputs"),
             AddKnownOneSidedPaymentScript(_) => write!(f, "AddKnownOneSidedPaymentScript"),
@@ -205,6 +300,35 @@ impl fmt::Display for OutputManagerRequest {
     }
 }
 
+impl OutputManagerRequest {
+    /// Returns true if fulfilling this request requires deriving spending keys or signing, which a watch-only wallet
+    /// is unable to do.
+    pub fn requires_spending_keys(&self) -> bool {
+        use OutputManagerRequest::*;
+        if let ForAccount(_, request) = self {
+            return request.requires_spending_keys();
+        }
+        matches!(
+            self,
+            GetRecipientTransaction(_) |
+                GetCoinbaseTransaction(_) |
+                PrepareToSendTransaction { .. } |
+                PrepareOfflineTransaction(_) |
+                GetMultisigKeys(_) |
+                CreatePayToSelfTransaction { .. } |
+                CreatePayToSelfWithOutputs { .. } |
+                CreateOneSidedBatchTransaction { .. } |
+                GetSeedWords |
+                CreateCoinSplit(_) |
+                CreateConsolidationTransaction { .. } |
+                CreateAccountTransfer { .. } |
+                CreateOutputWithFeatures { .. } |
+                CreateClaimShaAtomicSwapTransaction(..) |
+                CreateHtlcRefundTransaction(..)
+        )
+    }
+}
+
 /// API Reply enum
 #[derive(Debug, Clone)]
 pub enum OutputManagerResponse {
@@ -216,6 +340,7 @@ pub enum OutputManagerResponse {
     OutputConfirmed,
     PendingTransactionConfirmed,
     PayToSelfTransaction((MicroTari, Transaction)),
+    OneSidedBatchTransaction((MicroTari, Transaction)),
     TransactionToSend(SenderTransactionProtocol),
     TransactionCancelled,
     SpentOutputs(Vec<UnblindedOutput>),
@@ -225,10 +350,20 @@ pub enum OutputManagerResponse {
     BaseNodePublicKeySet,
     TxoValidationStarted(u64),
     Transaction((TxId, Transaction, MicroTari)),
+    ConsolidationTransaction(Box<(UtxoConsolidation, Transaction)>),
+    ConsolidationHistory(Vec<UtxoConsolidation>),
+    Account(Account),
+    Accounts(Vec<Account>),
+    AccountTxIds(Vec<TxId>),
     EncryptionApplied,
     EncryptionRemoved,
     PublicRewindKeys(Box<PublicRewindKeys>),
+    WatchOnlyKeys(Box<WatchOnlyKeys>),
+    UnsignedTransactionTemplate(Box<UnsignedTransactionTemplate>),
+    OfflineTransactionEncumbered,
+    MultisigKeys(Box<MultisigKeys>),
     FeeEstimate(MicroTari),
+    FeePerGram(MicroTari),
     RewoundOutputs(Vec<UnblindedOutput>),
     ScanOutputs(Vec<UnblindedOutput>),
     AddKnownOneSidedPaymentScript,
@@ -282,10 +417,21 @@ pub struct PublicRewindKeys {
     pub rewind_blinding_public_key: PublicKey,
 }
 
+/// The keys a wallet derives for a multisig session. The signing key is this wallet's contribution to the script key of
+/// the shared output, and the polynomial key derives the rest of the polynomial it is shared with. The spending key is
+/// only used for the shared output when this wallet leads the session.
+#[derive(Debug, Clone)]
+pub struct MultisigKeys {
+    pub spending_key: PrivateKey,
+    pub polynomial_key: PrivateKey,
+    pub signing_key: PrivateKey,
+}
+
 #[derive(Clone)]
 pub struct OutputManagerHandle {
     handle: SenderService<OutputManagerRequest, Result<OutputManagerResponse, OutputManagerError>>,
     event_stream_sender: OutputManagerEventSender,
+    account_id: Option<u64>,
 }
 
 impl OutputManagerHandle {
@@ -296,9 +442,33 @@ impl OutputManagerHandle {
         OutputManagerHandle {
             handle,
             event_stream_sender,
+            account_id: None,
         }
     }
 
+    /// Returns a handle whose requests act on the given account. Requests made through an unscoped handle act on the
+    /// default account.
+    pub fn for_account(&self, account_id: u64) -> Self {
+        OutputManagerHandle {
+            handle: self.handle.clone(),
+            event_stream_sender: self.event_stream_sender.clone(),
+            account_id: Some(account_id),
+        }
+    }
+
+    /// The account this handle is scoped to, if any
+    pub fn account_id(&self) -> Option<u64> {
+        self.account_id
+    }
+
+    async fn request(&mut self, request: OutputManagerRequest) -> Result<OutputManagerResponse, OutputManagerError> {
+        let request = match self.account_id {
+            Some(account_id) => OutputManagerRequest::ForAccount(account_id, Box::new(request)),
+            None => request,
+        };
+        self.handle.call(request).await?
+    }
+
     pub fn get_event_stream(&self) -> OutputManagerEventReceiver {
         self.event_stream_sender.subscribe()
     }
@@ -309,9 +479,8 @@ impl OutputManagerHandle {
         spend_priority: Option<SpendingPriority>,
     ) -> Result<(), OutputManagerError> {
         match self
-            .handle
-            .call(OutputManagerRequest::AddOutput((Box::new(output), spend_priority)))
-            .await??
+            .request(OutputManagerRequest::AddOutput((Box::new(output), spend_priority)))
+            .await?
         {
             OutputManagerResponse::OutputAdded => Ok(()),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
@@ -325,13 +494,12 @@ impl OutputManagerHandle {
         spend_priority: Option<SpendingPriority>,
     ) -> Result<(), OutputManagerError> {
         match self
-            .handle
-            .call(OutputManagerRequest::AddOutputWithTxId((
+            .request(OutputManagerRequest::AddOutputWithTxId((
                 tx_id,
                 Box::new(output),
                 spend_priority,
             )))
-            .await??
+            .await?
         {
             OutputManagerResponse::OutputAdded => Ok(()),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
@@ -346,14 +514,13 @@ impl OutputManagerHandle {
         custom_rewind_data: Option<RewindData>,
     ) -> Result<(), OutputManagerError> {
         match self
-            .handle
-            .call(OutputManagerRequest::AddRewindableOutputWithTxId((
+            .request(OutputManagerRequest::AddRewindableOutputWithTxId((
                 tx_id,
                 Box::new(output),
                 spend_priority,
                 custom_rewind_data,
             )))
-            .await??
+            .await?
         {
             OutputManagerResponse::OutputAdded => Ok(()),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
@@ -367,13 +534,12 @@ impl OutputManagerHandle {
         spend_priority: Option<SpendingPriority>,
     ) -> Result<(), OutputManagerError> {
         match self
-            .handle
-            .call(OutputManagerRequest::AddUnvalidatedOutput((
+            .request(OutputManagerRequest::AddUnvalidatedOutput((
                 tx_id,
                 Box::new(output),
                 spend_priority,
             )))
-            .await??
+            .await?
         {
             OutputManagerResponse::OutputAdded => Ok(()),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
@@ -386,12 +552,11 @@ impl OutputManagerHandle {
         features: OutputFeatures,
     ) -> Result<UnblindedOutputBuilder, OutputManagerError> {
         match self
-            .handle
-            .call(OutputManagerRequest::CreateOutputWithFeatures {
+            .request(OutputManagerRequest::CreateOutputWithFeatures {
                 value,
                 features: Box::new(features),
             })
-            .await??
+            .await?
         {
             OutputManagerResponse::CreateOutputWithFeatures { output } => Ok(*output),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
@@ -403,9 +568,8 @@ impl OutputManagerHandle {
         output: TransactionOutput,
     ) -> Result<(), OutputManagerError> {
         match self
-            .handle
-            .call(OutputManagerRequest::UpdateOutputMetadataSignature(Box::new(output)))
-            .await??
+            .request(OutputManagerRequest::UpdateOutputMetadataSignature(Box::new(output)))
+            .await?
         {
             OutputManagerResponse::OutputMetadataSignatureUpdated => Ok(()),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
@@ -413,14 +577,14 @@ impl OutputManagerHandle {
     }
 
     pub async fn get_balance(&mut self) -> Result<Balance, OutputManagerError> {
-        match self.handle.call(OutputManagerRequest::GetBalance).await?? {
+        match self.request(OutputManagerRequest::GetBalance).await? {
             OutputManagerResponse::Balance(b) => Ok(b),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
         }
     }
 
     pub async fn revalidate_all_outputs(&mut self) -> Result<u64, OutputManagerError> {
-        match self.handle.call(OutputManagerRequest::RevalidateTxos).await?? {
+        match self.request(OutputManagerRequest::RevalidateTxos).await? {
             OutputManagerResponse::TxoValidationStarted(request_key) => Ok(request_key),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
         }
@@ -431,9 +595,8 @@ impl OutputManagerHandle {
         sender_message: TransactionSenderMessage,
     ) -> Result<ReceiverTransactionProtocol, OutputManagerError> {
         match self
-            .handle
-            .call(OutputManagerRequest::GetRecipientTransaction(sender_message))
-            .await??
+            .request(OutputManagerRequest::GetRecipientTransaction(sender_message))
+            .await?
         {
             OutputManagerResponse::RecipientTransactionGenerated(rtp) => Ok(rtp),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
@@ -448,14 +611,13 @@ impl OutputManagerHandle {
         block_height: u64,
     ) -> Result<Transaction, OutputManagerError> {
         match self
-            .handle
-            .call(OutputManagerRequest::GetCoinbaseTransaction((
+            .request(OutputManagerRequest::GetCoinbaseTransaction((
                 tx_id,
                 reward,
                 fees,
                 block_height,
             )))
-            .await??
+            .await?
         {
             OutputManagerResponse::CoinbaseTransaction(tx) => Ok(tx),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
@@ -473,10 +635,10 @@ impl OutputManagerHandle {
         message: String,
         script: TariScript,
         covenant: Covenant,
+        selection_strategy: Option<UTXOSelectionStrategy>,
     ) -> Result<SenderTransactionProtocol, OutputManagerError> {
         match self
-            .handle
-            .call(OutputManagerRequest::PrepareToSendTransaction {
+            .request(OutputManagerRequest::PrepareToSendTransaction {
                 tx_id,
                 amount,
                 unique_id,
@@ -486,8 +648,41 @@ impl OutputManagerHandle {
                 message,
                 script,
                 covenant,
+                selection_strategy,
+                recipient_maturity: None,
+            })
+            .await?
+        {
+            OutputManagerResponse::TransactionToSend(stp) => Ok(stp),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Prepares a transaction whose recipient output cannot be spent before `recipient_maturity`
+    pub async fn prepare_time_locked_transaction_to_send(
+        &mut self,
+        tx_id: TxId,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+        script: TariScript,
+        recipient_maturity: u64,
+    ) -> Result<SenderTransactionProtocol, OutputManagerError> {
+        match self
+            .request(OutputManagerRequest::PrepareToSendTransaction {
+                tx_id,
+                amount,
+                unique_id: None,
+                parent_public_key: None,
+                fee_per_gram,
+                lock_height: None,
+                message,
+                script,
+                covenant: Covenant::default(),
+                selection_strategy: None,
+                recipient_maturity: Some(recipient_maturity),
             })
-            .await??
+            .await?
         {
             OutputManagerResponse::TransactionToSend(stp) => Ok(stp),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
@@ -495,34 +690,45 @@ impl OutputManagerHandle {
     }
 
     /// Get a fee estimate for an amount of MicroTari, at a specified fee per gram and given number of kernels and
-    /// outputs.
+    /// outputs. If no fee per gram is given, the base node's estimate for the configured confirmation target is used.
     pub async fn fee_estimate(
         &mut self,
         amount: MicroTari,
-        fee_per_gram: MicroTari,
+        fee_per_gram: Option<MicroTari>,
         num_kernels: usize,
         num_outputs: usize,
     ) -> Result<MicroTari, OutputManagerError> {
         match self
-            .handle
-            .call(OutputManagerRequest::FeeEstimate {
+            .request(OutputManagerRequest::FeeEstimate {
                 amount,
                 fee_per_gram,
                 num_kernels,
                 num_outputs,
             })
-            .await??
+            .await?
         {
             OutputManagerResponse::FeeEstimate(fee) => Ok(fee),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
         }
     }
 
+    /// Get the fee per gram needed for a transaction to be mined within `target_blocks` blocks, as estimated by the
+    /// connected base node's mempool. Uses the configured confirmation target if `target_blocks` is None and falls
+    /// back to the configured default fee per gram if the base node is offline or cannot make an estimate.
+    pub async fn estimate_fee_per_gram(&mut self, target_blocks: Option<u64>) -> Result<MicroTari, OutputManagerError> {
+        match self
+            .request(OutputManagerRequest::EstimateFeePerGram { target_blocks })
+            .await?
+        {
+            OutputManagerResponse::FeePerGram(fee_per_gram) => Ok(fee_per_gram),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn confirm_pending_transaction(&mut self, tx_id: TxId) -> Result<(), OutputManagerError> {
         match self
-            .handle
-            .call(OutputManagerRequest::ConfirmPendingTransaction(tx_id))
-            .await??
+            .request(OutputManagerRequest::ConfirmPendingTransaction(tx_id))
+            .await?
         {
             OutputManagerResponse::PendingTransactionConfirmed => Ok(()),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
@@ -530,18 +736,14 @@ impl OutputManagerHandle {
     }
 
     pub async fn cancel_transaction(&mut self, tx_id: TxId) -> Result<(), OutputManagerError> {
-        match self
-            .handle
-            .call(OutputManagerRequest::CancelTransaction(tx_id))
-            .await??
-        {
+        match self.request(OutputManagerRequest::CancelTransaction(tx_id)).await? {
             OutputManagerResponse::TransactionCancelled => Ok(()),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
         }
     }
 
     pub async fn get_spent_outputs(&mut self) -> Result<Vec<UnblindedOutput>, OutputManagerError> {
-        match self.handle.call(OutputManagerRequest::GetSpentOutputs).await?? {
+        match self.request(OutputManagerRequest::GetSpentOutputs).await? {
             OutputManagerResponse::SpentOutputs(s) => Ok(s),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
         }
@@ -549,35 +751,113 @@ impl OutputManagerHandle {
 
     /// Sorted from lowest value to highest
     pub async fn get_unspent_outputs(&mut self) -> Result<Vec<UnblindedOutput>, OutputManagerError> {
-        match self.handle.call(OutputManagerRequest::GetUnspentOutputs).await?? {
+        match self.request(OutputManagerRequest::GetUnspentOutputs).await? {
             OutputManagerResponse::UnspentOutputs(s) => Ok(s),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
         }
     }
 
     pub async fn get_invalid_outputs(&mut self) -> Result<Vec<UnblindedOutput>, OutputManagerError> {
-        match self.handle.call(OutputManagerRequest::GetInvalidOutputs).await?? {
+        match self.request(OutputManagerRequest::GetInvalidOutputs).await? {
             OutputManagerResponse::InvalidOutputs(s) => Ok(s),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
         }
     }
 
     pub async fn get_seed_words(&mut self) -> Result<Vec<String>, OutputManagerError> {
-        match self.handle.call(OutputManagerRequest::GetSeedWords).await?? {
+        match self.request(OutputManagerRequest::GetSeedWords).await? {
             OutputManagerResponse::SeedWords(s) => Ok(s),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
         }
     }
 
     pub async fn get_rewind_public_keys(&mut self) -> Result<PublicRewindKeys, OutputManagerError> {
-        match self.handle.call(OutputManagerRequest::GetPublicRewindKeys).await?? {
+        match self.request(OutputManagerRequest::GetPublicRewindKeys).await? {
             OutputManagerResponse::PublicRewindKeys(rk) => Ok(*rk),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
         }
     }
 
+    /// Returns the keys required to set up a watch-only copy of this wallet
+    pub async fn get_watch_only_keys(&mut self) -> Result<WatchOnlyKeys, OutputManagerError> {
+        match self.request(OutputManagerRequest::GetWatchOnlyKeys).await? {
+            OutputManagerResponse::WatchOnlyKeys(keys) => Ok(*keys),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Selects inputs for a one-sided payment to `destination` without encumbering them or signing anything, so that
+    /// the transaction can be built and signed by the wallet that holds the spending keys.
+    pub async fn create_unsigned_transaction_template(
+        &mut self,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        lock_height: Option<u64>,
+        message: String,
+        destination: PublicKey,
+        covenant: Covenant,
+    ) -> Result<UnsignedTransactionTemplate, OutputManagerError> {
+        match self
+            .request(OutputManagerRequest::CreateUnsignedTransactionTemplate {
+                amount,
+                fee_per_gram,
+                lock_height,
+                message,
+                destination,
+                covenant,
+            })
+            .await?
+        {
+            OutputManagerResponse::UnsignedTransactionTemplate(template) => Ok(*template),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Builds the sender half of a one-sided payment from a template exported by a watch-only copy of this wallet
+    pub async fn prepare_offline_transaction(
+        &mut self,
+        template: UnsignedTransactionTemplate,
+    ) -> Result<SenderTransactionProtocol, OutputManagerError> {
+        match self
+            .request(OutputManagerRequest::PrepareOfflineTransaction(Box::new(template)))
+            .await?
+        {
+            OutputManagerResponse::TransactionToSend(stp) => Ok(stp),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Encumbers the inputs of a transaction that was signed offline so that they are not selected again, and adds
+    /// its change output as pending incoming
+    pub async fn encumber_offline_transaction(
+        &mut self,
+        tx_id: TxId,
+        input_hashes: Vec<HashOutput>,
+        outputs: Vec<TransactionOutput>,
+    ) -> Result<(), OutputManagerError> {
+        match self
+            .request(OutputManagerRequest::EncumberOfflineTransaction {
+                tx_id,
+                input_hashes,
+                outputs,
+            })
+            .await?
+        {
+            OutputManagerResponse::OfflineTransactionEncumbered => Ok(()),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Derives this wallet's keys for the multisig session with the given id
+    pub async fn get_multisig_keys(&mut self, session_id: u64) -> Result<MultisigKeys, OutputManagerError> {
+        match self.request(OutputManagerRequest::GetMultisigKeys(session_id)).await? {
+            OutputManagerResponse::MultisigKeys(keys) => Ok(*keys),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn validate_txos(&mut self) -> Result<u64, OutputManagerError> {
-        match self.handle.call(OutputManagerRequest::ValidateUtxos).await?? {
+        match self.request(OutputManagerRequest::ValidateUtxos).await? {
             OutputManagerResponse::TxoValidationStarted(request_key) => Ok(request_key),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
         }
@@ -593,29 +873,102 @@ impl OutputManagerHandle {
         lock_height: Option<u64>,
     ) -> Result<(TxId, Transaction, MicroTari), OutputManagerError> {
         match self
-            .handle
-            .call(OutputManagerRequest::CreateCoinSplit((
+            .request(OutputManagerRequest::CreateCoinSplit((
                 amount_per_split,
                 split_count,
                 fee_per_gram,
                 lock_height,
             )))
-            .await??
+            .await?
         {
             OutputManagerResponse::Transaction(ct) => Ok(ct),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
         }
     }
 
+    /// Create a pay-to-self transaction that combines up to `max_inputs` of the smallest spendable outputs, optionally
+    /// only those worth at most `max_value`, into a single output.
+    pub async fn create_consolidation_transaction(
+        &mut self,
+        fee_per_gram: MicroTari,
+        max_inputs: usize,
+        max_value: Option<MicroTari>,
+    ) -> Result<(UtxoConsolidation, Transaction), OutputManagerError> {
+        match self
+            .request(OutputManagerRequest::CreateConsolidationTransaction {
+                fee_per_gram,
+                max_inputs,
+                max_value,
+            })
+            .await?
+        {
+            OutputManagerResponse::ConsolidationTransaction(result) => Ok(*result),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    pub async fn get_consolidation_history(&mut self) -> Result<Vec<UtxoConsolidation>, OutputManagerError> {
+        match self.request(OutputManagerRequest::GetConsolidationHistory).await? {
+            OutputManagerResponse::ConsolidationHistory(history) => Ok(history),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    pub async fn create_account(&mut self, name: String) -> Result<Account, OutputManagerError> {
+        match self.request(OutputManagerRequest::CreateAccount(name)).await? {
+            OutputManagerResponse::Account(account) => Ok(account),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    pub async fn get_accounts(&mut self) -> Result<Vec<Account>, OutputManagerError> {
+        match self.request(OutputManagerRequest::GetAccounts).await? {
+            OutputManagerResponse::Accounts(accounts) => Ok(accounts),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Returns the ids of the transactions that received or spent outputs belonging to this handle's account
+    pub async fn get_account_tx_ids(&mut self) -> Result<Vec<TxId>, OutputManagerError> {
+        match self.request(OutputManagerRequest::GetAccountTxIds).await? {
+            OutputManagerResponse::AccountTxIds(tx_ids) => Ok(tx_ids),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Create a pay-to-self transaction that moves `amount` from this handle's account to `to_account`, returning the
+    /// fee and the transaction
+    pub async fn create_account_transfer(
+        &mut self,
+        tx_id: TxId,
+        to_account: u64,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<(MicroTari, Transaction), OutputManagerError> {
+        match self
+            .request(OutputManagerRequest::CreateAccountTransfer {
+                tx_id,
+                to_account,
+                amount,
+                fee_per_gram,
+                message,
+            })
+            .await?
+        {
+            OutputManagerResponse::PayToSelfTransaction(result) => Ok(result),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn create_htlc_refund_transaction(
         &mut self,
         output: HashOutput,
         fee_per_gram: MicroTari,
     ) -> Result<(TxId, MicroTari, MicroTari, Transaction), OutputManagerError> {
         match self
-            .handle
-            .call(OutputManagerRequest::CreateHtlcRefundTransaction(output, fee_per_gram))
-            .await??
+            .request(OutputManagerRequest::CreateHtlcRefundTransaction(output, fee_per_gram))
+            .await?
         {
             OutputManagerResponse::ClaimHtlcTransaction(ct) => Ok(ct),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
@@ -629,13 +982,12 @@ impl OutputManagerHandle {
         fee_per_gram: MicroTari,
     ) -> Result<(TxId, MicroTari, MicroTari, Transaction), OutputManagerError> {
         match self
-            .handle
-            .call(OutputManagerRequest::CreateClaimShaAtomicSwapTransaction(
+            .request(OutputManagerRequest::CreateClaimShaAtomicSwapTransaction(
                 output,
                 pre_image,
                 fee_per_gram,
             ))
-            .await??
+            .await?
         {
             OutputManagerResponse::ClaimHtlcTransaction(ct) => Ok(ct),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
@@ -644,9 +996,8 @@ impl OutputManagerHandle {
 
     pub async fn apply_encryption(&mut self, cipher: Aes256Gcm) -> Result<(), OutputManagerError> {
         match self
-            .handle
-            .call(OutputManagerRequest::ApplyEncryption(Box::new(cipher)))
-            .await??
+            .request(OutputManagerRequest::ApplyEncryption(Box::new(cipher)))
+            .await?
         {
             OutputManagerResponse::EncryptionApplied => Ok(()),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
@@ -654,7 +1005,7 @@ impl OutputManagerHandle {
     }
 
     pub async fn remove_encryption(&mut self) -> Result<(), OutputManagerError> {
-        match self.handle.call(OutputManagerRequest::RemoveEncryption).await?? {
+        match self.request(OutputManagerRequest::RemoveEncryption).await? {
             OutputManagerResponse::EncryptionRemoved => Ok(()),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
         }
@@ -666,9 +1017,8 @@ impl OutputManagerHandle {
         tx_id: TxId,
     ) -> Result<Vec<UnblindedOutput>, OutputManagerError> {
         match self
-            .handle
-            .call(OutputManagerRequest::ScanForRecoverableOutputs { outputs, tx_id })
-            .await??
+            .request(OutputManagerRequest::ScanForRecoverableOutputs { outputs, tx_id })
+            .await?
         {
             OutputManagerResponse::RewoundOutputs(outputs) => Ok(outputs),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
@@ -681,9 +1031,8 @@ impl OutputManagerHandle {
         tx_id: TxId,
     ) -> Result<Vec<UnblindedOutput>, OutputManagerError> {
         match self
-            .handle
-            .call(OutputManagerRequest::ScanOutputs { outputs, tx_id })
-            .await??
+            .request(OutputManagerRequest::ScanOutputs { outputs, tx_id })
+            .await?
         {
             OutputManagerResponse::ScanOutputs(outputs) => Ok(outputs),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
@@ -692,9 +1041,8 @@ impl OutputManagerHandle {
 
     pub async fn add_known_script(&mut self, script: KnownOneSidedPaymentScript) -> Result<(), OutputManagerError> {
         match self
-            .handle
-            .call(OutputManagerRequest::AddKnownOneSidedPaymentScript(script))
-            .await??
+            .request(OutputManagerRequest::AddKnownOneSidedPaymentScript(script))
+            .await?
         {
             OutputManagerResponse::AddKnownOneSidedPaymentScript => Ok(()),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
@@ -709,14 +1057,13 @@ impl OutputManagerHandle {
         spending_parent_public_key: Option<PublicKey>,
     ) -> Result<(TxId, Transaction), OutputManagerError> {
         match self
-            .handle
-            .call(OutputManagerRequest::CreatePayToSelfWithOutputs {
+            .request(OutputManagerRequest::CreatePayToSelfWithOutputs {
                 outputs,
                 fee_per_gram,
                 spending_unique_id,
                 spending_parent_public_key,
             })
-            .await??
+            .await?
         {
             OutputManagerResponse::CreatePayToSelfWithOutputs { transaction, tx_id } => Ok((tx_id, *transaction)),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
@@ -732,10 +1079,10 @@ impl OutputManagerHandle {
         fee_per_gram: MicroTari,
         lock_height: Option<u64>,
         message: String,
+        selection_strategy: Option<UTXOSelectionStrategy>,
     ) -> Result<(MicroTari, Transaction), OutputManagerError> {
         match self
-            .handle
-            .call(OutputManagerRequest::CreatePayToSelfTransaction {
+            .request(OutputManagerRequest::CreatePayToSelfTransaction {
                 tx_id,
                 amount,
                 fee_per_gram,
@@ -743,22 +1090,45 @@ impl OutputManagerHandle {
                 message,
                 unique_id,
                 parent_public_key,
+                selection_strategy,
             })
-            .await??
+            .await?
         {
             OutputManagerResponse::PayToSelfTransaction(outputs) => Ok(outputs),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
         }
     }
 
+    /// Create a finalised transaction paying a one-sided output to each recipient, returning the fee and the
+    /// transaction
+    pub async fn create_one_sided_batch_transaction(
+        &mut self,
+        tx_id: TxId,
+        recipients: Vec<(PublicKey, MicroTari)>,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<(MicroTari, Transaction), OutputManagerError> {
+        match self
+            .request(OutputManagerRequest::CreateOneSidedBatchTransaction {
+                tx_id,
+                recipients,
+                fee_per_gram,
+                message,
+            })
+            .await?
+        {
+            OutputManagerResponse::OneSidedBatchTransaction(result) => Ok(result),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn reinstate_cancelled_inbound_transaction_outputs(
         &mut self,
         tx_id: TxId,
     ) -> Result<(), OutputManagerError> {
         match self
-            .handle
-            .call(OutputManagerRequest::ReinstateCancelledInboundTx(tx_id))
-            .await??
+            .request(OutputManagerRequest::ReinstateCancelledInboundTx(tx_id))
+            .await?
         {
             OutputManagerResponse::ReinstatedCancelledInboundTx => Ok(()),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
@@ -767,9 +1137,8 @@ impl OutputManagerHandle {
 
     pub async fn set_coinbase_abandoned(&mut self, tx_id: TxId, abandoned: bool) -> Result<(), OutputManagerError> {
         match self
-            .handle
-            .call(OutputManagerRequest::SetCoinbaseAbandoned(tx_id, abandoned))
-            .await??
+            .request(OutputManagerRequest::SetCoinbaseAbandoned(tx_id, abandoned))
+            .await?
         {
             OutputManagerResponse::CoinbaseAbandonedSet => Ok(()),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
@@ -781,9 +1150,8 @@ impl OutputManagerHandle {
         tx_id: TxId,
     ) -> Result<(Vec<OutputStatus>, Option<u64>, Option<BlockHash>), OutputManagerError> {
         match self
-            .handle
-            .call(OutputManagerRequest::GetOutputStatusesByTxId(tx_id))
-            .await??
+            .request(OutputManagerRequest::GetOutputStatusesByTxId(tx_id))
+            .await?
         {
             OutputManagerResponse::OutputStatusesByTxId {
                 statuses,
@@ -36,8 +36,12 @@ use tari_key_manager::{
 use crate::{
     output_manager_service::{
         error::OutputManagerError,
-        handle::PublicRewindKeys,
-        storage::database::{KeyManagerState, OutputManagerBackend, OutputManagerDatabase},
+        handle::{MultisigKeys, PublicRewindKeys},
+        storage::{
+            database::{KeyManagerState, OutputManagerBackend, OutputManagerDatabase},
+            models::DEFAULT_ACCOUNT_ID,
+        },
+        watch_only::{WalletKeySource, WatchOnlyKeys},
     },
     types::KeyDigest,
 };
@@ -53,27 +57,45 @@ pub enum KeyManagerBranch {
     CoinbaseScript,
     RecoveryViewOnly,
     RecoveryBlinding,
+    Multisig,
+    MultisigPolynomial,
+    MultisigSigning,
+    Account(u64),
+    AccountScript(u64),
 }
 
 impl Display for KeyManagerBranch {
     fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
-        let response = match self {
-            KeyManagerBranch::Spend => "",
-            KeyManagerBranch::SpendScript => "script",
-            KeyManagerBranch::Coinbase => "coinbase",
-            KeyManagerBranch::CoinbaseScript => "coinbase_script",
-            KeyManagerBranch::RecoveryViewOnly => "recovery_viewonly",
-            KeyManagerBranch::RecoveryBlinding => "recovery_blinding",
-        };
-        fmt.write_str(response)
+        match self {
+            KeyManagerBranch::Spend => fmt.write_str(""),
+            KeyManagerBranch::SpendScript => fmt.write_str("script"),
+            KeyManagerBranch::Coinbase => fmt.write_str("coinbase"),
+            KeyManagerBranch::CoinbaseScript => fmt.write_str("coinbase_script"),
+            KeyManagerBranch::RecoveryViewOnly => fmt.write_str("recovery_viewonly"),
+            KeyManagerBranch::RecoveryBlinding => fmt.write_str("recovery_blinding"),
+            KeyManagerBranch::Multisig => fmt.write_str("multisig"),
+            KeyManagerBranch::MultisigPolynomial => fmt.write_str("multisig_polynomial"),
+            KeyManagerBranch::MultisigSigning => fmt.write_str("multisig_signing"),
+            KeyManagerBranch::Account(id) => write!(fmt, "account_{}", id),
+            KeyManagerBranch::AccountScript(id) => write!(fmt, "account_{}_script", id),
+        }
     }
 }
 
-pub(crate) struct MasterKeyManager<TBackend> {
+struct SpendingKeyManagers {
     utxo_key_manager: Mutex<KeyManager<PrivateKey, KeyDigest>>,
     utxo_script_key_manager: Mutex<KeyManager<PrivateKey, KeyDigest>>,
     coinbase_key_manager: Mutex<KeyManager<PrivateKey, KeyDigest>>,
     coinbase_script_key_manager: Mutex<KeyManager<PrivateKey, KeyDigest>>,
+    multisig_key_manager: KeyManager<PrivateKey, KeyDigest>,
+    multisig_polynomial_key_manager: KeyManager<PrivateKey, KeyDigest>,
+    multisig_signing_key_manager: KeyManager<PrivateKey, KeyDigest>,
+}
+
+pub(crate) struct MasterKeyManager<TBackend> {
+    /// The key managers used to derive spending and script keys. These are not available to a watch-only wallet.
+    spending_key_managers: Option<SpendingKeyManagers>,
+    watch_only_keys: Option<WatchOnlyKeys>,
     rewind_data: RewindData,
     db: OutputManagerDatabase<TBackend>,
 }
@@ -81,7 +103,20 @@ pub(crate) struct MasterKeyManager<TBackend> {
 impl<TBackend> MasterKeyManager<TBackend>
 where TBackend: OutputManagerBackend + 'static
 {
-    pub async fn new(master_seed: CipherSeed, db: OutputManagerDatabase<TBackend>) -> Result<Self, OutputManagerError> {
+    pub async fn new(
+        key_source: WalletKeySource,
+        db: OutputManagerDatabase<TBackend>,
+    ) -> Result<Self, OutputManagerError> {
+        match key_source {
+            WalletKeySource::Seed(master_seed) => Self::from_master_seed(master_seed, db).await,
+            WalletKeySource::WatchOnly(keys) => Self::from_watch_only_keys(*keys, db).await,
+        }
+    }
+
+    async fn from_master_seed(
+        master_seed: CipherSeed,
+        db: OutputManagerDatabase<TBackend>,
+    ) -> Result<Self, OutputManagerError> {
         // Check to see if there is any persisted state. If there is confirm that the provided master secret key matches
         let key_manager_state = match db.get_key_manager_state().await? {
             None => {
@@ -125,6 +160,23 @@ where TBackend: OutputManagerBackend + 'static
             0,
         );
 
+        // Multisig keys are only ever derived at the index of their session, so these key managers are not stateful
+        let multisig_key_manager = KeyManager::<PrivateKey, KeyDigest>::from(
+            key_manager_state.seed.clone(),
+            KeyManagerBranch::Multisig.to_string(),
+            0,
+        );
+        let multisig_polynomial_key_manager = KeyManager::<PrivateKey, KeyDigest>::from(
+            key_manager_state.seed.clone(),
+            KeyManagerBranch::MultisigPolynomial.to_string(),
+            0,
+        );
+        let multisig_signing_key_manager = KeyManager::<PrivateKey, KeyDigest>::from(
+            key_manager_state.seed.clone(),
+            KeyManagerBranch::MultisigSigning.to_string(),
+            0,
+        );
+
         let rewind_key_manager = KeyManager::<PrivateKey, KeyDigest>::from(
             key_manager_state.seed.clone(),
             KeyManagerBranch::RecoveryViewOnly.to_string(),
@@ -146,40 +198,103 @@ where TBackend: OutputManagerBackend + 'static
         };
 
         Ok(Self {
-            utxo_key_manager: Mutex::new(utxo_key_manager),
-            utxo_script_key_manager: Mutex::new(utxo_script_key_manager),
-            coinbase_key_manager: Mutex::new(coinbase_key_manager),
-            coinbase_script_key_manager: Mutex::new(coinbase_script_key_manager),
+            spending_key_managers: Some(SpendingKeyManagers {
+                utxo_key_manager: Mutex::new(utxo_key_manager),
+                utxo_script_key_manager: Mutex::new(utxo_script_key_manager),
+                coinbase_key_manager: Mutex::new(coinbase_key_manager),
+                coinbase_script_key_manager: Mutex::new(coinbase_script_key_manager),
+                multisig_key_manager,
+                multisig_polynomial_key_manager,
+                multisig_signing_key_manager,
+            }),
+            watch_only_keys: None,
             rewind_data,
             db,
         })
     }
 
+    async fn from_watch_only_keys(
+        keys: WatchOnlyKeys,
+        db: OutputManagerDatabase<TBackend>,
+    ) -> Result<Self, OutputManagerError> {
+        // A database that has been used by a full wallet must not be reused to watch a different set of keys
+        if db.get_key_manager_state().await?.is_some() {
+            return Err(OutputManagerError::MasterSeedMismatch);
+        }
+
+        Ok(Self {
+            spending_key_managers: None,
+            rewind_data: keys.rewind_data(),
+            watch_only_keys: Some(keys),
+            db,
+        })
+    }
+
+    /// Returns true if this key manager was created from watch-only keys and is unable to derive spending keys
+    pub fn is_watch_only(&self) -> bool {
+        self.spending_key_managers.is_none()
+    }
+
+    fn spending_key_managers(&self) -> Result<&SpendingKeyManagers, OutputManagerError> {
+        self.spending_key_managers
+            .as_ref()
+            .ok_or(OutputManagerError::WatchOnlyWallet)
+    }
+
     pub fn rewind_data(&self) -> &RewindData {
         &self.rewind_data
     }
 
     /// Return the next pair of (spending_key, script_private_key) from the key managers. These will always be generated
-    /// in tandem and at corresponding increments
-    pub async fn get_next_spend_and_script_key(&self) -> Result<(PrivateKey, PrivateKey), OutputManagerError> {
-        let mut km = self.utxo_key_manager.lock().await;
+    /// in tandem and at corresponding increments. Accounts other than the default account derive their keys from their
+    /// own branches of the master seed.
+    pub async fn get_next_spend_and_script_key(
+        &self,
+        account_id: u64,
+    ) -> Result<(PrivateKey, PrivateKey), OutputManagerError> {
+        if account_id != DEFAULT_ACCOUNT_ID {
+            return self.get_next_account_spend_and_script_key(account_id).await;
+        }
+        let key_managers = self.spending_key_managers()?;
+        let mut km = key_managers.utxo_key_manager.lock().await;
         let key = km.next_key()?;
 
-        let mut skm = self.utxo_script_key_manager.lock().await;
+        let mut skm = key_managers.utxo_script_key_manager.lock().await;
         let script_key = skm.next_key()?;
 
         self.db.increment_key_index().await?;
         Ok((key.k, script_key.k))
     }
 
+    async fn get_next_account_spend_and_script_key(
+        &self,
+        account_id: u64,
+    ) -> Result<(PrivateKey, PrivateKey), OutputManagerError> {
+        // The UTXO key manager lock serialises index increments across accounts
+        let km = self.spending_key_managers()?.utxo_key_manager.lock().await;
+        let index = self.db.increment_account_key_index(account_id).await?;
+
+        let spend_km = KeyManager::<PrivateKey, KeyDigest>::from(
+            km.cipher_seed().clone(),
+            KeyManagerBranch::Account(account_id).to_string(),
+            0,
+        );
+        let script_km = KeyManager::<PrivateKey, KeyDigest>::from(
+            km.cipher_seed().clone(),
+            KeyManagerBranch::AccountScript(account_id).to_string(),
+            0,
+        );
+        Ok((spend_km.derive_key(index)?.k, script_km.derive_key(index)?.k))
+    }
+
     pub async fn get_script_key_at_index(&self, index: u64) -> Result<PrivateKey, OutputManagerError> {
-        let skm = self.utxo_script_key_manager.lock().await;
+        let skm = self.spending_key_managers()?.utxo_script_key_manager.lock().await;
         let script_key = skm.derive_key(index)?;
         Ok(script_key.k)
     }
 
     pub async fn get_coinbase_script_key_at_index(&self, index: u64) -> Result<PrivateKey, OutputManagerError> {
-        let skm = self.coinbase_script_key_manager.lock().await;
+        let skm = self.spending_key_managers()?.coinbase_script_key_manager.lock().await;
         let script_key = skm.derive_key(index)?;
         Ok(script_key.k)
     }
@@ -188,17 +303,28 @@ where TBackend: OutputManagerBackend + 'static
         &self,
         height: u64,
     ) -> Result<(PrivateKey, PrivateKey), OutputManagerError> {
-        let km = self.coinbase_key_manager.lock().await;
+        let key_managers = self.spending_key_managers()?;
+        let km = key_managers.coinbase_key_manager.lock().await;
         let spending_key = km.derive_key(height)?;
 
-        let mut skm = self.coinbase_script_key_manager.lock().await;
+        let mut skm = key_managers.coinbase_script_key_manager.lock().await;
         let script_key = skm.next_key()?;
         Ok((spending_key.k, script_key.k))
     }
 
+    /// Return this wallet's spending, polynomial and signing keys for the given multisig session
+    pub fn get_multisig_keys_for_session(&self, session_id: u64) -> Result<MultisigKeys, OutputManagerError> {
+        let key_managers = self.spending_key_managers()?;
+        Ok(MultisigKeys {
+            spending_key: key_managers.multisig_key_manager.derive_key(session_id)?.k,
+            polynomial_key: key_managers.multisig_polynomial_key_manager.derive_key(session_id)?.k,
+            signing_key: key_managers.multisig_signing_key_manager.derive_key(session_id)?.k,
+        })
+    }
+
     /// Return the Seed words for the current Master Key set in the Key Manager
     pub async fn get_seed_words(&self, language: &MnemonicLanguage) -> Result<Vec<String>, OutputManagerError> {
-        let km = self.utxo_key_manager.lock().await;
+        let km = self.spending_key_managers()?.utxo_key_manager.lock().await;
         let seed_words = (*km).cipher_seed().to_mnemonic(language, None)?;
         Ok(seed_words)
     }
@@ -211,11 +337,24 @@ where TBackend: OutputManagerBackend + 'static
         }
     }
 
+    /// Return the keys needed to watch this wallet without being able to spend from it. A full wallet receives
+    /// one-sided payments to its node identity, so the provided node public key is used as the spend public key.
+    pub fn get_watch_only_keys(&self, node_public_key: &PublicKey) -> WatchOnlyKeys {
+        match self.watch_only_keys {
+            Some(ref keys) => keys.clone(),
+            None => WatchOnlyKeys::new(
+                self.rewind_data.rewind_key.clone(),
+                self.rewind_data.rewind_blinding_key.clone(),
+                Some(node_public_key.clone()),
+            ),
+        }
+    }
+
     /// Search the specified branch key manager key chain to find the index of the specified key.
     pub async fn find_key_index(&self, key: PrivateKey, branch: KeyManagerBranch) -> Result<u64, OutputManagerError> {
         let key_manager = match branch {
-            KeyManagerBranch::Spend => self.utxo_key_manager.lock().await,
-            KeyManagerBranch::Coinbase => self.coinbase_key_manager.lock().await,
+            KeyManagerBranch::Spend => self.spending_key_managers()?.utxo_key_manager.lock().await,
+            KeyManagerBranch::Coinbase => self.spending_key_managers()?.coinbase_key_manager.lock().await,
             _ => return Err(OutputManagerError::KeyManagerBranchNotSupported),
         };
 
@@ -231,10 +370,70 @@ where TBackend: OutputManagerBackend + 'static
         Err(OutputManagerError::KeyNotFoundInKeyChain)
     }
 
+    /// Search the spend key chain of the default account and the key chains of the given accounts for the specified
+    /// key. The chains are searched in step so that a key near the start of any chain is found quickly. Returns the id
+    /// of the account and the index the key was found at.
+    pub async fn find_spend_key_index(
+        &self,
+        key: PrivateKey,
+        account_ids: &[u64],
+    ) -> Result<(u64, u64), OutputManagerError> {
+        let key_manager = self.spending_key_managers()?.utxo_key_manager.lock().await;
+        let account_key_managers = account_ids
+            .iter()
+            .filter(|id| **id != DEFAULT_ACCOUNT_ID)
+            .map(|id| {
+                let km = KeyManager::<PrivateKey, KeyDigest>::from(
+                    key_manager.cipher_seed().clone(),
+                    KeyManagerBranch::Account(*id).to_string(),
+                    0,
+                );
+                (*id, km)
+            })
+            .collect::<Vec<_>>();
+
+        let current_index = (*key_manager).key_index();
+
+        for i in 0u64..current_index + KEY_MANAGER_MAX_SEARCH_DEPTH {
+            if (*key_manager).derive_key(i)?.k == key {
+                trace!(target: LOG_TARGET, "Key found in Spend Key Chain at index {}", i);
+                return Ok((DEFAULT_ACCOUNT_ID, i));
+            }
+            for (account_id, km) in &account_key_managers {
+                if km.derive_key(i)?.k == key {
+                    trace!(
+                        target: LOG_TARGET,
+                        "Key found in {} Key Chain at index {}",
+                        KeyManagerBranch::Account(*account_id),
+                        i
+                    );
+                    return Ok((*account_id, i));
+                }
+            }
+        }
+
+        Err(OutputManagerError::KeyNotFoundInKeyChain)
+    }
+
+    pub async fn get_account_script_key_at_index(
+        &self,
+        account_id: u64,
+        index: u64,
+    ) -> Result<PrivateKey, OutputManagerError> {
+        let km = self.spending_key_managers()?.utxo_key_manager.lock().await;
+        let script_km = KeyManager::<PrivateKey, KeyDigest>::from(
+            km.cipher_seed().clone(),
+            KeyManagerBranch::AccountScript(account_id).to_string(),
+            0,
+        );
+        Ok(script_km.derive_key(index)?.k)
+    }
+
     /// If the supplied index is higher than the current UTXO key chain indices then they will be updated.
     pub async fn update_current_spend_key_index_if_higher(&self, index: u64) -> Result<(), OutputManagerError> {
-        let mut utxo_key_manager = self.utxo_key_manager.lock().await;
-        let mut utxo_script_key_manager = self.utxo_script_key_manager.lock().await;
+        let key_managers = self.spending_key_managers()?;
+        let mut utxo_key_manager = key_managers.utxo_key_manager.lock().await;
+        let mut utxo_script_key_manager = key_managers.utxo_script_key_manager.lock().await;
         let current_index = (*utxo_key_manager).key_index();
         if index > current_index {
             (*utxo_key_manager).update_key_index(index);
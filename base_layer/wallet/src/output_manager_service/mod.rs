@@ -27,7 +27,6 @@ use log::*;
 pub(crate) use master_key_manager::MasterKeyManager;
 use tari_comms::NodeIdentity;
 use tari_core::{consensus::NetworkConsensus, transactions::CryptoFactories};
-use tari_key_manager::cipher_seed::CipherSeed;
 use tari_service_framework::{
     async_trait,
     reply_channel,
@@ -45,9 +44,13 @@ use crate::{
         handle::OutputManagerHandle,
         service::OutputManagerService,
         storage::database::{OutputManagerBackend, OutputManagerDatabase},
+        tasks::UtxoConsolidationTask,
+        watch_only::WalletKeySource,
     },
+    transaction_service::handle::TransactionServiceHandle,
 };
 
+mod coin_selection;
 pub mod config;
 pub mod error;
 pub mod handle;
@@ -57,6 +60,7 @@ pub mod resources;
 pub mod service;
 pub mod storage;
 mod tasks;
+pub mod watch_only;
 
 const LOG_TARGET: &str = "wallet::output_manager_service::initializer";
 
@@ -67,7 +71,7 @@ where T: OutputManagerBackend
     backend: Option<T>,
     factories: CryptoFactories,
     network: NetworkConsensus,
-    master_seed: CipherSeed,
+    key_source: WalletKeySource,
     node_identity: Arc<NodeIdentity>,
 }
 
@@ -79,7 +83,7 @@ where T: OutputManagerBackend + 'static
         backend: T,
         factories: CryptoFactories,
         network: NetworkConsensus,
-        master_seed: CipherSeed,
+        key_source: WalletKeySource,
         node_identity: Arc<NodeIdentity>,
     ) -> Self {
         Self {
@@ -87,7 +91,7 @@ where T: OutputManagerBackend + 'static
             backend: Some(backend),
             factories,
             network,
-            master_seed,
+            key_source,
             node_identity,
         }
     }
@@ -109,7 +113,36 @@ where T: OutputManagerBackend + 'static
 
         // Register handle before waiting for handles to be ready
         let oms_handle = OutputManagerHandle::new(sender, publisher.clone());
-        context.register_handle(oms_handle);
+        context.register_handle(oms_handle.clone());
+
+        // A watch-only wallet cannot sign consolidation transactions
+        if let Some(policy) = self.config.consolidation_policy.clone() {
+            if self.key_source.is_watch_only() {
+                warn!(
+                    target: LOG_TARGET,
+                    "UTXO consolidation is not available for a watch-only wallet"
+                );
+            } else {
+                context.clone().spawn_when_ready(move |handles| async move {
+                    match handles.get_handle::<TransactionServiceHandle>() {
+                        Some(transaction_service) => {
+                            UtxoConsolidationTask::new(
+                                policy,
+                                oms_handle,
+                                transaction_service,
+                                handles.get_shutdown_signal(),
+                            )
+                            .run()
+                            .await
+                        },
+                        None => warn!(
+                            target: LOG_TARGET,
+                            "UTXO consolidation is disabled because the transaction service is not running"
+                        ),
+                    }
+                });
+            }
+        }
 
         let backend = self
             .backend
@@ -118,7 +151,7 @@ where T: OutputManagerBackend + 'static
         let factories = self.factories.clone();
         let config = self.config.clone();
         let constants = self.network.create_consensus_constants().pop().unwrap();
-        let master_seed = self.master_seed.clone();
+        let key_source = self.key_source.clone();
         let node_identity = self.node_identity.clone();
         context.spawn_when_ready(move |handles| async move {
             let base_node_service_handle = handles.expect_handle::<BaseNodeServiceHandle>();
@@ -134,7 +167,7 @@ where T: OutputManagerBackend + 'static
                 handles.get_shutdown_signal(),
                 base_node_service_handle,
                 connectivity,
-                master_seed,
+                key_source,
                 node_identity,
             )
             .await
@@ -44,13 +44,17 @@ use crate::output_manager_service::{
     master_key_manager::KeyManagerBranch,
     storage::{
         database::{OutputManagerBackend, OutputManagerDatabase},
-        models::DbUnblindedOutput,
+        models::{DbUnblindedOutput, DEFAULT_ACCOUNT_ID},
     },
     MasterKeyManager,
 };
 
 const LOG_TARGET: &str = "wallet::output_manager_service::recovery";
 
+/// Account ids are assigned in the order that accounts are created, so this many ids after the highest known account
+/// are also searched when recovering outputs. This allows accounts to be rediscovered when restoring from a seed.
+const ACCOUNT_RECOVERY_GAP_LIMIT: u64 = 10;
+
 pub(crate) struct StandardUtxoRecoverer<TBackend: OutputManagerBackend + 'static> {
     master_key_manager: Arc<MasterKeyManager<TBackend>>,
     factories: CryptoFactories,
@@ -125,8 +129,13 @@ where TBackend: OutputManagerBackend + 'static
         );
 
         for (output, proof) in rewound_outputs.iter_mut() {
-            self.update_outputs_script_private_key_and_update_key_manager_index(output)
-                .await?;
+            // A watch-only wallet cannot derive the script key, so the output is tracked with the placeholder key
+            let account_id = if self.master_key_manager.is_watch_only() {
+                DEFAULT_ACCOUNT_ID
+            } else {
+                self.update_outputs_script_private_key_and_update_key_manager_index(output)
+                    .await?
+            };
 
             let db_output = DbUnblindedOutput::rewindable_from_unblinded_output(
                 output.clone(),
@@ -136,7 +145,12 @@ where TBackend: OutputManagerBackend + 'static
                 Some(proof),
             )?;
             let output_hex = db_output.commitment.to_hex();
-            if let Err(e) = self.db.add_unspent_output_with_tx_id(tx_id, db_output).await {
+            if let Err(e) = self
+                .db
+                .for_account(account_id)
+                .add_unspent_output_with_tx_id(tx_id, db_output)
+                .await
+            {
                 match e {
                     OutputManagerStorageError::DuplicateOutput => {
                         info!(
@@ -163,11 +177,12 @@ where TBackend: OutputManagerBackend + 'static
 
     /// Find the key manager index that corresponds to the spending key in the rewound output, if found then modify
     /// output to contain correct associated script private key and update the key manager to the highest index it has
-    /// seen so far.
-    async fn update_outputs_script_private_key_and_update_key_manager_index(
+    /// seen so far. Returns the id of the account the output belongs to.
+    pub async fn update_outputs_script_private_key_and_update_key_manager_index(
         &mut self,
         output: &mut UnblindedOutput,
-    ) -> Result<(), OutputManagerError> {
+    ) -> Result<u64, OutputManagerError> {
+        let mut account_id = DEFAULT_ACCOUNT_ID;
         let script_key = if output.features.is_coinbase() {
             let found_index = self
                 .master_key_manager
@@ -178,20 +193,45 @@ where TBackend: OutputManagerBackend + 'static
                 .get_coinbase_script_key_at_index(found_index)
                 .await?
         } else {
-            let found_index = self
+            let account_ids = self.recovery_account_ids().await?;
+            let (found_account_id, found_index) = self
                 .master_key_manager
-                .find_key_index(output.spending_key.clone(), KeyManagerBranch::Spend)
+                .find_spend_key_index(output.spending_key.clone(), &account_ids)
                 .await?;
+            account_id = found_account_id;
 
-            self.master_key_manager
-                .update_current_spend_key_index_if_higher(found_index)
-                .await?;
+            if account_id == DEFAULT_ACCOUNT_ID {
+                self.master_key_manager
+                    .update_current_spend_key_index_if_higher(found_index)
+                    .await?;
 
-            self.master_key_manager.get_script_key_at_index(found_index).await?
+                self.master_key_manager.get_script_key_at_index(found_index).await?
+            } else {
+                self.db.restore_account(account_id, found_index).await?;
+
+                self.master_key_manager
+                    .get_account_script_key_at_index(account_id, found_index)
+                    .await?
+            }
         };
 
         output.input_data = inputs!(PublicKey::from_secret_key(&script_key));
         output.script_private_key = script_key;
-        Ok(())
+        Ok(account_id)
+    }
+
+    /// The ids of the accounts whose key chains are searched for a recovered output: every known account, and the ids
+    /// that follow the highest known account in case those accounts have not been recovered yet.
+    async fn recovery_account_ids(&self) -> Result<Vec<u64>, OutputManagerError> {
+        let mut account_ids = self
+            .db
+            .fetch_accounts()
+            .await?
+            .into_iter()
+            .map(|account| account.id)
+            .collect::<Vec<_>>();
+        let highest_id = account_ids.iter().copied().max().unwrap_or(DEFAULT_ACCOUNT_ID);
+        account_ids.extend(highest_id + 1..=highest_id + ACCOUNT_RECOVERY_GAP_LIMIT);
+        Ok(account_ids)
     }
 }
@@ -20,16 +20,17 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{convert::TryInto, fmt, fmt::Display, sync::Arc};
+use std::{collections::HashMap, convert::TryInto, fmt, fmt::Display, str::FromStr, sync::Arc};
 
 use blake2::Digest;
+use chrono::Utc;
 use diesel::result::{DatabaseErrorKind, Error as DieselError};
 use futures::{pin_mut, StreamExt};
 use log::*;
 use rand::{rngs::OsRng, RngCore};
 use tari_common_types::{
     transaction::TxId,
-    types::{BlockHash, HashOutput, PrivateKey, PublicKey},
+    types::{BlockHash, Commitment, HashOutput, PrivateKey, PublicKey},
 };
 use tari_comms::{types::CommsPublicKey, NodeIdentity};
 use tari_core::{
@@ -58,17 +59,17 @@ use tari_crypto::{
     inputs,
     keys::{DiffieHellmanSharedSecret, PublicKey as PublicKeyTrait, SecretKey},
     script,
-    script::TariScript,
+    script::{ExecutionStack, TariScript},
     tari_utilities::{hex::Hex, ByteArray},
 };
-use tari_key_manager::cipher_seed::CipherSeed;
 use tari_service_framework::reply_channel;
 use tari_shutdown::ShutdownSignal;
 
 use crate::{
     base_node_service::handle::{BaseNodeEvent, BaseNodeServiceHandle},
-    connectivity_service::WalletConnectivityInterface,
+    connectivity_service::{OnlineStatus, WalletConnectivityInterface},
     output_manager_service::{
+        coin_selection,
         config::OutputManagerServiceConfig,
         error::{OutputManagerError, OutputManagerProtocolError, OutputManagerStorageError},
         handle::{OutputManagerEvent, OutputManagerEventSender, OutputManagerRequest, OutputManagerResponse},
@@ -76,10 +77,11 @@ use crate::{
         resources::OutputManagerResources,
         storage::{
             database::{OutputManagerBackend, OutputManagerDatabase},
-            models::{DbUnblindedOutput, KnownOneSidedPaymentScript, SpendingPriority},
+            models::{Account, DbUnblindedOutput, KnownOneSidedPaymentScript, SpendingPriority, UtxoConsolidation},
             OutputStatus,
         },
         tasks::TxoValidationTask,
+        watch_only::{UnsignedTransactionTemplate, WalletKeySource},
         MasterKeyManager,
     },
     types::HashDigest,
@@ -118,14 +120,14 @@ where
         shutdown_signal: ShutdownSignal,
         base_node_service: BaseNodeServiceHandle,
         connectivity: TWalletConnectivity,
-        master_seed: CipherSeed,
+        key_source: WalletKeySource,
         node_identity: Arc<NodeIdentity>,
     ) -> Result<Self, OutputManagerError> {
         // Clear any encumberances for transactions that were being negotiated but did not complete to become official
         // Pending Transactions.
         db.clear_short_term_encumberances().await?;
 
-        let master_key_manager = MasterKeyManager::new(master_seed, db.clone()).await?;
+        let master_key_manager = MasterKeyManager::new(key_source, db.clone()).await?;
 
         let resources = OutputManagerResources {
             config,
@@ -171,7 +173,13 @@ where
                 Some(request_context) = request_stream.next() => {
                 trace!(target: LOG_TARGET, "Handling Service API Request");
                     let (request, reply_tx) = request_context.split();
-                    let response = self.handle_request(request).await.map_err(|e| {
+                    let response = match request {
+                        OutputManagerRequest::ForAccount(account_id, request) => {
+                            self.handle_account_request(account_id, *request).await
+                        },
+                        request => self.handle_request(request).await,
+                    };
+                    let response = response.map_err(|e| {
                         warn!(target: LOG_TARGET, "Error handling request: {:?}", e);
                         e
                     });
@@ -190,12 +198,33 @@ where
         Ok(())
     }
 
+    /// Handles a request on behalf of the given account by scoping the database to that account for the duration of
+    /// the request
+    async fn handle_account_request(
+        &mut self,
+        account_id: u64,
+        request: OutputManagerRequest,
+    ) -> Result<OutputManagerResponse, OutputManagerError> {
+        let accounts = self.resources.db.fetch_accounts().await?;
+        if !accounts.iter().any(|a| a.id == account_id) {
+            return Err(OutputManagerError::AccountNotFound(account_id));
+        }
+        let db = self.resources.db.clone();
+        self.resources.db = db.for_account(account_id);
+        let response = self.handle_request(request).await;
+        self.resources.db = db;
+        response
+    }
+
     /// This handler is called when the Service executor loops receives an API request
     async fn handle_request(
         &mut self,
         request: OutputManagerRequest,
     ) -> Result<OutputManagerResponse, OutputManagerError> {
         trace!(target: LOG_TARGET, "Handling Service Request: {}", request);
+        if self.resources.master_key_manager.is_watch_only() && request.requires_spending_keys() {
+            return Err(OutputManagerError::WatchOnlyWallet);
+        }
         match request {
             OutputManagerRequest::AddOutput((uo, spend_priority)) => self
                 .add_output(None, *uo, spend_priority)
@@ -244,6 +273,8 @@ where
                 message,
                 script,
                 covenant,
+                selection_strategy,
+                recipient_maturity,
             } => self
                 .prepare_transaction_to_send(
                     tx_id,
@@ -255,6 +286,8 @@ where
                     message,
                     script,
                     covenant,
+                    selection_strategy,
+                    recipient_maturity,
                 )
                 .await
                 .map(OutputManagerResponse::TransactionToSend),
@@ -266,6 +299,7 @@ where
                 fee_per_gram,
                 lock_height,
                 message,
+                selection_strategy,
             } => self
                 .create_pay_to_self_transaction(
                     tx_id,
@@ -275,18 +309,66 @@ where
                     fee_per_gram,
                     lock_height,
                     message,
+                    selection_strategy,
+                    self.resources.db.account_id(),
                 )
                 .await
                 .map(OutputManagerResponse::PayToSelfTransaction),
+            OutputManagerRequest::CreateAccountTransfer {
+                tx_id,
+                to_account,
+                amount,
+                fee_per_gram,
+                message,
+            } => self
+                .create_account_transfer(tx_id, to_account, amount, fee_per_gram, message)
+                .await
+                .map(OutputManagerResponse::PayToSelfTransaction),
+            OutputManagerRequest::CreateAccount(name) => {
+                self.create_account(name).await.map(OutputManagerResponse::Account)
+            },
+            OutputManagerRequest::GetAccounts => self
+                .resources
+                .db
+                .fetch_accounts()
+                .await
+                .map(OutputManagerResponse::Accounts)
+                .map_err(OutputManagerError::OutputManagerStorageError),
+            OutputManagerRequest::GetAccountTxIds => self
+                .resources
+                .db
+                .fetch_account_tx_ids()
+                .await
+                .map(OutputManagerResponse::AccountTxIds)
+                .map_err(OutputManagerError::OutputManagerStorageError),
+            // Account requests are unwrapped in the service loop
+            OutputManagerRequest::ForAccount(_, _) => Err(OutputManagerError::NestedAccountRequest),
+            OutputManagerRequest::CreateOneSidedBatchTransaction {
+                tx_id,
+                recipients,
+                fee_per_gram,
+                message,
+            } => self
+                .create_one_sided_batch_transaction(tx_id, recipients, fee_per_gram, message)
+                .await
+                .map(OutputManagerResponse::OneSidedBatchTransaction),
             OutputManagerRequest::FeeEstimate {
                 amount,
                 fee_per_gram,
                 num_kernels,
                 num_outputs,
-            } => self
-                .fee_estimate(amount, fee_per_gram, num_kernels, num_outputs)
-                .await
-                .map(OutputManagerResponse::FeeEstimate),
+            } => {
+                let fee_per_gram = match fee_per_gram {
+                    Some(fee_per_gram) => fee_per_gram,
+                    None => self.estimate_fee_per_gram(None).await,
+                };
+                self.fee_estimate(amount, fee_per_gram, num_kernels, num_outputs)
+                    .await
+                    .map(OutputManagerResponse::FeeEstimate)
+            },
+            OutputManagerRequest::EstimateFeePerGram { target_blocks } => Ok(OutputManagerResponse::FeePerGram(
+                self.estimate_fee_per_gram(target_blocks).await,
+            )),
             OutputManagerRequest::ConfirmPendingTransaction(tx_id) => self
                 .confirm_encumberance(tx_id)
                 .await
@@ -339,6 +421,21 @@ where
                 .create_coin_split(amount_per_split, split_count, fee_per_gram, lock_height)
                 .await
                 .map(OutputManagerResponse::Transaction),
+            OutputManagerRequest::CreateConsolidationTransaction {
+                fee_per_gram,
+                max_inputs,
+                max_value,
+            } => self
+                .create_consolidation_transaction(fee_per_gram, max_inputs, max_value)
+                .await
+                .map(|result| OutputManagerResponse::ConsolidationTransaction(Box::new(result))),
+            OutputManagerRequest::GetConsolidationHistory => self
+                .resources
+                .db
+                .fetch_utxo_consolidations()
+                .await
+                .map(OutputManagerResponse::ConsolidationHistory)
+                .map_err(OutputManagerError::OutputManagerStorageError),
             OutputManagerRequest::ApplyEncryption(cipher) => self
                 .resources
                 .db
@@ -357,6 +454,39 @@ where
             OutputManagerRequest::GetPublicRewindKeys => Ok(OutputManagerResponse::PublicRewindKeys(Box::new(
                 self.resources.master_key_manager.get_rewind_public_keys(),
             ))),
+            OutputManagerRequest::GetWatchOnlyKeys => Ok(OutputManagerResponse::WatchOnlyKeys(Box::new(
+                self.resources
+                    .master_key_manager
+                    .get_watch_only_keys(self.node_identity.public_key()),
+            ))),
+            OutputManagerRequest::CreateUnsignedTransactionTemplate {
+                amount,
+                fee_per_gram,
+                lock_height,
+                message,
+                destination,
+                covenant,
+            } => self
+                .create_unsigned_transaction_template(amount, fee_per_gram, lock_height, message, destination, covenant)
+                .await
+                .map(|template| OutputManagerResponse::UnsignedTransactionTemplate(Box::new(template))),
+            OutputManagerRequest::PrepareOfflineTransaction(template) => self
+                .prepare_offline_transaction(*template)
+                .await
+                .map(OutputManagerResponse::TransactionToSend),
+            OutputManagerRequest::EncumberOfflineTransaction {
+                tx_id,
+                input_hashes,
+                outputs,
+            } => self
+                .encumber_offline_transaction(tx_id, input_hashes, outputs)
+                .await
+                .map(|_| OutputManagerResponse::OfflineTransactionEncumbered),
+            OutputManagerRequest::GetMultisigKeys(session_id) => self
+                .resources
+                .master_key_manager
+                .get_multisig_keys_for_session(session_id)
+                .map(|keys| OutputManagerResponse::MultisigKeys(Box::new(keys))),
             OutputManagerRequest::ScanForRecoverableOutputs { outputs, tx_id } => StandardUtxoRecoverer::new(
                 self.resources.master_key_manager.clone(),
                 self.resources.factories.clone(),
@@ -624,7 +754,7 @@ where
         let (spending_key, script_private_key) = self
             .resources
             .master_key_manager
-            .get_next_spend_and_script_key()
+            .get_next_spend_and_script_key(self.resources.db.account_id())
             .await?;
         let input_data = inputs!(PublicKey::from_secret_key(&script_private_key));
         let script = script!(Nop);
@@ -667,7 +797,7 @@ where
         let (spending_key, script_private_key) = self
             .resources
             .master_key_manager
-            .get_next_spend_and_script_key()
+            .get_next_spend_and_script_key(self.resources.db.account_id())
             .await?;
 
         let output = DbUnblindedOutput::rewindable_from_unblinded_output(
@@ -779,6 +909,8 @@ where
         message: String,
         recipient_script: TariScript,
         recipient_covenant: Covenant,
+        selection_strategy: Option<UTXOSelectionStrategy>,
+        recipient_maturity: Option<u64>,
     ) -> Result<SenderTransactionProtocol, OutputManagerError> {
         debug!(
             target: LOG_TARGET,
@@ -787,7 +919,10 @@ where
             unique_id,
             fee_per_gram,
         );
-        let output_features = OutputFeatures::default();
+        let output_features = OutputFeatures {
+            maturity: recipient_maturity.unwrap_or(0),
+            ..Default::default()
+        };
         let metadata_byte_size = self
             .resources
             .consensus_constants
@@ -804,14 +939,14 @@ where
                 fee_per_gram,
                 1,
                 metadata_byte_size,
-                None,
+                selection_strategy,
                 unique_id.as_ref(),
                 parent_public_key.as_ref(),
             )
             .await?;
 
         // TODO: improve this logic #LOGGED
-        let output_features = match unique_id {
+        let mut output_features = match unique_id {
             Some(ref _unique_id) => match input_selection
                 .utxos
                 .iter()
@@ -822,6 +957,9 @@ where
             },
             _ => Default::default(),
         };
+        if let Some(maturity) = recipient_maturity {
+            output_features.maturity = maturity;
+        }
 
         let offset = PrivateKey::random(&mut OsRng);
         let nonce = PrivateKey::random(&mut OsRng);
@@ -863,7 +1001,7 @@ where
             let (spending_key, script_private_key) = self
                 .resources
                 .master_key_manager
-                .get_next_spend_and_script_key()
+                .get_next_spend_and_script_key(self.resources.db.account_id())
                 .await?;
             builder.with_change_secret(spending_key);
             builder.with_rewindable_outputs(self.resources.master_key_manager.rewind_data().clone());
@@ -911,6 +1049,251 @@ where
         Ok(stp)
     }
 
+    /// Select the inputs for a one-sided payment without encumbering them or signing anything. The selected inputs
+    /// are exported with range proofs that can be rewound with this wallet's rewind keys so that the wallet holding
+    /// the spending keys can recover the input secrets and sign the transaction offline.
+    async fn create_unsigned_transaction_template(
+        &mut self,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        lock_height: Option<u64>,
+        message: String,
+        destination: PublicKey,
+        recipient_covenant: Covenant,
+    ) -> Result<UnsignedTransactionTemplate, OutputManagerError> {
+        debug!(
+            target: LOG_TARGET,
+            "Creating unsigned transaction template. Amount: {}. Fee per gram: {}. ", amount, fee_per_gram,
+        );
+        let recipient_script = script!(PushPubKey(Box::new(destination.clone())));
+        let metadata_byte_size = self
+            .resources
+            .consensus_constants
+            .transaction_weight()
+            .round_up_metadata_size(
+                OutputFeatures::default().consensus_encode_exact_size() +
+                    recipient_script.consensus_encode_exact_size() +
+                    recipient_covenant.consensus_encode_exact_size(),
+            );
+
+        let input_selection = self
+            .select_utxos(amount, fee_per_gram, 1, metadata_byte_size, None, None, None)
+            .await?;
+
+        let fee = input_selection.as_final_fee();
+        let change = if input_selection.requires_change_output() {
+            input_selection.total_value() - amount - fee
+        } else {
+            MicroTari::from(0)
+        };
+
+        let rewind_data = self.resources.master_key_manager.rewind_data();
+        let inputs = input_selection
+            .iter()
+            .map(|uo| {
+                uo.unblinded_output
+                    .as_rewindable_transaction_output(&self.resources.factories, rewind_data, None)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let spend_public_key = self
+            .resources
+            .master_key_manager
+            .get_watch_only_keys(self.node_identity.public_key())
+            .spend_public_key;
+
+        Ok(UnsignedTransactionTemplate {
+            tx_id: TxId::new_random(),
+            amount,
+            fee_per_gram,
+            fee,
+            change,
+            lock_height: lock_height.unwrap_or(0),
+            message,
+            destination,
+            recipient_script,
+            recipient_covenant,
+            inputs,
+            spend_public_key,
+        })
+    }
+
+    /// Build the sender half of a one-sided payment from a template exported by a watch-only copy of this wallet. The
+    /// template inputs are rewound to recover their spending keys and their script keys are found in the key chain,
+    /// in the same way as during recovery. Nothing is encumbered as this wallet does not track the inputs itself.
+    async fn prepare_offline_transaction(
+        &mut self,
+        template: UnsignedTransactionTemplate,
+    ) -> Result<SenderTransactionProtocol, OutputManagerError> {
+        debug!(
+            target: LOG_TARGET,
+            "Preparing offline transaction (TxId: {}) with {} inputs",
+            template.tx_id,
+            template.inputs.len()
+        );
+        if let Some(ref spend_public_key) = template.spend_public_key {
+            if spend_public_key != self.node_identity.public_key() {
+                return Err(OutputManagerError::TemplateWalletMismatch);
+            }
+        }
+
+        let mut builder = SenderTransactionProtocol::builder(1, self.resources.consensus_constants.clone());
+        builder
+            .with_lock_height(template.lock_height)
+            .with_fee_per_gram(template.fee_per_gram)
+            .with_offset(PrivateKey::random(&mut OsRng))
+            .with_private_nonce(PrivateKey::random(&mut OsRng))
+            .with_amount(0, template.amount)
+            .with_recipient_data(
+                0,
+                template.recipient_script,
+                PrivateKey::random(&mut OsRng),
+                OutputFeatures::default(),
+                PrivateKey::random(&mut OsRng),
+                template.recipient_covenant,
+            )
+            .with_message(template.message)
+            .with_prevent_fee_gt_amount(self.resources.config.prevent_fee_gt_amount)
+            .with_tx_id(template.tx_id);
+
+        let rewind_data = self.resources.master_key_manager.rewind_data().clone();
+        let mut recoverer = StandardUtxoRecoverer::new(
+            self.resources.master_key_manager.clone(),
+            self.resources.factories.clone(),
+            self.resources.db.clone(),
+        );
+        for output in template.inputs {
+            let rewound = output
+                .full_rewind_range_proof(
+                    &self.resources.factories.range_proof,
+                    &rewind_data.rewind_key,
+                    &rewind_data.rewind_blinding_key,
+                )
+                .map_err(|_| OutputManagerError::UnspendableTemplateInput(output.commitment.to_hex()))?;
+            let commitment_hex = output.commitment.to_hex();
+            let mut unblinded_output = UnblindedOutput::new(
+                output.version,
+                rewound.committed_value,
+                rewound.blinding_factor,
+                output.features,
+                output.script,
+                ExecutionStack::default(),
+                PrivateKey::default(),
+                output.sender_offset_public_key,
+                output.metadata_signature,
+                0,
+                output.covenant,
+            );
+            recoverer
+                .update_outputs_script_private_key_and_update_key_manager_index(&mut unblinded_output)
+                .await
+                .map_err(|_| OutputManagerError::UnspendableTemplateInput(commitment_hex))?;
+            builder.with_input(
+                unblinded_output.as_transaction_input(&self.resources.factories.commitment)?,
+                unblinded_output,
+            );
+        }
+
+        if template.change > MicroTari::from(0) {
+            let (spending_key, script_private_key) = self
+                .resources
+                .master_key_manager
+                .get_next_spend_and_script_key(self.resources.db.account_id())
+                .await?;
+            builder.with_change_secret(spending_key);
+            builder.with_rewindable_outputs(rewind_data);
+            builder.with_change_script(
+                script!(Nop),
+                inputs!(PublicKey::from_secret_key(&script_private_key)),
+                script_private_key,
+            );
+        }
+
+        let stp = builder
+            .build::<HashDigest>(
+                &self.resources.factories,
+                None,
+                self.last_seen_tip_height.unwrap_or(u64::MAX),
+            )
+            .map_err(|e| OutputManagerError::BuildError(e.message))?;
+
+        debug!(
+            target: LOG_TARGET,
+            "Prepared offline transaction (TxId: {}) with fee {}",
+            template.tx_id,
+            stp.get_fee_amount()?
+        );
+        Ok(stp)
+    }
+
+    /// Encumber the inputs of a transaction that was signed offline against that transaction so that they are not
+    /// selected again while it is being broadcast. The change output was built by the signer with this wallet's rewind
+    /// keys, so it is found by rewinding the transaction outputs and is added as pending incoming in the same way as
+    /// the change of a transaction built by this wallet.
+    async fn encumber_offline_transaction(
+        &mut self,
+        tx_id: TxId,
+        input_hashes: Vec<HashOutput>,
+        outputs: Vec<TransactionOutput>,
+    ) -> Result<(), OutputManagerError> {
+        let mut inputs = Vec::with_capacity(input_hashes.len());
+        for hash in input_hashes {
+            inputs.push(self.resources.db.get_unspent_output(hash).await?);
+        }
+
+        let rewind_data = self.resources.master_key_manager.rewind_data().clone();
+        let mut recoverer = StandardUtxoRecoverer::new(
+            self.resources.master_key_manager.clone(),
+            self.resources.factories.clone(),
+            self.resources.db.clone(),
+        );
+        let mut change_outputs = Vec::new();
+        for output in outputs {
+            let rewound = match output.full_rewind_range_proof(
+                &self.resources.factories.range_proof,
+                &rewind_data.rewind_key,
+                &rewind_data.rewind_blinding_key,
+            ) {
+                Ok(rewound) => rewound,
+                // Not an output of this wallet
+                Err(_) => continue,
+            };
+            // A watch-only wallet cannot derive the script key, so the output is tracked with a placeholder key as
+            // it is during recovery
+            let script_private_key = PrivateKey::random(&mut OsRng);
+            let mut change = UnblindedOutput::new(
+                output.version,
+                rewound.committed_value,
+                rewound.blinding_factor,
+                output.features.clone(),
+                output.script.clone(),
+                inputs!(PublicKey::from_secret_key(&script_private_key)),
+                script_private_key,
+                output.sender_offset_public_key.clone(),
+                output.metadata_signature.clone(),
+                0,
+                output.covenant.clone(),
+            );
+            if !self.resources.master_key_manager.is_watch_only() {
+                recoverer
+                    .update_outputs_script_private_key_and_update_key_manager_index(&mut change)
+                    .await?;
+            }
+            change_outputs.push(DbUnblindedOutput::rewindable_from_unblinded_output(
+                change,
+                &self.resources.factories,
+                &rewind_data,
+                None,
+                Some(&output.proof),
+            )?);
+        }
+
+        self.resources
+            .db
+            .encumber_outputs(tx_id, inputs, change_outputs)
+            .await?;
+        self.confirm_encumberance(tx_id).await
+    }
+
     /// Request a Coinbase transaction for a specific block height. All existing pending transactions with
     /// this blockheight will be cancelled.
     /// The key will be derived from the coinbase specific keychain using the blockheight as an index. The coinbase
@@ -1050,7 +1433,7 @@ where
             let (spending_key, script_private_key) = self
                 .resources
                 .master_key_manager
-                .get_next_spend_and_script_key()
+                .get_next_spend_and_script_key(self.resources.db.account_id())
                 .await?;
             builder.with_change_secret(spending_key);
             builder.with_rewindable_outputs(self.resources.master_key_manager.rewind_data().clone());
@@ -1093,7 +1476,7 @@ where
         //     let (spending_key, script_private_key) = self
         //         .resources
         //         .master_key_manager
-        //         .get_next_spend_and_script_key()
+        //         .get_next_spend_and_script_key(self.resources.db.account_id())
         //         .await?;
         //     change_keys = Some((spending_key.clone(), script_private_key.clone()));
         //     builder.with_change_secret(spending_key);
@@ -1165,6 +1548,8 @@ where
         fee_per_gram: MicroTari,
         lock_height: Option<u64>,
         message: String,
+        selection_strategy: Option<UTXOSelectionStrategy>,
+        recipient_account: u64,
     ) -> Result<(MicroTari, Transaction), OutputManagerError> {
         let script = script!(Nop);
         let covenant = Covenant::default();
@@ -1188,7 +1573,7 @@ where
                 fee_per_gram,
                 1,
                 metadata_byte_size,
-                None,
+                selection_strategy,
                 unique_id.as_ref(),
                 parent_public_key.as_ref(),
             )
@@ -1220,7 +1605,7 @@ where
         let (spending_key, script_private_key) = self
             .resources
             .master_key_manager
-            .get_next_spend_and_script_key()
+            .get_next_spend_and_script_key(recipient_account)
             .await?;
         let metadata_signature = TransactionOutput::create_final_metadata_signature(
             &amount,
@@ -1252,13 +1637,14 @@ where
             .with_output(utxo.unblinded_output.clone(), sender_offset_private_key.clone())
             .map_err(|e| OutputManagerError::BuildError(e.message))?;
 
+        let recipient_commitment = utxo.commitment.clone();
         let mut outputs = vec![utxo];
 
         if input_selection.requires_change_output() {
             let (spending_key, script_private_key) = self
                 .resources
                 .master_key_manager
-                .get_next_spend_and_script_key()
+                .get_next_spend_and_script_key(self.resources.db.account_id())
                 .await?;
             builder.with_change_secret(spending_key);
             builder.with_rewindable_outputs(self.resources.master_key_manager.rewind_data().clone());
@@ -1303,6 +1689,12 @@ where
             .db
             .encumber_outputs(tx_id, input_selection.into_selected(), outputs)
             .await?;
+        if recipient_account != self.resources.db.account_id() {
+            self.resources
+                .db
+                .set_output_account(recipient_commitment, recipient_account)
+                .await?;
+        }
         self.confirm_encumberance(tx_id).await?;
         let fee = stp.get_fee_amount()?;
         trace!(target: LOG_TARGET, "Finalize send-to-self transaction ({}).", tx_id);
@@ -1317,6 +1709,188 @@ where
         Ok((fee, tx))
     }
 
+    /// Create a new named account with its own key branch. Account names are unique within the wallet.
+    async fn create_account(&mut self, name: String) -> Result<Account, OutputManagerError> {
+        let accounts = self.resources.db.fetch_accounts().await?;
+        if accounts.iter().any(|a| a.name == name) {
+            return Err(OutputManagerError::AccountAlreadyExists(name));
+        }
+        let account = self.resources.db.create_account(name).await?;
+        info!(
+            target: LOG_TARGET,
+            "Created account `{}` with id {}", account.name, account.id
+        );
+        Ok(account)
+    }
+
+    /// Create a pay-to-self transaction that moves `amount` from the current account to `to_account`. The receiving
+    /// output is derived from the destination account's key branch and any change stays with the sending account.
+    async fn create_account_transfer(
+        &mut self,
+        tx_id: TxId,
+        to_account: u64,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<(MicroTari, Transaction), OutputManagerError> {
+        let accounts = self.resources.db.fetch_accounts().await?;
+        if !accounts.iter().any(|a| a.id == to_account) {
+            return Err(OutputManagerError::AccountNotFound(to_account));
+        }
+        self.create_pay_to_self_transaction(tx_id, amount, None, None, fee_per_gram, None, message, None, to_account)
+            .await
+    }
+
+    /// Create a single transaction containing a one-sided payment to each recipient. The sender derives every
+    /// recipient's spending key from a Diffie-Hellman shared secret, so all of the recipient outputs are created here
+    /// without any interaction and only one change output is needed for the whole batch.
+    async fn create_one_sided_batch_transaction(
+        &mut self,
+        tx_id: TxId,
+        recipients: Vec<(PublicKey, MicroTari)>,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<(MicroTari, Transaction), OutputManagerError> {
+        let total_value = recipients.iter().map(|(_, amount)| *amount).sum::<MicroTari>();
+        let output_features = OutputFeatures::default();
+        let covenant = Covenant::default();
+        let weighting = self.resources.consensus_constants.transaction_weight();
+        let metadata_byte_size = recipients.iter().fold(0usize, |total, (destination, _)| {
+            total +
+                weighting.round_up_metadata_size(
+                    output_features.consensus_encode_exact_size() +
+                        script!(PushPubKey(Box::new(destination.clone()))).consensus_encode_exact_size() +
+                        covenant.consensus_encode_exact_size(),
+                )
+        });
+
+        let input_selection = self
+            .select_utxos(
+                total_value,
+                fee_per_gram,
+                recipients.len(),
+                metadata_byte_size,
+                None,
+                None,
+                None,
+            )
+            .await?;
+
+        let offset = PrivateKey::random(&mut OsRng);
+        let nonce = PrivateKey::random(&mut OsRng);
+
+        // The recipient outputs are added by the sender, so the protocol has no interactive recipients
+        let mut builder = SenderTransactionProtocol::builder(0, self.resources.consensus_constants.clone());
+        builder
+            .with_lock_height(0)
+            .with_fee_per_gram(fee_per_gram)
+            .with_offset(offset)
+            .with_private_nonce(nonce)
+            .with_message(message)
+            .with_prevent_fee_gt_amount(self.resources.config.prevent_fee_gt_amount)
+            .with_tx_id(tx_id);
+
+        for uo in input_selection.iter() {
+            builder.with_input(
+                uo.unblinded_output
+                    .as_transaction_input(&self.resources.factories.commitment)?,
+                uo.unblinded_output.clone(),
+            );
+        }
+
+        for (destination, amount) in recipients {
+            let sender_offset_private_key = PrivateKey::random(&mut OsRng);
+            let spending_key = PrivateKey::from_bytes(
+                CommsPublicKey::shared_secret(&sender_offset_private_key, &destination).as_bytes(),
+            )?;
+            let rewind_blinding_key = PrivateKey::from_bytes(&hash_secret_key(&spending_key))?;
+            let rewind_key = PrivateKey::from_bytes(&hash_secret_key(&rewind_blinding_key))?;
+            let script = script!(PushPubKey(Box::new(destination)));
+            let metadata_signature = TransactionOutput::create_final_metadata_signature(
+                &amount,
+                &spending_key,
+                &script,
+                &output_features,
+                &sender_offset_private_key,
+                &covenant,
+            )?;
+            // The script private key belongs to the recipient and is never known to the sender
+            let output = UnblindedOutput::new_current_version(
+                amount,
+                spending_key,
+                output_features.clone(),
+                script,
+                ExecutionStack::default(),
+                PrivateKey::default(),
+                PublicKey::from_secret_key(&sender_offset_private_key),
+                metadata_signature,
+                0,
+                covenant.clone(),
+            );
+            builder
+                .with_rewindable_output(output, sender_offset_private_key, RewindData {
+                    rewind_key,
+                    rewind_blinding_key,
+                    proof_message: [0u8; 21],
+                })
+                .map_err(|e| OutputManagerError::BuildError(e.message))?;
+        }
+
+        if input_selection.requires_change_output() {
+            let (spending_key, script_private_key) = self
+                .resources
+                .master_key_manager
+                .get_next_spend_and_script_key(self.resources.db.account_id())
+                .await?;
+            builder.with_change_secret(spending_key);
+            builder.with_rewindable_outputs(self.resources.master_key_manager.rewind_data().clone());
+            builder.with_change_script(
+                script!(Nop),
+                inputs!(PublicKey::from_secret_key(&script_private_key)),
+                script_private_key,
+            );
+        }
+
+        let mut stp = builder
+            .build::<HashDigest>(
+                &self.resources.factories,
+                None,
+                self.last_seen_tip_height.unwrap_or(u64::MAX),
+            )
+            .map_err(|e| OutputManagerError::BuildError(e.message))?;
+
+        let mut change_output = Vec::new();
+        if let Some(unblinded_output) = stp.get_change_unblinded_output()? {
+            change_output.push(DbUnblindedOutput::rewindable_from_unblinded_output(
+                unblinded_output,
+                &self.resources.factories,
+                self.resources.master_key_manager.rewind_data(),
+                None,
+                None,
+            )?);
+        }
+
+        self.resources
+            .db
+            .encumber_outputs(tx_id, input_selection.into_selected(), change_output)
+            .await?;
+        self.confirm_encumberance(tx_id).await?;
+        let fee = stp.get_fee_amount()?;
+        stp.finalize(
+            KernelFeatures::empty(),
+            &self.resources.factories,
+            None,
+            self.last_seen_tip_height.unwrap_or(u64::MAX),
+        )?;
+        let tx = stp.take_transaction()?;
+
+        debug!(
+            target: LOG_TARGET,
+            "Prepared one-sided batch transaction (TxId: {}) paying {} with fee {}", tx_id, total_value, fee
+        );
+        Ok((fee, tx))
+    }
+
     /// Confirm that a transaction has finished being negotiated between parties so the short-term encumberance can be
     /// made official
     async fn confirm_encumberance(&mut self, tx_id: TxId) -> Result<(), OutputManagerError> {
@@ -1427,10 +2001,10 @@ where
         //     },
         // };
         warn!(target: LOG_TARGET, "select_utxos selection strategy: {}", strategy);
-        let uo = self
+        let mut uo = self
             .resources
             .db
-            .fetch_unspent_outputs_for_spending(strategy, amount, tip_height)
+            .fetch_unspent_outputs_for_spending(strategy.clone(), amount, tip_height)
             .await?;
         trace!(target: LOG_TARGET, "We found {} UTXOs to select from", uo.len());
 
@@ -1438,6 +2012,106 @@ where
         let default_metadata_size = fee_calc.weighting().round_up_metadata_size(
             OutputFeatures::default().consensus_encode_exact_size() + script![Nop].consensus_encode_exact_size(),
         );
+        // The fee for each additional input, and the excess that the transaction builder will add to the fee rather
+        // than create a change output for (no change script is set when a change output is not required)
+        let input_fee = fee_calc.calculate(fee_per_gram, 0, 1, 0, 0);
+        let cost_of_change = fee_calc.calculate(
+            fee_per_gram,
+            0,
+            0,
+            1,
+            fee_calc
+                .weighting()
+                .round_up_metadata_size(OutputFeatures::default().consensus_encode_exact_size()),
+        );
+        let fees_for = |num_inputs: usize| {
+            (
+                fee_calc.calculate(fee_per_gram, 1, num_inputs, num_outputs, output_metadata_byte_size),
+                fee_calc.calculate(
+                    fee_per_gram,
+                    1,
+                    num_inputs,
+                    num_outputs + 1,
+                    output_metadata_byte_size + default_metadata_size,
+                ),
+            )
+        };
+
+        match strategy {
+            UTXOSelectionStrategy::Specific(commitments) => {
+                if !commitments.iter().all(|c| uo.iter().any(|o| &o.commitment == c)) {
+                    return Err(OutputManagerError::SpecifiedOutputsUnavailable);
+                }
+                utxos.extend(uo);
+                let (fee_without_change, fee_with_change) = fees_for(utxos.len());
+                return UtxoSelection::spend_all(utxos, amount, fee_without_change, fee_with_change, cost_of_change)
+                    .ok_or(OutputManagerError::NotEnoughFunds);
+            },
+            UTXOSelectionStrategy::BranchAndBound => {
+                let values = uo.iter().map(|o| o.unblinded_output.value.as_u64()).collect::<Vec<_>>();
+                let target = (amount + fees_for(utxos.len()).0)
+                    .as_u64()
+                    .saturating_sub(utxos_total_value.as_u64());
+                let selection =
+                    coin_selection::branch_and_bound(&values, target, input_fee.as_u64(), cost_of_change.as_u64())
+                        .and_then(|selected| {
+                            let mut selected_utxos = utxos.clone();
+                            selected_utxos.extend(selected.into_iter().map(|i| uo[i].clone()));
+                            let (fee_without_change, fee_with_change) = fees_for(selected_utxos.len());
+                            UtxoSelection::spend_all(
+                                selected_utxos,
+                                amount,
+                                fee_without_change,
+                                fee_with_change,
+                                cost_of_change,
+                            )
+                        });
+                match selection {
+                    Some(selection) => {
+                        debug!(
+                            target: LOG_TARGET,
+                            "Branch and bound selected {} UTXOs",
+                            selection.num_selected()
+                        );
+                        return Ok(selection);
+                    },
+                    None => debug!(
+                        target: LOG_TARGET,
+                        "No branch and bound match found, selecting the largest UTXOs instead"
+                    ),
+                }
+            },
+            UTXOSelectionStrategy::Privacy => {
+                let sources = self
+                    .resources
+                    .db
+                    .fetch_unspent_output_sources()
+                    .await?
+                    .into_iter()
+                    .map(|(commitment, source)| (commitment.as_bytes().to_vec(), source))
+                    .collect::<HashMap<_, _>>();
+                let candidates = uo
+                    .iter()
+                    .map(|o| (sources.get(o.commitment.as_bytes()), o.unblinded_output.value.as_u64()))
+                    .collect::<Vec<_>>();
+                let target = (amount + fees_for(utxos.len()).1)
+                    .as_u64()
+                    .saturating_sub(utxos_total_value.as_u64());
+                let (order, unlinked) = coin_selection::order_by_counterparty(&candidates, target, input_fee.as_u64());
+                if !unlinked {
+                    warn!(
+                        target: LOG_TARGET,
+                        "No single counterparty's UTXOs cover {}, outputs from different counterparties may be spent \
+                         together",
+                        amount
+                    );
+                }
+                let mut candidates = uo.into_iter().map(Some).collect::<Vec<_>>();
+                uo = order.into_iter().filter_map(|i| candidates[i].take()).collect();
+            },
+            _ => {},
+        }
+
         let mut requires_change_output = false;
         for o in uo {
             utxos_total_value += o.unblinded_output.value;
@@ -1569,7 +2243,7 @@ where
             let (spending_key, script_private_key) = self
                 .resources
                 .master_key_manager
-                .get_next_spend_and_script_key()
+                .get_next_spend_and_script_key(self.resources.db.account_id())
                 .await?;
             let sender_offset_private_key = PrivateKey::random(&mut OsRng);
 
@@ -1610,7 +2284,7 @@ where
             let (spending_key, script_private_key) = self
                 .resources
                 .master_key_manager
-                .get_next_spend_and_script_key()
+                .get_next_spend_and_script_key(self.resources.db.account_id())
                 .await?;
             builder.with_change_secret(spending_key);
             builder.with_rewindable_outputs(self.resources.master_key_manager.rewind_data().clone());
@@ -1669,6 +2343,141 @@ where
         Ok((tx_id, tx, utxos_total_value))
     }
 
+    /// Combine up to `max_inputs` of the smallest spendable outputs into a single pay-to-self output. Dust that costs
+    /// more to spend than it is worth, and outputs worth more than `max_value`, are left alone.
+    async fn create_consolidation_transaction(
+        &mut self,
+        fee_per_gram: MicroTari,
+        max_inputs: usize,
+        max_value: Option<MicroTari>,
+    ) -> Result<(UtxoConsolidation, Transaction), OutputManagerError> {
+        let tip_height = self
+            .base_node_service
+            .get_chain_metadata()
+            .await?
+            .map(|m| m.height_of_longest_chain());
+        let fee_calc = self.get_fee_calc();
+        let input_fee = fee_calc.calculate(fee_per_gram, 0, 1, 0, 0);
+        let inputs = self
+            .resources
+            .db
+            .fetch_unspent_outputs_for_spending(UTXOSelectionStrategy::Smallest, MicroTari::from(0), tip_height)
+            .await?
+            .into_iter()
+            .filter(|o| o.unblinded_output.value > input_fee)
+            .filter(|o| max_value.map(|max| o.unblinded_output.value <= max).unwrap_or(true))
+            .take(max_inputs)
+            .collect::<Vec<_>>();
+        if inputs.len() < 2 {
+            return Err(OutputManagerError::NoUtxosToConsolidate);
+        }
+
+        let metadata_byte_size = fee_calc.weighting().round_up_metadata_size(
+            OutputFeatures::default().consensus_encode_exact_size() +
+                script![Nop].consensus_encode_exact_size() +
+                Covenant::default().consensus_encode_exact_size(),
+        );
+        let fee = fee_calc.calculate(fee_per_gram, 1, inputs.len(), 1, metadata_byte_size);
+        let total_value = inputs
+            .iter()
+            .fold(MicroTari::from(0), |total, o| total + o.unblinded_output.value);
+        if total_value <= fee {
+            return Err(OutputManagerError::NoUtxosToConsolidate);
+        }
+        let amount = total_value - fee;
+
+        let tx_id = TxId::new_random();
+        let num_inputs = inputs.len();
+        let commitments = inputs.into_iter().map(|o| o.commitment).collect();
+        debug!(
+            target: LOG_TARGET,
+            "Consolidating {} outputs worth {} into one output of {} ({})", num_inputs, total_value, amount, tx_id
+        );
+        let (fee, tx) = self
+            .create_pay_to_self_transaction(
+                tx_id,
+                amount,
+                None,
+                None,
+                fee_per_gram,
+                None,
+                format!("Consolidation of {} outputs", num_inputs),
+                Some(UTXOSelectionStrategy::Specific(commitments)),
+                self.resources.db.account_id(),
+            )
+            .await?;
+
+        let consolidation = UtxoConsolidation {
+            tx_id,
+            num_inputs,
+            amount,
+            fee,
+            timestamp: Utc::now().naive_utc(),
+        };
+        self.resources.db.add_utxo_consolidation(consolidation.clone()).await?;
+        Ok((consolidation, tx))
+    }
+
+    /// Ask the base node for the fee per gram needed to be mined within `target_blocks` (or the configured target),
+    /// falling back to the configured default fee per gram if the base node is offline or has no estimate yet.
+    /// Estimates above the configured maximum fee per gram are capped at the maximum.
+    async fn estimate_fee_per_gram(&mut self, target_blocks: Option<u64>) -> MicroTari {
+        let target_blocks = target_blocks.unwrap_or(self.resources.config.fee_estimate_target_blocks);
+        let default_fee_per_gram = self.resources.config.default_fee_per_gram;
+        if self.resources.connectivity.get_connectivity_status() != OnlineStatus::Online {
+            debug!(
+                target: LOG_TARGET,
+                "Base node is not online, using default fee per gram of {}", default_fee_per_gram
+            );
+            return default_fee_per_gram;
+        }
+
+        let mut connectivity = self.resources.connectivity.clone();
+        let estimate = tokio::time::timeout(self.resources.config.base_node_query_timeout, async move {
+            let mut client = connectivity.obtain_base_node_wallet_rpc_client().await?;
+            client.estimate_fee_per_gram(target_blocks).await.ok()
+        })
+        .await;
+
+        match estimate {
+            Ok(Some(response)) => match response.estimate() {
+                Some(fee_per_gram) if fee_per_gram > self.resources.config.max_fee_per_gram => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Base node estimate of {} per gram exceeds the maximum, using the maximum of {}",
+                        fee_per_gram,
+                        self.resources.config.max_fee_per_gram
+                    );
+                    self.resources.config.max_fee_per_gram
+                },
+                Some(fee_per_gram) => {
+                    debug!(
+                        target: LOG_TARGET,
+                        "Base node estimates a fee per gram of {} to be mined within {} block(s)",
+                        fee_per_gram,
+                        target_blocks
+                    );
+                    fee_per_gram
+                },
+                None => {
+                    debug!(
+                        target: LOG_TARGET,
+                        "Base node could not estimate a fee per gram, using default of {}", default_fee_per_gram
+                    );
+                    default_fee_per_gram
+                },
+            },
+            Ok(None) | Err(_) => {
+                warn!(
+                    target: LOG_TARGET,
+                    "Could not get a fee per gram estimate from the base node, using default of {}",
+                    default_fee_per_gram
+                );
+                default_fee_per_gram
+            },
+        }
+    }
+
     async fn fetch_outputs_from_node(
         &mut self,
         hashes: Vec<HashOutput>,
@@ -1753,7 +2562,7 @@ where
         let (spending_key, script_private_key) = self
             .resources
             .master_key_manager
-            .get_next_spend_and_script_key()
+            .get_next_spend_and_script_key(self.resources.db.account_id())
             .await?;
         builder.with_change_secret(spending_key);
         builder.with_rewindable_outputs(self.resources.master_key_manager.rewind_data().clone());
@@ -1839,7 +2648,7 @@ where
         let (spending_key, script_private_key) = self
             .resources
             .master_key_manager
-            .get_next_spend_and_script_key()
+            .get_next_spend_and_script_key(self.resources.db.account_id())
             .await?;
         builder.with_change_secret(spending_key);
         builder.with_rewindable_outputs(self.resources.master_key_manager.rewind_data().clone());
@@ -1999,7 +2808,7 @@ where
 }
 
 /// Different UTXO selection strategies for choosing which UTXO's are used to fulfill a transaction
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum UTXOSelectionStrategy {
     // Start from the smallest UTXOs and work your way up until the amount is covered. Main benefit
     // is removing small UTXOs from the blockchain, con is that it costs more in fees
@@ -2011,6 +2820,14 @@ pub enum UTXOSelectionStrategy {
     // Heuristic for selection strategy: MaturityThenSmallest, but if the amount is greater than
     // the largest UTXO, use Largest UTXOs first
     Default,
+    // Search for a combination of UTXOs that covers the amount and fee closely enough that no change output is
+    // needed. Falls back to Largest if no such combination exists
+    BranchAndBound,
+    // Spend UTXOs received from a single counterparty where possible so that outputs from different senders are not
+    // linked together on chain
+    Privacy,
+    // Spend exactly the UTXOs with the given commitments ("coin control")
+    Specific(Vec<Commitment>),
 }
 
 impl Display for UTXOSelectionStrategy {
@@ -2020,6 +2837,26 @@ impl Display for UTXOSelectionStrategy {
             UTXOSelectionStrategy::MaturityThenSmallest => write!(f, "MaturityThenSmallest"),
             UTXOSelectionStrategy::Largest => write!(f, "Largest"),
             UTXOSelectionStrategy::Default => write!(f, "Default"),
+            UTXOSelectionStrategy::BranchAndBound => write!(f, "BranchAndBound"),
+            UTXOSelectionStrategy::Privacy => write!(f, "Privacy"),
+            UTXOSelectionStrategy::Specific(commitments) => write!(f, "Specific({} outputs)", commitments.len()),
+        }
+    }
+}
+
+impl FromStr for UTXOSelectionStrategy {
+    type Err = String;
+
+    /// Parses the name of a strategy. `Specific` cannot be parsed as it requires the commitments to spend.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace(&['-', '_'][..], "").as_str() {
+            "smallest" => Ok(UTXOSelectionStrategy::Smallest),
+            "maturitythensmallest" => Ok(UTXOSelectionStrategy::MaturityThenSmallest),
+            "largest" => Ok(UTXOSelectionStrategy::Largest),
+            "default" => Ok(UTXOSelectionStrategy::Default),
+            "branchandbound" => Ok(UTXOSelectionStrategy::BranchAndBound),
+            "privacy" => Ok(UTXOSelectionStrategy::Privacy),
+            _ => Err(format!("Unknown UTXO selection strategy '{}'", s)),
         }
     }
 }
@@ -2074,6 +2911,38 @@ struct UtxoSelection {
 }
 
 impl UtxoSelection {
+    /// Selects all of `utxos`. A change output is only required if the excess is more than `cost_of_change`, which the
+    /// transaction builder would otherwise add to the fee. Returns `None` if the UTXOs do not cover the amount and fee.
+    fn spend_all(
+        utxos: Vec<DbUnblindedOutput>,
+        amount: MicroTari,
+        fee_without_change: MicroTari,
+        fee_with_change: MicroTari,
+        cost_of_change: MicroTari,
+    ) -> Option<Self> {
+        let total_value = utxos.iter().map(|o| o.unblinded_output.value).sum::<MicroTari>();
+        let excess = total_value.checked_sub(amount + fee_without_change)?;
+        if excess <= cost_of_change {
+            return Some(Self {
+                utxos,
+                requires_change_output: false,
+                total_value,
+                fee_without_change: fee_without_change + excess,
+                fee_with_change,
+            });
+        }
+        if total_value > amount + fee_with_change {
+            return Some(Self {
+                utxos,
+                requires_change_output: true,
+                total_value,
+                fee_without_change,
+                fee_with_change,
+            });
+        }
+        None
+    }
+
     pub fn as_final_fee(&self) -> MicroTari {
         if self.requires_change_output {
             return self.fee_with_change;
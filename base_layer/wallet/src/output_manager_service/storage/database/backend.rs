@@ -10,7 +10,7 @@ use crate::output_manager_service::{
     service::{Balance, UTXOSelectionStrategy},
     storage::{
         database::{DbKey, DbValue, WriteOperation},
-        models::DbUnblindedOutput,
+        models::{Account, DbUnblindedOutput, UtxoConsolidation},
     },
 };
 
@@ -104,7 +104,7 @@ pub trait OutputManagerBackend: Send + Sync + Clone {
     /// Reinstate a cancelled inbound output
     fn reinstate_cancelled_inbound_output(&self, tx_id: TxId) -> Result<(), OutputManagerStorageError>;
     /// Return the available, time locked, pending incoming and pending outgoing balance
-    fn get_balance(&self, tip: Option<u64>) -> Result<Balance, OutputManagerStorageError>;
+    fn get_balance(&self, account_id: u64, tip: Option<u64>) -> Result<Balance, OutputManagerStorageError>;
     /// Import unvalidated output
     fn add_unvalidated_output(&self, output: DbUnblindedOutput, tx_id: TxId) -> Result<(), OutputManagerStorageError>;
     fn fetch_unspent_outputs_for_spending(
@@ -112,6 +112,26 @@ pub trait OutputManagerBackend: Send + Sync + Clone {
         strategy: UTXOSelectionStrategy,
         amount: u64,
         current_tip_height: Option<u64>,
+        account_id: u64,
     ) -> Result<Vec<DbUnblindedOutput>, OutputManagerStorageError>;
+    /// Return the commitments of unspent outputs along with the public key of the counterparty they were received from,
+    /// for those outputs that were received in a known transaction
+    fn fetch_unspent_output_sources(&self) -> Result<Vec<(Commitment, PublicKey)>, OutputManagerStorageError>;
     fn fetch_outputs_by_tx_id(&self, tx_id: TxId) -> Result<Vec<DbUnblindedOutput>, OutputManagerStorageError>;
+    /// Record a pay-to-self transaction that consolidated unspent outputs
+    fn add_utxo_consolidation(&self, consolidation: UtxoConsolidation) -> Result<(), OutputManagerStorageError>;
+    /// Return all recorded UTXO consolidations, most recent first
+    fn fetch_utxo_consolidations(&self) -> Result<Vec<UtxoConsolidation>, OutputManagerStorageError>;
+    /// Create a new account with the next available id
+    fn create_account(&self, name: String) -> Result<Account, OutputManagerStorageError>;
+    fn fetch_accounts(&self) -> Result<Vec<Account>, OutputManagerStorageError>;
+    /// Increment the key index of an account and return the new index
+    fn increment_account_key_index(&self, account_id: u64) -> Result<u64, OutputManagerStorageError>;
+    /// Make sure that the account with the given id exists and that its key index is at least `key_index`. Used when an
+    /// output of the account is found during recovery.
+    fn restore_account(&self, account_id: u64, key_index: u64) -> Result<(), OutputManagerStorageError>;
+    /// Move an output to a different account
+    fn set_output_account(&self, commitment: &Commitment, account_id: u64) -> Result<(), OutputManagerStorageError>;
+    /// Return the ids of the transactions in which outputs of the account were received or spent
+    fn fetch_account_tx_ids(&self, account_id: u64) -> Result<Vec<TxId>, OutputManagerStorageError>;
 }
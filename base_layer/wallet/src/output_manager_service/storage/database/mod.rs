@@ -44,7 +44,7 @@ use crate::output_manager_service::{
     error::OutputManagerStorageError,
     service::{Balance, UTXOSelectionStrategy},
     storage::{
-        models::{DbUnblindedOutput, KnownOneSidedPaymentScript},
+        models::{Account, DbUnblindedOutput, KnownOneSidedPaymentScript, UtxoConsolidation, DEFAULT_ACCOUNT_ID},
         OutputStatus,
     },
 };
@@ -101,17 +101,34 @@ pub enum WriteOperation {
 }
 
 /// This structure holds an inner type that implements the `OutputManagerBackend` trait and contains the more complex
-/// data access logic required by the module built onto the functionality defined by the trait
+/// data access logic required by the module built onto the functionality defined by the trait. New outputs are added
+/// to, and balances and spendable outputs are read from, the account this instance is scoped to.
 #[derive(Clone)]
 pub struct OutputManagerDatabase<T> {
     db: Arc<T>,
+    account_id: u64,
 }
 
 impl<T> OutputManagerDatabase<T>
 where T: OutputManagerBackend + 'static
 {
     pub fn new(db: T) -> Self {
-        Self { db: Arc::new(db) }
+        Self {
+            db: Arc::new(db),
+            account_id: DEFAULT_ACCOUNT_ID,
+        }
+    }
+
+    /// Returns a copy of this database scoped to the given account
+    pub fn for_account(&self, account_id: u64) -> Self {
+        Self {
+            db: self.db.clone(),
+            account_id,
+        }
+    }
+
+    pub fn account_id(&self) -> u64 {
+        self.account_id
     }
 
     pub async fn get_key_manager_state(&self) -> Result<Option<KeyManagerState>, OutputManagerStorageError> {
@@ -154,7 +171,8 @@ where T: OutputManagerBackend + 'static
         Ok(())
     }
 
-    pub async fn add_unspent_output(&self, output: DbUnblindedOutput) -> Result<(), OutputManagerStorageError> {
+    pub async fn add_unspent_output(&self, mut output: DbUnblindedOutput) -> Result<(), OutputManagerStorageError> {
+        output.account_id = self.account_id;
         let db_clone = self.db.clone();
         tokio::task::spawn_blocking(move || {
             db_clone.write(WriteOperation::Insert(DbKeyValuePair::UnspentOutput(
@@ -171,8 +189,9 @@ where T: OutputManagerBackend + 'static
     pub async fn add_unspent_output_with_tx_id(
         &self,
         tx_id: TxId,
-        output: DbUnblindedOutput,
+        mut output: DbUnblindedOutput,
     ) -> Result<(), OutputManagerStorageError> {
+        output.account_id = self.account_id;
         let db_clone = self.db.clone();
         tokio::task::spawn_blocking(move || {
             db_clone.write(WriteOperation::Insert(DbKeyValuePair::UnspentOutputWithTxId(
@@ -189,8 +208,9 @@ where T: OutputManagerBackend + 'static
     pub async fn add_unvalidated_output(
         &self,
         tx_id: TxId,
-        output: DbUnblindedOutput,
+        mut output: DbUnblindedOutput,
     ) -> Result<(), OutputManagerStorageError> {
+        output.account_id = self.account_id;
         let db_clone = self.db.clone();
         tokio::task::spawn_blocking(move || db_clone.add_unvalidated_output(output, tx_id))
             .await
@@ -202,9 +222,10 @@ where T: OutputManagerBackend + 'static
     pub async fn add_output_to_be_received(
         &self,
         tx_id: TxId,
-        output: DbUnblindedOutput,
+        mut output: DbUnblindedOutput,
         coinbase_block_height: Option<u64>,
     ) -> Result<(), OutputManagerStorageError> {
+        output.account_id = self.account_id;
         let db_clone = self.db.clone();
         tokio::task::spawn_blocking(move || {
             db_clone.write(WriteOperation::Insert(DbKeyValuePair::OutputToBeReceived(
@@ -223,7 +244,8 @@ where T: OutputManagerBackend + 'static
         current_tip_for_time_lock_calculation: Option<u64>,
     ) -> Result<Balance, OutputManagerStorageError> {
         let db_clone = self.db.clone();
-        tokio::task::spawn_blocking(move || db_clone.get_balance(current_tip_for_time_lock_calculation))
+        let account_id = self.account_id;
+        tokio::task::spawn_blocking(move || db_clone.get_balance(account_id, current_tip_for_time_lock_calculation))
             .await
             .map_err(|err| OutputManagerStorageError::BlockingTaskSpawnError(err.to_string()))?
     }
@@ -234,8 +256,11 @@ where T: OutputManagerBackend + 'static
         &self,
         tx_id: TxId,
         outputs_to_send: Vec<DbUnblindedOutput>,
-        outputs_to_receive: Vec<DbUnblindedOutput>,
+        mut outputs_to_receive: Vec<DbUnblindedOutput>,
     ) -> Result<(), OutputManagerStorageError> {
+        for output in outputs_to_receive.iter_mut() {
+            output.account_id = self.account_id;
+        }
         let db_clone = self.db.clone();
         tokio::task::spawn_blocking(move || {
             db_clone.short_term_encumber_outputs(tx_id, &outputs_to_send, &outputs_to_receive)
@@ -293,7 +318,7 @@ where T: OutputManagerBackend + 'static
             Some(other) => return unexpected_result(DbKey::UnspentOutputs, other),
             None => vec![],
         };
-        Ok(result)
+        Ok(result.into_iter().filter(|o| o.account_id == self.account_id).collect())
     }
 
     pub async fn fetch_with_features(
@@ -319,14 +344,90 @@ where T: OutputManagerBackend + 'static
         tip_height: Option<u64>,
     ) -> Result<Vec<DbUnblindedOutput>, OutputManagerStorageError> {
         let db_clone = self.db.clone();
+        let account_id = self.account_id;
         let utxos = tokio::task::spawn_blocking(move || {
-            db_clone.fetch_unspent_outputs_for_spending(strategy, amount.as_u64(), tip_height)
+            db_clone.fetch_unspent_outputs_for_spending(strategy, amount.as_u64(), tip_height, account_id)
         })
         .await
         .map_err(|err| OutputManagerStorageError::BlockingTaskSpawnError(err.to_string()))??;
         Ok(utxos)
     }
 
+    /// Retrieves the counterparty each unspent output was received from, where known
+    pub async fn fetch_unspent_output_sources(
+        &self,
+    ) -> Result<Vec<(Commitment, PublicKey)>, OutputManagerStorageError> {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || db_clone.fetch_unspent_output_sources())
+            .await
+            .map_err(|err| OutputManagerStorageError::BlockingTaskSpawnError(err.to_string()))?
+    }
+
+    pub async fn add_utxo_consolidation(
+        &self,
+        consolidation: UtxoConsolidation,
+    ) -> Result<(), OutputManagerStorageError> {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || db_clone.add_utxo_consolidation(consolidation))
+            .await
+            .map_err(|err| OutputManagerStorageError::BlockingTaskSpawnError(err.to_string()))?
+    }
+
+    pub async fn fetch_utxo_consolidations(&self) -> Result<Vec<UtxoConsolidation>, OutputManagerStorageError> {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || db_clone.fetch_utxo_consolidations())
+            .await
+            .map_err(|err| OutputManagerStorageError::BlockingTaskSpawnError(err.to_string()))?
+    }
+
+    pub async fn create_account(&self, name: String) -> Result<Account, OutputManagerStorageError> {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || db_clone.create_account(name))
+            .await
+            .map_err(|err| OutputManagerStorageError::BlockingTaskSpawnError(err.to_string()))?
+    }
+
+    pub async fn fetch_accounts(&self) -> Result<Vec<Account>, OutputManagerStorageError> {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || db_clone.fetch_accounts())
+            .await
+            .map_err(|err| OutputManagerStorageError::BlockingTaskSpawnError(err.to_string()))?
+    }
+
+    pub async fn increment_account_key_index(&self, account_id: u64) -> Result<u64, OutputManagerStorageError> {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || db_clone.increment_account_key_index(account_id))
+            .await
+            .map_err(|err| OutputManagerStorageError::BlockingTaskSpawnError(err.to_string()))?
+    }
+
+    pub async fn restore_account(&self, account_id: u64, key_index: u64) -> Result<(), OutputManagerStorageError> {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || db_clone.restore_account(account_id, key_index))
+            .await
+            .map_err(|err| OutputManagerStorageError::BlockingTaskSpawnError(err.to_string()))?
+    }
+
+    pub async fn set_output_account(
+        &self,
+        commitment: Commitment,
+        account_id: u64,
+    ) -> Result<(), OutputManagerStorageError> {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || db_clone.set_output_account(&commitment, account_id))
+            .await
+            .map_err(|err| OutputManagerStorageError::BlockingTaskSpawnError(err.to_string()))?
+    }
+
+    /// Retrieves the ids of the transactions that received or spent outputs of the account this database is scoped to
+    pub async fn fetch_account_tx_ids(&self) -> Result<Vec<TxId>, OutputManagerStorageError> {
+        let db_clone = self.db.clone();
+        let account_id = self.account_id;
+        tokio::task::spawn_blocking(move || db_clone.fetch_account_tx_ids(account_id))
+            .await
+            .map_err(|err| OutputManagerStorageError::BlockingTaskSpawnError(err.to_string()))?
+    }
+
     pub async fn fetch_spent_outputs(&self) -> Result<Vec<DbUnblindedOutput>, OutputManagerStorageError> {
         let db_clone = self.db.clone();
 
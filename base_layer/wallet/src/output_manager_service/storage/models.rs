@@ -22,8 +22,13 @@
 
 use std::cmp::Ordering;
 
-use tari_common_types::types::{BlockHash, Commitment, HashOutput, PrivateKey, RangeProof};
+use chrono::NaiveDateTime;
+use tari_common_types::{
+    transaction::TxId,
+    types::{BlockHash, Commitment, HashOutput, PrivateKey, RangeProof},
+};
 use tari_core::transactions::{
+    tari_amount::MicroTari,
     transaction_components::UnblindedOutput,
     transaction_protocol::RewindData,
     CryptoFactories,
@@ -33,6 +38,9 @@ use tari_utilities::hash::Hashable;
 
 use crate::output_manager_service::{error::OutputManagerStorageError, storage::OutputStatus};
 
+/// The account that owns all outputs of a wallet that has not created any other accounts
+pub const DEFAULT_ACCOUNT_ID: u64 = 0;
+
 #[derive(Debug, Clone)]
 pub struct DbUnblindedOutput {
     pub commitment: Commitment,
@@ -45,6 +53,8 @@ pub struct DbUnblindedOutput {
     pub marked_deleted_at_height: Option<u64>,
     pub marked_deleted_in_block: Option<BlockHash>,
     pub spending_priority: SpendingPriority,
+    /// The wallet account this output belongs to
+    pub account_id: u64,
 }
 
 impl DbUnblindedOutput {
@@ -65,6 +75,7 @@ impl DbUnblindedOutput {
             marked_deleted_at_height: None,
             marked_deleted_in_block: None,
             spending_priority: spend_priority.unwrap_or(SpendingPriority::Normal),
+            account_id: DEFAULT_ACCOUNT_ID,
         })
     }
 
@@ -87,6 +98,7 @@ impl DbUnblindedOutput {
             marked_deleted_at_height: None,
             marked_deleted_in_block: None,
             spending_priority: spending_priority.unwrap_or(SpendingPriority::Normal),
+            account_id: DEFAULT_ACCOUNT_ID,
         })
     }
 }
@@ -157,3 +169,23 @@ impl PartialEq for KnownOneSidedPaymentScript {
         self.script_hash == other.script_hash
     }
 }
+
+/// A record of a pay-to-self transaction that combined a number of unspent outputs into a single output
+#[derive(Debug, Clone, PartialEq)]
+pub struct UtxoConsolidation {
+    pub tx_id: TxId,
+    pub num_inputs: usize,
+    pub amount: MicroTari,
+    pub fee: MicroTari,
+    pub timestamp: NaiveDateTime,
+}
+
+/// A named account within the wallet. Each account derives its keys from its own key manager branch and has its own
+/// outputs and balance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Account {
+    pub id: u64,
+    pub name: String,
+    pub key_index: u64,
+    pub created_at: NaiveDateTime,
+}
@@ -36,7 +36,10 @@ use tari_common_types::{
     transaction::TxId,
     types::{Commitment, PrivateKey, PublicKey},
 };
-use tari_core::transactions::transaction_components::{OutputFlags, TransactionOutput};
+use tari_core::transactions::{
+    tari_amount::MicroTari,
+    transaction_components::{OutputFlags, TransactionOutput},
+};
 use tari_crypto::{
     script::{ExecutionStack, TariScript},
     tari_utilities::{
@@ -53,11 +56,18 @@ use crate::{
         service::{Balance, UTXOSelectionStrategy},
         storage::{
             database::{DbKey, DbKeyValuePair, DbValue, KeyManagerState, OutputManagerBackend, WriteOperation},
-            models::{DbUnblindedOutput, KnownOneSidedPaymentScript},
+            models::{Account, DbUnblindedOutput, KnownOneSidedPaymentScript, UtxoConsolidation},
             OutputStatus,
         },
     },
-    schema::{key_manager_states, known_one_sided_payment_scripts, outputs, outputs::columns},
+    schema::{
+        accounts,
+        key_manager_states,
+        known_one_sided_payment_scripts,
+        outputs,
+        outputs::columns,
+        utxo_consolidations,
+    },
     storage::sqlite_utilities::wallet_db_connection::WalletDbConnection,
     util::{
         diesel_ext::ExpectedRowsExtension,
@@ -841,13 +851,14 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
 
     fn get_balance(
         &self,
+        account_id: u64,
         current_tip_for_time_lock_calculation: Option<u64>,
     ) -> Result<Balance, OutputManagerStorageError> {
         let start = Instant::now();
         let conn = self.database_connection.get_pooled_connection()?;
         let acquire_lock = start.elapsed();
 
-        let result = OutputSql::get_balance(current_tip_for_time_lock_calculation, &conn);
+        let result = OutputSql::get_balance(account_id as i64, current_tip_for_time_lock_calculation, &conn);
         if start.elapsed().as_millis() > 0 {
             trace!(
                 target: LOG_TARGET,
@@ -1023,20 +1034,21 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
             o.update_encryption(&conn)?;
         }
 
-        let mut key_manager_state = KeyManagerStateSql::get_state(&conn)?;
-
-        let _ = CipherSeed::from_enciphered_bytes(&key_manager_state.seed, None).map_err(|_| {
-            error!(
-                target: LOG_TARGET,
-                "Could not create Cipher Seed from stored bytes, They might already be encrypted"
-            );
-            OutputManagerStorageError::AlreadyEncrypted
-        })?;
+        // A watch-only wallet has no key manager state to encrypt
+        if let Ok(mut key_manager_state) = KeyManagerStateSql::get_state(&conn) {
+            let _ = CipherSeed::from_enciphered_bytes(&key_manager_state.seed, None).map_err(|_| {
+                error!(
+                    target: LOG_TARGET,
+                    "Could not create Cipher Seed from stored bytes, They might already be encrypted"
+                );
+                OutputManagerStorageError::AlreadyEncrypted
+            })?;
 
-        key_manager_state
-            .encrypt(&cipher)
-            .map_err(|_| OutputManagerStorageError::AeadError("Encryption Error".to_string()))?;
-        key_manager_state.set_state(&conn)?;
+            key_manager_state
+                .encrypt(&cipher)
+                .map_err(|_| OutputManagerStorageError::AeadError("Encryption Error".to_string()))?;
+            key_manager_state.set_state(&conn)?;
+        }
 
         let mut known_one_sided_payment_scripts = KnownOneSidedPaymentScriptSql::index(&conn)?;
 
@@ -1086,11 +1098,12 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
             o.update_encryption(&conn)?;
         }
 
-        let mut key_manager_state = KeyManagerStateSql::get_state(&conn)?;
-        key_manager_state
-            .decrypt(&cipher)
-            .map_err(|_| OutputManagerStorageError::AeadError("Encryption Error".to_string()))?;
-        key_manager_state.set_state(&conn)?;
+        if let Ok(mut key_manager_state) = KeyManagerStateSql::get_state(&conn) {
+            key_manager_state
+                .decrypt(&cipher)
+                .map_err(|_| OutputManagerStorageError::AeadError("Encryption Error".to_string()))?;
+            key_manager_state.set_state(&conn)?;
+        }
 
         let mut known_one_sided_payment_scripts = KnownOneSidedPaymentScriptSql::index(&conn)?;
 
@@ -1241,6 +1254,7 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
         strategy: UTXOSelectionStrategy,
         amount: u64,
         tip_height: Option<u64>,
+        account_id: u64,
     ) -> Result<Vec<DbUnblindedOutput>, OutputManagerStorageError> {
         let start = Instant::now();
         let conn = self.database_connection.get_pooled_connection()?;
@@ -1249,7 +1263,8 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
             Some(v) => v as i64,
             None => i64::MAX,
         };
-        let mut outputs = OutputSql::fetch_unspent_outputs_for_spending(strategy, amount, tip, &conn)?;
+        let mut outputs =
+            OutputSql::fetch_unspent_outputs_for_spending(strategy, amount, tip, account_id as i64, &conn)?;
         for o in outputs.iter_mut() {
             self.decrypt_if_necessary(o)?;
         }
@@ -1266,6 +1281,25 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
             .collect::<Result<Vec<_>, _>>()
     }
 
+    fn fetch_unspent_output_sources(&self) -> Result<Vec<(Commitment, PublicKey)>, OutputManagerStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        let sources = OutputSql::fetch_unspent_output_sources(&conn)?;
+        sources
+            .into_iter()
+            .filter_map(|(commitment, source)| commitment.zip(source))
+            .map(|(commitment, source)| {
+                let commitment =
+                    Commitment::from_vec(&commitment).map_err(|_| OutputManagerStorageError::ConversionError {
+                        reason: "Invalid commitment".to_string(),
+                    })?;
+                let source = PublicKey::from_vec(&source).map_err(|_| OutputManagerStorageError::ConversionError {
+                    reason: "Invalid public key".to_string(),
+                })?;
+                Ok((commitment, source))
+            })
+            .collect()
+    }
+
     fn fetch_outputs_by_tx_id(&self, tx_id: TxId) -> Result<Vec<DbUnblindedOutput>, OutputManagerStorageError> {
         let conn = self.database_connection.get_pooled_connection()?;
         let mut outputs = OutputSql::find_by_tx_id(tx_id, &conn)?;
@@ -1277,6 +1311,80 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
             .map(|o| DbUnblindedOutput::try_from(o.clone()))
             .collect::<Result<Vec<_>, _>>()
     }
+
+    fn add_utxo_consolidation(&self, consolidation: UtxoConsolidation) -> Result<(), OutputManagerStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        UtxoConsolidationSql::from(consolidation).commit(&conn)
+    }
+
+    fn fetch_utxo_consolidations(&self) -> Result<Vec<UtxoConsolidation>, OutputManagerStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        UtxoConsolidationSql::index(&conn)?
+            .into_iter()
+            .map(UtxoConsolidation::try_from)
+            .collect()
+    }
+
+    fn create_account(&self, name: String) -> Result<Account, OutputManagerStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        conn.transaction::<_, OutputManagerStorageError, _>(|| {
+            let id = AccountSql::max_id(&conn)?.map(|id| id + 1).unwrap_or(0);
+            let account = AccountSql {
+                id,
+                name,
+                key_index: 0,
+                created_at: Utc::now().naive_utc(),
+            };
+            account.commit(&conn)?;
+            Ok(account.into())
+        })
+    }
+
+    fn fetch_accounts(&self) -> Result<Vec<Account>, OutputManagerStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        Ok(AccountSql::index(&conn)?.into_iter().map(Account::from).collect())
+    }
+
+    fn increment_account_key_index(&self, account_id: u64) -> Result<u64, OutputManagerStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        let index = AccountSql::increment_key_index(account_id as i64, &conn)?;
+        Ok(index as u64)
+    }
+
+    fn restore_account(&self, account_id: u64, key_index: u64) -> Result<(), OutputManagerStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        conn.transaction::<_, OutputManagerStorageError, _>(|| {
+            match AccountSql::find(account_id as i64, &conn)? {
+                Some(account) => {
+                    if account.key_index < key_index as i64 {
+                        AccountSql::set_key_index(account_id as i64, key_index as i64, &conn)?;
+                    }
+                },
+                None => {
+                    // The name of a recovered account is not known, so it is named after its id
+                    AccountSql {
+                        id: account_id as i64,
+                        name: format!("recovered_{}", account_id),
+                        key_index: key_index as i64,
+                        created_at: Utc::now().naive_utc(),
+                    }
+                    .commit(&conn)?;
+                },
+            }
+            Ok(())
+        })
+    }
+
+    fn set_output_account(&self, commitment: &Commitment, account_id: u64) -> Result<(), OutputManagerStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        OutputSql::set_account(commitment.as_bytes(), account_id as i64, &conn)
+    }
+
+    fn fetch_account_tx_ids(&self, account_id: u64) -> Result<Vec<TxId>, OutputManagerStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        let tx_ids = OutputSql::fetch_account_tx_ids(account_id as i64, &conn)?;
+        Ok(tx_ids.into_iter().map(|id| TxId::from(id as u64)).collect())
+    }
 }
 
 /// These are the fields that can be updated for an Output
@@ -1660,6 +1768,129 @@ impl Encryptable<Aes256Gcm> for KnownOneSidedPaymentScriptSql {
     }
 }
 
+#[derive(Clone, Debug, Queryable, Insertable, PartialEq)]
+#[table_name = "accounts"]
+pub struct AccountSql {
+    pub id: i64,
+    pub name: String,
+    pub key_index: i64,
+    pub created_at: NaiveDateTime,
+}
+
+impl AccountSql {
+    /// Write this struct to the database
+    pub fn commit(&self, conn: &SqliteConnection) -> Result<(), OutputManagerStorageError> {
+        diesel::insert_into(accounts::table)
+            .values(self.clone())
+            .execute(conn)?;
+        Ok(())
+    }
+
+    /// Return all accounts, ordered by id
+    pub fn index(conn: &SqliteConnection) -> Result<Vec<AccountSql>, OutputManagerStorageError> {
+        Ok(accounts::table.order(accounts::id.asc()).load::<AccountSql>(conn)?)
+    }
+
+    pub fn max_id(conn: &SqliteConnection) -> Result<Option<i64>, OutputManagerStorageError> {
+        Ok(accounts::table.select(diesel::dsl::max(accounts::id)).first(conn)?)
+    }
+
+    pub fn find(id: i64, conn: &SqliteConnection) -> Result<Option<AccountSql>, OutputManagerStorageError> {
+        Ok(accounts::table
+            .filter(accounts::id.eq(id))
+            .first::<AccountSql>(conn)
+            .optional()?)
+    }
+
+    pub fn set_key_index(id: i64, key_index: i64, conn: &SqliteConnection) -> Result<(), OutputManagerStorageError> {
+        diesel::update(accounts::table.filter(accounts::id.eq(id)))
+            .set(accounts::key_index.eq(key_index))
+            .execute(conn)
+            .num_rows_affected_or_not_found(1)?;
+        Ok(())
+    }
+
+    /// Increment the key index of an account, returning the new index
+    pub fn increment_key_index(id: i64, conn: &SqliteConnection) -> Result<i64, OutputManagerStorageError> {
+        conn.transaction::<_, OutputManagerStorageError, _>(|| {
+            diesel::update(accounts::table.filter(accounts::id.eq(id)))
+                .set(accounts::key_index.eq(accounts::key_index + 1))
+                .execute(conn)
+                .num_rows_affected_or_not_found(1)?;
+            Ok(accounts::table
+                .filter(accounts::id.eq(id))
+                .select(accounts::key_index)
+                .first(conn)?)
+        })
+    }
+}
+
+impl From<AccountSql> for Account {
+    fn from(o: AccountSql) -> Self {
+        Self {
+            id: o.id as u64,
+            name: o.name,
+            key_index: o.key_index as u64,
+            created_at: o.created_at,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Queryable, Insertable, PartialEq)]
+#[table_name = "utxo_consolidations"]
+pub struct UtxoConsolidationSql {
+    pub tx_id: i64,
+    pub num_inputs: i64,
+    pub amount: i64,
+    pub fee: i64,
+    pub timestamp: NaiveDateTime,
+}
+
+impl UtxoConsolidationSql {
+    /// Write this struct to the database
+    pub fn commit(&self, conn: &SqliteConnection) -> Result<(), OutputManagerStorageError> {
+        diesel::insert_into(utxo_consolidations::table)
+            .values(self.clone())
+            .execute(conn)?;
+        Ok(())
+    }
+
+    /// Return all consolidations, most recent first
+    pub fn index(conn: &SqliteConnection) -> Result<Vec<UtxoConsolidationSql>, OutputManagerStorageError> {
+        Ok(utxo_consolidations::table
+            .order(utxo_consolidations::timestamp.desc())
+            .load::<UtxoConsolidationSql>(conn)?)
+    }
+}
+
+impl From<UtxoConsolidation> for UtxoConsolidationSql {
+    fn from(consolidation: UtxoConsolidation) -> Self {
+        Self {
+            tx_id: consolidation.tx_id.as_u64() as i64,
+            num_inputs: consolidation.num_inputs as i64,
+            amount: consolidation.amount.as_u64() as i64,
+            fee: consolidation.fee.as_u64() as i64,
+            timestamp: consolidation.timestamp,
+        }
+    }
+}
+
+impl TryFrom<UtxoConsolidationSql> for UtxoConsolidation {
+    type Error = OutputManagerStorageError;
+
+    fn try_from(o: UtxoConsolidationSql) -> Result<Self, Self::Error> {
+        Ok(Self {
+            tx_id: (o.tx_id as u64).into(),
+            num_inputs: usize::try_from(o.num_inputs).map_err(|_| OutputManagerStorageError::ConversionError {
+                reason: "Invalid number of inputs".to_string(),
+            })?,
+            amount: MicroTari::from(o.amount as u64),
+            fee: MicroTari::from(o.fee as u64),
+            timestamp: o.timestamp,
+        })
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::{convert::TryFrom, time::Duration};
@@ -59,6 +59,7 @@ pub struct NewOutputSql {
     pub coinbase_block_height: Option<i64>,
     pub features_json: String,
     pub covenant: Vec<u8>,
+    pub account_id: i64,
 }
 
 impl NewOutputSql {
@@ -99,6 +100,7 @@ impl NewOutputSql {
                 }
             })?,
             covenant: output.unblinded_output.covenant.to_bytes(),
+            account_id: output.account_id as i64,
         })
     }
 
@@ -147,6 +149,7 @@ impl From<OutputSql> for NewOutputSql {
             coinbase_block_height: o.coinbase_block_height,
             features_json: o.features_json,
             covenant: o.covenant,
+            account_id: o.account_id,
         }
     }
 }
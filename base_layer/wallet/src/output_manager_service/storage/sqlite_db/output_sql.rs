@@ -54,7 +54,7 @@ use crate::{
             OutputStatus,
         },
     },
-    schema::outputs,
+    schema::{completed_transactions, outputs},
     util::{
         diesel_ext::ExpectedRowsExtension,
         encryption::{decrypt_bytes_integral_nonce, encrypt_bytes_integral_nonce, Encryptable},
@@ -96,6 +96,7 @@ pub struct OutputSql {
     pub spending_priority: i32,
     pub features_json: String,
     pub covenant: Vec<u8>,
+    pub account_id: i64,
 }
 
 impl OutputSql {
@@ -117,12 +118,14 @@ impl OutputSql {
         mut strategy: UTXOSelectionStrategy,
         amount: u64,
         tip_height: i64,
+        account_id: i64,
         conn: &SqliteConnection,
     ) -> Result<Vec<OutputSql>, OutputManagerStorageError> {
         if strategy == UTXOSelectionStrategy::Default {
             // lets get the max value for all utxos
             let max: Vec<i64> = outputs::table
                 .filter(outputs::status.eq(OutputStatus::Unspent as i32))
+                .filter(outputs::account_id.eq(account_id))
                 .filter(outputs::script_lock_height.le(tip_height))
                 .filter(outputs::maturity.le(tip_height))
                 .filter(outputs::features_unique_id.is_null())
@@ -143,6 +146,7 @@ impl OutputSql {
         let mut query = outputs::table
             .into_boxed()
             .filter(outputs::status.eq(OutputStatus::Unspent as i32))
+            .filter(outputs::account_id.eq(account_id))
             .filter(outputs::script_lock_height.le(tip_height))
             .filter(outputs::maturity.le(tip_height))
             .filter(outputs::features_unique_id.is_null())
@@ -157,14 +161,76 @@ impl OutputSql {
                     .then_order_by(outputs::maturity.asc())
                     .then_order_by(outputs::value.asc());
             },
-            UTXOSelectionStrategy::Largest => {
+            UTXOSelectionStrategy::Largest | UTXOSelectionStrategy::BranchAndBound => {
                 query = query.then_order_by(outputs::value.desc());
             },
+            UTXOSelectionStrategy::Privacy => {
+                query = query.then_order_by(outputs::value.asc());
+            },
+            UTXOSelectionStrategy::Specific(commitments) => {
+                query = query
+                    .filter(
+                        outputs::commitment
+                            .eq_any(commitments.iter().map(|c| c.as_bytes().to_vec()).collect::<Vec<_>>()),
+                    )
+                    .then_order_by(outputs::value.desc());
+            },
             UTXOSelectionStrategy::Default => {},
         };
         Ok(query.load(conn)?)
     }
 
+    /// Return the commitment of every unspent output together with the public key of the counterparty that sent it,
+    /// if it was received in a known transaction.
+    #[allow(clippy::type_complexity)]
+    pub fn fetch_unspent_output_sources(
+        conn: &SqliteConnection,
+    ) -> Result<Vec<(Option<Vec<u8>>, Option<Vec<u8>>)>, OutputManagerStorageError> {
+        Ok(outputs::table
+            .left_join(
+                completed_transactions::table
+                    .on(outputs::received_in_tx_id.eq(completed_transactions::tx_id.nullable())),
+            )
+            .filter(outputs::status.eq(OutputStatus::Unspent as i32))
+            .select((
+                outputs::commitment,
+                completed_transactions::source_public_key.nullable(),
+            ))
+            .load(conn)?)
+    }
+
+    /// Return the ids of the transactions in which outputs of the given account were received or spent
+    pub fn fetch_account_tx_ids(
+        account_id: i64,
+        conn: &SqliteConnection,
+    ) -> Result<Vec<i64>, OutputManagerStorageError> {
+        let received: Vec<Option<i64>> = outputs::table
+            .filter(outputs::account_id.eq(account_id))
+            .select(outputs::received_in_tx_id)
+            .load(conn)?;
+        let spent: Vec<Option<i64>> = outputs::table
+            .filter(outputs::account_id.eq(account_id))
+            .select(outputs::spent_in_tx_id)
+            .load(conn)?;
+        let mut tx_ids = received.into_iter().chain(spent).flatten().collect::<Vec<_>>();
+        tx_ids.sort_unstable();
+        tx_ids.dedup();
+        Ok(tx_ids)
+    }
+
+    /// Move the output with the given commitment to another account
+    pub fn set_account(
+        commitment: &[u8],
+        account_id: i64,
+        conn: &SqliteConnection,
+    ) -> Result<(), OutputManagerStorageError> {
+        diesel::update(outputs::table.filter(outputs::commitment.eq(commitment)))
+            .set(outputs::account_id.eq(account_id))
+            .execute(conn)
+            .num_rows_affected_or_not_found(1)?;
+        Ok(())
+    }
+
     /// Return all unspent outputs that have a maturity above the provided chain tip
     pub fn index_time_locked(tip: u64, conn: &SqliteConnection) -> Result<Vec<OutputSql>, OutputManagerStorageError> {
         Ok(outputs::table
@@ -243,6 +309,7 @@ impl OutputSql {
 
     /// Return the available, time locked, pending incoming and pending outgoing balance
     pub fn get_balance(
+        account_id: i64,
         current_tip_for_time_lock_calculation: Option<u64>,
         conn: &SqliteConnection,
     ) -> Result<Balance, OutputManagerStorageError> {
@@ -256,28 +323,32 @@ impl OutputSql {
         let balance_query_result = if let Some(current_tip) = current_tip_for_time_lock_calculation {
             let balance_query = sql_query(
                 "SELECT coalesce(sum(value), 0) as amount, 'available_balance' as category \
-                 FROM outputs WHERE status = ? \
+                 FROM outputs WHERE account_id = ? AND status = ? \
                  UNION ALL \
                  SELECT coalesce(sum(value), 0) as amount, 'time_locked_balance' as category \
-                 FROM outputs WHERE status = ? AND maturity > ? OR script_lock_height > ? \
+                 FROM outputs WHERE account_id = ? AND (status = ? AND maturity > ? OR script_lock_height > ?) \
                  UNION ALL \
                  SELECT coalesce(sum(value), 0) as amount, 'pending_incoming_balance' as category \
-                 FROM outputs WHERE status = ? OR status = ? OR status = ? \
+                 FROM outputs WHERE account_id = ? AND (status = ? OR status = ? OR status = ?) \
                  UNION ALL \
                  SELECT coalesce(sum(value), 0) as amount, 'pending_outgoing_balance' as category \
-                 FROM outputs WHERE status = ? OR status = ? OR status = ?",
+                 FROM outputs WHERE account_id = ? AND (status = ? OR status = ? OR status = ?)",
             )
                 // available_balance
+                .bind::<diesel::sql_types::BigInt, _>(account_id)
                 .bind::<diesel::sql_types::Integer, _>(OutputStatus::Unspent as i32)
                 // time_locked_balance
+                .bind::<diesel::sql_types::BigInt, _>(account_id)
                 .bind::<diesel::sql_types::Integer, _>(OutputStatus::Unspent as i32)
                 .bind::<diesel::sql_types::BigInt, _>(current_tip as i64)
                 .bind::<diesel::sql_types::BigInt, _>(current_tip as i64)
                 // pending_incoming_balance
+                .bind::<diesel::sql_types::BigInt, _>(account_id)
                 .bind::<diesel::sql_types::Integer, _>(OutputStatus::EncumberedToBeReceived as i32)
                 .bind::<diesel::sql_types::Integer, _>(OutputStatus::ShortTermEncumberedToBeReceived as i32)
                 .bind::<diesel::sql_types::Integer, _>(OutputStatus::UnspentMinedUnconfirmed as i32)
                 // pending_outgoing_balance
+                .bind::<diesel::sql_types::BigInt, _>(account_id)
                 .bind::<diesel::sql_types::Integer, _>(OutputStatus::EncumberedToBeSpent as i32)
                 .bind::<diesel::sql_types::Integer, _>(OutputStatus::ShortTermEncumberedToBeSpent as i32)
                 .bind::<diesel::sql_types::Integer, _>(OutputStatus::SpentMinedUnconfirmed as i32);
@@ -285,21 +356,24 @@ impl OutputSql {
         } else {
             let balance_query = sql_query(
                 "SELECT coalesce(sum(value), 0) as amount, 'available_balance' as category \
-                 FROM outputs WHERE status = ? \
+                 FROM outputs WHERE account_id = ? AND status = ? \
                  UNION ALL \
                  SELECT coalesce(sum(value), 0) as amount, 'pending_incoming_balance' as category \
-                 FROM outputs WHERE status = ? OR status = ? OR status = ? \
+                 FROM outputs WHERE account_id = ? AND (status = ? OR status = ? OR status = ?) \
                  UNION ALL \
                  SELECT coalesce(sum(value), 0) as amount, 'pending_outgoing_balance' as category \
-                 FROM outputs WHERE status = ? OR status = ? OR status = ?",
+                 FROM outputs WHERE account_id = ? AND (status = ? OR status = ? OR status = ?)",
             )
                 // available_balance
+                .bind::<diesel::sql_types::BigInt, _>(account_id)
                 .bind::<diesel::sql_types::Integer, _>(OutputStatus::Unspent as i32)
                 // pending_incoming_balance
+                .bind::<diesel::sql_types::BigInt, _>(account_id)
                 .bind::<diesel::sql_types::Integer, _>(OutputStatus::EncumberedToBeReceived as i32)
                 .bind::<diesel::sql_types::Integer, _>(OutputStatus::ShortTermEncumberedToBeReceived as i32)
                 .bind::<diesel::sql_types::Integer, _>(OutputStatus::UnspentMinedUnconfirmed as i32)
                 // pending_outgoing_balance
+                .bind::<diesel::sql_types::BigInt, _>(account_id)
                 .bind::<diesel::sql_types::Integer, _>(OutputStatus::EncumberedToBeSpent as i32)
                 .bind::<diesel::sql_types::Integer, _>(OutputStatus::ShortTermEncumberedToBeSpent as i32)
                 .bind::<diesel::sql_types::Integer, _>(OutputStatus::SpentMinedUnconfirmed as i32);
@@ -600,6 +674,7 @@ impl TryFrom<OutputSql> for DbUnblindedOutput {
             marked_deleted_at_height: o.marked_deleted_at_height.map(|d| d as u64),
             marked_deleted_in_block: o.marked_deleted_in_block,
             spending_priority,
+            account_id: o.account_id as u64,
         })
     }
 }
@@ -21,5 +21,7 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 mod txo_validation_task;
+mod utxo_consolidation_task;
 
 pub use txo_validation_task::TxoValidationTask;
+pub use utxo_consolidation_task::UtxoConsolidationTask;
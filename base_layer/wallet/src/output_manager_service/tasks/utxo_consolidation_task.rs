@@ -0,0 +1,157 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::cmp;
+
+use log::*;
+use tari_core::transactions::tari_amount::MicroTari;
+use tari_shutdown::ShutdownSignal;
+use tokio::time::{self, MissedTickBehavior};
+
+use crate::{
+    output_manager_service::{config::ConsolidationPolicy, error::OutputManagerError, handle::OutputManagerHandle},
+    transaction_service::{error::TransactionServiceError, handle::TransactionServiceHandle},
+};
+
+const LOG_TARGET: &str = "wallet::output_service::utxo_consolidation_task";
+
+/// Periodically combines the wallet's unspent outputs into single pay-to-self outputs, either because the wallet holds
+/// more outputs than the policy allows or to sweep up dust.
+pub struct UtxoConsolidationTask {
+    policy: ConsolidationPolicy,
+    output_manager: OutputManagerHandle,
+    transaction_service: TransactionServiceHandle,
+    shutdown_signal: ShutdownSignal,
+}
+
+impl UtxoConsolidationTask {
+    pub fn new(
+        policy: ConsolidationPolicy,
+        output_manager: OutputManagerHandle,
+        transaction_service: TransactionServiceHandle,
+        shutdown_signal: ShutdownSignal,
+    ) -> Self {
+        Self {
+            policy,
+            output_manager,
+            transaction_service,
+            shutdown_signal,
+        }
+    }
+
+    pub async fn run(mut self) {
+        let mut interval = time::interval_at(time::Instant::now() + self.policy.interval, self.policy.interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut shutdown = self.shutdown_signal.clone();
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if let Err(e) = self.apply_policy().await {
+                        warn!(target: LOG_TARGET, "UTXO consolidation failed: {}", e);
+                    }
+                },
+                _ = shutdown.wait() => {
+                    info!(target: LOG_TARGET, "UTXO consolidation task shutting down");
+                    break;
+                }
+            }
+        }
+    }
+
+    async fn apply_policy(&mut self) -> Result<(), TransactionServiceError> {
+        let fee_per_gram = self.output_manager.estimate_fee_per_gram(None).await?;
+        if fee_per_gram > self.policy.max_fee_per_gram {
+            debug!(
+                target: LOG_TARGET,
+                "Postponing UTXO consolidation, estimated fee per gram {} is above the maximum of {}",
+                fee_per_gram,
+                self.policy.max_fee_per_gram
+            );
+            return Ok(());
+        }
+
+        let mut num_utxos = self.output_manager.get_unspent_outputs().await?.len();
+        if num_utxos > self.policy.max_utxo_count {
+            // Spending n inputs into one output reduces the output count by n - 1
+            while num_utxos > self.policy.max_utxo_count {
+                let max_inputs = cmp::min(
+                    num_utxos - self.policy.max_utxo_count + 1,
+                    self.policy.max_inputs_per_transaction,
+                );
+                match self.consolidate(fee_per_gram, cmp::max(max_inputs, 2), None).await? {
+                    Some(num_inputs) => num_utxos -= num_inputs - 1,
+                    None => break,
+                }
+            }
+        } else if self.policy.dust_threshold > MicroTari::from(0) {
+            self.consolidate(
+                fee_per_gram,
+                self.policy.max_inputs_per_transaction,
+                Some(self.policy.dust_threshold),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Creates and submits a single consolidation transaction, returning the number of outputs it spent or None if
+    /// there was nothing worth consolidating.
+    async fn consolidate(
+        &mut self,
+        fee_per_gram: MicroTari,
+        max_inputs: usize,
+        max_value: Option<MicroTari>,
+    ) -> Result<Option<usize>, TransactionServiceError> {
+        let (consolidation, tx) = match self
+            .output_manager
+            .create_consolidation_transaction(fee_per_gram, max_inputs, max_value)
+            .await
+        {
+            Ok(result) => result,
+            Err(OutputManagerError::NoUtxosToConsolidate) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let message = format!("Consolidation of {} outputs", consolidation.num_inputs);
+        if let Err(e) = self
+            .transaction_service
+            .submit_transaction(consolidation.tx_id, tx, consolidation.amount, message)
+            .await
+        {
+            if let Err(e) = self.output_manager.cancel_transaction(consolidation.tx_id).await {
+                warn!(
+                    target: LOG_TARGET,
+                    "Could not cancel consolidation transaction {}: {}", consolidation.tx_id, e
+                );
+            }
+            return Err(e);
+        }
+        info!(
+            target: LOG_TARGET,
+            "Submitted consolidation transaction {} spending {} outputs into {} (fee {})",
+            consolidation.tx_id,
+            consolidation.num_inputs,
+            consolidation.amount,
+            consolidation.fee
+        );
+        Ok(Some(consolidation.num_inputs))
+    }
+}
@@ -0,0 +1,178 @@
+//  Copyright 2022, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use tari_common_types::{
+    transaction::TxId,
+    types::{PrivateKey, PublicKey},
+};
+use tari_core::{
+    covenants::Covenant,
+    transactions::{
+        tari_amount::MicroTari,
+        transaction_components::{Transaction, TransactionOutput},
+        transaction_protocol::RewindData,
+    },
+};
+use tari_crypto::{range_proof::REWIND_USER_MESSAGE_LENGTH, script::TariScript, tari_utilities::hex::Hex};
+use tari_key_manager::cipher_seed::CipherSeed;
+
+/// The keys a watch-only wallet is driven by. The rewind keys are enough to identify and value the wallet's outputs on
+/// chain, but not to spend them. The optional spend public key is the public key one-sided payments to the wallet are
+/// sent to; it is carried along so that exported transaction templates can be matched to the signing wallet.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatchOnlyKeys {
+    pub rewind_key: PrivateKey,
+    pub rewind_blinding_key: PrivateKey,
+    pub spend_public_key: Option<PublicKey>,
+}
+
+impl WatchOnlyKeys {
+    pub fn new(rewind_key: PrivateKey, rewind_blinding_key: PrivateKey, spend_public_key: Option<PublicKey>) -> Self {
+        Self {
+            rewind_key,
+            rewind_blinding_key,
+            spend_public_key,
+        }
+    }
+
+    pub fn rewind_data(&self) -> RewindData {
+        RewindData {
+            rewind_key: self.rewind_key.clone(),
+            rewind_blinding_key: self.rewind_blinding_key.clone(),
+            proof_message: [0u8; REWIND_USER_MESSAGE_LENGTH],
+        }
+    }
+}
+
+impl fmt::Debug for WatchOnlyKeys {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WatchOnlyKeys")
+            .field("rewind_key", &"<secret>")
+            .field("rewind_blinding_key", &"<secret>")
+            .field(
+                "spend_public_key",
+                &self.spend_public_key.as_ref().map(|pk| pk.to_hex()),
+            )
+            .finish()
+    }
+}
+
+/// The secret material a wallet is started with. A full wallet derives all of its keys from the master seed, while a
+/// watch-only wallet only holds the keys needed to recognise its outputs and is unable to sign.
+#[derive(Clone)]
+pub enum WalletKeySource {
+    Seed(CipherSeed),
+    WatchOnly(Box<WatchOnlyKeys>),
+}
+
+impl WalletKeySource {
+    pub fn is_watch_only(&self) -> bool {
+        matches!(self, WalletKeySource::WatchOnly(_))
+    }
+}
+
+impl From<CipherSeed> for WalletKeySource {
+    fn from(seed: CipherSeed) -> Self {
+        WalletKeySource::Seed(seed)
+    }
+}
+
+impl From<WatchOnlyKeys> for WalletKeySource {
+    fn from(keys: WatchOnlyKeys) -> Self {
+        WalletKeySource::WatchOnly(Box::new(keys))
+    }
+}
+
+/// Everything required to build and sign a one-sided payment elsewhere: the inputs selected by the watch-only wallet
+/// together with the payment and fee details. The inputs are the full outputs as they appear on chain so that the
+/// signing wallet can rewind them to recover their keys without having to be synced itself.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnsignedTransactionTemplate {
+    pub tx_id: TxId,
+    pub amount: MicroTari,
+    pub fee_per_gram: MicroTari,
+    pub fee: MicroTari,
+    pub change: MicroTari,
+    pub lock_height: u64,
+    pub message: String,
+    pub destination: PublicKey,
+    pub recipient_script: TariScript,
+    pub recipient_covenant: Covenant,
+    pub inputs: Vec<TransactionOutput>,
+    pub spend_public_key: Option<PublicKey>,
+}
+
+impl UnsignedTransactionTemplate {
+    /// The total value of the selected inputs
+    pub fn total_input_value(&self) -> MicroTari {
+        self.amount + self.fee + self.change
+    }
+
+    pub fn requires_change_output(&self) -> bool {
+        self.change > MicroTari::from(0)
+    }
+}
+
+/// A one-sided payment built from an [UnsignedTransactionTemplate] and signed by the wallet holding the spending keys.
+/// The signing wallet plays both sides of the sender and recipient protocols and finalises the kernel itself, so that
+/// none of the secrets used to build the transaction have to leave it; the watch-only wallet only has to validate the
+/// transaction against the chain tip before recording and broadcasting it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfflineSignedTransaction {
+    pub tx_id: TxId,
+    pub destination: PublicKey,
+    pub amount: MicroTari,
+    pub fee: MicroTari,
+    pub message: String,
+    pub transaction: Transaction,
+}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::OsRng;
+    use tari_crypto::{
+        keys::{PublicKey as PublicKeyTrait, SecretKey},
+        tari_utilities::message_format::MessageFormat,
+    };
+
+    use super::*;
+
+    #[test]
+    fn it_round_trips_watch_only_keys_through_json() {
+        let (_, spend_public_key) = PublicKey::random_keypair(&mut OsRng);
+        let keys = WatchOnlyKeys::new(
+            PrivateKey::random(&mut OsRng),
+            PrivateKey::random(&mut OsRng),
+            Some(spend_public_key),
+        );
+        let json = keys.to_json().unwrap();
+        let decoded = WatchOnlyKeys::from_json(&json).unwrap();
+        assert_eq!(decoded, keys);
+        assert_eq!(decoded.rewind_data().rewind_key, keys.rewind_key);
+
+        let debug = format!("{:?}", keys);
+        assert!(!debug.contains(&keys.rewind_key.to_hex()));
+    }
+}
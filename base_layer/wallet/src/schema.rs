@@ -1,3 +1,22 @@
+table! {
+    accounts (id) {
+        id -> BigInt,
+        name -> Text,
+        key_index -> BigInt,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    batch_payments (tx_id, payment_index) {
+        tx_id -> BigInt,
+        payment_index -> Integer,
+        destination_public_key -> Binary,
+        amount -> BigInt,
+        message -> Text,
+    }
+}
+
 table! {
     client_key_values (key) {
         key -> Text,
@@ -33,6 +52,10 @@ table! {
     contacts (public_key) {
         public_key -> Binary,
         alias -> Text,
+        tags -> Text,
+        notes -> Text,
+        preferred_send_method -> Integer,
+        last_seen -> Nullable<Timestamp>,
     }
 }
 
@@ -51,6 +74,21 @@ table! {
     }
 }
 
+table! {
+    invoices (id) {
+        id -> BigInt,
+        reference -> Text,
+        amount -> BigInt,
+        recipient -> Binary,
+        message -> Text,
+        status -> Integer,
+        tx_id -> Nullable<BigInt>,
+        created_at -> Timestamp,
+        expires_at -> Nullable<Timestamp>,
+        paid_at -> Nullable<Timestamp>,
+    }
+}
+
 table! {
     key_manager_states (id) {
         id -> Integer,
@@ -71,6 +109,16 @@ table! {
     }
 }
 
+table! {
+    multisig_sessions (session_id) {
+        session_id -> BigInt,
+        leader_public_key -> Binary,
+        status -> Integer,
+        session_data -> Text,
+        timestamp -> Timestamp,
+    }
+}
+
 table! {
     outbound_transactions (tx_id) {
         tx_id -> BigInt,
@@ -119,6 +167,7 @@ table! {
         spending_priority -> Integer,
         features_json -> Text,
         covenant -> Binary,
+        account_id -> BigInt,
     }
 }
 
@@ -132,6 +181,35 @@ table! {
     }
 }
 
+table! {
+    scheduled_payments (id) {
+        id -> BigInt,
+        destination_public_key -> Binary,
+        amount -> BigInt,
+        fee_per_gram -> BigInt,
+        message -> Text,
+        one_sided -> Integer,
+        recipient_lock_height -> Nullable<BigInt>,
+        trigger_height -> Nullable<BigInt>,
+        trigger_time -> Nullable<Timestamp>,
+        repeat_interval -> Nullable<BigInt>,
+        remaining_payments -> Nullable<BigInt>,
+        status -> Integer,
+        last_tx_id -> Nullable<BigInt>,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    utxo_consolidations (tx_id) {
+        tx_id -> BigInt,
+        num_inputs -> BigInt,
+        amount -> BigInt,
+        fee -> BigInt,
+        timestamp -> Timestamp,
+    }
+}
+
 table! {
     wallet_settings (key) {
         key -> Text,
@@ -139,15 +217,33 @@ table! {
     }
 }
 
+table! {
+    webhook_outbox (id) {
+        id -> Integer,
+        url -> Text,
+        payload -> Text,
+        attempts -> Integer,
+        next_attempt_at -> Timestamp,
+        created_at -> Timestamp,
+    }
+}
+
 allow_tables_to_appear_in_same_query!(
+    accounts,
+    batch_payments,
     client_key_values,
     completed_transactions,
     contacts,
     inbound_transactions,
+    invoices,
     key_manager_states,
     known_one_sided_payment_scripts,
+    multisig_sessions,
     outbound_transactions,
     outputs,
     scanned_blocks,
+    scheduled_payments,
+    utxo_consolidations,
     wallet_settings,
+    webhook_outbox,
 );
@@ -26,6 +26,7 @@ use std::{
 };
 
 use aes_gcm::Aes256Gcm;
+use chrono::NaiveDateTime;
 use log::*;
 use tari_common_types::chain_metadata::ChainMetadata;
 use tari_comms::{
@@ -35,7 +36,12 @@ use tari_comms::{
 };
 use tari_key_manager::cipher_seed::CipherSeed;
 
-use crate::{error::WalletStorageError, utxo_scanner_service::service::ScannedBlock};
+use crate::{
+    error::WalletStorageError,
+    invoice_service::invoice::Invoice,
+    output_manager_service::watch_only::WatchOnlyKeys,
+    utxo_scanner_service::service::ScannedBlock,
+};
 
 const LOG_TARGET: &str = "wallet::database";
 
@@ -62,6 +68,40 @@ pub trait WalletBackend: Send + Sync + Clone {
         height: u64,
         exclude_recovered: bool,
     ) -> Result<(), WalletStorageError>;
+
+    /// Queue a webhook payload for delivery to the given url
+    fn queue_webhook_delivery(&self, url: String, payload: String) -> Result<(), WalletStorageError>;
+    /// Fetch up to `limit` queued webhook deliveries whose next attempt is due at or before `now`
+    fn get_due_webhook_deliveries(
+        &self,
+        now: NaiveDateTime,
+        limit: usize,
+    ) -> Result<Vec<WebhookDelivery>, WalletStorageError>;
+    /// Record a failed webhook delivery attempt and schedule the next one
+    fn reschedule_webhook_delivery(
+        &self,
+        id: u32,
+        attempts: u32,
+        next_attempt_at: NaiveDateTime,
+    ) -> Result<(), WalletStorageError>;
+    /// Remove a webhook delivery from the outbox, either because it succeeded or it was abandoned
+    fn remove_webhook_delivery(&self, id: u32) -> Result<(), WalletStorageError>;
+
+    /// Fetch all invoices, newest first
+    fn get_invoices(&self) -> Result<Vec<Invoice>, WalletStorageError>;
+    /// Insert or update an invoice
+    fn save_invoice(&self, invoice: Invoice) -> Result<(), WalletStorageError>;
+}
+
+/// A webhook payload waiting in the outbox to be delivered to a url
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookDelivery {
+    pub id: u32,
+    pub url: String,
+    pub payload: String,
+    pub attempts: u32,
+    pub next_attempt_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -73,6 +113,7 @@ pub enum DbKey {
     BaseNodeChainMetadata,
     ClientKey(String),
     MasterSeed,
+    WatchOnlyKeys,
     PassphraseHash,
     EncryptionSalt,
     WalletBirthday,
@@ -87,6 +128,7 @@ pub enum DbValue {
     ValueCleared,
     BaseNodeChainMetadata(ChainMetadata),
     MasterSeed(CipherSeed),
+    WatchOnlyKeys(Box<WatchOnlyKeys>),
     PassphraseHash(String),
     EncryptionSalt(String),
     WalletBirthday(String),
@@ -98,6 +140,7 @@ pub enum DbKeyValuePair {
     TorId(TorIdentity),
     BaseNodeChainMetadata(ChainMetadata),
     MasterSeed(CipherSeed),
+    WatchOnlyKeys(Box<WatchOnlyKeys>),
     CommsAddress(Multiaddr),
     CommsFeatures(PeerFeatures),
     CommsIdentitySignature(Box<IdentitySignature>),
@@ -151,6 +194,31 @@ where T: WalletBackend + 'static
         Ok(())
     }
 
+    pub async fn get_watch_only_keys(&self) -> Result<Option<WatchOnlyKeys>, WalletStorageError> {
+        let db_clone = self.db.clone();
+
+        let c = tokio::task::spawn_blocking(move || match db_clone.fetch(&DbKey::WatchOnlyKeys) {
+            Ok(None) => Ok(None),
+            Ok(Some(DbValue::WatchOnlyKeys(k))) => Ok(Some(*k)),
+            Ok(Some(other)) => unexpected_result(DbKey::WatchOnlyKeys, other),
+            Err(e) => log_error(DbKey::WatchOnlyKeys, e),
+        })
+        .await
+        .map_err(|err| WalletStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(c)
+    }
+
+    pub async fn set_watch_only_keys(&self, keys: WatchOnlyKeys) -> Result<(), WalletStorageError> {
+        let db_clone = self.db.clone();
+
+        tokio::task::spawn_blocking(move || {
+            db_clone.write(WriteOperation::Insert(DbKeyValuePair::WatchOnlyKeys(Box::new(keys))))
+        })
+        .await
+        .map_err(|err| WalletStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(())
+    }
+
     pub async fn get_tor_id(&self) -> Result<Option<TorIdentity>, WalletStorageError> {
         let db_clone = self.db.clone();
 
@@ -427,12 +495,82 @@ where T: WalletBackend + 'static
 
         Ok(())
     }
+
+    pub async fn queue_webhook_delivery(&self, url: String, payload: String) -> Result<(), WalletStorageError> {
+        let db_clone = self.db.clone();
+
+        tokio::task::spawn_blocking(move || db_clone.queue_webhook_delivery(url, payload))
+            .await
+            .map_err(|err| WalletStorageError::BlockingTaskSpawnError(err.to_string()))??;
+
+        Ok(())
+    }
+
+    pub async fn get_due_webhook_deliveries(
+        &self,
+        now: NaiveDateTime,
+        limit: usize,
+    ) -> Result<Vec<WebhookDelivery>, WalletStorageError> {
+        let db_clone = self.db.clone();
+
+        let result = tokio::task::spawn_blocking(move || db_clone.get_due_webhook_deliveries(now, limit))
+            .await
+            .map_err(|err| WalletStorageError::BlockingTaskSpawnError(err.to_string()))??;
+
+        Ok(result)
+    }
+
+    pub async fn reschedule_webhook_delivery(
+        &self,
+        id: u32,
+        attempts: u32,
+        next_attempt_at: NaiveDateTime,
+    ) -> Result<(), WalletStorageError> {
+        let db_clone = self.db.clone();
+
+        tokio::task::spawn_blocking(move || db_clone.reschedule_webhook_delivery(id, attempts, next_attempt_at))
+            .await
+            .map_err(|err| WalletStorageError::BlockingTaskSpawnError(err.to_string()))??;
+
+        Ok(())
+    }
+
+    pub async fn remove_webhook_delivery(&self, id: u32) -> Result<(), WalletStorageError> {
+        let db_clone = self.db.clone();
+
+        tokio::task::spawn_blocking(move || db_clone.remove_webhook_delivery(id))
+            .await
+            .map_err(|err| WalletStorageError::BlockingTaskSpawnError(err.to_string()))??;
+
+        Ok(())
+    }
+
+    pub async fn get_invoices(&self) -> Result<Vec<Invoice>, WalletStorageError> {
+        let db_clone = self.db.clone();
+
+        let result = tokio::task::spawn_blocking(move || db_clone.get_invoices())
+            .await
+            .map_err(|err| WalletStorageError::BlockingTaskSpawnError(err.to_string()))??;
+
+        Ok(result)
+    }
+
+    pub async fn save_invoice(&self, invoice: Invoice) -> Result<(), WalletStorageError> {
+        let db_clone = self.db.clone();
+
+        tokio::task::spawn_blocking(move || db_clone.save_invoice(invoice))
+            .await
+            .map_err(|err| WalletStorageError::BlockingTaskSpawnError(err.to_string()))??;
+
+        Ok(())
+    }
 }
 
 impl Display for DbKey {
     fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
         match self {
             DbKey::MasterSeed => f.write_str("MasterSeed"),
+            DbKey::WatchOnlyKeys => f.write_str("WatchOnlyKeys"),
             DbKey::CommsAddress => f.write_str("CommsAddress"),
             DbKey::CommsFeatures => f.write_str("Nod features"),
             DbKey::TorId => f.write_str("TorId"),
@@ -450,6 +588,7 @@ impl Display for DbValue {
     fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
         match self {
             DbValue::MasterSeed(k) => f.write_str(&format!("MasterSeed: {:?}", k)),
+            DbValue::WatchOnlyKeys(k) => f.write_str(&format!("WatchOnlyKeys: {:?}", k)),
             DbValue::ClientValue(v) => f.write_str(&format!("ClientValue: {:?}", v)),
             DbValue::ValueCleared => f.write_str("ValueCleared"),
             DbValue::CommsFeatures(_) => f.write_str("Node features"),
@@ -482,15 +621,23 @@ fn unexpected_result<T>(req: DbKey, res: DbValue) -> Result<T, WalletStorageErro
 
 #[cfg(test)]
 mod test {
+    use chrono::{Duration, Utc};
+    use rand::rngs::OsRng;
+    use tari_common_types::{transaction::TxId, types::PublicKey};
+    use tari_core::transactions::tari_amount::MicroTari;
+    use tari_crypto::keys::PublicKey as PublicKeyTrait;
     use tari_key_manager::cipher_seed::CipherSeed;
     use tari_test_utils::random::string;
     use tempfile::tempdir;
     use tokio::runtime::Runtime;
 
-    use crate::storage::{
-        database::WalletDatabase,
-        sqlite_db::wallet::WalletSqliteDatabase,
-        sqlite_utilities::run_migration_and_create_sqlite_connection,
+    use crate::{
+        invoice_service::invoice::{Invoice, InvoiceStatus},
+        storage::{
+            database::WalletDatabase,
+            sqlite_db::wallet::WalletSqliteDatabase,
+            sqlite_utilities::run_migration_and_create_sqlite_connection,
+        },
     };
 
     #[test]
@@ -551,4 +698,86 @@ mod test {
             .block_on(db.clear_client_value(client_key_values[0].0.clone()))
             .unwrap());
     }
+
+    #[test]
+    fn test_webhook_outbox() {
+        let runtime = Runtime::new().unwrap();
+
+        let db_name = format!("{}.sqlite3", string(8).as_str());
+        let db_folder = tempdir().unwrap().path().to_str().unwrap().to_string();
+        let connection = run_migration_and_create_sqlite_connection(&format!("{}{}", db_folder, db_name), 16).unwrap();
+
+        let db = WalletDatabase::new(WalletSqliteDatabase::new(connection, None).unwrap());
+
+        runtime
+            .block_on(db.queue_webhook_delivery("http://a".to_string(), "{\"tx_id\":1}".to_string()))
+            .unwrap();
+        runtime
+            .block_on(db.queue_webhook_delivery("http://b".to_string(), "{\"tx_id\":2}".to_string()))
+            .unwrap();
+
+        let now = Utc::now().naive_utc() + Duration::seconds(1);
+        let due = runtime.block_on(db.get_due_webhook_deliveries(now, 10)).unwrap();
+        assert_eq!(due.len(), 2);
+        assert_eq!(due[0].url, "http://a");
+        assert_eq!(due[0].attempts, 0);
+
+        runtime
+            .block_on(db.reschedule_webhook_delivery(due[0].id, 1, now + Duration::minutes(5)))
+            .unwrap();
+        let due_now = runtime.block_on(db.get_due_webhook_deliveries(now, 10)).unwrap();
+        assert_eq!(due_now.len(), 1);
+        assert_eq!(due_now[0].url, "http://b");
+
+        let due_later = runtime
+            .block_on(db.get_due_webhook_deliveries(now + Duration::minutes(10), 10))
+            .unwrap();
+        assert_eq!(due_later.len(), 2);
+        assert_eq!(due_later[0].attempts, 1);
+
+        runtime.block_on(db.remove_webhook_delivery(due[1].id)).unwrap();
+        let remaining = runtime
+            .block_on(db.get_due_webhook_deliveries(now + Duration::minutes(10), 10))
+            .unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, due[0].id);
+    }
+
+    #[test]
+    fn test_invoice_persistence() {
+        let runtime = Runtime::new().unwrap();
+
+        let db_name = format!("{}.sqlite3", string(8).as_str());
+        let db_folder = tempdir().unwrap().path().to_str().unwrap().to_string();
+        let connection = run_migration_and_create_sqlite_connection(&format!("{}{}", db_folder, db_name), 16).unwrap();
+
+        let db = WalletDatabase::new(WalletSqliteDatabase::new(connection, None).unwrap());
+
+        let (_, recipient) = PublicKey::random_keypair(&mut OsRng);
+        let mut invoice = Invoice {
+            id: 1,
+            reference: "INV-1".to_string(),
+            amount: MicroTari::from(1000),
+            recipient,
+            message: "Order 1".to_string(),
+            status: InvoiceStatus::Pending,
+            tx_id: None,
+            created_at: Utc::now().naive_utc(),
+            expires_at: Some(Utc::now().naive_utc() + Duration::hours(1)),
+            paid_at: None,
+        };
+        runtime.block_on(db.save_invoice(invoice.clone())).unwrap();
+        let invoices = runtime.block_on(db.get_invoices()).unwrap();
+        assert_eq!(invoices.len(), 1);
+        assert_eq!(invoices[0].reference, invoice.reference);
+        assert_eq!(invoices[0].recipient, invoice.recipient);
+
+        invoice.status = InvoiceStatus::Paid;
+        invoice.tx_id = Some(TxId::from(42u64));
+        runtime.block_on(db.save_invoice(invoice)).unwrap();
+        let invoices = runtime.block_on(db.get_invoices()).unwrap();
+        assert_eq!(invoices.len(), 1);
+        assert_eq!(invoices[0].status, InvoiceStatus::Paid);
+        assert_eq!(invoices[0].tx_id, Some(TxId::from(42u64)));
+    }
 }
@@ -0,0 +1,102 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::convert::{TryFrom, TryInto};
+
+use chrono::NaiveDateTime;
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl, SqliteConnection};
+use tari_common_types::types::PublicKey;
+use tari_core::transactions::tari_amount::MicroTari;
+use tari_crypto::tari_utilities::ByteArray;
+
+use crate::{error::WalletStorageError, invoice_service::invoice::Invoice, schema::invoices};
+
+#[derive(Clone, Debug, Queryable, Insertable, PartialEq)]
+#[table_name = "invoices"]
+pub struct InvoiceSql {
+    id: i64,
+    reference: String,
+    amount: i64,
+    recipient: Vec<u8>,
+    message: String,
+    status: i32,
+    tx_id: Option<i64>,
+    created_at: NaiveDateTime,
+    expires_at: Option<NaiveDateTime>,
+    paid_at: Option<NaiveDateTime>,
+}
+
+impl InvoiceSql {
+    pub fn index(conn: &SqliteConnection) -> Result<Vec<InvoiceSql>, WalletStorageError> {
+        Ok(invoices::table
+            .order(invoices::created_at.desc())
+            .load::<InvoiceSql>(conn)?)
+    }
+
+    /// Insert the invoice, replacing any existing invoice with the same id
+    pub fn upsert(&self, conn: &SqliteConnection) -> Result<(), WalletStorageError> {
+        diesel::replace_into(invoices::table)
+            .values(self.clone())
+            .execute(conn)?;
+        Ok(())
+    }
+}
+
+impl From<Invoice> for InvoiceSql {
+    fn from(i: Invoice) -> Self {
+        Self {
+            id: i.id as i64,
+            reference: i.reference,
+            amount: i.amount.as_u64() as i64,
+            recipient: i.recipient.to_vec(),
+            message: i.message,
+            status: i.status.into(),
+            tx_id: i.tx_id.map(|id| id.as_u64() as i64),
+            created_at: i.created_at,
+            expires_at: i.expires_at,
+            paid_at: i.paid_at,
+        }
+    }
+}
+
+impl TryFrom<InvoiceSql> for Invoice {
+    type Error = WalletStorageError;
+
+    fn try_from(i: InvoiceSql) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: i.id as u64,
+            reference: i.reference,
+            amount: MicroTari::from(i.amount as u64),
+            recipient: PublicKey::from_bytes(&i.recipient)
+                .map_err(|_| WalletStorageError::ConversionError("Invalid invoice recipient".to_string()))?,
+            message: i.message,
+            status: i
+                .status
+                .try_into()
+                .map_err(|_| WalletStorageError::ConversionError("Invalid invoice status".to_string()))?,
+            tx_id: i.tx_id.map(|id| (id as u64).into()),
+            created_at: i.created_at,
+            expires_at: i.expires_at,
+            paid_at: i.paid_at,
+        })
+    }
+}
@@ -21,6 +21,7 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use std::{
+    convert::TryFrom,
     str::{from_utf8, FromStr},
     sync::{Arc, RwLock},
 };
@@ -34,6 +35,7 @@ use argon2::{
     password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
     Argon2,
 };
+use chrono::NaiveDateTime;
 use diesel::{prelude::*, SqliteConnection};
 use log::*;
 use tari_common_types::chain_metadata::ChainMetadata;
@@ -51,10 +53,16 @@ use tokio::time::Instant;
 
 use crate::{
     error::WalletStorageError,
+    invoice_service::invoice::Invoice,
+    output_manager_service::watch_only::WatchOnlyKeys,
     schema::{client_key_values, wallet_settings},
     storage::{
-        database::{DbKey, DbKeyValuePair, DbValue, WalletBackend, WriteOperation},
-        sqlite_db::scanned_blocks::ScannedBlockSql,
+        database::{DbKey, DbKeyValuePair, DbValue, WalletBackend, WebhookDelivery, WriteOperation},
+        sqlite_db::{
+            invoices::InvoiceSql,
+            scanned_blocks::ScannedBlockSql,
+            webhook_outbox::{NewWebhookDeliverySql, WebhookDeliverySql},
+        },
         sqlite_utilities::wallet_db_connection::WalletDbConnection,
     },
     util::encryption::{decrypt_bytes_integral_nonce, encrypt_bytes_integral_nonce, Encryptable, AES_NONCE_BYTES},
@@ -121,6 +129,50 @@ impl WalletSqliteDatabase {
         }
     }
 
+    fn set_watch_only_keys(&self, keys: &WatchOnlyKeys, conn: &SqliteConnection) -> Result<(), WalletStorageError> {
+        let cipher = acquire_read_lock!(self.cipher);
+        match cipher.as_ref() {
+            None => {
+                let keys_string = keys
+                    .to_json()
+                    .map_err(|e| WalletStorageError::ConversionError(e.to_string()))?;
+                WalletSettingSql::new(DbKey::WatchOnlyKeys.to_string(), keys_string).set(conn)?;
+            },
+            Some(cipher) => {
+                let bytes = bincode::serialize(keys).map_err(|e| WalletStorageError::ConversionError(e.to_string()))?;
+                let ciphertext_integral_nonce = encrypt_bytes_integral_nonce(cipher, bytes)
+                    .map_err(|e| WalletStorageError::AeadError(format!("Encryption Error:{}", e)))?;
+                WalletSettingSql::new(DbKey::WatchOnlyKeys.to_string(), ciphertext_integral_nonce.to_hex())
+                    .set(conn)?;
+            },
+        }
+        // The rewind keys carry no birthday, so a watch-only wallet has to scan the chain from genesis
+        if WalletSettingSql::get(DbKey::WalletBirthday.to_string(), conn)?.is_none() {
+            WalletSettingSql::new(DbKey::WalletBirthday.to_string(), "0".to_string()).set(conn)?;
+        }
+
+        Ok(())
+    }
+
+    fn get_watch_only_keys(&self, conn: &SqliteConnection) -> Result<Option<WatchOnlyKeys>, WalletStorageError> {
+        let cipher = acquire_read_lock!(self.cipher);
+        if let Some(key_str) = WalletSettingSql::get(DbKey::WatchOnlyKeys.to_string(), conn)? {
+            let keys = match cipher.as_ref() {
+                None => WatchOnlyKeys::from_json(&key_str)
+                    .map_err(|e| WalletStorageError::ConversionError(e.to_string()))?,
+                Some(cipher) => {
+                    let decrypted_key_bytes = decrypt_bytes_integral_nonce(cipher, from_hex(&key_str)?)
+                        .map_err(|e| WalletStorageError::AeadError(format!("Decryption Error:{}", e)))?;
+                    bincode::deserialize(&decrypted_key_bytes)
+                        .map_err(|e| WalletStorageError::ConversionError(e.to_string()))?
+                },
+            };
+            Ok(Some(keys))
+        } else {
+            Ok(None)
+        }
+    }
+
     fn decrypt_if_necessary<T: Encryptable<Aes256Gcm>>(&self, o: &mut T) -> Result<(), WalletStorageError> {
         let cipher = acquire_read_lock!(self.cipher);
         if let Some(cipher) = cipher.as_ref() {
@@ -226,6 +278,10 @@ impl WalletSqliteDatabase {
                 kvp_text = "MasterSeed";
                 self.set_master_seed(&seed, &(*conn))?;
             },
+            DbKeyValuePair::WatchOnlyKeys(keys) => {
+                kvp_text = "WatchOnlyKeys";
+                self.set_watch_only_keys(&keys, &(*conn))?;
+            },
             DbKeyValuePair::TorId(node_id) => {
                 kvp_text = "TorId";
                 self.set_tor_id(node_id, &(*conn))?;
@@ -297,6 +353,9 @@ impl WalletSqliteDatabase {
             DbKey::MasterSeed => {
                 let _ = WalletSettingSql::clear(DbKey::MasterSeed.to_string(), &conn)?;
             },
+            DbKey::WatchOnlyKeys => {
+                let _ = WalletSettingSql::clear(DbKey::WatchOnlyKeys.to_string(), &conn)?;
+            },
             DbKey::ClientKey(ref k) => {
                 if ClientKeyValueSql::clear(k, &conn)? {
                     return Ok(Some(DbValue::ValueCleared));
@@ -342,6 +401,9 @@ impl WalletBackend for WalletSqliteDatabase {
 
         let result = match key {
             DbKey::MasterSeed => self.get_master_seed(&conn)?.map(DbValue::MasterSeed),
+            DbKey::WatchOnlyKeys => self
+                .get_watch_only_keys(&conn)?
+                .map(|keys| DbValue::WatchOnlyKeys(Box::new(keys))),
             DbKey::ClientKey(k) => match ClientKeyValueSql::get(k, &conn)? {
                 None => None,
                 Some(mut v) => {
@@ -420,16 +482,29 @@ impl WalletBackend for WalletSqliteDatabase {
         WalletSettingSql::new(DbKey::PassphraseHash.to_string(), passphrase_hash).set(&conn)?;
         WalletSettingSql::new(DbKey::EncryptionSalt.to_string(), encryption_salt.as_str().to_string()).set(&conn)?;
 
-        let master_seed_str = match WalletSettingSql::get(DbKey::MasterSeed.to_string(), &conn)? {
+        let watch_only_keys = WalletSettingSql::get(DbKey::WatchOnlyKeys.to_string(), &conn)?;
+        match WalletSettingSql::get(DbKey::MasterSeed.to_string(), &conn)? {
+            Some(master_seed_str) => {
+                let master_seed_bytes = from_hex(master_seed_str.as_str())?;
+                // Sanity check that the decrypted bytes are a valid CipherSeed
+                let _master_seed = CipherSeed::from_enciphered_bytes(&master_seed_bytes, None)?;
+                let ciphertext_integral_nonce = encrypt_bytes_integral_nonce(&cipher, master_seed_bytes)
+                    .map_err(|e| WalletStorageError::AeadError(format!("Encryption Error:{}", e)))?;
+                WalletSettingSql::new(DbKey::MasterSeed.to_string(), ciphertext_integral_nonce.to_hex()).set(&conn)?;
+            },
+            // A watch-only wallet does not have a master seed
+            None if watch_only_keys.is_some() => {},
             None => return Err(WalletStorageError::ValueNotFound(DbKey::MasterSeed)),
-            Some(sk) => sk,
-        };
-        let master_seed_bytes = from_hex(master_seed_str.as_str())?;
-        // Sanity check that the decrypted bytes are a valid CipherSeed
-        let _master_seed = CipherSeed::from_enciphered_bytes(&master_seed_bytes, None)?;
-        let ciphertext_integral_nonce = encrypt_bytes_integral_nonce(&cipher, master_seed_bytes)
-            .map_err(|e| WalletStorageError::AeadError(format!("Encryption Error:{}", e)))?;
-        WalletSettingSql::new(DbKey::MasterSeed.to_string(), ciphertext_integral_nonce.to_hex()).set(&conn)?;
+        }
+
+        // Encrypt the watch-only keys if present
+        if let Some(v) = watch_only_keys {
+            let keys = WatchOnlyKeys::from_json(&v).map_err(|e| WalletStorageError::ConversionError(e.to_string()))?;
+            let bytes = bincode::serialize(&keys).map_err(|e| WalletStorageError::ConversionError(e.to_string()))?;
+            let ciphertext_integral_nonce = encrypt_bytes_integral_nonce(&cipher, bytes)
+                .map_err(|e| WalletStorageError::AeadError(format!("Encryption Error:{}", e)))?;
+            WalletSettingSql::new(DbKey::WatchOnlyKeys.to_string(), ciphertext_integral_nonce.to_hex()).set(&conn)?;
+        }
 
         // Encrypt all the client values
         let mut client_key_values = ClientKeyValueSql::index(&conn)?;
@@ -473,16 +548,31 @@ impl WalletBackend for WalletSqliteDatabase {
         let start = Instant::now();
         let conn = self.database_connection.get_pooled_connection()?;
         let acquire_lock = start.elapsed();
-        let master_seed_str = match WalletSettingSql::get(DbKey::MasterSeed.to_string(), &conn)? {
+        let watch_only_keys = WalletSettingSql::get(DbKey::WatchOnlyKeys.to_string(), &conn)?;
+        match WalletSettingSql::get(DbKey::MasterSeed.to_string(), &conn)? {
+            Some(master_seed_str) => {
+                let master_seed_bytes = decrypt_bytes_integral_nonce(&cipher, from_hex(master_seed_str.as_str())?)
+                    .map_err(|e| WalletStorageError::AeadError(format!("Decryption Error:{}", e)))?;
+                // Sanity check that the decrypted bytes are a valid CipherSeed
+                let _master_seed = CipherSeed::from_enciphered_bytes(&master_seed_bytes, None)?;
+                WalletSettingSql::new(DbKey::MasterSeed.to_string(), master_seed_bytes.to_hex()).set(&conn)?;
+            },
+            // A watch-only wallet does not have a master seed
+            None if watch_only_keys.is_some() => {},
             None => return Err(WalletStorageError::ValueNotFound(DbKey::MasterSeed)),
-            Some(sk) => sk,
-        };
+        }
 
-        let master_seed_bytes = decrypt_bytes_integral_nonce(&cipher, from_hex(master_seed_str.as_str())?)
-            .map_err(|e| WalletStorageError::AeadError(format!("Decryption Error:{}", e)))?;
-        // Sanity check that the decrypted bytes are a valid CipherSeed
-        let _master_seed = CipherSeed::from_enciphered_bytes(&master_seed_bytes, None)?;
-        WalletSettingSql::new(DbKey::MasterSeed.to_string(), master_seed_bytes.to_hex()).set(&conn)?;
+        // Remove the watch-only keys encryption if present
+        if let Some(v) = watch_only_keys {
+            let decrypted_key_bytes = decrypt_bytes_integral_nonce(&cipher, from_hex(v.as_str())?)
+                .map_err(|e| WalletStorageError::AeadError(format!("Decryption Error:{}", e)))?;
+            let keys: WatchOnlyKeys = bincode::deserialize(&decrypted_key_bytes)
+                .map_err(|e| WalletStorageError::ConversionError(e.to_string()))?;
+            let keys_string = keys
+                .to_json()
+                .map_err(|e| WalletStorageError::ConversionError(e.to_string()))?;
+            WalletSettingSql::new(DbKey::WatchOnlyKeys.to_string(), keys_string).set(&conn)?;
+        }
 
         let _ = WalletSettingSql::clear(DbKey::PassphraseHash.to_string(), &conn)?;
         let _ = WalletSettingSql::clear(DbKey::EncryptionSalt.to_string(), &conn)?;
@@ -551,6 +641,46 @@ impl WalletBackend for WalletSqliteDatabase {
         let conn = self.database_connection.get_pooled_connection()?;
         ScannedBlockSql::clear_before_height(height, exclude_recovered, &conn)
     }
+
+    fn queue_webhook_delivery(&self, url: String, payload: String) -> Result<(), WalletStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        NewWebhookDeliverySql::new(url, payload).commit(&conn)
+    }
+
+    fn get_due_webhook_deliveries(
+        &self,
+        now: NaiveDateTime,
+        limit: usize,
+    ) -> Result<Vec<WebhookDelivery>, WalletStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        WebhookDeliverySql::index_due(now, limit as i64, &conn)
+            .map(|deliveries| deliveries.into_iter().map(WebhookDelivery::from).collect())
+    }
+
+    fn reschedule_webhook_delivery(
+        &self,
+        id: u32,
+        attempts: u32,
+        next_attempt_at: NaiveDateTime,
+    ) -> Result<(), WalletStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        WebhookDeliverySql::reschedule(id as i32, attempts, next_attempt_at, &conn)
+    }
+
+    fn remove_webhook_delivery(&self, id: u32) -> Result<(), WalletStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        WebhookDeliverySql::delete(id as i32, &conn)
+    }
+
+    fn get_invoices(&self) -> Result<Vec<Invoice>, WalletStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        InvoiceSql::index(&conn)?.into_iter().map(Invoice::try_from).collect()
+    }
+
+    fn save_invoice(&self, invoice: Invoice) -> Result<(), WalletStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        InvoiceSql::from(invoice).upsert(&conn)
+    }
 }
 
 /// Confirm if database is encrypted or not and if a cipher is provided confirm the cipher is correct.
@@ -598,8 +728,9 @@ fn check_db_encryption_status(
     };
 
     let secret_seed = WalletSettingSql::get(DbKey::MasterSeed.to_string(), &conn)?;
+    let watch_only_keys = WalletSettingSql::get(DbKey::WatchOnlyKeys.to_string(), &conn)?;
 
-    if cipher.is_some() && secret_seed.is_none() {
+    if cipher.is_some() && secret_seed.is_none() && watch_only_keys.is_none() {
         error!(
             target: LOG_TARGET,
             "Cipher is provided but there is no Master Secret Key in DB to decrypt"
@@ -651,6 +782,38 @@ fn check_db_encryption_status(
             },
         };
     }
+
+    // A watch-only wallet stores its keys in the clear as JSON, so if they do not parse they must be encrypted
+    if let Some(keys) = watch_only_keys {
+        match (WatchOnlyKeys::from_json(&keys).is_ok(), cipher.as_ref()) {
+            (true, Some(_)) => {
+                error!(
+                    target: LOG_TARGET,
+                    "Cipher is provided but Watch-only Keys are not encrypted"
+                );
+                return Err(WalletStorageError::InvalidEncryptionCipher);
+            },
+            (false, Some(cipher_inner)) => {
+                let decrypted_keys =
+                    decrypt_bytes_integral_nonce(cipher_inner, from_hex(keys.as_str())?).map_err(|e| {
+                        error!(target: LOG_TARGET, "Incorrect passphrase ({})", e);
+                        WalletStorageError::InvalidPassphrase
+                    })?;
+                let _: WatchOnlyKeys = bincode::deserialize(&decrypted_keys).map_err(|_| {
+                    error!(target: LOG_TARGET, "Decrypted Watch-only Keys cannot be parsed");
+                    WalletStorageError::InvalidEncryptionCipher
+                })?;
+            },
+            (false, None) => {
+                error!(
+                    target: LOG_TARGET,
+                    "Cipher was not provided but Watch-only Keys are encrypted"
+                );
+                return Err(WalletStorageError::NoPasswordError);
+            },
+            (true, None) => {},
+        }
+    }
     if start.elapsed().as_millis() > 0 {
         trace!(
             target: LOG_TARGET,
@@ -768,15 +931,20 @@ impl Encryptable<Aes256Gcm> for ClientKeyValueSql {
 
 #[cfg(test)]
 mod test {
-    use tari_crypto::tari_utilities::hex::Hex;
+    use rand::rngs::OsRng;
+    use tari_common_types::types::PrivateKey;
+    use tari_crypto::{keys::SecretKey, tari_utilities::hex::Hex};
     use tari_key_manager::cipher_seed::CipherSeed;
     use tari_test_utils::random::string;
     use tempfile::tempdir;
 
-    use crate::storage::{
-        database::{DbKey, DbValue, WalletBackend},
-        sqlite_db::wallet::{ClientKeyValueSql, WalletSettingSql, WalletSqliteDatabase},
-        sqlite_utilities::run_migration_and_create_sqlite_connection,
+    use crate::{
+        output_manager_service::watch_only::WatchOnlyKeys,
+        storage::{
+            database::{DbKey, DbValue, WalletBackend},
+            sqlite_db::wallet::{ClientKeyValueSql, WalletSettingSql, WalletSqliteDatabase},
+            sqlite_utilities::run_migration_and_create_sqlite_connection,
+        },
     };
 
     #[test]
@@ -934,6 +1102,39 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_watch_only_keys_encryption() {
+        let db_name = format!("{}.sqlite3", string(8).as_str());
+        let db_tempdir = tempdir().unwrap();
+        let db_folder = db_tempdir.path().to_str().unwrap().to_string();
+        let connection = run_migration_and_create_sqlite_connection(&format!("{}{}", db_folder, db_name), 16).unwrap();
+
+        let keys = WatchOnlyKeys::new(PrivateKey::random(&mut OsRng), PrivateKey::random(&mut OsRng), None);
+        let passphrase = "an example very very secret key.".to_string();
+        {
+            let db = WalletSqliteDatabase::new(connection.clone(), None).unwrap();
+            let conn = connection.get_pooled_connection().unwrap();
+            db.set_watch_only_keys(&keys, &conn).unwrap();
+            // There is no master seed but encryption can still be applied to a watch-only wallet
+            db.apply_encryption(passphrase.clone()).unwrap();
+            match db.fetch(&DbKey::WatchOnlyKeys).unwrap().unwrap() {
+                DbValue::WatchOnlyKeys(k) => assert_eq!(*k, keys),
+                _ => panic!("Should be able to read Watch-only Keys"),
+            }
+        }
+
+        assert!(WalletSqliteDatabase::new(connection.clone(), None).is_err());
+        assert!(WalletSqliteDatabase::new(connection.clone(), Some("wrong passphrase".to_string())).is_err());
+        let db = WalletSqliteDatabase::new(connection.clone(), Some(passphrase)).unwrap();
+
+        db.remove_encryption().unwrap();
+        let db = WalletSqliteDatabase::new(connection, None).unwrap();
+        match db.fetch(&DbKey::WatchOnlyKeys).unwrap().unwrap() {
+            DbValue::WatchOnlyKeys(k) => assert_eq!(*k, keys),
+            _ => panic!("Should be able to read Watch-only Keys"),
+        }
+    }
+
     #[test]
     fn test_client_key_value_store() {
         let db_name = format!("{}.sqlite3", string(8).as_str());
@@ -0,0 +1,118 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use chrono::{NaiveDateTime, Utc};
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl, SqliteConnection};
+
+use crate::{error::WalletStorageError, schema::webhook_outbox, storage::database::WebhookDelivery};
+
+/// A webhook delivery that has not yet been written to the database and therefore has no id
+#[derive(Clone, Debug, Insertable, PartialEq)]
+#[table_name = "webhook_outbox"]
+pub struct NewWebhookDeliverySql {
+    url: String,
+    payload: String,
+    attempts: i32,
+    next_attempt_at: NaiveDateTime,
+    created_at: NaiveDateTime,
+}
+
+impl NewWebhookDeliverySql {
+    pub fn new(url: String, payload: String) -> Self {
+        let now = Utc::now().naive_utc();
+        Self {
+            url,
+            payload,
+            attempts: 0,
+            next_attempt_at: now,
+            created_at: now,
+        }
+    }
+
+    pub fn commit(&self, conn: &SqliteConnection) -> Result<(), WalletStorageError> {
+        diesel::insert_into(webhook_outbox::table)
+            .values(self.clone())
+            .execute(conn)?;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Queryable, PartialEq)]
+pub struct WebhookDeliverySql {
+    id: i32,
+    url: String,
+    payload: String,
+    attempts: i32,
+    next_attempt_at: NaiveDateTime,
+    created_at: NaiveDateTime,
+}
+
+impl WebhookDeliverySql {
+    /// Return the deliveries that are due at `now`, oldest first
+    pub fn index_due(
+        now: NaiveDateTime,
+        limit: i64,
+        conn: &SqliteConnection,
+    ) -> Result<Vec<WebhookDeliverySql>, WalletStorageError> {
+        Ok(webhook_outbox::table
+            .filter(webhook_outbox::next_attempt_at.le(now))
+            .order(webhook_outbox::id.asc())
+            .limit(limit)
+            .load::<WebhookDeliverySql>(conn)?)
+    }
+
+    pub fn reschedule(
+        id: i32,
+        attempts: u32,
+        next_attempt_at: NaiveDateTime,
+        conn: &SqliteConnection,
+    ) -> Result<(), WalletStorageError> {
+        let num_updated = diesel::update(webhook_outbox::table.filter(webhook_outbox::id.eq(id)))
+            .set((
+                webhook_outbox::attempts.eq(attempts as i32),
+                webhook_outbox::next_attempt_at.eq(next_attempt_at),
+            ))
+            .execute(conn)?;
+        if num_updated == 0 {
+            return Err(WalletStorageError::ValuesNotFound);
+        }
+        Ok(())
+    }
+
+    pub fn delete(id: i32, conn: &SqliteConnection) -> Result<(), WalletStorageError> {
+        diesel::delete(webhook_outbox::table.filter(webhook_outbox::id.eq(id))).execute(conn)?;
+        Ok(())
+    }
+}
+
+impl From<WebhookDeliverySql> for WebhookDelivery {
+    fn from(d: WebhookDeliverySql) -> Self {
+        Self {
+            id: d.id as u32,
+            url: d.url,
+            payload: d.payload,
+            attempts: d.attempts as u32,
+            next_attempt_at: d.next_attempt_at,
+            created_at: d.created_at,
+        }
+    }
+}
@@ -51,6 +51,10 @@ pub enum TransactionServiceError {
     InvalidStateError,
     #[error("One-sided transaction error: `{0}`")]
     OneSidedTransactionError(String),
+    #[error("Multisig session `{0}` not found")]
+    MultisigSessionNotFound(u64),
+    #[error("Multisig error: `{0}`")]
+    MultisigError(String),
     #[error("Transaction Protocol Error: `{0}`")]
     TransactionProtocolError(#[from] TransactionProtocolError),
     #[error("The message being processed is not recognized by the Transaction Manager")]
@@ -63,6 +67,14 @@ pub enum TransactionServiceError {
     OutboundMessageServiceNotInitialized,
     #[error("Received an unexpected API response")]
     UnexpectedApiResponse,
+    #[error("Account requests cannot be nested")]
+    NestedAccountRequest,
+    #[error("Scheduled payment `{0}` not found")]
+    ScheduledPaymentNotFound(u64),
+    #[error("Scheduled payment `{0}` is no longer active")]
+    ScheduledPaymentNotActive(u64),
+    #[error("Invalid scheduled payment: `{0}`")]
+    InvalidScheduledPayment(String),
     #[error("Failed to send from API")]
     ApiSendFailed,
     #[error("Failed to receive in API from service")]
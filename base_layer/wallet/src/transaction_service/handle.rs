@@ -28,9 +28,12 @@ use tari_common_types::{
     types::PublicKey,
 };
 use tari_comms::types::CommsPublicKey;
-use tari_core::transactions::{
-    tari_amount::MicroTari,
-    transaction_components::{Transaction, TransactionOutput},
+use tari_core::{
+    covenants::Covenant,
+    transactions::{
+        tari_amount::MicroTari,
+        transaction_components::{Transaction, TransactionOutput},
+    },
 };
 use tari_service_framework::reply_channel::SenderService;
 use tari_utilities::hex::Hex;
@@ -38,12 +41,20 @@ use tokio::sync::broadcast;
 use tower::Service;
 
 use crate::{
+    output_manager_service::{
+        service::UTXOSelectionStrategy,
+        watch_only::{OfflineSignedTransaction, UnsignedTransactionTemplate},
+    },
     transaction_service::{
         error::TransactionServiceError,
         storage::models::{
+            BatchPayment,
             CompletedTransaction,
             InboundTransaction,
+            MultisigSession,
             OutboundTransaction,
+            PaymentTrigger,
+            ScheduledPayment,
             TxCancellationReason,
             WalletTransaction,
         },
@@ -51,6 +62,14 @@ use crate::{
     OperationId,
 };
 
+/// A single payment within a one-sided batch transaction
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchPaymentRecipient {
+    pub destination: CommsPublicKey,
+    pub amount: MicroTari,
+    pub message: String,
+}
+
 /// API Request enum
 #[allow(clippy::large_enum_variant)]
 pub enum TransactionServiceRequest {
@@ -69,6 +88,8 @@ pub enum TransactionServiceRequest {
         parent_public_key: Option<PublicKey>,
         fee_per_gram: MicroTari,
         message: String,
+        covenant: Covenant,
+        selection_strategy: Option<UTXOSelectionStrategy>,
     },
     SendOneSidedTransaction {
         dest_pubkey: CommsPublicKey,
@@ -77,8 +98,32 @@ pub enum TransactionServiceRequest {
         parent_public_key: Option<PublicKey>,
         fee_per_gram: MicroTari,
         message: String,
+        covenant: Covenant,
+        selection_strategy: Option<UTXOSelectionStrategy>,
+    },
+    SendOneSidedBatchTransaction {
+        recipients: Vec<BatchPaymentRecipient>,
+        fee_per_gram: MicroTari,
+        message: String,
     },
     SendShaAtomicSwapTransaction(CommsPublicKey, MicroTari, MicroTari, String),
+    SignOfflineTransaction(Box<UnsignedTransactionTemplate>),
+    SubmitOfflineTransaction(Box<OfflineSignedTransaction>),
+    CreateMultisigOutput {
+        cosigners: Vec<CommsPublicKey>,
+        threshold: u8,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+    },
+    ProposeMultisigSpend {
+        session_id: u64,
+        destination: CommsPublicKey,
+        fee_per_gram: MicroTari,
+        message: String,
+    },
+    ApproveMultisigSpend(u64),
+    GetMultisigSessions,
     CancelTransaction(TxId),
     ImportUtxoWithStatus {
         amount: MicroTari,
@@ -101,6 +146,43 @@ pub enum TransactionServiceRequest {
     SetNumConfirmationsRequired(u64),
     ValidateTransactions,
     ReValidateTransactions,
+    TransferBetweenAccounts {
+        to_account: u64,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+    },
+    /// Send a one-sided payment whose output the recipient cannot spend before `lock_height`
+    SendTimeLockedTransaction {
+        dest_pubkey: CommsPublicKey,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+        lock_height: u64,
+    },
+    SchedulePayment(Box<ScheduledPaymentRequest>),
+    GetScheduledPayments,
+    CancelScheduledPayment(u64),
+    GetBatchPayments(TxId),
+    /// Handle the wrapped request on behalf of the given account rather than the whole wallet
+    ForAccount(u64, Box<TransactionServiceRequest>),
+}
+
+/// The details of a payment to be made once `first_payment` is due
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduledPaymentRequest {
+    pub destination: CommsPublicKey,
+    pub amount: MicroTari,
+    pub fee_per_gram: MicroTari,
+    pub message: String,
+    pub one_sided: bool,
+    /// The height before which the recipient cannot spend the payment. Requires a one-sided payment.
+    pub recipient_lock_height: Option<u64>,
+    pub first_payment: PaymentTrigger,
+    /// Repeat the payment every `repeat_interval` blocks or seconds, depending on the trigger
+    pub repeat_interval: Option<u64>,
+    /// The total number of payments to make, or `None` to repeat until cancelled
+    pub num_payments: Option<u64>,
 }
 
 impl fmt::Display for TransactionServiceRequest {
@@ -135,9 +217,47 @@ impl fmt::Display for TransactionServiceRequest {
                 amount,
                 message
             )),
+            Self::SendOneSidedBatchTransaction {
+                recipients, message, ..
+            } => f.write_str(&format!(
+                "SendOneSidedBatchTransaction ({} recipients, {})",
+                recipients.len(),
+                message
+            )),
             Self::SendShaAtomicSwapTransaction(k, v, _, msg) => {
                 f.write_str(&format!("SendShaAtomicSwapTransaction (to {}, {}, {})", k, v, msg))
             },
+            Self::SignOfflineTransaction(template) => f.write_str(&format!(
+                "SignOfflineTransaction ({}, to {}, {})",
+                template.tx_id,
+                template.destination.to_hex(),
+                template.amount
+            )),
+            Self::SubmitOfflineTransaction(signed) => {
+                f.write_str(&format!("SubmitOfflineTransaction ({})", signed.tx_id))
+            },
+            Self::CreateMultisigOutput {
+                cosigners,
+                threshold,
+                amount,
+                ..
+            } => f.write_str(&format!(
+                "CreateMultisigOutput ({}-of-{}, {})",
+                threshold,
+                cosigners.len() + 1,
+                amount
+            )),
+            Self::ProposeMultisigSpend {
+                session_id,
+                destination,
+                ..
+            } => f.write_str(&format!(
+                "ProposeMultisigSpend ({}, to {})",
+                session_id,
+                destination.to_hex()
+            )),
+            Self::ApproveMultisigSpend(session_id) => f.write_str(&format!("ApproveMultisigSpend ({})", session_id)),
+            Self::GetMultisigSessions => f.write_str("GetMultisigSessions"),
             Self::CancelTransaction(t) => f.write_str(&format!("CancelTransaction ({})", t)),
             Self::ImportUtxoWithStatus {
                 amount,
@@ -172,6 +292,31 @@ impl fmt::Display for TransactionServiceRequest {
             Self::GetAnyTransaction(t) => f.write_str(&format!("GetAnyTransaction({})", t)),
             TransactionServiceRequest::ValidateTransactions => f.write_str("ValidateTransactions"),
             TransactionServiceRequest::ReValidateTransactions => f.write_str("ReValidateTransactions"),
+            Self::TransferBetweenAccounts { to_account, amount, .. } => f.write_str(&format!(
+                "TransferBetweenAccounts ({} to account {})",
+                amount, to_account
+            )),
+            Self::SendTimeLockedTransaction {
+                dest_pubkey,
+                amount,
+                lock_height,
+                ..
+            } => f.write_str(&format!(
+                "SendTimeLockedTransaction (to {}, {}, locked until {})",
+                dest_pubkey.to_hex(),
+                amount,
+                lock_height
+            )),
+            Self::SchedulePayment(request) => f.write_str(&format!(
+                "SchedulePayment (to {}, {}, at {})",
+                request.destination.to_hex(),
+                request.amount,
+                request.first_payment
+            )),
+            Self::GetScheduledPayments => f.write_str("GetScheduledPayments"),
+            Self::CancelScheduledPayment(id) => f.write_str(&format!("CancelScheduledPayment ({})", id)),
+            Self::GetBatchPayments(tx_id) => f.write_str(&format!("GetBatchPayments ({})", tx_id)),
+            Self::ForAccount(account_id, request) => f.write_str(&format!("{} (account {})", request, account_id)),
         }
     }
 }
@@ -200,6 +345,15 @@ pub enum TransactionServiceResponse {
     ValidationStarted(OperationId),
     CompletedTransactionValidityChanged,
     ShaAtomicSwapTransactionSent(Box<(TxId, PublicKey, TransactionOutput)>),
+    OfflineTransactionSigned(Box<OfflineSignedTransaction>),
+    MultisigSessionCreated(u64),
+    MultisigSpendProposed,
+    MultisigSpendApproved,
+    MultisigSessions(Vec<MultisigSession>),
+    PaymentScheduled(u64),
+    ScheduledPayments(Vec<ScheduledPayment>),
+    ScheduledPaymentCancelled,
+    BatchPayments(Vec<BatchPayment>),
 }
 
 /// Events that can be published on the Text Message Service Event Stream
@@ -238,6 +392,7 @@ pub enum TransactionEvent {
     TransactionValidationStateChanged(OperationId),
     TransactionValidationCompleted(OperationId),
     TransactionValidationFailed(OperationId),
+    MultisigSessionUpdated(u64),
     Error(String),
 }
 
@@ -320,6 +475,89 @@ impl fmt::Display for TransactionEvent {
             TransactionEvent::TransactionValidationFailed(operation_id) => {
                 write!(f, "Transaction validation failed: {}", operation_id)
             },
+            TransactionEvent::MultisigSessionUpdated(session_id) => {
+                write!(f, "Multisig session updated: {}", session_id)
+            },
+        }
+    }
+}
+
+impl TransactionEvent {
+    /// A stable, snake_case name for the event suitable for external consumers
+    pub fn name(&self) -> &'static str {
+        use TransactionEvent::*;
+        match self {
+            MempoolBroadcastTimedOut(_) => "mempool_broadcast_timed_out",
+            ReceivedTransaction(_) => "received_transaction",
+            ReceivedTransactionReply(_) => "received_transaction_reply",
+            ReceivedFinalizedTransaction(_) => "received_finalized_transaction",
+            TransactionDiscoveryInProgress(_) => "transaction_discovery_in_progress",
+            TransactionDirectSendResult(_, _) => "transaction_direct_send_result",
+            TransactionCompletedImmediately(_) => "transaction_completed_immediately",
+            TransactionStoreForwardSendResult(_, _) => "transaction_store_forward_send_result",
+            TransactionCancelled(_, _) => "transaction_cancelled",
+            TransactionBroadcast(_) => "transaction_broadcast",
+            TransactionImported(_) => "transaction_imported",
+            FauxTransactionUnconfirmed { .. } => "faux_transaction_unconfirmed",
+            FauxTransactionConfirmed { .. } => "faux_transaction_confirmed",
+            TransactionMined { .. } => "transaction_mined",
+            TransactionMinedRequestTimedOut(_) => "transaction_mined_request_timed_out",
+            TransactionMinedUnconfirmed { .. } => "transaction_mined_unconfirmed",
+            TransactionValidationStateChanged(_) => "transaction_validation_state_changed",
+            TransactionValidationCompleted(_) => "transaction_validation_completed",
+            TransactionValidationFailed(_) => "transaction_validation_failed",
+            MultisigSessionUpdated(_) => "multisig_session_updated",
+            Error(_) => "error",
+        }
+    }
+
+    /// The transaction this event refers to, if any
+    pub fn tx_id(&self) -> Option<TxId> {
+        use TransactionEvent::*;
+        match self {
+            MempoolBroadcastTimedOut(tx_id) |
+            ReceivedTransaction(tx_id) |
+            ReceivedTransactionReply(tx_id) |
+            ReceivedFinalizedTransaction(tx_id) |
+            TransactionDiscoveryInProgress(tx_id) |
+            TransactionDirectSendResult(tx_id, _) |
+            TransactionCompletedImmediately(tx_id) |
+            TransactionStoreForwardSendResult(tx_id, _) |
+            TransactionCancelled(tx_id, _) |
+            TransactionBroadcast(tx_id) |
+            TransactionImported(tx_id) |
+            FauxTransactionUnconfirmed { tx_id, .. } |
+            FauxTransactionConfirmed { tx_id, .. } |
+            TransactionMined { tx_id, .. } |
+            TransactionMinedRequestTimedOut(tx_id) |
+            TransactionMinedUnconfirmed { tx_id, .. } => Some(*tx_id),
+            TransactionValidationStateChanged(_) |
+            TransactionValidationCompleted(_) |
+            TransactionValidationFailed(_) |
+            MultisigSessionUpdated(_) |
+            Error(_) => None,
+        }
+    }
+
+    /// The number of confirmations reported by the event, if any
+    pub fn num_confirmations(&self) -> Option<u64> {
+        match self {
+            TransactionEvent::FauxTransactionUnconfirmed { num_confirmations, .. } |
+            TransactionEvent::TransactionMinedUnconfirmed { num_confirmations, .. } => Some(*num_confirmations),
+            _ => None,
+        }
+    }
+
+    /// Whether the event reports success or validity, for the events that carry such a flag
+    pub fn is_valid(&self) -> Option<bool> {
+        match self {
+            TransactionEvent::TransactionDirectSendResult(_, valid) |
+            TransactionEvent::TransactionStoreForwardSendResult(_, valid) |
+            TransactionEvent::FauxTransactionUnconfirmed { is_valid: valid, .. } |
+            TransactionEvent::FauxTransactionConfirmed { is_valid: valid, .. } |
+            TransactionEvent::TransactionMined { is_valid: valid, .. } |
+            TransactionEvent::TransactionMinedUnconfirmed { is_valid: valid, .. } => Some(*valid),
+            _ => None,
         }
     }
 }
@@ -332,6 +570,7 @@ pub type TransactionEventReceiver = broadcast::Receiver<Arc<TransactionEvent>>;
 pub struct TransactionServiceHandle {
     handle: SenderService<TransactionServiceRequest, Result<TransactionServiceResponse, TransactionServiceError>>,
     event_stream_sender: TransactionEventSender,
+    account_id: Option<u64>,
 }
 
 impl TransactionServiceHandle {
@@ -342,9 +581,41 @@ impl TransactionServiceHandle {
         Self {
             handle,
             event_stream_sender,
+            account_id: None,
+        }
+    }
+
+    /// Returns a handle whose requests act on the given account. Payments are sent from the account and the
+    /// transaction history only includes the account's transactions, while all other requests act on the wallet as a
+    /// whole. Spending requests made through an unscoped handle use the default account, while its transaction history
+    /// covers the whole wallet.
+    ///
+    /// Payments received from other wallets are always added to the default account, because a sender has no way to
+    /// address an account. They can be moved to another account with `transfer_between_accounts`.
+    pub fn for_account(&self, account_id: u64) -> Self {
+        Self {
+            handle: self.handle.clone(),
+            event_stream_sender: self.event_stream_sender.clone(),
+            account_id: Some(account_id),
         }
     }
 
+    /// The account this handle is scoped to, if any
+    pub fn account_id(&self) -> Option<u64> {
+        self.account_id
+    }
+
+    async fn request(
+        &mut self,
+        request: TransactionServiceRequest,
+    ) -> Result<TransactionServiceResponse, TransactionServiceError> {
+        let request = match self.account_id {
+            Some(account_id) => TransactionServiceRequest::ForAccount(account_id, Box::new(request)),
+            None => request,
+        };
+        self.handle.call(request).await?
+    }
+
     pub fn get_event_stream(&self) -> TransactionEventReceiver {
         self.event_stream_sender.subscribe()
     }
@@ -357,16 +628,17 @@ impl TransactionServiceHandle {
         message: String,
     ) -> Result<TxId, TransactionServiceError> {
         match self
-            .handle
-            .call(TransactionServiceRequest::SendTransaction {
+            .request(TransactionServiceRequest::SendTransaction {
                 dest_pubkey,
                 amount,
                 unique_id: None,
                 parent_public_key: None,
                 fee_per_gram,
                 message,
+                covenant: Covenant::default(),
+                selection_strategy: None,
             })
-            .await??
+            .await?
         {
             TransactionServiceResponse::TransactionSent(tx_id) => Ok(tx_id),
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
@@ -381,18 +653,21 @@ impl TransactionServiceHandle {
         parent_public_key: Option<PublicKey>,
         fee_per_gram: MicroTari,
         message: String,
+        covenant: Covenant,
+        selection_strategy: Option<UTXOSelectionStrategy>,
     ) -> Result<TxId, TransactionServiceError> {
         match self
-            .handle
-            .call(TransactionServiceRequest::SendTransaction {
+            .request(TransactionServiceRequest::SendTransaction {
                 dest_pubkey,
                 amount,
                 unique_id,
                 parent_public_key,
                 fee_per_gram,
                 message,
+                covenant,
+                selection_strategy,
             })
-            .await??
+            .await?
         {
             TransactionServiceResponse::TransactionSent(tx_id) => Ok(tx_id),
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
@@ -407,16 +682,59 @@ impl TransactionServiceHandle {
         message: String,
     ) -> Result<TxId, TransactionServiceError> {
         match self
-            .handle
-            .call(TransactionServiceRequest::SendOneSidedTransaction {
+            .request(TransactionServiceRequest::SendOneSidedTransaction {
                 dest_pubkey,
                 amount,
                 unique_id: None,
                 parent_public_key: None,
                 fee_per_gram,
                 message,
+                covenant: Covenant::default(),
+                selection_strategy: None,
+            })
+            .await?
+        {
+            TransactionServiceResponse::TransactionSent(tx_id) => Ok(tx_id),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Pay every recipient with a one-sided output in a single transaction
+    pub async fn send_one_sided_batch_transaction(
+        &mut self,
+        recipients: Vec<BatchPaymentRecipient>,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<TxId, TransactionServiceError> {
+        match self
+            .request(TransactionServiceRequest::SendOneSidedBatchTransaction {
+                recipients,
+                fee_per_gram,
+                message,
+            })
+            .await?
+        {
+            TransactionServiceResponse::TransactionSent(tx_id) => Ok(tx_id),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Move funds from this handle's account to another account of the same wallet
+    pub async fn transfer_between_accounts(
+        &mut self,
+        to_account: u64,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<TxId, TransactionServiceError> {
+        match self
+            .request(TransactionServiceRequest::TransferBetweenAccounts {
+                to_account,
+                amount,
+                fee_per_gram,
+                message,
             })
-            .await??
+            .await?
         {
             TransactionServiceResponse::TransactionSent(tx_id) => Ok(tx_id),
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
@@ -431,29 +749,184 @@ impl TransactionServiceHandle {
         parent_public_key: Option<PublicKey>,
         fee_per_gram: MicroTari,
         message: String,
+        covenant: Covenant,
+        selection_strategy: Option<UTXOSelectionStrategy>,
     ) -> Result<TxId, TransactionServiceError> {
         match self
-            .handle
-            .call(TransactionServiceRequest::SendOneSidedTransaction {
+            .request(TransactionServiceRequest::SendOneSidedTransaction {
                 dest_pubkey,
                 amount,
                 unique_id,
                 parent_public_key,
                 fee_per_gram,
                 message,
+                covenant,
+                selection_strategy,
+            })
+            .await?
+        {
+            TransactionServiceResponse::TransactionSent(tx_id) => Ok(tx_id),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Signs a one-sided payment prepared by a watch-only copy of this wallet
+    pub async fn sign_offline_transaction(
+        &mut self,
+        template: UnsignedTransactionTemplate,
+    ) -> Result<OfflineSignedTransaction, TransactionServiceError> {
+        match self
+            .request(TransactionServiceRequest::SignOfflineTransaction(Box::new(template)))
+            .await?
+        {
+            TransactionServiceResponse::OfflineTransactionSigned(signed) => Ok(*signed),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Validates, records and broadcasts a transaction that was signed offline
+    pub async fn submit_offline_transaction(
+        &mut self,
+        signed: OfflineSignedTransaction,
+    ) -> Result<TxId, TransactionServiceError> {
+        match self
+            .request(TransactionServiceRequest::SubmitOfflineTransaction(Box::new(signed)))
+            .await?
+        {
+            TransactionServiceResponse::TransactionSent(tx_id) => Ok(tx_id),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Starts a multisig session with the cosigners. Once every cosigner has verified its key shares, this wallet funds
+    /// an output that needs `threshold` of the members to sign for spending it.
+    pub async fn create_multisig_output(
+        &mut self,
+        cosigners: Vec<CommsPublicKey>,
+        threshold: u8,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<u64, TransactionServiceError> {
+        match self
+            .request(TransactionServiceRequest::CreateMultisigOutput {
+                cosigners,
+                threshold,
+                amount,
+                fee_per_gram,
+                message,
+            })
+            .await?
+        {
+            TransactionServiceResponse::MultisigSessionCreated(session_id) => Ok(session_id),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Proposes sending the whole multisig output, less the fee, to the destination as a one-sided payment
+    pub async fn propose_multisig_spend(
+        &mut self,
+        session_id: u64,
+        destination: CommsPublicKey,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<(), TransactionServiceError> {
+        match self
+            .request(TransactionServiceRequest::ProposeMultisigSpend {
+                session_id,
+                destination,
+                fee_per_gram,
+                message,
+            })
+            .await?
+        {
+            TransactionServiceResponse::MultisigSpendProposed => Ok(()),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Approves the pending spend of a multisig session and sends the approval to the leader of the session
+    pub async fn approve_multisig_spend(&mut self, session_id: u64) -> Result<(), TransactionServiceError> {
+        match self
+            .request(TransactionServiceRequest::ApproveMultisigSpend(session_id))
+            .await?
+        {
+            TransactionServiceResponse::MultisigSpendApproved => Ok(()),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    pub async fn get_multisig_sessions(&mut self) -> Result<Vec<MultisigSession>, TransactionServiceError> {
+        match self.request(TransactionServiceRequest::GetMultisigSessions).await? {
+            TransactionServiceResponse::MultisigSessions(sessions) => Ok(sessions),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    pub async fn send_time_locked_transaction(
+        &mut self,
+        dest_pubkey: CommsPublicKey,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+        lock_height: u64,
+    ) -> Result<TxId, TransactionServiceError> {
+        match self
+            .request(TransactionServiceRequest::SendTimeLockedTransaction {
+                dest_pubkey,
+                amount,
+                fee_per_gram,
+                message,
+                lock_height,
             })
-            .await??
+            .await?
         {
             TransactionServiceResponse::TransactionSent(tx_id) => Ok(tx_id),
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
         }
     }
 
+    /// Schedule a payment, returning the id of the schedule. Scheduled payments are always made from the default
+    /// account.
+    pub async fn schedule_payment(&mut self, request: ScheduledPaymentRequest) -> Result<u64, TransactionServiceError> {
+        match self
+            .request(TransactionServiceRequest::SchedulePayment(Box::new(request)))
+            .await?
+        {
+            TransactionServiceResponse::PaymentScheduled(id) => Ok(id),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    pub async fn get_scheduled_payments(&mut self) -> Result<Vec<ScheduledPayment>, TransactionServiceError> {
+        match self.request(TransactionServiceRequest::GetScheduledPayments).await? {
+            TransactionServiceResponse::ScheduledPayments(payments) => Ok(payments),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Returns the individual payments made by a one-sided batch transaction
+    pub async fn get_batch_payments(&mut self, tx_id: TxId) -> Result<Vec<BatchPayment>, TransactionServiceError> {
+        match self.request(TransactionServiceRequest::GetBatchPayments(tx_id)).await? {
+            TransactionServiceResponse::BatchPayments(payments) => Ok(payments),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    pub async fn cancel_scheduled_payment(&mut self, id: u64) -> Result<(), TransactionServiceError> {
+        match self
+            .request(TransactionServiceRequest::CancelScheduledPayment(id))
+            .await?
+        {
+            TransactionServiceResponse::ScheduledPaymentCancelled => Ok(()),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn cancel_transaction(&mut self, tx_id: TxId) -> Result<(), TransactionServiceError> {
         match self
-            .handle
-            .call(TransactionServiceRequest::CancelTransaction(tx_id))
-            .await??
+            .request(TransactionServiceRequest::CancelTransaction(tx_id))
+            .await?
         {
             TransactionServiceResponse::TransactionCancelled => Ok(()),
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
@@ -464,9 +937,8 @@ impl TransactionServiceHandle {
         &mut self,
     ) -> Result<HashMap<TxId, InboundTransaction>, TransactionServiceError> {
         match self
-            .handle
-            .call(TransactionServiceRequest::GetPendingInboundTransactions)
-            .await??
+            .request(TransactionServiceRequest::GetPendingInboundTransactions)
+            .await?
         {
             TransactionServiceResponse::PendingInboundTransactions(p) => Ok(p),
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
@@ -477,9 +949,8 @@ impl TransactionServiceHandle {
         &mut self,
     ) -> Result<HashMap<TxId, InboundTransaction>, TransactionServiceError> {
         match self
-            .handle
-            .call(TransactionServiceRequest::GetCancelledPendingInboundTransactions)
-            .await??
+            .request(TransactionServiceRequest::GetCancelledPendingInboundTransactions)
+            .await?
         {
             TransactionServiceResponse::PendingInboundTransactions(p) => Ok(p),
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
@@ -490,9 +961,8 @@ impl TransactionServiceHandle {
         &mut self,
     ) -> Result<HashMap<TxId, OutboundTransaction>, TransactionServiceError> {
         match self
-            .handle
-            .call(TransactionServiceRequest::GetPendingOutboundTransactions)
-            .await??
+            .request(TransactionServiceRequest::GetPendingOutboundTransactions)
+            .await?
         {
             TransactionServiceResponse::PendingOutboundTransactions(p) => Ok(p),
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
@@ -503,9 +973,8 @@ impl TransactionServiceHandle {
         &mut self,
     ) -> Result<HashMap<TxId, OutboundTransaction>, TransactionServiceError> {
         match self
-            .handle
-            .call(TransactionServiceRequest::GetCancelledPendingOutboundTransactions)
-            .await??
+            .request(TransactionServiceRequest::GetCancelledPendingOutboundTransactions)
+            .await?
         {
             TransactionServiceResponse::PendingOutboundTransactions(p) => Ok(p),
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
@@ -516,9 +985,8 @@ impl TransactionServiceHandle {
         &mut self,
     ) -> Result<HashMap<TxId, CompletedTransaction>, TransactionServiceError> {
         match self
-            .handle
-            .call(TransactionServiceRequest::GetCompletedTransactions)
-            .await??
+            .request(TransactionServiceRequest::GetCompletedTransactions)
+            .await?
         {
             TransactionServiceResponse::CompletedTransactions(c) => Ok(c),
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
@@ -529,9 +997,8 @@ impl TransactionServiceHandle {
         &mut self,
     ) -> Result<HashMap<TxId, CompletedTransaction>, TransactionServiceError> {
         match self
-            .handle
-            .call(TransactionServiceRequest::GetCancelledCompletedTransactions)
-            .await??
+            .request(TransactionServiceRequest::GetCancelledCompletedTransactions)
+            .await?
         {
             TransactionServiceResponse::CompletedTransactions(c) => Ok(c),
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
@@ -543,9 +1010,8 @@ impl TransactionServiceHandle {
         tx_id: TxId,
     ) -> Result<CompletedTransaction, TransactionServiceError> {
         match self
-            .handle
-            .call(TransactionServiceRequest::GetCompletedTransaction(tx_id))
-            .await??
+            .request(TransactionServiceRequest::GetCompletedTransaction(tx_id))
+            .await?
         {
             TransactionServiceResponse::CompletedTransaction(t) => Ok(*t),
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
@@ -557,9 +1023,8 @@ impl TransactionServiceHandle {
         tx_id: TxId,
     ) -> Result<Option<WalletTransaction>, TransactionServiceError> {
         match self
-            .handle
-            .call(TransactionServiceRequest::GetAnyTransaction(tx_id))
-            .await??
+            .request(TransactionServiceRequest::GetAnyTransaction(tx_id))
+            .await?
         {
             TransactionServiceResponse::AnyTransaction(t) => Ok(*t),
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
@@ -577,8 +1042,7 @@ impl TransactionServiceHandle {
         current_height: Option<u64>,
     ) -> Result<TxId, TransactionServiceError> {
         match self
-            .handle
-            .call(TransactionServiceRequest::ImportUtxoWithStatus {
+            .request(TransactionServiceRequest::ImportUtxoWithStatus {
                 amount,
                 source_public_key,
                 message,
@@ -587,7 +1051,7 @@ impl TransactionServiceHandle {
                 tx_id,
                 current_height,
             })
-            .await??
+            .await?
         {
             TransactionServiceResponse::UtxoImported(tx_id) => Ok(tx_id),
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
@@ -603,11 +1067,10 @@ impl TransactionServiceHandle {
     ) -> Result<(), TransactionServiceError> {
         let fee = tx.body.get_total_fee();
         match self
-            .handle
-            .call(TransactionServiceRequest::SubmitTransactionToSelf(
+            .request(TransactionServiceRequest::SubmitTransactionToSelf(
                 tx_id, tx, fee, amount, message,
             ))
-            .await??
+            .await?
         {
             TransactionServiceResponse::TransactionSubmitted => Ok(()),
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
@@ -615,29 +1078,21 @@ impl TransactionServiceHandle {
     }
 
     pub async fn set_low_power_mode(&mut self) -> Result<(), TransactionServiceError> {
-        match self.handle.call(TransactionServiceRequest::SetLowPowerMode).await?? {
+        match self.request(TransactionServiceRequest::SetLowPowerMode).await? {
             TransactionServiceResponse::LowPowerModeSet => Ok(()),
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
         }
     }
 
     pub async fn revalidate_all_transactions(&mut self) -> Result<(), TransactionServiceError> {
-        match self
-            .handle
-            .call(TransactionServiceRequest::ReValidateTransactions)
-            .await??
-        {
+        match self.request(TransactionServiceRequest::ReValidateTransactions).await? {
             TransactionServiceResponse::ValidationStarted(_) => Ok(()),
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
         }
     }
 
     pub async fn set_normal_power_mode(&mut self) -> Result<(), TransactionServiceError> {
-        match self
-            .handle
-            .call(TransactionServiceRequest::SetNormalPowerMode)
-            .await??
-        {
+        match self.request(TransactionServiceRequest::SetNormalPowerMode).await? {
             TransactionServiceResponse::NormalPowerModeSet => Ok(()),
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
         }
@@ -645,9 +1100,8 @@ impl TransactionServiceHandle {
 
     pub async fn apply_encryption(&mut self, cipher: Aes256Gcm) -> Result<(), TransactionServiceError> {
         match self
-            .handle
-            .call(TransactionServiceRequest::ApplyEncryption(Box::new(cipher)))
-            .await??
+            .request(TransactionServiceRequest::ApplyEncryption(Box::new(cipher)))
+            .await?
         {
             TransactionServiceResponse::EncryptionApplied => Ok(()),
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
@@ -655,7 +1109,7 @@ impl TransactionServiceHandle {
     }
 
     pub async fn remove_encryption(&mut self) -> Result<(), TransactionServiceError> {
-        match self.handle.call(TransactionServiceRequest::RemoveEncryption).await?? {
+        match self.request(TransactionServiceRequest::RemoveEncryption).await? {
             TransactionServiceResponse::EncryptionRemoved => Ok(()),
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
         }
@@ -663,9 +1117,8 @@ impl TransactionServiceHandle {
 
     pub async fn get_num_confirmations_required(&mut self) -> Result<u64, TransactionServiceError> {
         match self
-            .handle
-            .call(TransactionServiceRequest::GetNumConfirmationsRequired)
-            .await??
+            .request(TransactionServiceRequest::GetNumConfirmationsRequired)
+            .await?
         {
             TransactionServiceResponse::NumConfirmationsRequired(confirmations) => Ok(confirmations),
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
@@ -674,9 +1127,8 @@ impl TransactionServiceHandle {
 
     pub async fn set_num_confirmations_required(&mut self, number: u64) -> Result<(), TransactionServiceError> {
         match self
-            .handle
-            .call(TransactionServiceRequest::SetNumConfirmationsRequired(number))
-            .await??
+            .request(TransactionServiceRequest::SetNumConfirmationsRequired(number))
+            .await?
         {
             TransactionServiceResponse::NumConfirmationsSet => Ok(()),
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
@@ -690,13 +1142,12 @@ impl TransactionServiceHandle {
         block_height: u64,
     ) -> Result<Transaction, TransactionServiceError> {
         match self
-            .handle
-            .call(TransactionServiceRequest::GenerateCoinbaseTransaction(
+            .request(TransactionServiceRequest::GenerateCoinbaseTransaction(
                 rewards,
                 fees,
                 block_height,
             ))
-            .await??
+            .await?
         {
             TransactionServiceResponse::CoinbaseTransactionGenerated(tx) => Ok(*tx),
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
@@ -705,9 +1156,8 @@ impl TransactionServiceHandle {
 
     pub async fn restart_transaction_protocols(&mut self) -> Result<(), TransactionServiceError> {
         match self
-            .handle
-            .call(TransactionServiceRequest::RestartTransactionProtocols)
-            .await??
+            .request(TransactionServiceRequest::RestartTransactionProtocols)
+            .await?
         {
             TransactionServiceResponse::ProtocolsRestarted => Ok(()),
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
@@ -716,9 +1166,8 @@ impl TransactionServiceHandle {
 
     pub async fn restart_broadcast_protocols(&mut self) -> Result<(), TransactionServiceError> {
         match self
-            .handle
-            .call(TransactionServiceRequest::RestartBroadcastProtocols)
-            .await??
+            .request(TransactionServiceRequest::RestartBroadcastProtocols)
+            .await?
         {
             TransactionServiceResponse::ProtocolsRestarted => Ok(()),
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
@@ -726,11 +1175,7 @@ impl TransactionServiceHandle {
     }
 
     pub async fn validate_transactions(&mut self) -> Result<OperationId, TransactionServiceError> {
-        match self
-            .handle
-            .call(TransactionServiceRequest::ValidateTransactions)
-            .await??
-        {
+        match self.request(TransactionServiceRequest::ValidateTransactions).await? {
             TransactionServiceResponse::ValidationStarted(id) => Ok(id),
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
         }
@@ -744,14 +1189,13 @@ impl TransactionServiceHandle {
         message: String,
     ) -> Result<(TxId, PublicKey, TransactionOutput), TransactionServiceError> {
         match self
-            .handle
-            .call(TransactionServiceRequest::SendShaAtomicSwapTransaction(
+            .request(TransactionServiceRequest::SendShaAtomicSwapTransaction(
                 dest_pubkey,
                 amount,
                 fee_per_gram,
                 message,
             ))
-            .await??
+            .await?
         {
             TransactionServiceResponse::ShaAtomicSwapTransactionSent(boxed) => {
                 let (tx_id, pre_image, output) = *boxed;
@@ -61,6 +61,7 @@ use crate::{
 pub mod config;
 pub mod error;
 pub mod handle;
+pub mod multisig;
 pub mod protocols;
 pub mod service;
 pub mod storage;
@@ -171,6 +172,19 @@ where
             .map(map_decode::<proto::TransactionCancelledMessage>)
             .filter_map(ok_or_skip_result)
     }
+
+    fn multisig_stream(&self) -> impl Stream<Item = DomainMessage<proto::MultisigMessage>> {
+        trace!(
+            target: LOG_TARGET,
+            "Subscription '{}' for topic '{:?}' created.",
+            SUBSCRIPTION_LABEL,
+            TariMessageType::Multisig
+        );
+        self.subscription_factory
+            .get_subscription(TariMessageType::Multisig, SUBSCRIPTION_LABEL)
+            .map(map_decode::<proto::MultisigMessage>)
+            .filter_map(ok_or_skip_result)
+    }
 }
 
 #[async_trait]
@@ -186,6 +200,7 @@ where
         let transaction_finalized_stream = self.transaction_finalized_stream();
         let base_node_response_stream = self.base_node_response_stream();
         let transaction_cancelled_stream = self.transaction_cancelled_stream();
+        let multisig_stream = self.multisig_stream();
 
         let (publisher, _) = broadcast::channel(self.config.transaction_event_channel_size);
 
@@ -224,6 +239,7 @@ where
                 transaction_finalized_stream,
                 base_node_response_stream,
                 transaction_cancelled_stream,
+                multisig_stream,
                 output_manager_service,
                 outbound_message_service,
                 connectivity,
@@ -0,0 +1,1459 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! M-of-N multisig outputs. The output is locked by a script key that no single member knows. Each member's signing
+//! key is combined into the script key with a key aggregation coefficient, and is split between the members with a
+//! verifiable secret sharing polynomial of degree `threshold - 1`, so any `threshold` members can sign for the script
+//! key together while fewer cannot.
+//!
+//! A spend takes three rounds. Each approving member first sends the leader a hash of fresh public nonces. The leader
+//! picks `threshold` of the approvals and sends those members every commitment along with a hash of its own nonces, and
+//! only then do the signers reveal their nonces. No one, the leader included, can choose its nonces after seeing
+//! anyone else's, which is what makes it safe to add the nonces together. The leader then builds the one-sided payment
+//! output and asks the signers to sign, and each signer checks every nonce against its commitment first. The partial
+//! signatures cover the script signature challenge of the input and the metadata signature challenge of the output, so
+//! they cannot be used for any other spend. The leader knows the commitment blinding factor of the shared output, which
+//! is needed for its range proof, but cannot produce a valid script signature without the other members.
+
+use std::convert::{TryFrom, TryInto};
+
+use blake2::Digest;
+use rand::rngs::OsRng;
+use serde::{Deserialize, Serialize};
+use tari_common_types::{
+    transaction::TxId,
+    types::{ComSignature, Commitment, PrivateKey, PublicKey, Signature},
+};
+use tari_comms::types::CommsPublicKey;
+use tari_core::{
+    consensus::ConsensusEncodingSized,
+    covenants::Covenant,
+    transactions::{
+        fee::Fee,
+        tari_amount::MicroTari,
+        transaction_components::{
+            KernelBuilder,
+            OutputFeatures,
+            Transaction,
+            TransactionInput,
+            TransactionInputVersion,
+            TransactionOutput,
+            UnblindedOutput,
+        },
+        transaction_protocol::{build_challenge, proto::protocol as proto, RewindData, TransactionMetadata},
+        weight::TransactionWeight,
+        CryptoFactories,
+    },
+};
+use tari_crypto::{
+    commitment::HomomorphicCommitmentFactory,
+    keys::{DiffieHellmanSharedSecret, PublicKey as PublicKeyTrait, SecretKey},
+    script,
+    script::{ExecutionStack, TariScript},
+    tari_utilities::ByteArray,
+};
+
+use crate::{
+    transaction_service::{error::TransactionServiceError, service::hash_secret_key},
+    types::HashDigest,
+};
+
+/// The largest number of members a multisig session can have
+pub const MAX_MULTISIG_MEMBERS: usize = 32;
+
+/// The order of the Ristretto group less two, as little-endian bytes
+const GROUP_ORDER_MINUS_TWO: [u8; 32] = [
+    0xeb, 0xd3, 0xf5, 0x5c, 0x1a, 0x63, 0x12, 0x58, 0xd6, 0x9c, 0xf7, 0xa2, 0xde, 0xf9, 0xde, 0x14, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10,
+];
+
+/// The script of a multisig output. It leaves the aggregated script public key of the session on the stack, so the
+/// script signature of a spend can only be made by `threshold` members together.
+pub fn multisig_script(script_public_key: PublicKey) -> TariScript {
+    script!(PushPubKey(Box::new(script_public_key)))
+}
+
+/// The script of the one-sided output that a multisig spend pays to
+fn destination_script(destination: &PublicKey) -> TariScript {
+    script!(PushPubKey(Box::new(destination.clone())))
+}
+
+fn scalar_from_hash(hash: &[u8]) -> Result<PrivateKey, TransactionServiceError> {
+    Ok(PrivateKey::from_bytes(hash)?)
+}
+
+/// The evaluation point of the key share polynomials for the member at `index`. Zero is never used, as the
+/// polynomials evaluate to the members' signing keys there.
+fn evaluation_point(index: usize) -> PrivateKey {
+    PrivateKey::from(index as u64 + 1)
+}
+
+/// A member's secret sharing polynomial. Its constant term is the member's signing key and its other coefficients are
+/// derived from the member's polynomial key for the session, so the polynomial can be recreated at any time.
+pub struct KeySharePolynomial {
+    coefficients: Vec<PrivateKey>,
+}
+
+impl KeySharePolynomial {
+    pub fn new(
+        signing_key: &PrivateKey,
+        polynomial_key: &PrivateKey,
+        threshold: u8,
+    ) -> Result<Self, TransactionServiceError> {
+        let mut coefficients = vec![signing_key.clone()];
+        for degree in 1..threshold {
+            let hash = HashDigest::new()
+                .chain(b"com.tari.wallet.multisig.polynomial")
+                .chain(polynomial_key.as_bytes())
+                .chain([degree])
+                .finalize();
+            coefficients.push(scalar_from_hash(hash.as_slice())?);
+        }
+        Ok(Self { coefficients })
+    }
+
+    /// The public commitments to the coefficients of the polynomial. The first commitment is the member's signing
+    /// public key.
+    pub fn commitments(&self) -> Vec<PublicKey> {
+        self.coefficients.iter().map(PublicKey::from_secret_key).collect()
+    }
+
+    /// The share of the member at `index`
+    pub fn share(&self, index: usize) -> PrivateKey {
+        let x = evaluation_point(index);
+        let mut coefficients = self.coefficients.iter().rev();
+        let highest = coefficients.next().cloned().unwrap_or_default();
+        coefficients.fold(highest, |acc, coefficient| &(&acc * &x) + coefficient)
+    }
+}
+
+/// Evaluates a polynomial in the exponent, giving the public key of the share of the member at `index`
+fn evaluate_commitments(commitments: &[PublicKey], index: usize) -> PublicKey {
+    let x = evaluation_point(index);
+    let mut commitments = commitments.iter().rev();
+    let highest = commitments.next().cloned().unwrap_or_default();
+    commitments.fold(highest, |acc, commitment| &(&x * &acc) + commitment)
+}
+
+/// Checks a share received from another member against that member's polynomial commitments
+pub fn verify_key_share(commitments: &[PublicKey], index: usize, share: &PrivateKey) -> bool {
+    PublicKey::from_secret_key(share) == evaluate_commitments(commitments, index)
+}
+
+/// The MuSig key aggregation coefficient of each signing public key. Each coefficient depends on all of the keys, so no
+/// member can choose its key to cancel out the keys of the others.
+pub fn key_aggregation_coefficients(
+    signing_public_keys: &[PublicKey],
+) -> Result<Vec<PrivateKey>, TransactionServiceError> {
+    let common = signing_public_keys
+        .iter()
+        .fold(HashDigest::new(), |hasher, key| hasher.chain(key.as_bytes()))
+        .finalize();
+    signing_public_keys
+        .iter()
+        .map(|key| {
+            let hash = HashDigest::new()
+                .chain(b"com.tari.wallet.multisig.key_aggregation")
+                .chain(common.as_slice())
+                .chain(key.as_bytes())
+                .finalize();
+            scalar_from_hash(hash.as_slice())
+        })
+        .collect()
+}
+
+/// The keys of a multisig session that follow from the polynomial commitments of all of its members
+pub struct MultisigSessionKeys {
+    commitments: Vec<Vec<PublicKey>>,
+    coefficients: Vec<PrivateKey>,
+}
+
+impl MultisigSessionKeys {
+    /// `commitments` holds the polynomial commitments of every member, in member order
+    pub fn new(commitments: Vec<Vec<PublicKey>>, threshold: u8) -> Result<Self, TransactionServiceError> {
+        if commitments.len() < threshold as usize || commitments.iter().any(|c| c.len() != threshold as usize) {
+            return Err(TransactionServiceError::MultisigError(
+                "Multisig polynomial commitments do not match the threshold".to_string(),
+            ));
+        }
+        let signing_public_keys = commitments.iter().map(|c| c[0].clone()).collect::<Vec<_>>();
+        let coefficients = key_aggregation_coefficients(&signing_public_keys)?;
+        Ok(Self {
+            commitments,
+            coefficients,
+        })
+    }
+
+    /// The aggregated script public key of the shared output
+    pub fn script_public_key(&self) -> PublicKey {
+        let signing_public_keys = self.commitments.iter().map(|c| c[0].clone()).collect::<Vec<_>>();
+        PublicKey::batch_mul(&self.coefficients, &signing_public_keys)
+    }
+
+    /// The public key of the script key share of the member at `index`
+    pub fn verification_key(&self, index: usize) -> PublicKey {
+        let share_public_keys = self
+            .commitments
+            .iter()
+            .map(|c| evaluate_commitments(c, index))
+            .collect::<Vec<_>>();
+        PublicKey::batch_mul(&self.coefficients, &share_public_keys)
+    }
+
+    /// Combines the shares a member received from every member, in member order, into its script key share
+    pub fn key_share(&self, shares: &[PrivateKey]) -> PrivateKey {
+        self.coefficients
+            .iter()
+            .zip(shares)
+            .fold(PrivateKey::default(), |acc, (coefficient, share)| {
+                &acc + &(coefficient * share)
+            })
+    }
+}
+
+/// The Lagrange coefficient of the member at `index` when the script key is reconstructed from the shares of `signers`
+pub fn lagrange_coefficient(index: usize, signers: &[usize]) -> PrivateKey {
+    let x = evaluation_point(index);
+    let mut numerator = PrivateKey::from(1);
+    let mut denominator = PrivateKey::from(1);
+    for signer in signers.iter().filter(|s| **s != index) {
+        let x_signer = evaluation_point(*signer);
+        denominator = &denominator * &(&x_signer - &x);
+        numerator = &numerator * &x_signer;
+    }
+    &numerator * &invert(&denominator)
+}
+
+/// The multiplicative inverse of a non-zero scalar, calculated as `k^(l - 2)` where `l` is the order of the group
+fn invert(k: &PrivateKey) -> PrivateKey {
+    let mut result = PrivateKey::from(1);
+    for byte in GROUP_ORDER_MINUS_TWO.iter().rev() {
+        for bit in (0..8).rev() {
+            result = &result * &result;
+            if (byte >> bit) & 1 == 1 {
+                result = &result * k;
+            }
+        }
+    }
+    result
+}
+
+/// The secret nonces a member generates when it approves a spend. They are only ever used for a single signing request.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MultisigSecretNonces {
+    script_nonce: PrivateKey,
+    metadata_nonce: PrivateKey,
+    sender_offset_key: PrivateKey,
+}
+
+impl MultisigSecretNonces {
+    pub fn random() -> Self {
+        Self {
+            script_nonce: PrivateKey::random(&mut OsRng),
+            metadata_nonce: PrivateKey::random(&mut OsRng),
+            sender_offset_key: PrivateKey::random(&mut OsRng),
+        }
+    }
+
+    /// The public nonces that are sent to the leader as this member's approval of a spend to `destination`
+    pub fn public_nonces(&self, destination: &PublicKey) -> Result<MultisigNonces, TransactionServiceError> {
+        let sender_offset_public_key = PublicKey::from_secret_key(&self.sender_offset_key);
+        let shared_secret = CommsPublicKey::shared_secret(&self.sender_offset_key, destination);
+        let nonce = PrivateKey::random(&mut OsRng);
+        let challenge = shared_secret_challenge(
+            destination,
+            &sender_offset_public_key,
+            &shared_secret,
+            &PublicKey::from_secret_key(&nonce),
+            &(&nonce * destination),
+        )?;
+        let response = &nonce + &(&challenge * &self.sender_offset_key);
+        Ok(MultisigNonces {
+            script_nonce: PublicKey::from_secret_key(&self.script_nonce),
+            metadata_nonce: PublicKey::from_secret_key(&self.metadata_nonce),
+            sender_offset_public_key,
+            shared_secret,
+            proof: SharedSecretProof { challenge, response },
+        })
+    }
+}
+
+/// A member's public nonces for a spend. The member's part of the sender offset key of the payment output is included
+/// along with its Diffie-Hellman shared secret with the destination, so the recipient can recover the output. The proof
+/// shows that both were made with the same secret key.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MultisigNonces {
+    pub script_nonce: PublicKey,
+    pub metadata_nonce: PublicKey,
+    pub sender_offset_public_key: PublicKey,
+    pub shared_secret: PublicKey,
+    pub proof: SharedSecretProof,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SharedSecretProof {
+    pub challenge: PrivateKey,
+    pub response: PrivateKey,
+}
+
+fn shared_secret_challenge(
+    destination: &PublicKey,
+    sender_offset_public_key: &PublicKey,
+    shared_secret: &PublicKey,
+    nonce: &PublicKey,
+    destination_nonce: &PublicKey,
+) -> Result<PrivateKey, TransactionServiceError> {
+    let hash = HashDigest::new()
+        .chain(b"com.tari.wallet.multisig.shared_secret")
+        .chain(destination.as_bytes())
+        .chain(sender_offset_public_key.as_bytes())
+        .chain(shared_secret.as_bytes())
+        .chain(nonce.as_bytes())
+        .chain(destination_nonce.as_bytes())
+        .finalize();
+    scalar_from_hash(hash.as_slice())
+}
+
+impl MultisigNonces {
+    /// Checks that the shared secret was made with the secret key of the sender offset public key
+    pub fn verify(&self, destination: &PublicKey) -> bool {
+        let SharedSecretProof { challenge, response } = &self.proof;
+        let nonce = &PublicKey::from_secret_key(response) - &(challenge * &self.sender_offset_public_key);
+        let destination_nonce = &(response * destination) - &(challenge * &self.shared_secret);
+        shared_secret_challenge(
+            destination,
+            &self.sender_offset_public_key,
+            &self.shared_secret,
+            &nonce,
+            &destination_nonce,
+        )
+        .map_or(false, |expected| &expected == challenge)
+    }
+
+    /// The hash that a member sends in place of its nonces when it approves a spend
+    pub fn commitment(&self) -> Vec<u8> {
+        HashDigest::new()
+            .chain(b"com.tari.wallet.multisig.nonces")
+            .chain(self.script_nonce.as_bytes())
+            .chain(self.metadata_nonce.as_bytes())
+            .chain(self.sender_offset_public_key.as_bytes())
+            .chain(self.shared_secret.as_bytes())
+            .chain(self.proof.challenge.as_bytes())
+            .chain(self.proof.response.as_bytes())
+            .finalize()
+            .to_vec()
+    }
+}
+
+/// The leader's secret nonces for the value and blinding factor parts of the script and metadata signatures of a
+/// spend. The leader commits to them before any signer reveals its nonces.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MultisigLeaderNonces {
+    script_value_nonce: PrivateKey,
+    script_blinding_nonce: PrivateKey,
+    metadata_value_nonce: PrivateKey,
+    metadata_blinding_nonce: PrivateKey,
+}
+
+impl MultisigLeaderNonces {
+    pub fn random() -> Self {
+        Self {
+            script_value_nonce: PrivateKey::random(&mut OsRng),
+            script_blinding_nonce: PrivateKey::random(&mut OsRng),
+            metadata_value_nonce: PrivateKey::random(&mut OsRng),
+            metadata_blinding_nonce: PrivateKey::random(&mut OsRng),
+        }
+    }
+
+    fn script_nonce(&self, factories: &CryptoFactories) -> Commitment {
+        factories
+            .commitment
+            .commit(&self.script_blinding_nonce, &self.script_value_nonce)
+    }
+
+    fn metadata_nonce(&self, factories: &CryptoFactories) -> Commitment {
+        factories
+            .commitment
+            .commit(&self.metadata_blinding_nonce, &self.metadata_value_nonce)
+    }
+
+    /// The hash of the public nonces that the leader sends to the signers before they reveal their nonces
+    pub fn commitment(&self, factories: &CryptoFactories) -> Vec<u8> {
+        leader_nonce_commitment(&self.script_nonce(factories), &self.metadata_nonce(factories))
+    }
+}
+
+fn leader_nonce_commitment(script_nonce: &Commitment, metadata_nonce: &Commitment) -> Vec<u8> {
+    HashDigest::new()
+        .chain(b"com.tari.wallet.multisig.leader_nonces")
+        .chain(script_nonce.as_bytes())
+        .chain(metadata_nonce.as_bytes())
+        .finalize()
+        .to_vec()
+}
+
+/// Sent by the leader to the members it picked to sign a spend. It fixes the nonce commitments of the signers and of
+/// the leader, so the signers can reveal their nonces.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MultisigNonceRequest {
+    /// The member indexes of the signers, in ascending order
+    pub signers: Vec<usize>,
+    /// The nonce commitments of the signers, in the same order as `signers`
+    pub commitments: Vec<Vec<u8>>,
+    pub leader_commitment: Vec<u8>,
+}
+
+impl MultisigNonceRequest {
+    /// Checks that the request is for `threshold` signers and that it holds the nonce commitment this wallet sent as
+    /// the member at `index`
+    pub fn validate(
+        &self,
+        threshold: u8,
+        index: usize,
+        nonces: &MultisigNonces,
+    ) -> Result<(), TransactionServiceError> {
+        let err = |reason: &str| {
+            Err(TransactionServiceError::MultisigError(format!(
+                "Invalid nonce request: {}",
+                reason
+            )))
+        };
+        if self.signers.len() != threshold as usize || self.commitments.len() != self.signers.len() {
+            return err("wrong number of signers");
+        }
+        if self.signers.windows(2).any(|w| w[0] >= w[1]) {
+            return err("signers are not in order");
+        }
+        if self.commitment_of(index) != Some(&nonces.commitment()) {
+            return err("this wallet's nonce commitment is missing");
+        }
+        Ok(())
+    }
+
+    fn commitment_of(&self, index: usize) -> Option<&Vec<u8>> {
+        self.signers
+            .iter()
+            .position(|s| *s == index)
+            .map(|position| &self.commitments[position])
+    }
+
+    /// Checks the nonces that the signer at `index` revealed against its commitment
+    pub fn verify_nonces(&self, index: usize, nonces: &MultisigNonces, destination: &PublicKey) -> bool {
+        self.commitment_of(index) == Some(&nonces.commitment()) && nonces.verify(destination)
+    }
+}
+
+/// The fee of a multisig spend, which has one input, one output and one kernel
+pub fn multisig_spend_fee(fee_per_gram: MicroTari, destination: &PublicKey) -> MicroTari {
+    let weighting = TransactionWeight::latest();
+    let metadata_byte_size = weighting.round_up_metadata_size(
+        OutputFeatures::default().consensus_encode_exact_size() +
+            destination_script(destination).consensus_encode_exact_size() +
+            Covenant::default().consensus_encode_exact_size(),
+    );
+    Fee::new(weighting).calculate(fee_per_gram, 1, 1, 1, metadata_byte_size)
+}
+
+/// The spending key of the one-sided payment output. The recipient recovers it from the sender offset public key of the
+/// output.
+fn payment_spending_key(nonces: &[MultisigNonces]) -> Result<PrivateKey, TransactionServiceError> {
+    let shared_secret = nonces
+        .iter()
+        .fold(PublicKey::default(), |acc, n| &acc + &n.shared_secret);
+    Ok(PrivateKey::from_bytes(shared_secret.as_bytes())?)
+}
+
+/// Sent by the leader to the members it picked to sign a spend. The output is the complete payment output apart from
+/// its metadata signature.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MultisigSigningRequest {
+    /// The member indexes of the signers, in ascending order
+    pub signers: Vec<usize>,
+    /// The public nonces of the signers, in the same order as `signers`
+    pub nonces: Vec<MultisigNonces>,
+    /// The leader's nonce for the value and blinding factor part of the script signature
+    pub script_nonce: Commitment,
+    /// The leader's nonce for the value and blinding factor part of the metadata signature
+    pub metadata_nonce: Commitment,
+    pub output: TransactionOutput,
+    pub fee: MicroTari,
+}
+
+impl MultisigSigningRequest {
+    fn sender_offset_public_key(&self) -> PublicKey {
+        self.nonces
+            .iter()
+            .fold(PublicKey::default(), |acc, n| &acc + &n.sender_offset_public_key)
+    }
+
+    fn script_public_nonce(&self) -> Commitment {
+        self.nonces.iter().fold(self.script_nonce.clone(), |acc, n| {
+            &acc + &Commitment::from_public_key(&n.script_nonce)
+        })
+    }
+
+    fn metadata_public_nonce(&self) -> Commitment {
+        self.nonces.iter().fold(self.metadata_nonce.clone(), |acc, n| {
+            &acc + &Commitment::from_public_key(&n.metadata_nonce)
+        })
+    }
+
+    /// The challenge of the script signature of the input that spends the shared output
+    pub fn script_challenge(
+        &self,
+        input: &TransactionOutput,
+        script_public_key: &PublicKey,
+    ) -> Result<PrivateKey, TransactionServiceError> {
+        scalar_from_hash(&TransactionInput::build_script_challenge(
+            &self.script_public_nonce(),
+            &input.script,
+            &ExecutionStack::default(),
+            script_public_key,
+            &input.commitment,
+        ))
+    }
+
+    /// The challenge of the metadata signature of the payment output
+    pub fn metadata_challenge(&self) -> Result<PrivateKey, TransactionServiceError> {
+        let challenge = TransactionOutput::build_metadata_signature_challenge(
+            &self.output.script,
+            &self.output.features,
+            &self.output.sender_offset_public_key,
+            &self.metadata_public_nonce(),
+            &self.output.commitment,
+            &self.output.covenant,
+        );
+        scalar_from_hash(challenge.finalize().as_slice())
+    }
+
+    /// Checks that the request uses the nonces that the signers and the leader committed to in the nonce request, and
+    /// that it pays the whole value of the shared output, less the fee, to a one-sided output for the destination that
+    /// the recipient will be able to find and spend
+    pub fn validate(
+        &self,
+        nonce_request: &MultisigNonceRequest,
+        threshold: u8,
+        destination: &PublicKey,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        factories: &CryptoFactories,
+    ) -> Result<(), TransactionServiceError> {
+        let err = |reason: &str| {
+            Err(TransactionServiceError::MultisigError(format!(
+                "Invalid signing request: {}",
+                reason
+            )))
+        };
+        if self.signers.len() != threshold as usize || self.nonces.len() != self.signers.len() {
+            return err("wrong number of signers");
+        }
+        if self.signers.windows(2).any(|w| w[0] >= w[1]) {
+            return err("signers are not in order");
+        }
+        if self.signers != nonce_request.signers {
+            return err("the signers do not match the nonce request");
+        }
+        if !self
+            .signers
+            .iter()
+            .zip(&self.nonces)
+            .all(|(index, nonces)| nonce_request.verify_nonces(*index, nonces, destination))
+        {
+            return err("the nonces of the signers do not match their commitments");
+        }
+        if leader_nonce_commitment(&self.script_nonce, &self.metadata_nonce) != nonce_request.leader_commitment {
+            return err("the leader's nonces do not match its commitment");
+        }
+        if self.fee != multisig_spend_fee(fee_per_gram, destination) {
+            return err("wrong fee");
+        }
+        let value = amount
+            .checked_sub(self.fee)
+            .ok_or_else(|| TransactionServiceError::MultisigError("The fee exceeds the multisig amount".to_string()))?;
+        let spending_key = payment_spending_key(&self.nonces)?;
+        if self.output.commitment != factories.commitment.commit_value(&spending_key, value.as_u64()) ||
+            self.output.script != destination_script(destination) ||
+            self.output.features != OutputFeatures::default() ||
+            self.output.covenant != Covenant::default() ||
+            self.output.sender_offset_public_key != self.sender_offset_public_key()
+        {
+            return err("the output does not pay the destination");
+        }
+        Ok(())
+    }
+}
+
+/// A signer's part of the script signature, metadata signature and script offset of a spend
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MultisigPartialSignature {
+    pub script_signature: ComSignature,
+    pub metadata_signature: ComSignature,
+    pub script_offset: PrivateKey,
+}
+
+impl MultisigPartialSignature {
+    /// Signs a request with the member's script key share. The secret nonces must be discarded afterwards.
+    pub fn sign(
+        request: &MultisigSigningRequest,
+        index: usize,
+        key_share: &PrivateKey,
+        secret_nonces: &MultisigSecretNonces,
+        input: &TransactionOutput,
+        script_public_key: &PublicKey,
+    ) -> Result<Self, TransactionServiceError> {
+        let nonces = request
+            .signers
+            .iter()
+            .position(|s| *s == index)
+            .map(|position| &request.nonces[position])
+            .ok_or_else(|| TransactionServiceError::MultisigError("This wallet is not a signer".to_string()))?;
+        if nonces.script_nonce != PublicKey::from_secret_key(&secret_nonces.script_nonce) ||
+            nonces.metadata_nonce != PublicKey::from_secret_key(&secret_nonces.metadata_nonce) ||
+            nonces.sender_offset_public_key != PublicKey::from_secret_key(&secret_nonces.sender_offset_key)
+        {
+            return Err(TransactionServiceError::MultisigError(
+                "The signing request does not use this wallet's nonces".to_string(),
+            ));
+        }
+
+        let weighted_share = &lagrange_coefficient(index, &request.signers) * key_share;
+        let script_challenge = request.script_challenge(input, script_public_key)?;
+        let metadata_challenge = request.metadata_challenge()?;
+        Ok(Self {
+            script_signature: ComSignature::new(
+                Commitment::from_public_key(&nonces.script_nonce),
+                &secret_nonces.script_nonce + &(&script_challenge * &weighted_share),
+                PrivateKey::default(),
+            ),
+            metadata_signature: ComSignature::new(
+                Commitment::from_public_key(&nonces.metadata_nonce),
+                &secret_nonces.metadata_nonce + &(&metadata_challenge * &secret_nonces.sender_offset_key),
+                PrivateKey::default(),
+            ),
+            script_offset: &weighted_share - &secret_nonces.sender_offset_key,
+        })
+    }
+
+    /// Checks a partial signature against the verification key of the signer at `index`
+    pub fn verify(
+        &self,
+        request: &MultisigSigningRequest,
+        index: usize,
+        verification_key: &PublicKey,
+        input: &TransactionOutput,
+        script_public_key: &PublicKey,
+    ) -> bool {
+        let nonces = match request.signers.iter().position(|s| *s == index) {
+            Some(position) => &request.nonces[position],
+            None => return false,
+        };
+        let (script_challenge, metadata_challenge) = match (
+            request.script_challenge(input, script_public_key),
+            request.metadata_challenge(),
+        ) {
+            (Ok(s), Ok(m)) => (s, m),
+            _ => return false,
+        };
+        let weighted_key = &lagrange_coefficient(index, &request.signers) * verification_key;
+        let (script_nonce, script_u, script_v) = self.script_signature.complete_signature_tuple();
+        let (metadata_nonce, metadata_u, metadata_v) = self.metadata_signature.complete_signature_tuple();
+
+        script_nonce == &Commitment::from_public_key(&nonces.script_nonce) &&
+            script_v == &PrivateKey::default() &&
+            PublicKey::from_secret_key(script_u) == &nonces.script_nonce + &(&script_challenge * &weighted_key) &&
+            metadata_nonce == &Commitment::from_public_key(&nonces.metadata_nonce) &&
+            metadata_v == &PrivateKey::default() &&
+            PublicKey::from_secret_key(metadata_u) ==
+                &nonces.metadata_nonce + &(&metadata_challenge * &nonces.sender_offset_public_key) &&
+            PublicKey::from_secret_key(&self.script_offset) == &weighted_key - &nonces.sender_offset_public_key
+    }
+}
+
+/// The leader's half of a spend. It holds the signing request along with the leader's secret nonces.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MultisigSpendTemplate {
+    pub request: MultisigSigningRequest,
+    leader_nonces: MultisigLeaderNonces,
+}
+
+impl MultisigSpendTemplate {
+    /// Builds the payment output of a spend of `amount` to `destination` that is signed for by `signers`. The leader
+    /// nonces must be the ones the leader committed to before the signers revealed their nonces.
+    pub fn new(
+        signers: Vec<usize>,
+        nonces: Vec<MultisigNonces>,
+        leader_nonces: MultisigLeaderNonces,
+        destination: &PublicKey,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        factories: &CryptoFactories,
+    ) -> Result<Self, TransactionServiceError> {
+        let fee = multisig_spend_fee(fee_per_gram, destination);
+        let value = amount
+            .checked_sub(fee)
+            .ok_or_else(|| TransactionServiceError::MultisigError("The fee exceeds the multisig amount".to_string()))?;
+        let spending_key = payment_spending_key(&nonces)?;
+        let rewind_blinding_key = PrivateKey::from_bytes(&hash_secret_key(&spending_key))?;
+        let rewind_data = RewindData {
+            rewind_key: PrivateKey::from_bytes(&hash_secret_key(&rewind_blinding_key))?,
+            rewind_blinding_key,
+            proof_message: [0u8; 21],
+        };
+        let sender_offset_public_key = nonces
+            .iter()
+            .fold(PublicKey::default(), |acc, n| &acc + &n.sender_offset_public_key);
+        let output = UnblindedOutput::new_current_version(
+            value,
+            spending_key,
+            OutputFeatures::default(),
+            destination_script(destination),
+            ExecutionStack::default(),
+            PrivateKey::default(),
+            sender_offset_public_key,
+            ComSignature::default(),
+            0,
+            Covenant::default(),
+        )
+        .as_rewindable_transaction_output(factories, &rewind_data, None)?;
+
+        Ok(Self {
+            request: MultisigSigningRequest {
+                signers,
+                nonces,
+                script_nonce: leader_nonces.script_nonce(factories),
+                metadata_nonce: leader_nonces.metadata_nonce(factories),
+                output,
+                fee,
+            },
+            leader_nonces,
+        })
+    }
+
+    /// Completes the spending transaction once every signer has sent its partial signature, in signer order. The
+    /// partial signatures are expected to have been verified already.
+    pub fn finalize(
+        &self,
+        input: &TransactionOutput,
+        input_spending_key: &PrivateKey,
+        amount: MicroTari,
+        script_public_key: &PublicKey,
+        partial_signatures: &[MultisigPartialSignature],
+        factories: &CryptoFactories,
+    ) -> Result<Transaction, TransactionServiceError> {
+        let request = &self.request;
+        if partial_signatures.len() != request.signers.len() {
+            return Err(TransactionServiceError::MultisigError(format!(
+                "Expected {} partial signatures but have {}",
+                request.signers.len(),
+                partial_signatures.len()
+            )));
+        }
+        let map_err = |e: tari_crypto::signatures::CommitmentSignatureError| {
+            TransactionServiceError::MultisigError(e.to_string())
+        };
+
+        let script_signature = ComSignature::sign(
+            PrivateKey::from(amount.as_u64()),
+            input_spending_key.clone(),
+            self.leader_nonces.script_value_nonce.clone(),
+            self.leader_nonces.script_blinding_nonce.clone(),
+            request.script_challenge(input, script_public_key)?.as_bytes(),
+            &*factories.commitment,
+        )
+        .map_err(map_err)?;
+        let script_signature = partial_signatures
+            .iter()
+            .fold(script_signature, |acc, p| &acc + &p.script_signature);
+        let input = TransactionInput::new_with_output_data(
+            TransactionInputVersion::get_current_version(),
+            input.features.clone(),
+            input.commitment.clone(),
+            input.script.clone(),
+            ExecutionStack::default(),
+            script_signature,
+            input.sender_offset_public_key.clone(),
+            input.covenant.clone(),
+        );
+
+        let value = amount - request.fee;
+        let output_spending_key = payment_spending_key(&request.nonces)?;
+        let metadata_signature = ComSignature::sign(
+            PrivateKey::from(value.as_u64()),
+            output_spending_key.clone(),
+            self.leader_nonces.metadata_value_nonce.clone(),
+            self.leader_nonces.metadata_blinding_nonce.clone(),
+            request.metadata_challenge()?.as_bytes(),
+            &*factories.commitment,
+        )
+        .map_err(map_err)?;
+        let mut output = request.output.clone();
+        output.metadata_signature = partial_signatures
+            .iter()
+            .fold(metadata_signature, |acc, p| &acc + &p.metadata_signature);
+
+        let script_offset = partial_signatures
+            .iter()
+            .fold(PrivateKey::default(), |acc, p| &acc + &p.script_offset);
+        let offset = PrivateKey::random(&mut OsRng);
+        let excess = &(&output_spending_key - input_spending_key) - &offset;
+        let nonce = PrivateKey::random(&mut OsRng);
+        let challenge = build_challenge(&PublicKey::from_secret_key(&nonce), &TransactionMetadata {
+            fee: request.fee,
+            lock_height: 0,
+        });
+        let signature = Signature::sign(excess.clone(), nonce, &challenge)
+            .map_err(|e| TransactionServiceError::MultisigError(e.to_string()))?;
+        let kernel = KernelBuilder::new()
+            .with_fee(request.fee)
+            .with_lock_height(0)
+            .with_excess(&Commitment::from_public_key(&PublicKey::from_secret_key(&excess)))
+            .with_signature(&signature)
+            .build()?;
+
+        Ok(Transaction::new(
+            vec![input],
+            vec![output],
+            vec![kernel],
+            offset,
+            script_offset,
+        ))
+    }
+}
+
+/// A message exchanged between the members of a multisig session
+#[derive(Debug, Clone)]
+pub struct MultisigMessage {
+    pub session_id: u64,
+    pub payload: MultisigPayload,
+}
+
+#[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum MultisigPayload {
+    Proposal {
+        threshold: u8,
+        members: Vec<CommsPublicKey>,
+        amount: MicroTari,
+        message: String,
+    },
+    Commitments(Vec<PublicKey>),
+    SessionCommitments(Vec<Vec<PublicKey>>),
+    KeyShare(PrivateKey),
+    KeySharesVerified,
+    Finalized {
+        output: TransactionOutput,
+        funding_tx_id: TxId,
+    },
+    SpendRequest {
+        destination: PublicKey,
+        fee_per_gram: MicroTari,
+        message: String,
+        nonce_commitment: Vec<u8>,
+    },
+    Approval(Vec<u8>),
+    NonceRequest(MultisigNonceRequest),
+    Nonces(MultisigNonces),
+    SigningRequest(MultisigSigningRequest),
+    PartialSignature(MultisigPartialSignature),
+    SpendBroadcast(TxId),
+}
+
+fn public_keys_from_bytes(keys: &[Vec<u8>]) -> Result<Vec<PublicKey>, String> {
+    keys.iter()
+        .map(|k| PublicKey::from_bytes(k))
+        .collect::<Result<_, _>>()
+        .map_err(|err| format!("{}", err))
+}
+
+impl TryFrom<proto::MultisigMessage> for MultisigMessage {
+    type Error = String;
+
+    fn try_from(message: proto::MultisigMessage) -> Result<Self, Self::Error> {
+        use proto::multisig_message::Message;
+        let payload = match message
+            .message
+            .ok_or_else(|| "Multisig message payload not provided".to_string())?
+        {
+            Message::Proposal(p) => MultisigPayload::Proposal {
+                threshold: u8::try_from(p.threshold).map_err(|_| "Invalid multisig threshold".to_string())?,
+                members: p
+                    .members
+                    .iter()
+                    .map(|m| CommsPublicKey::from_bytes(m))
+                    .collect::<Result<_, _>>()
+                    .map_err(|err| format!("{}", err))?,
+                amount: p.amount.into(),
+                message: p.message,
+            },
+            Message::Commitments(c) => MultisigPayload::Commitments(public_keys_from_bytes(&c.commitments)?),
+            Message::SessionCommitments(s) => MultisigPayload::SessionCommitments(
+                s.members
+                    .iter()
+                    .map(|c| public_keys_from_bytes(&c.commitments))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Message::KeyShare(k) => {
+                MultisigPayload::KeyShare(PrivateKey::from_bytes(&k.share).map_err(|err| format!("{}", err))?)
+            },
+            Message::KeySharesVerified(_) => MultisigPayload::KeySharesVerified,
+            Message::Finalized(f) => MultisigPayload::Finalized {
+                output: f
+                    .output
+                    .map(TryInto::try_into)
+                    .ok_or_else(|| "Multisig output not provided".to_string())??,
+                funding_tx_id: f.funding_tx_id.into(),
+            },
+            Message::SpendRequest(r) => MultisigPayload::SpendRequest {
+                destination: PublicKey::from_bytes(&r.destination).map_err(|err| format!("{}", err))?,
+                fee_per_gram: r.fee_per_gram.into(),
+                message: r.message,
+                nonce_commitment: r.nonce_commitment,
+            },
+            Message::Approval(a) => MultisigPayload::Approval(a.nonce_commitment),
+            Message::NonceRequest(r) => MultisigPayload::NonceRequest(r.into()),
+            Message::Nonces(n) => MultisigPayload::Nonces(n.try_into()?),
+            Message::SigningRequest(r) => MultisigPayload::SigningRequest(r.try_into()?),
+            Message::PartialSignature(p) => MultisigPayload::PartialSignature(p.try_into()?),
+            Message::SpendBroadcast(b) => MultisigPayload::SpendBroadcast(b.tx_id.into()),
+        };
+        Ok(Self {
+            session_id: message.session_id,
+            payload,
+        })
+    }
+}
+
+impl From<MultisigMessage> for proto::MultisigMessage {
+    fn from(message: MultisigMessage) -> Self {
+        use proto::multisig_message::Message;
+        let payload = match message.payload {
+            MultisigPayload::Proposal {
+                threshold,
+                members,
+                amount,
+                message,
+            } => Message::Proposal(proto::MultisigSessionProposal {
+                threshold: threshold.into(),
+                members: members.iter().map(|m| m.to_vec()).collect(),
+                amount: amount.into(),
+                message,
+            }),
+            MultisigPayload::Commitments(commitments) => Message::Commitments(proto::MultisigCommitments {
+                commitments: commitments.iter().map(|c| c.to_vec()).collect(),
+            }),
+            MultisigPayload::SessionCommitments(members) => {
+                Message::SessionCommitments(proto::MultisigSessionCommitments {
+                    members: members
+                        .iter()
+                        .map(|commitments| proto::MultisigCommitments {
+                            commitments: commitments.iter().map(|c| c.to_vec()).collect(),
+                        })
+                        .collect(),
+                })
+            },
+            MultisigPayload::KeyShare(share) => Message::KeyShare(proto::MultisigKeyShare { share: share.to_vec() }),
+            MultisigPayload::KeySharesVerified => Message::KeySharesVerified(proto::MultisigKeySharesVerified {}),
+            MultisigPayload::Finalized { output, funding_tx_id } => {
+                Message::Finalized(proto::MultisigSessionFinalized {
+                    output: Some(output.into()),
+                    funding_tx_id: funding_tx_id.into(),
+                })
+            },
+            MultisigPayload::SpendRequest {
+                destination,
+                fee_per_gram,
+                message,
+                nonce_commitment,
+            } => Message::SpendRequest(proto::MultisigSpendRequest {
+                destination: destination.to_vec(),
+                fee_per_gram: fee_per_gram.into(),
+                message,
+                nonce_commitment,
+            }),
+            MultisigPayload::Approval(nonce_commitment) => {
+                Message::Approval(proto::MultisigApproval { nonce_commitment })
+            },
+            MultisigPayload::NonceRequest(request) => Message::NonceRequest(request.into()),
+            MultisigPayload::Nonces(nonces) => Message::Nonces(nonces.into()),
+            MultisigPayload::SigningRequest(request) => Message::SigningRequest(request.into()),
+            MultisigPayload::PartialSignature(partial) => Message::PartialSignature(partial.into()),
+            MultisigPayload::SpendBroadcast(tx_id) => {
+                Message::SpendBroadcast(proto::MultisigSpendBroadcast { tx_id: tx_id.into() })
+            },
+        };
+        Self {
+            session_id: message.session_id,
+            message: Some(payload),
+        }
+    }
+}
+
+impl TryFrom<proto::MultisigNonces> for MultisigNonces {
+    type Error = String;
+
+    fn try_from(nonces: proto::MultisigNonces) -> Result<Self, Self::Error> {
+        let public_key = |bytes: &[u8]| PublicKey::from_bytes(bytes).map_err(|err| format!("{}", err));
+        let private_key = |bytes: &[u8]| PrivateKey::from_bytes(bytes).map_err(|err| format!("{}", err));
+        Ok(Self {
+            script_nonce: public_key(&nonces.script_nonce)?,
+            metadata_nonce: public_key(&nonces.metadata_nonce)?,
+            sender_offset_public_key: public_key(&nonces.sender_offset_public_key)?,
+            shared_secret: public_key(&nonces.shared_secret)?,
+            proof: SharedSecretProof {
+                challenge: private_key(&nonces.proof_challenge)?,
+                response: private_key(&nonces.proof_response)?,
+            },
+        })
+    }
+}
+
+impl From<MultisigNonces> for proto::MultisigNonces {
+    fn from(nonces: MultisigNonces) -> Self {
+        Self {
+            script_nonce: nonces.script_nonce.to_vec(),
+            metadata_nonce: nonces.metadata_nonce.to_vec(),
+            sender_offset_public_key: nonces.sender_offset_public_key.to_vec(),
+            shared_secret: nonces.shared_secret.to_vec(),
+            proof_challenge: nonces.proof.challenge.to_vec(),
+            proof_response: nonces.proof.response.to_vec(),
+        }
+    }
+}
+
+impl From<proto::MultisigNonceRequest> for MultisigNonceRequest {
+    fn from(request: proto::MultisigNonceRequest) -> Self {
+        Self {
+            signers: request.signers.iter().map(|s| *s as usize).collect(),
+            commitments: request.commitments,
+            leader_commitment: request.leader_commitment,
+        }
+    }
+}
+
+impl From<MultisigNonceRequest> for proto::MultisigNonceRequest {
+    fn from(request: MultisigNonceRequest) -> Self {
+        Self {
+            signers: request.signers.iter().map(|s| *s as u32).collect(),
+            commitments: request.commitments,
+            leader_commitment: request.leader_commitment,
+        }
+    }
+}
+
+impl TryFrom<proto::MultisigSigningRequest> for MultisigSigningRequest {
+    type Error = String;
+
+    fn try_from(request: proto::MultisigSigningRequest) -> Result<Self, Self::Error> {
+        Ok(Self {
+            signers: request.signers.iter().map(|s| *s as usize).collect(),
+            nonces: request
+                .nonces
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+            script_nonce: request
+                .script_nonce
+                .map(TryInto::try_into)
+                .ok_or_else(|| "Multisig script nonce not provided".to_string())?
+                .map_err(|err| format!("{}", err))?,
+            metadata_nonce: request
+                .metadata_nonce
+                .map(TryInto::try_into)
+                .ok_or_else(|| "Multisig metadata nonce not provided".to_string())?
+                .map_err(|err| format!("{}", err))?,
+            output: request
+                .output
+                .map(TryInto::try_into)
+                .ok_or_else(|| "Multisig output not provided".to_string())??,
+            fee: request.fee.into(),
+        })
+    }
+}
+
+impl From<MultisigSigningRequest> for proto::MultisigSigningRequest {
+    fn from(request: MultisigSigningRequest) -> Self {
+        Self {
+            signers: request.signers.iter().map(|s| *s as u32).collect(),
+            nonces: request.nonces.into_iter().map(Into::into).collect(),
+            script_nonce: Some(request.script_nonce.into()),
+            metadata_nonce: Some(request.metadata_nonce.into()),
+            output: Some(request.output.into()),
+            fee: request.fee.into(),
+        }
+    }
+}
+
+impl TryFrom<proto::MultisigPartialSignature> for MultisigPartialSignature {
+    type Error = String;
+
+    fn try_from(partial: proto::MultisigPartialSignature) -> Result<Self, Self::Error> {
+        Ok(Self {
+            script_signature: partial
+                .script_signature
+                .map(TryInto::try_into)
+                .ok_or_else(|| "Multisig script signature not provided".to_string())?
+                .map_err(|err| format!("{}", err))?,
+            metadata_signature: partial
+                .metadata_signature
+                .map(TryInto::try_into)
+                .ok_or_else(|| "Multisig metadata signature not provided".to_string())?
+                .map_err(|err| format!("{}", err))?,
+            script_offset: PrivateKey::from_bytes(&partial.script_offset).map_err(|err| format!("{}", err))?,
+        })
+    }
+}
+
+impl From<MultisigPartialSignature> for proto::MultisigPartialSignature {
+    fn from(partial: MultisigPartialSignature) -> Self {
+        Self {
+            script_signature: Some(partial.script_signature.into()),
+            metadata_signature: Some(partial.metadata_signature.into()),
+            script_offset: partial.script_offset.to_vec(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tari_core::transactions::transaction_components::TransactionError;
+
+    use super::*;
+
+    const THRESHOLD: u8 = 2;
+
+    struct Member {
+        polynomial: KeySharePolynomial,
+        key_share: PrivateKey,
+    }
+
+    struct Session {
+        members: Vec<Member>,
+        keys: MultisigSessionKeys,
+        input: TransactionOutput,
+        input_spending_key: PrivateKey,
+        amount: MicroTari,
+        destination: PublicKey,
+        factories: CryptoFactories,
+    }
+
+    fn setup() -> Session {
+        let factories = CryptoFactories::default();
+        let polynomials = (0..3)
+            .map(|_| {
+                KeySharePolynomial::new(
+                    &PrivateKey::random(&mut OsRng),
+                    &PrivateKey::random(&mut OsRng),
+                    THRESHOLD,
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let keys = MultisigSessionKeys::new(polynomials.iter().map(|p| p.commitments()).collect(), THRESHOLD).unwrap();
+        let shares = (0..3)
+            .map(|index| polynomials.iter().map(|p| p.share(index)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let members = polynomials
+            .into_iter()
+            .zip(&shares)
+            .map(|(polynomial, shares)| Member {
+                polynomial,
+                key_share: keys.key_share(shares),
+            })
+            .collect();
+
+        let amount = MicroTari::from(100_000);
+        let input_spending_key = PrivateKey::random(&mut OsRng);
+        let script = multisig_script(keys.script_public_key());
+        let features = OutputFeatures::default();
+        let covenant = Covenant::default();
+        let sender_offset_key = PrivateKey::random(&mut OsRng);
+        let metadata_signature = TransactionOutput::create_final_metadata_signature(
+            &amount,
+            &input_spending_key,
+            &script,
+            &features,
+            &sender_offset_key,
+            &covenant,
+        )
+        .unwrap();
+        let input = UnblindedOutput::new_current_version(
+            amount,
+            input_spending_key.clone(),
+            features,
+            script,
+            ExecutionStack::default(),
+            PrivateKey::default(),
+            PublicKey::from_secret_key(&sender_offset_key),
+            metadata_signature,
+            0,
+            covenant,
+        )
+        .as_transaction_output(&factories)
+        .unwrap();
+
+        Session {
+            members,
+            keys,
+            input,
+            input_spending_key,
+            amount,
+            destination: PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
+            factories,
+        }
+    }
+
+    impl Session {
+        fn nonce_request(
+            &self,
+            signers: &[usize],
+            nonces: &[MultisigNonces],
+            leader_nonces: &MultisigLeaderNonces,
+        ) -> MultisigNonceRequest {
+            MultisigNonceRequest {
+                signers: signers.to_vec(),
+                commitments: nonces.iter().map(MultisigNonces::commitment).collect(),
+                leader_commitment: leader_nonces.commitment(&self.factories),
+            }
+        }
+
+        fn template(
+            &self,
+            signers: &[usize],
+            nonces: &[MultisigNonces],
+            leader_nonces: MultisigLeaderNonces,
+        ) -> MultisigSpendTemplate {
+            MultisigSpendTemplate::new(
+                signers.to_vec(),
+                nonces.to_vec(),
+                leader_nonces,
+                &self.destination,
+                self.amount,
+                MicroTari::from(5),
+                &self.factories,
+            )
+            .unwrap()
+        }
+
+        /// Commits to fresh nonces for each signer and the leader, and builds the spend once they have been revealed
+        fn spend(
+            &self,
+            signers: &[usize],
+            secret_nonces: &[MultisigSecretNonces],
+        ) -> (MultisigNonceRequest, MultisigSpendTemplate) {
+            let nonces = secret_nonces
+                .iter()
+                .map(|n| n.public_nonces(&self.destination).unwrap())
+                .collect::<Vec<_>>();
+            let leader_nonces = MultisigLeaderNonces::random();
+            let nonce_request = self.nonce_request(signers, &nonces, &leader_nonces);
+            (nonce_request, self.template(signers, &nonces, leader_nonces))
+        }
+
+        fn validate(
+            &self,
+            nonce_request: &MultisigNonceRequest,
+            template: &MultisigSpendTemplate,
+        ) -> Result<(), TransactionServiceError> {
+            template.request.validate(
+                nonce_request,
+                THRESHOLD,
+                &self.destination,
+                self.amount,
+                MicroTari::from(5),
+                &self.factories,
+            )
+        }
+
+        fn sign(
+            &self,
+            template: &MultisigSpendTemplate,
+            secret_nonces: &[MultisigSecretNonces],
+        ) -> Vec<MultisigPartialSignature> {
+            template
+                .request
+                .signers
+                .iter()
+                .zip(secret_nonces)
+                .map(|(index, nonces)| {
+                    MultisigPartialSignature::sign(
+                        &template.request,
+                        *index,
+                        &self.members[*index].key_share,
+                        nonces,
+                        &self.input,
+                        &self.keys.script_public_key(),
+                    )
+                    .unwrap()
+                })
+                .collect()
+        }
+
+        fn finalize(&self, template: &MultisigSpendTemplate, partials: &[MultisigPartialSignature]) -> Transaction {
+            template
+                .finalize(
+                    &self.input,
+                    &self.input_spending_key,
+                    self.amount,
+                    &self.keys.script_public_key(),
+                    partials,
+                    &self.factories,
+                )
+                .unwrap()
+        }
+    }
+
+    #[test]
+    fn key_shares_are_verified_against_the_commitments() {
+        let session = setup();
+        let commitments = session.members[1].polynomial.commitments();
+        let share = session.members[1].polynomial.share(2);
+        assert!(verify_key_share(&commitments, 2, &share));
+        assert!(!verify_key_share(&commitments, 0, &share));
+        assert!(!verify_key_share(&commitments, 2, &(&share + &PrivateKey::from(1))));
+
+        for (index, member) in session.members.iter().enumerate() {
+            assert_eq!(
+                PublicKey::from_secret_key(&member.key_share),
+                session.keys.verification_key(index)
+            );
+        }
+    }
+
+    #[test]
+    fn scalars_are_inverted() {
+        let k = PrivateKey::random(&mut OsRng);
+        assert_eq!(&k * &invert(&k), PrivateKey::from(1));
+    }
+
+    #[test]
+    fn threshold_of_members_can_spend() {
+        let session = setup();
+        let secret_nonces = vec![MultisigSecretNonces::random(), MultisigSecretNonces::random()];
+        let (nonce_request, template) = session.spend(&[0, 2], &secret_nonces);
+        session.validate(&nonce_request, &template).unwrap();
+        let partials = session.sign(&template, &secret_nonces);
+        for (index, partial) in template.request.signers.iter().zip(&partials) {
+            assert!(partial.verify(
+                &template.request,
+                *index,
+                &session.keys.verification_key(*index),
+                &session.input,
+                &session.keys.script_public_key(),
+            ));
+        }
+
+        let tx = session.finalize(&template, &partials);
+        tx.validate_internal_consistency(false, &session.factories, None, None, u64::MAX)
+            .unwrap();
+    }
+
+    #[test]
+    fn fewer_than_threshold_members_cannot_spend() {
+        let session = setup();
+        let secret_nonces = vec![MultisigSecretNonces::random()];
+        let (nonce_request, template) = session.spend(&[1], &secret_nonces);
+        assert!(session.validate(&nonce_request, &template).is_err());
+
+        let partials = session.sign(&template, &secret_nonces);
+        let tx = session.finalize(&template, &partials);
+        assert!(matches!(
+            tx.validate_internal_consistency(false, &session.factories, None, None, u64::MAX),
+            Err(TransactionError::InvalidSignatureError(_))
+        ));
+    }
+
+    #[test]
+    fn reused_partial_signature_is_rejected() {
+        let session = setup();
+        let first_nonces = vec![MultisigSecretNonces::random(), MultisigSecretNonces::random()];
+        let (_, first_template) = session.spend(&[0, 1], &first_nonces);
+        let first_partials = session.sign(&first_template, &first_nonces);
+
+        let second_nonces = vec![MultisigSecretNonces::random(), MultisigSecretNonces::random()];
+        let (_, second_template) = session.spend(&[0, 1], &second_nonces);
+        let mut partials = session.sign(&second_template, &second_nonces);
+        partials[1] = first_partials[1].clone();
+        assert!(!partials[1].verify(
+            &second_template.request,
+            1,
+            &session.keys.verification_key(1),
+            &session.input,
+            &session.keys.script_public_key(),
+        ));
+
+        let tx = session.finalize(&second_template, &partials);
+        assert!(matches!(
+            tx.validate_internal_consistency(false, &session.factories, None, None, u64::MAX),
+            Err(TransactionError::InvalidSignatureError(_))
+        ));
+    }
+
+    #[test]
+    fn nonces_that_do_not_match_their_commitments_are_rejected() {
+        let session = setup();
+        let secret_nonces = vec![MultisigSecretNonces::random(), MultisigSecretNonces::random()];
+        let nonces = secret_nonces
+            .iter()
+            .map(|n| n.public_nonces(&session.destination).unwrap())
+            .collect::<Vec<_>>();
+        let leader_nonces = MultisigLeaderNonces::random();
+        let nonce_request = session.nonce_request(&[0, 2], &nonces, &leader_nonces);
+        assert!(nonce_request.verify_nonces(0, &nonces[0], &session.destination));
+        assert!(nonce_request.verify_nonces(2, &nonces[1], &session.destination));
+        assert!(nonce_request.validate(THRESHOLD, 2, &nonces[1]).is_ok());
+
+        // A signer that picks new nonces once it has seen the others is caught by the leader
+        let late_nonces = MultisigSecretNonces::random()
+            .public_nonces(&session.destination)
+            .unwrap();
+        assert!(!nonce_request.verify_nonces(2, &late_nonces, &session.destination));
+        assert!(nonce_request.validate(THRESHOLD, 2, &late_nonces).is_err());
+
+        // A leader that slips different signer nonces into the signing request is caught by the signers
+        let template = session.template(&[0, 2], &[nonces[0].clone(), late_nonces], leader_nonces);
+        assert!(session.validate(&nonce_request, &template).is_err());
+    }
+
+    #[test]
+    fn leader_nonces_revealed_late_are_rejected() {
+        let session = setup();
+        let secret_nonces = vec![MultisigSecretNonces::random(), MultisigSecretNonces::random()];
+        let nonces = secret_nonces
+            .iter()
+            .map(|n| n.public_nonces(&session.destination).unwrap())
+            .collect::<Vec<_>>();
+        let committed_nonces = MultisigLeaderNonces::random();
+        let nonce_request = session.nonce_request(&[0, 2], &nonces, &committed_nonces);
+
+        // The leader picks its nonces after the signers have revealed theirs, instead of the ones it committed to
+        let template = session.template(&[0, 2], &nonces, MultisigLeaderNonces::random());
+        match session.validate(&nonce_request, &template) {
+            Err(TransactionServiceError::MultisigError(reason)) => {
+                assert!(reason.contains("the leader's nonces do not match its commitment"))
+            },
+            other => panic!("Expected the signing request to be rejected, got {:?}", other),
+        }
+
+        let template = session.template(&[0, 2], &nonces, committed_nonces);
+        session.validate(&nonce_request, &template).unwrap();
+    }
+}
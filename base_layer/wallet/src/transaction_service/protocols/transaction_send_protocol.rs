@@ -56,6 +56,7 @@ use tokio::{
 
 use crate::{
     connectivity_service::WalletConnectivityInterface,
+    output_manager_service::service::UTXOSelectionStrategy,
     transaction_service::{
         config::TransactionRoutingMechanism,
         error::{TransactionServiceError, TransactionServiceProtocolError},
@@ -90,6 +91,8 @@ pub struct TransactionSendProtocol<TBackend, TWalletConnectivity> {
     parent_public_key: Option<PublicKey>,
     fee_per_gram: MicroTari,
     message: String,
+    covenant: Covenant,
+    selection_strategy: Option<UTXOSelectionStrategy>,
     service_request_reply_channel: Option<oneshot::Sender<Result<TransactionServiceResponse, TransactionServiceError>>>,
     stage: TransactionSendProtocolStage,
     resources: TransactionServiceResources<TBackend, TWalletConnectivity>,
@@ -115,6 +118,8 @@ where
         parent_public_key: Option<PublicKey>,
         fee_per_gram: MicroTari,
         message: String,
+        covenant: Covenant,
+        selection_strategy: Option<UTXOSelectionStrategy>,
         service_request_reply_channel: Option<
             oneshot::Sender<Result<TransactionServiceResponse, TransactionServiceError>>,
         >,
@@ -133,6 +138,8 @@ where
             parent_public_key,
             fee_per_gram,
             message,
+            covenant,
+            selection_strategy,
             service_request_reply_channel,
             stage,
             prev_header,
@@ -188,7 +195,8 @@ where
                 None,
                 self.message.clone(),
                 script!(Nop),
-                Covenant::default(),
+                self.covenant.clone(),
+                self.selection_strategy.clone(),
             )
             .await
         {
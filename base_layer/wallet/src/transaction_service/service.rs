@@ -22,7 +22,7 @@
 
 use std::{
     collections::{HashMap, HashSet},
-    convert::TryInto,
+    convert::{TryFrom, TryInto},
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -53,6 +53,7 @@ use tari_core::{
         },
         CryptoFactories,
         ReceiverTransactionProtocol,
+        SenderTransactionProtocol,
     },
 };
 use tari_crypto::{
@@ -73,14 +74,40 @@ use crate::{
     base_node_service::handle::{BaseNodeEvent, BaseNodeServiceHandle},
     connectivity_service::WalletConnectivityInterface,
     output_manager_service::{
+        error::OutputManagerError,
         handle::{OutputManagerEvent, OutputManagerHandle},
+        service::UTXOSelectionStrategy,
         storage::models::SpendingPriority,
+        watch_only::{OfflineSignedTransaction, UnsignedTransactionTemplate},
     },
     storage::database::{WalletBackend, WalletDatabase},
     transaction_service::{
         config::TransactionServiceConfig,
-        error::{TransactionServiceError, TransactionServiceProtocolError},
-        handle::{TransactionEvent, TransactionEventSender, TransactionServiceRequest, TransactionServiceResponse},
+        error::{TransactionServiceError, TransactionServiceProtocolError, TransactionStorageError},
+        handle::{
+            BatchPaymentRecipient,
+            ScheduledPaymentRequest,
+            TransactionEvent,
+            TransactionEventSender,
+            TransactionServiceRequest,
+            TransactionServiceResponse,
+        },
+        multisig::{
+            multisig_script,
+            verify_key_share,
+            KeySharePolynomial,
+            MultisigLeaderNonces,
+            MultisigMessage,
+            MultisigNonceRequest,
+            MultisigNonces,
+            MultisigPartialSignature,
+            MultisigPayload,
+            MultisigSecretNonces,
+            MultisigSessionKeys,
+            MultisigSigningRequest,
+            MultisigSpendTemplate,
+            MAX_MULTISIG_MEMBERS,
+        },
         protocols::{
             transaction_broadcast_protocol::TransactionBroadcastProtocol,
             transaction_receive_protocol::{TransactionReceiveProtocol, TransactionReceiveProtocolStage},
@@ -89,11 +116,21 @@ use crate::{
         },
         storage::{
             database::{TransactionBackend, TransactionDatabase},
-            models::{CompletedTransaction, TxCancellationReason},
+            models::{
+                BatchPayment,
+                CompletedTransaction,
+                MultisigSession,
+                MultisigSessionStatus,
+                MultisigSpend,
+                ScheduledPayment,
+                ScheduledPaymentStatus,
+                TxCancellationReason,
+            },
         },
         tasks::{
             check_faux_transaction_status::check_faux_transactions,
             send_finalized_transaction::send_finalized_transaction_message,
+            send_multisig_message::send_multisig_message,
             send_transaction_cancelled::send_transaction_cancelled_message,
             send_transaction_reply::send_transaction_reply,
         },
@@ -127,6 +164,7 @@ pub struct TransactionService<
     BNResponseStream,
     TBackend,
     TTxCancelledStream,
+    TMultisigStream,
     TWalletBackend,
     TWalletConnectivity,
 > {
@@ -138,6 +176,7 @@ pub struct TransactionService<
     transaction_finalized_stream: Option<TTxFinalizedStream>,
     base_node_response_stream: Option<BNResponseStream>,
     transaction_cancelled_stream: Option<TTxCancelledStream>,
+    multisig_stream: Option<TMultisigStream>,
     request_stream: Option<
         reply_channel::Receiver<TransactionServiceRequest, Result<TransactionServiceResponse, TransactionServiceError>>,
     >,
@@ -163,6 +202,7 @@ impl<
         BNResponseStream,
         TBackend,
         TTxCancelledStream,
+        TMultisigStream,
         TWalletBackend,
         TWalletConnectivity,
     >
@@ -173,6 +213,7 @@ impl<
         BNResponseStream,
         TBackend,
         TTxCancelledStream,
+        TMultisigStream,
         TWalletBackend,
         TWalletConnectivity,
     >
@@ -182,6 +223,7 @@ where
     TTxFinalizedStream: Stream<Item = DomainMessage<proto::TransactionFinalizedMessage>>,
     BNResponseStream: Stream<Item = DomainMessage<base_node_proto::BaseNodeServiceResponse>>,
     TTxCancelledStream: Stream<Item = DomainMessage<proto::TransactionCancelledMessage>>,
+    TMultisigStream: Stream<Item = DomainMessage<proto::MultisigMessage>>,
     TBackend: TransactionBackend + 'static,
     TWalletBackend: WalletBackend + 'static,
     TWalletConnectivity: WalletConnectivityInterface,
@@ -199,6 +241,7 @@ where
         transaction_finalized_stream: TTxFinalizedStream,
         base_node_response_stream: BNResponseStream,
         transaction_cancelled_stream: TTxCancelledStream,
+        multisig_stream: TMultisigStream,
         output_manager_service: OutputManagerHandle,
         outbound_message_service: OutboundMessageRequester,
         connectivity: TWalletConnectivity,
@@ -238,6 +281,7 @@ where
             transaction_finalized_stream: Some(transaction_finalized_stream),
             base_node_response_stream: Some(base_node_response_stream),
             transaction_cancelled_stream: Some(transaction_cancelled_stream),
+            multisig_stream: Some(multisig_stream),
             request_stream: Some(request_stream),
             event_publisher,
             node_identity,
@@ -293,6 +337,12 @@ where
             .expect("Transaction Service initialized without transaction_cancelled_stream")
             .fuse();
         pin_mut!(transaction_cancelled_stream);
+        let multisig_stream = self
+            .multisig_stream
+            .take()
+            .expect("Transaction Service initialized without multisig_stream")
+            .fuse();
+        pin_mut!(multisig_stream);
 
         let mut shutdown = self.resources.shutdown_signal.clone();
 
@@ -327,7 +377,12 @@ where
                 // Base Node Monitoring Service event
                 event = base_node_service_event_stream.recv() => {
                     match event {
-                        Ok(msg) => self.handle_base_node_service_event(msg, &mut transaction_validation_protocol_handles).await,
+                        Ok(msg) => self.handle_base_node_service_event(
+                            msg,
+                            &mut transaction_validation_protocol_handles,
+                            &mut send_transaction_protocol_handles,
+                            &mut transaction_broadcast_protocol_handles,
+                        ).await,
                         Err(e) => debug!(target: LOG_TARGET, "Lagging read on base node event broadcast channel: {}", e),
                     };
                 },
@@ -338,7 +393,12 @@ where
                     let (request, reply_tx) = request_context.split();
                     let event = format!("Handling Service API Request ({})", request);
                     trace!(target: LOG_TARGET, "{}", event);
+                    let (request, account_id) = match request {
+                        TransactionServiceRequest::ForAccount(account_id, request) => (*request, Some(account_id)),
+                        request => (request, None),
+                    };
                     let _ = self.handle_request(request,
+                        account_id,
                         &mut send_transaction_protocol_handles,
                         &mut receive_transaction_protocol_handles,
                         &mut transaction_broadcast_protocol_handles,
@@ -483,6 +543,18 @@ where
                         start.elapsed().as_millis(),
                     );
                 }
+                // Incoming messages from the Comms layer
+                Some(msg) = multisig_stream.next() => {
+                    let (origin_public_key, inner_msg) = msg.clone().into_origin_and_inner();
+                    trace!(target: LOG_TARGET, "Handling Multisig message, Trace: {}", msg.dht_header.message_tag);
+                    if let Err(e) = self.handle_multisig_message(
+                        origin_public_key,
+                        inner_msg,
+                        &mut transaction_broadcast_protocol_handles,
+                    ).await {
+                        warn!(target: LOG_TARGET, "Error handling Multisig message: {:?}", e);
+                    }
+                }
                 Some(join_result) = send_transaction_protocol_handles.next() => {
                     trace!(target: LOG_TARGET, "Send Protocol for Transaction has ended with result {:?}", join_result);
                     match join_result {
@@ -527,10 +599,13 @@ where
         Ok(())
     }
 
-    /// This handler is called when requests arrive from the various streams
+    /// Handles a request from the service API. The account id is given when the request was made through a handle
+    /// scoped to an account. Sending and transaction history act on that account, while all other requests act on the
+    /// wallet as a whole.
     async fn handle_request(
         &mut self,
         request: TransactionServiceRequest,
+        account_id: Option<u64>,
         send_transaction_join_handles: &mut FuturesUnordered<JoinHandle<Result<TxId, TransactionServiceProtocolError>>>,
         receive_transaction_join_handles: &mut FuturesUnordered<
             JoinHandle<Result<TxId, TransactionServiceProtocolError>>,
@@ -554,15 +629,20 @@ where
                 parent_public_key,
                 fee_per_gram,
                 message,
+                covenant,
+                selection_strategy,
             } => {
                 let rp = reply_channel.take().expect("Cannot be missing");
                 self.send_transaction(
+                    account_id,
                     dest_pubkey,
                     amount,
                     unique_id,
                     parent_public_key,
                     fee_per_gram,
                     message,
+                    covenant,
+                    selection_strategy,
                     send_transaction_join_handles,
                     transaction_broadcast_join_handles,
                     rp,
@@ -577,14 +657,70 @@ where
                 parent_public_key,
                 fee_per_gram,
                 message,
+                covenant,
+                selection_strategy,
             } => self
                 .send_one_sided_transaction(
+                    account_id,
                     dest_pubkey,
                     amount,
                     unique_id,
                     parent_public_key,
                     fee_per_gram,
                     message,
+                    covenant,
+                    selection_strategy,
+                    None,
+                    transaction_broadcast_join_handles,
+                )
+                .await
+                .map(TransactionServiceResponse::TransactionSent),
+            TransactionServiceRequest::SendTimeLockedTransaction {
+                dest_pubkey,
+                amount,
+                fee_per_gram,
+                message,
+                lock_height,
+            } => self
+                .send_one_sided_transaction(
+                    account_id,
+                    dest_pubkey,
+                    amount,
+                    None,
+                    None,
+                    fee_per_gram,
+                    message,
+                    Covenant::default(),
+                    None,
+                    Some(lock_height),
+                    transaction_broadcast_join_handles,
+                )
+                .await
+                .map(TransactionServiceResponse::TransactionSent),
+            TransactionServiceRequest::SchedulePayment(request) => self
+                .schedule_payment(*request)
+                .await
+                .map(TransactionServiceResponse::PaymentScheduled),
+            TransactionServiceRequest::GetScheduledPayments => Ok(TransactionServiceResponse::ScheduledPayments(
+                self.db.get_scheduled_payments().await?,
+            )),
+            TransactionServiceRequest::GetBatchPayments(tx_id) => Ok(TransactionServiceResponse::BatchPayments(
+                self.db.get_batch_payments(tx_id).await?,
+            )),
+            TransactionServiceRequest::CancelScheduledPayment(id) => self
+                .cancel_scheduled_payment(id)
+                .await
+                .map(|_| TransactionServiceResponse::ScheduledPaymentCancelled),
+            TransactionServiceRequest::SendOneSidedBatchTransaction {
+                recipients,
+                fee_per_gram,
+                message,
+            } => self
+                .send_one_sided_batch_transaction(
+                    account_id,
+                    recipients,
+                    fee_per_gram,
+                    message,
                     transaction_broadcast_join_handles,
                 )
                 .await
@@ -592,6 +728,7 @@ where
             TransactionServiceRequest::SendShaAtomicSwapTransaction(dest_pubkey, amount, fee_per_gram, message) => {
                 Ok(TransactionServiceResponse::ShaAtomicSwapTransactionSent(
                     self.send_sha_atomic_swap_transaction(
+                        account_id,
                         dest_pubkey,
                         amount,
                         fee_per_gram,
@@ -601,24 +738,69 @@ where
                     .await?,
                 ))
             },
+            TransactionServiceRequest::SignOfflineTransaction(template) => self
+                .sign_offline_transaction(*template)
+                .await
+                .map(|signed| TransactionServiceResponse::OfflineTransactionSigned(Box::new(signed))),
+            TransactionServiceRequest::SubmitOfflineTransaction(signed) => self
+                .submit_offline_transaction(*signed, transaction_broadcast_join_handles)
+                .await
+                .map(TransactionServiceResponse::TransactionSent),
+            TransactionServiceRequest::CreateMultisigOutput {
+                cosigners,
+                threshold,
+                amount,
+                fee_per_gram,
+                message,
+            } => self
+                .create_multisig_output(cosigners, threshold, amount, fee_per_gram, message)
+                .await
+                .map(TransactionServiceResponse::MultisigSessionCreated),
+            TransactionServiceRequest::ProposeMultisigSpend {
+                session_id,
+                destination,
+                fee_per_gram,
+                message,
+            } => self
+                .propose_multisig_spend(
+                    session_id,
+                    destination,
+                    fee_per_gram,
+                    message,
+                    transaction_broadcast_join_handles,
+                )
+                .await
+                .map(|_| TransactionServiceResponse::MultisigSpendProposed),
+            TransactionServiceRequest::ApproveMultisigSpend(session_id) => self
+                .approve_multisig_spend(session_id, transaction_broadcast_join_handles)
+                .await
+                .map(|_| TransactionServiceResponse::MultisigSpendApproved),
+            TransactionServiceRequest::GetMultisigSessions => Ok(TransactionServiceResponse::MultisigSessions(
+                self.db.get_multisig_sessions().await?,
+            )),
             TransactionServiceRequest::CancelTransaction(tx_id) => self
                 .cancel_pending_transaction(tx_id)
                 .await
                 .map(|_| TransactionServiceResponse::TransactionCancelled),
             TransactionServiceRequest::GetPendingInboundTransactions => {
-                Ok(TransactionServiceResponse::PendingInboundTransactions(
-                    self.db.get_pending_inbound_transactions().await?,
-                ))
+                let transactions = self.db.get_pending_inbound_transactions().await?;
+                self.retain_account_transactions(account_id, transactions)
+                    .await
+                    .map(TransactionServiceResponse::PendingInboundTransactions)
             },
             TransactionServiceRequest::GetPendingOutboundTransactions => {
-                Ok(TransactionServiceResponse::PendingOutboundTransactions(
-                    self.db.get_pending_outbound_transactions().await?,
-                ))
+                let transactions = self.db.get_pending_outbound_transactions().await?;
+                self.retain_account_transactions(account_id, transactions)
+                    .await
+                    .map(TransactionServiceResponse::PendingOutboundTransactions)
             },
 
-            TransactionServiceRequest::GetCompletedTransactions => Ok(
-                TransactionServiceResponse::CompletedTransactions(self.db.get_completed_transactions().await?),
-            ),
+            TransactionServiceRequest::GetCompletedTransactions => {
+                let transactions = self.db.get_completed_transactions().await?;
+                self.retain_account_transactions(account_id, transactions)
+                    .await
+                    .map(TransactionServiceResponse::CompletedTransactions)
+            },
             TransactionServiceRequest::GetCancelledPendingInboundTransactions => {
                 Ok(TransactionServiceResponse::PendingInboundTransactions(
                     self.db.get_cancelled_pending_inbound_transactions().await?,
@@ -716,6 +898,23 @@ where
                 .start_transaction_revalidation(transaction_validation_join_handles)
                 .await
                 .map(TransactionServiceResponse::ValidationStarted),
+            TransactionServiceRequest::TransferBetweenAccounts {
+                to_account,
+                amount,
+                fee_per_gram,
+                message,
+            } => self
+                .transfer_between_accounts(
+                    account_id,
+                    transaction_broadcast_join_handles,
+                    to_account,
+                    amount,
+                    fee_per_gram,
+                    message,
+                )
+                .await
+                .map(TransactionServiceResponse::TransactionSent),
+            TransactionServiceRequest::ForAccount(_, _) => Err(TransactionServiceError::NestedAccountRequest),
         };
 
         // If the individual handlers did not already send the API response then do it here.
@@ -734,6 +933,10 @@ where
         transaction_validation_join_handles: &mut FuturesUnordered<
             JoinHandle<Result<OperationId, TransactionServiceProtocolError>>,
         >,
+        send_transaction_join_handles: &mut FuturesUnordered<JoinHandle<Result<TxId, TransactionServiceProtocolError>>>,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError>>,
+        >,
     ) {
         match (*event).clone() {
             BaseNodeEvent::BaseNodeStateChanged(state) => {
@@ -753,6 +956,13 @@ where
                         });
                 }
                 self.last_seen_tip_height = state.chain_metadata.map(|cm| cm.height_of_longest_chain());
+
+                if let Err(e) = self
+                    .process_scheduled_payments(send_transaction_join_handles, transaction_broadcast_join_handles)
+                    .await
+                {
+                    warn!(target: LOG_TARGET, "Error processing scheduled payments: {}", e);
+                }
             },
             BaseNodeEvent::NewBlockDetected(_) => {},
         }
@@ -787,18 +997,22 @@ where
     /// 'fee_per_gram': The amount of fee per transaction gram to be included in transaction
     pub async fn send_transaction(
         &mut self,
+        account_id: Option<u64>,
         dest_pubkey: CommsPublicKey,
         amount: MicroTari,
         unique_id: Option<Vec<u8>>,
         parent_public_key: Option<PublicKey>,
         fee_per_gram: MicroTari,
         message: String,
+        covenant: Covenant,
+        selection_strategy: Option<UTXOSelectionStrategy>,
         join_handles: &mut FuturesUnordered<JoinHandle<Result<TxId, TransactionServiceProtocolError>>>,
         transaction_broadcast_join_handles: &mut FuturesUnordered<
             JoinHandle<Result<TxId, TransactionServiceProtocolError>>,
         >,
         reply_channel: oneshot::Sender<Result<TransactionServiceResponse, TransactionServiceError>>,
-    ) -> Result<(), TransactionServiceError> {
+    ) -> Result<TxId, TransactionServiceError> {
+        let mut output_manager = self.account_output_manager(account_id);
         let tx_id = TxId::new_random();
 
         // If we're paying ourselves, let's complete and submit the transaction immediately
@@ -808,8 +1022,7 @@ where
                 "Received transaction with spend-to-self transaction"
             );
 
-            let (fee, transaction) = self
-                .output_manager_service
+            let (fee, transaction) = output_manager
                 .create_pay_to_self_transaction(
                     tx_id,
                     amount,
@@ -818,6 +1031,7 @@ where
                     fee_per_gram,
                     None,
                     message.clone(),
+                    selection_strategy,
                 )
                 .await?;
 
@@ -852,7 +1066,7 @@ where
                     e
                 });
 
-            return Ok(());
+            return Ok(tx_id);
         }
 
         let (tx_reply_sender, tx_reply_receiver) = mpsc::channel(100);
@@ -863,7 +1077,7 @@ where
 
         let protocol = TransactionSendProtocol::new(
             tx_id,
-            self.resources.clone(),
+            self.account_resources(account_id),
             tx_reply_receiver,
             cancellation_receiver,
             dest_pubkey,
@@ -872,6 +1086,8 @@ where
             parent_public_key,
             fee_per_gram,
             message,
+            covenant,
+            selection_strategy,
             Some(reply_channel),
             TransactionSendProtocolStage::Initial,
             None,
@@ -881,7 +1097,7 @@ where
         let join_handle = tokio::spawn(protocol.execute());
         join_handles.push(join_handle);
 
-        Ok(())
+        Ok(tx_id)
     }
 
     /// broadcasts a SHA-XTR atomic swap transaction
@@ -891,6 +1107,7 @@ where
     /// 'fee_per_gram': The amount of fee per transaction gram to be included in transaction
     pub async fn send_sha_atomic_swap_transaction(
         &mut self,
+        account_id: Option<u64>,
         dest_pubkey: CommsPublicKey,
         amount: MicroTari,
         fee_per_gram: MicroTari,
@@ -899,6 +1116,7 @@ where
             JoinHandle<Result<TxId, TransactionServiceProtocolError>>,
         >,
     ) -> Result<Box<(TxId, PublicKey, TransactionOutput)>, TransactionServiceError> {
+        let mut output_manager = self.account_output_manager(account_id);
         let tx_id = TxId::new_random();
         // this can be anything, so lets generate a random private key
         let pre_image = PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng));
@@ -914,8 +1132,7 @@ where
         let covenant = Covenant::default();
 
         // Prepare sender part of the transaction
-        let mut stp = self
-            .output_manager_service
+        let mut stp = output_manager
             .prepare_transaction_to_send(
                 tx_id,
                 amount,
@@ -926,6 +1143,7 @@ where
                 message.clone(),
                 script.clone(),
                 covenant.clone(),
+                None,
             )
             .await?;
 
@@ -935,7 +1153,7 @@ where
             .build_single_round_message()
             .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
 
-        self.output_manager_service
+        output_manager
             .confirm_pending_transaction(tx_id)
             .await
             .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
@@ -957,8 +1175,8 @@ where
         let rewind_key = PrivateKey::from_bytes(&hash_secret_key(&blinding_key))?;
 
         let rewind_data = RewindData {
-            rewind_key: rewind_key.clone(),
-            rewind_blinding_key: blinding_key.clone(),
+            rewind_key,
+            rewind_blinding_key: blinding_key,
             proof_message: [0u8; 21],
         };
 
@@ -1021,7 +1239,7 @@ where
         let fee = stp
             .get_fee_amount()
             .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
-        self.output_manager_service
+        output_manager
             .add_rewindable_output_with_tx_id(
                 tx_id,
                 unblinded_output,
@@ -1058,16 +1276,21 @@ where
     /// 'fee_per_gram': The amount of fee per transaction gram to be included in transaction
     pub async fn send_one_sided_transaction(
         &mut self,
+        account_id: Option<u64>,
         dest_pubkey: CommsPublicKey,
         amount: MicroTari,
         unique_id: Option<Vec<u8>>,
         parent_public_key: Option<PublicKey>,
         fee_per_gram: MicroTari,
         message: String,
+        covenant: Covenant,
+        selection_strategy: Option<UTXOSelectionStrategy>,
+        recipient_lock_height: Option<u64>,
         transaction_broadcast_join_handles: &mut FuturesUnordered<
             JoinHandle<Result<TxId, TransactionServiceProtocolError>>,
         >,
     ) -> Result<TxId, TransactionServiceError> {
+        let mut output_manager = self.account_output_manager(account_id);
         if self.node_identity.public_key() == &dest_pubkey {
             warn!(target: LOG_TARGET, "One-sided spend-to-self transactions not supported");
             return Err(TransactionServiceError::OneSidedTransactionError(
@@ -1078,20 +1301,38 @@ where
         let tx_id = TxId::new_random();
 
         // Prepare sender part of the transaction
-        let mut stp = self
-            .output_manager_service
-            .prepare_transaction_to_send(
-                tx_id,
-                amount,
-                unique_id.clone(),
-                parent_public_key.clone(),
-                fee_per_gram,
-                None,
-                message.clone(),
-                script!(PushPubKey(Box::new(dest_pubkey.clone()))),
-                Covenant::default(),
-            )
-            .await?;
+        let script = script!(PushPubKey(Box::new(dest_pubkey.clone())));
+        let mut stp = match recipient_lock_height {
+            // The recipient's output is time locked through its maturity, which base nodes enforce when it is spent
+            Some(lock_height) => {
+                output_manager
+                    .prepare_time_locked_transaction_to_send(
+                        tx_id,
+                        amount,
+                        fee_per_gram,
+                        message.clone(),
+                        script,
+                        lock_height,
+                    )
+                    .await?
+            },
+            None => {
+                output_manager
+                    .prepare_transaction_to_send(
+                        tx_id,
+                        amount,
+                        unique_id.clone(),
+                        parent_public_key.clone(),
+                        fee_per_gram,
+                        None,
+                        message.clone(),
+                        script,
+                        covenant,
+                        selection_strategy,
+                    )
+                    .await?
+            },
+        };
 
         // This call is needed to advance the state from `SingleRoundMessageReady` to `SingleRoundMessageReady`,
         // but the returned value is not used
@@ -1099,29 +1340,194 @@ where
             .build_single_round_message()
             .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
 
-        self.output_manager_service
+        output_manager
             .confirm_pending_transaction(tx_id)
             .await
             .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
 
         // Prepare receiver part of the transaction
+        let recipient_reply = self.create_one_sided_recipient_reply(&stp, &dest_pubkey, tx_id)?;
+
+        // Start finalizing
 
+        stp.add_single_recipient_info(recipient_reply, &self.resources.factories.range_proof)
+            .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
+
+        // Finalize
+
+        stp.finalize(
+            KernelFeatures::empty(),
+            &self.resources.factories,
+            None,
+            self.last_seen_tip_height.unwrap_or(u64::MAX),
+        )
+        .map_err(|e| {
+            error!(
+                target: LOG_TARGET,
+                "Transaction (TxId: {}) could not be finalized. Failure error: {:?}", tx_id, e,
+            );
+            TransactionServiceProtocolError::new(tx_id, e.into())
+        })?;
+        info!(target: LOG_TARGET, "Finalized one-side transaction TxId: {}", tx_id);
+
+        // This event being sent is important, but not critical to the protocol being successful. Send only fails if
+        // there are no subscribers.
+        let _ = self
+            .event_publisher
+            .send(Arc::new(TransactionEvent::TransactionCompletedImmediately(tx_id)));
+
+        // Broadcast one-sided transaction
+
+        let tx = stp
+            .get_transaction()
+            .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
+        let fee = stp
+            .get_fee_amount()
+            .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
+        self.submit_transaction(
+            transaction_broadcast_join_handles,
+            CompletedTransaction::new(
+                tx_id,
+                self.resources.node_identity.public_key().clone(),
+                dest_pubkey.clone(),
+                amount,
+                fee,
+                tx.clone(),
+                TransactionStatus::Completed,
+                message.clone(),
+                Utc::now().naive_utc(),
+                TransactionDirection::Outbound,
+                None,
+                None,
+            ),
+        )
+        .await?;
+
+        Ok(tx_id)
+    }
+
+    /// Sends a one-sided payment to each recipient in a single transaction with a single change output. The
+    /// transaction is recorded once, against the first recipient, with the total amount and the batch message. The
+    /// recipient, amount and message of each payment are stored as the batch payments of the transaction.
+    pub async fn send_one_sided_batch_transaction(
+        &mut self,
+        account_id: Option<u64>,
+        recipients: Vec<BatchPaymentRecipient>,
+        fee_per_gram: MicroTari,
+        message: String,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError>>,
+        >,
+    ) -> Result<TxId, TransactionServiceError> {
+        let mut output_manager = self.account_output_manager(account_id);
+        let first_destination = match recipients.first() {
+            Some(recipient) => recipient.destination.clone(),
+            None => {
+                return Err(TransactionServiceError::OneSidedTransactionError(
+                    "A batch transaction needs at least one recipient".to_string(),
+                ))
+            },
+        };
+        if recipients
+            .iter()
+            .any(|r| &r.destination == self.node_identity.public_key())
+        {
+            warn!(target: LOG_TARGET, "One-sided spend-to-self transactions not supported");
+            return Err(TransactionServiceError::OneSidedTransactionError(
+                "One-sided spend-to-self transactions not supported".to_string(),
+            ));
+        }
+        if recipients.iter().any(|r| r.amount == MicroTari::from(0)) {
+            return Err(TransactionServiceError::OneSidedTransactionError(
+                "Every payment in a batch must have a non-zero amount".to_string(),
+            ));
+        }
+
+        let tx_id = TxId::new_random();
+        let amount = recipients.iter().map(|r| r.amount).sum::<MicroTari>();
+        let (fee, tx) = output_manager
+            .create_one_sided_batch_transaction(
+                tx_id,
+                recipients.iter().map(|r| (r.destination.clone(), r.amount)).collect(),
+                fee_per_gram,
+                message.clone(),
+            )
+            .await?;
+        let payments = recipients
+            .into_iter()
+            .map(|r| BatchPayment {
+                tx_id,
+                destination: r.destination,
+                amount: r.amount,
+                message: r.message,
+            })
+            .collect();
+        self.db.add_batch_payments(tx_id, payments).await?;
+        info!(
+            target: LOG_TARGET,
+            "Finalized one-sided batch transaction TxId: {} paying {}", tx_id, amount
+        );
+
+        // This event being sent is important, but not critical to the protocol being successful. Send only fails if
+        // there are no subscribers.
+        let _ = self
+            .event_publisher
+            .send(Arc::new(TransactionEvent::TransactionCompletedImmediately(tx_id)));
+
+        self.submit_transaction(
+            transaction_broadcast_join_handles,
+            CompletedTransaction::new(
+                tx_id,
+                self.resources.node_identity.public_key().clone(),
+                first_destination,
+                amount,
+                fee,
+                tx,
+                TransactionStatus::Completed,
+                message,
+                Utc::now().naive_utc(),
+                TransactionDirection::Outbound,
+                None,
+                None,
+            ),
+        )
+        .await?;
+
+        Ok(tx_id)
+    }
+
+    /// Plays the recipient's part of a one-sided payment. The recipient's spending key is derived from a Diffie-Hellman
+    /// shared secret, so the sender is able to sign for the recipient output without any interaction.
+    fn create_one_sided_recipient_reply(
+        &self,
+        stp: &SenderTransactionProtocol,
+        dest_pubkey: &CommsPublicKey,
+        tx_id: TxId,
+    ) -> Result<RecipientSignedMessage, TransactionServiceError> {
         // Diffie-Hellman shared secret `k_Ob * K_Sb = K_Ob * k_Sb` results in a public key, which is converted to
         // bytes to enable conversion into a private key to be used as the spending key
         let sender_offset_private_key = stp
             .get_recipient_sender_offset_private_key(0)
             .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
-        let spend_key = PrivateKey::from_bytes(
-            CommsPublicKey::shared_secret(&sender_offset_private_key.clone(), &dest_pubkey.clone()).as_bytes(),
-        )
-        .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
+        let spend_key =
+            PrivateKey::from_bytes(CommsPublicKey::shared_secret(&sender_offset_private_key, dest_pubkey).as_bytes())
+                .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
+
+        self.create_recipient_reply(stp, spend_key)
+    }
 
+    /// Plays the recipient's part of a transaction for an output with a spending key known to this wallet
+    fn create_recipient_reply(
+        &self,
+        stp: &SenderTransactionProtocol,
+        spend_key: PrivateKey,
+    ) -> Result<RecipientSignedMessage, TransactionServiceError> {
         let sender_message = TransactionSenderMessage::new_single_round_message(stp.get_single_round_message()?);
         let blinding_key = PrivateKey::from_bytes(&hash_secret_key(&spend_key))?;
         let rewind_key = PrivateKey::from_bytes(&hash_secret_key(&blinding_key))?;
         let rewind_data = RewindData {
-            rewind_key: rewind_key.clone(),
-            rewind_blinding_key: blinding_key.clone(),
+            rewind_key,
+            rewind_blinding_key: blinding_key,
             proof_message: [0u8; 21],
         };
 
@@ -1133,55 +1539,1190 @@ where
             &rewind_data,
         );
 
-        let recipient_reply = rtp.get_signed_data()?.clone();
+        Ok(rtp.get_signed_data()?.clone())
+    }
+
+    /// Signs a one-sided payment that was prepared by a watch-only copy of this wallet. The inputs are rewound and
+    /// signed for by the output manager, and the transaction is finalised here so that the watch-only wallet never
+    /// sees any of the secrets used to build it.
+    pub async fn sign_offline_transaction(
+        &mut self,
+        template: UnsignedTransactionTemplate,
+    ) -> Result<OfflineSignedTransaction, TransactionServiceError> {
+        let tx_id = template.tx_id;
+        let destination = template.destination.clone();
+        let amount = template.amount;
+        let message = template.message.clone();
+
+        let mut stp = self
+            .output_manager_service
+            .prepare_offline_transaction(template)
+            .await?;
+        let _ = stp
+            .build_single_round_message()
+            .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
+        let recipient_reply = self.create_one_sided_recipient_reply(&stp, &destination, tx_id)?;
+        stp.add_single_recipient_info(recipient_reply, &self.resources.factories.range_proof)
+            .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
+        let fee = stp
+            .get_fee_amount()
+            .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
+        stp.finalize(
+            KernelFeatures::empty(),
+            &self.resources.factories,
+            None,
+            self.last_seen_tip_height.unwrap_or(u64::MAX),
+        )
+        .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
+        info!(target: LOG_TARGET, "Signed offline transaction TxId: {}", tx_id);
+
+        let transaction = stp
+            .take_transaction()
+            .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
+        Ok(OfflineSignedTransaction {
+            tx_id,
+            destination,
+            amount,
+            fee,
+            message,
+            transaction,
+        })
+    }
+
+    /// Completes a transaction that was signed offline: it is validated against the current chain tip, its inputs are
+    /// encumbered, its change is added as pending incoming and it is recorded as a completed outbound transaction
+    /// before being broadcast.
+    pub async fn submit_offline_transaction(
+        &mut self,
+        signed: OfflineSignedTransaction,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError>>,
+        >,
+    ) -> Result<TxId, TransactionServiceError> {
+        let tx_id = signed.tx_id;
+        signed.transaction.validate_internal_consistency(
+            false,
+            &self.resources.factories,
+            None,
+            None,
+            self.last_seen_tip_height.unwrap_or(u64::MAX),
+        )?;
+
+        let input_hashes = signed
+            .transaction
+            .body
+            .inputs()
+            .iter()
+            .map(|input| input.output_hash())
+            .collect();
+        self.output_manager_service
+            .encumber_offline_transaction(tx_id, input_hashes, signed.transaction.body.outputs().clone())
+            .await?;
+        info!(
+            target: LOG_TARGET,
+            "Imported offline signed transaction TxId: {}", tx_id
+        );
+
+        let _ = self
+            .event_publisher
+            .send(Arc::new(TransactionEvent::TransactionCompletedImmediately(tx_id)));
+
+        self.submit_transaction(
+            transaction_broadcast_join_handles,
+            CompletedTransaction::new(
+                tx_id,
+                self.resources.node_identity.public_key().clone(),
+                signed.destination,
+                signed.amount,
+                signed.fee,
+                signed.transaction,
+                TransactionStatus::Completed,
+                signed.message,
+                Utc::now().naive_utc(),
+                TransactionDirection::Outbound,
+                None,
+                None,
+            ),
+        )
+        .await?;
+
+        Ok(tx_id)
+    }
+
+    /// Starts a multisig session led by this wallet. The cosigners are asked for the commitments to their key share
+    /// polynomials, and the output is funded once every one of them has verified its key shares.
+    pub async fn create_multisig_output(
+        &mut self,
+        cosigners: Vec<CommsPublicKey>,
+        threshold: u8,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<u64, TransactionServiceError> {
+        let mut members = vec![self.node_identity.public_key().clone()];
+        for cosigner in cosigners {
+            if members.contains(&cosigner) {
+                return Err(TransactionServiceError::MultisigError(format!(
+                    "Multisig member {} was provided more than once",
+                    cosigner
+                )));
+            }
+            members.push(cosigner);
+        }
+        if members.len() < 2 || members.len() > MAX_MULTISIG_MEMBERS {
+            return Err(TransactionServiceError::MultisigError(format!(
+                "A multisig session must have between 2 and {} members",
+                MAX_MULTISIG_MEMBERS
+            )));
+        }
+        if threshold == 0 || threshold as usize > members.len() {
+            return Err(TransactionServiceError::MultisigError(format!(
+                "A threshold of {} is not valid for {} members",
+                threshold,
+                members.len()
+            )));
+        }
+
+        let session_id = TxId::new_random().as_u64();
+        let polynomial = self.multisig_polynomial(session_id, threshold).await?;
+        let mut commitments = vec![None; members.len()];
+        commitments[0] = Some(polynomial.commitments());
+        let mut key_shares = vec![None; members.len()];
+        key_shares[0] = Some(polynomial.share(0));
+        let session = MultisigSession {
+            session_id,
+            leader: self.node_identity.public_key().clone(),
+            members: members.clone(),
+            threshold,
+            amount,
+            fee_per_gram,
+            message: message.clone(),
+            status: MultisigSessionStatus::AwaitingKeyShares,
+            commitments,
+            key_shares,
+            verified_members: vec![false; members.len()],
+            output: None,
+            funding_tx_id: None,
+            spend: None,
+            timestamp: Utc::now().naive_utc(),
+        };
+        self.db.save_multisig_session(session).await?;
+        info!(
+            target: LOG_TARGET,
+            "Started {}-of-{} multisig session {}",
+            threshold,
+            members.len(),
+            session_id
+        );
+
+        let proposal = MultisigMessage {
+            session_id,
+            payload: MultisigPayload::Proposal {
+                threshold,
+                members: members.clone(),
+                amount,
+                message,
+            },
+        };
+        self.send_multisig_message_to_others(&members, proposal);
+
+        Ok(session_id)
+    }
+
+    /// Proposes spending the output of an active multisig session. This wallet's approval is included, and the leader
+    /// asks the members for their signatures as soon as enough of them have approved it.
+    pub async fn propose_multisig_spend(
+        &mut self,
+        session_id: u64,
+        destination: CommsPublicKey,
+        fee_per_gram: MicroTari,
+        message: String,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError>>,
+        >,
+    ) -> Result<(), TransactionServiceError> {
+        let mut session = self.fetch_multisig_session(session_id).await?;
+        if session.status != MultisigSessionStatus::Active {
+            return Err(TransactionServiceError::MultisigError(format!(
+                "Multisig session {} cannot be spent while it is '{}'",
+                session_id, session.status
+            )));
+        }
+        let own_index = self.own_multisig_index(&session)?;
+        let secret_nonces = MultisigSecretNonces::random();
+        let nonces = secret_nonces.public_nonces(&destination)?;
+        let nonce_commitment = nonces.commitment();
+        let mut spend = MultisigSpend::new(
+            self.node_identity.public_key().clone(),
+            destination.clone(),
+            fee_per_gram,
+            message.clone(),
+            session.members.len(),
+        );
+        spend.nonces = Some(nonces);
+        spend.secret_nonces = Some(secret_nonces);
+        session.spend = Some(spend);
+        session.status = MultisigSessionStatus::SpendPending;
+
+        let request = MultisigMessage {
+            session_id,
+            payload: MultisigPayload::SpendRequest {
+                destination,
+                fee_per_gram,
+                message,
+                nonce_commitment: nonce_commitment.clone(),
+            },
+        };
+        let members = session.members.clone();
+        if &session.leader == self.node_identity.public_key() {
+            self.record_multisig_approval(session, own_index, nonce_commitment, transaction_broadcast_join_handles)
+                .await?;
+        } else {
+            if let Some(spend) = session.spend.as_mut() {
+                spend.approvals[own_index] = Some(nonce_commitment);
+            }
+            self.db.save_multisig_session(session).await?;
+        }
+        self.send_multisig_message_to_others(&members, request);
+        self.publish_multisig_session_updated(session_id);
+        Ok(())
+    }
+
+    /// Approves the pending spend of a multisig session. The approval holds a commitment to fresh nonces for the spend
+    /// and is sent to the leader, who asks for the nonces and signatures of the members once enough of them have
+    /// approved it.
+    pub async fn approve_multisig_spend(
+        &mut self,
+        session_id: u64,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError>>,
+        >,
+    ) -> Result<(), TransactionServiceError> {
+        let mut session = self.fetch_multisig_session(session_id).await?;
+        if session.status != MultisigSessionStatus::SpendPending {
+            return Err(TransactionServiceError::MultisigError(format!(
+                "Multisig session {} does not have a pending spend",
+                session_id
+            )));
+        }
+        let own_index = self.own_multisig_index(&session)?;
+        let spend = session
+            .spend
+            .as_mut()
+            .ok_or_else(|| TransactionServiceError::MultisigError("Pending spend is missing".to_string()))?;
+        if spend.approvals[own_index].is_some() || spend.secret_nonces.is_some() {
+            return Err(TransactionServiceError::MultisigError(format!(
+                "This wallet has already approved the pending spend of multisig session {}",
+                session_id
+            )));
+        }
+        let secret_nonces = MultisigSecretNonces::random();
+        let nonces = secret_nonces.public_nonces(&spend.destination)?;
+        let nonce_commitment = nonces.commitment();
+        spend.nonces = Some(nonces);
+        spend.secret_nonces = Some(secret_nonces);
+
+        if &session.leader == self.node_identity.public_key() {
+            self.record_multisig_approval(session, own_index, nonce_commitment, transaction_broadcast_join_handles)
+                .await?;
+        } else {
+            spend.approvals[own_index] = Some(nonce_commitment.clone());
+            let leader = session.leader.clone();
+            self.db.save_multisig_session(session).await?;
+            self.send_multisig_message_to(leader, MultisigMessage {
+                session_id,
+                payload: MultisigPayload::Approval(nonce_commitment),
+            });
+        }
+        self.publish_multisig_session_updated(session_id);
+        Ok(())
+    }
+
+    /// Handles a message from another member of a multisig session. Messages are sent both directly and via
+    /// store-and-forward, so every step must ignore a message that has already been applied.
+    pub async fn handle_multisig_message(
+        &mut self,
+        source_pubkey: CommsPublicKey,
+        message: proto::MultisigMessage,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError>>,
+        >,
+    ) -> Result<(), TransactionServiceError> {
+        let message = MultisigMessage::try_from(message).map_err(TransactionServiceError::InvalidMessageError)?;
+        let session_id = message.session_id;
+
+        let session = match self.db.get_multisig_session(session_id).await {
+            Ok(session) => Some(session),
+            Err(TransactionStorageError::ValueNotFound(_)) => None,
+            Err(e) => return Err(e.into()),
+        };
+
+        match (session, message.payload) {
+            (
+                None,
+                MultisigPayload::Proposal {
+                    threshold,
+                    members,
+                    amount,
+                    message,
+                },
+            ) => {
+                self.accept_multisig_proposal(source_pubkey, session_id, threshold, members, amount, message)
+                    .await?
+            },
+            (Some(_), MultisigPayload::Proposal { .. }) => {
+                trace!(
+                    target: LOG_TARGET,
+                    "Multisig session {} has already been accepted",
+                    session_id
+                );
+                return Ok(());
+            },
+            (None, _) => return Err(TransactionServiceError::MultisigSessionNotFound(session_id)),
+            (Some(mut session), payload) => {
+                let index = session.member_index(&source_pubkey).ok_or_else(|| {
+                    TransactionServiceError::MultisigError(format!(
+                        "{} is not a member of multisig session {}",
+                        source_pubkey, session_id
+                    ))
+                })?;
+                let is_leader = &session.leader == self.node_identity.public_key();
+                let from_leader = source_pubkey == session.leader;
+                match payload {
+                    MultisigPayload::Proposal { .. } => unreachable!("handled above"),
+                    MultisigPayload::Commitments(commitments) => {
+                        if !is_leader ||
+                            session.status != MultisigSessionStatus::AwaitingKeyShares ||
+                            session.commitments[index].is_some()
+                        {
+                            return Ok(());
+                        }
+                        if commitments.len() != session.threshold as usize {
+                            return Err(TransactionServiceError::MultisigError(format!(
+                                "Invalid key share commitments from {} for multisig session {}",
+                                source_pubkey, session_id
+                            )));
+                        }
+                        session.commitments[index] = Some(commitments);
+                        self.db.save_multisig_session(session.clone()).await?;
+                        if session.commitments.iter().all(Option::is_some) {
+                            self.share_multisig_commitments(session).await?;
+                        }
+                    },
+                    MultisigPayload::SessionCommitments(commitments) => {
+                        if !from_leader ||
+                            session.status != MultisigSessionStatus::AwaitingKeyShares ||
+                            session.commitments.iter().all(Option::is_some)
+                        {
+                            return Ok(());
+                        }
+                        self.accept_multisig_commitments(session, commitments, transaction_broadcast_join_handles)
+                            .await?;
+                    },
+                    MultisigPayload::KeyShare(share) => {
+                        if session.status != MultisigSessionStatus::AwaitingKeyShares ||
+                            session.key_shares[index].is_some()
+                        {
+                            return Ok(());
+                        }
+                        session.key_shares[index] = Some(share);
+                        self.verify_multisig_key_shares(session, transaction_broadcast_join_handles)
+                            .await?;
+                    },
+                    MultisigPayload::KeySharesVerified => {
+                        if !is_leader ||
+                            session.status != MultisigSessionStatus::AwaitingKeyShares ||
+                            session.verified_members[index]
+                        {
+                            return Ok(());
+                        }
+                        session.verified_members[index] = true;
+                        self.record_verified_multisig_member(session, transaction_broadcast_join_handles)
+                            .await?;
+                    },
+                    MultisigPayload::Finalized { output, funding_tx_id } => {
+                        if !from_leader || session.status != MultisigSessionStatus::AwaitingKeyShares {
+                            return Ok(());
+                        }
+                        self.accept_finalized_multisig_session(session, output, funding_tx_id)
+                            .await?;
+                    },
+                    MultisigPayload::SpendRequest {
+                        destination,
+                        fee_per_gram,
+                        message,
+                        nonce_commitment,
+                    } => {
+                        if session.status != MultisigSessionStatus::Active {
+                            return Ok(());
+                        }
+                        session.spend = Some(MultisigSpend::new(
+                            source_pubkey,
+                            destination,
+                            fee_per_gram,
+                            message,
+                            session.members.len(),
+                        ));
+                        session.status = MultisigSessionStatus::SpendPending;
+                        if is_leader {
+                            self.record_multisig_approval(
+                                session,
+                                index,
+                                nonce_commitment,
+                                transaction_broadcast_join_handles,
+                            )
+                            .await?;
+                        } else {
+                            if let Some(spend) = session.spend.as_mut() {
+                                spend.approvals[index] = Some(nonce_commitment);
+                            }
+                            self.db.save_multisig_session(session).await?;
+                        }
+                    },
+                    MultisigPayload::Approval(nonce_commitment) => {
+                        if !is_leader || session.status != MultisigSessionStatus::SpendPending {
+                            return Ok(());
+                        }
+                        if session
+                            .spend
+                            .as_ref()
+                            .map_or(true, |spend| spend.approvals[index].is_some())
+                        {
+                            return Ok(());
+                        }
+                        self.record_multisig_approval(
+                            session,
+                            index,
+                            nonce_commitment,
+                            transaction_broadcast_join_handles,
+                        )
+                        .await?;
+                    },
+                    MultisigPayload::NonceRequest(request) => {
+                        if !from_leader ||
+                            session.status != MultisigSessionStatus::SpendPending ||
+                            session
+                                .spend
+                                .as_ref()
+                                .map_or(true, |spend| spend.nonce_request.is_some())
+                        {
+                            return Ok(());
+                        }
+                        self.reveal_multisig_nonces(session, request).await?;
+                    },
+                    MultisigPayload::Nonces(nonces) => {
+                        if !is_leader || session.status != MultisigSessionStatus::SpendPending {
+                            return Ok(());
+                        }
+                        let awaiting_nonces = session.spend.as_ref().map_or(false, |spend| {
+                            spend.nonce_request.is_some() &&
+                                spend.template.is_none() &&
+                                spend.revealed_nonces[index].is_none()
+                        });
+                        if !awaiting_nonces {
+                            return Ok(());
+                        }
+                        self.record_multisig_nonces(session, index, nonces, transaction_broadcast_join_handles)
+                            .await?;
+                    },
+                    MultisigPayload::SigningRequest(request) => {
+                        if !from_leader || session.status != MultisigSessionStatus::SpendPending {
+                            return Ok(());
+                        }
+                        self.sign_multisig_spend(session, request).await?;
+                    },
+                    MultisigPayload::PartialSignature(partial_signature) => {
+                        if !is_leader || session.status != MultisigSessionStatus::SpendPending {
+                            return Ok(());
+                        }
+                        let awaiting_partial_signature = session.spend.as_ref().map_or(false, |spend| {
+                            spend.template.is_some() && spend.partial_signatures[index].is_none()
+                        });
+                        if !awaiting_partial_signature {
+                            return Ok(());
+                        }
+                        self.record_multisig_partial_signature(
+                            session,
+                            index,
+                            partial_signature,
+                            transaction_broadcast_join_handles,
+                        )
+                        .await?;
+                    },
+                    MultisigPayload::SpendBroadcast(tx_id) => {
+                        if !from_leader || session.status == MultisigSessionStatus::Spent {
+                            return Ok(());
+                        }
+                        if let Some(spend) = session.spend.as_mut() {
+                            spend.tx_id = Some(tx_id);
+                            spend.secret_nonces = None;
+                        }
+                        session.status = MultisigSessionStatus::Spent;
+                        self.db.save_multisig_session(session).await?;
+                    },
+                }
+            },
+        }
+
+        self.publish_multisig_session_updated(session_id);
+        Ok(())
+    }
+
+    async fn accept_multisig_proposal(
+        &mut self,
+        leader: CommsPublicKey,
+        session_id: u64,
+        threshold: u8,
+        members: Vec<CommsPublicKey>,
+        amount: MicroTari,
+        message: String,
+    ) -> Result<(), TransactionServiceError> {
+        if members.first() != Some(&leader) ||
+            members.len() > MAX_MULTISIG_MEMBERS ||
+            threshold == 0 ||
+            threshold as usize > members.len()
+        {
+            return Err(TransactionServiceError::MultisigError(format!(
+                "Invalid proposal for multisig session {}",
+                session_id
+            )));
+        }
+        let own_index = members
+            .iter()
+            .position(|m| m == self.node_identity.public_key())
+            .ok_or_else(|| {
+                TransactionServiceError::MultisigError(format!(
+                    "This wallet is not a member of multisig session {}",
+                    session_id
+                ))
+            })?;
+
+        let polynomial = self.multisig_polynomial(session_id, threshold).await?;
+        let mut commitments = vec![None; members.len()];
+        commitments[own_index] = Some(polynomial.commitments());
+        let mut key_shares = vec![None; members.len()];
+        key_shares[own_index] = Some(polynomial.share(own_index));
+        let session = MultisigSession {
+            session_id,
+            leader: leader.clone(),
+            members: members.clone(),
+            threshold,
+            amount,
+            fee_per_gram: MicroTari::from(0),
+            message,
+            status: MultisigSessionStatus::AwaitingKeyShares,
+            commitments,
+            key_shares,
+            verified_members: vec![false; members.len()],
+            output: None,
+            funding_tx_id: None,
+            spend: None,
+            timestamp: Utc::now().naive_utc(),
+        };
+        self.db.save_multisig_session(session).await?;
+        info!(
+            target: LOG_TARGET,
+            "Joined multisig session {} led by {}", session_id, leader
+        );
+
+        self.send_multisig_message_to(leader, MultisigMessage {
+            session_id,
+            payload: MultisigPayload::Commitments(polynomial.commitments()),
+        });
+        Ok(())
+    }
+
+    /// Sends the key share polynomial commitments of every member to the other members, along with their shares of
+    /// this wallet's polynomial. Called by the leader once it has the commitments of every member.
+    async fn share_multisig_commitments(&mut self, session: MultisigSession) -> Result<(), TransactionServiceError> {
+        let commitments = session
+            .commitments
+            .iter()
+            .cloned()
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| TransactionServiceError::MultisigError("Missing key share commitments".to_string()))?;
+        self.send_multisig_message_to_others(&session.members, MultisigMessage {
+            session_id: session.session_id,
+            payload: MultisigPayload::SessionCommitments(commitments),
+        });
+        self.send_multisig_key_shares(&session).await
+    }
+
+    /// Accepts the key share polynomial commitments of every member from the leader, and sends this wallet's shares to
+    /// the other members
+    async fn accept_multisig_commitments(
+        &mut self,
+        mut session: MultisigSession,
+        commitments: Vec<Vec<PublicKey>>,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError>>,
+        >,
+    ) -> Result<(), TransactionServiceError> {
+        let own_index = self.own_multisig_index(&session)?;
+        if commitments.len() != session.members.len() ||
+            commitments.iter().any(|c| c.len() != session.threshold as usize) ||
+            Some(&commitments[own_index]) != session.commitments[own_index].as_ref()
+        {
+            return Err(TransactionServiceError::MultisigError(format!(
+                "Key share commitments for multisig session {} do not match",
+                session.session_id
+            )));
+        }
+        session.commitments = commitments.into_iter().map(Some).collect();
+        self.send_multisig_key_shares(&session).await?;
+        self.verify_multisig_key_shares(session, transaction_broadcast_join_handles)
+            .await
+    }
+
+    /// Sends each of the other members its share of this wallet's key share polynomial
+    async fn send_multisig_key_shares(&mut self, session: &MultisigSession) -> Result<(), TransactionServiceError> {
+        let own_index = self.own_multisig_index(session)?;
+        let polynomial = self.multisig_polynomial(session.session_id, session.threshold).await?;
+        for (index, member) in session.members.iter().enumerate().filter(|(i, _)| *i != own_index) {
+            self.send_multisig_message_to(member.clone(), MultisigMessage {
+                session_id: session.session_id,
+                payload: MultisigPayload::KeyShare(polynomial.share(index)),
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks the key shares this wallet received against the commitments of the members that sent them once all of
+    /// them are known, and lets the leader know that they are valid
+    async fn verify_multisig_key_shares(
+        &mut self,
+        mut session: MultisigSession,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError>>,
+        >,
+    ) -> Result<(), TransactionServiceError> {
+        if session.commitments.iter().any(Option::is_none) || session.key_shares.iter().any(Option::is_none) {
+            self.db.save_multisig_session(session).await?;
+            return Ok(());
+        }
+        let own_index = self.own_multisig_index(&session)?;
+        for (index, (commitments, share)) in session.commitments.iter().zip(&session.key_shares).enumerate() {
+            if let (Some(commitments), Some(share)) = (commitments, share) {
+                if !verify_key_share(commitments, own_index, share) {
+                    return Err(TransactionServiceError::MultisigError(format!(
+                        "Invalid key share from {} for multisig session {}",
+                        session.members[index], session.session_id
+                    )));
+                }
+            }
+        }
+        info!(
+            target: LOG_TARGET,
+            "Verified key shares for multisig session {}", session.session_id
+        );
+
+        if &session.leader == self.node_identity.public_key() {
+            session.verified_members[own_index] = true;
+            self.record_verified_multisig_member(session, transaction_broadcast_join_handles)
+                .await
+        } else {
+            let leader = session.leader.clone();
+            let session_id = session.session_id;
+            self.db.save_multisig_session(session).await?;
+            self.send_multisig_message_to(leader, MultisigMessage {
+                session_id,
+                payload: MultisigPayload::KeySharesVerified,
+            });
+            Ok(())
+        }
+    }
+
+    /// Funds the shared output once every member, including the leader, has verified its key shares
+    async fn record_verified_multisig_member(
+        &mut self,
+        session: MultisigSession,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError>>,
+        >,
+    ) -> Result<(), TransactionServiceError> {
+        self.db.save_multisig_session(session.clone()).await?;
+        if session.verified_members.iter().all(|verified| *verified) {
+            self.fund_multisig_output(session, transaction_broadcast_join_handles)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Sends the session amount to an output locked by the aggregated script key of the session, using this wallet's
+    /// spending key for the session for the recipient's part of the transaction, and shares the output with the other
+    /// members
+    async fn fund_multisig_output(
+        &mut self,
+        mut session: MultisigSession,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError>>,
+        >,
+    ) -> Result<(), TransactionServiceError> {
+        let keys = self
+            .output_manager_service
+            .get_multisig_keys(session.session_id)
+            .await?;
+        let script = multisig_script(multisig_session_keys(&session)?.script_public_key());
+
+        let tx_id = TxId::new_random();
+        let mut stp = self
+            .output_manager_service
+            .prepare_transaction_to_send(
+                tx_id,
+                session.amount,
+                None,
+                None,
+                session.fee_per_gram,
+                None,
+                session.message.clone(),
+                script,
+                Covenant::default(),
+                None,
+            )
+            .await?;
+        let _ = stp
+            .build_single_round_message()
+            .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
+        self.output_manager_service
+            .confirm_pending_transaction(tx_id)
+            .await
+            .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
+
+        let recipient_reply = self.create_recipient_reply(&stp, keys.spending_key)?;
+        let commitment = recipient_reply.output.commitment.clone();
+        stp.add_single_recipient_info(recipient_reply, &self.resources.factories.range_proof)
+            .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
+        stp.finalize(
+            KernelFeatures::empty(),
+            &self.resources.factories,
+            None,
+            self.last_seen_tip_height.unwrap_or(u64::MAX),
+        )
+        .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
+        info!(
+            target: LOG_TARGET,
+            "Finalized funding transaction (TxId: {}) for multisig session {}", tx_id, session.session_id
+        );
+
+        let tx = stp
+            .get_transaction()
+            .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
+        // The metadata signature of the shared output is only complete in the finalized transaction
+        let output = tx
+            .body
+            .outputs()
+            .iter()
+            .find(|o| o.commitment == commitment)
+            .cloned()
+            .ok_or_else(|| TransactionServiceError::MultisigError("Multisig output is missing".to_string()))?;
+        let fee = stp
+            .get_fee_amount()
+            .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
+        self.submit_transaction(
+            transaction_broadcast_join_handles,
+            CompletedTransaction::new(
+                tx_id,
+                self.resources.node_identity.public_key().clone(),
+                self.resources.node_identity.public_key().clone(),
+                session.amount,
+                fee,
+                tx.clone(),
+                TransactionStatus::Completed,
+                session.message.clone(),
+                Utc::now().naive_utc(),
+                TransactionDirection::Outbound,
+                None,
+                None,
+            ),
+        )
+        .await?;
+
+        session.output = Some(output.clone());
+        session.funding_tx_id = Some(tx_id);
+        session.status = MultisigSessionStatus::Active;
+        self.db.save_multisig_session(session.clone()).await?;
+
+        let finalized = MultisigMessage {
+            session_id: session.session_id,
+            payload: MultisigPayload::Finalized {
+                output,
+                funding_tx_id: tx_id,
+            },
+        };
+        self.send_multisig_message_to_others(&session.members, finalized);
+        Ok(())
+    }
+
+    /// Checks that the output the leader funded really is locked by the aggregated script key of the session before
+    /// the session is activated
+    async fn accept_finalized_multisig_session(
+        &mut self,
+        mut session: MultisigSession,
+        output: TransactionOutput,
+        funding_tx_id: TxId,
+    ) -> Result<(), TransactionServiceError> {
+        let session_id = session.session_id;
+        if output.script != multisig_script(multisig_session_keys(&session)?.script_public_key()) {
+            return Err(TransactionServiceError::MultisigError(format!(
+                "Output for multisig session {} does not match the session",
+                session_id
+            )));
+        }
+
+        session.output = Some(output);
+        session.funding_tx_id = Some(funding_tx_id);
+        session.status = MultisigSessionStatus::Active;
+        self.db.save_multisig_session(session).await?;
+        info!(target: LOG_TARGET, "Multisig session {} is active", session_id);
+        Ok(())
+    }
+
+    /// Records a member's approval of the pending spend of a session led by this wallet. Once enough members have
+    /// approved it, the first `threshold` members to approve are asked to reveal the nonces they committed to.
+    async fn record_multisig_approval(
+        &mut self,
+        mut session: MultisigSession,
+        index: usize,
+        nonce_commitment: Vec<u8>,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError>>,
+        >,
+    ) -> Result<(), TransactionServiceError> {
+        let spend = session
+            .spend
+            .as_mut()
+            .ok_or_else(|| TransactionServiceError::MultisigError("Pending spend is missing".to_string()))?;
+        spend.approvals[index] = Some(nonce_commitment);
+        if spend.nonce_request.is_some() || spend.num_approvals() < session.threshold as usize {
+            self.db.save_multisig_session(session).await?;
+            return Ok(());
+        }
+
+        let (signers, commitments): (Vec<_>, Vec<_>) = spend
+            .approvals
+            .iter()
+            .enumerate()
+            .filter_map(|(i, commitment)| commitment.clone().map(|c| (i, c)))
+            .take(session.threshold as usize)
+            .unzip();
+        let leader_nonces = MultisigLeaderNonces::random();
+        let request = MultisigNonceRequest {
+            signers,
+            commitments,
+            leader_commitment: leader_nonces.commitment(&self.resources.factories),
+        };
+        spend.leader_nonces = Some(leader_nonces);
+        spend.nonce_request = Some(request.clone());
+        let own_nonces = spend.nonces.clone();
+        self.db.save_multisig_session(session.clone()).await?;
+
+        let own_index = self.own_multisig_index(&session)?;
+        for signer in request.signers.iter().filter(|s| **s != own_index) {
+            self.send_multisig_message_to(session.members[*signer].clone(), MultisigMessage {
+                session_id: session.session_id,
+                payload: MultisigPayload::NonceRequest(request.clone()),
+            });
+        }
+        match own_nonces {
+            Some(nonces) if request.signers.contains(&own_index) => {
+                self.record_multisig_nonces(session, own_index, nonces, transaction_broadcast_join_handles)
+                    .await
+            },
+            _ => Ok(()),
+        }
+    }
 
-        // Start finalizing
+    /// Reveals this wallet's nonces for the pending spend to the leader, once the leader has fixed the signers and the
+    /// nonce commitments of every one of them
+    async fn reveal_multisig_nonces(
+        &mut self,
+        mut session: MultisigSession,
+        request: MultisigNonceRequest,
+    ) -> Result<(), TransactionServiceError> {
+        let own_index = self.own_multisig_index(&session)?;
+        let spend = session
+            .spend
+            .as_mut()
+            .ok_or_else(|| TransactionServiceError::MultisigError("Pending spend is missing".to_string()))?;
+        let nonces = match (&spend.nonces, &spend.secret_nonces) {
+            (Some(nonces), Some(_)) => nonces.clone(),
+            _ => {
+                trace!(
+                    target: LOG_TARGET,
+                    "Ignoring nonce request for multisig session {} without unused nonces",
+                    session.session_id
+                );
+                return Ok(());
+            },
+        };
+        request.validate(session.threshold, own_index, &nonces)?;
+        spend.nonce_request = Some(request);
+        let leader = session.leader.clone();
+        let session_id = session.session_id;
+        self.db.save_multisig_session(session).await?;
+        self.send_multisig_message_to(leader, MultisigMessage {
+            session_id,
+            payload: MultisigPayload::Nonces(nonces),
+        });
+        Ok(())
+    }
 
-        stp.add_single_recipient_info(recipient_reply, &self.resources.factories.range_proof)
-            .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
+    /// Records the nonces a signer revealed for the pending spend of a session led by this wallet. Once every signer
+    /// has revealed its nonces, the payment output is built and the signers are asked to sign.
+    async fn record_multisig_nonces(
+        &mut self,
+        mut session: MultisigSession,
+        index: usize,
+        nonces: MultisigNonces,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError>>,
+        >,
+    ) -> Result<(), TransactionServiceError> {
+        let spend = session
+            .spend
+            .as_mut()
+            .ok_or_else(|| TransactionServiceError::MultisigError("Pending spend is missing".to_string()))?;
+        let nonce_request = spend
+            .nonce_request
+            .clone()
+            .ok_or_else(|| TransactionServiceError::MultisigError("Signers have not been picked".to_string()))?;
+        if !nonce_request.verify_nonces(index, &nonces, &spend.destination) {
+            return Err(TransactionServiceError::MultisigError(format!(
+                "Nonces from {} for the pending spend of multisig session {} do not match its commitment",
+                session.members[index], session.session_id
+            )));
+        }
+        spend.revealed_nonces[index] = Some(nonces);
+        let nonces = nonce_request
+            .signers
+            .iter()
+            .map(|signer| spend.revealed_nonces[*signer].clone())
+            .collect::<Option<Vec<_>>>();
+        let nonces = match nonces {
+            Some(nonces) => nonces,
+            None => {
+                self.db.save_multisig_session(session).await?;
+                return Ok(());
+            },
+        };
 
-        // Finalize
+        let leader_nonces = spend
+            .leader_nonces
+            .take()
+            .ok_or_else(|| TransactionServiceError::MultisigError("Leader nonces are missing".to_string()))?;
+        let template = MultisigSpendTemplate::new(
+            nonce_request.signers.clone(),
+            nonces,
+            leader_nonces,
+            &spend.destination,
+            session.amount,
+            spend.fee_per_gram,
+            &self.resources.factories,
+        )?;
+        spend.template = Some(template.clone());
+        self.db.save_multisig_session(session.clone()).await?;
+
+        let own_index = self.own_multisig_index(&session)?;
+        for signer in template.request.signers.iter().filter(|s| **s != own_index) {
+            self.send_multisig_message_to(session.members[*signer].clone(), MultisigMessage {
+                session_id: session.session_id,
+                payload: MultisigPayload::SigningRequest(template.request.clone()),
+            });
+        }
+        if template.request.signers.contains(&own_index) {
+            let partial_signature = self.sign_multisig_request(&mut session, &template.request).await?;
+            self.record_multisig_partial_signature(
+                session,
+                own_index,
+                partial_signature,
+                transaction_broadcast_join_handles,
+            )
+            .await?;
+        }
+        Ok(())
+    }
 
-        stp.finalize(
-            KernelFeatures::empty(),
+    /// Signs the leader's request for the pending spend, if it pays the whole session amount less the agreed fee to
+    /// the destination, and sends the partial signature to the leader
+    async fn sign_multisig_spend(
+        &mut self,
+        mut session: MultisigSession,
+        request: MultisigSigningRequest,
+    ) -> Result<(), TransactionServiceError> {
+        let spend = session
+            .spend
+            .as_ref()
+            .ok_or_else(|| TransactionServiceError::MultisigError("Pending spend is missing".to_string()))?;
+        let nonce_request = match (&spend.nonce_request, &spend.secret_nonces) {
+            (Some(nonce_request), Some(_)) => nonce_request,
+            _ => {
+                trace!(
+                    target: LOG_TARGET,
+                    "Ignoring signing request for multisig session {} without unused nonces",
+                    session.session_id
+                );
+                return Ok(());
+            },
+        };
+        request.validate(
+            nonce_request,
+            session.threshold,
+            &spend.destination,
+            session.amount,
+            spend.fee_per_gram,
             &self.resources.factories,
-            None,
-            self.last_seen_tip_height.unwrap_or(u64::MAX),
+        )?;
+        let partial_signature = self.sign_multisig_request(&mut session, &request).await?;
+        let leader = session.leader.clone();
+        let session_id = session.session_id;
+        self.db.save_multisig_session(session).await?;
+        self.send_multisig_message_to(leader, MultisigMessage {
+            session_id,
+            payload: MultisigPayload::PartialSignature(partial_signature),
+        });
+        Ok(())
+    }
+
+    /// Signs a request with this wallet's script key share. The secret nonces of the spend are removed, so the session
+    /// must be saved before the partial signature is sent anywhere.
+    async fn sign_multisig_request(
+        &mut self,
+        session: &mut MultisigSession,
+        request: &MultisigSigningRequest,
+    ) -> Result<MultisigPartialSignature, TransactionServiceError> {
+        let own_index = self.own_multisig_index(session)?;
+        let session_keys = multisig_session_keys(session)?;
+        let shares = session
+            .key_shares
+            .iter()
+            .cloned()
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| TransactionServiceError::MultisigError("Missing key shares".to_string()))?;
+        let input = session
+            .output
+            .as_ref()
+            .ok_or_else(|| TransactionServiceError::MultisigError("Multisig output is missing".to_string()))?;
+        let secret_nonces = session
+            .spend
+            .as_mut()
+            .and_then(|spend| spend.secret_nonces.take())
+            .ok_or_else(|| TransactionServiceError::MultisigError("No unused nonces for the spend".to_string()))?;
+        MultisigPartialSignature::sign(
+            request,
+            own_index,
+            &session_keys.key_share(&shares),
+            &secret_nonces,
+            input,
+            &session_keys.script_public_key(),
         )
-        .map_err(|e| {
-            error!(
-                target: LOG_TARGET,
-                "Transaction (TxId: {}) could not be finalized. Failure error: {:?}", tx_id, e,
-            );
-            TransactionServiceProtocolError::new(tx_id, e.into())
-        })?;
-        info!(target: LOG_TARGET, "Finalized one-side transaction TxId: {}", tx_id);
+    }
 
-        // This event being sent is important, but not critical to the protocol being successful. Send only fails if
-        // there are no subscribers.
-        let _ = self
-            .event_publisher
-            .send(Arc::new(TransactionEvent::TransactionCompletedImmediately(tx_id)));
+    /// Records a signer's partial signature for the pending spend of a session led by this wallet, and completes the
+    /// spend once every signer has signed
+    async fn record_multisig_partial_signature(
+        &mut self,
+        mut session: MultisigSession,
+        index: usize,
+        partial_signature: MultisigPartialSignature,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError>>,
+        >,
+    ) -> Result<(), TransactionServiceError> {
+        let session_keys = multisig_session_keys(&session)?;
+        let input = session
+            .output
+            .clone()
+            .ok_or_else(|| TransactionServiceError::MultisigError("Multisig output is missing".to_string()))?;
+        let spend = session
+            .spend
+            .as_mut()
+            .ok_or_else(|| TransactionServiceError::MultisigError("Pending spend is missing".to_string()))?;
+        let template = spend
+            .template
+            .clone()
+            .ok_or_else(|| TransactionServiceError::MultisigError("Pending spend has not been built".to_string()))?;
+        if !partial_signature.verify(
+            &template.request,
+            index,
+            &session_keys.verification_key(index),
+            &input,
+            &session_keys.script_public_key(),
+        ) {
+            return Err(TransactionServiceError::MultisigError(format!(
+                "Invalid partial signature from {} for the pending spend of multisig session {}",
+                session.members[index], session.session_id
+            )));
+        }
+        spend.partial_signatures[index] = Some(partial_signature);
+        let partial_signatures = template
+            .request
+            .signers
+            .iter()
+            .map(|signer| spend.partial_signatures[*signer].clone())
+            .collect::<Option<Vec<_>>>();
+        match partial_signatures {
+            Some(partial_signatures) => {
+                self.complete_multisig_spend(
+                    session,
+                    template,
+                    partial_signatures,
+                    transaction_broadcast_join_handles,
+                )
+                .await
+            },
+            None => {
+                self.db.save_multisig_session(session).await?;
+                Ok(())
+            },
+        }
+    }
 
-        // Broadcast one-sided transaction
+    /// Spends the multisig output once every signer picked by the leader has sent its partial signature
+    async fn complete_multisig_spend(
+        &mut self,
+        mut session: MultisigSession,
+        template: MultisigSpendTemplate,
+        partial_signatures: Vec<MultisigPartialSignature>,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError>>,
+        >,
+    ) -> Result<(), TransactionServiceError> {
+        let session_id = session.session_id;
+        let input = session
+            .output
+            .clone()
+            .ok_or_else(|| TransactionServiceError::MultisigError("Multisig output is missing".to_string()))?;
+        let spend = session
+            .spend
+            .clone()
+            .ok_or_else(|| TransactionServiceError::MultisigError("Pending spend is missing".to_string()))?;
+        let keys = self.output_manager_service.get_multisig_keys(session_id).await?;
+        let tx = template.finalize(
+            &input,
+            &keys.spending_key,
+            session.amount,
+            &multisig_session_keys(&session)?.script_public_key(),
+            &partial_signatures,
+            &self.resources.factories,
+        )?;
+        let tx_id = TxId::new_random();
+        let fee = template.request.fee;
+        info!(
+            target: LOG_TARGET,
+            "Finalized spend (TxId: {}) of multisig session {}", tx_id, session_id
+        );
 
-        let tx = stp
-            .get_transaction()
-            .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
-        let fee = stp
-            .get_fee_amount()
-            .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
         self.submit_transaction(
             transaction_broadcast_join_handles,
             CompletedTransaction::new(
                 tx_id,
                 self.resources.node_identity.public_key().clone(),
-                dest_pubkey.clone(),
-                amount,
+                spend.destination.clone(),
+                session.amount - fee,
                 fee,
-                tx.clone(),
+                tx,
                 TransactionStatus::Completed,
-                message.clone(),
+                spend.message.clone(),
                 Utc::now().naive_utc(),
                 TransactionDirection::Outbound,
                 None,
@@ -1190,7 +2731,65 @@ where
         )
         .await?;
 
-        Ok(tx_id)
+        if let Some(spend) = session.spend.as_mut() {
+            spend.tx_id = Some(tx_id);
+        }
+        session.status = MultisigSessionStatus::Spent;
+        self.db.save_multisig_session(session.clone()).await?;
+        self.send_multisig_message_to_others(&session.members, MultisigMessage {
+            session_id,
+            payload: MultisigPayload::SpendBroadcast(tx_id),
+        });
+        Ok(())
+    }
+
+    async fn fetch_multisig_session(&self, session_id: u64) -> Result<MultisigSession, TransactionServiceError> {
+        match self.db.get_multisig_session(session_id).await {
+            Ok(session) => Ok(session),
+            Err(TransactionStorageError::ValueNotFound(_)) => {
+                Err(TransactionServiceError::MultisigSessionNotFound(session_id))
+            },
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn own_multisig_index(&self, session: &MultisigSession) -> Result<usize, TransactionServiceError> {
+        session.member_index(self.node_identity.public_key()).ok_or_else(|| {
+            TransactionServiceError::MultisigError(format!(
+                "This wallet is not a member of multisig session {}",
+                session.session_id
+            ))
+        })
+    }
+
+    async fn multisig_polynomial(
+        &mut self,
+        session_id: u64,
+        threshold: u8,
+    ) -> Result<KeySharePolynomial, TransactionServiceError> {
+        let keys = self.output_manager_service.get_multisig_keys(session_id).await?;
+        KeySharePolynomial::new(&keys.signing_key, &keys.polynomial_key, threshold)
+    }
+
+    fn send_multisig_message_to(&self, member: CommsPublicKey, message: MultisigMessage) {
+        tokio::spawn(send_multisig_message(
+            message,
+            member,
+            self.resources.outbound_message_service.clone(),
+        ));
+    }
+
+    fn send_multisig_message_to_others(&self, members: &[CommsPublicKey], message: MultisigMessage) {
+        for member in members.iter().filter(|m| *m != self.node_identity.public_key()) {
+            self.send_multisig_message_to(member.clone(), message.clone());
+        }
+    }
+
+    fn publish_multisig_session_updated(&self, session_id: u64) {
+        // Send only fails if there are no subscribers
+        let _ = self
+            .event_publisher
+            .send(Arc::new(TransactionEvent::MultisigSessionUpdated(session_id)));
     }
 
     /// Accept the public reply from a recipient and apply the reply to the relevant transaction protocol
@@ -1380,6 +2979,198 @@ where
         }
     }
 
+    /// Stores a new scheduled payment, which is made by `process_scheduled_payments` once it is due
+    async fn schedule_payment(&mut self, request: ScheduledPaymentRequest) -> Result<u64, TransactionServiceError> {
+        if request.amount == MicroTari::from(0) {
+            return Err(TransactionServiceError::InvalidScheduledPayment(
+                "The amount must be greater than zero".to_string(),
+            ));
+        }
+        if request.recipient_lock_height.is_some() && !request.one_sided {
+            return Err(TransactionServiceError::InvalidScheduledPayment(
+                "Only one-sided payments can be time locked".to_string(),
+            ));
+        }
+        if let Some(interval) = request.repeat_interval {
+            if interval == 0 {
+                return Err(TransactionServiceError::InvalidScheduledPayment(
+                    "The repeat interval must be greater than zero".to_string(),
+                ));
+            }
+            if request.first_payment.after(interval).is_none() {
+                return Err(TransactionServiceError::InvalidScheduledPayment(
+                    "The repeat interval is too large".to_string(),
+                ));
+            }
+        }
+        match (request.num_payments, request.repeat_interval) {
+            (Some(0), _) => {
+                return Err(TransactionServiceError::InvalidScheduledPayment(
+                    "At least one payment must be made".to_string(),
+                ))
+            },
+            (Some(n), None) if n > 1 => {
+                return Err(TransactionServiceError::InvalidScheduledPayment(
+                    "Recurring payments need a repeat interval".to_string(),
+                ))
+            },
+            _ => {},
+        }
+        if request.one_sided && self.node_identity.public_key() == &request.destination {
+            return Err(TransactionServiceError::InvalidScheduledPayment(
+                "One-sided spend-to-self transactions not supported".to_string(),
+            ));
+        }
+
+        let payment = ScheduledPayment {
+            id: TxId::new_random().as_u64(),
+            destination: request.destination,
+            amount: request.amount,
+            fee_per_gram: request.fee_per_gram,
+            message: request.message,
+            one_sided: request.one_sided,
+            recipient_lock_height: request.recipient_lock_height,
+            next_payment: request.first_payment,
+            remaining_payments: match request.repeat_interval {
+                Some(_) => request.num_payments,
+                None => Some(1),
+            },
+            repeat_interval: request.repeat_interval,
+            status: ScheduledPaymentStatus::Scheduled,
+            last_tx_id: None,
+            created_at: Utc::now().naive_utc(),
+        };
+        let id = payment.id;
+        info!(
+            target: LOG_TARGET,
+            "Scheduled payment {} of {} to {} at {}", id, payment.amount, payment.destination, payment.next_payment
+        );
+        self.db.save_scheduled_payment(payment).await?;
+        Ok(id)
+    }
+
+    async fn cancel_scheduled_payment(&mut self, id: u64) -> Result<(), TransactionServiceError> {
+        let mut payment = match self.db.get_scheduled_payment(id).await {
+            Ok(payment) => payment,
+            Err(TransactionStorageError::ValueNotFound(_)) => {
+                return Err(TransactionServiceError::ScheduledPaymentNotFound(id))
+            },
+            Err(e) => return Err(e.into()),
+        };
+        if payment.status != ScheduledPaymentStatus::Scheduled {
+            return Err(TransactionServiceError::ScheduledPaymentNotActive(id));
+        }
+        payment.status = ScheduledPaymentStatus::Cancelled;
+        self.db.save_scheduled_payment(payment).await?;
+        info!(target: LOG_TARGET, "Scheduled payment {} cancelled", id);
+        Ok(())
+    }
+
+    /// Makes every scheduled payment that is due according to the chain tip last reported by the base node and the
+    /// current time. A payment that fails for a transient reason, such as funds that are still pending or an
+    /// unreachable base node, stays due and is retried the next time payments are processed. Any other failure marks
+    /// the payment as failed.
+    async fn process_scheduled_payments(
+        &mut self,
+        send_transaction_join_handles: &mut FuturesUnordered<JoinHandle<Result<TxId, TransactionServiceProtocolError>>>,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError>>,
+        >,
+    ) -> Result<(), TransactionServiceError> {
+        let now = Utc::now().naive_utc();
+        let tip_height = self.last_seen_tip_height;
+        let due_payments = self
+            .db
+            .get_scheduled_payments()
+            .await?
+            .into_iter()
+            .filter(|p| p.is_due(tip_height, now))
+            .collect::<Vec<_>>();
+
+        for mut payment in due_payments {
+            match self
+                .make_scheduled_payment(
+                    &payment,
+                    send_transaction_join_handles,
+                    transaction_broadcast_join_handles,
+                )
+                .await
+            {
+                Ok(tx_id) => {
+                    info!(
+                        target: LOG_TARGET,
+                        "Scheduled payment {} sent in transaction {}", payment.id, tx_id
+                    );
+                    payment.record_payment(tx_id, tip_height, now);
+                },
+                Err(e) if is_transient_payment_error(&e) => {
+                    debug!(
+                        target: LOG_TARGET,
+                        "Scheduled payment {} could not be made yet, will retry: {}", payment.id, e
+                    );
+                    continue;
+                },
+                Err(e) => {
+                    warn!(target: LOG_TARGET, "Scheduled payment {} failed: {}", payment.id, e);
+                    payment.status = ScheduledPaymentStatus::Failed;
+                },
+            }
+            self.db.save_scheduled_payment(payment).await?;
+        }
+        Ok(())
+    }
+
+    async fn make_scheduled_payment(
+        &mut self,
+        payment: &ScheduledPayment,
+        send_transaction_join_handles: &mut FuturesUnordered<JoinHandle<Result<TxId, TransactionServiceProtocolError>>>,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError>>,
+        >,
+    ) -> Result<TxId, TransactionServiceError> {
+        // A zero fee means the fee is estimated when the payment is made
+        let fee_per_gram = if payment.fee_per_gram == MicroTari::from(0) {
+            self.output_manager_service.estimate_fee_per_gram(None).await?
+        } else {
+            payment.fee_per_gram
+        };
+        if payment.one_sided {
+            self.send_one_sided_transaction(
+                None,
+                payment.destination.clone(),
+                payment.amount,
+                None,
+                None,
+                fee_per_gram,
+                payment.message.clone(),
+                Covenant::default(),
+                None,
+                payment.recipient_lock_height,
+                transaction_broadcast_join_handles,
+            )
+            .await
+        } else {
+            // Nobody is waiting on the reply for a scheduled payment, the outcome is reported through the usual
+            // transaction events
+            let (reply_sender, _) = oneshot::channel();
+            self.send_transaction(
+                None,
+                payment.destination.clone(),
+                payment.amount,
+                None,
+                None,
+                fee_per_gram,
+                payment.message.clone(),
+                Covenant::default(),
+                None,
+                send_transaction_join_handles,
+                transaction_broadcast_join_handles,
+                reply_sender,
+            )
+            .await
+        }
+    }
+
     /// Cancel a pending transaction
     async fn cancel_pending_transaction(&mut self, tx_id: TxId) -> Result<(), TransactionServiceError> {
         self.db.cancel_pending_transaction(tx_id).await.map_err(|e| {
@@ -1475,6 +3266,8 @@ where
                     None,
                     tx.fee,
                     tx.message,
+                    Covenant::default(),
+                    None,
                     None,
                     TransactionSendProtocolStage::WaitForReply,
                     None,
@@ -2150,6 +3943,75 @@ where
         Ok(())
     }
 
+    /// The output manager handle that acts on the given account, or on the default account if no account is given
+    fn account_output_manager(&self, account_id: Option<u64>) -> OutputManagerHandle {
+        match account_id {
+            Some(account_id) => self.output_manager_service.for_account(account_id),
+            None => self.output_manager_service.clone(),
+        }
+    }
+
+    /// The resources for a protocol that is started on behalf of the given account
+    fn account_resources(&self, account_id: Option<u64>) -> TransactionServiceResources<TBackend, TWalletConnectivity> {
+        let mut resources = self.resources.clone();
+        resources.output_manager_service = self.account_output_manager(account_id);
+        resources
+    }
+
+    /// When handling a request for an account, only keep the transactions that received or spent that account's
+    /// outputs
+    async fn retain_account_transactions<T>(
+        &mut self,
+        account_id: Option<u64>,
+        mut transactions: HashMap<TxId, T>,
+    ) -> Result<HashMap<TxId, T>, TransactionServiceError> {
+        if account_id.is_some() {
+            let tx_ids = self
+                .account_output_manager(account_id)
+                .get_account_tx_ids()
+                .await?
+                .into_iter()
+                .collect::<HashSet<_>>();
+            transactions.retain(|tx_id, _| tx_ids.contains(tx_id));
+        }
+        Ok(transactions)
+    }
+
+    /// Moves funds from the current account to another account with a pay-to-self transaction that is submitted
+    /// immediately
+    async fn transfer_between_accounts(
+        &mut self,
+        account_id: Option<u64>,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError>>,
+        >,
+        to_account: u64,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<TxId, TransactionServiceError> {
+        let mut output_manager = self.account_output_manager(account_id);
+        let tx_id = TxId::new_random();
+        let (fee, transaction) = output_manager
+            .create_account_transfer(tx_id, to_account, amount, fee_per_gram, message.clone())
+            .await?;
+
+        let _ = self
+            .event_publisher
+            .send(Arc::new(TransactionEvent::TransactionCompletedImmediately(tx_id)));
+
+        self.submit_transaction_to_self(
+            transaction_broadcast_join_handles,
+            tx_id,
+            transaction,
+            fee,
+            amount,
+            message,
+        )
+        .await?;
+        Ok(tx_id)
+    }
+
     async fn generate_coinbase_transaction(
         &mut self,
         reward: MicroTari,
@@ -2281,6 +4143,36 @@ pub struct PendingCoinbaseSpendingKey {
     pub spending_key: PrivateKey,
 }
 
-fn hash_secret_key(key: &PrivateKey) -> Vec<u8> {
+pub(crate) fn hash_secret_key(key: &PrivateKey) -> Vec<u8> {
     HashDigest::new().chain(key.as_bytes()).finalize().to_vec()
 }
+
+/// The keys of a multisig session, once the key share polynomial commitments of every member are known
+fn multisig_session_keys(session: &MultisigSession) -> Result<MultisigSessionKeys, TransactionServiceError> {
+    let commitments = session
+        .commitments
+        .iter()
+        .cloned()
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| TransactionServiceError::MultisigError("Missing key share commitments".to_string()))?;
+    MultisigSessionKeys::new(commitments, session.threshold)
+}
+
+/// Whether a scheduled payment that failed with this error may succeed if it is retried later
+fn is_transient_payment_error(error: &TransactionServiceError) -> bool {
+    match error {
+        TransactionServiceError::OutputManagerError(e) => matches!(
+            e,
+            OutputManagerError::FundsPending |
+                OutputManagerError::BaseNodeNotSynced |
+                OutputManagerError::ConnectivityError { .. } |
+                OutputManagerError::RpcError(_) |
+                OutputManagerError::BaseNodeServiceError(_)
+        ),
+        TransactionServiceError::BaseNodeNotSynced |
+        TransactionServiceError::ConnectivityError { .. } |
+        TransactionServiceError::RpcError(_) |
+        TransactionServiceError::WalletRecoveryInProgress => true,
+        _ => false,
+    }
+}
@@ -42,9 +42,12 @@ use crate::transaction_service::{
     error::TransactionStorageError,
     storage::{
         models::{
+            BatchPayment,
             CompletedTransaction,
             InboundTransaction,
+            MultisigSession,
             OutboundTransaction,
+            ScheduledPayment,
             TxCancellationReason,
             WalletTransaction,
         },
@@ -175,6 +178,11 @@ pub enum DbKey {
     CancelledPendingOutboundTransaction(TxId),
     CancelledPendingInboundTransaction(TxId),
     AnyTransaction(TxId),
+    MultisigSession(u64),
+    MultisigSessions,
+    ScheduledPayment(u64),
+    ScheduledPayments,
+    BatchPayments(TxId),
 }
 
 impl fmt::Debug for DbKey {
@@ -228,6 +236,21 @@ impl fmt::Debug for DbKey {
             AnyTransaction(tx_id) => {
                 write!(f, "AnyTransaction ({}u64, {}i64)", tx_id, i64::from(*tx_id))
             },
+            MultisigSession(session_id) => {
+                write!(f, "MultisigSession ({}u64, {}i64)", session_id, *session_id as i64)
+            },
+            MultisigSessions => {
+                write!(f, "MultisigSessions")
+            },
+            ScheduledPayment(id) => {
+                write!(f, "ScheduledPayment ({}u64, {}i64)", id, *id as i64)
+            },
+            ScheduledPayments => {
+                write!(f, "ScheduledPayments")
+            },
+            BatchPayments(tx_id) => {
+                write!(f, "BatchPayments ({}u64, {}i64)", tx_id, i64::from(*tx_id))
+            },
         }
     }
 }
@@ -241,12 +264,23 @@ pub enum DbValue {
     PendingInboundTransactions(HashMap<TxId, InboundTransaction>),
     CompletedTransactions(HashMap<TxId, CompletedTransaction>),
     WalletTransaction(Box<WalletTransaction>),
+    MultisigSession(Box<MultisigSession>),
+    MultisigSessions(Vec<MultisigSession>),
+    ScheduledPayment(Box<ScheduledPayment>),
+    ScheduledPayments(Vec<ScheduledPayment>),
+    BatchPayments(Vec<BatchPayment>),
 }
 
 pub enum DbKeyValuePair {
     PendingOutboundTransaction(TxId, Box<OutboundTransaction>),
     PendingInboundTransaction(TxId, Box<InboundTransaction>),
     CompletedTransaction(TxId, Box<CompletedTransaction>),
+    /// Inserts a multisig session, replacing any existing session with the same id
+    MultisigSession(u64, Box<MultisigSession>),
+    /// Inserts a scheduled payment, replacing any existing payment with the same id
+    ScheduledPayment(u64, Box<ScheduledPayment>),
+    /// Inserts the payments of a batch transaction
+    BatchPayments(TxId, Vec<BatchPayment>),
 }
 
 pub enum WriteOperation {
@@ -519,6 +553,125 @@ where T: TransactionBackend + 'static
         self.get_pending_inbound_transactions_by_cancelled(false).await
     }
 
+    pub async fn save_multisig_session(&self, session: MultisigSession) -> Result<(), TransactionStorageError> {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || {
+            db_clone.write(WriteOperation::Insert(DbKeyValuePair::MultisigSession(
+                session.session_id,
+                Box::new(session),
+            )))
+        })
+        .await
+        .map_err(|err| TransactionStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(())
+    }
+
+    pub async fn get_multisig_session(&self, session_id: u64) -> Result<MultisigSession, TransactionStorageError> {
+        let db_clone = self.db.clone();
+        let key = DbKey::MultisigSession(session_id);
+        let session = tokio::task::spawn_blocking(move || match db_clone.fetch(&DbKey::MultisigSession(session_id)) {
+            Ok(None) => Err(TransactionStorageError::ValueNotFound(key)),
+            Ok(Some(DbValue::MultisigSession(s))) => Ok(s),
+            Ok(Some(other)) => unexpected_result(key, other),
+            Err(e) => log_error(key, e),
+        })
+        .await
+        .map_err(|err| TransactionStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(*session)
+    }
+
+    pub async fn get_multisig_sessions(&self) -> Result<Vec<MultisigSession>, TransactionStorageError> {
+        let db_clone = self.db.clone();
+        let sessions = tokio::task::spawn_blocking(move || match db_clone.fetch(&DbKey::MultisigSessions) {
+            Ok(None) => log_error(
+                DbKey::MultisigSessions,
+                TransactionStorageError::UnexpectedResult("Could not retrieve multisig sessions".to_string()),
+            ),
+            Ok(Some(DbValue::MultisigSessions(s))) => Ok(s),
+            Ok(Some(other)) => unexpected_result(DbKey::MultisigSessions, other),
+            Err(e) => log_error(DbKey::MultisigSessions, e),
+        })
+        .await
+        .map_err(|err| TransactionStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(sessions)
+    }
+
+    pub async fn save_scheduled_payment(&self, payment: ScheduledPayment) -> Result<(), TransactionStorageError> {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || {
+            db_clone.write(WriteOperation::Insert(DbKeyValuePair::ScheduledPayment(
+                payment.id,
+                Box::new(payment),
+            )))
+        })
+        .await
+        .map_err(|err| TransactionStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(())
+    }
+
+    pub async fn get_scheduled_payment(&self, id: u64) -> Result<ScheduledPayment, TransactionStorageError> {
+        let db_clone = self.db.clone();
+        let key = DbKey::ScheduledPayment(id);
+        let payment = tokio::task::spawn_blocking(move || match db_clone.fetch(&DbKey::ScheduledPayment(id)) {
+            Ok(None) => Err(TransactionStorageError::ValueNotFound(key)),
+            Ok(Some(DbValue::ScheduledPayment(p))) => Ok(p),
+            Ok(Some(other)) => unexpected_result(key, other),
+            Err(e) => log_error(key, e),
+        })
+        .await
+        .map_err(|err| TransactionStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(*payment)
+    }
+
+    pub async fn add_batch_payments(
+        &self,
+        tx_id: TxId,
+        payments: Vec<BatchPayment>,
+    ) -> Result<(), TransactionStorageError> {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || {
+            db_clone.write(WriteOperation::Insert(DbKeyValuePair::BatchPayments(tx_id, payments)))
+        })
+        .await
+        .map_err(|err| TransactionStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(())
+    }
+
+    /// Returns the payments made by the batch transaction with the given id, in the order they were requested. This is
+    /// empty if the transaction is not a batch transaction.
+    pub async fn get_batch_payments(&self, tx_id: TxId) -> Result<Vec<BatchPayment>, TransactionStorageError> {
+        let db_clone = self.db.clone();
+        let key = DbKey::BatchPayments(tx_id);
+        let payments = tokio::task::spawn_blocking(move || match db_clone.fetch(&key) {
+            Ok(None) => log_error(
+                key,
+                TransactionStorageError::UnexpectedResult("Could not retrieve batch payments".to_string()),
+            ),
+            Ok(Some(DbValue::BatchPayments(p))) => Ok(p),
+            Ok(Some(other)) => unexpected_result(key, other),
+            Err(e) => log_error(key, e),
+        })
+        .await
+        .map_err(|err| TransactionStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(payments)
+    }
+
+    pub async fn get_scheduled_payments(&self) -> Result<Vec<ScheduledPayment>, TransactionStorageError> {
+        let db_clone = self.db.clone();
+        let payments = tokio::task::spawn_blocking(move || match db_clone.fetch(&DbKey::ScheduledPayments) {
+            Ok(None) => log_error(
+                DbKey::ScheduledPayments,
+                TransactionStorageError::UnexpectedResult("Could not retrieve scheduled payments".to_string()),
+            ),
+            Ok(Some(DbValue::ScheduledPayments(p))) => Ok(p),
+            Ok(Some(other)) => unexpected_result(DbKey::ScheduledPayments, other),
+            Err(e) => log_error(DbKey::ScheduledPayments, e),
+        })
+        .await
+        .map_err(|err| TransactionStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(payments)
+    }
+
     pub async fn get_cancelled_pending_inbound_transactions(
         &self,
     ) -> Result<HashMap<TxId, InboundTransaction>, TransactionStorageError> {
@@ -925,6 +1078,11 @@ impl Display for DbKey {
                 f.write_str(&"Cancelled Pending Inbound Transaction".to_string())
             },
             DbKey::AnyTransaction(_) => f.write_str(&"Any Transaction".to_string()),
+            DbKey::MultisigSession(_) => f.write_str(&"Multisig Session".to_string()),
+            DbKey::MultisigSessions => f.write_str(&"All Multisig Sessions".to_string()),
+            DbKey::ScheduledPayment(_) => f.write_str(&"Scheduled Payment".to_string()),
+            DbKey::ScheduledPayments => f.write_str(&"All Scheduled Payments".to_string()),
+            DbKey::BatchPayments(_) => f.write_str(&"Batch Payments".to_string()),
         }
     }
 }
@@ -939,6 +1097,11 @@ impl Display for DbValue {
             DbValue::PendingInboundTransactions(_) => f.write_str(&"All Pending Inbound Transactions".to_string()),
             DbValue::CompletedTransactions(_) => f.write_str(&"All Complete Transactions".to_string()),
             DbValue::WalletTransaction(_) => f.write_str(&"Any Wallet Transaction".to_string()),
+            DbValue::MultisigSession(_) => f.write_str(&"Multisig Session".to_string()),
+            DbValue::MultisigSessions(_) => f.write_str(&"All Multisig Sessions".to_string()),
+            DbValue::ScheduledPayment(_) => f.write_str(&"Scheduled Payment".to_string()),
+            DbValue::ScheduledPayments(_) => f.write_str(&"All Scheduled Payments".to_string()),
+            DbValue::BatchPayments(_) => f.write_str(&"Batch Payments".to_string()),
         }
     }
 }
@@ -29,17 +29,26 @@ use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use tari_common_types::{
     transaction::{TransactionConversionError, TransactionDirection, TransactionStatus, TxId},
-    types::{BlockHash, PrivateKey, Signature},
+    types::{BlockHash, PrivateKey, PublicKey, Signature},
 };
 use tari_comms::types::CommsPublicKey;
 use tari_core::transactions::{
     tari_amount::MicroTari,
-    transaction_components::Transaction,
+    transaction_components::{Transaction, TransactionOutput},
     ReceiverTransactionProtocol,
     SenderTransactionProtocol,
 };
 use tari_utilities::hex::Hex;
 
+use crate::transaction_service::multisig::{
+    MultisigLeaderNonces,
+    MultisigNonceRequest,
+    MultisigNonces,
+    MultisigPartialSignature,
+    MultisigSecretNonces,
+    MultisigSpendTemplate,
+};
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct InboundTransaction {
     pub tx_id: TxId,
@@ -388,3 +397,261 @@ impl Display for TxCancellationReason {
         fmt.write_str(response)
     }
 }
+
+/// An M-of-N multisig session. Every member shares its signing key with the others so that any `threshold` of them
+/// can sign for the aggregated script key of the shared output, which the leader funds once every member has verified
+/// its key shares. Any member can then propose to spend the output, which the leader builds once `threshold` members
+/// have approved it and signed for it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MultisigSession {
+    pub session_id: u64,
+    pub leader: CommsPublicKey,
+    /// All of the members of the session, starting with the leader
+    pub members: Vec<CommsPublicKey>,
+    pub threshold: u8,
+    pub amount: MicroTari,
+    pub fee_per_gram: MicroTari,
+    pub message: String,
+    pub status: MultisigSessionStatus,
+    /// The key share polynomial commitments of each member, in the same order as `members`
+    pub commitments: Vec<Option<Vec<PublicKey>>>,
+    /// The key shares this wallet has received from each member, in the same order as `members`
+    pub key_shares: Vec<Option<PrivateKey>>,
+    /// Whether each member has verified its key shares. Only tracked by the leader.
+    pub verified_members: Vec<bool>,
+    pub output: Option<TransactionOutput>,
+    pub funding_tx_id: Option<TxId>,
+    pub spend: Option<MultisigSpend>,
+    pub timestamp: NaiveDateTime,
+}
+
+impl MultisigSession {
+    pub fn member_index(&self, public_key: &CommsPublicKey) -> Option<usize> {
+        self.members.iter().position(|m| m == public_key)
+    }
+}
+
+/// A proposal to spend the shared output of a multisig session to a one-sided destination
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MultisigSpend {
+    pub proposer: CommsPublicKey,
+    pub destination: PublicKey,
+    pub fee_per_gram: MicroTari,
+    pub message: String,
+    /// The nonce commitments of the members that have approved the spend, in the same order as the session members
+    pub approvals: Vec<Option<Vec<u8>>>,
+    /// This wallet's public nonces for the spend, which are only revealed once the signers have been fixed
+    pub nonces: Option<MultisigNonces>,
+    /// This wallet's secret nonces for the spend. They are discarded as soon as they have been used to sign.
+    pub secret_nonces: Option<MultisigSecretNonces>,
+    /// The leader's secret nonces for the spend, until the spend has been built. Only used by the leader.
+    pub leader_nonces: Option<MultisigLeaderNonces>,
+    /// The signers and nonce commitments that the leader fixed once enough members approved the spend
+    pub nonce_request: Option<MultisigNonceRequest>,
+    /// The nonces the signers have revealed, in the same order as the session members. Only used by the leader.
+    pub revealed_nonces: Vec<Option<MultisigNonces>>,
+    /// The leader's half of the spend, once every signer has revealed its nonces
+    pub template: Option<MultisigSpendTemplate>,
+    /// The partial signatures the leader has received, in the same order as the session members
+    pub partial_signatures: Vec<Option<MultisigPartialSignature>>,
+    pub tx_id: Option<TxId>,
+}
+
+impl MultisigSpend {
+    pub fn new(
+        proposer: CommsPublicKey,
+        destination: PublicKey,
+        fee_per_gram: MicroTari,
+        message: String,
+        num_members: usize,
+    ) -> Self {
+        Self {
+            proposer,
+            destination,
+            fee_per_gram,
+            message,
+            approvals: vec![None; num_members],
+            nonces: None,
+            secret_nonces: None,
+            leader_nonces: None,
+            nonce_request: None,
+            revealed_nonces: vec![None; num_members],
+            template: None,
+            partial_signatures: vec![None; num_members],
+            tx_id: None,
+        }
+    }
+
+    pub fn num_approvals(&self) -> usize {
+        self.approvals.iter().filter(|a| a.is_some()).count()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MultisigSessionStatus {
+    AwaitingKeyShares, // 0
+    Active,            // 1
+    SpendPending,      // 2
+    Spent,             // 3
+}
+
+impl TryFrom<i32> for MultisigSessionStatus {
+    type Error = TransactionConversionError;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(MultisigSessionStatus::AwaitingKeyShares),
+            1 => Ok(MultisigSessionStatus::Active),
+            2 => Ok(MultisigSessionStatus::SpendPending),
+            3 => Ok(MultisigSessionStatus::Spent),
+            code => Err(TransactionConversionError { code }),
+        }
+    }
+}
+
+impl Display for MultisigSessionStatus {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
+        use MultisigSessionStatus::*;
+        let response = match self {
+            AwaitingKeyShares => "Awaiting Key Shares",
+            Active => "Active",
+            SpendPending => "Spend Pending",
+            Spent => "Spent",
+        };
+        fmt.write_str(response)
+    }
+}
+
+/// One of the payments made by a one-sided batch transaction. The transaction itself is recorded once, so the recipient
+/// and message of each payment are kept alongside it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchPayment {
+    pub tx_id: TxId,
+    pub destination: CommsPublicKey,
+    pub amount: MicroTari,
+    pub message: String,
+}
+
+/// A payment that is sent automatically once the chain reaches a height or the clock reaches a time, optionally
+/// repeating at a fixed interval until cancelled or until the given number of payments has been made.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduledPayment {
+    pub id: u64,
+    pub destination: CommsPublicKey,
+    pub amount: MicroTari,
+    pub fee_per_gram: MicroTari,
+    pub message: String,
+    pub one_sided: bool,
+    /// The height before which the recipient cannot spend the payment. Only supported for one-sided payments.
+    pub recipient_lock_height: Option<u64>,
+    pub next_payment: PaymentTrigger,
+    /// The interval between payments, in blocks for height triggers and seconds for time triggers
+    pub repeat_interval: Option<u64>,
+    /// The number of payments still to be made, or `None` to repeat until cancelled
+    pub remaining_payments: Option<u64>,
+    pub status: ScheduledPaymentStatus,
+    pub last_tx_id: Option<TxId>,
+    pub created_at: NaiveDateTime,
+}
+
+impl ScheduledPayment {
+    pub fn is_due(&self, tip_height: Option<u64>, now: NaiveDateTime) -> bool {
+        self.status == ScheduledPaymentStatus::Scheduled && self.next_payment.is_due(tip_height, now)
+    }
+
+    /// Records a payment made for this schedule and moves the schedule on to the next payment. Occurrences that were
+    /// missed while the wallet was offline are skipped rather than paid all at once. The schedule completes when no
+    /// payments remain or the next payment cannot be represented.
+    pub fn record_payment(&mut self, tx_id: TxId, tip_height: Option<u64>, now: NaiveDateTime) {
+        self.last_tx_id = Some(tx_id);
+        self.remaining_payments = self.remaining_payments.map(|n| n.saturating_sub(1));
+        let interval = match self.repeat_interval {
+            Some(interval) if interval > 0 && self.remaining_payments != Some(0) => interval,
+            _ => {
+                self.status = ScheduledPaymentStatus::Completed;
+                return;
+            },
+        };
+        let mut next_payment = self.next_payment.after(interval);
+        while let Some(trigger) = next_payment.filter(|t| t.is_due(tip_height, now)) {
+            next_payment = trigger.after(interval);
+        }
+        match next_payment {
+            Some(trigger) => self.next_payment = trigger,
+            None => self.status = ScheduledPaymentStatus::Completed,
+        }
+    }
+}
+
+/// When a scheduled payment is due
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaymentTrigger {
+    /// Once the base node reports a chain tip at or above this height
+    Height(u64),
+    /// Once this (UTC) time has passed
+    Time(NaiveDateTime),
+}
+
+impl PaymentTrigger {
+    pub fn is_due(&self, tip_height: Option<u64>, now: NaiveDateTime) -> bool {
+        match self {
+            PaymentTrigger::Height(height) => tip_height.map(|tip| tip >= *height).unwrap_or(false),
+            PaymentTrigger::Time(time) => now >= *time,
+        }
+    }
+
+    /// The trigger `interval` blocks or seconds after this one, or `None` if that height or time cannot be represented
+    pub fn after(&self, interval: u64) -> Option<Self> {
+        match self {
+            PaymentTrigger::Height(height) => height.checked_add(interval).map(PaymentTrigger::Height),
+            PaymentTrigger::Time(time) => {
+                let interval = chrono::Duration::from_std(std::time::Duration::from_secs(interval)).ok()?;
+                time.checked_add_signed(interval).map(PaymentTrigger::Time)
+            },
+        }
+    }
+}
+
+impl Display for PaymentTrigger {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
+        match self {
+            PaymentTrigger::Height(height) => write!(fmt, "height {}", height),
+            PaymentTrigger::Time(time) => write!(fmt, "{} UTC", time.format("%Y-%m-%d %H:%M:%S")),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScheduledPaymentStatus {
+    Scheduled, // 0
+    Completed, // 1
+    Cancelled, // 2
+    Failed,    // 3
+}
+
+impl TryFrom<i32> for ScheduledPaymentStatus {
+    type Error = TransactionConversionError;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(ScheduledPaymentStatus::Scheduled),
+            1 => Ok(ScheduledPaymentStatus::Completed),
+            2 => Ok(ScheduledPaymentStatus::Cancelled),
+            3 => Ok(ScheduledPaymentStatus::Failed),
+            code => Err(TransactionConversionError { code }),
+        }
+    }
+}
+
+impl Display for ScheduledPaymentStatus {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
+        use ScheduledPaymentStatus::*;
+        let response = match self {
+            Scheduled => "Scheduled",
+            Completed => "Completed",
+            Cancelled => "Cancelled",
+            Failed => "Failed",
+        };
+        fmt.write_str(response)
+    }
+}
@@ -51,16 +51,28 @@ use thiserror::Error;
 use tokio::time::Instant;
 
 use crate::{
-    schema::{completed_transactions, inbound_transactions, outbound_transactions},
+    schema::{
+        batch_payments,
+        completed_transactions,
+        inbound_transactions,
+        multisig_sessions,
+        outbound_transactions,
+        scheduled_payments,
+    },
     storage::sqlite_utilities::wallet_db_connection::WalletDbConnection,
     transaction_service::{
         error::{TransactionKeyError, TransactionStorageError},
         storage::{
             database::{DbKey, DbKeyValuePair, DbValue, TransactionBackend, WriteOperation},
             models::{
+                BatchPayment,
                 CompletedTransaction,
                 InboundTransaction,
+                MultisigSession,
                 OutboundTransaction,
+                PaymentTrigger,
+                ScheduledPayment,
+                ScheduledPaymentStatus,
                 TxCancellationReason,
                 WalletTransaction,
             },
@@ -117,6 +129,22 @@ impl TransactionServiceSqliteDatabase {
 
                 c.commit(conn)?;
             },
+            DbKeyValuePair::MultisigSession(_, v) => {
+                let mut m = MultisigSessionSql::try_from(*v)?;
+                self.encrypt_if_necessary(&mut m)?;
+                m.commit(conn)?;
+            },
+            DbKeyValuePair::ScheduledPayment(_, v) => {
+                ScheduledPaymentSql::from(*v).commit(conn)?;
+            },
+            DbKeyValuePair::BatchPayments(_, v) => {
+                conn.transaction::<_, TransactionStorageError, _>(|| {
+                    for (index, payment) in v.into_iter().enumerate() {
+                        BatchPaymentSql::new(index, payment).commit(conn)?;
+                    }
+                    Ok(())
+                })?;
+            },
         }
         Ok(())
     }
@@ -199,6 +227,11 @@ impl TransactionServiceSqliteDatabase {
                 }
             },
             DbKey::AnyTransaction(_) => Err(TransactionStorageError::OperationNotSupported),
+            DbKey::MultisigSession(_) => Err(TransactionStorageError::OperationNotSupported),
+            DbKey::MultisigSessions => Err(TransactionStorageError::OperationNotSupported),
+            DbKey::ScheduledPayment(_) => Err(TransactionStorageError::OperationNotSupported),
+            DbKey::ScheduledPayments => Err(TransactionStorageError::OperationNotSupported),
+            DbKey::BatchPayments(_) => Err(TransactionStorageError::OperationNotSupported),
         }
     }
 
@@ -373,6 +406,40 @@ impl TransactionBackend for TransactionServiceSqliteDatabase {
                     Err(e) => return Err(e),
                 }
             },
+            DbKey::MultisigSession(session_id) => match MultisigSessionSql::find(*session_id, &conn) {
+                Ok(mut m) => {
+                    self.decrypt_if_necessary(&mut m)?;
+                    Some(DbValue::MultisigSession(Box::new(MultisigSession::try_from(m)?)))
+                },
+                Err(TransactionStorageError::DieselError(DieselError::NotFound)) => None,
+                Err(e) => return Err(e),
+            },
+            DbKey::MultisigSessions => {
+                let mut result = Vec::new();
+                for m in MultisigSessionSql::index(&conn)?.iter_mut() {
+                    self.decrypt_if_necessary(m)?;
+                    result.push(MultisigSession::try_from((*m).clone())?);
+                }
+
+                Some(DbValue::MultisigSessions(result))
+            },
+            DbKey::ScheduledPayment(id) => match ScheduledPaymentSql::find(*id, &conn) {
+                Ok(p) => Some(DbValue::ScheduledPayment(Box::new(ScheduledPayment::try_from(p)?))),
+                Err(TransactionStorageError::DieselError(DieselError::NotFound)) => None,
+                Err(e) => return Err(e),
+            },
+            DbKey::ScheduledPayments => Some(DbValue::ScheduledPayments(
+                ScheduledPaymentSql::index(&conn)?
+                    .into_iter()
+                    .map(ScheduledPayment::try_from)
+                    .collect::<Result<Vec<_>, _>>()?,
+            )),
+            DbKey::BatchPayments(tx_id) => Some(DbValue::BatchPayments(
+                BatchPaymentSql::find_by_tx_id(*tx_id, &conn)?
+                    .into_iter()
+                    .map(BatchPayment::try_from)
+                    .collect::<Result<Vec<_>, _>>()?,
+            )),
         };
         if start.elapsed().as_millis() > 0 {
             trace!(
@@ -414,6 +481,11 @@ impl TransactionBackend for TransactionServiceSqliteDatabase {
                     InboundTransactionSql::find(*k, &conn).is_ok() ||
                     OutboundTransactionSql::find(*k, &conn).is_ok()
             },
+            DbKey::MultisigSession(k) => MultisigSessionSql::find(*k, &conn).is_ok(),
+            DbKey::MultisigSessions => false,
+            DbKey::ScheduledPayment(k) => ScheduledPaymentSql::find(*k, &conn).is_ok(),
+            DbKey::ScheduledPayments => false,
+            DbKey::BatchPayments(_) => false,
         };
         if start.elapsed().as_millis() > 0 {
             trace!(
@@ -849,6 +921,23 @@ impl TransactionBackend for TransactionServiceSqliteDatabase {
             tx.update_encryption(&conn)?;
         }
 
+        let mut multisig_sessions = MultisigSessionSql::index(&conn)?;
+        // If the db is already encrypted then the very first session we try to encrypt will fail.
+        for session in multisig_sessions.iter_mut() {
+            // Test if this session is encrypted or not to avoid a double encryption.
+            let _ = MultisigSession::try_from(session.clone()).map_err(|_| {
+                error!(
+                    target: LOG_TARGET,
+                    "Could not convert Multisig Session from database version, it might already be encrypted"
+                );
+                TransactionStorageError::AlreadyEncrypted
+            })?;
+            session
+                .encrypt(&cipher)
+                .map_err(|_| TransactionStorageError::AeadError("Encryption Error".to_string()))?;
+            session.update_encryption(&conn)?;
+        }
+
         (*current_cipher) = Some(cipher);
         if start.elapsed().as_millis() > 0 {
             trace!(
@@ -898,6 +987,14 @@ impl TransactionBackend for TransactionServiceSqliteDatabase {
             tx.update_encryption(&conn)?;
         }
 
+        let mut multisig_sessions = MultisigSessionSql::index(&conn)?;
+        for session in multisig_sessions.iter_mut() {
+            session
+                .decrypt(&cipher)
+                .map_err(|_| TransactionStorageError::AeadError("Decryption Error".to_string()))?;
+            session.update_encryption(&conn)?;
+        }
+
         // Now that all the decryption has been completed we can safely remove the cipher fully
         let _ = (*current_cipher).take();
         if start.elapsed().as_millis() > 0 {
@@ -2121,6 +2218,240 @@ impl UnconfirmedTransactionInfoSql {
     }
 }
 
+/// A structure to represent a Sql compatible version of the MultisigSession struct. The session itself, which holds
+/// the aggregated keys of the shared output, is stored as JSON so that it can be encrypted.
+#[derive(Clone, Debug, Queryable, Insertable, PartialEq)]
+#[table_name = "multisig_sessions"]
+struct MultisigSessionSql {
+    session_id: i64,
+    leader_public_key: Vec<u8>,
+    status: i32,
+    session_data: String,
+    timestamp: NaiveDateTime,
+}
+
+impl MultisigSessionSql {
+    /// Inserts the session, replacing any existing session with the same id
+    pub fn commit(&self, conn: &SqliteConnection) -> Result<(), TransactionStorageError> {
+        diesel::replace_into(multisig_sessions::table)
+            .values(self.clone())
+            .execute(conn)?;
+        Ok(())
+    }
+
+    pub fn index(conn: &SqliteConnection) -> Result<Vec<MultisigSessionSql>, TransactionStorageError> {
+        Ok(multisig_sessions::table
+            .order_by(multisig_sessions::timestamp.asc())
+            .load::<MultisigSessionSql>(conn)?)
+    }
+
+    pub fn find(session_id: u64, conn: &SqliteConnection) -> Result<MultisigSessionSql, TransactionStorageError> {
+        Ok(multisig_sessions::table
+            .filter(multisig_sessions::session_id.eq(session_id as i64))
+            .first::<MultisigSessionSql>(conn)?)
+    }
+
+    pub fn update_encryption(&self, conn: &SqliteConnection) -> Result<(), TransactionStorageError> {
+        diesel::update(multisig_sessions::table.filter(multisig_sessions::session_id.eq(&self.session_id)))
+            .set(multisig_sessions::session_data.eq(&self.session_data))
+            .execute(conn)
+            .num_rows_affected_or_not_found(1)?;
+        Ok(())
+    }
+}
+
+impl Encryptable<Aes256Gcm> for MultisigSessionSql {
+    fn encrypt(&mut self, cipher: &Aes256Gcm) -> Result<(), String> {
+        let encrypted_session = encrypt_bytes_integral_nonce(cipher, self.session_data.as_bytes().to_vec())?;
+        self.session_data = encrypted_session.to_hex();
+        Ok(())
+    }
+
+    fn decrypt(&mut self, cipher: &Aes256Gcm) -> Result<(), String> {
+        let decrypted_session =
+            decrypt_bytes_integral_nonce(cipher, from_hex(self.session_data.as_str()).map_err(|e| e.to_string())?)?;
+        self.session_data = from_utf8(decrypted_session.as_slice())
+            .map_err(|e| e.to_string())?
+            .to_string();
+        Ok(())
+    }
+}
+
+impl TryFrom<MultisigSession> for MultisigSessionSql {
+    type Error = TransactionStorageError;
+
+    fn try_from(m: MultisigSession) -> Result<Self, Self::Error> {
+        Ok(Self {
+            session_id: m.session_id as i64,
+            leader_public_key: m.leader.to_vec(),
+            status: m.status as i32,
+            session_data: serde_json::to_string(&m)?,
+            timestamp: m.timestamp,
+        })
+    }
+}
+
+impl TryFrom<MultisigSessionSql> for MultisigSession {
+    type Error = TransactionStorageError;
+
+    fn try_from(m: MultisigSessionSql) -> Result<Self, Self::Error> {
+        Ok(serde_json::from_str(&m.session_data)?)
+    }
+}
+
+/// A structure to represent a Sql compatible version of the ScheduledPayment struct. Exactly one of `trigger_height`
+/// and `trigger_time` is set.
+#[derive(Clone, Debug, Queryable, Insertable, PartialEq)]
+#[table_name = "scheduled_payments"]
+struct ScheduledPaymentSql {
+    id: i64,
+    destination_public_key: Vec<u8>,
+    amount: i64,
+    fee_per_gram: i64,
+    message: String,
+    one_sided: i32,
+    recipient_lock_height: Option<i64>,
+    trigger_height: Option<i64>,
+    trigger_time: Option<NaiveDateTime>,
+    repeat_interval: Option<i64>,
+    remaining_payments: Option<i64>,
+    status: i32,
+    last_tx_id: Option<i64>,
+    created_at: NaiveDateTime,
+}
+
+impl ScheduledPaymentSql {
+    /// Inserts the payment, replacing any existing payment with the same id
+    pub fn commit(&self, conn: &SqliteConnection) -> Result<(), TransactionStorageError> {
+        diesel::replace_into(scheduled_payments::table)
+            .values(self.clone())
+            .execute(conn)?;
+        Ok(())
+    }
+
+    pub fn index(conn: &SqliteConnection) -> Result<Vec<ScheduledPaymentSql>, TransactionStorageError> {
+        Ok(scheduled_payments::table
+            .order_by(scheduled_payments::created_at.asc())
+            .load::<ScheduledPaymentSql>(conn)?)
+    }
+
+    pub fn find(id: u64, conn: &SqliteConnection) -> Result<ScheduledPaymentSql, TransactionStorageError> {
+        Ok(scheduled_payments::table
+            .filter(scheduled_payments::id.eq(id as i64))
+            .first::<ScheduledPaymentSql>(conn)?)
+    }
+}
+
+impl From<ScheduledPayment> for ScheduledPaymentSql {
+    fn from(p: ScheduledPayment) -> Self {
+        let (trigger_height, trigger_time) = match p.next_payment {
+            PaymentTrigger::Height(height) => (Some(height as i64), None),
+            PaymentTrigger::Time(time) => (None, Some(time)),
+        };
+        Self {
+            id: p.id as i64,
+            destination_public_key: p.destination.to_vec(),
+            amount: u64::from(p.amount) as i64,
+            fee_per_gram: u64::from(p.fee_per_gram) as i64,
+            message: p.message,
+            one_sided: p.one_sided as i32,
+            recipient_lock_height: p.recipient_lock_height.map(|h| h as i64),
+            trigger_height,
+            trigger_time,
+            repeat_interval: p.repeat_interval.map(|i| i as i64),
+            remaining_payments: p.remaining_payments.map(|n| n as i64),
+            status: p.status as i32,
+            last_tx_id: p.last_tx_id.map(i64::from),
+            created_at: p.created_at,
+        }
+    }
+}
+
+impl TryFrom<ScheduledPaymentSql> for ScheduledPayment {
+    type Error = TransactionStorageError;
+
+    fn try_from(p: ScheduledPaymentSql) -> Result<Self, Self::Error> {
+        let next_payment = match (p.trigger_height, p.trigger_time) {
+            (Some(height), _) => PaymentTrigger::Height(height as u64),
+            (None, Some(time)) => PaymentTrigger::Time(time),
+            (None, None) => {
+                return Err(TransactionStorageError::UnexpectedResult(format!(
+                    "Scheduled payment {} has no trigger",
+                    p.id
+                )))
+            },
+        };
+        Ok(Self {
+            id: p.id as u64,
+            destination: PublicKey::from_vec(&p.destination_public_key)?,
+            amount: MicroTari::from(p.amount as u64),
+            fee_per_gram: MicroTari::from(p.fee_per_gram as u64),
+            message: p.message,
+            one_sided: p.one_sided != 0,
+            recipient_lock_height: p.recipient_lock_height.map(|h| h as u64),
+            next_payment,
+            repeat_interval: p.repeat_interval.map(|i| i as u64),
+            remaining_payments: p.remaining_payments.map(|n| n as u64),
+            status: ScheduledPaymentStatus::try_from(p.status)?,
+            last_tx_id: p.last_tx_id.map(|id| TxId::from(id as u64)),
+            created_at: p.created_at,
+        })
+    }
+}
+
+/// A structure to represent a Sql compatible version of the BatchPayment struct
+#[derive(Clone, Debug, Queryable, Insertable, PartialEq)]
+#[table_name = "batch_payments"]
+struct BatchPaymentSql {
+    tx_id: i64,
+    payment_index: i32,
+    destination_public_key: Vec<u8>,
+    amount: i64,
+    message: String,
+}
+
+impl BatchPaymentSql {
+    fn new(index: usize, payment: BatchPayment) -> Self {
+        Self {
+            tx_id: i64::from(payment.tx_id),
+            payment_index: index as i32,
+            destination_public_key: payment.destination.to_vec(),
+            amount: u64::from(payment.amount) as i64,
+            message: payment.message,
+        }
+    }
+
+    pub fn commit(&self, conn: &SqliteConnection) -> Result<(), TransactionStorageError> {
+        diesel::insert_into(batch_payments::table)
+            .values(self.clone())
+            .execute(conn)?;
+        Ok(())
+    }
+
+    pub fn find_by_tx_id(
+        tx_id: TxId,
+        conn: &SqliteConnection,
+    ) -> Result<Vec<BatchPaymentSql>, TransactionStorageError> {
+        Ok(batch_payments::table
+            .filter(batch_payments::tx_id.eq(i64::from(tx_id)))
+            .order_by(batch_payments::payment_index.asc())
+            .load::<BatchPaymentSql>(conn)?)
+    }
+}
+
+impl TryFrom<BatchPaymentSql> for BatchPayment {
+    type Error = TransactionStorageError;
+
+    fn try_from(p: BatchPaymentSql) -> Result<Self, Self::Error> {
+        Ok(Self {
+            tx_id: TxId::from(p.tx_id as u64),
+            destination: PublicKey::from_vec(&p.destination_public_key)?,
+            amount: MicroTari::from(p.amount as u64),
+            message: p.message,
+        })
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::{convert::TryFrom, time::Duration};
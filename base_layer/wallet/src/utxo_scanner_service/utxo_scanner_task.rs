@@ -490,7 +490,9 @@ where TBackend: WalletBackend + 'static
     ) -> Result<(TxId, Vec<(UnblindedOutput, String)>), UtxoScannerError> {
         let mut found_outputs: Vec<(UnblindedOutput, String)> = Vec::new();
         let tx_id = TxId::new_random();
-        if self.mode == UtxoScannerMode::Recovery {
+        // A watch-only wallet relies on rewinding to find all of its outputs, not only during recovery
+        let is_watch_only = self.resources.db.get_watch_only_keys().await?.is_some();
+        if self.mode == UtxoScannerMode::Recovery || is_watch_only {
             found_outputs.append(
                 &mut self
                     .resources
@@ -53,7 +53,7 @@ use tari_crypto::{
     script,
     script::{ExecutionStack, TariScript},
     signatures::{SchnorrSignature, SchnorrSignatureError},
-    tari_utilities::hex::Hex,
+    tari_utilities::{hex::Hex, ByteArray},
 };
 use tari_key_manager::{cipher_seed::CipherSeed, key_manager::KeyManager};
 use tari_p2p::{
@@ -61,6 +61,7 @@ use tari_p2p::{
     comms_connector::pubsub_connector,
     initialization,
     initialization::P2pInitializer,
+    services::liveness::{LivenessConfig, LivenessInitializer},
 };
 use tari_service_framework::StackBuilder;
 use tari_shutdown::ShutdownSignal;
@@ -72,10 +73,12 @@ use crate::{
     connectivity_service::{WalletConnectivityHandle, WalletConnectivityInitializer, WalletConnectivityInterface},
     contacts_service::{handle::ContactsServiceHandle, storage::database::ContactsBackend, ContactsServiceInitializer},
     error::WalletError,
+    invoice_service::{handle::InvoiceServiceHandle, InvoiceServiceInitializer},
     output_manager_service::{
         error::OutputManagerError,
         handle::OutputManagerHandle,
         storage::{database::OutputManagerBackend, models::KnownOneSidedPaymentScript},
+        watch_only::{WalletKeySource, WatchOnlyKeys},
         OutputManagerServiceInitializer,
     },
     storage::database::{WalletBackend, WalletDatabase},
@@ -85,7 +88,7 @@ use crate::{
         storage::database::TransactionBackend,
         TransactionServiceInitializer,
     },
-    types::KeyDigest,
+    types::{HashDigest, KeyDigest},
     utxo_scanner_service::{handle::UtxoScannerHandle, UtxoScannerServiceInitializer, RECOVERY_KEY},
 };
 
@@ -103,6 +106,7 @@ pub struct Wallet<T, U, V, W> {
     pub transaction_service: TransactionServiceHandle,
     pub wallet_connectivity: WalletConnectivityHandle,
     pub contacts_service: ContactsServiceHandle,
+    pub invoice_service: InvoiceServiceHandle,
     pub base_node_service: BaseNodeServiceHandle,
     pub utxo_scanner_service: UtxoScannerHandle,
     pub asset_manager: AssetManagerHandle,
@@ -129,13 +133,14 @@ where
         output_manager_backend: V,
         contacts_backend: W,
         shutdown_signal: ShutdownSignal,
-        master_seed: CipherSeed,
+        key_source: WalletKeySource,
     ) -> Result<Self, WalletError> {
         let factories = config.factories.clone();
         let (publisher, subscription_factory) = pubsub_connector(config.buffer_size, config.rate_limit);
         let peer_message_subscription_factory = Arc::new(subscription_factory);
         let transport_type = config.comms_config.transport_type.clone();
         let node_identity = config.comms_config.node_identity.clone();
+        let is_watch_only = key_source.is_watch_only();
 
         debug!(target: LOG_TARGET, "Wallet Initializing");
         info!(
@@ -163,18 +168,35 @@ where
                 output_manager_backend.clone(),
                 factories.clone(),
                 config.network,
-                master_seed,
+                key_source,
                 node_identity.clone(),
             ))
             .add_initializer(TransactionServiceInitializer::new(
                 config.transaction_service_config.unwrap_or_default(),
-                peer_message_subscription_factory,
+                peer_message_subscription_factory.clone(),
                 transaction_backend,
                 node_identity.clone(),
                 factories.clone(),
                 wallet_database.clone(),
             ))
-            .add_initializer(ContactsServiceInitializer::new(contacts_backend))
+            // Liveness is only used to ping contacts on request, so automatic ping rounds are disabled
+            .add_initializer(LivenessInitializer::new(
+                LivenessConfig {
+                    auto_ping_interval: None,
+                    num_peers_per_round: 0,
+                    max_allowed_ping_failures: 0,
+                    ..Default::default()
+                },
+                peer_message_subscription_factory,
+            ))
+            .add_initializer(ContactsServiceInitializer::new(
+                config.contacts_service_config,
+                contacts_backend,
+            ))
+            .add_initializer(InvoiceServiceInitializer::new(
+                wallet_database.clone(),
+                node_identity.clone(),
+            ))
             .add_initializer(BaseNodeServiceInitializer::new(
                 config.base_node_service_config.clone(),
                 wallet_database.clone(),
@@ -212,6 +234,7 @@ where
         let mut output_manager_handle = handles.expect_handle::<OutputManagerHandle>();
         let transaction_service_handle = handles.expect_handle::<TransactionServiceHandle>();
         let contacts_handle = handles.expect_handle::<ContactsServiceHandle>();
+        let invoice_handle = handles.expect_handle::<InvoiceServiceHandle>();
         let dht = handles.expect_handle::<Dht>();
         let store_and_forward_requester = dht.store_and_forward_requester();
 
@@ -224,12 +247,16 @@ where
             .updater_config
             .map(|_updater_config| handles.expect_handle::<SoftwareUpdaterHandle>());
 
-        persist_one_sided_payment_script_for_node_identity(&mut output_manager_handle, comms.node_identity())
-            .await
-            .map_err(|e| {
-                error!(target: LOG_TARGET, "{:?}", e);
-                e
-            })?;
+        // The node identity of a watch-only wallet is not the key one-sided payments are sent to, so there is nothing
+        // to scan for
+        if !is_watch_only {
+            persist_one_sided_payment_script_for_node_identity(&mut output_manager_handle, comms.node_identity())
+                .await
+                .map_err(|e| {
+                    error!(target: LOG_TARGET, "{:?}", e);
+                    e
+                })?;
+        }
 
         // Persist the comms node address and features after it has been spawned to capture any modifications made
         // during comms startup. In the case of a Tor Transport the public address could have been generated
@@ -248,6 +275,7 @@ where
             output_manager_service: output_manager_handle,
             transaction_service: transaction_service_handle,
             contacts_service: contacts_handle,
+            invoice_service: invoice_handle,
             base_node_service: base_node_service_handle,
             utxo_scanner_service: utxo_scanner_service_handle,
             updater_service: updater_handle,
@@ -579,6 +607,35 @@ pub async fn read_or_create_master_seed<T: WalletBackend + 'static>(
     Ok(master_seed)
 }
 
+/// Read the watch-only keys from the database, or store the provided keys if this is a new watch-only wallet. Returns
+/// None if the wallet is not a watch-only wallet.
+pub async fn read_or_import_watch_only_keys<T: WalletBackend + 'static>(
+    watch_only_keys: Option<WatchOnlyKeys>,
+    db: &WalletDatabase<T>,
+) -> Result<Option<WatchOnlyKeys>, WalletError> {
+    let db_watch_only_keys = db.get_watch_only_keys().await?;
+
+    match (watch_only_keys, db_watch_only_keys) {
+        (None, db_keys) => Ok(db_keys),
+        (Some(keys), None) => {
+            if db.get_master_seed().await?.is_some() {
+                let msg = "A wallet with a master seed already exists! Move the existing wallet database file.";
+                return Err(WalletError::WalletRecoveryError(msg.to_string()));
+            }
+            db.set_watch_only_keys(keys.clone()).await?;
+            Ok(Some(keys))
+        },
+        (Some(_), Some(_)) => {
+            error!(
+                target: LOG_TARGET,
+                "Attempted watch-only import would overwrite the existing wallet database keys"
+            );
+            let msg = "Wallet already exists! Move the existing wallet database file.".to_string();
+            Err(WalletError::WalletRecoveryError(msg))
+        },
+    }
+}
+
 pub fn derive_comms_secret_key(master_seed: &CipherSeed) -> Result<CommsSecretKey, WalletError> {
     let comms_key_manager = KeyManager::<PrivateKey, KeyDigest>::from(
         master_seed.clone(),
@@ -588,6 +645,16 @@ pub fn derive_comms_secret_key(master_seed: &CipherSeed) -> Result<CommsSecretKe
     Ok(comms_key_manager.derive_key(0)?.k)
 }
 
+/// A watch-only wallet has no master seed to derive its comms key from, so it is derived from the rewind key instead.
+/// This keeps the node identity stable across restarts without revealing anything about the watched wallet.
+pub fn derive_watch_only_comms_secret_key(keys: &WatchOnlyKeys) -> Result<CommsSecretKey, WalletError> {
+    let key_bytes = HashDigest::new()
+        .chain(KEY_MANAGER_COMMS_SECRET_KEY_BRANCH_KEY.as_bytes())
+        .chain(keys.rewind_key.as_bytes())
+        .finalize();
+    Ok(CommsSecretKey::from_bytes(key_bytes.as_slice())?)
+}
+
 /// Persist the one-sided payment script for the current wallet NodeIdentity for use during scanning for One-sided
 /// payment outputs. This is peristed so that if the Node Identity changes the wallet will still scan for outputs
 /// using old node identities.
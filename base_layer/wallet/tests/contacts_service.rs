@@ -49,10 +49,12 @@ use tari_service_framework::StackBuilder;
 use tari_shutdown::Shutdown;
 use tari_test_utils::random;
 use tari_wallet::contacts_service::{
+    config::ContactsServiceConfig,
     error::{ContactsServiceError, ContactsServiceStorageError},
     handle::ContactsServiceHandle,
+    import_export::{export_csv, export_json, import_csv, import_json},
     storage::{
-        database::{Contact, ContactsBackend, DbKey},
+        database::{Contact, ContactsBackend, DbKey, SendMethod},
         sqlite_db::ContactsServiceSqliteDatabase,
     },
     ContactsServiceInitializer,
@@ -68,7 +70,10 @@ pub fn setup_contacts_service<T: ContactsBackend + 'static>(
 ) -> (ContactsServiceHandle, Shutdown) {
     let shutdown = Shutdown::new();
     let fut = StackBuilder::new(shutdown.to_signal())
-        .add_initializer(ContactsServiceInitializer::new(backend))
+        .add_initializer(ContactsServiceInitializer::new(
+            ContactsServiceConfig::default(),
+            backend,
+        ))
         .build();
 
     let handles = runtime.block_on(fut).expect("Service initialization failed");
@@ -90,10 +95,7 @@ pub fn test_contacts_service() {
     for i in 0..5 {
         let (_secret_key, public_key) = PublicKey::random_keypair(&mut OsRng);
 
-        contacts.push(Contact {
-            alias: random::string(8),
-            public_key,
-        });
+        contacts.push(Contact::new(random::string(8), public_key));
 
         runtime
             .block_on(contacts_service.upsert_contact(contacts[i].clone()))
@@ -147,3 +149,72 @@ pub fn test_contacts_service() {
 
     assert_eq!(new_contact.alias, updated_contact.alias);
 }
+
+#[test]
+pub fn test_contact_details_and_import_export() {
+    let mut runtime = Runtime::new().unwrap();
+    let (connection, _tempdir) = get_temp_sqlite_database_connection();
+    let backend = ContactsServiceSqliteDatabase::new(connection);
+
+    let (mut contacts_service, _shutdown) = setup_contacts_service(&mut runtime, backend);
+
+    let (_secret_key, public_key) = PublicKey::random_keypair(&mut OsRng);
+    let mut contact = Contact::new("Alice".to_string(), public_key);
+    contact.tags = vec!["friends".to_string(), "miners".to_string()];
+    contact.notes = "Met at the conference, owes me lunch".to_string();
+    contact.preferred_send_method = SendMethod::OneSided;
+    runtime
+        .block_on(contacts_service.upsert_contact(contact.clone()))
+        .unwrap();
+
+    let (_secret_key, public_key) = PublicKey::random_keypair(&mut OsRng);
+    let other_contact = Contact::new("Bob".to_string(), public_key);
+    runtime
+        .block_on(contacts_service.upsert_contact(other_contact))
+        .unwrap();
+
+    let stored = runtime
+        .block_on(contacts_service.get_contact(contact.public_key.clone()))
+        .unwrap();
+    assert_eq!(stored, contact);
+
+    let contacts = runtime.block_on(contacts_service.get_contacts()).unwrap();
+    assert_eq!(import_json(&export_json(&contacts).unwrap()).unwrap(), contacts);
+    assert_eq!(import_csv(&export_csv(&contacts)).unwrap(), contacts);
+
+    let (_secret_key, public_key) = PublicKey::random_keypair(&mut OsRng);
+    let csv = format!(
+        "# comment\n\n{},Carol,interactive,family,\"likes, commas\"\n",
+        public_key
+    );
+    let imported = import_csv(&csv).unwrap();
+    assert_eq!(imported.len(), 1);
+    assert_eq!(imported[0].tags, vec!["family".to_string()]);
+    assert_eq!(imported[0].notes, "likes, commas");
+    assert!(import_csv("not a key,Dave").is_err());
+    assert!(import_csv(&format!("{},Dave,interactive,,unquoted, commas", public_key)).is_err());
+    assert!(import_csv(&format!("{},\"Dave", public_key)).is_err());
+
+    let num_imported = runtime.block_on(contacts_service.import_contacts(imported)).unwrap();
+    assert_eq!(num_imported, 1);
+    let contacts = runtime.block_on(contacts_service.get_contacts()).unwrap();
+    assert_eq!(contacts.len(), 3);
+
+    match runtime.block_on(contacts_service.get_contact_transactions(contact.public_key)) {
+        Err(ContactsServiceError::TransactionServiceUnavailable) => {},
+        _ => panic!("There should be a specific error here"),
+    }
+}
+
+#[test]
+pub fn test_csv_export_and_import_preserve_special_characters() {
+    let (_secret_key, public_key) = PublicKey::random_keypair(&mut OsRng);
+    let mut contact = Contact::new(" Eve, \"the\" eavesdropper ".to_string(), public_key);
+    contact.tags = vec!["a,b".to_string(), "semi;colon".to_string(), "back\\slash".to_string()];
+    contact.notes = "  Line one, with a comma\nLine \"two\"  ".to_string();
+    let (_secret_key, public_key) = PublicKey::random_keypair(&mut OsRng);
+    let other_contact = Contact::new("#hashtag".to_string(), public_key);
+    let contacts = vec![contact, other_contact];
+
+    assert_eq!(import_csv(&export_csv(&contacts)).unwrap(), contacts);
+}
@@ -66,18 +66,19 @@ use tari_wallet::{
         mock_base_node_service::MockBaseNodeService,
         service::BaseNodeState,
     },
-    connectivity_service::{create_wallet_connectivity_mock, WalletConnectivityMock},
+    connectivity_service::{create_wallet_connectivity_mock, OnlineStatus, WalletConnectivityMock},
     output_manager_service::{
         config::OutputManagerServiceConfig,
         error::{OutputManagerError, OutputManagerStorageError},
         handle::{OutputManagerEvent, OutputManagerHandle},
-        service::OutputManagerService,
+        service::{OutputManagerService, UTXOSelectionStrategy},
         storage::{
             database::{OutputManagerBackend, OutputManagerDatabase},
             models::SpendingPriority,
             sqlite_db::OutputManagerSqliteDatabase,
             OutputStatus,
         },
+        watch_only::WatchOnlyKeys,
     },
     test_utils::create_consensus_constants,
     transaction_service::handle::TransactionServiceHandle,
@@ -202,7 +203,7 @@ async fn setup_output_manager_service<T: OutputManagerBackend + 'static>(
         shutdown.to_signal(),
         basenode_service_handle,
         wallet_connectivity_mock.clone(),
-        cipher_seed,
+        cipher_seed.into(),
         server_node_identity.clone(),
     )
     .await
@@ -270,7 +271,7 @@ pub async fn setup_oms_with_bn_state<T: OutputManagerBackend + 'static>(
         shutdown.to_signal(),
         base_node_service_handle.clone(),
         connectivity,
-        CipherSeed::new(),
+        CipherSeed::new().into(),
         node_identity,
     )
     .await
@@ -340,7 +341,7 @@ async fn fee_estimate() {
     // minimum fpg
     let fee_per_gram = MicroTari::from(1);
     let fee = oms
-        .fee_estimate(MicroTari::from(100), fee_per_gram, 1, 1)
+        .fee_estimate(MicroTari::from(100), Some(fee_per_gram), 1, 1)
         .await
         .unwrap();
     assert_eq!(
@@ -351,7 +352,7 @@ async fn fee_estimate() {
     let fee_per_gram = MicroTari::from(5);
     for outputs in 1..5 {
         let fee = oms
-            .fee_estimate(MicroTari::from(100), fee_per_gram, 1, outputs)
+            .fee_estimate(MicroTari::from(100), Some(fee_per_gram), 1, outputs)
             .await
             .unwrap();
 
@@ -369,12 +370,60 @@ async fn fee_estimate() {
 
     // not enough funds
     let err = oms
-        .fee_estimate(MicroTari::from(2750), fee_per_gram, 1, 1)
+        .fee_estimate(MicroTari::from(2750), Some(fee_per_gram), 1, 1)
         .await
         .unwrap_err();
     assert!(matches!(err, OutputManagerError::NotEnoughFunds));
 }
 
+#[tokio::test]
+async fn estimate_fee_per_gram() {
+    let (connection, _tempdir) = get_temp_sqlite_database_connection();
+    let backend = OutputManagerSqliteDatabase::new(connection, None);
+    let (mut oms, wallet_connectivity, _shutdown, _, mock_rpc_server, server_node_identity, rpc_service_state, _) =
+        setup_output_manager_service(backend, true).await;
+    let default_fee_per_gram = OutputManagerServiceConfig::default().default_fee_per_gram;
+
+    // The base node is offline so the configured default is used
+    let fee_per_gram = oms.estimate_fee_per_gram(None).await.unwrap();
+    assert_eq!(fee_per_gram, default_fee_per_gram);
+
+    // The base node is online but has not seen enough transactions to make an estimate. The connection is held for the
+    // rest of the test so that the base node keeps responding.
+    let mut connection = mock_rpc_server
+        .create_connection(server_node_identity.to_peer(), "t/bnwallet/1".into())
+        .await;
+    wallet_connectivity.set_base_node_wallet_rpc_client(connect_rpc_client(&mut connection).await);
+    wallet_connectivity.set_connectivity_status(OnlineStatus::Online);
+    let fee_per_gram = oms.estimate_fee_per_gram(None).await.unwrap();
+    assert_eq!(fee_per_gram, default_fee_per_gram);
+
+    rpc_service_state.set_fee_per_gram_estimate(42);
+    let fee_per_gram = oms.estimate_fee_per_gram(Some(1)).await.unwrap();
+    assert_eq!(fee_per_gram, MicroTari::from(42));
+
+    // Estimates above the configured maximum are capped at the maximum
+    let max_fee_per_gram = OutputManagerServiceConfig::default().max_fee_per_gram;
+    rpc_service_state.set_fee_per_gram_estimate(max_fee_per_gram.as_u64() * 10);
+    let fee_per_gram = oms.estimate_fee_per_gram(Some(1)).await.unwrap();
+    assert_eq!(fee_per_gram, max_fee_per_gram);
+    rpc_service_state.set_fee_per_gram_estimate(max_fee_per_gram.as_u64());
+    let fee_per_gram = oms.estimate_fee_per_gram(Some(1)).await.unwrap();
+    assert_eq!(fee_per_gram, max_fee_per_gram);
+    rpc_service_state.set_fee_per_gram_estimate(42);
+
+    // A fee estimate without a fee per gram uses the base node's estimate
+    let factories = CryptoFactories::default();
+    let (_, uo) = make_input(&mut OsRng.clone(), MicroTari::from(30_000), &factories.commitment);
+    oms.add_output(uo, None).await.unwrap();
+    let fee_calc = Fee::new(*create_consensus_constants(0).transaction_weight());
+    let fee = oms.fee_estimate(MicroTari::from(100), None, 1, 1).await.unwrap();
+    assert_eq!(
+        fee,
+        fee_calc.calculate(MicroTari::from(42), 1, 1, 2, 2 * default_metadata_byte_size())
+    );
+}
+
 #[allow(clippy::identity_op)]
 #[tokio::test]
 async fn test_utxo_selection_no_chain_metadata() {
@@ -404,6 +453,7 @@ async fn test_utxo_selection_no_chain_metadata() {
             "".to_string(),
             script!(Nop),
             Covenant::default(),
+            None,
         )
         .await
         .unwrap_err();
@@ -432,6 +482,7 @@ async fn test_utxo_selection_no_chain_metadata() {
             "".to_string(),
             script!(Nop),
             Covenant::default(),
+            None,
         )
         .await
         .unwrap();
@@ -447,7 +498,7 @@ async fn test_utxo_selection_no_chain_metadata() {
     }
 
     // test that we can get a fee estimate with no chain metadata
-    let fee = oms.fee_estimate(amount, fee_per_gram, 1, 2).await.unwrap();
+    let fee = oms.fee_estimate(amount, Some(fee_per_gram), 1, 2).await.unwrap();
     let expected_fee = fee_calc.calculate(fee_per_gram, 1, 1, 3, default_metadata_byte_size() * 3);
     assert_eq!(fee, expected_fee);
 
@@ -456,14 +507,17 @@ async fn test_utxo_selection_no_chain_metadata() {
     // so instead of returning "not enough funds", return "funds pending"
     let spendable_amount = (3..=10).sum::<u64>() * amount;
     let err = oms
-        .fee_estimate(spendable_amount, fee_per_gram, 1, 2)
+        .fee_estimate(spendable_amount, Some(fee_per_gram), 1, 2)
         .await
         .unwrap_err();
     assert!(matches!(err, OutputManagerError::FundsPending));
 
     // test not enough funds
     let broke_amount = spendable_amount + MicroTari::from(2000);
-    let err = oms.fee_estimate(broke_amount, fee_per_gram, 1, 2).await.unwrap_err();
+    let err = oms
+        .fee_estimate(broke_amount, Some(fee_per_gram), 1, 2)
+        .await
+        .unwrap_err();
     assert!(matches!(err, OutputManagerError::NotEnoughFunds));
 
     // coin split uses the "Largest" selection strategy
@@ -512,6 +566,7 @@ async fn test_utxo_selection_with_chain_metadata() {
             "".to_string(),
             script!(Nop),
             Covenant::default(),
+            None,
         )
         .await
         .unwrap_err();
@@ -532,7 +587,7 @@ async fn test_utxo_selection_with_chain_metadata() {
     assert_eq!(utxos.len(), 10);
 
     // test fee estimates
-    let fee = oms.fee_estimate(amount, fee_per_gram, 1, 2).await.unwrap();
+    let fee = oms.fee_estimate(amount, Some(fee_per_gram), 1, 2).await.unwrap();
     let expected_fee = fee_calc.calculate(fee_per_gram, 1, 2, 3, default_metadata_byte_size() * 3);
     assert_eq!(fee, expected_fee);
 
@@ -540,7 +595,7 @@ async fn test_utxo_selection_with_chain_metadata() {
     // even though we have utxos for the fee, they can't be spent because they are not mature yet
     let spendable_amount = (1..=6).sum::<u64>() * amount;
     let err = oms
-        .fee_estimate(spendable_amount, fee_per_gram, 1, 2)
+        .fee_estimate(spendable_amount, Some(fee_per_gram), 1, 2)
         .await
         .unwrap_err();
     assert!(matches!(err, OutputManagerError::NotEnoughFunds));
@@ -569,6 +624,7 @@ async fn test_utxo_selection_with_chain_metadata() {
             "".to_string(),
             script!(Nop),
             Covenant::default(),
+            None,
         )
         .await
         .unwrap();
@@ -596,6 +652,7 @@ async fn test_utxo_selection_with_chain_metadata() {
             "".to_string(),
             script!(Nop),
             Covenant::default(),
+            None,
         )
         .await
         .unwrap();
@@ -660,6 +717,7 @@ async fn test_utxo_selection_with_tx_priority() {
             "".to_string(),
             script!(Nop),
             Covenant::default(),
+            None,
         )
         .await
         .unwrap();
@@ -672,6 +730,259 @@ async fn test_utxo_selection_with_tx_priority() {
     assert!(!utxos[0].features.flags.contains(OutputFlags::COINBASE_OUTPUT));
 }
 
+#[tokio::test]
+async fn test_utxo_selection_branch_and_bound_and_coin_control() {
+    let factories = CryptoFactories::default();
+    let (connection, _tempdir) = get_temp_sqlite_database_connection();
+
+    let server_node_identity = build_node_identity(PeerFeatures::COMMUNICATION_NODE);
+    let (mut oms, _shutdown, _, _, _) = setup_oms_with_bn_state(
+        OutputManagerSqliteDatabase::new(connection, None),
+        Some(6),
+        server_node_identity,
+    )
+    .await;
+
+    let fee_per_gram = MicroTari::from(2);
+    let fee_calc = Fee::new(*create_consensus_constants(0).transaction_weight());
+    let mut commitments = Vec::new();
+    for value in [2000u64, 3000u64, 5000u64] {
+        let (_, uo) = make_input(&mut OsRng.clone(), MicroTari::from(value), &factories.commitment);
+        commitments.push(uo.as_transaction_output(&factories).unwrap().commitment);
+        oms.add_output(uo, None).await.unwrap();
+    }
+
+    // Branch and bound finds the single output that covers the amount and fee without needing change
+    let amount = MicroTari::from(1990) - fee_calc.calculate(fee_per_gram, 1, 1, 1, default_metadata_byte_size());
+    oms.prepare_transaction_to_send(
+        TxId::new_random(),
+        amount,
+        None,
+        None,
+        fee_per_gram,
+        None,
+        "".to_string(),
+        script!(Nop),
+        Covenant::default(),
+        Some(UTXOSelectionStrategy::BranchAndBound),
+    )
+    .await
+    .unwrap();
+    let utxos = oms.get_unspent_outputs().await.unwrap();
+    assert_eq!(utxos.len(), 2);
+    assert!(utxos.iter().all(|o| o.value != MicroTari::from(2000)));
+    let balance = oms.get_balance().await.unwrap();
+    assert_eq!(balance.pending_incoming_balance, MicroTari::from(0));
+
+    // Coin control spends exactly the requested output
+    oms.prepare_transaction_to_send(
+        TxId::new_random(),
+        MicroTari::from(1000),
+        None,
+        None,
+        fee_per_gram,
+        None,
+        "".to_string(),
+        script!(Nop),
+        Covenant::default(),
+        Some(UTXOSelectionStrategy::Specific(vec![commitments[1].clone()])),
+    )
+    .await
+    .unwrap();
+    let utxos = oms.get_unspent_outputs().await.unwrap();
+    assert_eq!(utxos.len(), 1);
+    assert_eq!(utxos[0].value, MicroTari::from(5000));
+
+    // An output that is no longer unspent cannot be selected
+    let err = oms
+        .prepare_transaction_to_send(
+            TxId::new_random(),
+            MicroTari::from(1000),
+            None,
+            None,
+            fee_per_gram,
+            None,
+            "".to_string(),
+            script!(Nop),
+            Covenant::default(),
+            Some(UTXOSelectionStrategy::Specific(vec![commitments[1].clone()])),
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(err, OutputManagerError::SpecifiedOutputsUnavailable));
+
+    // The selected outputs must cover the amount on their own
+    let err = oms
+        .prepare_transaction_to_send(
+            TxId::new_random(),
+            MicroTari::from(6000),
+            None,
+            None,
+            fee_per_gram,
+            None,
+            "".to_string(),
+            script!(Nop),
+            Covenant::default(),
+            Some(UTXOSelectionStrategy::Specific(vec![commitments[2].clone()])),
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(err, OutputManagerError::NotEnoughFunds));
+}
+
+#[tokio::test]
+async fn test_utxo_consolidation() {
+    let factories = CryptoFactories::default();
+    let (connection, _tempdir) = get_temp_sqlite_database_connection();
+
+    let server_node_identity = build_node_identity(PeerFeatures::COMMUNICATION_NODE);
+    let (mut oms, _shutdown, _, _, _) = setup_oms_with_bn_state(
+        OutputManagerSqliteDatabase::new(connection, None),
+        Some(6),
+        server_node_identity,
+    )
+    .await;
+
+    for value in [1u64, 2000u64, 3000u64, 5000u64, 100_000u64] {
+        let (_, uo) = make_input(&mut OsRng.clone(), MicroTari::from(value), &factories.commitment);
+        oms.add_output(uo, None).await.unwrap();
+    }
+
+    // The dust output is not worth spending and the large output is above the maximum value
+    let fee_per_gram = MicroTari::from(2);
+    let (consolidation, tx) = oms
+        .create_consolidation_transaction(fee_per_gram, 10, Some(MicroTari::from(5000)))
+        .await
+        .unwrap();
+    assert_eq!(consolidation.num_inputs, 3);
+    assert_eq!(tx.body.inputs().len(), 3);
+    assert_eq!(tx.body.outputs().len(), 1);
+    assert_eq!(consolidation.amount + consolidation.fee, MicroTari::from(10_000));
+    assert_eq!(tx.body.get_total_fee(), consolidation.fee);
+
+    let utxos = oms.get_unspent_outputs().await.unwrap();
+    assert_eq!(utxos.len(), 2);
+    let balance = oms.get_balance().await.unwrap();
+    assert_eq!(balance.pending_incoming_balance, consolidation.amount);
+
+    let history = oms.get_consolidation_history().await.unwrap();
+    assert_eq!(history, vec![consolidation]);
+
+    let err = oms
+        .create_consolidation_transaction(fee_per_gram, 10, Some(MicroTari::from(5000)))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, OutputManagerError::NoUtxosToConsolidate));
+}
+
+#[tokio::test]
+async fn test_account_transfer() {
+    let factories = CryptoFactories::default();
+    let (connection, _tempdir) = get_temp_sqlite_database_connection();
+
+    let server_node_identity = build_node_identity(PeerFeatures::COMMUNICATION_NODE);
+    let (mut oms, _shutdown, _, _, _) = setup_oms_with_bn_state(
+        OutputManagerSqliteDatabase::new(connection, None),
+        Some(6),
+        server_node_identity,
+    )
+    .await;
+
+    let (_, uo) = make_input(&mut OsRng.clone(), MicroTari::from(100_000), &factories.commitment);
+    oms.add_output(uo, None).await.unwrap();
+
+    let savings = oms.create_account("savings".to_string()).await.unwrap();
+    let err = oms.create_account("savings".to_string()).await.unwrap_err();
+    assert!(matches!(err, OutputManagerError::AccountAlreadyExists(_)));
+    let accounts = oms.get_accounts().await.unwrap();
+    assert_eq!(accounts.len(), 2);
+    assert_eq!(accounts[0].name, "default");
+    assert_eq!(accounts[1], savings);
+
+    let mut savings_oms = oms.for_account(savings.id);
+    assert_eq!(
+        savings_oms.get_balance().await.unwrap().available_balance,
+        MicroTari::from(0)
+    );
+
+    let tx_id = TxId::new_random();
+    let (fee, tx) = oms
+        .create_account_transfer(
+            tx_id,
+            savings.id,
+            MicroTari::from(20_000),
+            MicroTari::from(2),
+            "To savings".to_string(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(tx.body.outputs().len(), 2);
+
+    let savings_balance = savings_oms.get_balance().await.unwrap();
+    assert_eq!(savings_balance.pending_incoming_balance, MicroTari::from(20_000));
+    let default_balance = oms.get_balance().await.unwrap();
+    assert_eq!(default_balance.available_balance, MicroTari::from(0));
+    assert_eq!(
+        default_balance.pending_incoming_balance,
+        MicroTari::from(100_000) - MicroTari::from(20_000) - fee
+    );
+    assert_eq!(savings_oms.get_account_tx_ids().await.unwrap(), vec![tx_id]);
+
+    let err = oms.for_account(42).get_balance().await.unwrap_err();
+    assert!(matches!(err, OutputManagerError::AccountNotFound(42)));
+}
+
+#[tokio::test]
+async fn test_recover_account_outputs() {
+    let factories = CryptoFactories::default();
+    let (connection, _tempdir) = get_temp_sqlite_database_connection();
+    let (mut oms, _, _shutdown, _, _, _, _, _) =
+        setup_output_manager_service(OutputManagerSqliteDatabase::new(connection, None), true).await;
+
+    let (_, uo) = make_input(&mut OsRng.clone(), MicroTari::from(100_000), &factories.commitment);
+    oms.add_output(uo, None).await.unwrap();
+    let savings = oms.create_account("savings".to_string()).await.unwrap();
+    let tx_id = TxId::new_random();
+    let (fee, tx) = oms
+        .create_account_transfer(
+            tx_id,
+            savings.id,
+            MicroTari::from(20_000),
+            MicroTari::from(2),
+            "To savings".to_string(),
+        )
+        .await
+        .unwrap();
+
+    // A wallet restored from the same seed finds the account output and rediscovers the account
+    let (connection, _tempdir2) = get_temp_sqlite_database_connection();
+    let (mut recovered_oms, _, _shutdown2, _, _, _, _, _) =
+        setup_output_manager_service(OutputManagerSqliteDatabase::new(connection, None), true).await;
+    let recovered = recovered_oms
+        .scan_for_recoverable_outputs(tx.body.outputs().clone(), tx_id)
+        .await
+        .unwrap();
+    assert_eq!(recovered.len(), 2);
+
+    let accounts = recovered_oms.get_accounts().await.unwrap();
+    assert_eq!(accounts.len(), 2);
+    assert_eq!(accounts[1].id, savings.id);
+    assert_eq!(accounts[1].key_index, 1);
+    assert_eq!(
+        recovered_oms
+            .for_account(savings.id)
+            .get_balance()
+            .await
+            .unwrap()
+            .available_balance,
+        MicroTari::from(20_000)
+    );
+    assert_eq!(
+        recovered_oms.get_balance().await.unwrap().available_balance,
+        MicroTari::from(100_000) - MicroTari::from(20_000) - fee
+    );
+}
+
 #[tokio::test]
 async fn send_not_enough_funds() {
     let factories = CryptoFactories::default();
@@ -701,6 +1012,7 @@ async fn send_not_enough_funds() {
             "".to_string(),
             script!(Nop),
             Covenant::default(),
+            None,
         )
         .await
     {
@@ -756,6 +1068,7 @@ async fn send_no_change() {
             "".to_string(),
             script!(Nop),
             Covenant::default(),
+            None,
         )
         .await
         .unwrap();
@@ -812,6 +1125,7 @@ async fn send_not_enough_for_change() {
             "".to_string(),
             script!(Nop),
             Covenant::default(),
+            None,
         )
         .await
     {
@@ -849,6 +1163,7 @@ async fn cancel_transaction() {
             "".to_string(),
             script!(Nop),
             Covenant::default(),
+            None,
         )
         .await
         .unwrap();
@@ -928,6 +1243,7 @@ async fn test_get_balance() {
             "".to_string(),
             script!(Nop),
             Covenant::default(),
+            None,
         )
         .await
         .unwrap();
@@ -975,6 +1291,7 @@ async fn sending_transaction_with_short_term_clear() {
             "".to_string(),
             script!(Nop),
             Covenant::default(),
+            None,
         )
         .await
         .unwrap();
@@ -1003,6 +1320,7 @@ async fn sending_transaction_with_short_term_clear() {
             "".to_string(),
             script!(Nop),
             Covenant::default(),
+            None,
         )
         .await
         .unwrap();
@@ -1275,6 +1593,7 @@ async fn test_txo_validation() {
         "".to_string(),
         script!(Nop),
         Covenant::default(),
+        None,
     )
     .await
     .unwrap();
@@ -1842,7 +2161,7 @@ async fn test_oms_key_manager_discrepancy() {
         shutdown.to_signal(),
         basenode_service_handle.clone(),
         wallet_connectivity.clone(),
-        master_seed1.clone(),
+        master_seed1.clone().into(),
         server_node_identity,
     )
     .await
@@ -1863,7 +2182,7 @@ async fn test_oms_key_manager_discrepancy() {
         shutdown.to_signal(),
         basenode_service_handle.clone(),
         wallet_connectivity.clone(),
-        master_seed1,
+        master_seed1.into(),
         server_node_identity2,
     )
     .await
@@ -1883,7 +2202,7 @@ async fn test_oms_key_manager_discrepancy() {
         shutdown.to_signal(),
         basenode_service_handle,
         wallet_connectivity,
-        master_seed2,
+        master_seed2.into(),
         server_node_identity3,
     )
     .await;
@@ -1894,6 +2213,99 @@ async fn test_oms_key_manager_discrepancy() {
     ));
 }
 
+#[tokio::test]
+async fn test_watch_only_oms() {
+    let shutdown = Shutdown::new();
+    let factories = CryptoFactories::default();
+    let (oms_request_sender, oms_request_receiver) = reply_channel::unbounded();
+
+    let (oms_event_publisher, _) = broadcast::channel(200);
+    let constants = create_consensus_constants(0);
+    let (sender, receiver_bns) = reply_channel::unbounded();
+    let (event_publisher_bns, _) = broadcast::channel(100);
+
+    let basenode_service_handle = BaseNodeServiceHandle::new(sender, event_publisher_bns);
+    let mut mock_base_node_service = MockBaseNodeService::new(receiver_bns, shutdown.to_signal());
+    mock_base_node_service.set_default_base_node_state();
+    task::spawn(mock_base_node_service.run());
+
+    let (connection, _tempdir) = get_temp_sqlite_database_connection();
+    let db = OutputManagerDatabase::new(OutputManagerSqliteDatabase::new(connection, None));
+
+    let (_, spend_public_key) = PublicKey::random_keypair(&mut OsRng);
+    let watch_only_keys = WatchOnlyKeys::new(
+        PrivateKey::random(&mut OsRng),
+        PrivateKey::random(&mut OsRng),
+        Some(spend_public_key.clone()),
+    );
+    let output_manager_service = OutputManagerService::new(
+        OutputManagerServiceConfig::default(),
+        oms_request_receiver,
+        db,
+        oms_event_publisher.clone(),
+        factories.clone(),
+        constants,
+        shutdown.to_signal(),
+        basenode_service_handle,
+        create_wallet_connectivity_mock(),
+        watch_only_keys.clone().into(),
+        build_node_identity(PeerFeatures::COMMUNICATION_CLIENT),
+    )
+    .await
+    .unwrap();
+    let mut oms = OutputManagerHandle::new(oms_request_sender, oms_event_publisher);
+    task::spawn(async move { output_manager_service.start().await.unwrap() });
+
+    assert_eq!(oms.get_watch_only_keys().await.unwrap(), watch_only_keys);
+
+    let (_, uo) = make_input(&mut OsRng, MicroTari::from(10_000), &factories.commitment);
+    oms.add_output(uo.clone(), None).await.unwrap();
+    let balance = oms.get_balance().await.unwrap();
+    assert_eq!(balance.available_balance, MicroTari::from(10_000));
+
+    let err = oms.get_seed_words().await.unwrap_err();
+    assert!(matches!(err, OutputManagerError::WatchOnlyWallet));
+    let err = oms
+        .prepare_transaction_to_send(
+            TxId::new_random(),
+            MicroTari::from(1_000),
+            None,
+            None,
+            MicroTari::from(5),
+            None,
+            "".to_string(),
+            script!(Nop),
+            Covenant::default(),
+            None,
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(err, OutputManagerError::WatchOnlyWallet));
+
+    let template = oms
+        .create_unsigned_transaction_template(
+            MicroTari::from(1_000),
+            MicroTari::from(5),
+            None,
+            "cold storage".to_string(),
+            PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
+            Covenant::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(template.inputs.len(), 1);
+    assert_eq!(
+        template.inputs[0].commitment,
+        uo.as_transaction_output(&factories).unwrap().commitment
+    );
+    assert_eq!(template.total_input_value(), MicroTari::from(10_000));
+    assert!(template.requires_change_output());
+    assert_eq!(template.spend_public_key, Some(spend_public_key));
+    // The inputs are not encumbered by creating a template
+    let balance = oms.get_balance().await.unwrap();
+    assert_eq!(balance.available_balance, MicroTari::from(10_000));
+}
+
 #[tokio::test]
 async fn test_get_status_by_tx_id() {
     let factories = CryptoFactories::default();
@@ -47,6 +47,8 @@ use tari_core::{
             FetchUtxosResponse,
             QueryDeletedRequest,
             QueryDeletedResponse,
+            SearchUtxosByScriptRequest,
+            SearchUtxosBySenderOffsetPublicKeyRequest,
             Signatures as SignaturesProto,
             SyncUtxosByBlockRequest,
             SyncUtxosByBlockResponse,
@@ -107,6 +109,7 @@ pub struct BaseNodeWalletRpcMockState {
     blocks: Arc<Mutex<HashMap<u64, BlockHeader>>>,
     utxos_by_block: Arc<Mutex<Vec<UtxosByBlock>>>,
     sync_utxos_by_block_trigger_channel: Arc<Mutex<Option<mpsc::Receiver<usize>>>>,
+    fee_per_gram_estimate: Arc<Mutex<u64>>,
 }
 
 #[allow(clippy::mutex_atomic)]
@@ -170,6 +173,7 @@ impl BaseNodeWalletRpcMockState {
             blocks: Arc::new(Mutex::new(Default::default())),
             utxos_by_block: Arc::new(Mutex::new(vec![])),
             sync_utxos_by_block_trigger_channel: Arc::new(Mutex::new(None)),
+            fee_per_gram_estimate: Arc::new(Mutex::new(0)),
         }
     }
 
@@ -193,6 +197,11 @@ impl BaseNodeWalletRpcMockState {
         *lock = response;
     }
 
+    pub fn set_fee_per_gram_estimate(&self, fee_per_gram: u64) {
+        let mut lock = acquire_lock!(self.fee_per_gram_estimate);
+        *lock = fee_per_gram;
+    }
+
     pub fn set_utxo_query_response(&self, response: UtxoQueryResponses) {
         let mut lock = acquire_lock!(self.utxo_query_response);
         *lock = response;
@@ -813,6 +822,32 @@ impl BaseNodeWalletService for BaseNodeWalletRpcMockService {
             Err(RpcStatus::not_found("Headers not found"))
         }
     }
+
+    async fn search_utxos_by_script(
+        &self,
+        _request: Request<SearchUtxosByScriptRequest>,
+    ) -> Result<Response<UtxoQueryResponses>, RpcStatus> {
+        let lock = acquire_lock!(self.state.utxo_query_response);
+        Ok(Response::new(lock.clone()))
+    }
+
+    async fn search_utxos_by_sender_offset_public_key(
+        &self,
+        _request: Request<SearchUtxosBySenderOffsetPublicKeyRequest>,
+    ) -> Result<Response<UtxoQueryResponses>, RpcStatus> {
+        let lock = acquire_lock!(self.state.utxo_query_response);
+        Ok(Response::new(lock.clone()))
+    }
+
+    async fn estimate_fee_per_gram(
+        &self,
+        _request: Request<u64>,
+    ) -> Result<Response<proto::mempool::FeePerGramEstimateResponse>, RpcStatus> {
+        let lock = acquire_lock!(self.state.fee_per_gram_estimate);
+        Ok(Response::new(proto::mempool::FeePerGramEstimateResponse {
+            fee_per_gram: *lock,
+        }))
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -100,7 +100,7 @@ use tari_p2p::{comms_connector::pubsub_connector, domain_message::DomainMessage,
 use tari_service_framework::{reply_channel, RegisterHandle, StackBuilder};
 use tari_shutdown::{Shutdown, ShutdownSignal};
 use tari_test_utils::random;
-use tari_utilities::Hashable;
+use tari_utilities::{message_format::MessageFormat, Hashable};
 use tari_wallet::{
     base_node_service::{
         config::BaseNodeServiceConfig,
@@ -124,6 +124,7 @@ use tari_wallet::{
             models::KnownOneSidedPaymentScript,
             sqlite_db::OutputManagerSqliteDatabase,
         },
+        watch_only::{OfflineSignedTransaction, UnsignedTransactionTemplate, WalletKeySource},
         OutputManagerServiceInitializer,
     },
     storage::{
@@ -135,11 +136,27 @@ use tari_wallet::{
     transaction_service::{
         config::TransactionServiceConfig,
         error::TransactionServiceError,
-        handle::{TransactionEvent, TransactionServiceHandle},
+        handle::{BatchPaymentRecipient, ScheduledPaymentRequest, TransactionEvent, TransactionServiceHandle},
+        multisig::{
+            multisig_script,
+            verify_key_share,
+            KeySharePolynomial,
+            MultisigMessage,
+            MultisigPayload,
+            MultisigSessionKeys,
+        },
         service::TransactionService,
         storage::{
             database::{DbKeyValuePair, TransactionBackend, TransactionDatabase, WriteOperation},
-            models::{CompletedTransaction, InboundTransaction, OutboundTransaction, WalletTransaction},
+            models::{
+                CompletedTransaction,
+                InboundTransaction,
+                MultisigSessionStatus,
+                OutboundTransaction,
+                PaymentTrigger,
+                ScheduledPaymentStatus,
+                WalletTransaction,
+            },
             sqlite_db::TransactionServiceSqliteDatabase,
         },
         TransactionServiceInitializer,
@@ -182,6 +199,35 @@ pub fn setup_transaction_service<P: AsRef<Path>>(
     OutputManagerHandle,
     CommsNode,
     WalletConnectivityHandle,
+) {
+    setup_transaction_service_with_key_source(
+        runtime,
+        node_identity,
+        peers,
+        factories,
+        db_connection,
+        database_path,
+        discovery_request_timeout,
+        shutdown_signal,
+        CipherSeed::new().into(),
+    )
+}
+
+pub fn setup_transaction_service_with_key_source<P: AsRef<Path>>(
+    runtime: &mut Runtime,
+    node_identity: Arc<NodeIdentity>,
+    peers: Vec<Arc<NodeIdentity>>,
+    factories: CryptoFactories,
+    db_connection: WalletDbConnection,
+    database_path: P,
+    discovery_request_timeout: Duration,
+    shutdown_signal: ShutdownSignal,
+    key_source: WalletKeySource,
+) -> (
+    TransactionServiceHandle,
+    OutputManagerHandle,
+    CommsNode,
+    WalletConnectivityHandle,
 ) {
     let _enter = runtime.enter();
     let (publisher, subscription_factory) = pubsub_connector(100, 20);
@@ -211,7 +257,7 @@ pub fn setup_transaction_service<P: AsRef<Path>>(
             oms_backend,
             factories.clone(),
             Network::Weatherwax.into(),
-            CipherSeed::new(),
+            key_source,
             comms.node_identity(),
         ))
         .add_initializer(TransactionServiceInitializer::new(
@@ -257,6 +303,7 @@ pub struct TransactionServiceNoCommsInterface {
     transaction_finalize_message_channel: Sender<DomainMessage<proto::TransactionFinalizedMessage>>,
     _base_node_response_message_channel: Sender<DomainMessage<base_node_proto::BaseNodeServiceResponse>>,
     transaction_cancelled_message_channel: Sender<DomainMessage<proto::TransactionCancelledMessage>>,
+    multisig_message_channel: Sender<DomainMessage<proto::MultisigMessage>>,
     _shutdown: Shutdown,
     _mock_rpc_server: MockRpcServer<BaseNodeWalletRpcServer<BaseNodeWalletRpcMockService>>,
     base_node_identity: Arc<NodeIdentity>,
@@ -288,6 +335,7 @@ pub fn setup_transaction_service_no_comms(
     let (transaction_finalize_message_channel, tx_finalized_receiver) = mpsc::channel(20);
     let (base_node_response_message_channel, base_node_response_receiver) = mpsc::channel(20);
     let (transaction_cancelled_message_channel, tx_cancelled_receiver) = mpsc::channel(20);
+    let (multisig_message_channel, multisig_receiver) = mpsc::channel(20);
 
     let outbound_service_mock_state = mock_outbound_service.get_state();
     runtime.spawn(mock_outbound_service.run());
@@ -351,7 +399,7 @@ pub fn setup_transaction_service_no_comms(
             shutdown.to_signal(),
             base_node_service_handle.clone(),
             wallet_connectivity_service_mock.clone(),
-            CipherSeed::new(),
+            CipherSeed::new().into(),
             base_node_identity.clone(),
         ))
         .unwrap();
@@ -383,6 +431,7 @@ pub fn setup_transaction_service_no_comms(
         tx_finalized_receiver,
         base_node_response_receiver,
         tx_cancelled_receiver,
+        multisig_receiver,
         output_manager_service_handle.clone(),
         outbound_message_requester,
         wallet_connectivity_service_mock.clone(),
@@ -403,6 +452,7 @@ pub fn setup_transaction_service_no_comms(
         transaction_finalize_message_channel,
         _base_node_response_message_channel: base_node_response_message_channel,
         transaction_cancelled_message_channel,
+        multisig_message_channel,
         _shutdown: shutdown,
         _mock_rpc_server: mock_rpc_server,
         base_node_identity,
@@ -802,6 +852,152 @@ fn send_one_sided_transaction_to_other() {
     });
 }
 
+#[test]
+fn sign_and_broadcast_offline_transaction() {
+    let mut runtime = create_runtime();
+
+    let factories = CryptoFactories::default();
+    // The offline signer holds the seed, the online wallet only the watch-only keys
+    let signer_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+    let watch_only_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+    let bob_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+
+    let shutdown = Shutdown::new();
+    let signer_temp_dir = tempdir().unwrap();
+    let signer_database_path = signer_temp_dir.path().to_str().unwrap().to_string();
+    let (signer_db_connection, _signer_tempdir) = make_wallet_database_connection(Some(signer_database_path.clone()));
+    let (mut signer_ts, mut signer_oms, _signer_comms, _signer_connectivity) =
+        setup_transaction_service_with_key_source(
+            &mut runtime,
+            signer_node_identity.clone(),
+            vec![],
+            factories.clone(),
+            signer_db_connection,
+            signer_database_path,
+            Duration::from_secs(0),
+            shutdown.to_signal(),
+            CipherSeed::new().into(),
+        );
+
+    let watch_only_keys = runtime.block_on(signer_oms.get_watch_only_keys()).unwrap();
+    assert_eq!(
+        watch_only_keys.spend_public_key.as_ref(),
+        Some(signer_node_identity.public_key())
+    );
+
+    let watch_only_temp_dir = tempdir().unwrap();
+    let watch_only_database_path = watch_only_temp_dir.path().to_str().unwrap().to_string();
+    let (watch_only_db_connection, _watch_only_tempdir) =
+        make_wallet_database_connection(Some(watch_only_database_path.clone()));
+    let (mut watch_only_ts, mut watch_only_oms, _watch_only_comms, _watch_only_connectivity) =
+        setup_transaction_service_with_key_source(
+            &mut runtime,
+            watch_only_node_identity,
+            vec![],
+            factories.clone(),
+            watch_only_db_connection,
+            watch_only_database_path,
+            Duration::from_secs(0),
+            shutdown.to_signal(),
+            watch_only_keys.into(),
+        );
+
+    // An output derived from the signer's key chain, as the watch-only wallet would find it while scanning
+    let initial_wallet_value = MicroTari::from(25_000);
+    let sender_offset_private_key = PrivateKey::random(&mut OsRng);
+    let mut builder = runtime
+        .block_on(signer_oms.create_output_with_features(initial_wallet_value, OutputFeatures::default()))
+        .unwrap();
+    builder
+        .sign_as_receiver(
+            PublicKey::from_secret_key(&sender_offset_private_key),
+            PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
+        )
+        .unwrap();
+    builder.sign_as_sender(&sender_offset_private_key).unwrap();
+    let output = builder.try_build().unwrap();
+    runtime
+        .block_on(watch_only_oms.add_output(output.clone(), None))
+        .unwrap();
+
+    let message = "Signed in the vault".to_string();
+    let value = MicroTari::from(10_000);
+    let template = runtime
+        .block_on(watch_only_oms.create_unsigned_transaction_template(
+            value,
+            MicroTari::from(20),
+            None,
+            message.clone(),
+            bob_node_identity.public_key().clone(),
+            Covenant::default(),
+        ))
+        .unwrap();
+    // The template is carried to the signer as a file
+    let template = UnsignedTransactionTemplate::from_json(&template.to_json().unwrap()).unwrap();
+
+    let signed = runtime.block_on(signer_ts.sign_offline_transaction(template)).unwrap();
+    assert_eq!(signed.amount, value);
+    assert_eq!(signed.transaction.body.inputs().len(), 1);
+    assert_eq!(signed.transaction.body.outputs().len(), 2);
+    assert_eq!(
+        signed.transaction.body.inputs()[0].output_hash(),
+        output.as_transaction_output(&factories).unwrap().hash()
+    );
+
+    // A watch-only wallet can not sign the transaction itself
+    let template = runtime
+        .block_on(watch_only_oms.create_unsigned_transaction_template(
+            value,
+            MicroTari::from(20),
+            None,
+            message.clone(),
+            bob_node_identity.public_key().clone(),
+            Covenant::default(),
+        ))
+        .unwrap();
+    assert!(runtime
+        .block_on(watch_only_ts.sign_offline_transaction(template))
+        .is_err());
+
+    let signed = OfflineSignedTransaction::from_json(&signed.to_json().unwrap()).unwrap();
+    let fee = signed.fee;
+    let tx_id = runtime
+        .block_on(watch_only_ts.submit_offline_transaction(signed))
+        .unwrap();
+
+    runtime.block_on(async move {
+        let completed_tx = watch_only_ts
+            .get_completed_transaction(tx_id)
+            .await
+            .expect("Could not find the imported offline transaction");
+        assert_eq!(completed_tx.amount, value);
+        assert_eq!(completed_tx.fee, fee);
+        assert_eq!(completed_tx.message, message);
+        assert_eq!(
+            completed_tx.destination_public_key,
+            bob_node_identity.public_key().clone()
+        );
+
+        // The change is tracked as pending incoming until the transaction is mined
+        let balance = watch_only_oms.get_balance().await.unwrap();
+        assert_eq!(balance.available_balance, MicroTari::from(0));
+        assert_eq!(balance.pending_outgoing_balance, initial_wallet_value);
+        assert_eq!(balance.pending_incoming_balance, initial_wallet_value - value - fee);
+    });
+}
+
 #[test]
 fn recover_one_sided_transaction() {
     let mut runtime = create_runtime();
@@ -923,6 +1119,261 @@ fn recover_one_sided_transaction() {
     });
 }
 
+#[test]
+fn recover_one_sided_batch_transaction() {
+    let mut runtime = create_runtime();
+
+    let factories = CryptoFactories::default();
+    let alice_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+    let base_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+
+    let temp_dir = tempdir().unwrap();
+    let database_path = temp_dir.path().to_str().unwrap().to_string();
+    let (alice_connection, _tempdir) = make_wallet_database_connection(Some(database_path.clone()));
+
+    let shutdown = Shutdown::new();
+    let (mut alice_ts, mut alice_oms, _alice_comms, mut alice_connectivity) = setup_transaction_service(
+        &mut runtime,
+        alice_node_identity,
+        vec![],
+        factories.clone(),
+        alice_connection,
+        database_path,
+        Duration::from_secs(0),
+        shutdown.to_signal(),
+    );
+    alice_connectivity.set_base_node(base_node_identity.to_peer());
+
+    // Set up two recipients that each know the script of their own one-sided payments
+    let mut recipients = Vec::new();
+    let mut recipient_oms = Vec::new();
+    let mut recipient_dirs = Vec::new();
+    for value in [1000u64, 1500u64] {
+        let node_identity = Arc::new(NodeIdentity::random(
+            &mut OsRng,
+            get_next_memory_address(),
+            PeerFeatures::COMMUNICATION_NODE,
+        ));
+        let temp_dir = tempdir().unwrap();
+        let database_path = temp_dir.path().to_str().unwrap().to_string();
+        let (connection, db_dir) = make_wallet_database_connection(Some(database_path.clone()));
+        let (_ts, mut oms, _comms, _connectivity) = setup_transaction_service(
+            &mut runtime,
+            node_identity.clone(),
+            vec![],
+            factories.clone(),
+            connection,
+            database_path,
+            Duration::from_secs(0),
+            shutdown.to_signal(),
+        );
+        let script = script!(PushPubKey(Box::new(node_identity.public_key().clone())));
+        let known_script = KnownOneSidedPaymentScript {
+            script_hash: script.as_hash::<Blake256>().unwrap().to_vec(),
+            private_key: node_identity.secret_key().clone(),
+            script,
+            input: ExecutionStack::default(),
+            script_lock_height: 0,
+        };
+        runtime.block_on(oms.add_known_script(known_script)).unwrap();
+        recipients.push(BatchPaymentRecipient {
+            destination: node_identity.public_key().clone(),
+            amount: MicroTari::from(value),
+            message: format!("Payment of {}", value),
+        });
+        recipient_oms.push(oms);
+        recipient_dirs.push((temp_dir, db_dir));
+    }
+
+    let (_utxo, uo1) = make_input(&mut OsRng, 5000.into(), &factories.commitment);
+    runtime.block_on(alice_oms.add_output(uo1, None)).unwrap();
+
+    runtime.block_on(async move {
+        let tx_id = alice_ts
+            .send_one_sided_batch_transaction(recipients.clone(), 20.into(), "Payroll".to_string())
+            .await
+            .expect("Alice sending one-sided batch tx");
+        let completed_tx = alice_ts
+            .get_completed_transaction(tx_id)
+            .await
+            .expect("Could not find completed one-sided batch tx");
+        assert_eq!(completed_tx.amount, MicroTari::from(2500));
+        assert_eq!(completed_tx.destination_public_key, recipients[0].destination);
+        assert_eq!(completed_tx.message, "Payroll");
+        let payments = alice_ts.get_batch_payments(tx_id).await.unwrap();
+        assert_eq!(payments.len(), recipients.len());
+        for (payment, recipient) in payments.iter().zip(recipients.iter()) {
+            assert_eq!(payment.tx_id, tx_id);
+            assert_eq!(payment.destination, recipient.destination);
+            assert_eq!(payment.amount, recipient.amount);
+            assert_eq!(payment.message, recipient.message);
+        }
+        // One output for each recipient plus the change
+        let outputs = completed_tx.transaction.body.outputs().clone();
+        assert_eq!(outputs.len(), 3);
+
+        for (oms, recipient) in recipient_oms.iter_mut().zip(recipients.iter()) {
+            let unblinded = oms
+                .scan_outputs_for_one_sided_payments(outputs.clone(), TxId::new_random())
+                .await
+                .unwrap();
+            assert_eq!(1, unblinded.len());
+            assert_eq!(recipient.amount, unblinded[0].value);
+        }
+    });
+}
+
+#[test]
+fn test_time_locked_and_scheduled_payments() {
+    let mut runtime = create_runtime();
+
+    let factories = CryptoFactories::default();
+    let alice_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+    let bob_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+    let base_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+
+    let temp_dir = tempdir().unwrap();
+    let temp_dir2 = tempdir().unwrap();
+    let database_path = temp_dir.path().to_str().unwrap().to_string();
+    let database_path2 = temp_dir2.path().to_str().unwrap().to_string();
+    let (alice_connection, _tempdir) = make_wallet_database_connection(Some(database_path.clone()));
+    let (bob_connection, _tempdir2) = make_wallet_database_connection(Some(database_path2.clone()));
+
+    let shutdown = Shutdown::new();
+    let (mut alice_ts, mut alice_oms, _alice_comms, mut alice_connectivity) = setup_transaction_service(
+        &mut runtime,
+        alice_node_identity,
+        vec![],
+        factories.clone(),
+        alice_connection,
+        database_path,
+        Duration::from_secs(0),
+        shutdown.to_signal(),
+    );
+    let (_bob_ts, mut bob_oms, _bob_comms, _bob_connectivity) = setup_transaction_service(
+        &mut runtime,
+        bob_node_identity.clone(),
+        vec![],
+        factories.clone(),
+        bob_connection,
+        database_path2,
+        Duration::from_secs(0),
+        shutdown.to_signal(),
+    );
+    alice_connectivity.set_base_node(base_node_identity.to_peer());
+
+    let script = script!(PushPubKey(Box::new(bob_node_identity.public_key().clone())));
+    let known_script = KnownOneSidedPaymentScript {
+        script_hash: script.as_hash::<Blake256>().unwrap().to_vec(),
+        private_key: bob_node_identity.secret_key().clone(),
+        script,
+        input: ExecutionStack::default(),
+        script_lock_height: 0,
+    };
+    runtime.block_on(bob_oms.add_known_script(known_script)).unwrap();
+
+    let (_utxo, uo1) = make_input(&mut OsRng, 2500.into(), &factories.commitment);
+    runtime.block_on(alice_oms.add_output(uo1, None)).unwrap();
+
+    runtime.block_on(async move {
+        // The recipient's output of a time-locked payment cannot be spent before the lock height
+        let tx_id = alice_ts
+            .send_time_locked_transaction(
+                bob_node_identity.public_key().clone(),
+                1000.into(),
+                20.into(),
+                "Locked".to_string(),
+                5000,
+            )
+            .await
+            .expect("Alice sending time-locked tx to Bob");
+        let completed_tx = alice_ts.get_completed_transaction(tx_id).await.unwrap();
+        let unblinded = bob_oms
+            .scan_outputs_for_one_sided_payments(completed_tx.transaction.body.outputs().clone(), TxId::new_random())
+            .await
+            .unwrap();
+        assert_eq!(1, unblinded.len());
+        assert_eq!(unblinded[0].value, MicroTari::from(1000));
+        assert_eq!(unblinded[0].features.maturity, 5000);
+
+        let request = ScheduledPaymentRequest {
+            destination: bob_node_identity.public_key().clone(),
+            amount: 500.into(),
+            fee_per_gram: 20.into(),
+            message: "Rent".to_string(),
+            one_sided: false,
+            recipient_lock_height: None,
+            first_payment: PaymentTrigger::Height(1000),
+            repeat_interval: Some(100),
+            num_payments: Some(3),
+        };
+
+        // Only one-sided payments can be time locked
+        let mut invalid = request.clone();
+        invalid.recipient_lock_height = Some(2000);
+        assert!(matches!(
+            alice_ts.schedule_payment(invalid).await,
+            Err(TransactionServiceError::InvalidScheduledPayment(_))
+        ));
+        // Recurring payments need a repeat interval
+        let mut invalid = request.clone();
+        invalid.repeat_interval = None;
+        assert!(matches!(
+            alice_ts.schedule_payment(invalid).await,
+            Err(TransactionServiceError::InvalidScheduledPayment(_))
+        ));
+
+        // The repeat interval must not overflow the next payment
+        let mut invalid = request.clone();
+        invalid.first_payment = PaymentTrigger::Time(Utc::now().naive_utc());
+        invalid.repeat_interval = Some(u64::MAX);
+        assert!(matches!(
+            alice_ts.schedule_payment(invalid).await,
+            Err(TransactionServiceError::InvalidScheduledPayment(_))
+        ));
+
+        let id = alice_ts.schedule_payment(request).await.unwrap();
+        let payments = alice_ts.get_scheduled_payments().await.unwrap();
+        assert_eq!(payments.len(), 1);
+        assert_eq!(payments[0].id, id);
+        assert_eq!(payments[0].next_payment, PaymentTrigger::Height(1000));
+        assert_eq!(payments[0].remaining_payments, Some(3));
+        assert_eq!(payments[0].status, ScheduledPaymentStatus::Scheduled);
+
+        alice_ts.cancel_scheduled_payment(id).await.unwrap();
+        let payments = alice_ts.get_scheduled_payments().await.unwrap();
+        assert_eq!(payments[0].status, ScheduledPaymentStatus::Cancelled);
+        assert!(matches!(
+            alice_ts.cancel_scheduled_payment(id).await,
+            Err(TransactionServiceError::ScheduledPaymentNotActive(_))
+        ));
+        assert!(matches!(
+            alice_ts.cancel_scheduled_payment(id.wrapping_add(1)).await,
+            Err(TransactionServiceError::ScheduledPaymentNotFound(_))
+        ));
+    });
+}
+
 #[test]
 fn test_htlc_send_and_claim() {
     let mut runtime = create_runtime();
@@ -1524,6 +1975,7 @@ fn finalize_tx_with_incorrect_pubkey() {
                     "".to_string(),
                     script!(Nop),
                     Covenant::default(),
+                    None,
                 ),
         )
         .unwrap();
@@ -1645,6 +2097,7 @@ fn finalize_tx_with_missing_output() {
                     "".to_string(),
                     script!(Nop),
                     Covenant::default(),
+                    None,
                 ),
         )
         .unwrap();
@@ -5510,3 +5963,151 @@ fn test_update_faux_tx_on_oms_validation() {
         "Should have found the updated statuses"
     );
 }
+
+#[test]
+fn test_multisig_session_funded_after_key_shares() {
+    let factories = CryptoFactories::default();
+    let mut runtime = Runtime::new().unwrap();
+
+    let (connection, _temp_dir) = make_wallet_database_connection(None);
+    let mut alice_ts_interface = setup_transaction_service_no_comms(&mut runtime, factories.clone(), connection, None);
+    let alice_public_key = alice_ts_interface.base_node_identity.public_key().clone();
+    let bob_node_identity = build_node_identity(PeerFeatures::COMMUNICATION_NODE);
+
+    let (_utxo, uo) = make_input(&mut OsRng, MicroTari(250000), &factories.commitment);
+    runtime
+        .block_on(alice_ts_interface.output_manager_service_handle.add_output(uo, None))
+        .unwrap();
+
+    let amount = MicroTari::from(50000);
+    let session_id = runtime
+        .block_on(alice_ts_interface.transaction_service_handle.create_multisig_output(
+            vec![bob_node_identity.public_key().clone()],
+            2,
+            amount,
+            MicroTari::from(5),
+            "multisig".to_string(),
+        ))
+        .unwrap();
+
+    // The proposal is sent directly and via store-and-forward
+    alice_ts_interface
+        .outbound_service_mock_state
+        .wait_call_count(2, Duration::from_secs(10))
+        .unwrap();
+    let (_, body) = alice_ts_interface.outbound_service_mock_state.pop_call().unwrap();
+    let envelope_body = EnvelopeBody::decode(body.to_vec().as_slice()).unwrap();
+    let proposal =
+        MultisigMessage::try_from(envelope_body.decode_part::<proto::MultisigMessage>(1).unwrap().unwrap()).unwrap();
+    assert_eq!(proposal.session_id, session_id);
+    match proposal.payload {
+        MultisigPayload::Proposal { threshold, members, .. } => {
+            assert_eq!(threshold, 2);
+            assert_eq!(members, vec![alice_public_key, bob_node_identity.public_key().clone()]);
+        },
+        _ => panic!("Expected a multisig proposal"),
+    }
+    alice_ts_interface.outbound_service_mock_state.take_calls();
+
+    let sessions = runtime
+        .block_on(alice_ts_interface.transaction_service_handle.get_multisig_sessions())
+        .unwrap();
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0].status, MultisigSessionStatus::AwaitingKeyShares);
+
+    let bob_polynomial =
+        KeySharePolynomial::new(&PrivateKey::random(&mut OsRng), &PrivateKey::random(&mut OsRng), 2).unwrap();
+    let send_from_bob = |runtime: &mut Runtime, ts_interface: &mut TransactionServiceNoCommsInterface, payload| {
+        let message = MultisigMessage { session_id, payload };
+        // Repeated messages from store-and-forward must only be applied once
+        for _ in 0..2 {
+            runtime
+                .block_on(ts_interface.multisig_message_channel.send(create_dummy_message(
+                    proto::MultisigMessage::from(message.clone()),
+                    bob_node_identity.public_key(),
+                )))
+                .unwrap();
+        }
+    };
+    let take_messages = |ts_interface: &TransactionServiceNoCommsInterface, count| {
+        ts_interface
+            .outbound_service_mock_state
+            .wait_call_count(count, Duration::from_secs(10))
+            .unwrap();
+        ts_interface
+            .outbound_service_mock_state
+            .take_calls()
+            .into_iter()
+            .map(|(_, body)| {
+                let envelope_body = EnvelopeBody::decode(body.to_vec().as_slice()).unwrap();
+                MultisigMessage::try_from(envelope_body.decode_part::<proto::MultisigMessage>(1).unwrap().unwrap())
+                    .unwrap()
+                    .payload
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // Once Alice has Bob's commitments she shares the commitments of every member and sends Bob his key share
+    send_from_bob(
+        &mut runtime,
+        &mut alice_ts_interface,
+        MultisigPayload::Commitments(bob_polynomial.commitments()),
+    );
+    let mut commitments = None;
+    let mut bob_key_share = None;
+    for payload in take_messages(&alice_ts_interface, 4) {
+        match payload {
+            MultisigPayload::SessionCommitments(c) => commitments = Some(c),
+            MultisigPayload::KeyShare(share) => bob_key_share = Some(share),
+            _ => panic!("Expected the session commitments and a key share"),
+        }
+    }
+    let commitments = commitments.unwrap();
+    assert_eq!(commitments.len(), 2);
+    assert_eq!(commitments[1], bob_polynomial.commitments());
+    assert!(verify_key_share(&commitments[0], 1, &bob_key_share.unwrap()));
+
+    // The output is only funded once Bob has verified his key shares
+    send_from_bob(
+        &mut runtime,
+        &mut alice_ts_interface,
+        MultisigPayload::KeyShare(bob_polynomial.share(0)),
+    );
+    let sessions = runtime
+        .block_on(alice_ts_interface.transaction_service_handle.get_multisig_sessions())
+        .unwrap();
+    assert_eq!(sessions[0].status, MultisigSessionStatus::AwaitingKeyShares);
+    send_from_bob(
+        &mut runtime,
+        &mut alice_ts_interface,
+        MultisigPayload::KeySharesVerified,
+    );
+
+    // The output is funded and its details are sent to Bob
+    let messages = take_messages(&alice_ts_interface, 2);
+    let session = runtime
+        .block_on(alice_ts_interface.transaction_service_handle.get_multisig_sessions())
+        .unwrap()
+        .remove(0);
+    assert_eq!(session.status, MultisigSessionStatus::Active);
+    let output = session.output.unwrap();
+    match &messages[0] {
+        MultisigPayload::Finalized {
+            output: finalized_output,
+            ..
+        } => {
+            assert_eq!(finalized_output, &output);
+            let session_keys = MultisigSessionKeys::new(commitments, 2).unwrap();
+            assert_eq!(output.script, multisig_script(session_keys.script_public_key()));
+        },
+        _ => panic!("Expected the finalized multisig session"),
+    }
+    let funding_tx = runtime
+        .block_on(
+            alice_ts_interface
+                .transaction_service_handle
+                .get_completed_transaction(session.funding_tx_id.unwrap()),
+        )
+        .unwrap();
+    assert!(funding_tx.transaction.body.outputs().contains(&output));
+}
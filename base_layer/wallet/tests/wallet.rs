@@ -136,6 +136,7 @@ async fn create_wallet(
         auxilary_tcp_listener_address: None,
         datastore_path: data_path.to_path_buf(),
         peer_database_name: random::string(8),
+        database_passphrase: None,
         max_concurrent_inbound_tasks: 10,
         max_concurrent_outbound_tasks: 10,
         outbound_buffer_size: 100,
@@ -198,7 +199,7 @@ async fn create_wallet(
         output_manager_backend,
         contacts_backend,
         shutdown_signal,
-        master_seed,
+        master_seed.into(),
     )
     .await
 }
@@ -305,10 +306,7 @@ async fn test_wallet() {
     for i in 0..2 {
         let (_secret_key, public_key) = PublicKey::random_keypair(&mut OsRng);
 
-        contacts.push(Contact {
-            alias: random::string(8),
-            public_key,
-        });
+        contacts.push(Contact::new(random::string(8), public_key));
 
         alice_wallet
             .contacts_service
@@ -712,6 +710,7 @@ async fn test_import_utxo() {
         auxilary_tcp_listener_address: None,
         datastore_path: temp_dir.path().to_path_buf(),
         peer_database_name: random::string(8),
+        database_passphrase: None,
         max_concurrent_inbound_tasks: 10,
         max_concurrent_outbound_tasks: 10,
         outbound_buffer_size: 10,
@@ -745,7 +744,7 @@ async fn test_import_utxo() {
         OutputManagerSqliteDatabase::new(connection.clone(), None),
         ContactsServiceSqliteDatabase::new(connection.clone()),
         shutdown.to_signal(),
-        CipherSeed::new(),
+        CipherSeed::new().into(),
     )
     .await
     .unwrap();
@@ -1339,10 +1339,7 @@ pub unsafe extern "C" fn contact_create(
         return ptr::null_mut();
     }
 
-    let contact = Contact {
-        alias: alias_string,
-        public_key: (*public_key).clone(),
-    };
+    let contact = Contact::new(alias_string, (*public_key).clone());
     Box::into_raw(Box::new(contact))
 }
 
@@ -3024,6 +3021,7 @@ pub unsafe extern "C" fn comms_config_create(
                         auxilary_tcp_listener_address: None,
                         datastore_path,
                         peer_database_name: database_name_string,
+                        database_passphrase: None,
                         max_concurrent_inbound_tasks: 25,
                         max_concurrent_outbound_tasks: 50,
                         outbound_buffer_size: 50,
@@ -3491,7 +3489,7 @@ pub unsafe extern "C" fn wallet_create(
         output_manager_backend,
         contacts_backend,
         shutdown.to_signal(),
-        master_seed,
+        master_seed.into(),
     ));
 
     match w {
@@ -4155,7 +4153,7 @@ pub unsafe extern "C" fn wallet_send_transaction(
 /// ## Arguments
 /// `wallet` - The TariWallet pointer
 /// `amount` - The amount
-/// `fee_per_gram` - The fee per gram
+/// `fee_per_gram` - The fee per gram, or 0 to use the fee per gram estimated by the base node
 /// `num_kernels` - The number of transaction kernels
 /// `num_outputs` - The number of outputs
 /// `error_out` - Pointer to an int which will be modified to an error code should one occur, may not be null. Functions
@@ -4187,7 +4185,7 @@ pub unsafe extern "C" fn wallet_get_fee_estimate(
         .runtime
         .block_on((*wallet).wallet.output_manager_service.fee_estimate(
             MicroTari::from(amount),
-            MicroTari::from(fee_per_gram),
+            Some(MicroTari::from(fee_per_gram)).filter(|fee| *fee > MicroTari(0)),
             num_kernels as usize,
             num_outputs as usize,
         )) {
@@ -597,7 +597,7 @@ unsigned long long balance_get_pending_incoming(struct TariBalance *balance, int
 // Gets the available balance from a TariBalance
 unsigned long long balance_get_pending_outgoing(struct TariBalance *balance, int *error_out);
 
-// Get a fee estimate from a TariWallet for a given amount
+// Get a fee estimate from a TariWallet for a given amount, a fee_per_gram of 0 uses the base node estimate
 unsigned long long wallet_get_fee_estimate(struct TariWallet *wallet, unsigned long long amount, unsigned long long fee_per_gram, unsigned long long num_kernels, unsigned long long num_outputs, int *error_out);
 
 // Get the number of mining confirmations by the wallet transaction service
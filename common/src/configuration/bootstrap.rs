@@ -111,6 +111,9 @@ pub struct ConfigBootstrap {
     /// This will rebuild the db, adding block for block in
     #[structopt(long, alias = "rebuild_db")]
     pub rebuild_db: bool,
+    /// Copy the chain from the LMDB database into the database configured in `db_type`, then exit
+    #[structopt(long, alias = "migrate_db")]
+    pub migrate_db: bool,
     /// Path to input file of commands
     #[structopt(short, long, aliases = &["input", "script"], parse(from_os_str))]
     pub input_file: Option<PathBuf>,
@@ -125,6 +128,10 @@ pub struct ConfigBootstrap {
     /// possible.
     #[structopt(long, env = "TARI_WALLET_PASSWORD")]
     pub password: Option<String>,
+    /// Supply the passphrase for an encrypted peer database. As with the wallet password, prefer the env var over the
+    /// command line.
+    #[structopt(long, env = "TARI_PEER_DB_PASSPHRASE")]
+    pub peer_db_passphrase: Option<String>,
     /// Change the password for the console wallet
     #[structopt(long, alias = "update-password")]
     pub change_password: bool,
@@ -137,6 +144,9 @@ pub struct ConfigBootstrap {
     /// Supply the optional file name to save the wallet seed words into
     #[structopt(long, aliases = &["seed_words_file_name", "seed-words-file"], parse(from_os_str))]
     pub seed_words_file_name: Option<PathBuf>,
+    /// Create a watch-only wallet from the keys exported to this file by a full wallet
+    #[structopt(long, alias = "watch-only", parse(from_os_str))]
+    pub watch_only_keys_file: Option<PathBuf>,
     /// Wallet notify script
     #[structopt(long, alias = "notify")]
     pub wallet_notify: Option<PathBuf>,
@@ -187,14 +197,17 @@ impl Default for ConfigBootstrap {
             create_id: false,
             non_interactive_mode: false,
             rebuild_db: false,
+            migrate_db: false,
             input_file: None,
             command: None,
             clean_orphans_db: false,
             password: None,
+            peer_db_passphrase: None,
             change_password: false,
             recovery: false,
             seed_words: None,
             seed_words_file_name: None,
+            watch_only_keys_file: None,
             wallet_notify: None,
             command_mode_auto_exit: false,
             mine_until_height: None,
@@ -443,6 +456,7 @@ mod test {
             "--init",
             "--create-id",
             "--rebuild_db",
+            "--migrate-db",
             "--clean_orphans_db",
             "--base-path",
             "no-temp-path-created",
@@ -461,6 +475,7 @@ mod test {
         assert!(bootstrap.init);
         assert!(bootstrap.create_id);
         assert!(bootstrap.rebuild_db);
+        assert!(bootstrap.migrate_db);
         assert!(bootstrap.clean_orphans_db);
         assert_eq!(bootstrap.base_path.to_str(), Some("no-temp-path-created"));
         assert_eq!(bootstrap.log_config.to_str(), Some("no-log-config-file-created"));
@@ -23,7 +23,7 @@
 //! # Global configuration of tari base layer system
 
 use std::{
-    convert::TryInto,
+    convert::{TryFrom, TryInto},
     fmt,
     fmt::{Display, Formatter},
     net::SocketAddr,
@@ -46,6 +46,7 @@ use crate::{
         CollectiblesConfig,
         MergeMiningConfig,
         Network,
+        UtxoConsolidationConfig,
         ValidatorNodeConfig,
         WalletConfig,
     },
@@ -60,6 +61,9 @@ const DB_INIT_MIN_MB: i64 = 100;
 const DB_GROW_SIZE_MIN_MB: i64 = 20;
 const DB_RESIZE_THRESHOLD_MIN_MB: i64 = 10;
 
+const SLED_CACHE_CAPACITY_DEFAULT_MB: u64 = 512;
+const SLED_FLUSH_EVERY_DEFAULT_MS: u64 = 1000;
+
 //-------------------------------------        Main Configuration Struct      --------------------------------------//
 
 #[derive(Debug, Clone)]
@@ -75,13 +79,25 @@ pub struct GlobalConfig {
     pub listnener_liveness_max_sessions: usize,
     pub listener_liveness_allowlist_cidrs: Vec<String>,
     pub rpc_max_simultaneous_sessions: Option<usize>,
+    pub rpc_peer_quota_capacity: u32,
+    pub rpc_peer_quota_refill_per_sec: u32,
+    pub rpc_method_quota_capacity: u32,
+    pub rpc_method_quota_refill_per_sec: u32,
+    pub peer_db_passphrase: Option<String>,
     pub data_dir: PathBuf,
     pub db_type: DatabaseType,
     pub db_config: LMDBConfig,
+    pub sled_cache_capacity_mb: u64,
+    pub sled_flush_every_ms: Option<u64>,
+    pub db_migration_source_path: Option<PathBuf>,
+    pub enable_utxo_search_indexes: bool,
     pub orphan_storage_capacity: usize,
     pub orphan_db_clean_out_threshold: usize,
     pub pruning_horizon: u64,
     pub pruned_mode_cleanup_interval: u64,
+    pub mempool_persistence_enabled: bool,
+    pub mempool_persistence_max_transactions: usize,
+    pub mempool_persistence_file: PathBuf,
     pub core_threads: Option<usize>,
     pub base_node_identity_file: PathBuf,
     pub public_address: Option<Multiaddr>,
@@ -114,6 +130,9 @@ pub struct GlobalConfig {
     pub transaction_broadcast_send_timeout: Duration,
     pub transaction_routing_mechanism: String,
     pub transaction_num_confirmations_required: u64,
+    pub transaction_fee_estimate_target_blocks: u64,
+    pub transaction_default_fee_per_gram: Option<u64>,
+    pub transaction_max_fee_per_gram: u64,
     pub transaction_event_channel_size: usize,
     pub base_node_event_channel_size: usize,
     pub output_manager_event_channel_size: usize,
@@ -199,6 +218,7 @@ fn convert_node_config(
     let db_type = match db_type.as_str() {
         "memory" => Ok(DatabaseType::Memory),
         "lmdb" => Ok(DatabaseType::LMDB(data_dir.join("db"))),
+        "sled" => Ok(DatabaseType::Sled(data_dir.join("sled_db"))),
         invalid_opt => Err(ConfigurationError::new(
             "base_node.db_type",
             Some(invalid_opt.to_string()),
@@ -278,6 +298,45 @@ fn convert_node_config(
 
     let db_config = LMDBConfig::new_from_mb(init_size_mb, grow_size_mb, resize_threshold_mb);
 
+    let key = config_string("base_node", net_str, "sled_cache_capacity_mb");
+    let sled_cache_capacity_mb = match optional(cfg.get_int(&key)) {
+        Ok(Some(mb)) if mb <= 0 => {
+            return Err(ConfigurationError::new(
+                &key,
+                Some(mb.to_string()),
+                "Sled cache capacity must be greater than zero.",
+            ));
+        },
+        Ok(mb) => mb.map(|mb| mb as u64).unwrap_or(SLED_CACHE_CAPACITY_DEFAULT_MB),
+        Err(e) => return Err(ConfigurationError::new(&key, None, &e.to_string())),
+    };
+
+    // A flush interval of 0 disables background flushing. Writes are always flushed on commit.
+    let key = config_string("base_node", net_str, "sled_flush_every_ms");
+    let sled_flush_every_ms = match optional(cfg.get_int(&key)) {
+        Ok(Some(ms)) if ms < 0 => {
+            return Err(ConfigurationError::new(
+                &key,
+                Some(ms.to_string()),
+                "Sled flush interval cannot be negative.",
+            ));
+        },
+        Ok(Some(0)) => None,
+        Ok(Some(ms)) => Some(ms as u64),
+        Ok(None) => Some(SLED_FLUSH_EVERY_DEFAULT_MS),
+        Err(e) => return Err(ConfigurationError::new(&key, None, &e.to_string())),
+    };
+
+    let key = config_string("base_node", net_str, "enable_utxo_search_indexes");
+    let enable_utxo_search_indexes = optional(cfg.get_bool(&key))
+        .map_err(|_| ConfigurationError::new(&key, None, "Invalid boolean"))?
+        .unwrap_or(false);
+
+    let key = config_string("base_node", net_str, "db_migration_source_path");
+    let db_migration_source_path = optional(cfg.get_str(&key))
+        .map_err(|e| ConfigurationError::new(&key, None, &e.to_string()))?
+        .map(PathBuf::from);
+
     let key = config_string("base_node", net_str, "orphan_storage_capacity");
     let orphan_storage_capacity = cfg.get_int(&key).unwrap_or(720) as usize;
 
@@ -290,6 +349,19 @@ fn convert_node_config(
     let key = config_string("base_node", net_str, "pruned_mode_cleanup_interval");
     let pruned_mode_cleanup_interval = cfg.get_int(&key).unwrap_or(50) as u64;
 
+    // Mempool persistence
+    let key = config_string("base_node", net_str, "mempool_persistence_enabled");
+    let mempool_persistence_enabled = optional(cfg.get_bool(&key))
+        .map_err(|_| ConfigurationError::new(&key, None, "Invalid boolean"))?
+        .unwrap_or(false);
+
+    let key = config_string("base_node", net_str, "mempool_persistence_max_transactions");
+    let mempool_persistence_max_transactions = optional(cfg.get_int(&key))
+        .map_err(|e| ConfigurationError::new(&key, None, &e.to_string()))?
+        .unwrap_or(10_000) as usize;
+
+    let mempool_persistence_file = data_dir.join("mempool.dat");
+
     // Thread counts
     let key = config_string("base_node", net_str, "core_threads");
     let core_threads = optional(cfg.get_int(&key).map(|n| n as usize))
@@ -385,6 +457,86 @@ fn convert_node_config(
         } else {
             None
         };
+
+        // Webhooks can be an array or a comma separated list (e.g. in an ENVVAR)
+        let key = "wallet.webhook_urls";
+        config.webhook_urls = match cfg.get_array(key) {
+            Ok(urls) => urls
+                .into_iter()
+                .map(|v| {
+                    v.into_str()
+                        .map_err(|err| ConfigurationError::new(key, None, &err.to_string()))
+                })
+                .collect::<Result<_, _>>()?,
+            Err(..) => optional(cfg.get_str(key))?
+                .map(|s| {
+                    s.split(',')
+                        .map(|v| v.trim().to_string())
+                        .filter(|v| !v.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+        };
+
+        let key = "wallet.webhook_secret";
+        config.webhook_secret = optional(cfg.get_str(key))?;
+        if !config.webhook_urls.is_empty() && config.webhook_secret.is_none() {
+            return Err(ConfigurationError::new(
+                key,
+                None,
+                "a webhook secret is required when webhook urls are configured",
+            ));
+        }
+
+        let key = "wallet.webhook_max_attempts";
+        config.webhook_max_attempts = optional(cfg.get_int(key))?
+            .map(|n| u32::try_from(n).map_err(|e| ConfigurationError::new(key, Some(n.to_string()), &e.to_string())))
+            .transpose()?
+            .unwrap_or(10);
+        if config.webhook_max_attempts == 0 {
+            return Err(ConfigurationError::new(
+                key,
+                Some("0".to_string()),
+                "must be at least 1",
+            ));
+        }
+
+        let key = "wallet.utxo_consolidation_enabled";
+        config.utxo_consolidation = if cfg.get_bool(key).unwrap_or_default() {
+            let key = "wallet.utxo_consolidation_max_inputs";
+            let max_inputs = optional(cfg.get_int(key))?.map(|n| n as usize).unwrap_or(100);
+            if max_inputs < 2 {
+                return Err(ConfigurationError::new(
+                    key,
+                    Some(max_inputs.to_string()),
+                    "must be at least 2",
+                ));
+            }
+            let key = "wallet.utxo_consolidation_interval";
+            let interval = optional(cfg.get_int(key))?.map(|n| n as u64).unwrap_or(3600);
+            if interval == 0 {
+                return Err(ConfigurationError::new(
+                    key,
+                    Some("0".to_string()),
+                    "must be at least 1 second",
+                ));
+            }
+            Some(UtxoConsolidationConfig {
+                max_utxo_count: optional(cfg.get_int("wallet.utxo_consolidation_max_utxo_count"))?
+                    .map(|n| n as usize)
+                    .unwrap_or(500),
+                dust_threshold: optional(cfg.get_int("wallet.utxo_consolidation_dust_threshold"))?
+                    .map(|n| n as u64)
+                    .unwrap_or(0),
+                max_fee_per_gram: optional(cfg.get_int("wallet.utxo_consolidation_max_fee_per_gram"))?
+                    .map(|n| n as u64)
+                    .unwrap_or(25),
+                max_inputs,
+                interval: Duration::from_secs(interval),
+            })
+        } else {
+            None
+        };
         wallet_config = Some(config);
     }
 
@@ -510,6 +662,15 @@ fn convert_node_config(
     let key = "wallet.transaction_num_confirmations_required";
     let transaction_num_confirmations_required = optional(cfg.get_int(key))?.unwrap_or(3) as u64;
 
+    let key = "wallet.transaction_fee_estimate_target_blocks";
+    let transaction_fee_estimate_target_blocks = optional(cfg.get_int(key))?.unwrap_or(3) as u64;
+
+    let key = "wallet.transaction_default_fee_per_gram";
+    let transaction_default_fee_per_gram = optional(cfg.get_int(key))?.map(|v| v as u64);
+
+    let key = "wallet.transaction_max_fee_per_gram";
+    let transaction_max_fee_per_gram = optional(cfg.get_int(key))?.unwrap_or(1_000) as u64;
+
     let key = "wallet.transaction_event_channel_size";
     let transaction_event_channel_size = optional(cfg.get_int(key))?.unwrap_or(1000) as usize;
 
@@ -611,6 +772,41 @@ fn convert_node_config(
             )),
         })?;
 
+    let key = "common.rpc_peer_quota_capacity";
+    let rpc_peer_quota_capacity = cfg
+        .get_int(key)
+        .map_err(|e| ConfigurationError::new(key, None, &e.to_string()))
+        .and_then(|v| {
+            u32::try_from(v).map_err(|e| ConfigurationError::new(key, Some(v.to_string()), &e.to_string()))
+        })?;
+
+    let key = "common.rpc_peer_quota_refill_per_sec";
+    let rpc_peer_quota_refill_per_sec = cfg
+        .get_int(key)
+        .map_err(|e| ConfigurationError::new(key, None, &e.to_string()))
+        .and_then(|v| {
+            u32::try_from(v).map_err(|e| ConfigurationError::new(key, Some(v.to_string()), &e.to_string()))
+        })?;
+
+    let key = "common.rpc_method_quota_capacity";
+    let rpc_method_quota_capacity = cfg
+        .get_int(key)
+        .map_err(|e| ConfigurationError::new(key, None, &e.to_string()))
+        .and_then(|v| {
+            u32::try_from(v).map_err(|e| ConfigurationError::new(key, Some(v.to_string()), &e.to_string()))
+        })?;
+
+    let key = "common.rpc_method_quota_refill_per_sec";
+    let rpc_method_quota_refill_per_sec = cfg
+        .get_int(key)
+        .map_err(|e| ConfigurationError::new(key, None, &e.to_string()))
+        .and_then(|v| {
+            u32::try_from(v).map_err(|e| ConfigurationError::new(key, Some(v.to_string()), &e.to_string()))
+        })?;
+
+    let key = "common.peer_db_passphrase";
+    let peer_db_passphrase = optional(cfg.get_str(key))?;
+
     let key = "common.buffer_size_base_node";
     let buffer_size_base_node = cfg
         .get_int(key)
@@ -808,13 +1004,25 @@ fn convert_node_config(
         listnener_liveness_max_sessions: liveness_max_sessions,
         listener_liveness_allowlist_cidrs: liveness_allowlist_cidrs,
         rpc_max_simultaneous_sessions,
+        rpc_peer_quota_capacity,
+        rpc_peer_quota_refill_per_sec,
+        rpc_method_quota_capacity,
+        rpc_method_quota_refill_per_sec,
+        peer_db_passphrase,
         data_dir,
         db_type,
         db_config,
+        sled_cache_capacity_mb,
+        sled_flush_every_ms,
+        db_migration_source_path,
+        enable_utxo_search_indexes,
         orphan_storage_capacity,
         orphan_db_clean_out_threshold,
         pruning_horizon,
         pruned_mode_cleanup_interval,
+        mempool_persistence_enabled,
+        mempool_persistence_max_transactions,
+        mempool_persistence_file,
         core_threads,
         base_node_identity_file,
         public_address,
@@ -847,6 +1055,9 @@ fn convert_node_config(
         transaction_broadcast_send_timeout,
         transaction_routing_mechanism,
         transaction_num_confirmations_required,
+        transaction_fee_estimate_target_blocks,
+        transaction_default_fee_per_gram,
+        transaction_max_fee_per_gram,
         transaction_event_channel_size,
         base_node_event_channel_size,
         wallet_connection_manager_pool_size,
@@ -1047,6 +1258,18 @@ fn network_transport_config(
                 auth,
             })
         },
+        "websocket" => {
+            let key = config_string(app_str, network, "websocket_listener_address");
+            let listener_address = get_conf_multiaddr(&key)?;
+
+            Ok(CommsTransport::WebSocket { listener_address })
+        },
+        "quic" => {
+            let key = config_string(app_str, network, "quic_listener_address");
+            let listener_address = get_conf_multiaddr(&key)?;
+
+            Ok(CommsTransport::Quic { listener_address })
+        },
         t => Err(ConfigurationError::new(
             &transport_key,
             Some(t.to_string()),
@@ -1064,6 +1287,7 @@ fn config_string(prefix: &str, network: &str, key: &str) -> String {
 #[derive(Debug, Clone)]
 pub enum DatabaseType {
     LMDB(PathBuf),
+    Sled(PathBuf),
     Memory,
 }
 
@@ -1187,6 +1411,12 @@ pub enum CommsTransport {
         auth: SocksAuthentication,
         listener_address: Multiaddr,
     },
+    /// Use WebSockets to join the Tari network. The listener address must end in `/ws` and only peers with WebSocket
+    /// addresses are contactable.
+    WebSocket { listener_address: Multiaddr },
+    /// Use QUIC to join the Tari network. The listener address must be a UDP address ending in `/quic` and only peers
+    /// with QUIC addresses are contactable.
+    Quic { listener_address: Multiaddr },
 }
 
 #[derive(Debug, Clone)]
@@ -54,4 +54,4 @@ pub use base_node_config::BaseNodeConfig;
 pub use collectibles_config::CollectiblesConfig;
 pub use merge_mining_config::MergeMiningConfig;
 pub use validator_node_config::ValidatorNodeConfig;
-pub use wallet_config::WalletConfig;
+pub use wallet_config::{UtxoConsolidationConfig, WalletConfig};
@@ -74,6 +74,10 @@ pub fn default_config(bootstrap: &ConfigBootstrap) -> Config {
     cfg.set_default("common.message_cache_ttl", 1440).unwrap();
     cfg.set_default("common.peer_allowlist", Vec::<String>::new()).unwrap();
     cfg.set_default("common.rpc_max_simultaneous_sessions", 1000).unwrap();
+    cfg.set_default("common.rpc_peer_quota_capacity", 0).unwrap();
+    cfg.set_default("common.rpc_peer_quota_refill_per_sec", 0).unwrap();
+    cfg.set_default("common.rpc_method_quota_capacity", 0).unwrap();
+    cfg.set_default("common.rpc_method_quota_refill_per_sec", 0).unwrap();
     cfg.set_default("common.liveness_max_sessions", 0).unwrap();
     cfg.set_default("common.denylist_ban_period", 1440).unwrap();
     cfg.set_default("common.buffer_size_base_node", 1_500).unwrap();
@@ -20,9 +20,33 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use std::time::Duration;
+
 use multiaddr::Multiaddr;
 
 #[derive(Debug, Clone, Default)]
 pub struct WalletConfig {
     pub grpc_address: Option<Multiaddr>,
+    /// Urls that transaction event webhooks are POSTed to
+    pub webhook_urls: Vec<String>,
+    /// Shared secret used to HMAC sign webhook payloads
+    pub webhook_secret: Option<String>,
+    /// Number of delivery attempts after which a webhook is dropped from the outbox
+    pub webhook_max_attempts: u32,
+    /// Automatic consolidation of unspent outputs, disabled if not set
+    pub utxo_consolidation: Option<UtxoConsolidationConfig>,
+}
+
+#[derive(Debug, Clone)]
+pub struct UtxoConsolidationConfig {
+    /// Consolidate when the wallet holds more than this many unspent outputs
+    pub max_utxo_count: usize,
+    /// Outputs worth at most this many MicroTari are swept together regardless of the output count
+    pub dust_threshold: u64,
+    /// Do not consolidate while the estimated fee per gram is above this value
+    pub max_fee_per_gram: u64,
+    /// The maximum number of inputs in a single consolidation transaction
+    pub max_inputs: usize,
+    /// How often to check whether outputs should be consolidated
+    pub interval: Duration,
 }
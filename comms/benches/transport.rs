@@ -0,0 +1,192 @@
+// Copyright 2022, The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+#[cfg(not(feature = "benches"))]
+mod benches {
+    pub fn main() {
+        println!("Enable the `benches` feature to run benches");
+    }
+}
+
+/// Compares the throughput of substreams over the TCP (noise + yamux) and QUIC transports. The same amount of data is
+/// sent over one substream and over many concurrent substreams, the latter shows the effect of head-of-line blocking.
+#[cfg(feature = "benches")]
+mod benches {
+    use std::time::Duration;
+
+    use criterion::{criterion_group, Criterion};
+    use futures::future;
+    use tari_comms::{
+        multiaddr::Multiaddr,
+        peer_manager::PeerFeatures,
+        protocol::{ProtocolEvent, ProtocolNotificationRx, ProtocolNotificationTx},
+        test_utils::node_identity::build_node_identity,
+        transports::{QuicTransport, TcpTransport, Transport},
+        Bytes,
+        CommsBuilder,
+        CommsNode,
+        PeerConnection,
+        Substream,
+    };
+    use tari_shutdown::{Shutdown, ShutdownSignal};
+    use tari_storage::HashmapDatabase;
+    use tokio::{
+        io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+        runtime::Runtime,
+        sync::mpsc,
+    };
+
+    static BENCH_PROTOCOL: Bytes = Bytes::from_static(b"/tari/bench/transfer");
+    const TOTAL_BYTES: usize = 4 * 1024 * 1024;
+
+    async fn spawn_node<T>(
+        transport: T,
+        listener_address: Multiaddr,
+        notifier: Option<ProtocolNotificationTx<Substream>>,
+        shutdown_signal: ShutdownSignal,
+    ) -> CommsNode
+    where
+        T: Transport + Unpin + Send + Sync + Clone + 'static,
+        T::Output: AsyncRead + AsyncWrite + Send + Sync + Unpin + 'static,
+    {
+        let node_identity = build_node_identity(PeerFeatures::COMMUNICATION_NODE);
+        let mut node = CommsBuilder::new()
+            .allow_test_addresses()
+            .with_listener_address(listener_address)
+            .with_peer_storage(HashmapDatabase::new(), None)
+            .with_node_identity(node_identity.clone())
+            .with_shutdown_signal(shutdown_signal)
+            .build()
+            .unwrap();
+        if let Some(notifier) = notifier {
+            node = node.add_protocol(&[BENCH_PROTOCOL.clone()], notifier);
+        }
+        let node = node.spawn_with_transport(transport).await.unwrap();
+        node_identity.set_public_address(node.listening_address().clone());
+        node
+    }
+
+    async fn connect<T>(
+        transport: T,
+        listener_address: Multiaddr,
+        shutdown_signal: ShutdownSignal,
+    ) -> (Vec<CommsNode>, PeerConnection, ProtocolNotificationRx<Substream>)
+    where
+        T: Transport + Unpin + Send + Sync + Clone + 'static,
+        T::Output: AsyncRead + AsyncWrite + Send + Sync + Unpin + 'static,
+    {
+        let (notif_tx, notif_rx) = mpsc::channel(100);
+        let node1 = spawn_node(
+            transport.clone(),
+            listener_address.clone(),
+            None,
+            shutdown_signal.clone(),
+        )
+        .await;
+        let node2 = spawn_node(transport, listener_address, Some(notif_tx), shutdown_signal).await;
+
+        node1
+            .peer_manager()
+            .add_peer(node2.node_identity().to_peer())
+            .await
+            .unwrap();
+        let conn = node1
+            .connectivity()
+            .dial_peer(node2.node_identity().node_id().clone())
+            .await
+            .unwrap();
+        (vec![node1, node2], conn, notif_rx)
+    }
+
+    async fn transfer(conn: &PeerConnection, notif_rx: &mut ProtocolNotificationRx<Substream>, num_substreams: usize) {
+        let len = TOTAL_BYTES / num_substreams;
+        let sends = (0..num_substreams).map(|_| {
+            let mut conn = conn.clone();
+            tokio::spawn(async move {
+                let mut substream = conn.open_substream(&BENCH_PROTOCOL).await.unwrap();
+                substream.stream.write_all(&vec![0xAA; len]).await.unwrap();
+                substream.stream.shutdown().await.unwrap();
+            })
+        });
+        let sends = future::join_all(sends);
+
+        // Readers run concurrently so that unread data on one substream does not hold up the others
+        let receives = async {
+            let mut reads = Vec::with_capacity(num_substreams);
+            for _ in 0..num_substreams {
+                let notification = notif_rx.recv().await.unwrap();
+                if let ProtocolEvent::NewInboundSubstream(_, mut substream) = notification.event {
+                    reads.push(tokio::spawn(async move {
+                        let mut buf = Vec::with_capacity(len);
+                        substream.read_to_end(&mut buf).await.unwrap();
+                        assert_eq!(buf.len(), len);
+                    }));
+                }
+            }
+            future::join_all(reads).await
+        };
+
+        let (sends, receives) = tokio::join!(sends, receives);
+        sends.into_iter().chain(receives).for_each(|result| result.unwrap());
+    }
+
+    fn bench_transport<T>(c: &mut Criterion, name: &str, transport: T, listener_address: &str)
+    where
+        T: Transport + Unpin + Send + Sync + Clone + 'static,
+        T::Output: AsyncRead + AsyncWrite + Send + Sync + Unpin + 'static,
+    {
+        let runtime = Runtime::new().unwrap();
+        let shutdown = Shutdown::new();
+        // The nodes keep running until the benchmark is complete
+        let (_nodes, conn, mut notif_rx) = runtime.block_on(connect(
+            transport,
+            listener_address.parse().unwrap(),
+            shutdown.to_signal(),
+        ));
+
+        c.bench_function_over_inputs(
+            &format!("{} transfer 4MiB", name),
+            move |b, &&num_substreams| b.iter(|| runtime.block_on(transfer(&conn, &mut notif_rx, num_substreams))),
+            &[1usize, 16],
+        );
+    }
+
+    fn transfer_benches(c: &mut Criterion) {
+        bench_transport(c, "tcp", TcpTransport::new(), "/ip4/127.0.0.1/tcp/0");
+        bench_transport(c, "quic", QuicTransport::new().unwrap(), "/ip4/127.0.0.1/udp/0/quic");
+    }
+
+    criterion_group!(
+        name = transport;
+        config = Criterion::default().warm_up_time(Duration::from_millis(500)).sample_size(10);
+        targets = transfer_benches
+    );
+
+    pub fn main() {
+        transport();
+        criterion::Criterion::default().configure_from_args().final_summary();
+    }
+}
+
+fn main() {
+    benches::main();
+}
@@ -92,6 +92,11 @@ impl DhtBuilder {
         self
     }
 
+    pub fn with_database_passphrase(&mut self, passphrase: String) -> &mut Self {
+        self.config.database_passphrase = Some(passphrase);
+        self
+    }
+
     pub fn with_dedup_cache_trim_interval(&mut self, trim_interval: Duration) -> &mut Self {
         self.config.dedup_cache_trim_interval = trim_interval;
         self
@@ -35,6 +35,10 @@ pub struct DhtConfig {
     pub protocol_version: DhtProtocolVersion,
     /// The `DbConnectionUrl` for the Dht database. Default: In-memory database
     pub database_url: DbConnectionUrl,
+    /// If set, stored messages in the Dht database are encrypted at rest using a key derived from this passphrase.
+    /// An existing unencrypted database is encrypted on startup.
+    /// Default: None
+    pub database_passphrase: Option<String>,
     /// The size of the buffer (channel) which holds pending outbound message requests.
     /// Default: 20
     pub outbound_buffer_size: usize,
@@ -146,6 +150,7 @@ impl Default for DhtConfig {
             dedup_cache_trim_interval: Duration::from_secs(5 * 60),
             dedup_allowed_message_occurrences: 1,
             database_url: DbConnectionUrl::Memory,
+            database_passphrase: None,
             discovery_request_timeout: Duration::from_secs(2 * 60),
             connectivity_update_interval: Duration::from_secs(2 * 60),
             connectivity_random_pool_refresh: Duration::from_secs(2 * 60 * 60),
@@ -52,7 +52,13 @@ use crate::{
     rpc,
     storage::{DbConnection, StorageError},
     store_forward,
-    store_forward::{StoreAndForwardError, StoreAndForwardRequest, StoreAndForwardRequester, StoreAndForwardService},
+    store_forward::{
+        StoreAndForwardDatabase,
+        StoreAndForwardError,
+        StoreAndForwardRequest,
+        StoreAndForwardRequester,
+        StoreAndForwardService,
+    },
     DedupLayer,
     DhtActorError,
     DhtBuilder,
@@ -70,6 +76,8 @@ const DHT_EVENT_BROADCAST_CHANNEL_SIZE: usize = 100;
 pub enum DhtInitializationError {
     #[error("Database initialization failed: {0}")]
     DatabaseMigrationFailed(#[from] StorageError),
+    #[error("Failed to open database: {0}")]
+    DatabaseOpenFailed(StorageError),
     #[error("StoreAndForwardInitializationError: {0}")]
     StoreAndForwardInitializationError(#[from] StoreAndForwardError),
     #[error("DhtActorInitializationError: {0}")]
@@ -139,11 +147,16 @@ impl Dht {
 
         let conn = DbConnection::connect_and_migrate(dht.config.database_url.clone())
             .map_err(DhtInitializationError::DatabaseMigrationFailed)?;
+        let saf_database = match dht.config.database_passphrase.as_deref() {
+            Some(passphrase) => StoreAndForwardDatabase::open_encrypted(conn.clone(), passphrase),
+            None => StoreAndForwardDatabase::open(conn.clone()),
+        }
+        .map_err(DhtInitializationError::DatabaseOpenFailed)?;
 
         dht.network_discovery_service(shutdown_signal.clone()).spawn();
         dht.connectivity_service(shutdown_signal.clone()).spawn();
         dht.store_and_forward_service(
-            conn.clone(),
+            saf_database,
             saf_receiver,
             shutdown_signal.clone(),
             saf_response_signal_receiver,
@@ -228,14 +241,14 @@ impl Dht {
 
     fn store_and_forward_service(
         &self,
-        conn: DbConnection,
+        database: StoreAndForwardDatabase,
         request_rx: mpsc::Receiver<StoreAndForwardRequest>,
         shutdown_signal: ShutdownSignal,
         saf_response_signal_rx: mpsc::Receiver<()>,
     ) -> StoreAndForwardService {
         StoreAndForwardService::new(
             self.config.saf_config.clone(),
-            conn,
+            database,
             self.peer_manager.clone(),
             self.dht_requester(),
             self.connectivity.clone(),
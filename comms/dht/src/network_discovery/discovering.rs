@@ -25,7 +25,7 @@ use std::convert::TryInto;
 use futures::{stream::FuturesUnordered, Stream, StreamExt};
 use log::*;
 use tari_comms::{
-    connectivity::ConnectivityError,
+    connectivity::{ConnectivityError, ReputationEvent},
     peer_manager::{NodeDistance, NodeId, Peer, PeerFeatures},
     PeerConnection,
     PeerManager,
@@ -208,14 +208,14 @@ impl Discovering {
             Err(err) => {
                 warn!(
                     target: LOG_TARGET,
-                    "Received invalid peer from sync peer '{}': {}. Banning sync peer.", sync_peer, err
+                    "Received invalid peer '{}' from sync peer '{}': {}. Reporting sync peer.",
+                    new_peer_node_id,
+                    sync_peer,
+                    err
                 );
                 self.context
                     .connectivity
-                    .ban_peer(
-                        sync_peer.clone(),
-                        format!("Network discovery peer sent invalid peer '{}'", new_peer_node_id),
-                    )
+                    .report_peer(sync_peer.clone(), ReputationEvent::BadRpcResponse)
                     .await?;
                 Err(err.into())
             },
@@ -20,11 +20,15 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{convert::TryInto, time::Duration};
+use std::convert::TryInto;
 
 use futures::StreamExt;
 use log::*;
-use tari_comms::{connectivity::ConnectivityEvent, peer_manager::NodeId, PeerConnection};
+use tari_comms::{
+    connectivity::{ConnectivityEvent, ReputationEvent},
+    peer_manager::NodeId,
+    PeerConnection,
+};
 use tokio::sync::broadcast;
 
 use crate::{
@@ -85,15 +89,11 @@ impl OnConnect {
                     match self.sync_peers(conn.clone()).await {
                         Ok(_) => continue,
                         Err(err @ NetworkDiscoveryError::PeerValidationError(_)) => {
-                            warn!(target: LOG_TARGET, "{}. Banning peer.", err);
+                            warn!(target: LOG_TARGET, "{}. Reporting peer.", err);
                             if let Err(err) = self
                                 .context
                                 .connectivity
-                                .ban_peer_until(
-                                    conn.peer_node_id().clone(),
-                                    Duration::from_secs(60 * 60),
-                                    err.to_string(),
-                                )
+                                .report_peer(conn.peer_node_id().clone(), ReputationEvent::BadRpcResponse)
                                 .await
                             {
                                 return err.into();
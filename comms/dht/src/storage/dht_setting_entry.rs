@@ -30,6 +30,8 @@ pub enum DhtMetadataKey {
     OfflineTimestamp,
     /// Timestamp of the most recent SAF message received
     LastSafMessageReceived,
+    /// Passphrase hash and salt used to derive the key for encrypted stored messages
+    EncryptionParams,
 }
 
 impl fmt::Display for DhtMetadataKey {
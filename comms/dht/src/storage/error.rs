@@ -21,6 +21,7 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use tari_common_sqlite::error::SqliteStorageError;
+use tari_comms::utils::encryption::DatabaseEncryptionError;
 use tari_utilities::message_format::MessageFormatError;
 use thiserror::Error;
 use tokio::task;
@@ -45,4 +46,10 @@ pub enum StorageError {
     UnexpectedResult(String),
     #[error("Diesel R2d2 error: `{0}`")]
     DieselR2d2Error(#[from] SqliteStorageError),
+    #[error("Database encryption error: {0}")]
+    EncryptionError(#[from] DatabaseEncryptionError),
+    #[error("The DHT database is encrypted and cannot be opened without a passphrase")]
+    DatabaseEncrypted,
+    #[error("DHT database integrity check failed: {0}")]
+    IntegrityCheckFailed(String),
 }
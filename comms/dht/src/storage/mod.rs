@@ -27,6 +27,7 @@ mod error;
 pub use error::StorageError;
 
 mod dht_setting_entry;
+pub(crate) use dht_setting_entry::NewDhtMetadataEntry;
 pub use dht_setting_entry::{DhtMetadataEntry, DhtMetadataKey};
 
 mod database;
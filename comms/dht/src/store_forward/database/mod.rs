@@ -22,29 +22,139 @@
 
 mod stored_message;
 use chrono::{DateTime, NaiveDateTime, Utc};
-use diesel::{dsl, result::DatabaseErrorKind, BoolExpressionMethods, ExpressionMethods, QueryDsl, RunQueryDsl};
+use diesel::{
+    dsl,
+    result::DatabaseErrorKind,
+    BoolExpressionMethods,
+    Connection,
+    ExpressionMethods,
+    QueryDsl,
+    RunQueryDsl,
+};
+use log::*;
 pub use stored_message::{NewStoredMessage, StoredMessage};
-use tari_comms::{peer_manager::NodeId, types::CommsPublicKey};
-use tari_utilities::hex::Hex;
+use tari_comms::{
+    peer_manager::NodeId,
+    types::CommsPublicKey,
+    utils::encryption::{DatabaseCipher, EncryptionParams},
+};
+use tari_utilities::{hex::Hex, message_format::MessageFormat};
 
 use crate::{
     envelope::DhtMessageType,
-    schema::stored_messages,
-    storage::{DbConnection, StorageError},
+    schema::{dht_metadata, stored_messages},
+    storage::{DbConnection, DhtDatabase, DhtMetadataKey, NewDhtMetadataEntry, StorageError},
     store_forward::message::StoredMessagePriority,
 };
 
+const LOG_TARGET: &str = "comms::dht::store_forward::database";
+
 pub struct StoreAndForwardDatabase {
     connection: DbConnection,
+    cipher: Option<DatabaseCipher>,
 }
 
 impl StoreAndForwardDatabase {
     pub fn new(connection: DbConnection) -> Self {
-        Self { connection }
+        Self {
+            connection,
+            cipher: None,
+        }
+    }
+
+    /// Opens an unencrypted database. Returns `StorageError::DatabaseEncrypted` if the database has been encrypted.
+    pub fn open(connection: DbConnection) -> Result<Self, StorageError> {
+        let params = DhtDatabase::new(connection.clone()).get_metadata_value_bytes(DhtMetadataKey::EncryptionParams)?;
+        if params.is_some() {
+            return Err(StorageError::DatabaseEncrypted);
+        }
+        Ok(Self::new(connection))
+    }
+
+    /// Opens an encrypted database, verifying the passphrase and the integrity of every stored message. If the
+    /// database has not been encrypted yet, all stored messages are encrypted in place with a newly derived key.
+    pub fn open_encrypted(connection: DbConnection, passphrase: &str) -> Result<Self, StorageError> {
+        let params = DhtDatabase::new(connection.clone())
+            .get_metadata_value::<EncryptionParams>(DhtMetadataKey::EncryptionParams)?;
+        let cipher = match params {
+            Some(params) => params.unlock(passphrase)?,
+            None => Self::apply_encryption(&connection, passphrase)?,
+        };
+
+        let db = Self {
+            connection,
+            cipher: Some(cipher),
+        };
+        let num_messages = db.verify_integrity()?;
+        debug!(
+            target: LOG_TARGET,
+            "Opened encrypted store and forward database. {} message(s) verified", num_messages
+        );
+        Ok(db)
+    }
+
+    /// Encrypts all stored messages and stores the encryption params in a single transaction
+    fn apply_encryption(connection: &DbConnection, passphrase: &str) -> Result<DatabaseCipher, StorageError> {
+        let (params, cipher) = EncryptionParams::generate(passphrase)?;
+        let conn = connection.get_pooled_connection()?;
+        let num_messages = conn.transaction::<_, StorageError, _>(|| {
+            let messages: Vec<StoredMessage> = stored_messages::table
+                .select(stored_messages::all_columns)
+                .get_results(&conn)?;
+            let num_messages = messages.len();
+            for mut message in messages {
+                message.encrypt(&cipher)?;
+                diesel::update(stored_messages::table.filter(stored_messages::id.eq(message.id)))
+                    .set((
+                        stored_messages::header.eq(message.header),
+                        stored_messages::body.eq(message.body),
+                    ))
+                    .execute(&conn)?;
+            }
+
+            diesel::replace_into(dht_metadata::table)
+                .values(NewDhtMetadataEntry {
+                    key: DhtMetadataKey::EncryptionParams.to_string(),
+                    value: params.to_binary()?,
+                })
+                .execute(&conn)?;
+            Ok(num_messages)
+        })?;
+
+        info!(
+            target: LOG_TARGET,
+            "Encrypted store and forward database ({} message(s) migrated)", num_messages
+        );
+        Ok(cipher)
+    }
+
+    /// Decrypts every stored message, returning the number of messages if all messages are intact
+    fn verify_integrity(&self) -> Result<usize, StorageError> {
+        let conn = self.connection.get_pooled_connection()?;
+        let messages: Vec<StoredMessage> = stored_messages::table
+            .select(stored_messages::all_columns)
+            .get_results(&conn)?;
+        let num_messages = messages.len();
+        self.decrypt_messages(messages)?;
+        Ok(num_messages)
+    }
+
+    fn decrypt_messages(&self, mut messages: Vec<StoredMessage>) -> Result<Vec<StoredMessage>, StorageError> {
+        if let Some(cipher) = self.cipher.as_ref() {
+            for message in &mut messages {
+                message.decrypt(cipher).map_err(|err| {
+                    StorageError::IntegrityCheckFailed(format!("stored message {}: {}", message.id, err))
+                })?;
+            }
+        }
+        Ok(messages)
     }
 
     /// Inserts and returns Ok(true) if the item already existed and Ok(false) if it didn't
-    pub fn insert_message_if_unique(&self, message: NewStoredMessage) -> Result<bool, StorageError> {
+    pub fn insert_message_if_unique(&self, mut message: NewStoredMessage) -> Result<bool, StorageError> {
+        if let Some(cipher) = self.cipher.as_ref() {
+            message.encrypt(cipher)?;
+        }
         let conn = self.connection.get_pooled_connection()?;
         match diesel::insert_into(stored_messages::table)
             .values(message)
@@ -91,11 +201,11 @@ impl StoreAndForwardDatabase {
             query = query.filter(stored_messages::stored_at.gt(since.naive_utc()));
         }
 
-        query
+        let messages = query
             .order_by(stored_messages::stored_at.desc())
             .limit(limit)
-            .get_results(&conn)
-            .map_err(Into::into)
+            .get_results(&conn)?;
+        self.decrypt_messages(messages)
     }
 
     pub fn find_anonymous_messages(
@@ -116,11 +226,11 @@ impl StoreAndForwardDatabase {
             query = query.filter(stored_messages::stored_at.gt(since.naive_utc()));
         }
 
-        query
+        let messages = query
             .order_by(stored_messages::stored_at.desc())
             .limit(limit)
-            .get_results(&conn)
-            .map_err(Into::into)
+            .get_results(&conn)?;
+        self.decrypt_messages(messages)
     }
 
     pub fn find_join_messages(
@@ -138,11 +248,11 @@ impl StoreAndForwardDatabase {
             query = query.filter(stored_messages::stored_at.gt(since.naive_utc()));
         }
 
-        query
+        let messages = query
             .order_by(stored_messages::stored_at.desc())
             .limit(limit)
-            .get_results(&conn)
-            .map_err(Into::into)
+            .get_results(&conn)?;
+        self.decrypt_messages(messages)
     }
 
     pub fn find_messages_of_type_for_pubkey(
@@ -164,20 +274,20 @@ impl StoreAndForwardDatabase {
             query = query.filter(stored_messages::stored_at.gt(since.naive_utc()));
         }
 
-        query
+        let messages = query
             .order_by(stored_messages::stored_at.desc())
             .limit(limit)
-            .get_results(&conn)
-            .map_err(Into::into)
+            .get_results(&conn)?;
+        self.decrypt_messages(messages)
     }
 
     #[cfg(test)]
     pub(crate) fn get_all_messages(&self) -> Result<Vec<StoredMessage>, StorageError> {
         let conn = self.connection.get_pooled_connection()?;
-        stored_messages::table
+        let messages = stored_messages::table
             .select(stored_messages::all_columns)
-            .get_results(&conn)
-            .map_err(Into::into)
+            .get_results(&conn)?;
+        self.decrypt_messages(messages)
     }
 
     pub(crate) fn delete_messages_with_priority_older_than(
@@ -300,4 +410,63 @@ mod test {
         assert_eq!(messages[0].body_hash, msg3.body_hash);
         assert_eq!(messages[1].body_hash, msg4.body_hash);
     }
+
+    #[runtime::test]
+    async fn encrypt_existing_messages() {
+        let conn = DbConnection::connect_memory(random::string(8)).unwrap();
+        conn.migrate().unwrap();
+        let db = StoreAndForwardDatabase::new(conn.clone());
+        let mut msg1 = NewStoredMessage::default();
+        msg1.body_hash.push('1');
+        msg1.body = b"body 1".to_vec();
+        db.insert_message_if_unique(msg1.clone()).unwrap();
+
+        let db = StoreAndForwardDatabase::open_encrypted(conn.clone(), "secret").unwrap();
+        let mut msg2 = NewStoredMessage::default();
+        msg2.body_hash.push('2');
+        msg2.body = b"body 2".to_vec();
+        db.insert_message_if_unique(msg2.clone()).unwrap();
+        let messages = db.get_all_messages().unwrap();
+        assert_eq!(messages[0].body, msg1.body);
+        assert_eq!(messages[1].body, msg2.body);
+
+        // Messages are not readable without the key
+        let messages = StoreAndForwardDatabase::new(conn.clone()).get_all_messages().unwrap();
+        assert_ne!(messages[0].body, msg1.body);
+        assert_ne!(messages[1].body, msg2.body);
+
+        let err = StoreAndForwardDatabase::open(conn.clone()).err().unwrap();
+        assert!(matches!(err, StorageError::DatabaseEncrypted));
+        let err = StoreAndForwardDatabase::open_encrypted(conn.clone(), "not the secret")
+            .err()
+            .unwrap();
+        assert!(matches!(err, StorageError::EncryptionError(_)));
+
+        let db = StoreAndForwardDatabase::open_encrypted(conn, "secret").unwrap();
+        assert_eq!(db.get_all_messages().unwrap().len(), 2);
+    }
+
+    #[runtime::test]
+    async fn detect_tampered_messages() {
+        let conn = DbConnection::connect_memory(random::string(8)).unwrap();
+        conn.migrate().unwrap();
+        let db = StoreAndForwardDatabase::open_encrypted(conn.clone(), "secret").unwrap();
+        let mut msg1 = NewStoredMessage::default();
+        msg1.body_hash.push('1');
+        db.insert_message_if_unique(msg1).unwrap();
+        let mut msg2 = NewStoredMessage::default();
+        msg2.body_hash.push('2');
+        db.insert_message_if_unique(msg2).unwrap();
+
+        // Swap the encrypted bodies of the two messages
+        let messages = StoreAndForwardDatabase::new(conn.clone()).get_all_messages().unwrap();
+        let pooled = conn.get_pooled_connection().unwrap();
+        diesel::update(stored_messages::table.filter(stored_messages::id.eq(messages[0].id)))
+            .set(stored_messages::body.eq(messages[1].body.clone()))
+            .execute(&pooled)
+            .unwrap();
+
+        let err = StoreAndForwardDatabase::open_encrypted(conn, "secret").err().unwrap();
+        assert!(matches!(err, StorageError::IntegrityCheckFailed(_)));
+    }
 }
@@ -24,7 +24,11 @@ use std::convert::TryInto;
 
 use chrono::NaiveDateTime;
 use digest::Digest;
-use tari_comms::{message::MessageExt, types::Challenge};
+use tari_comms::{
+    message::MessageExt,
+    types::Challenge,
+    utils::encryption::{DatabaseCipher, DatabaseEncryptionError},
+};
 use tari_utilities::{hex, hex::Hex};
 
 use crate::{
@@ -79,6 +83,13 @@ impl NewStoredMessage {
             body,
         })
     }
+
+    /// Encrypt the header and body of this message. The body hash is left in plaintext for deduplication.
+    pub fn encrypt(&mut self, cipher: &DatabaseCipher) -> Result<(), DatabaseEncryptionError> {
+        self.header = cipher.encrypt(&header_aad(&self.body_hash), &self.header)?;
+        self.body = cipher.encrypt(&body_aad(&self.body_hash), &self.body)?;
+        Ok(())
+    }
 }
 
 #[derive(Clone, Debug, Queryable, Identifiable)]
@@ -96,3 +107,28 @@ pub struct StoredMessage {
     pub stored_at: NaiveDateTime,
     pub body_hash: String,
 }
+
+impl StoredMessage {
+    /// Encrypt the header and body of this message. The body hash is left in plaintext for deduplication.
+    pub fn encrypt(&mut self, cipher: &DatabaseCipher) -> Result<(), DatabaseEncryptionError> {
+        self.header = cipher.encrypt(&header_aad(&self.body_hash), &self.header)?;
+        self.body = cipher.encrypt(&body_aad(&self.body_hash), &self.body)?;
+        Ok(())
+    }
+
+    /// Decrypt and authenticate the header and body of this message
+    pub fn decrypt(&mut self, cipher: &DatabaseCipher) -> Result<(), DatabaseEncryptionError> {
+        self.header = cipher.decrypt(&header_aad(&self.body_hash), &self.header)?;
+        self.body = cipher.decrypt(&body_aad(&self.body_hash), &self.body)?;
+        Ok(())
+    }
+}
+
+/// The header and body ciphertexts are bound to the message body hash so that they cannot be swapped between messages
+fn header_aad(body_hash: &str) -> Vec<u8> {
+    [b"header:".as_ref(), body_hash.as_bytes()].concat()
+}
+
+fn body_aad(body_hash: &str) -> Vec<u8> {
+    [b"body:".as_ref(), body_hash.as_bytes()].concat()
+}
@@ -26,7 +26,7 @@ mod service;
 pub use service::{StoreAndForwardRequest, StoreAndForwardRequester, StoreAndForwardService};
 
 mod database;
-pub use database::StoredMessage;
+pub use database::{StoreAndForwardDatabase, StoredMessage};
 
 mod error;
 pub use error::StoreAndForwardError;
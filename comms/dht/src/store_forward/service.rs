@@ -50,7 +50,7 @@ use crate::{
     event::{DhtEvent, DhtEventSender},
     outbound::{OutboundMessageRequester, SendMessageParams},
     proto::store_forward::{stored_messages_response::SafResponseType, StoredMessagesRequest},
-    storage::{DbConnection, DhtMetadataKey},
+    storage::DhtMetadataKey,
     store_forward::{local_state::SafLocalState, SafConfig},
     DhtRequester,
 };
@@ -193,7 +193,7 @@ pub struct StoreAndForwardService {
 impl StoreAndForwardService {
     pub fn new(
         config: SafConfig,
-        conn: DbConnection,
+        database: StoreAndForwardDatabase,
         peer_manager: Arc<PeerManager>,
         dht_requester: DhtRequester,
         connectivity: ConnectivityRequester,
@@ -205,7 +205,7 @@ impl StoreAndForwardService {
     ) -> Self {
         Self {
             config,
-            database: StoreAndForwardDatabase::new(conn),
+            database,
             peer_manager,
             dht_requester,
             request_rx,
@@ -64,6 +64,7 @@ impl TraitInfoCollector {
         let mut info = RpcMethodInfo {
             method_ident: node.sig.ident.clone(),
             method_num: 0,
+            cost: 1,
             is_server_streaming: false,
             request_type: None,
             return_type: None,
@@ -110,6 +111,9 @@ impl TraitInfoCollector {
                                             ));
                                         }
                                     },
+                                    "cost" => {
+                                        info.cost = extract_u32(ident, &name_value.lit)?;
+                                    },
                                     s => {
                                         return Err(syn_error!(
                                             name_value,
@@ -61,6 +61,7 @@ impl RpcCodeGenerator {
             .iter()
             .map(|m| {
                 let method_num = m.method_num;
+                let cost = m.cost;
                 let method_name = &m.method_ident;
                 let ret = if m.is_server_streaming {
                     quote!(Ok(Response::new(resp.into_body())))
@@ -70,6 +71,7 @@ impl RpcCodeGenerator {
                 quote! {
                     #method_num => {
                          let fut = async move {
+                            req.consume_quota(#cost)?;
                             let resp = inner.#method_name(req.decode()?).await?;
                             #ret
                         };
@@ -23,7 +23,7 @@ mod options;
 ///     async fn say_hello(&self, request: Request<String>) -> Result<Response<String>, RpcStatus>;
 ///     #[rpc(method = 2)]
 ///     async fn return_error(&self, request: Request<()>) -> Result<Response<()>, RpcStatus>;
-///     #[rpc(method = 3)]
+///     #[rpc(method = 3, cost = 5)]
 ///     async fn get_greetings(&self, request: Request<u32>) -> Result<Streaming<String>, RpcStatus>;
 /// }
 ///
@@ -66,6 +66,10 @@ mod options;
 /// `rpc` attribute
 /// - `method` is a unique number that uniquely identifies each function within the service. Once a `method` is used it
 ///   should never be reused (think protobuf field numbers).
+/// - `cost` (optional, default 1) is the number of quota tokens consumed by each call to the method. Expensive methods
+///   should declare a higher cost. Quotas are configured using `RpcServer::builder().with_peer_quota(...)` and
+///   `with_method_quota(...)`, and are disabled by default. Streaming methods can additionally charge for each item
+///   they send using the handle returned by `Request::quota()`.
 #[proc_macro_attribute]
 pub fn tari_rpc(attr: TokenStream, item: TokenStream) -> TokenStream {
     let options = syn::parse_macro_input!(attr as options::RpcTraitOptions);
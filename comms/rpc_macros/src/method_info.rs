@@ -24,6 +24,7 @@
 pub struct RpcMethodInfo {
     pub method_ident: syn::Ident,
     pub method_num: u32,
+    pub cost: u32,
     pub is_server_streaming: bool,
     pub request_type: Option<syn::Type>,
     pub return_type: Option<syn::Type>,
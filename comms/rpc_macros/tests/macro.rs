@@ -45,7 +45,7 @@ use tower_service::Service;
 pub trait Test: Sync + Send + 'static {
     #[rpc(method = 1)]
     async fn request_response(&self, request: Request<u32>) -> Result<Response<u32>, RpcStatus>;
-    #[rpc(method = 2)]
+    #[rpc(method = 2, cost = 10)]
     async fn server_streaming(&self, request: Request<CustomMessage>) -> Result<Streaming<u32>, RpcStatus>;
     /// Some docs for unit
     #[rpc(method = 3)]
@@ -155,7 +155,7 @@ async fn it_returns_an_error_for_invalid_method_nums() {
 async fn it_generates_client_calls() {
     let (_, sock_client, mut sock_server) = build_multiplexed_connections().await;
     let client = task::spawn(TestClient::connect(framing::canonical(
-        sock_client.get_yamux_control().open_stream().await.unwrap(),
+        sock_client.get_control().open_stream().await.unwrap(),
         1024,
     )));
     let mut sock_server = framing::canonical(sock_server.incoming_mut().next().await.unwrap(), 1024);
@@ -38,7 +38,13 @@ use crate::{
         ConnectionManagerRequester,
         ListenerInfo,
     },
-    connectivity::{ConnectivityEventRx, ConnectivityManager, ConnectivityRequest, ConnectivityRequester},
+    connectivity::{
+        ConnectivityConfig,
+        ConnectivityEventRx,
+        ConnectivityManager,
+        ConnectivityRequest,
+        ConnectivityRequester,
+    },
     multiaddr::Multiaddr,
     noise::NoiseConfig,
     peer_manager::{NodeIdentity, PeerManager},
@@ -228,6 +234,7 @@ impl UnspawnedCommsNode {
             shutdown_signal,
             connection_manager_requester,
             connectivity_requester,
+            connectivity_config,
             listening_info,
             node_identity,
             peer_manager,
@@ -269,6 +276,8 @@ pub struct CommsNode {
     connection_manager_requester: ConnectionManagerRequester,
     /// Requester for the ConnectivityManager
     connectivity_requester: ConnectivityRequester,
+    /// The config used by the ConnectivityManager
+    connectivity_config: ConnectivityConfig,
     /// Node identity for this node
     node_identity: Arc<NodeIdentity>,
     /// Shared PeerManager instance
@@ -322,6 +331,11 @@ impl CommsNode {
         self.connectivity_requester.clone()
     }
 
+    /// Returns the config used by the ConnectivityManager
+    pub fn connectivity_config(&self) -> &ConnectivityConfig {
+        &self.connectivity_config
+    }
+
     /// Returns a new `ShutdownSignal`
     pub fn shutdown_signal(&self) -> ShutdownSignal {
         self.shutdown_signal.clone()
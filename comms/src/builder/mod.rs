@@ -46,6 +46,8 @@ use std::{fs::File, sync::Arc};
 use tari_shutdown::ShutdownSignal;
 use tokio::sync::{broadcast, mpsc};
 
+#[cfg(not(test))]
+use crate::peer_manager::{EncryptedPeerDatabase, PeerManagerError};
 use crate::{
     backoff::{Backoff, BoxedBackoff, ExponentialBackoff},
     connection_manager::{ConnectionManagerConfig, ConnectionManagerRequester},
@@ -61,6 +63,7 @@ use crate::{
 pub struct CommsBuilder {
     peer_storage: Option<CommsDatabase>,
     peer_storage_file_lock: Option<File>,
+    peer_storage_passphrase: Option<String>,
     node_identity: Option<Arc<NodeIdentity>>,
     dial_backoff: BoxedBackoff,
     hidden_service_ctl: Option<tor::HiddenServiceController>,
@@ -75,6 +78,7 @@ impl Default for CommsBuilder {
         Self {
             peer_storage: None,
             peer_storage_file_lock: None,
+            peer_storage_passphrase: None,
             node_identity: None,
             dial_backoff: Box::new(ExponentialBackoff::default()),
             hidden_service_ctl: None,
@@ -194,6 +198,13 @@ impl CommsBuilder {
         self
     }
 
+    /// Encrypt the peer storage database at rest using a key derived from the given passphrase. An existing
+    /// unencrypted database is encrypted when comms is built. This only applies to the LMDB peer database.
+    pub fn with_peer_storage_passphrase(mut self, passphrase: String) -> Self {
+        self.peer_storage_passphrase = Some(passphrase);
+        self
+    }
+
     /// Set the backoff that [ConnectionManager] uses when dialing peers. This is optional. If omitted the default
     /// ExponentialBackoff is used. [ConnectionManager]: crate::connection_manager::next::ConnectionManager
     pub fn with_dial_backoff<T>(mut self, backoff: T) -> Self
@@ -204,17 +215,13 @@ impl CommsBuilder {
 
     fn make_peer_manager(&mut self) -> Result<Arc<PeerManager>, CommsBuilderError> {
         let file_lock = self.peer_storage_file_lock.take();
-
-        match self.peer_storage.take() {
-            Some(storage) => {
-                #[cfg(not(test))]
-                PeerManager::migrate_lmdb(&storage.inner())?;
-
-                let peer_manager = PeerManager::new(storage, file_lock).map_err(CommsBuilderError::PeerManagerError)?;
-                Ok(Arc::new(peer_manager))
-            },
-            None => Err(CommsBuilderError::PeerStorageNotProvided),
-        }
+        let passphrase = self.peer_storage_passphrase.take();
+        let storage = self
+            .peer_storage
+            .take()
+            .ok_or(CommsBuilderError::PeerStorageNotProvided)?;
+        let peer_manager = open_peer_manager(storage, passphrase, file_lock)?;
+        Ok(Arc::new(peer_manager))
     }
 
     /// Build comms services and handles. Services will not be started.
@@ -252,3 +259,36 @@ impl CommsBuilder {
         })
     }
 }
+
+#[cfg(not(test))]
+fn open_peer_manager(
+    storage: CommsDatabase,
+    passphrase: Option<String>,
+    file_lock: Option<File>,
+) -> Result<PeerManager, CommsBuilderError> {
+    let database = storage.inner();
+    PeerManager::migrate_lmdb(&database, passphrase.as_deref())?;
+
+    match passphrase {
+        Some(passphrase) => {
+            let database = EncryptedPeerDatabase::open(database, &passphrase)?;
+            Ok(PeerManager::new_encrypted(database, file_lock)?)
+        },
+        None => {
+            if EncryptedPeerDatabase::is_encrypted(&database)? {
+                return Err(PeerManagerError::PeerDatabaseEncrypted.into());
+            }
+            Ok(PeerManager::new(storage, file_lock)?)
+        },
+    }
+}
+
+/// Unit tests use an in-memory peer database that is never written to disk, so there is nothing to encrypt
+#[cfg(test)]
+fn open_peer_manager(
+    storage: CommsDatabase,
+    _passphrase: Option<String>,
+    file_lock: Option<File>,
+) -> Result<PeerManager, CommsBuilderError> {
+    Ok(PeerManager::new(storage, file_lock)?)
+}
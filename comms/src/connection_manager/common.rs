@@ -211,6 +211,35 @@ fn validate_address(addr: &Multiaddr, allow_test_addrs: bool) -> Result<(), Conn
         None => Ok(()),
     };
 
+    // A TCP address may be followed by /ws to connect using the WebSocket transport
+    let expect_end_of_tcp_address = |mut iter: multiaddr::Iter<'_>| match iter.next() {
+        Some(Protocol::Ws(_)) | None => expect_end_of_address(iter),
+        Some(p) => Err(ConnectionManagerError::InvalidMultiaddr(format!(
+            "Unexpected multiaddress component '{}'",
+            p
+        ))),
+    };
+
+    // A UDP port must be followed by /quic to connect using the QUIC transport
+    let expect_transport_address = |mut iter: multiaddr::Iter<'_>| match iter.next() {
+        Some(Protocol::Udp(0)) => Err(ConnectionManagerError::InvalidMultiaddr(
+            "Cannot connect to a zero UDP port".to_string(),
+        )),
+        Some(Protocol::Udp(_)) => match iter.next() {
+            Some(Protocol::Quic) => expect_end_of_address(iter),
+            _ => Err(ConnectionManagerError::InvalidMultiaddr(
+                "A UDP address must be followed by /quic".to_string(),
+            )),
+        },
+        Some(tcp) => {
+            validate_tcp_port(tcp)?;
+            expect_end_of_tcp_address(iter)
+        },
+        None => Err(ConnectionManagerError::InvalidMultiaddr(
+            "Address does not include a TCP port".to_string(),
+        )),
+    };
+
     /// Returns [true] if the address is a unicast link-local address (fe80::/10).
     #[inline]
     const fn is_unicast_link_local(addr: &Ipv6Addr) -> bool {
@@ -218,15 +247,7 @@ fn validate_address(addr: &Multiaddr, allow_test_addrs: bool) -> Result<(), Conn
     }
 
     match proto {
-        Protocol::Dns4(_) | Protocol::Dns6(_) | Protocol::Dnsaddr(_) => {
-            let tcp = addr_iter.next().ok_or_else(|| {
-                ConnectionManagerError::InvalidMultiaddr("Address does not include a TCP port".to_string())
-            })?;
-
-            validate_tcp_port(tcp)?;
-
-            expect_end_of_address(addr_iter)
-        },
+        Protocol::Dns4(_) | Protocol::Dns6(_) | Protocol::Dnsaddr(_) => expect_transport_address(addr_iter),
 
         Protocol::Ip4(addr)
             if !allow_test_addrs && (addr.is_loopback() || addr.is_link_local() || addr.is_unspecified()) =>
@@ -242,14 +263,7 @@ fn validate_address(addr: &Multiaddr, allow_test_addrs: bool) -> Result<(), Conn
                 "Non-global IP addresses are invalid".to_string(),
             ))
         },
-        Protocol::Ip4(_) | Protocol::Ip6(_) => {
-            let tcp = addr_iter.next().ok_or_else(|| {
-                ConnectionManagerError::InvalidMultiaddr("Address does not include a TCP port".to_string())
-            })?;
-
-            validate_tcp_port(tcp)?;
-            expect_end_of_address(addr_iter)
-        },
+        Protocol::Ip4(_) | Protocol::Ip6(_) => expect_transport_address(addr_iter),
         Protocol::Memory(0) => Err(ConnectionManagerError::InvalidMultiaddr(
             "Cannot connect to a zero memory port".to_string(),
         )),
@@ -301,10 +315,20 @@ mod test {
                 .parse()
                 .unwrap(),
             multiaddr!(Dnsaddr("mike-magic-nodes.com"), Tcp(1u16)),
+            "/ip4/172.0.0.1/tcp/1/ws".parse().unwrap(),
+            "/dns4/mike-magic-nodes.com/tcp/1/ws".parse().unwrap(),
+            "/ip4/172.0.0.1/udp/1/quic".parse().unwrap(),
+            "/dns4/mike-magic-nodes.com/udp/1/quic".parse().unwrap(),
         ];
 
         let invalid = &[
             multiaddr!(Ip4([127, 0, 0, 1]), Tcp(1u16)),
+            "/ip4/127.0.0.1/tcp/1/ws".parse().unwrap(),
+            "/ip4/172.0.0.1/tcp/1/ws/ws".parse().unwrap(),
+            "/ip4/127.0.0.1/udp/1/quic".parse().unwrap(),
+            "/ip4/172.0.0.1/udp/1".parse().unwrap(),
+            "/ip4/172.0.0.1/udp/0/quic".parse().unwrap(),
+            "/ip4/172.0.0.1/tcp/1/quic".parse().unwrap(),
             multiaddr!(Ip4([169, 254, 0, 1]), Tcp(1u16)),
             multiaddr!(Ip4([172, 0, 0, 1])),
             "/onion/aaimaq4ygg2iegci:1234/http".parse().unwrap(),
@@ -332,6 +356,7 @@ mod test {
                 .unwrap(),
             multiaddr!(Dnsaddr("mike-magic-nodes.com"), Tcp(1u16)),
             multiaddr!(Memory(1234u64)),
+            "/ip4/127.0.0.1/udp/1/quic".parse().unwrap(),
         ];
 
         let invalid = &[
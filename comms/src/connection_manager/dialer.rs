@@ -51,7 +51,6 @@ use crate::{
         peer_connection,
     },
     multiaddr::Multiaddr,
-    multiplexing::Yamux,
     noise::{NoiseConfig, NoiseSocket},
     peer_manager::{NodeId, NodeIdentity, Peer, PeerFeatures, PeerManager},
     protocol::ProtocolId,
@@ -426,11 +425,12 @@ where
             peer_node_id.short_str()
         );
 
-        let muxer = Yamux::upgrade_connection(socket, CONNECTION_DIRECTION)
+        let muxer = TTransport::upgrade_multiplexed(socket, CONNECTION_DIRECTION)
+            .await
             .map_err(|err| ConnectionManagerError::YamuxUpgradeFailure(err.to_string()))?;
 
         if cancel_signal.is_terminated() {
-            muxer.get_yamux_control().close().await?;
+            muxer.get_control().close().await?;
             return Err(ConnectionManagerError::DialCancelled);
         }
 
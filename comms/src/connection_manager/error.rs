@@ -50,7 +50,7 @@ pub enum ConnectionManagerError {
     ConnectFailedMaximumAttemptsReached,
     #[error("Yamux connection error: {0}")]
     YamuxConnectionError(String),
-    #[error("Failed to perform yamux upgrade on socket: {0}")]
+    #[error("Failed to perform multiplexer upgrade on connection: {0}")]
     YamuxUpgradeFailure(String),
     #[error("Establisher channel is closed or full")]
     EstablisherChannelError,
@@ -58,7 +58,6 @@ use crate::{
         wire_mode::{WireMode, LIVENESS_WIRE_MODE},
     },
     multiaddr::Multiaddr,
-    multiplexing::Yamux,
     noise::NoiseConfig,
     peer_manager::{NodeIdentity, PeerFeatures},
     protocol::ProtocolId,
@@ -410,7 +409,8 @@ where
             peer_node_id.short_str()
         );
 
-        let muxer = Yamux::upgrade_connection(noise_socket, CONNECTION_DIRECTION)
+        let muxer = TTransport::upgrade_multiplexed(noise_socket, CONNECTION_DIRECTION)
+            .await
             .map_err(|err| ConnectionManagerError::YamuxUpgradeFailure(err.to_string()))?;
 
         peer_connection::create(
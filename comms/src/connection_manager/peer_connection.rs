@@ -57,7 +57,7 @@ use crate::protocol::rpc::{
 use crate::{
     framing,
     framing::CanonicalFraming,
-    multiplexing::{Control, IncomingSubstreams, Substream, Yamux},
+    multiplexing::{Control, IncomingSubstreams, Multiplexed, Substream},
     peer_manager::{NodeId, PeerFeatures},
     protocol::{ProtocolId, ProtocolNegotiation},
     runtime,
@@ -69,7 +69,7 @@ const LOG_TARGET: &str = "comms::connection_manager::peer_connection";
 static ID_COUNTER: AtomicUsize = AtomicUsize::new(0);
 
 pub fn create(
-    connection: Yamux,
+    connection: Multiplexed,
     peer_addr: Multiaddr,
     peer_node_id: NodeId,
     peer_features: PeerFeatures,
@@ -333,7 +333,7 @@ impl PeerConnectionActor {
         id: ConnectionId,
         peer_node_id: NodeId,
         direction: ConnectionDirection,
-        connection: Yamux,
+        connection: Multiplexed,
         request_rx: mpsc::Receiver<PeerConnectionRequest>,
         event_notifier: mpsc::Sender<ConnectionManagerEvent>,
         our_supported_protocols: Vec<ProtocolId>,
@@ -343,7 +343,7 @@ impl PeerConnectionActor {
             id,
             peer_node_id,
             direction,
-            control: connection.get_yamux_control(),
+            control: connection.get_control(),
             incoming_substreams: connection.into_incoming(),
             request_rx,
             event_notifier,
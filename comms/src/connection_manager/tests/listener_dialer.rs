@@ -45,7 +45,7 @@ use crate::{
     protocol::ProtocolId,
     runtime,
     test_utils::{build_peer_manager, node_identity::build_node_identity},
-    transports::MemoryTransport,
+    transports::{MemoryTransport, QuicTransport, WebSocketTransport},
 };
 
 #[runtime::test]
@@ -255,3 +255,167 @@ async fn banned() {
 
     timeout(Duration::from_secs(5), dialer_fut).await.unwrap().unwrap();
 }
+
+#[runtime::test]
+async fn smoke_websocket() {
+    let rt_handle = runtime::current();
+    let (event_tx, mut event_rx) = mpsc::channel(10);
+    let mut shutdown = Shutdown::new();
+
+    let node_identity1 = build_node_identity(PeerFeatures::COMMUNICATION_NODE);
+    let noise_config1 = NoiseConfig::new(node_identity1.clone());
+    let expected_proto = ProtocolId::from_static(b"/tari/test-proto");
+    let supported_protocols = vec![expected_proto.clone()];
+    let mut listener = PeerListener::new(
+        Default::default(),
+        "/ip4/127.0.0.1/tcp/0/ws".parse().unwrap(),
+        WebSocketTransport::new(),
+        noise_config1,
+        event_tx.clone(),
+        build_peer_manager(),
+        node_identity1.clone(),
+        shutdown.to_signal(),
+    );
+    listener.set_supported_protocols(supported_protocols.clone());
+
+    let address = listener.listen().await.unwrap();
+    unpack_enum!(Protocol::Ws(_path) = address.iter().last().unwrap());
+
+    let node_identity2 = build_node_identity(PeerFeatures::COMMUNICATION_NODE);
+    let noise_config2 = NoiseConfig::new(node_identity2.clone());
+    let (request_tx, request_rx) = mpsc::channel(1);
+    let mut dialer = Dialer::new(
+        ConnectionManagerConfig::default(),
+        node_identity2.clone(),
+        build_peer_manager(),
+        WebSocketTransport::new(),
+        noise_config2,
+        ConstantBackoff::new(Duration::from_millis(100)),
+        request_rx,
+        event_tx,
+        shutdown.to_signal(),
+    );
+    dialer.set_supported_protocols(supported_protocols);
+
+    let dialer_fut = rt_handle.spawn(dialer.run());
+
+    let mut peer = node_identity1.to_peer();
+    peer.addresses = vec![address].into();
+    peer.set_id_for_test(1);
+
+    let (reply_tx, reply_rx) = oneshot::channel();
+    request_tx
+        .send(DialerRequest::Dial(Box::new(peer), Some(reply_tx)))
+        .await
+        .unwrap();
+
+    let mut outbound_peer_conn = reply_rx.await.unwrap().unwrap();
+
+    {
+        let mut out_stream = outbound_peer_conn.open_substream(&expected_proto).await.unwrap();
+        out_stream.stream.write_all(b"HELLO").await.unwrap();
+        out_stream.stream.flush().await.unwrap();
+    }
+
+    unpack_enum!(ConnectionManagerEvent::PeerConnected(conn1) = event_rx.recv().await.unwrap());
+    unpack_enum!(ConnectionManagerEvent::PeerConnected(_conn2) = event_rx.recv().await.unwrap());
+
+    let listen_event = event_rx.recv().await.unwrap();
+    {
+        unpack_enum!(ConnectionManagerEvent::NewInboundSubstream(node_id, proto, in_stream) = listen_event);
+        assert_eq!(&node_id, node_identity2.node_id());
+        assert_eq!(proto, expected_proto);
+
+        let mut buf = [0u8; 5];
+        in_stream.read_exact(&mut buf).await.unwrap();
+        assert_eq!(buf, *b"HELLO");
+    }
+
+    conn1.disconnect().await.unwrap();
+
+    shutdown.trigger();
+
+    timeout(Duration::from_secs(5), dialer_fut).await.unwrap().unwrap();
+}
+
+#[runtime::test]
+async fn smoke_quic() {
+    let rt_handle = runtime::current();
+    let (event_tx, mut event_rx) = mpsc::channel(10);
+    let mut shutdown = Shutdown::new();
+
+    let node_identity1 = build_node_identity(PeerFeatures::COMMUNICATION_NODE);
+    let noise_config1 = NoiseConfig::new(node_identity1.clone());
+    let expected_proto = ProtocolId::from_static(b"/tari/test-proto");
+    let supported_protocols = vec![expected_proto.clone()];
+    let mut listener = PeerListener::new(
+        Default::default(),
+        "/ip4/127.0.0.1/udp/0/quic".parse().unwrap(),
+        QuicTransport::new().unwrap(),
+        noise_config1,
+        event_tx.clone(),
+        build_peer_manager(),
+        node_identity1.clone(),
+        shutdown.to_signal(),
+    );
+    listener.set_supported_protocols(supported_protocols.clone());
+
+    let address = listener.listen().await.unwrap();
+    assert!(matches!(address.iter().last(), Some(Protocol::Quic)));
+
+    let node_identity2 = build_node_identity(PeerFeatures::COMMUNICATION_NODE);
+    let noise_config2 = NoiseConfig::new(node_identity2.clone());
+    let (request_tx, request_rx) = mpsc::channel(1);
+    let mut dialer = Dialer::new(
+        ConnectionManagerConfig::default(),
+        node_identity2.clone(),
+        build_peer_manager(),
+        QuicTransport::new().unwrap(),
+        noise_config2,
+        ConstantBackoff::new(Duration::from_millis(100)),
+        request_rx,
+        event_tx,
+        shutdown.to_signal(),
+    );
+    dialer.set_supported_protocols(supported_protocols);
+
+    let dialer_fut = rt_handle.spawn(dialer.run());
+
+    let mut peer = node_identity1.to_peer();
+    peer.addresses = vec![address].into();
+    peer.set_id_for_test(1);
+
+    let (reply_tx, reply_rx) = oneshot::channel();
+    request_tx
+        .send(DialerRequest::Dial(Box::new(peer), Some(reply_tx)))
+        .await
+        .unwrap();
+
+    let mut outbound_peer_conn = reply_rx.await.unwrap().unwrap();
+
+    {
+        let mut out_stream = outbound_peer_conn.open_substream(&expected_proto).await.unwrap();
+        out_stream.stream.write_all(b"HELLO").await.unwrap();
+        out_stream.stream.flush().await.unwrap();
+    }
+
+    unpack_enum!(ConnectionManagerEvent::PeerConnected(conn1) = event_rx.recv().await.unwrap());
+    unpack_enum!(ConnectionManagerEvent::PeerConnected(_conn2) = event_rx.recv().await.unwrap());
+
+    let listen_event = event_rx.recv().await.unwrap();
+    {
+        unpack_enum!(ConnectionManagerEvent::NewInboundSubstream(node_id, proto, in_stream) = listen_event);
+        assert_eq!(&node_id, node_identity2.node_id());
+        assert_eq!(proto, expected_proto);
+
+        let mut buf = [0u8; 5];
+        in_stream.read_exact(&mut buf).await.unwrap();
+        assert_eq!(buf, *b"HELLO");
+    }
+
+    conn1.disconnect().await.unwrap();
+
+    shutdown.trigger();
+
+    timeout(Duration::from_secs(5), dialer_fut).await.unwrap().unwrap();
+}
@@ -45,6 +45,20 @@ pub struct ConnectivityConfig {
     /// next connection attempt.
     /// Default: 24 hours
     pub expire_peer_last_seen_duration: Duration,
+    /// The reputation score at or below which a peer is automatically banned. This should not be lower than
+    /// `MIN_REPUTATION_SCORE` (-1000), otherwise peers are never banned for a low reputation.
+    /// Default: -100
+    pub reputation_ban_threshold: i32,
+    /// The time taken for a peer's reputation score to decay by half.
+    /// Default: 1 hour
+    pub reputation_half_life: Duration,
+    /// The length of the first ban of a peer with a low reputation. Each subsequent ban is twice as long, up to
+    /// `reputation_max_ban_duration`.
+    /// Default: 30 minutes
+    pub reputation_min_ban_duration: Duration,
+    /// The maximum length of a ban due to a low reputation.
+    /// Default: 7 days
+    pub reputation_max_ban_duration: Duration,
 }
 
 impl Default for ConnectivityConfig {
@@ -57,6 +71,10 @@ impl Default for ConnectivityConfig {
             max_failures_mark_offline: 1,
             connection_tie_break_linger: Duration::from_secs(2),
             expire_peer_last_seen_duration: Duration::from_secs(24 * 60 * 60),
+            reputation_ban_threshold: -100,
+            reputation_half_life: Duration::from_secs(60 * 60),
+            reputation_min_ban_duration: Duration::from_secs(30 * 60),
+            reputation_max_ban_duration: Duration::from_secs(7 * 24 * 60 * 60),
         }
     }
 }
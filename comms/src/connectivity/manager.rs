@@ -26,6 +26,7 @@ use std::{
     time::{Duration, Instant},
 };
 
+use chrono::Utc;
 use log::*;
 use nom::lib::std::collections::hash_map::Entry;
 use tari_shutdown::ShutdownSignal;
@@ -37,6 +38,7 @@ use super::{
     connection_pool::{ConnectionPool, ConnectionStatus},
     connection_stats::PeerConnectionStats,
     error::ConnectivityError,
+    reputation::{reputation_ban_duration, ReputationEvent},
     requester::{ConnectivityEvent, ConnectivityRequest},
     selection::ConnectivitySelection,
     ConnectivityEventTx,
@@ -301,6 +303,14 @@ impl ConnectivityManagerActor {
                     );
                 }
             },
+            ReportPeer(node_id, event) => {
+                if let Err(err) = self.handle_reputation_event(&node_id, event).await {
+                    error!(
+                        target: LOG_TARGET,
+                        "Error when handling reputation event {} for peer {}: {:?}", event, node_id, err
+                    );
+                }
+            },
             AddPeerToAllowList(node_id) => {
                 if !self.allow_list.contains(&node_id) {
                     self.allow_list.push(node_id)
@@ -824,6 +834,86 @@ impl ConnectivityManagerActor {
         Ok(())
     }
 
+    async fn handle_reputation_event(
+        &mut self,
+        node_id: &NodeId,
+        event: ReputationEvent,
+    ) -> Result<(), ConnectivityError> {
+        #[cfg(feature = "metrics")]
+        super::metrics::reputation_events_counter(event).inc();
+
+        let now = Utc::now().naive_utc();
+        let half_life = self.config.reputation_half_life;
+        let reputation = self
+            .peer_manager
+            .update_peer_reputation(node_id, |reputation| {
+                reputation.adjust(event.score_delta(), now, half_life);
+            })
+            .await?;
+        debug!(
+            target: LOG_TARGET,
+            "Peer {} reputation event {} ({:+}). Score is {}",
+            node_id,
+            event,
+            event.score_delta(),
+            reputation.score()
+        );
+
+        if reputation.score() > self.config.reputation_ban_threshold {
+            return Ok(());
+        }
+
+        if self.allow_list.contains(node_id) {
+            info!(
+                target: LOG_TARGET,
+                "Peer {} has a reputation score of {} but is excluded from being banned as it was found in the \
+                 AllowList",
+                node_id,
+                reputation.score()
+            );
+            return Ok(());
+        }
+
+        let duration = reputation_ban_duration(&self.config, reputation.num_bans());
+        // Do not shorten a longer ban that is already in place, e.g. a ban for sending invalid data
+        let ban_ends = chrono::Duration::from_std(duration)
+            .ok()
+            .and_then(|duration| now.checked_add_signed(duration));
+        let banned_until = self
+            .peer_manager
+            .find_by_node_id(node_id)
+            .await?
+            .and_then(|peer| peer.banned_until().copied());
+        let is_banned_for_longer =
+            matches!((banned_until, ban_ends), (Some(banned_until), Some(ban_ends)) if banned_until >= ban_ends);
+        if is_banned_for_longer {
+            debug!(
+                target: LOG_TARGET,
+                "Peer {} has a reputation score of {} but is already banned for longer than {}",
+                node_id,
+                reputation.score(),
+                format_duration(duration)
+            );
+        } else {
+            self.ban_peer(
+                node_id,
+                duration,
+                format!(
+                    "Reputation score {} is at or below the threshold of {} (last event: {})",
+                    reputation.score(),
+                    self.config.reputation_ban_threshold,
+                    event
+                ),
+            )
+            .await?;
+        }
+        self.peer_manager
+            .update_peer_reputation(node_id, |reputation| reputation.record_ban(now))
+            .await?;
+
+        Ok(())
+    }
+
     fn cleanup_connection_stats(&mut self) {
         let mut to_remove = Vec::new();
         for node_id in self.connection_stats.keys() {
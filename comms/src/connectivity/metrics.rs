@@ -23,6 +23,7 @@
 use once_cell::sync::Lazy;
 use tari_metrics::{IntCounter, IntCounterVec, IntGauge, IntGaugeVec};
 
+use super::ReputationEvent;
 use crate::{connection_manager::ConnectionDirection, peer_manager::NodeId};
 
 pub fn connections(direction: ConnectionDirection) -> IntGauge {
@@ -57,3 +58,16 @@ pub fn banned_peers_counter(peer: &NodeId) -> IntCounter {
 
     METER.with_label_values(&[peer.to_string().as_str()])
 }
+
+pub fn reputation_events_counter(event: ReputationEvent) -> IntCounter {
+    static METER: Lazy<IntCounterVec> = Lazy::new(|| {
+        tari_metrics::register_int_counter_vec(
+            "comms::connectivity::reputation_events",
+            "The number of reported peer reputation events by event type",
+            &["event"],
+        )
+        .unwrap()
+    });
+
+    METER.with_label_values(&[event.as_str()])
+}
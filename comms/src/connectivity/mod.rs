@@ -37,6 +37,9 @@ pub use manager::ConnectivityStatus;
 #[cfg(feature = "metrics")]
 mod metrics;
 
+mod reputation;
+pub use reputation::ReputationEvent;
+
 mod requester;
 pub(crate) use requester::ConnectivityRequest;
 pub use requester::{ConnectivityEvent, ConnectivityEventRx, ConnectivityEventTx, ConnectivityRequester};
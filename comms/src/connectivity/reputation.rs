@@ -0,0 +1,99 @@
+//  Copyright 2022, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{fmt, time::Duration};
+
+use super::ConnectivityConfig;
+use crate::peer_manager::{MAX_REPUTATION_SCORE, MIN_REPUTATION_SCORE};
+
+/// Events that services report to the connectivity manager to adjust the reputation of a peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReputationEvent {
+    /// The peer sent an invalid block or block header. This always takes the score to the minimum, so that the peer is
+    /// banned however much credit it has built up.
+    InvalidBlock,
+    /// The peer sent a malformed, invalid or unexpected RPC response
+    BadRpcResponse,
+    /// The peer did not respond within the expected time
+    Timeout,
+    /// The peer provided useful data during a sync
+    UsefulSync,
+}
+
+impl ReputationEvent {
+    /// The amount by which the event adjusts the reputation score of the peer
+    pub fn score_delta(self) -> i32 {
+        use ReputationEvent::*;
+        match self {
+            InvalidBlock => MIN_REPUTATION_SCORE - MAX_REPUTATION_SCORE,
+            BadRpcResponse => -25,
+            Timeout => -5,
+            UsefulSync => 10,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        use ReputationEvent::*;
+        match self {
+            InvalidBlock => "InvalidBlock",
+            BadRpcResponse => "BadRpcResponse",
+            Timeout => "Timeout",
+            UsefulSync => "UsefulSync",
+        }
+    }
+}
+
+impl fmt::Display for ReputationEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Returns the ban duration for a peer that has previously been banned `num_bans` times for having a low reputation.
+/// The duration doubles with each ban, up to `reputation_max_ban_duration`.
+pub(super) fn reputation_ban_duration(config: &ConnectivityConfig, num_bans: u32) -> Duration {
+    let multiplier = 2u32.checked_pow(num_bans).unwrap_or(u32::MAX);
+    config
+        .reputation_min_ban_duration
+        .checked_mul(multiplier)
+        .unwrap_or(config.reputation_max_ban_duration)
+        .min(config.reputation_max_ban_duration)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_escalates_the_ban_duration() {
+        let config = ConnectivityConfig {
+            reputation_min_ban_duration: Duration::from_secs(60),
+            reputation_max_ban_duration: Duration::from_secs(60 * 60),
+            ..Default::default()
+        };
+        assert_eq!(reputation_ban_duration(&config, 0), Duration::from_secs(60));
+        assert_eq!(reputation_ban_duration(&config, 1), Duration::from_secs(120));
+        assert_eq!(reputation_ban_duration(&config, 3), Duration::from_secs(480));
+        assert_eq!(reputation_ban_duration(&config, 6), Duration::from_secs(60 * 60));
+        assert_eq!(reputation_ban_duration(&config, u32::MAX), Duration::from_secs(60 * 60));
+    }
+}
@@ -38,6 +38,7 @@ use super::{
     error::ConnectivityError,
     manager::ConnectivityStatus,
     ConnectivitySelection,
+    ReputationEvent,
 };
 use crate::{connection_manager::ConnectionManagerError, peer_manager::NodeId, PeerConnection};
 
@@ -93,6 +94,7 @@ pub enum ConnectivityRequest {
     GetAllConnectionStates(oneshot::Sender<Vec<PeerConnectionState>>),
     GetActiveConnections(oneshot::Sender<Vec<PeerConnection>>),
     BanPeer(NodeId, Duration, String),
+    ReportPeer(NodeId, ReputationEvent),
     AddPeerToAllowList(NodeId),
     RemovePeerFromAllowList(NodeId),
 }
@@ -239,6 +241,16 @@ impl ConnectivityRequester {
             .await
     }
 
+    /// Reports an event that adjusts the reputation of the peer. Peers whose reputation falls to the configured
+    /// threshold are banned.
+    pub async fn report_peer(&mut self, node_id: NodeId, event: ReputationEvent) -> Result<(), ConnectivityError> {
+        self.sender
+            .send(ConnectivityRequest::ReportPeer(node_id, event))
+            .await
+            .map_err(|_| ConnectivityError::ActorDisconnected)?;
+        Ok(())
+    }
+
     pub async fn add_peer_to_allow_list(&mut self, node_id: NodeId) -> Result<(), ConnectivityError> {
         self.sender
             .send(ConnectivityRequest::AddPeerToAllowList(node_id))
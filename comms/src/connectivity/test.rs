@@ -32,11 +32,12 @@ use super::{
     manager::ConnectivityManager,
     requester::{ConnectivityEvent, ConnectivityRequester},
     selection::ConnectivitySelection,
+    ReputationEvent,
 };
 use crate::{
     connection_manager::{ConnectionManagerError, ConnectionManagerEvent},
     connectivity::ConnectivityEventRx,
-    peer_manager::{Peer, PeerFeatures},
+    peer_manager::{Peer, PeerFeatures, MAX_REPUTATION_SCORE},
     runtime,
     runtime::task,
     test_utils::{
@@ -284,6 +285,123 @@ async fn ban_peer() {
     assert!(conn.is_none());
 }
 
+#[runtime::test]
+async fn ban_peer_with_low_reputation() {
+    let (mut connectivity, mut event_stream, _, peer_manager, _, _shutdown) =
+        setup_connectivity_manager(ConnectivityConfig {
+            reputation_ban_threshold: -100,
+            reputation_min_ban_duration: Duration::from_secs(60),
+            ..Default::default()
+        });
+    let peer = add_test_peers(&peer_manager, 1).await.pop().unwrap();
+
+    let mut events = collect_try_recv!(event_stream, take = 1, timeout = Duration::from_secs(10));
+    unpack_enum!(ConnectivityEvent::ConnectivityStateInitialized = events.remove(0));
+
+    connectivity
+        .report_peer(peer.node_id.clone(), ReputationEvent::UsefulSync)
+        .await
+        .unwrap();
+    for _ in 0..4 {
+        connectivity
+            .report_peer(peer.node_id.clone(), ReputationEvent::BadRpcResponse)
+            .await
+            .unwrap();
+    }
+    // Wait for the requests to be processed
+    connectivity.wait_started().await.unwrap();
+    let peer = peer_manager.find_by_node_id(&peer.node_id).await.unwrap().unwrap();
+    assert!(!peer.is_banned());
+    assert_eq!(peer.reputation.score(), -90);
+
+    connectivity
+        .report_peer(peer.node_id.clone(), ReputationEvent::BadRpcResponse)
+        .await
+        .unwrap();
+
+    let event = collect_try_recv!(event_stream, take = 1, timeout = Duration::from_secs(10))
+        .pop()
+        .unwrap();
+    unpack_enum!(ConnectivityEvent::PeerBanned(node_id) = event);
+    assert_eq!(node_id, peer.node_id);
+
+    connectivity.wait_started().await.unwrap();
+    let peer = peer_manager.find_by_node_id(&peer.node_id).await.unwrap().unwrap();
+    assert!(peer.is_banned());
+    assert_eq!(peer.reputation.score(), 0);
+    assert_eq!(peer.reputation.num_bans(), 1);
+}
+
+#[runtime::test]
+async fn ban_peer_with_maximum_reputation_for_invalid_block() {
+    let (mut connectivity, mut event_stream, _, peer_manager, _, _shutdown) =
+        setup_connectivity_manager(ConnectivityConfig {
+            reputation_ban_threshold: -100,
+            reputation_min_ban_duration: Duration::from_secs(60),
+            ..Default::default()
+        });
+    let peer = add_test_peers(&peer_manager, 1).await.pop().unwrap();
+
+    let mut events = collect_try_recv!(event_stream, take = 1, timeout = Duration::from_secs(10));
+    unpack_enum!(ConnectivityEvent::ConnectivityStateInitialized = events.remove(0));
+
+    for _ in 0..20 {
+        connectivity
+            .report_peer(peer.node_id.clone(), ReputationEvent::UsefulSync)
+            .await
+            .unwrap();
+    }
+    connectivity.wait_started().await.unwrap();
+    let peer = peer_manager.find_by_node_id(&peer.node_id).await.unwrap().unwrap();
+    assert_eq!(peer.reputation.score(), MAX_REPUTATION_SCORE);
+
+    // A single invalid block outweighs any credit built up by the peer
+    connectivity
+        .report_peer(peer.node_id.clone(), ReputationEvent::InvalidBlock)
+        .await
+        .unwrap();
+
+    let event = collect_try_recv!(event_stream, take = 1, timeout = Duration::from_secs(10))
+        .pop()
+        .unwrap();
+    unpack_enum!(ConnectivityEvent::PeerBanned(node_id) = event);
+    assert_eq!(node_id, peer.node_id);
+
+    connectivity.wait_started().await.unwrap();
+    let peer = peer_manager.find_by_node_id(&peer.node_id).await.unwrap().unwrap();
+    assert!(peer.is_banned());
+    assert_eq!(peer.reputation.num_bans(), 1);
+}
+
+#[runtime::test]
+async fn reputation_ban_does_not_shorten_existing_ban() {
+    let (mut connectivity, mut event_stream, _, peer_manager, _, _shutdown) =
+        setup_connectivity_manager(ConnectivityConfig {
+            reputation_ban_threshold: -100,
+            reputation_min_ban_duration: Duration::from_secs(60),
+            ..Default::default()
+        });
+    let peer = add_test_peers(&peer_manager, 1).await.pop().unwrap();
+
+    let mut events = collect_try_recv!(event_stream, take = 1, timeout = Duration::from_secs(10));
+    unpack_enum!(ConnectivityEvent::ConnectivityStateInitialized = events.remove(0));
+
+    connectivity
+        .ban_peer(peer.node_id.clone(), "Propagated an invalid block".to_string())
+        .await
+        .unwrap();
+    connectivity
+        .report_peer(peer.node_id.clone(), ReputationEvent::InvalidBlock)
+        .await
+        .unwrap();
+    connectivity.wait_started().await.unwrap();
+
+    let peer = peer_manager.find_by_node_id(&peer.node_id).await.unwrap().unwrap();
+    assert!(peer.is_banned());
+    assert_eq!(peer.reason_banned(), "Propagated an invalid block");
+    assert_eq!(peer.reputation.num_bans(), 1);
+}
+
 #[runtime::test]
 async fn peer_selection() {
     let config = ConnectivityConfig {
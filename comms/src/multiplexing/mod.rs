@@ -23,5 +23,11 @@
 #[cfg(feature = "metrics")]
 mod metrics;
 
+mod multiplexed;
+pub use multiplexed::{Control, IncomingSubstreams, Multiplexed, Substream};
+
+mod quic;
+pub use quic::{Quic, QuicStream};
+
 mod yamux;
-pub use self::yamux::{ConnectionError, Control, IncomingSubstreams, Substream, Yamux};
+pub use self::yamux::{ConnectionError, Yamux};
@@ -0,0 +1,252 @@
+// Copyright 2022, The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{io, pin::Pin, task::Poll};
+
+use futures::{task::Context, Stream};
+use tari_shutdown::Shutdown;
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    sync::mpsc,
+};
+use tokio_util::compat::{Compat, FuturesAsyncReadCompatExt};
+
+use super::{quic, ConnectionError, QuicStream};
+use crate::{
+    stream_id,
+    stream_id::StreamId,
+    utils::atomic_ref_counter::{AtomicRefCounter, AtomicRefCounterGuard},
+};
+
+/// A connection over which substreams are multiplexed. Substreams are either yamux streams over a single socket or
+/// native QUIC streams, the rest of comms does not need to know which.
+pub struct Multiplexed {
+    control: Control,
+    incoming: IncomingSubstreams,
+    substream_counter: AtomicRefCounter,
+}
+
+impl Multiplexed {
+    pub(super) fn new(control: Control, incoming: IncomingSubstreams, substream_counter: AtomicRefCounter) -> Self {
+        Self {
+            control,
+            incoming,
+            substream_counter,
+        }
+    }
+
+    /// Get the control struct used to open substreams and close the connection
+    pub fn get_control(&self) -> Control {
+        self.control.clone()
+    }
+
+    /// Returns a mutable reference to a `Stream` that emits substreams initiated by the remote
+    pub fn incoming_mut(&mut self) -> &mut IncomingSubstreams {
+        &mut self.incoming
+    }
+
+    /// Consumes this object and returns a `Stream` that emits substreams initiated by the remote
+    pub fn into_incoming(self) -> IncomingSubstreams {
+        self.incoming
+    }
+
+    /// Return the number of active substreams
+    pub fn substream_count(&self) -> usize {
+        self.substream_counter.get()
+    }
+
+    /// Return a SubstreamCounter for this connection
+    pub(crate) fn substream_counter(&self) -> AtomicRefCounter {
+        self.substream_counter.clone()
+    }
+}
+
+#[derive(Clone)]
+pub struct Control {
+    inner: ControlInner,
+    substream_counter: AtomicRefCounter,
+}
+
+#[derive(Clone)]
+enum ControlInner {
+    Yamux(yamux::Control),
+    Quic(quinn::Connection),
+}
+
+impl Control {
+    pub(super) fn yamux(inner: yamux::Control, substream_counter: AtomicRefCounter) -> Self {
+        Self {
+            inner: ControlInner::Yamux(inner),
+            substream_counter,
+        }
+    }
+
+    pub(super) fn quic(connection: quinn::Connection, substream_counter: AtomicRefCounter) -> Self {
+        Self {
+            inner: ControlInner::Quic(connection),
+            substream_counter,
+        }
+    }
+
+    /// Open a new stream to the remote.
+    pub async fn open_stream(&mut self) -> Result<Substream, ConnectionError> {
+        // Ensure that this counts as used while the substream is being opened
+        let counter_guard = self.substream_counter.new_guard();
+        let stream = match &mut self.inner {
+            ControlInner::Yamux(control) => SubstreamInner::Yamux(control.open_stream().await?.compat()),
+            ControlInner::Quic(connection) => {
+                let (send, recv) = connection.open_bi().await.map_err(quic::to_connection_error)?;
+                SubstreamInner::Quic(QuicStream::new(send, recv))
+            },
+        };
+        Ok(Substream {
+            stream,
+            _counter_guard: counter_guard,
+        })
+    }
+
+    /// Close the connection.
+    pub async fn close(&mut self) -> Result<(), ConnectionError> {
+        match &mut self.inner {
+            ControlInner::Yamux(control) => control.close().await,
+            ControlInner::Quic(connection) => {
+                quic::close_connection(connection);
+                Ok(())
+            },
+        }
+    }
+
+    pub fn substream_count(&self) -> usize {
+        self.substream_counter.get()
+    }
+
+    pub(crate) fn substream_counter(&self) -> AtomicRefCounter {
+        self.substream_counter.clone()
+    }
+}
+
+pub struct IncomingSubstreams {
+    inner: mpsc::Receiver<SubstreamInner>,
+    substream_counter: AtomicRefCounter,
+    shutdown: Shutdown,
+}
+
+impl IncomingSubstreams {
+    pub(super) fn new(
+        inner: mpsc::Receiver<SubstreamInner>,
+        substream_counter: AtomicRefCounter,
+        shutdown: Shutdown,
+    ) -> Self {
+        Self {
+            inner,
+            substream_counter,
+            shutdown,
+        }
+    }
+
+    pub fn substream_count(&self) -> usize {
+        self.substream_counter.get()
+    }
+}
+
+impl Stream for IncomingSubstreams {
+    type Item = Substream;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match futures::ready!(Pin::new(&mut self.inner).poll_recv(cx)) {
+            Some(stream) => Poll::Ready(Some(Substream {
+                stream,
+                _counter_guard: self.substream_counter.new_guard(),
+            })),
+            None => Poll::Ready(None),
+        }
+    }
+}
+
+impl Drop for IncomingSubstreams {
+    fn drop(&mut self) {
+        let _ = self.shutdown.trigger();
+    }
+}
+
+#[derive(Debug)]
+pub struct Substream {
+    stream: SubstreamInner,
+    _counter_guard: AtomicRefCounterGuard,
+}
+
+#[derive(Debug)]
+pub(super) enum SubstreamInner {
+    Yamux(Compat<yamux::Stream>),
+    Quic(QuicStream),
+}
+
+impl StreamId for Substream {
+    fn stream_id(&self) -> stream_id::Id {
+        match &self.stream {
+            SubstreamInner::Yamux(stream) => stream.get_ref().id().into(),
+            SubstreamInner::Quic(stream) => stream.stream_id(),
+        }
+    }
+}
+
+impl AsyncRead for Substream {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let result = match &mut self.stream {
+            SubstreamInner::Yamux(stream) => Pin::new(stream).poll_read(cx, buf),
+            SubstreamInner::Quic(stream) => Pin::new(stream).poll_read(cx, buf),
+        };
+        match result {
+            Poll::Ready(Ok(())) => {
+                #[cfg(feature = "metrics")]
+                super::metrics::TOTAL_BYTES_READ.inc_by(buf.filled().len() as u64);
+                Poll::Ready(Ok(()))
+            },
+            res => res,
+        }
+    }
+}
+
+impl AsyncWrite for Substream {
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        #[cfg(feature = "metrics")]
+        super::metrics::TOTAL_BYTES_WRITTEN.inc_by(buf.len() as u64);
+        match &mut self.stream {
+            SubstreamInner::Yamux(stream) => Pin::new(stream).poll_write(cx, buf),
+            SubstreamInner::Quic(stream) => Pin::new(stream).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match &mut self.stream {
+            SubstreamInner::Yamux(stream) => Pin::new(stream).poll_flush(cx),
+            SubstreamInner::Quic(stream) => Pin::new(stream).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match &mut self.stream {
+            SubstreamInner::Yamux(stream) => Pin::new(stream).poll_shutdown(cx),
+            SubstreamInner::Quic(stream) => Pin::new(stream).poll_shutdown(cx),
+        }
+    }
+}
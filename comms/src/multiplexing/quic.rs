@@ -0,0 +1,182 @@
+// Copyright 2022, The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::StreamExt;
+use log::*;
+use quinn::{Connection, IncomingBiStreams, RecvStream, SendStream, VarInt};
+use tari_shutdown::{Shutdown, ShutdownSignal};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    sync::mpsc,
+};
+
+use super::{multiplexed::SubstreamInner, ConnectionError, Control, IncomingSubstreams, Multiplexed};
+use crate::{runtime, stream_id, utils::atomic_ref_counter::AtomicRefCounter};
+
+const LOG_TARGET: &str = "comms::multiplexing::quic";
+
+/// Multiplexes substreams using the native bidirectional streams of a QUIC connection
+pub struct Quic;
+
+impl Quic {
+    /// Wrap an established QUIC connection. Substreams opened by the remote are read from `incoming`.
+    pub fn upgrade_connection(connection: Connection, incoming: IncomingBiStreams) -> Multiplexed {
+        let substream_counter = AtomicRefCounter::new();
+        let control = Control::quic(connection.clone(), substream_counter.clone());
+
+        let shutdown = Shutdown::new();
+        let (incoming_tx, incoming_rx) = mpsc::channel(10);
+        let worker = IncomingWorker::new(connection, incoming, incoming_tx, shutdown.to_signal());
+        runtime::task::spawn(worker.run());
+        let incoming = IncomingSubstreams::new(incoming_rx, substream_counter.clone(), shutdown);
+
+        Multiplexed::new(control, incoming, substream_counter)
+    }
+}
+
+/// Close a QUIC connection. Any open streams are reset.
+pub(super) fn close_connection(connection: &Connection) {
+    connection.close(VarInt::from_u32(0), b"");
+}
+
+/// Maps QUIC connection errors onto the multiplexer `ConnectionError` so that callers handle both multiplexers the
+/// same way
+pub(super) fn to_connection_error(err: quinn::ConnectionError) -> ConnectionError {
+    use quinn::ConnectionError::*;
+    match err {
+        LocallyClosed | ApplicationClosed(_) | ConnectionClosed(_) => ConnectionError::Closed,
+        err => ConnectionError::Io(io::Error::new(io::ErrorKind::ConnectionAborted, err)),
+    }
+}
+
+/// A bidirectional QUIC stream
+#[derive(Debug)]
+pub struct QuicStream {
+    send: SendStream,
+    recv: RecvStream,
+}
+
+impl QuicStream {
+    pub(crate) fn new(send: SendStream, recv: RecvStream) -> Self {
+        Self { send, recv }
+    }
+
+    pub(super) fn stream_id(&self) -> stream_id::Id {
+        // Stream ids are only used to identify substreams in logs, the lower 32 bits are sufficient
+        stream_id::Id::new(self.send.id().index() as u32)
+    }
+}
+
+impl AsyncRead for QuicStream {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.recv).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for QuicStream {
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.send).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.send).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.send).poll_shutdown(cx)
+    }
+}
+
+struct IncomingWorker {
+    connection: Connection,
+    incoming: IncomingBiStreams,
+    sender: mpsc::Sender<SubstreamInner>,
+    shutdown_signal: ShutdownSignal,
+}
+
+impl IncomingWorker {
+    pub fn new(
+        connection: Connection,
+        incoming: IncomingBiStreams,
+        sender: mpsc::Sender<SubstreamInner>,
+        shutdown_signal: ShutdownSignal,
+    ) -> Self {
+        Self {
+            connection,
+            incoming,
+            sender,
+            shutdown_signal,
+        }
+    }
+
+    pub async fn run(mut self) {
+        let remote_address = self.connection.remote_address();
+        loop {
+            tokio::select! {
+                biased;
+
+                _ = self.shutdown_signal.wait() => {
+                    close_connection(&self.connection);
+                    debug!(target: LOG_TARGET, "QUIC connection to {} has closed", remote_address);
+                    break;
+                }
+
+                result = self.incoming.next() => {
+                    match result {
+                        Some(Ok((send, recv))) => {
+                            let stream = SubstreamInner::Quic(QuicStream::new(send, recv));
+                            if self.sender.send(stream).await.is_err() {
+                                debug!(
+                                    target: LOG_TARGET,
+                                    "Incoming QUIC substream task for {} is stopping because the internal stream \
+                                     sender channel was closed",
+                                    remote_address
+                                );
+                                break;
+                            }
+                        },
+                        None | Some(Err(quinn::ConnectionError::LocallyClosed)) |
+                        Some(Err(quinn::ConnectionError::ApplicationClosed(_))) => {
+                            debug!(target: LOG_TARGET, "Incoming QUIC substreams from {} ended.", remote_address);
+                            break;
+                        },
+                        Some(Err(err)) => {
+                            error!(
+                                target: LOG_TARGET,
+                                "Incoming QUIC substream task for {} received an error because '{}'",
+                                remote_address,
+                                err
+                            );
+                            break;
+                        },
+                    }
+                }
+            }
+        }
+    }
+}
@@ -20,42 +20,33 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{future::Future, io, pin::Pin, task::Poll};
+use std::io;
 
-use futures::{task::Context, Stream};
 use tari_shutdown::{Shutdown, ShutdownSignal};
 use tokio::{
-    io::{AsyncRead, AsyncWrite, ReadBuf},
+    io::{AsyncRead, AsyncWrite},
     sync::mpsc,
 };
-use tokio_util::compat::{Compat, FuturesAsyncReadCompatExt, TokioAsyncReadCompatExt};
+use tokio_util::compat::{FuturesAsyncReadCompatExt, TokioAsyncReadCompatExt};
 use tracing::{self, debug, error, event, Level};
 // Reexport
 pub use yamux::ConnectionError;
 use yamux::Mode;
 
-use crate::{
-    connection_manager::ConnectionDirection,
-    runtime,
-    stream_id,
-    stream_id::StreamId,
-    utils::atomic_ref_counter::{AtomicRefCounter, AtomicRefCounterGuard},
-};
+use super::{multiplexed::SubstreamInner, Control, IncomingSubstreams, Multiplexed};
+use crate::{connection_manager::ConnectionDirection, runtime, stream_id, utils::atomic_ref_counter::AtomicRefCounter};
 
 const LOG_TARGET: &str = "comms::multiplexing::yamux";
 
-pub struct Yamux {
-    control: Control,
-    incoming: IncomingSubstreams,
-    substream_counter: AtomicRefCounter,
-}
+/// Multiplexes substreams over a single socket using yamux
+pub struct Yamux;
 
 const MAX_BUFFER_SIZE: u32 = 8 * 1024 * 1024; // 8MiB
 const RECEIVE_WINDOW: u32 = 5 * 1024 * 1024; // 5MiB
 
 impl Yamux {
     /// Upgrade the underlying socket to use yamux
-    pub fn upgrade_connection<TSocket>(socket: TSocket, direction: ConnectionDirection) -> io::Result<Self>
+    pub fn upgrade_connection<TSocket>(socket: TSocket, direction: ConnectionDirection) -> io::Result<Multiplexed>
     where TSocket: AsyncRead + AsyncWrite + Send + Unpin + 'static {
         let mode = match direction {
             ConnectionDirection::Inbound => Mode::Server,
@@ -72,14 +63,10 @@ impl Yamux {
 
         let substream_counter = AtomicRefCounter::new();
         let connection = yamux::Connection::new(socket.compat(), config, mode);
-        let control = Control::new(connection.control(), substream_counter.clone());
+        let control = Control::yamux(connection.control(), substream_counter.clone());
         let incoming = Self::spawn_incoming_stream_worker(connection, substream_counter.clone());
 
-        Ok(Self {
-            control,
-            incoming,
-            substream_counter,
-        })
+        Ok(Multiplexed::new(control, incoming, substream_counter))
     }
 
     // yamux@0.4 requires the incoming substream stream be polled in order to make progress on requests from it's
@@ -97,155 +84,6 @@ impl Yamux {
         runtime::task::spawn(incoming.run());
         IncomingSubstreams::new(incoming_rx, counter, shutdown)
     }
-
-    /// Get the yamux control struct
-    pub fn get_yamux_control(&self) -> Control {
-        self.control.clone()
-    }
-
-    /// Returns a mutable reference to a `Stream` that emits substreams initiated by the remote
-    pub fn incoming_mut(&mut self) -> &mut IncomingSubstreams {
-        &mut self.incoming
-    }
-
-    /// Consumes this object and returns a `Stream` that emits substreams initiated by the remote
-    pub fn into_incoming(self) -> IncomingSubstreams {
-        self.incoming
-    }
-
-    /// Return the number of active substreams
-    pub fn substream_count(&self) -> usize {
-        self.substream_counter.get()
-    }
-
-    /// Return a SubstreamCounter for this connection
-    pub(crate) fn substream_counter(&self) -> AtomicRefCounter {
-        self.substream_counter.clone()
-    }
-}
-
-#[derive(Clone)]
-pub struct Control {
-    inner: yamux::Control,
-    substream_counter: AtomicRefCounter,
-}
-
-impl Control {
-    pub fn new(inner: yamux::Control, substream_counter: AtomicRefCounter) -> Self {
-        Self {
-            inner,
-            substream_counter,
-        }
-    }
-
-    /// Open a new stream to the remote.
-    pub async fn open_stream(&mut self) -> Result<Substream, ConnectionError> {
-        // Ensure that this counts as used while the substream is being opened
-        let counter_guard = self.substream_counter.new_guard();
-        let stream = self.inner.open_stream().await?;
-        Ok(Substream {
-            stream: stream.compat(),
-            _counter_guard: counter_guard,
-        })
-    }
-
-    /// Close the connection.
-    pub fn close(&mut self) -> impl Future<Output = Result<(), ConnectionError>> + '_ {
-        self.inner.close()
-    }
-
-    pub fn substream_count(&self) -> usize {
-        self.substream_counter.get()
-    }
-
-    pub(crate) fn substream_counter(&self) -> AtomicRefCounter {
-        self.substream_counter.clone()
-    }
-}
-
-pub struct IncomingSubstreams {
-    inner: mpsc::Receiver<yamux::Stream>,
-    substream_counter: AtomicRefCounter,
-    shutdown: Shutdown,
-}
-
-impl IncomingSubstreams {
-    pub(self) fn new(
-        inner: mpsc::Receiver<yamux::Stream>,
-        substream_counter: AtomicRefCounter,
-        shutdown: Shutdown,
-    ) -> Self {
-        Self {
-            inner,
-            substream_counter,
-            shutdown,
-        }
-    }
-
-    pub fn substream_count(&self) -> usize {
-        self.substream_counter.get()
-    }
-}
-
-impl Stream for IncomingSubstreams {
-    type Item = Substream;
-
-    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        match futures::ready!(Pin::new(&mut self.inner).poll_recv(cx)) {
-            Some(stream) => Poll::Ready(Some(Substream {
-                stream: stream.compat(),
-                _counter_guard: self.substream_counter.new_guard(),
-            })),
-            None => Poll::Ready(None),
-        }
-    }
-}
-
-impl Drop for IncomingSubstreams {
-    fn drop(&mut self) {
-        let _ = self.shutdown.trigger();
-    }
-}
-
-#[derive(Debug)]
-pub struct Substream {
-    stream: Compat<yamux::Stream>,
-    _counter_guard: AtomicRefCounterGuard,
-}
-
-impl StreamId for Substream {
-    fn stream_id(&self) -> stream_id::Id {
-        self.stream.get_ref().id().into()
-    }
-}
-
-impl tokio::io::AsyncRead for Substream {
-    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
-        match Pin::new(&mut self.stream).poll_read(cx, buf) {
-            Poll::Ready(Ok(())) => {
-                #[cfg(feature = "metrics")]
-                super::metrics::TOTAL_BYTES_READ.inc_by(buf.filled().len() as u64);
-                Poll::Ready(Ok(()))
-            },
-            res => res,
-        }
-    }
-}
-
-impl tokio::io::AsyncWrite for Substream {
-    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
-        #[cfg(feature = "metrics")]
-        super::metrics::TOTAL_BYTES_WRITTEN.inc_by(buf.len() as u64);
-        Pin::new(&mut self.stream).poll_write(cx, buf)
-    }
-
-    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
-        Pin::new(&mut self.stream).poll_flush(cx)
-    }
-
-    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
-        Pin::new(&mut self.stream).poll_shutdown(cx)
-    }
 }
 
 impl From<yamux::StreamId> for stream_id::Id {
@@ -256,7 +94,7 @@ impl From<yamux::StreamId> for stream_id::Id {
 
 struct IncomingWorker<TSocket> {
     connection: yamux::Connection<TSocket>,
-    sender: mpsc::Sender<yamux::Stream>,
+    sender: mpsc::Sender<SubstreamInner>,
     shutdown_signal: ShutdownSignal,
 }
 
@@ -265,7 +103,7 @@ where TSocket: futures::AsyncRead + futures::AsyncWrite + Unpin + Send + 'static
 {
     pub fn new(
         connection: yamux::Connection<TSocket>,
-        sender: mpsc::Sender<yamux::Stream>,
+        sender: mpsc::Sender<SubstreamInner>,
         shutdown_signal: ShutdownSignal,
     ) -> Self {
         Self {
@@ -297,7 +135,7 @@ where TSocket: futures::AsyncRead + futures::AsyncWrite + Unpin + Send + 'static
                      match result {
                         Ok(Some(stream)) => {
                             event!(Level::TRACE, "yamux::incoming_worker::new_stream {}", stream);
-                            if self.sender.send(stream).await.is_err() {
+                            if self.sender.send(SubstreamInner::Yamux(stream.compat())).await.is_err() {
                                 debug!(
                                     target: LOG_TARGET,
                                     "{} Incoming peer substream task is stopping because the internal stream sender channel \
@@ -348,7 +186,7 @@ mod test {
     use crate::{
         connection_manager::ConnectionDirection,
         memsocket::MemorySocket,
-        multiplexing::yamux::Yamux,
+        multiplexing::Yamux,
         runtime,
         runtime::task,
     };
@@ -359,7 +197,7 @@ mod test {
         let msg = b"The Way of Kings";
 
         let dialer = Yamux::upgrade_connection(dialer, ConnectionDirection::Outbound)?;
-        let mut dialer_control = dialer.get_yamux_control();
+        let mut dialer_control = dialer.get_control();
 
         task::spawn(async move {
             let mut substream = dialer_control.open_stream().await.unwrap();
@@ -391,7 +229,7 @@ mod test {
         let (dialer, listener) = MemorySocket::new_pair();
 
         let dialer = Yamux::upgrade_connection(dialer, ConnectionDirection::Outbound).unwrap();
-        let mut dialer_control = dialer.get_yamux_control();
+        let mut dialer_control = dialer.get_control();
 
         let substreams_out = task::spawn(async move {
             let mut substreams = Vec::with_capacity(NUM_SUBSTREAMS);
@@ -422,7 +260,7 @@ mod test {
         let msg = b"Words of Radiance";
 
         let dialer = Yamux::upgrade_connection(dialer, ConnectionDirection::Outbound)?;
-        let mut dialer_control = dialer.get_yamux_control();
+        let mut dialer_control = dialer.get_control();
 
         task::spawn(async move {
             let mut substream = dialer_control.open_stream().await.unwrap();
@@ -463,7 +301,7 @@ mod test {
         let (dialer, listener) = MemorySocket::new_pair();
 
         let dialer = Yamux::upgrade_connection(dialer, ConnectionDirection::Outbound)?;
-        let mut dialer_control = dialer.get_yamux_control();
+        let mut dialer_control = dialer.get_control();
 
         task::spawn(async move {
             assert_eq!(dialer_control.substream_count(), 0);
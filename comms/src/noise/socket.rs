@@ -158,6 +158,16 @@ impl<TSocket> NoiseSocket<TSocket> {
         self.get_remote_static()
             .and_then(|s| CommsPublicKey::from_bytes(s).ok())
     }
+
+    /// Get a reference to the underlying socket
+    pub fn get_ref(&self) -> &TSocket {
+        &self.socket
+    }
+
+    /// Consumes the noise session and returns the underlying socket. Any buffered data is discarded.
+    pub fn into_inner(self) -> TSocket {
+        self.socket
+    }
 }
 
 fn poll_write_all<TSocket>(
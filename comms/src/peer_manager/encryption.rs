@@ -0,0 +1,602 @@
+//  Copyright 2022, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::BTreeMap,
+    sync::Arc,
+};
+
+use log::*;
+use serde::{de::DeserializeOwned, Serialize};
+use tari_storage::{
+    lmdb_store::{LMDBDatabase, LMDBError},
+    IterationResult,
+    KeyValStoreError,
+    KeyValueStore,
+};
+
+use crate::{
+    peer_manager::{
+        migrations::{PeerRecordStore, MIGRATION_VERSION_KEY},
+        Peer,
+        PeerId,
+        PeerManagerError,
+    },
+    types::CommsDatabase,
+    utils::encryption::{DatabaseCipher, EncryptionParams},
+};
+
+const LOG_TARGET: &str = "comms::peer_manager::encryption";
+
+/// Reserved key under which the passphrase hash and encryption salt are stored in an encrypted peer database.
+pub(super) const ENCRYPTION_PARAMS_KEY: u64 = u64::MAX - 1;
+
+/// A peer database in which every peer record is encrypted and authenticated with a key derived from a passphrase.
+///
+/// Records are stored as the AES-256-GCM ciphertext of the serialized `Peer`, with the record key used as associated
+/// data so that records cannot be swapped or replaced with plaintext without detection.
+pub struct EncryptedPeerDatabase {
+    database: Arc<LMDBDatabase>,
+    cipher: DatabaseCipher,
+}
+
+impl EncryptedPeerDatabase {
+    /// Opens an encrypted peer database, verifying the passphrase and the integrity of every stored peer. If the
+    /// database has not been encrypted yet, all existing peers are encrypted in place with a newly derived key.
+    pub fn open(database: Arc<LMDBDatabase>, passphrase: &str) -> Result<Self, PeerManagerError> {
+        let params = database
+            .get::<_, EncryptionParams>(&ENCRYPTION_PARAMS_KEY)
+            .map_err(KeyValStoreError::from)?;
+        let cipher = match params {
+            Some(params) => params.unlock(passphrase)?,
+            None => apply_encryption(&database, passphrase)?,
+        };
+
+        let db = Self { database, cipher };
+        let num_peers = db.verify_integrity()?;
+        debug!(
+            target: LOG_TARGET,
+            "Opened encrypted peer database. {} peer(s) verified", num_peers
+        );
+        Ok(db)
+    }
+
+    /// Returns true if the given database has been encrypted, otherwise false
+    pub fn is_encrypted(database: &LMDBDatabase) -> Result<bool, PeerManagerError> {
+        database
+            .contains_key(&ENCRYPTION_PARAMS_KEY)
+            .map_err(|err| KeyValStoreError::from(err).into())
+    }
+
+    /// Decrypts every peer record, returning the number of peers if all records are intact.
+    fn verify_integrity(&self) -> Result<usize, PeerManagerError> {
+        let mut num_verified = 0;
+        let mut failure = None;
+        self.database
+            .for_each::<PeerId, Vec<u8>, _>(|result| match result {
+                Ok((key, _)) if is_reserved_key(key) => IterationResult::Continue,
+                Ok((key, bytes)) => match self.decrypt_peer(key, &bytes) {
+                    Ok(_) => {
+                        num_verified += 1;
+                        IterationResult::Continue
+                    },
+                    Err(err) => {
+                        failure = Some(format!("peer record {}: {}", key, err));
+                        IterationResult::Break
+                    },
+                },
+                // The migration version record cannot be read as a byte vector. It is accounted for below.
+                Err(_) => IterationResult::Continue,
+            })
+            .map_err(KeyValStoreError::from)?;
+
+        if let Some(failure) = failure {
+            return Err(PeerManagerError::IntegrityCheckFailed(failure));
+        }
+
+        let num_records = self.database.len().map_err(KeyValStoreError::from)? - num_reserved_records(&self.database)?;
+        if num_verified != num_records {
+            return Err(PeerManagerError::IntegrityCheckFailed(format!(
+                "{} of {} peer record(s) could not be read",
+                num_records - num_verified,
+                num_records
+            )));
+        }
+
+        Ok(num_verified)
+    }
+
+    fn encrypt_peer(&self, key: PeerId, peer: &Peer) -> Result<Vec<u8>, KeyValStoreError> {
+        encrypt_peer(&self.cipher, key, peer)
+    }
+
+    fn decrypt_peer(&self, key: PeerId, bytes: &[u8]) -> Result<Peer, KeyValStoreError> {
+        let plaintext = self
+            .cipher
+            .decrypt(&key.to_le_bytes(), bytes)
+            .map_err(|err| KeyValStoreError::DeserializationError(err.to_string()))?;
+        bincode::deserialize(&plaintext).map_err(|err| KeyValStoreError::DeserializationError(err.to_string()))
+    }
+}
+
+impl KeyValueStore<PeerId, Peer> for EncryptedPeerDatabase {
+    fn insert(&self, key: PeerId, value: Peer) -> Result<(), KeyValStoreError> {
+        check_not_reserved(key)?;
+        let ciphertext = self.encrypt_peer(key, &value)?;
+        self.database.insert(&key, &ciphertext).map_err(Into::into)
+    }
+
+    fn get(&self, key: &PeerId) -> Result<Option<Peer>, KeyValStoreError> {
+        if is_reserved_key(*key) {
+            return Ok(None);
+        }
+        match self.database.get::<_, Vec<u8>>(key)? {
+            Some(bytes) => self.decrypt_peer(*key, &bytes).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn get_many(&self, keys: &[PeerId]) -> Result<Vec<Peer>, KeyValStoreError> {
+        let records = self.database.with_read_transaction(|access| {
+            keys.iter()
+                .filter(|k| !is_reserved_key(**k))
+                .filter_map(|k| match access.get::<_, Vec<u8>>(k) {
+                    Ok(Some(v)) => Some(Ok((*k, v))),
+                    Ok(None) => None,
+                    Err(e) => Some(Err(e)),
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })??;
+
+        records
+            .into_iter()
+            .map(|(key, bytes)| self.decrypt_peer(key, &bytes))
+            .collect()
+    }
+
+    fn size(&self) -> Result<usize, KeyValStoreError> {
+        // Exclude the encryption params. The migration version record is excluded by the `KeyValueWrapper`.
+        let num_params_records = if self.database.contains_key(&ENCRYPTION_PARAMS_KEY)? {
+            1
+        } else {
+            0
+        };
+        Ok(self.database.len()?.saturating_sub(num_params_records))
+    }
+
+    fn for_each<F>(&self, mut f: F) -> Result<(), KeyValStoreError>
+    where
+        Self: Sized,
+        F: FnMut(Result<(PeerId, Peer), KeyValStoreError>) -> IterationResult,
+    {
+        self.database
+            .for_each::<PeerId, Vec<u8>, _>(|result| match result {
+                Ok((key, _)) if is_reserved_key(key) => IterationResult::Continue,
+                Ok((key, bytes)) => f(self.decrypt_peer(key, &bytes).map(|peer| (key, peer))),
+                Err(err) => f(Err(err)),
+            })
+            .map_err(Into::into)
+    }
+
+    fn exists(&self, key: &PeerId) -> Result<bool, KeyValStoreError> {
+        if is_reserved_key(*key) {
+            return Ok(false);
+        }
+        self.database.contains_key(key).map_err(Into::into)
+    }
+
+    fn delete(&self, key: &PeerId) -> Result<(), KeyValStoreError> {
+        check_not_reserved(*key)?;
+        self.database.remove(key).map_err(Into::into)
+    }
+}
+
+/// The backing store of the `PeerManager`, which is either a plaintext or an encrypted peer database.
+pub(super) enum PeerDatabase {
+    Plaintext(CommsDatabase),
+    Encrypted(Box<EncryptedPeerDatabase>),
+}
+
+impl KeyValueStore<PeerId, Peer> for PeerDatabase {
+    fn insert(&self, key: PeerId, value: Peer) -> Result<(), KeyValStoreError> {
+        match self {
+            PeerDatabase::Plaintext(db) => db.insert(key, value),
+            PeerDatabase::Encrypted(db) => db.insert(key, value),
+        }
+    }
+
+    fn get(&self, key: &PeerId) -> Result<Option<Peer>, KeyValStoreError> {
+        match self {
+            PeerDatabase::Plaintext(db) => db.get(key),
+            PeerDatabase::Encrypted(db) => db.get(key),
+        }
+    }
+
+    fn get_many(&self, keys: &[PeerId]) -> Result<Vec<Peer>, KeyValStoreError> {
+        match self {
+            PeerDatabase::Plaintext(db) => db.get_many(keys),
+            PeerDatabase::Encrypted(db) => db.get_many(keys),
+        }
+    }
+
+    fn size(&self) -> Result<usize, KeyValStoreError> {
+        match self {
+            PeerDatabase::Plaintext(db) => db.size(),
+            PeerDatabase::Encrypted(db) => db.size(),
+        }
+    }
+
+    fn for_each<F>(&self, f: F) -> Result<(), KeyValStoreError>
+    where
+        Self: Sized,
+        F: FnMut(Result<(PeerId, Peer), KeyValStoreError>) -> IterationResult,
+    {
+        match self {
+            PeerDatabase::Plaintext(db) => db.for_each(f),
+            PeerDatabase::Encrypted(db) => db.for_each(f),
+        }
+    }
+
+    fn exists(&self, key: &PeerId) -> Result<bool, KeyValStoreError> {
+        match self {
+            PeerDatabase::Plaintext(db) => db.exists(key),
+            PeerDatabase::Encrypted(db) => db.exists(key),
+        }
+    }
+
+    fn delete(&self, key: &PeerId) -> Result<(), KeyValStoreError> {
+        match self {
+            PeerDatabase::Plaintext(db) => db.delete(key),
+            PeerDatabase::Encrypted(db) => db.delete(key),
+        }
+    }
+}
+
+/// The decrypted records of an encrypted peer database. Records are held in memory so that migrations can be applied
+/// to them without writing plaintext peers to disk.
+pub(super) struct DecryptedPeerRecords {
+    cipher: DatabaseCipher,
+    records: RefCell<BTreeMap<PeerId, Vec<u8>>>,
+    version: Cell<Option<u32>>,
+}
+
+impl DecryptedPeerRecords {
+    /// Decrypts every peer record in the encrypted database
+    pub(super) fn load(database: &LMDBDatabase, passphrase: &str) -> Result<Self, PeerManagerError> {
+        let params = database
+            .get::<_, EncryptionParams>(&ENCRYPTION_PARAMS_KEY)
+            .map_err(KeyValStoreError::from)?
+            .ok_or_else(|| PeerManagerError::DataInconsistency("Peer database is not encrypted".to_string()))?;
+        let cipher = params.unlock(passphrase)?;
+
+        let mut records = BTreeMap::new();
+        let mut failure = None;
+        database
+            .for_each::<PeerId, Vec<u8>, _>(|result| match result {
+                Ok((key, _)) if is_reserved_key(key) => IterationResult::Continue,
+                Ok((key, bytes)) => match cipher.decrypt(&key.to_le_bytes(), &bytes) {
+                    Ok(plaintext) => {
+                        records.insert(key, plaintext);
+                        IterationResult::Continue
+                    },
+                    Err(err) => {
+                        failure = Some(format!("peer record {}: {}", key, err));
+                        IterationResult::Break
+                    },
+                },
+                // The migration version record cannot be read as a byte vector
+                Err(_) => IterationResult::Continue,
+            })
+            .map_err(KeyValStoreError::from)?;
+
+        if let Some(failure) = failure {
+            return Err(PeerManagerError::IntegrityCheckFailed(failure));
+        }
+
+        Ok(Self {
+            cipher,
+            records: RefCell::new(records),
+            version: Cell::new(None),
+        })
+    }
+
+    /// Re-encrypts the records and writes them, along with the migration version, in a single transaction
+    pub(super) fn commit(self, database: &LMDBDatabase) -> Result<(), PeerManagerError> {
+        let Self {
+            cipher,
+            records,
+            version,
+        } = self;
+        let records = records
+            .into_inner()
+            .into_iter()
+            .map(|(key, plaintext)| {
+                cipher
+                    .encrypt(&key.to_le_bytes(), &plaintext)
+                    .map(|ciphertext| (key, ciphertext))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let version = version.get();
+
+        database
+            .with_write_transaction(|mut txn| {
+                for (key, ciphertext) in &records {
+                    txn.insert(key, ciphertext)?;
+                }
+                if let Some(version) = version {
+                    txn.insert(&MIGRATION_VERSION_KEY, &version)?;
+                }
+                Ok(())
+            })
+            .map_err(KeyValStoreError::from)?;
+
+        info!(
+            target: LOG_TARGET,
+            "Migrated encrypted peer database ({} peer(s))",
+            records.len()
+        );
+        Ok(())
+    }
+}
+
+impl PeerRecordStore for DecryptedPeerRecords {
+    fn for_each<V, F>(&self, mut f: F) -> Result<(), LMDBError>
+    where
+        V: DeserializeOwned,
+        F: FnMut(Result<(PeerId, V), KeyValStoreError>) -> IterationResult,
+    {
+        // Iterate over a snapshot so that records can be replaced during iteration
+        let records = self.records.borrow().clone();
+        for (key, plaintext) in records {
+            let result = bincode::deserialize(&plaintext)
+                .map(|value| (key, value))
+                .map_err(|err| KeyValStoreError::DeserializationError(err.to_string()));
+            if let IterationResult::Break = f(result) {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn insert<V: Serialize>(&self, key: &PeerId, value: &V) -> Result<(), LMDBError> {
+        let plaintext = bincode::serialize(value).map_err(|err| LMDBError::SerializationErr(err.to_string()))?;
+        self.records.borrow_mut().insert(*key, plaintext);
+        Ok(())
+    }
+
+    fn set_version(&self, version: u32) {
+        self.version.set(Some(version));
+    }
+}
+
+/// Encrypts all plaintext peers in a single transaction and stores the encryption params.
+fn apply_encryption(database: &LMDBDatabase, passphrase: &str) -> Result<DatabaseCipher, PeerManagerError> {
+    let num_records = database.len().map_err(KeyValStoreError::from)? - num_reserved_records(database)?;
+    let mut peers = Vec::with_capacity(num_records);
+    if num_records > 0 {
+        database
+            .for_each::<PeerId, Peer, _>(|result| {
+                // The migration version record cannot be read as a peer
+                if let Ok((key, peer)) = result {
+                    peers.push((key, peer));
+                }
+                IterationResult::Continue
+            })
+            .map_err(KeyValStoreError::from)?;
+    }
+
+    if peers.len() != num_records {
+        return Err(PeerManagerError::DataInconsistency(format!(
+            "Unable to encrypt peer database: {} of {} peer record(s) could not be read",
+            num_records - peers.len(),
+            num_records
+        )));
+    }
+
+    let (params, cipher) = EncryptionParams::generate(passphrase)?;
+    let records = peers
+        .iter()
+        .map(|(key, peer)| encrypt_peer(&cipher, *key, peer).map(|ciphertext| (*key, ciphertext)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    database
+        .with_write_transaction(|mut txn| {
+            for (key, ciphertext) in &records {
+                txn.insert(key, ciphertext)?;
+            }
+            txn.insert(&ENCRYPTION_PARAMS_KEY, &params)
+        })
+        .map_err(KeyValStoreError::from)?;
+
+    info!(
+        target: LOG_TARGET,
+        "Encrypted peer database ({} peer(s) migrated)",
+        records.len()
+    );
+
+    Ok(cipher)
+}
+
+fn encrypt_peer(cipher: &DatabaseCipher, key: PeerId, peer: &Peer) -> Result<Vec<u8>, KeyValStoreError> {
+    let plaintext = bincode::serialize(peer).map_err(|err| KeyValStoreError::SerializationError(err.to_string()))?;
+    cipher
+        .encrypt(&key.to_le_bytes(), &plaintext)
+        .map_err(|err| KeyValStoreError::SerializationError(err.to_string()))
+}
+
+fn num_reserved_records(database: &LMDBDatabase) -> Result<usize, PeerManagerError> {
+    let mut n = 0;
+    for key in &[MIGRATION_VERSION_KEY, ENCRYPTION_PARAMS_KEY] {
+        if database.contains_key(key).map_err(KeyValStoreError::from)? {
+            n += 1;
+        }
+    }
+    Ok(n)
+}
+
+fn is_reserved_key(key: PeerId) -> bool {
+    key == MIGRATION_VERSION_KEY || key == ENCRYPTION_PARAMS_KEY
+}
+
+/// Returns an error if the given key is reserved for the migration version or the encryption params and cannot hold a
+/// peer record
+fn check_not_reserved(key: PeerId) -> Result<(), KeyValStoreError> {
+    if is_reserved_key(key) {
+        return Err(KeyValStoreError::DatabaseError(format!(
+            "Key {} is reserved and cannot be used for a peer record",
+            key
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use multiaddr::Multiaddr;
+    use tari_crypto::{keys::PublicKey, ristretto::RistrettoPublicKey};
+    use tari_storage::lmdb_store::{LMDBBuilder, LMDBStore};
+    use tempfile::{tempdir, TempDir};
+
+    use super::*;
+    use crate::{
+        net_address::MultiaddressesWithStats,
+        peer_manager::{NodeId, PeerFeatures, PeerFlags},
+        utils::encryption::DatabaseEncryptionError,
+    };
+
+    const DB_NAME: &str = "peers";
+
+    fn init_datastore() -> (LMDBStore, Arc<LMDBDatabase>, TempDir) {
+        let dir = tempdir().unwrap();
+        let datastore = LMDBBuilder::new()
+            .set_path(dir.path())
+            .set_env_config(Default::default())
+            .set_max_number_of_databases(1)
+            .add_database(DB_NAME, lmdb_zero::db::CREATE)
+            .build()
+            .unwrap();
+        let database = Arc::new(datastore.get_handle(DB_NAME).unwrap());
+        (datastore, database, dir)
+    }
+
+    fn create_peer() -> Peer {
+        let (_sk, pk) = RistrettoPublicKey::random_keypair(&mut rand::rngs::OsRng);
+        let node_id = NodeId::from_key(&pk);
+        let net_addresses = MultiaddressesWithStats::from("/ip4/1.2.3.4/tcp/8000".parse::<Multiaddr>().unwrap());
+        Peer::new(
+            pk,
+            node_id,
+            net_addresses,
+            PeerFlags::default(),
+            PeerFeatures::COMMUNICATION_NODE,
+            Default::default(),
+            Default::default(),
+        )
+    }
+
+    #[test]
+    fn it_encrypts_an_existing_plaintext_database() {
+        let (_datastore, database, _dir) = init_datastore();
+        let peer1 = create_peer();
+        let peer2 = create_peer();
+        database.insert(&1u64, &peer1).unwrap();
+        database.insert(&2u64, &peer2).unwrap();
+        database.insert(&MIGRATION_VERSION_KEY, &7u32).unwrap();
+        assert!(!EncryptedPeerDatabase::is_encrypted(&database).unwrap());
+
+        let db = EncryptedPeerDatabase::open(database.clone(), "secret").unwrap();
+        assert!(EncryptedPeerDatabase::is_encrypted(&database).unwrap());
+        assert_eq!(db.size().unwrap(), 3);
+        let record = database.get::<_, Vec<u8>>(&1u64).unwrap().unwrap();
+        assert_ne!(record, bincode::serialize(&peer1).unwrap());
+        assert_eq!(db.get(&1).unwrap().unwrap().node_id, peer1.node_id);
+        assert_eq!(db.get_many(&[2]).unwrap()[0].node_id, peer2.node_id);
+        assert!(db.get(&ENCRYPTION_PARAMS_KEY).unwrap().is_none());
+
+        let peer3 = create_peer();
+        db.insert(3, peer3.clone()).unwrap();
+        drop(db);
+
+        let db = EncryptedPeerDatabase::open(database, "secret").unwrap();
+        let mut node_ids = Vec::new();
+        db.for_each_ok(|(_, peer)| {
+            node_ids.push(peer.node_id);
+            IterationResult::Continue
+        })
+        .unwrap();
+        assert_eq!(node_ids, vec![peer1.node_id, peer2.node_id, peer3.node_id]);
+    }
+
+    #[test]
+    fn it_rejects_reserved_keys() {
+        let (_datastore, database, _dir) = init_datastore();
+        let db = EncryptedPeerDatabase::open(database.clone(), "secret").unwrap();
+        assert_eq!(db.size().unwrap(), 0);
+
+        let err = db.insert(ENCRYPTION_PARAMS_KEY, create_peer()).unwrap_err();
+        assert!(matches!(err, KeyValStoreError::DatabaseError(_)));
+        let err = db.delete(&ENCRYPTION_PARAMS_KEY).unwrap_err();
+        assert!(matches!(err, KeyValStoreError::DatabaseError(_)));
+        assert!(EncryptedPeerDatabase::is_encrypted(&database).unwrap());
+
+        // Without the params record there is nothing to exclude from the size
+        database.remove(&ENCRYPTION_PARAMS_KEY).unwrap();
+        database.insert(&1u64, &create_peer()).unwrap();
+        assert_eq!(db.size().unwrap(), 1);
+    }
+
+    #[test]
+    fn it_rejects_an_incorrect_passphrase() {
+        let (_datastore, database, _dir) = init_datastore();
+        database.insert(&1u64, &create_peer()).unwrap();
+        EncryptedPeerDatabase::open(database.clone(), "secret").unwrap();
+
+        let err = EncryptedPeerDatabase::open(database, "not the secret").err().unwrap();
+        assert!(matches!(
+            err,
+            PeerManagerError::EncryptionError(DatabaseEncryptionError::InvalidPassphrase)
+        ));
+    }
+
+    #[test]
+    fn it_detects_tampered_records() {
+        let (_datastore, database, _dir) = init_datastore();
+        database.insert(&1u64, &create_peer()).unwrap();
+        database.insert(&2u64, &create_peer()).unwrap();
+        EncryptedPeerDatabase::open(database.clone(), "secret").unwrap();
+
+        // Swap two encrypted records
+        let record1 = database.get::<_, Vec<u8>>(&1u64).unwrap().unwrap();
+        let record2 = database.get::<_, Vec<u8>>(&2u64).unwrap().unwrap();
+        database.insert(&1u64, &record2).unwrap();
+        database.insert(&2u64, &record1).unwrap();
+        let err = EncryptedPeerDatabase::open(database.clone(), "secret").err().unwrap();
+        assert!(matches!(err, PeerManagerError::IntegrityCheckFailed(_)));
+
+        // Replace with a plaintext record
+        database.insert(&1u64, &record1).unwrap();
+        database.insert(&2u64, &create_peer()).unwrap();
+        let err = EncryptedPeerDatabase::open(database, "secret").err().unwrap();
+        assert!(matches!(err, PeerManagerError::IntegrityCheckFailed(_)));
+    }
+}
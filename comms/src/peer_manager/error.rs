@@ -25,6 +25,8 @@ use std::sync::PoisonError;
 use tari_storage::KeyValStoreError;
 use thiserror::Error;
 
+use crate::utils::encryption::DatabaseEncryptionError;
+
 #[derive(Debug, Error, Clone)]
 pub enum PeerManagerError {
     #[error("The requested peer does not exist")]
@@ -39,6 +41,12 @@ pub enum PeerManagerError {
     MigrationError(String),
     #[error("Identity signature is invalid")]
     InvalidIdentitySignature,
+    #[error("Peer database encryption error: {0}")]
+    EncryptionError(#[from] DatabaseEncryptionError),
+    #[error("The peer database is encrypted and cannot be opened without a passphrase")]
+    PeerDatabaseEncrypted,
+    #[error("Peer database integrity check failed: {0}")]
+    IntegrityCheckFailed(String),
 }
 
 impl PeerManagerError {
@@ -28,6 +28,7 @@ use tokio::sync::RwLock;
 
 use crate::{
     peer_manager::{
+        encryption::{EncryptedPeerDatabase, PeerDatabase},
         migrations,
         peer::{Peer, PeerFlags},
         peer_id::PeerId,
@@ -38,6 +39,7 @@ use crate::{
         PeerFeatures,
         PeerManagerError,
         PeerQuery,
+        PeerReputation,
     },
     types::{CommsDatabase, CommsPublicKey},
 };
@@ -45,13 +47,25 @@ use crate::{
 /// The PeerManager consist of a routing table of previously discovered peers.
 /// It also provides functionality to add, find and delete peers.
 pub struct PeerManager {
-    peer_storage: RwLock<PeerStorage<KeyValueWrapper<CommsDatabase>>>,
+    peer_storage: RwLock<PeerStorage<KeyValueWrapper<PeerDatabase>>>,
     _file_lock: Option<File>,
 }
 
 impl PeerManager {
     /// Constructs a new empty PeerManager
     pub fn new(database: CommsDatabase, file_lock: Option<File>) -> Result<PeerManager, PeerManagerError> {
+        Self::with_database(PeerDatabase::Plaintext(database), file_lock)
+    }
+
+    /// Constructs a new PeerManager backed by an encrypted peer database
+    pub fn new_encrypted(
+        database: EncryptedPeerDatabase,
+        file_lock: Option<File>,
+    ) -> Result<PeerManager, PeerManagerError> {
+        Self::with_database(PeerDatabase::Encrypted(Box::new(database)), file_lock)
+    }
+
+    fn with_database(database: PeerDatabase, file_lock: Option<File>) -> Result<PeerManager, PeerManagerError> {
         let storage = PeerStorage::new_indexed(KeyValueWrapper::new(database))?;
         Ok(Self {
             peer_storage: RwLock::new(storage),
@@ -60,8 +74,10 @@ impl PeerManager {
     }
 
     /// Migrate the peer database, this only applies to the LMDB database
-    pub fn migrate_lmdb(database: &LMDBDatabase) -> Result<(), PeerManagerError> {
-        migrations::migrate(database).map_err(|err| PeerManagerError::MigrationError(err.to_string()))
+    /// Migrates the LMDB peer database to the latest version. The passphrase is required to migrate an encrypted
+    /// database.
+    pub fn migrate_lmdb(database: &LMDBDatabase, passphrase: Option<&str>) -> Result<(), PeerManagerError> {
+        migrations::migrate(database, passphrase)
     }
 
     pub async fn count(&self) -> usize {
@@ -315,6 +331,13 @@ impl PeerManager {
     ) -> Result<Option<Vec<u8>>, PeerManagerError> {
         self.peer_storage.write().await.set_peer_metadata(node_id, key, data)
     }
+
+    /// Applies `f` to the reputation of the peer provided by the NodeId and persists the result. The updated
+    /// reputation is returned.
+    pub async fn update_peer_reputation<F>(&self, node_id: &NodeId, f: F) -> Result<PeerReputation, PeerManagerError>
+    where F: FnOnce(&mut PeerReputation) {
+        self.peer_storage.write().await.update_peer_reputation(node_id, f)
+    }
 }
 
 impl fmt::Debug for PeerManager {
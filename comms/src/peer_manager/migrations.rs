@@ -22,35 +22,50 @@
 
 mod v5;
 mod v6;
+mod v7;
 
 use log::*;
-use tari_storage::lmdb_store::{LMDBDatabase, LMDBError};
+use serde::{de::DeserializeOwned, Serialize};
+use tari_storage::{
+    lmdb_store::{LMDBDatabase, LMDBError},
+    IterationResult,
+    KeyValStoreError,
+};
+
+use crate::peer_manager::{
+    encryption::{DecryptedPeerRecords, EncryptedPeerDatabase},
+    PeerId,
+    PeerManagerError,
+};
 
 const LOG_TARGET: &str = "comms::peer_manager::migrations";
 
 pub(super) const MIGRATION_VERSION_KEY: u64 = u64::MAX;
 
-pub fn migrate(database: &LMDBDatabase) -> Result<(), LMDBError> {
+fn migrations<T: PeerRecordStore>() -> Vec<Box<dyn Migration<T, Error = LMDBError>>> {
     // Add migrations here in version order
-    let migrations = vec![v5::Migration.boxed(), v6::Migration.boxed()];
-    if migrations.is_empty() {
-        return Ok(());
-    }
-    let latest_version = migrations.last().unwrap().get_version();
+    vec![v5::Migration.boxed(), v6::Migration.boxed(), v7::Migration.boxed()]
+}
+
+/// Migrates the peer database to the latest version. The records of an encrypted database are decrypted in memory
+/// using the given passphrase, migrated and re-encrypted, so that plaintext peers are never written to disk.
+pub fn migrate(database: &LMDBDatabase, passphrase: Option<&str>) -> Result<(), PeerManagerError> {
+    let latest_version = match migrations::<LMDBDatabase>().last() {
+        Some(migration) => migration.get_version(),
+        None => return Ok(()),
+    };
 
     // If the database is empty there is nothing to migrate, so set it to the latest version
-    if database.len()? == 0 {
+    if database.len().map_err(migration_error)? == 0 {
         debug!(target: LOG_TARGET, "New database does not require migration");
-        if let Err(err) = database.insert(&MIGRATION_VERSION_KEY, &latest_version) {
-            error!(
-                target: LOG_TARGET,
-                "Failed to update migration counter: {}. ** Database may be corrupt **", err
-            );
-        }
+        database.set_version(latest_version);
         return Ok(());
     }
 
-    let mut version = database.get::<_, u32>(&MIGRATION_VERSION_KEY)?.unwrap_or(0);
+    let version = database
+        .get::<_, u32>(&MIGRATION_VERSION_KEY)
+        .map_err(migration_error)?
+        .unwrap_or(0);
 
     if version == latest_version {
         debug!(
@@ -65,19 +80,25 @@ pub fn migrate(database: &LMDBDatabase) -> Result<(), LMDBError> {
         "Migrating database from version {} to {}", version, latest_version
     );
 
-    loop {
+    if EncryptedPeerDatabase::is_encrypted(database)? {
+        let passphrase = passphrase.ok_or(PeerManagerError::PeerDatabaseEncrypted)?;
+        let records = DecryptedPeerRecords::load(database, passphrase)?;
+        run_migrations(&records, version, latest_version).map_err(migration_error)?;
+        records.commit(database)?;
+        return Ok(());
+    }
+
+    run_migrations(database, version, latest_version).map_err(migration_error)
+}
+
+fn run_migrations<T: PeerRecordStore>(store: &T, mut version: u32, latest_version: u32) -> Result<(), LMDBError> {
+    let migrations = migrations::<T>();
+    while version < latest_version {
         version += 1;
-        let migration = migrations.iter().find(|m| m.get_version() == version);
-        match migration {
+        match migrations.iter().find(|m| m.get_version() == version) {
             Some(migration) => {
-                migration.migrate(database)?;
-                if let Err(err) = database.insert(&MIGRATION_VERSION_KEY, &version) {
-                    error!(
-                        target: LOG_TARGET,
-                        "Failed to update migration counter: {}. ** Database may be corrupt **", err
-                    );
-                }
-
+                migration.migrate(store)?;
+                store.set_version(version);
                 debug!(target: LOG_TARGET, "Migration {} complete", version);
             },
             None => {
@@ -89,6 +110,46 @@ pub fn migrate(database: &LMDBDatabase) -> Result<(), LMDBError> {
             },
         }
     }
+    Ok(())
+}
+
+fn migration_error(err: LMDBError) -> PeerManagerError {
+    PeerManagerError::MigrationError(err.to_string())
+}
+
+/// The serialized peer records that migrations read and rewrite
+pub(super) trait PeerRecordStore {
+    fn for_each<V, F>(&self, f: F) -> Result<(), LMDBError>
+    where
+        V: DeserializeOwned,
+        F: FnMut(Result<(PeerId, V), KeyValStoreError>) -> IterationResult;
+
+    fn insert<V: Serialize>(&self, key: &PeerId, value: &V) -> Result<(), LMDBError>;
+
+    fn set_version(&self, version: u32);
+}
+
+impl PeerRecordStore for LMDBDatabase {
+    fn for_each<V, F>(&self, f: F) -> Result<(), LMDBError>
+    where
+        V: DeserializeOwned,
+        F: FnMut(Result<(PeerId, V), KeyValStoreError>) -> IterationResult,
+    {
+        LMDBDatabase::for_each::<PeerId, V, F>(self, f)
+    }
+
+    fn insert<V: Serialize>(&self, key: &PeerId, value: &V) -> Result<(), LMDBError> {
+        LMDBDatabase::insert(self, key, value)
+    }
+
+    fn set_version(&self, version: u32) {
+        if let Err(err) = LMDBDatabase::insert(self, &MIGRATION_VERSION_KEY, &version) {
+            error!(
+                target: LOG_TARGET,
+                "Failed to update migration counter: {}. ** Database may be corrupt **", err
+            );
+        }
+    }
 }
 
 trait Migration<T> {
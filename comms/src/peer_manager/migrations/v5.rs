@@ -26,16 +26,13 @@ use chrono::NaiveDateTime;
 use log::*;
 use serde::{Deserialize, Serialize};
 use tari_crypto::tari_utilities::hex::serialize_to_hex;
-use tari_storage::{
-    lmdb_store::{LMDBDatabase, LMDBError},
-    IterationResult,
-};
+use tari_storage::{lmdb_store::LMDBError, IterationResult};
 
 use crate::{
     net_address::MultiaddressesWithStats,
     peer_manager::{
         connection_stats::PeerConnectionStats,
-        migrations::MIGRATION_VERSION_KEY,
+        migrations::{PeerRecordStore, MIGRATION_VERSION_KEY},
         node_id::deserialize_node_id_from_hex,
         IdentitySignature,
         NodeId,
@@ -94,15 +91,15 @@ pub struct PeerV5 {
 
 pub struct Migration;
 
-impl super::Migration<LMDBDatabase> for Migration {
+impl<T: PeerRecordStore> super::Migration<T> for Migration {
     type Error = LMDBError;
 
     fn get_version(&self) -> u32 {
         5
     }
 
-    fn migrate(&self, db: &LMDBDatabase) -> Result<(), Self::Error> {
-        db.for_each::<PeerId, PeerV4, _>(|old_peer| {
+    fn migrate(&self, db: &T) -> Result<(), Self::Error> {
+        db.for_each::<PeerV4, _>(|old_peer| {
             let result = old_peer.and_then(|(key, peer)| {
                 if key == MIGRATION_VERSION_KEY {
                     return Ok(());
@@ -72,6 +72,9 @@
 
 mod connection_stats;
 
+mod encryption;
+pub use encryption::EncryptedPeerDatabase;
+
 mod error;
 pub use error::PeerManagerError;
 
@@ -96,6 +99,9 @@ pub use peer_features::PeerFeatures;
 mod peer_id;
 pub(crate) use peer_id::PeerId;
 
+mod peer_reputation;
+pub use peer_reputation::{PeerReputation, MAX_REPUTATION_SCORE, MIN_REPUTATION_SCORE};
+
 mod manager;
 pub use manager::PeerManager;
 
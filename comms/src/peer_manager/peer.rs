@@ -38,6 +38,7 @@ use super::{
     node_id::{deserialize_node_id_from_hex, NodeId},
     peer_id::PeerId,
     PeerFeatures,
+    PeerReputation,
 };
 use crate::{
     net_address::MultiaddressesWithStats,
@@ -98,6 +99,8 @@ pub struct Peer {
     /// Signs the peer information with a timestamp to prevent malleability. This is optional for backward
     /// compatibility, but without this, the identity (addresses etc) cannot be updated.
     pub identity_signature: Option<IdentitySignature>,
+    /// The reputation score of the peer, maintained by the connectivity manager
+    pub reputation: PeerReputation,
 }
 
 impl Peer {
@@ -128,6 +131,7 @@ impl Peer {
             user_agent,
             metadata: HashMap::new(),
             identity_signature: None,
+            reputation: Default::default(),
         }
     }
 
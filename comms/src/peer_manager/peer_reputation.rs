@@ -0,0 +1,131 @@
+// Copyright 2022, The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::time::Duration;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// The maximum reputation score of a peer. Capping positive scores prevents a peer from building up enough credit to
+/// misbehave without consequence.
+pub const MAX_REPUTATION_SCORE: i32 = 100;
+/// The minimum reputation score of a peer
+pub const MIN_REPUTATION_SCORE: i32 = -1000;
+
+/// The persisted reputation of a peer. The score decays towards zero over time so that recent events carry more weight
+/// than old ones.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PeerReputation {
+    /// The score as of `updated_at`. Negative scores indicate misbehaviour.
+    score: i32,
+    /// The last time the score was updated
+    updated_at: Option<NaiveDateTime>,
+    /// The number of times the peer has been banned because of a low reputation score
+    num_bans: u32,
+}
+
+impl PeerReputation {
+    /// Returns the score as of the last update, without applying decay
+    pub fn score(&self) -> i32 {
+        self.score
+    }
+
+    /// Returns the last time the score was updated, or None if no reputation events have been recorded for the peer
+    pub fn updated_at(&self) -> Option<NaiveDateTime> {
+        self.updated_at
+    }
+
+    /// Returns the number of times the peer has been banned because of a low reputation score
+    pub fn num_bans(&self) -> u32 {
+        self.num_bans
+    }
+
+    /// Returns the score at `now`, halving it for every `half_life` that has elapsed since the last update.
+    pub fn score_at(&self, now: NaiveDateTime, half_life: Duration) -> i32 {
+        let updated_at = match self.updated_at {
+            Some(updated_at) => updated_at,
+            None => return self.score,
+        };
+        if half_life.as_secs_f64() <= 0.0 {
+            return 0;
+        }
+        let elapsed = now.signed_duration_since(updated_at).to_std().unwrap_or_default();
+        let factor = 0.5f64.powf(elapsed.as_secs_f64() / half_life.as_secs_f64());
+        (f64::from(self.score) * factor).round() as i32
+    }
+
+    /// Decays the score up to `now` and then adds `delta`, returning the new score.
+    pub fn adjust(&mut self, delta: i32, now: NaiveDateTime, half_life: Duration) -> i32 {
+        let score = self.score_at(now, half_life).saturating_add(delta);
+        self.score = score.clamp(MIN_REPUTATION_SCORE, MAX_REPUTATION_SCORE);
+        self.updated_at = Some(now);
+        self.score
+    }
+
+    /// Records that the peer was banned. The score is reset so that the peer starts afresh once the ban expires, but
+    /// the ban count is kept so that subsequent bans can be escalated.
+    pub fn record_ban(&mut self, now: NaiveDateTime) {
+        self.score = 0;
+        self.updated_at = Some(now);
+        self.num_bans = self.num_bans.saturating_add(1);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::Utc;
+
+    use super::*;
+
+    const HALF_LIFE: Duration = Duration::from_secs(60 * 60);
+
+    #[test]
+    fn it_decays_the_score() {
+        let now = Utc::now().naive_utc();
+        let mut reputation = PeerReputation::default();
+        assert_eq!(reputation.adjust(-80, now, HALF_LIFE), -80);
+        let later = now + chrono::Duration::hours(1);
+        assert_eq!(reputation.score_at(later, HALF_LIFE), -40);
+        let later = now + chrono::Duration::hours(2);
+        assert_eq!(reputation.score_at(later, HALF_LIFE), -20);
+        assert_eq!(reputation.adjust(10, later, HALF_LIFE), -10);
+        assert_eq!(reputation.updated_at(), Some(later));
+    }
+
+    #[test]
+    fn it_clamps_the_score() {
+        let now = Utc::now().naive_utc();
+        let mut reputation = PeerReputation::default();
+        assert_eq!(reputation.adjust(1000, now, HALF_LIFE), MAX_REPUTATION_SCORE);
+        assert_eq!(reputation.adjust(i32::MIN, now, HALF_LIFE), MIN_REPUTATION_SCORE);
+    }
+
+    #[test]
+    fn it_resets_the_score_when_banned() {
+        let now = Utc::now().naive_utc();
+        let mut reputation = PeerReputation::default();
+        reputation.adjust(-200, now, HALF_LIFE);
+        reputation.record_ban(now);
+        assert_eq!(reputation.score(), 0);
+        assert_eq!(reputation.num_bans(), 1);
+    }
+}
@@ -39,6 +39,7 @@ use crate::{
         PeerManagerError,
         PeerQuery,
         PeerQuerySortBy,
+        PeerReputation,
     },
     protocol::ProtocolId,
     types::{CommsDatabase, CommsPublicKey},
@@ -565,6 +566,27 @@ where DS: KeyValueStore<PeerId, Peer>
         Ok(result)
     }
 
+    /// Applies `f` to the reputation of the peer provided by the NodeId and persists the result. The updated
+    /// reputation is returned.
+    pub fn update_peer_reputation<F>(&mut self, node_id: &NodeId, f: F) -> Result<PeerReputation, PeerManagerError>
+    where F: FnOnce(&mut PeerReputation) {
+        let peer_key = *self
+            .node_id_index
+            .get(node_id)
+            .ok_or(PeerManagerError::PeerNotFoundError)?;
+        let mut peer: Peer = self
+            .peer_db
+            .get(&peer_key)
+            .map_err(PeerManagerError::DatabaseError)?
+            .expect("node_id_index is out of sync with peer db");
+        f(&mut peer.reputation);
+        let reputation = peer.reputation.clone();
+        self.peer_db
+            .insert(peer_key, peer)
+            .map_err(PeerManagerError::DatabaseError)?;
+        Ok(reputation)
+    }
+
     pub fn mark_last_seen(&mut self, node_id: &NodeId) -> Result<(), PeerManagerError> {
         let mut peer = self
             .find_by_node_id(node_id)?
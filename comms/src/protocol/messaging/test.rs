@@ -128,7 +128,7 @@ async fn new_inbound_substream_handling() {
     let (_, muxer_ours, mut muxer_theirs) = transport::build_multiplexed_connections().await;
 
     // Notify the messaging protocol that a new substream has been established that wants to talk the messaging.
-    let stream_ours = muxer_ours.get_yamux_control().open_stream().await.unwrap();
+    let stream_ours = muxer_ours.get_control().open_stream().await.unwrap();
     proto_tx
         .send(ProtocolNotification::new(
             MESSAGING_PROTOCOL.clone(),
@@ -24,7 +24,7 @@ use std::{fmt, sync::Arc};
 
 use async_trait::async_trait;
 
-use super::RpcError;
+use super::{server::RequestQuota, RpcError, RpcStatus};
 use crate::{
     connectivity::{ConnectivityRequester, ConnectivitySelection},
     peer_manager::{NodeId, OrNotFound, Peer},
@@ -90,6 +90,7 @@ pub struct RequestContext {
     request_id: u32,
     backend: Box<dyn RpcCommsProvider>,
     node_id: NodeId,
+    quota: Option<RequestQuota>,
 }
 
 impl RequestContext {
@@ -98,9 +99,15 @@ impl RequestContext {
             request_id,
             backend,
             node_id,
+            quota: None,
         }
     }
 
+    pub(super) fn with_quota(mut self, quota: RequestQuota) -> Self {
+        self.quota = Some(quota);
+        self
+    }
+
     pub fn peer_node_id(&self) -> &NodeId {
         &self.node_id
     }
@@ -109,6 +116,20 @@ impl RequestContext {
         self.request_id
     }
 
+    /// Consumes `cost` tokens from the RPC quotas of the requesting peer, returning a `QuotaExceeded` status if
+    /// there are insufficient tokens. This is a no-op if the server has no quotas configured.
+    pub fn consume_quota(&self, cost: u32) -> Result<(), RpcStatus> {
+        match self.quota {
+            Some(ref quota) => quota.consume(cost),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the quota handle for this request, or `None` if the server has no quotas configured.
+    pub fn quota(&self) -> Option<RequestQuota> {
+        self.quota.clone()
+    }
+
     pub(crate) async fn fetch_peer(&self) -> Result<Peer, RpcError> {
         self.backend.fetch_peer(&self.node_id).await
     }
@@ -126,6 +147,7 @@ impl fmt::Debug for RequestContext {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("RequestContext")
             .field("node_id", &self.node_id)
+            .field("quota", &self.quota)
             .field("backend", &"dyn RpcCommsProvider")
             .finish()
     }
@@ -33,6 +33,8 @@ use crate::{
         body::{Body, IntoBody},
         context::RequestContext,
         error::HandshakeRejectReason,
+        RequestQuota,
+        RpcStatus,
         RpcStatusCode,
     },
 };
@@ -95,6 +97,21 @@ impl<T> Request<T> {
         )
     }
 
+    /// Consumes `cost` tokens from the RPC quotas of the requesting peer. Requests without a context (i.e. in tests)
+    /// are not subject to quotas.
+    pub fn consume_quota(&self, cost: u32) -> Result<(), RpcStatus> {
+        match self.context {
+            Some(ref context) => context.consume_quota(cost),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the quota handle for this request. Streaming methods can use this to charge the peer for each item or
+    /// batch sent. Returns `None` if the server has no quotas configured or the request has no context.
+    pub fn quota(&self) -> Option<RequestQuota> {
+        self.context.as_ref().and_then(|context| context.quota())
+    }
+
     /// Returns the request context that is provided to every service request.
     ///
     /// ## Panics
@@ -61,7 +61,7 @@ pub use body::{Body, ClientStreaming, IntoBody, Streaming};
 mod context;
 
 mod server;
-pub use server::{mock, NamedProtocolService, RpcServer, RpcServerError, RpcServerHandle};
+pub use server::{mock, NamedProtocolService, RequestQuota, RpcQuota, RpcServer, RpcServerError, RpcServerHandle};
 
 mod client;
 pub use client::{
@@ -116,3 +116,21 @@ pub fn outbound_response_bytes(node_id: &NodeId, protocol: &ProtocolId) -> Histo
 
     METER.with_label_values(&[node_id.to_string().as_str(), String::from_utf8_lossy(protocol).as_ref()])
 }
+
+pub fn quota_exceeded_counter(node_id: &NodeId, protocol: &ProtocolId, method: u32, quota: &str) -> IntCounter {
+    static METER: Lazy<IntCounterVec> = Lazy::new(|| {
+        tari_metrics::register_int_counter_vec(
+            "comms::rpc::server::quota_exceeded_count",
+            "The number of requests rejected due to an exhausted RPC quota per peer per protocol per method",
+            &["peer_id", "protocol", "method", "quota"],
+        )
+        .unwrap()
+    });
+
+    METER.with_label_values(&[
+        node_id.to_string().as_str(),
+        String::from_utf8_lossy(protocol).as_ref(),
+        method.to_string().as_str(),
+        quota,
+    ])
+}
@@ -34,6 +34,10 @@ mod metrics;
 
 pub mod mock;
 
+mod quota;
+use quota::RpcQuotaTracker;
+pub use quota::{RequestQuota, RpcQuota};
+
 mod router;
 use std::{
     borrow::Cow,
@@ -57,7 +61,7 @@ use super::{
     body::Body,
     context::{RequestContext, RpcCommsProvider},
     error::HandshakeRejectReason,
-    message::{Request, Response, RpcMessageFlags},
+    message::{Request, Response, RpcMessageFlags, RpcMethod},
     not_found::ProtocolServiceNotFound,
     status::RpcStatus,
     Handshake,
@@ -166,6 +170,8 @@ pub struct RpcServerBuilder {
     maximum_simultaneous_sessions: Option<usize>,
     minimum_client_deadline: Duration,
     handshake_timeout: Duration,
+    peer_quota: Option<RpcQuota>,
+    method_quota: Option<RpcQuota>,
 }
 
 impl RpcServerBuilder {
@@ -188,6 +194,18 @@ impl RpcServerBuilder {
         self
     }
 
+    /// Limits the total cost of requests a peer can make across all methods and sessions. Disabled by default.
+    pub fn with_peer_quota(mut self, quota: RpcQuota) -> Self {
+        self.peer_quota = Some(quota);
+        self
+    }
+
+    /// Limits the total cost of requests a peer can make to each individual method. Disabled by default.
+    pub fn with_method_quota(mut self, quota: RpcQuota) -> Self {
+        self.method_quota = Some(quota);
+        self
+    }
+
     pub fn finish(self) -> RpcServer {
         let (request_tx, request_rx) = mpsc::channel(10);
         RpcServer {
@@ -204,6 +222,8 @@ impl Default for RpcServerBuilder {
             maximum_simultaneous_sessions: Some(1000),
             minimum_client_deadline: Duration::from_secs(1),
             handshake_timeout: Duration::from_secs(15),
+            peer_quota: None,
+            method_quota: None,
         }
     }
 }
@@ -211,6 +231,7 @@ impl Default for RpcServerBuilder {
 pub(super) struct PeerRpcServer<TSvc, TCommsProvider> {
     executor: BoundedExecutor,
     config: RpcServerBuilder,
+    quota_tracker: Arc<RpcQuotaTracker>,
     service: TSvc,
     protocol_notifications: Option<ProtocolNotificationRx<Substream>>,
     comms_provider: TCommsProvider,
@@ -244,6 +265,7 @@ where
                 Some(num) => BoundedExecutor::from_current(num),
                 None => BoundedExecutor::allow_maximum(),
             },
+            quota_tracker: Arc::new(RpcQuotaTracker::new(config.peer_quota, config.method_quota)),
             config,
             service,
             protocol_notifications: Some(protocol_notifications),
@@ -376,6 +398,7 @@ where
 
         let service = ActivePeerRpcService::new(
             self.config.clone(),
+            self.quota_tracker.clone(),
             protocol,
             node_id.clone(),
             service,
@@ -399,6 +422,7 @@ where
 
 struct ActivePeerRpcService<TSvc, TCommsProvider> {
     config: RpcServerBuilder,
+    quota_tracker: Arc<RpcQuotaTracker>,
     protocol: ProtocolId,
     node_id: NodeId,
     service: TSvc,
@@ -414,6 +438,7 @@ where
 {
     pub(self) fn new(
         config: RpcServerBuilder,
+        quota_tracker: Arc<RpcQuotaTracker>,
         protocol: ProtocolId,
         node_id: NodeId,
         service: TSvc,
@@ -429,6 +454,7 @@ where
             )),
 
             config,
+            quota_tracker,
             protocol,
             node_id,
             service,
@@ -558,7 +584,7 @@ where
         );
 
         let req = Request::with_context(
-            self.create_request_context(request_id),
+            self.create_request_context(request_id, method),
             method,
             decoded_msg.payload.into(),
         );
@@ -727,8 +753,18 @@ where
         }
     }
 
-    fn create_request_context(&self, request_id: u32) -> RequestContext {
-        RequestContext::new(request_id, self.node_id.clone(), Box::new(self.comms_provider.clone()))
+    fn create_request_context(&self, request_id: u32, method: RpcMethod) -> RequestContext {
+        let context = RequestContext::new(request_id, self.node_id.clone(), Box::new(self.comms_provider.clone()));
+        if self.quota_tracker.is_enabled() {
+            context.with_quota(RequestQuota::new(
+                self.quota_tracker.clone(),
+                self.node_id.clone(),
+                self.protocol.clone(),
+                method,
+            ))
+        } else {
+            context
+        }
     }
 }
 
@@ -0,0 +1,316 @@
+//  Copyright 2022, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use super::metrics;
+use crate::{
+    peer_manager::NodeId,
+    protocol::{
+        rpc::{message::RpcMethod, RpcStatus},
+        ProtocolId,
+    },
+};
+
+/// Once a bucket map grows beyond this many entries, buckets that have fully refilled are discarded.
+const PRUNE_THRESHOLD: usize = 1024;
+
+/// Token bucket parameters for an RPC quota. A bucket starts with `capacity` tokens and is replenished at
+/// `refill_per_second` tokens per second, up to `capacity`. Each RPC call consumes the cost declared for that method
+/// (see the `cost` option of the `#[rpc(...)]` attribute).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RpcQuota {
+    pub capacity: u32,
+    pub refill_per_second: u32,
+}
+
+impl RpcQuota {
+    pub fn new(capacity: u32, refill_per_second: u32) -> Self {
+        Self {
+            capacity,
+            refill_per_second,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn full(quota: &RpcQuota, now: Instant) -> Self {
+        Self {
+            tokens: f64::from(quota.capacity),
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, quota: &RpcQuota, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        let tokens = self.tokens + elapsed * f64::from(quota.refill_per_second);
+        self.tokens = tokens.min(f64::from(quota.capacity));
+        self.last_refill = now;
+    }
+
+    fn has_tokens(&self, cost: f64) -> bool {
+        self.tokens >= cost
+    }
+
+    fn is_full(&self, quota: &RpcQuota) -> bool {
+        self.tokens >= f64::from(quota.capacity)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct MethodKey {
+    node_id: NodeId,
+    protocol: ProtocolId,
+    method: u32,
+}
+
+#[derive(Debug, Default)]
+struct QuotaState {
+    peers: HashMap<NodeId, TokenBucket>,
+    methods: HashMap<MethodKey, TokenBucket>,
+}
+
+/// Tracks per-peer and per-peer-per-method token buckets. A single tracker is shared by all sessions of an
+/// `RpcServer` so that a peer cannot sidestep its quota by opening more sessions.
+pub(super) struct RpcQuotaTracker {
+    peer_quota: Option<RpcQuota>,
+    method_quota: Option<RpcQuota>,
+    state: Mutex<QuotaState>,
+}
+
+impl RpcQuotaTracker {
+    pub fn new(peer_quota: Option<RpcQuota>, method_quota: Option<RpcQuota>) -> Self {
+        Self {
+            peer_quota,
+            method_quota,
+            state: Mutex::new(QuotaState::default()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.peer_quota.is_some() || self.method_quota.is_some()
+    }
+
+    /// Consumes `cost` tokens from both the peer and method buckets, or from neither if either bucket has
+    /// insufficient tokens. A cost larger than the capacity of a bucket is capped at that capacity, so that every
+    /// method can be called when the bucket is full.
+    fn try_consume(&self, node_id: &NodeId, protocol: &ProtocolId, method: u32, cost: u32) -> Result<(), RpcStatus> {
+        if cost == 0 || !self.is_enabled() {
+            return Ok(());
+        }
+
+        let now = Instant::now();
+        let mut state = self.state.lock().expect("RpcQuotaTracker lock poisoned");
+        let QuotaState { peers, methods } = &mut *state;
+
+        let mut peer_bucket = self.peer_quota.map(|quota| {
+            let bucket = peers
+                .entry(node_id.clone())
+                .or_insert_with(|| TokenBucket::full(&quota, now));
+            bucket.refill(&quota, now);
+            (bucket, f64::from(cost.min(quota.capacity)))
+        });
+        if let Some((bucket, cost)) = peer_bucket.as_ref() {
+            if !bucket.has_tokens(*cost) {
+                metrics::quota_exceeded_counter(node_id, protocol, method, "peer").inc();
+                return Err(RpcStatus::quota_exceeded("Peer RPC quota exceeded"));
+            }
+        }
+
+        if let Some(quota) = self.method_quota {
+            let key = MethodKey {
+                node_id: node_id.clone(),
+                protocol: protocol.clone(),
+                method,
+            };
+            let bucket = methods.entry(key).or_insert_with(|| TokenBucket::full(&quota, now));
+            bucket.refill(&quota, now);
+            let cost = f64::from(cost.min(quota.capacity));
+            if !bucket.has_tokens(cost) {
+                metrics::quota_exceeded_counter(node_id, protocol, method, "method").inc();
+                return Err(RpcStatus::quota_exceeded("Method RPC quota exceeded"));
+            }
+            bucket.tokens -= cost;
+        }
+
+        if let Some((bucket, cost)) = peer_bucket.as_mut() {
+            bucket.tokens -= *cost;
+        }
+
+        self.prune(&mut *state);
+
+        Ok(())
+    }
+
+    fn prune(&self, state: &mut QuotaState) {
+        let now = Instant::now();
+        if let Some(quota) = self.peer_quota {
+            if state.peers.len() > PRUNE_THRESHOLD {
+                state.peers.retain(|_, bucket| {
+                    bucket.refill(&quota, now);
+                    !bucket.is_full(&quota)
+                });
+            }
+        }
+        if let Some(quota) = self.method_quota {
+            if state.methods.len() > PRUNE_THRESHOLD {
+                state.methods.retain(|_, bucket| {
+                    bucket.refill(&quota, now);
+                    !bucket.is_full(&quota)
+                });
+            }
+        }
+    }
+}
+
+impl fmt::Debug for RpcQuotaTracker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RpcQuotaTracker")
+            .field("peer_quota", &self.peer_quota)
+            .field("method_quota", &self.method_quota)
+            .finish()
+    }
+}
+
+/// The quota handle given to each request, bound to the requesting peer and the protocol and method being called.
+/// Streaming methods can keep a clone of this handle to charge for each item or batch they send.
+#[derive(Debug, Clone)]
+pub struct RequestQuota {
+    tracker: Arc<RpcQuotaTracker>,
+    node_id: NodeId,
+    protocol: ProtocolId,
+    method: RpcMethod,
+}
+
+impl RequestQuota {
+    pub(super) fn new(tracker: Arc<RpcQuotaTracker>, node_id: NodeId, protocol: ProtocolId, method: RpcMethod) -> Self {
+        Self {
+            tracker,
+            node_id,
+            protocol,
+            method,
+        }
+    }
+
+    /// Consumes `cost` tokens from the RPC quotas of the requesting peer, returning a `QuotaExceeded` status if
+    /// there are insufficient tokens.
+    pub fn consume(&self, cost: u32) -> Result<(), RpcStatus> {
+        self.tracker
+            .try_consume(&self.node_id, &self.protocol, self.method.id(), cost)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{protocol::rpc::RpcStatusCode, test_utils::node_identity::build_node_identity};
+
+    fn node_id() -> NodeId {
+        build_node_identity(Default::default()).node_id().clone()
+    }
+
+    #[test]
+    fn token_bucket_refills_up_to_capacity() {
+        let quota = RpcQuota::new(10, 2);
+        let start = Instant::now();
+        let mut bucket = TokenBucket::full(&quota, start);
+        bucket.tokens = 0.0;
+        bucket.refill(&quota, start + Duration::from_secs(2));
+        assert!(bucket.has_tokens(4.0));
+        assert!(!bucket.has_tokens(5.0));
+        bucket.refill(&quota, start + Duration::from_secs(60));
+        assert!(bucket.is_full(&quota));
+        assert!(!bucket.has_tokens(11.0));
+    }
+
+    #[test]
+    fn it_limits_each_method_separately() {
+        let tracker = RpcQuotaTracker::new(None, Some(RpcQuota::new(3, 0)));
+        let node_id = node_id();
+        let protocol = ProtocolId::from_static(b"/test/quota");
+        tracker.try_consume(&node_id, &protocol, 1, 2).unwrap();
+        let err = tracker.try_consume(&node_id, &protocol, 1, 2).unwrap_err();
+        assert_eq!(err.as_status_code(), RpcStatusCode::QuotaExceeded);
+        tracker.try_consume(&node_id, &protocol, 2, 3).unwrap();
+        // Other peers have their own buckets
+        tracker.try_consume(&self::node_id(), &protocol, 1, 3).unwrap();
+    }
+
+    #[test]
+    fn it_limits_the_peer_across_methods() {
+        let tracker = RpcQuotaTracker::new(Some(RpcQuota::new(4, 0)), Some(RpcQuota::new(3, 0)));
+        let node_id = node_id();
+        let protocol = ProtocolId::from_static(b"/test/quota");
+        tracker.try_consume(&node_id, &protocol, 1, 3).unwrap();
+        // The method bucket is empty, so the peer bucket must not be charged
+        tracker.try_consume(&node_id, &protocol, 1, 1).unwrap_err();
+        tracker.try_consume(&node_id, &protocol, 2, 1).unwrap();
+        let err = tracker.try_consume(&node_id, &protocol, 3, 1).unwrap_err();
+        assert!(err.as_status_code().is_quota_exceeded());
+    }
+
+    #[test]
+    fn it_caps_the_cost_at_the_capacity() {
+        let tracker = RpcQuotaTracker::new(Some(RpcQuota::new(5, 0)), None);
+        let node_id = node_id();
+        let protocol = ProtocolId::from_static(b"/test/quota");
+        tracker.try_consume(&node_id, &protocol, 1, 100).unwrap();
+        tracker.try_consume(&node_id, &protocol, 1, 1).unwrap_err();
+    }
+
+    #[test]
+    fn it_does_nothing_when_disabled() {
+        let tracker = RpcQuotaTracker::new(None, None);
+        let node_id = node_id();
+        let protocol = ProtocolId::from_static(b"/test/quota");
+        for _ in 0..10 {
+            tracker.try_consume(&node_id, &protocol, 1, u32::MAX).unwrap();
+        }
+    }
+
+    #[test]
+    fn it_charges_the_request_peer_and_method() {
+        let tracker = Arc::new(RpcQuotaTracker::new(None, Some(RpcQuota::new(3, 0))));
+        let node_id = node_id();
+        let protocol = ProtocolId::from_static(b"/test/quota");
+        let quota = RequestQuota::new(tracker.clone(), node_id.clone(), protocol.clone(), 1.into());
+        let streaming_quota = quota.clone();
+        quota.consume(1).unwrap();
+        streaming_quota.consume(2).unwrap();
+        let err = streaming_quota.consume(1).unwrap_err();
+        assert!(err.as_status_code().is_quota_exceeded());
+        tracker.try_consume(&node_id, &protocol, 2, 3).unwrap();
+    }
+}
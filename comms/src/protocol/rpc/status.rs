@@ -106,6 +106,15 @@ impl RpcStatus {
         }
     }
 
+    /// Returns a status indicating that the peer has exhausted its RPC quota. The client may retry the request once
+    /// the quota has been replenished.
+    pub fn quota_exceeded<T: ToString>(details: T) -> Self {
+        Self {
+            code: RpcStatusCode::QuotaExceeded,
+            details: details.to_string(),
+        }
+    }
+
     /// Returns a closure that logs the given error and returns a generic general error that does not leak any
     /// potentially sensitive error information. Use this function with map_err to catch "miscellaneous" errors.
     pub fn log_internal_error<'a, E: std::error::Error + 'a>(target: &'a str) -> impl Fn(E) -> Self + 'a {
@@ -206,6 +215,8 @@ pub enum RpcStatusCode {
     Forbidden = 9,
     /// RPC conflict error
     Conflict = 10,
+    /// The peer has exhausted its RPC quota
+    QuotaExceeded = 11,
     // The following status represents anything that is not recognised (i.e not one of the above codes).
     /// Unrecognised RPC status code
     InvalidRpcStatusCode,
@@ -224,6 +235,10 @@ impl RpcStatusCode {
         self == Self::Timeout
     }
 
+    pub fn is_quota_exceeded(self) -> bool {
+        self == Self::QuotaExceeded
+    }
+
     pub fn as_u32(&self) -> u32 {
         *self as u32
     }
@@ -248,6 +263,7 @@ impl From<u32> for RpcStatusCode {
             8 => ProtocolError,
             9 => Forbidden,
             10 => Conflict,
+            11 => QuotaExceeded,
             _ => InvalidRpcStatusCode,
         }
     }
@@ -272,6 +288,7 @@ mod test {
         assert_eq!(RpcStatusCode::from(ProtocolError as u32), ProtocolError);
         assert_eq!(RpcStatusCode::from(Forbidden as u32), Forbidden);
         assert_eq!(RpcStatusCode::from(Conflict as u32), Conflict);
+        assert_eq!(RpcStatusCode::from(QuotaExceeded as u32), QuotaExceeded);
         assert_eq!(RpcStatusCode::from(123), InvalidRpcStatusCode);
     }
 }
@@ -290,6 +290,7 @@ impl<T: GreetingRpc> __rpc_deps::Service<Request<__rpc_deps::Bytes>> for Greetin
             // say_hello
             1 => {
                 let fut = async move {
+                    req.consume_quota(1)?;
                     let resp = inner.say_hello(req.decode()?).await?;
                     Ok(resp.map(IntoBody::into_body))
                 };
@@ -298,6 +299,7 @@ impl<T: GreetingRpc> __rpc_deps::Service<Request<__rpc_deps::Bytes>> for Greetin
             // return_error
             2 => {
                 let fut = async move {
+                    req.consume_quota(1)?;
                     let resp = inner.return_error(req.decode()?).await?;
                     Ok(resp.map(IntoBody::into_body))
                 };
@@ -306,6 +308,7 @@ impl<T: GreetingRpc> __rpc_deps::Service<Request<__rpc_deps::Bytes>> for Greetin
             // get_greetings
             3 => {
                 let fut = async move {
+                    req.consume_quota(1)?;
                     let resp = inner.get_greetings(req.decode()?).await?;
                     Ok(Response::new(resp.into_body()))
                 };
@@ -314,6 +317,7 @@ impl<T: GreetingRpc> __rpc_deps::Service<Request<__rpc_deps::Bytes>> for Greetin
             // streaming_error
             4 => {
                 let fut = async move {
+                    req.consume_quota(1)?;
                     let resp = inner.streaming_error(req.decode()?).await?;
                     Ok(Response::new(resp.into_body()))
                 };
@@ -322,6 +326,7 @@ impl<T: GreetingRpc> __rpc_deps::Service<Request<__rpc_deps::Bytes>> for Greetin
             // streaming_error2
             5 => {
                 let fut = async move {
+                    req.consume_quota(1)?;
                     let resp = inner.streaming_error2(req.decode()?).await?;
                     Ok(Response::new(resp.into_body()))
                 };
@@ -330,6 +335,7 @@ impl<T: GreetingRpc> __rpc_deps::Service<Request<__rpc_deps::Bytes>> for Greetin
             // get_public_key_hex
             6 => {
                 let fut = async move {
+                    req.consume_quota(1)?;
                     let resp = inner.get_public_key_hex(req.decode()?).await?;
                     Ok(Response::new(resp.into_body()))
                 };
@@ -338,6 +344,7 @@ impl<T: GreetingRpc> __rpc_deps::Service<Request<__rpc_deps::Bytes>> for Greetin
             // reply_with_msg_of_size
             7 => {
                 let fut = async move {
+                    req.consume_quota(1)?;
                     let resp = inner.reply_with_msg_of_size(req.decode()?).await?;
                     Ok(Response::new(resp.into_body()))
                 };
@@ -346,6 +353,7 @@ impl<T: GreetingRpc> __rpc_deps::Service<Request<__rpc_deps::Bytes>> for Greetin
             // slow_stream
             8 => {
                 let fut = async move {
+                    req.consume_quota(1)?;
                     let resp = inner.slow_stream(req.decode()?).await?;
                     Ok(Response::new(resp.into_body()))
                 };
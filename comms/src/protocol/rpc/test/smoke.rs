@@ -34,13 +34,14 @@ use tokio::{
 
 use crate::{
     framing,
-    multiplexing::Yamux,
+    multiplexing::Multiplexed,
     protocol::{
         rpc,
         rpc::{
             context::RpcCommsBackend,
             error::HandshakeRejectReason,
             handshake::RpcHandshakeError,
+            server::RpcServerBuilder,
             test::{
                 greeting_service::{
                     GreetingClient,
@@ -54,6 +55,7 @@ use crate::{
                 mock::create_mocked_rpc_context,
             },
             RpcError,
+            RpcQuota,
             RpcServer,
             RpcStatusCode,
         },
@@ -75,6 +77,21 @@ pub(super) async fn setup_service<T: GreetingRpc>(
     task::JoinHandle<()>,
     RpcCommsBackend,
     Shutdown,
+) {
+    let builder = RpcServer::builder()
+        .with_maximum_simultaneous_sessions(num_concurrent_sessions)
+        .with_minimum_client_deadline(Duration::from_secs(0));
+    setup_service_with_builder(service_impl, builder).await
+}
+
+pub(super) async fn setup_service_with_builder<T: GreetingRpc>(
+    service_impl: T,
+    builder: RpcServerBuilder,
+) -> (
+    mpsc::Sender<ProtocolNotification<Substream>>,
+    task::JoinHandle<()>,
+    RpcCommsBackend,
+    Shutdown,
 ) {
     let (notif_tx, notif_rx) = mpsc::channel(1);
     let shutdown = Shutdown::new();
@@ -83,9 +100,7 @@ pub(super) async fn setup_service<T: GreetingRpc>(
         let context = context.clone();
         let shutdown_signal = shutdown.to_signal();
         async move {
-            let fut = RpcServer::builder()
-                .with_maximum_simultaneous_sessions(num_concurrent_sessions)
-                .with_minimum_client_deadline(Duration::from_secs(0))
+            let fut = builder
                 .finish()
                 .add_service(GreetingServer::new(service_impl))
                 .serve(notif_rx, context);
@@ -103,10 +118,32 @@ pub(super) async fn setup_service<T: GreetingRpc>(
 pub(super) async fn setup<T: GreetingRpc>(
     service_impl: T,
     num_concurrent_sessions: usize,
-) -> (Yamux, Yamux, task::JoinHandle<()>, Arc<NodeIdentity>, Shutdown) {
-    let (notif_tx, server_hnd, context, shutdown) = setup_service(service_impl, num_concurrent_sessions).await;
+) -> (
+    Multiplexed,
+    Multiplexed,
+    task::JoinHandle<()>,
+    Arc<NodeIdentity>,
+    Shutdown,
+) {
+    let builder = RpcServer::builder()
+        .with_maximum_simultaneous_sessions(num_concurrent_sessions)
+        .with_minimum_client_deadline(Duration::from_secs(0));
+    setup_with_builder(service_impl, builder).await
+}
+
+pub(super) async fn setup_with_builder<T: GreetingRpc>(
+    service_impl: T,
+    builder: RpcServerBuilder,
+) -> (
+    Multiplexed,
+    Multiplexed,
+    task::JoinHandle<()>,
+    Arc<NodeIdentity>,
+    Shutdown,
+) {
+    let (notif_tx, server_hnd, context, shutdown) = setup_service_with_builder(service_impl, builder).await;
     let (_, inbound, outbound) = build_multiplexed_connections().await;
-    let substream = outbound.get_yamux_control().open_stream().await.unwrap();
+    let substream = outbound.get_control().open_stream().await.unwrap();
 
     let node_identity = build_node_identity(Default::default());
     // Notify that a peer wants to speak the greeting RPC protocol
@@ -331,7 +368,7 @@ async fn unknown_protocol() {
     let (notif_tx, _, _, _shutdown) = setup_service(GreetingService::new(&[]), 1).await;
 
     let (_, inbound, mut outbound) = build_multiplexed_connections().await;
-    let in_substream = inbound.get_yamux_control().open_stream().await.unwrap();
+    let in_substream = inbound.get_control().open_stream().await.unwrap();
 
     let node_identity = build_node_identity(Default::default());
 
@@ -453,3 +490,42 @@ async fn stream_interruption_handling() {
         .unwrap()
         .unwrap();
 }
+
+#[runtime::test]
+async fn quota_exceeded() {
+    let builder = RpcServer::builder()
+        .with_minimum_client_deadline(Duration::from_secs(0))
+        .with_peer_quota(RpcQuota::new(3, 0))
+        .with_method_quota(RpcQuota::new(2, 0));
+    let (mut muxer, _outbound, _, _, _shutdown) = setup_with_builder(GreetingService::default(), builder).await;
+    let socket = muxer.incoming_mut().next().await.unwrap();
+    let framed = framing::canonical(socket, 1024);
+    let mut client = GreetingClient::builder().connect(framed).await.unwrap();
+
+    for _ in 0..2 {
+        client
+            .say_hello(SayHelloRequest {
+                name: "Yathvan".to_string(),
+                language: 1,
+            })
+            .await
+            .unwrap();
+    }
+
+    // Method quota is exhausted
+    let err = client
+        .say_hello(SayHelloRequest {
+            name: "Yathvan".to_string(),
+            language: 1,
+        })
+        .await
+        .unwrap_err();
+    unpack_enum!(RpcError::RequestFailed(status) = err);
+    assert_eq!(status.as_status_code(), RpcStatusCode::QuotaExceeded);
+
+    // Another method still has quota remaining in its own bucket, but the peer quota only has one token left
+    client.get_public_key_hex().await.unwrap();
+    let err = client.get_public_key_hex().await.unwrap_err();
+    unpack_enum!(RpcError::RequestFailed(status) = err);
+    assert!(status.as_status_code().is_quota_exceeded());
+}
@@ -260,6 +260,7 @@ impl ConnectivityManagerMock {
             },
             GetAllConnectionStates(_) => unimplemented!(),
             BanPeer(_, _, _) => {},
+            ReportPeer(_, _) => {},
             AddPeerToAllowList(_) => {},
             RemovePeerFromAllowList(_) => {},
             GetActiveConnections(reply) => {
@@ -41,7 +41,7 @@ use crate::{
     },
     multiaddr::Multiaddr,
     multiplexing,
-    multiplexing::{IncomingSubstreams, Substream, Yamux},
+    multiplexing::{IncomingSubstreams, Multiplexed, Substream},
     peer_manager::{NodeId, Peer, PeerFeatures},
     test_utils::{node_identity::build_node_identity, transport},
     utils::atomic_ref_counter::AtomicRefCounter,
@@ -132,8 +132,8 @@ pub struct PeerConnectionMockState {
 }
 
 impl PeerConnectionMockState {
-    pub fn new(muxer: Yamux) -> Self {
-        let control = muxer.get_yamux_control();
+    pub fn new(muxer: Multiplexed) -> Self {
+        let control = muxer.get_control();
         let substream_counter = control.substream_counter();
         Self {
             call_count: Arc::new(AtomicUsize::new(0)),
@@ -178,7 +178,7 @@ pub struct PeerConnectionMock {
 }
 
 impl PeerConnectionMock {
-    pub fn new(receiver: mpsc::Receiver<PeerConnectionRequest>, muxer: Yamux) -> Self {
+    pub fn new(receiver: mpsc::Receiver<PeerConnectionRequest>, muxer: Multiplexed) -> Self {
         Self {
             receiver,
             state: PeerConnectionMockState::new(muxer),
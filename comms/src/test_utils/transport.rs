@@ -26,7 +26,7 @@ use crate::{
     connection_manager::ConnectionDirection,
     memsocket::MemorySocket,
     multiaddr::Multiaddr,
-    multiplexing::Yamux,
+    multiplexing::{Multiplexed, Yamux},
     transports::{MemoryTransport, Transport},
 };
 
@@ -37,7 +37,7 @@ pub async fn build_connected_sockets() -> (Multiaddr, MemorySocket, MemorySocket
     (addr, dial_sock.unwrap(), listen_sock)
 }
 
-pub async fn build_multiplexed_connections() -> (Multiaddr, Yamux, Yamux) {
+pub async fn build_multiplexed_connections() -> (Multiaddr, Multiplexed, Multiplexed) {
     let (addr, socket_out, socket_in) = build_connected_sockets().await;
 
     let muxer_out = Yamux::upgrade_connection(socket_out, ConnectionDirection::Outbound).unwrap();
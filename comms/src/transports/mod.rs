@@ -24,9 +24,18 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use std::io;
+
 use multiaddr::Multiaddr;
+use tokio::io::{AsyncRead, AsyncWrite};
 use tokio_stream::Stream;
 
+use crate::{
+    connection_manager::ConnectionDirection,
+    multiplexing::{Multiplexed, Yamux},
+    noise::NoiseSocket,
+};
+
 mod dns;
 
 pub mod predicate;
@@ -34,6 +43,9 @@ pub mod predicate;
 mod memory;
 pub use memory::MemoryTransport;
 
+mod quic;
+pub use quic::{QuicSocket, QuicTransport};
+
 mod socks;
 pub use socks::{SocksConfig, SocksTransport};
 
@@ -43,6 +55,9 @@ pub use tcp::TcpTransport;
 mod tcp_with_tor;
 pub use tcp_with_tor::TcpWithTorTransport;
 
+mod websocket;
+pub use websocket::{WebSocketTransport, WsStream};
+
 #[crate::async_trait]
 pub trait Transport {
     /// The output of the transport after a connection is established
@@ -57,4 +72,16 @@ pub trait Transport {
 
     /// Connect (dial) to the given multiaddr
     async fn dial(&self, addr: Multiaddr) -> Result<Self::Output, Self::Error>;
+
+    /// Upgrade an authenticated connection so that substreams can be opened over it. By default substreams are
+    /// multiplexed over the socket using yamux. Transports with native stream support override this.
+    async fn upgrade_multiplexed(
+        socket: NoiseSocket<Self::Output>,
+        direction: ConnectionDirection,
+    ) -> io::Result<Multiplexed>
+    where
+        Self::Output: AsyncRead + AsyncWrite + Send + Unpin + 'static,
+    {
+        Yamux::upgrade_connection(socket, direction)
+    }
 }
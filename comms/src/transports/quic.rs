@@ -0,0 +1,638 @@
+// Copyright 2022, The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{
+    io,
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr},
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, SystemTime},
+};
+
+use blake2::{Blake2b, Digest};
+use futures::{future::BoxFuture, stream::FuturesUnordered, FutureExt, StreamExt};
+use log::*;
+use multiaddr::{Multiaddr, Protocol};
+use quinn::{
+    ClientConfig,
+    Connecting,
+    Connection,
+    Endpoint,
+    Incoming,
+    IncomingBiStreams,
+    NewConnection,
+    ServerConfig,
+    TransportConfig,
+    VarInt,
+};
+use rustls::{
+    client::{ServerCertVerified, ServerCertVerifier, ServerName},
+    Certificate,
+    PrivateKey,
+};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf},
+    net::{lookup_host, TcpStream},
+    time,
+};
+use tokio_stream::Stream;
+
+use super::{TcpTransport, Transport};
+use crate::{
+    connection_manager::ConnectionDirection,
+    multiplexing::{Multiplexed, Quic, QuicStream, Yamux},
+    noise::NoiseSocket,
+};
+
+const LOG_TARGET: &str = "comms::transports::quic";
+
+/// The name placed in (and expected from) self-signed certificates. Certificates are not used to identify peers.
+const SERVER_NAME: &str = "tari";
+const MAX_CONCURRENT_STREAMS: u32 = 1024;
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(5);
+const CHANNEL_BINDING_TIMEOUT: Duration = Duration::from_secs(10);
+
+type PendingConnection = BoxFuture<'static, io::Result<(QuicSocket, Multiaddr)>>;
+
+/// Transport implementation for QUIC. Addresses are UDP/IP or DNS addresses followed by `/quic`, for example
+/// `/ip4/127.0.0.1/udp/18189/quic`.
+///
+/// Peers are authenticated by the usual noise handshake and identity exchange, which run over the first stream of the
+/// connection. QUIC's own TLS session uses a throwaway self-signed certificate, so once the noise session is
+/// established both sides confirm that they see the same certificate. This binds the noise session to the QUIC
+/// connection and prevents a relaying node from opening substreams on behalf of the peer. Substreams are native
+/// QUIC streams, so a slow substream does not hold up the others.
+///
+/// Outbound connections are made from the listener's endpoint, so that they come from the advertised UDP port. Peers
+/// that only have a TCP/IP or DNS address are dialed over TCP and multiplexed with yamux, so a QUIC node can still
+/// connect to the rest of the network.
+#[derive(Clone)]
+pub struct QuicTransport {
+    server_config: ServerConfig,
+    client_config: ClientConfig,
+    certificate_fingerprint: Arc<Vec<u8>>,
+    handshake_timeout: Duration,
+    endpoint: Arc<Mutex<Option<Endpoint>>>,
+    tcp_transport: TcpTransport,
+}
+
+impl QuicTransport {
+    // #[doc("Sets the time allowed for the QUIC handshake of a new connection.")]
+    setter_mut!(set_handshake_timeout, handshake_timeout, Duration);
+
+    /// Create a new QuicTransport with a newly generated self-signed certificate
+    pub fn new() -> io::Result<Self> {
+        let certificate = rcgen::generate_simple_self_signed(vec![SERVER_NAME.to_string()]).map_err(into_io_error)?;
+        let certificate_der = certificate.serialize_der().map_err(into_io_error)?;
+        let certificate_fingerprint = certificate_fingerprint(&certificate_der);
+
+        let mut server_config = ServerConfig::with_single_cert(
+            vec![Certificate(certificate_der)],
+            PrivateKey(certificate.serialize_private_key_der()),
+        )
+        .map_err(into_io_error)?;
+        server_config.transport = Arc::new(transport_config());
+
+        let crypto = rustls::ClientConfig::builder()
+            .with_safe_defaults()
+            .with_custom_certificate_verifier(Arc::new(AcceptAnyCertificate))
+            .with_no_client_auth();
+        let mut client_config = ClientConfig::new(Arc::new(crypto));
+        client_config.transport = Arc::new(transport_config());
+
+        Ok(Self {
+            server_config,
+            client_config,
+            certificate_fingerprint: Arc::new(certificate_fingerprint),
+            handshake_timeout: Duration::from_secs(10),
+            endpoint: Arc::new(Mutex::new(None)),
+            tcp_transport: TcpTransport::new(),
+        })
+    }
+
+    pub fn tcp_transport_mut(&mut self) -> &mut TcpTransport {
+        &mut self.tcp_transport
+    }
+
+    /// Returns the endpoint to dial `socket_addr` from. This is the listener's endpoint if it is listening on the same
+    /// address family, otherwise a client endpoint that is created on the first dial and shared by later dials.
+    fn dial_endpoint(&self, socket_addr: &SocketAddr) -> io::Result<Endpoint> {
+        let mut endpoint = self.endpoint.lock().unwrap();
+        if let Some(endpoint) = endpoint.as_ref() {
+            if endpoint.local_addr()?.is_ipv4() == socket_addr.is_ipv4() {
+                return Ok(endpoint.clone());
+            }
+            // The endpoint cannot reach this address family, use a separate endpoint for this connection
+            return Endpoint::client(unspecified_addr(socket_addr));
+        }
+
+        let client_endpoint = Endpoint::client(unspecified_addr(socket_addr))?;
+        *endpoint = Some(client_endpoint.clone());
+        Ok(client_endpoint)
+    }
+}
+
+#[crate::async_trait]
+impl Transport for QuicTransport {
+    type Error = io::Error;
+    type Listener = QuicInbound;
+    type Output = QuicSocket;
+
+    async fn listen(&self, addr: Multiaddr) -> Result<(Self::Listener, Multiaddr), Self::Error> {
+        let socket_addr = resolve_quic_address(&addr).await?;
+        let (endpoint, incoming) = Endpoint::server(self.server_config.clone(), socket_addr)?;
+        let local_addr = socketaddr_to_quic_multiaddr(endpoint.local_addr()?);
+        *self.endpoint.lock().unwrap() = Some(endpoint.clone());
+        let listener = QuicInbound::new(
+            endpoint,
+            incoming,
+            self.certificate_fingerprint.clone(),
+            self.handshake_timeout,
+        );
+        Ok((listener, local_addr))
+    }
+
+    async fn dial(&self, addr: Multiaddr) -> Result<Self::Output, Self::Error> {
+        if !is_quic_address(&addr) {
+            let socket = self.tcp_transport.dial(addr).await?;
+            return Ok(QuicSocket::tcp(socket));
+        }
+        let socket_addr = resolve_quic_address(&addr).await?;
+        let endpoint = self.dial_endpoint(&socket_addr)?;
+        let connecting = endpoint
+            .connect_with(self.client_config.clone(), socket_addr, SERVER_NAME)
+            .map_err(into_io_error)?;
+        let NewConnection {
+            connection, bi_streams, ..
+        } = time::timeout(self.handshake_timeout, connecting)
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "QUIC handshake timed out"))?
+            .map_err(into_io_error)?;
+
+        let certificate_fingerprint = peer_certificate_fingerprint(&connection)?;
+        let (send, recv) = connection.open_bi().await.map_err(into_io_error)?;
+        Ok(QuicSocket::quic(
+            connection,
+            bi_streams,
+            QuicStream::new(send, recv),
+            certificate_fingerprint,
+        ))
+    }
+
+    async fn upgrade_multiplexed(
+        mut socket: NoiseSocket<Self::Output>,
+        direction: ConnectionDirection,
+    ) -> io::Result<Multiplexed> {
+        let certificate_fingerprint = match socket.get_ref().certificate_fingerprint() {
+            Some(certificate_fingerprint) => certificate_fingerprint.to_vec(),
+            None => return Yamux::upgrade_connection(socket, direction),
+        };
+        let their_fingerprint = time::timeout(
+            CHANNEL_BINDING_TIMEOUT,
+            exchange_certificate_fingerprints(&mut socket, &certificate_fingerprint),
+        )
+        .await
+        .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "QUIC channel binding timed out"))??;
+
+        let (connection, incoming) = socket
+            .into_inner()
+            .into_connection()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Expected a QUIC connection"))?;
+        if their_fingerprint != certificate_fingerprint {
+            warn!(
+                target: LOG_TARGET,
+                "Peer at {} saw a different QUIC certificate to this node. The connection may be relayed.",
+                connection.remote_address()
+            );
+            connection.close(VarInt::from_u32(0), b"");
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "QUIC certificate does not match the certificate seen by the peer",
+            ));
+        }
+
+        Ok(Quic::upgrade_connection(connection, incoming))
+    }
+}
+
+/// Writes our view of the server certificate fingerprint over the noise session and reads the peer's view of it
+async fn exchange_certificate_fingerprints(
+    socket: &mut NoiseSocket<QuicSocket>,
+    certificate_fingerprint: &[u8],
+) -> io::Result<Vec<u8>> {
+    socket.write_all(certificate_fingerprint).await?;
+    socket.flush().await?;
+    let mut buf = vec![0u8; certificate_fingerprint.len()];
+    socket.read_exact(&mut buf).await?;
+    Ok(buf)
+}
+
+/// Accepts QUIC connections and waits for the first stream of each of them. Connections are accepted concurrently so
+/// that a slow client does not hold up other connections.
+pub struct QuicInbound {
+    // Keeps the endpoint open for as long as the listener is alive
+    _endpoint: Endpoint,
+    incoming: Incoming,
+    certificate_fingerprint: Arc<Vec<u8>>,
+    handshake_timeout: Duration,
+    pending_connections: FuturesUnordered<PendingConnection>,
+}
+
+impl QuicInbound {
+    fn new(
+        endpoint: Endpoint,
+        incoming: Incoming,
+        certificate_fingerprint: Arc<Vec<u8>>,
+        handshake_timeout: Duration,
+    ) -> Self {
+        Self {
+            _endpoint: endpoint,
+            incoming,
+            certificate_fingerprint,
+            handshake_timeout,
+            pending_connections: FuturesUnordered::new(),
+        }
+    }
+}
+
+impl Stream for QuicInbound {
+    type Item = io::Result<(QuicSocket, Multiaddr)>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match self.incoming.poll_next_unpin(cx) {
+                Poll::Ready(Some(connecting)) => {
+                    let certificate_fingerprint = (*self.certificate_fingerprint).clone();
+                    let handshake_timeout = self.handshake_timeout;
+                    self.pending_connections
+                        .push(accept_connection(connecting, certificate_fingerprint, handshake_timeout).boxed());
+                },
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => break,
+            }
+        }
+
+        match self.pending_connections.poll_next_unpin(cx) {
+            Poll::Ready(Some(result)) => Poll::Ready(Some(result)),
+            // No connections are in progress, the endpoint will wake this task on the next connection
+            Poll::Ready(None) | Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+async fn accept_connection(
+    connecting: Connecting,
+    certificate_fingerprint: Vec<u8>,
+    handshake_timeout: Duration,
+) -> io::Result<(QuicSocket, Multiaddr)> {
+    let remote_address = connecting.remote_address();
+    let accept = async move {
+        let NewConnection {
+            connection,
+            mut bi_streams,
+            ..
+        } = connecting.await.map_err(into_io_error)?;
+        // The dialer opens the first stream straight away to perform the noise handshake
+        let (send, recv) = bi_streams
+            .next()
+            .await
+            .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "QUIC connection closed without a stream"))?
+            .map_err(into_io_error)?;
+        Ok(QuicSocket::quic(
+            connection,
+            bi_streams,
+            QuicStream::new(send, recv),
+            certificate_fingerprint,
+        ))
+    };
+
+    let socket = time::timeout(handshake_timeout, accept)
+        .await
+        .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "QUIC handshake timed out"))?
+        .map_err(|err: io::Error| {
+            debug!(
+                target: LOG_TARGET,
+                "QUIC connection from '{}' failed: {}", remote_address, err
+            );
+            err
+        })?;
+    Ok((socket, socketaddr_to_quic_multiaddr(remote_address)))
+}
+
+/// The first stream of a QUIC connection, over which the connection is authenticated. The connection is taken over by
+/// the QUIC multiplexer once the peer is authenticated. Connections dialed to peers without a QUIC address are plain
+/// TCP connections.
+pub struct QuicSocket {
+    inner: QuicSocketInner,
+}
+
+enum QuicSocketInner {
+    Quic {
+        connection: Connection,
+        incoming: IncomingBiStreams,
+        stream: QuicStream,
+        /// Fingerprint of the server certificate of this connection
+        certificate_fingerprint: Vec<u8>,
+    },
+    Tcp(TcpStream),
+}
+
+impl QuicSocket {
+    fn quic(
+        connection: Connection,
+        incoming: IncomingBiStreams,
+        stream: QuicStream,
+        certificate_fingerprint: Vec<u8>,
+    ) -> Self {
+        Self {
+            inner: QuicSocketInner::Quic {
+                connection,
+                incoming,
+                stream,
+                certificate_fingerprint,
+            },
+        }
+    }
+
+    fn tcp(socket: TcpStream) -> Self {
+        Self {
+            inner: QuicSocketInner::Tcp(socket),
+        }
+    }
+
+    /// Returns the fingerprint of the server certificate, or None if this is a TCP connection
+    fn certificate_fingerprint(&self) -> Option<&[u8]> {
+        match &self.inner {
+            QuicSocketInner::Quic {
+                certificate_fingerprint,
+                ..
+            } => Some(certificate_fingerprint),
+            QuicSocketInner::Tcp(_) => None,
+        }
+    }
+
+    fn into_connection(self) -> Option<(Connection, IncomingBiStreams)> {
+        match self.inner {
+            QuicSocketInner::Quic {
+                connection, incoming, ..
+            } => Some((connection, incoming)),
+            QuicSocketInner::Tcp(_) => None,
+        }
+    }
+}
+
+impl AsyncRead for QuicSocket {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        match &mut self.inner {
+            QuicSocketInner::Quic { stream, .. } => Pin::new(stream).poll_read(cx, buf),
+            QuicSocketInner::Tcp(socket) => Pin::new(socket).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for QuicSocket {
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        match &mut self.inner {
+            QuicSocketInner::Quic { stream, .. } => Pin::new(stream).poll_write(cx, buf),
+            QuicSocketInner::Tcp(socket) => Pin::new(socket).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match &mut self.inner {
+            QuicSocketInner::Quic { stream, .. } => Pin::new(stream).poll_flush(cx),
+            QuicSocketInner::Tcp(socket) => Pin::new(socket).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match &mut self.inner {
+            QuicSocketInner::Quic { stream, .. } => Pin::new(stream).poll_shutdown(cx),
+            QuicSocketInner::Tcp(socket) => Pin::new(socket).poll_shutdown(cx),
+        }
+    }
+}
+
+/// Peers use throwaway self-signed certificates, so any certificate is accepted during the TLS handshake. The
+/// certificate is instead checked against the peer's view of it once the noise session is established.
+struct AcceptAnyCertificate;
+
+impl ServerCertVerifier for AcceptAnyCertificate {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &Certificate,
+        _intermediates: &[Certificate],
+        _server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+}
+
+fn transport_config() -> TransportConfig {
+    let mut config = TransportConfig::default();
+    config
+        .max_concurrent_bidi_streams(VarInt::from_u32(MAX_CONCURRENT_STREAMS))
+        .max_concurrent_uni_streams(VarInt::from_u32(0))
+        // Peer connections may be idle for long periods, keep-alives prevent the idle timeout from closing them
+        .keep_alive_interval(Some(KEEP_ALIVE_INTERVAL));
+    config
+}
+
+fn certificate_fingerprint(certificate_der: &[u8]) -> Vec<u8> {
+    Blake2b::digest(certificate_der).to_vec()
+}
+
+fn peer_certificate_fingerprint(connection: &Connection) -> io::Result<Vec<u8>> {
+    connection
+        .peer_identity()
+        .and_then(|identity| identity.downcast::<Vec<Certificate>>().ok())
+        .and_then(|certificates| {
+            certificates
+                .first()
+                .map(|certificate| certificate_fingerprint(&certificate.0))
+        })
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "QUIC peer did not present a certificate"))
+}
+
+fn is_quic_address(addr: &Multiaddr) -> bool {
+    matches!(addr.iter().last(), Some(Protocol::Quic))
+}
+
+/// Returns the unspecified address of the same address family as `socket_addr`, for binding a client endpoint
+fn unspecified_addr(socket_addr: &SocketAddr) -> SocketAddr {
+    if socket_addr.is_ipv4() {
+        SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0))
+    } else {
+        SocketAddr::from((Ipv6Addr::UNSPECIFIED, 0))
+    }
+}
+
+/// Resolves a `/ip4|ip6|dns|dns4|dns6/<host>/udp/<port>/quic` address to a socket address
+async fn resolve_quic_address(addr: &Multiaddr) -> io::Result<SocketAddr> {
+    let invalid_address = || io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid QUIC address '{}'", addr));
+    let mut iter = addr.iter();
+    let (host, port) = match (iter.next(), iter.next(), iter.next(), iter.next()) {
+        (Some(host), Some(Protocol::Udp(port)), Some(Protocol::Quic), None) => (host, port),
+        _ => return Err(invalid_address()),
+    };
+
+    match host {
+        Protocol::Ip4(ip) => Ok((ip, port).into()),
+        Protocol::Ip6(ip) => Ok((ip, port).into()),
+        Protocol::Dns(name) | Protocol::Dns4(name) | Protocol::Dns6(name) => lookup_host((&*name, port))
+            .await?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("Failed to resolve '{}'", addr))),
+        _ => Err(invalid_address()),
+    }
+}
+
+fn socketaddr_to_quic_multiaddr(socket_addr: SocketAddr) -> Multiaddr {
+    let mut addr = Multiaddr::from(socket_addr.ip());
+    addr.push(Protocol::Udp(socket_addr.port()));
+    addr.push(Protocol::Quic);
+    addr
+}
+
+fn into_io_error<E: std::error::Error + Send + Sync + 'static>(err: E) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, err)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::runtime;
+
+    #[runtime::test]
+    async fn resolve_address() {
+        let addr = resolve_quic_address(&"/ip4/127.0.0.1/udp/1234/quic".parse().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(addr, "127.0.0.1:1234".parse().unwrap());
+        let addr = resolve_quic_address(&"/ip6/::1/udp/1234/quic".parse().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(addr, "[::1]:1234".parse().unwrap());
+
+        resolve_quic_address(&"/ip4/127.0.0.1/udp/1234".parse().unwrap())
+            .await
+            .unwrap_err();
+        resolve_quic_address(&"/ip4/127.0.0.1/tcp/1234".parse().unwrap())
+            .await
+            .unwrap_err();
+        resolve_quic_address(&"/ip4/127.0.0.1/udp/1234/quic/ws".parse().unwrap())
+            .await
+            .unwrap_err();
+    }
+
+    #[runtime::test]
+    async fn listen_and_dial() {
+        let transport = QuicTransport::new().unwrap();
+        let (mut listener, addr) = transport
+            .listen("/ip4/127.0.0.1/udp/0/quic".parse().unwrap())
+            .await
+            .unwrap();
+        assert!(matches!(addr.iter().last(), Some(Protocol::Quic)));
+
+        // The first stream is only seen by the listener once the dialer has written to it
+        let (outbound, inbound) = tokio::join!(
+            async {
+                let mut socket = transport.dial(addr).await.unwrap();
+                socket.write_all(b"Oathbringer").await.unwrap();
+                socket.flush().await.unwrap();
+                socket
+            },
+            listener.next()
+        );
+        let (mut inbound, peer_addr) = inbound.unwrap().unwrap();
+        assert!(matches!(peer_addr.iter().nth(1), Some(Protocol::Udp(_))));
+
+        let mut buf = [0u8; 11];
+        inbound.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"Oathbringer");
+
+        // Both sides see the listener's certificate
+        assert_eq!(
+            outbound.certificate_fingerprint().unwrap(),
+            transport.certificate_fingerprint.as_slice()
+        );
+        assert_eq!(inbound.certificate_fingerprint(), outbound.certificate_fingerprint());
+    }
+
+    #[runtime::test]
+    async fn dial_from_listener_endpoint() {
+        let transport = QuicTransport::new().unwrap();
+        let (_listener, local_addr) = transport
+            .listen("/ip4/127.0.0.1/udp/0/quic".parse().unwrap())
+            .await
+            .unwrap();
+
+        let peer_transport = QuicTransport::new().unwrap();
+        let (mut peer_listener, peer_addr) = peer_transport
+            .listen("/ip4/127.0.0.1/udp/0/quic".parse().unwrap())
+            .await
+            .unwrap();
+
+        let (_outbound, inbound) = tokio::join!(
+            async {
+                let mut socket = transport.dial(peer_addr).await.unwrap();
+                socket.write_all(b"Rhythm").await.unwrap();
+                socket.flush().await.unwrap();
+                socket
+            },
+            peer_listener.next()
+        );
+        // The connection comes from the port that the dialer is listening on
+        let (_, remote_addr) = inbound.unwrap().unwrap();
+        assert_eq!(remote_addr.iter().nth(1), local_addr.iter().nth(1));
+    }
+
+    #[runtime::test]
+    async fn dial_tcp_peer() {
+        let tcp_transport = TcpTransport::new();
+        let (mut listener, addr) = tcp_transport
+            .listen("/ip4/127.0.0.1/tcp/0".parse().unwrap())
+            .await
+            .unwrap();
+
+        // A peer without a QUIC address is dialed over plain TCP
+        let transport = QuicTransport::new().unwrap();
+        let (outbound, inbound) = tokio::join!(transport.dial(addr), listener.next());
+        let mut outbound = outbound.unwrap();
+        assert!(outbound.certificate_fingerprint().is_none());
+        let (mut inbound, _) = inbound.unwrap().unwrap();
+
+        outbound.write_all(b"HELLO").await.unwrap();
+        outbound.flush().await.unwrap();
+        let mut buf = [0u8; 5];
+        inbound.read_exact(&mut buf).await.unwrap();
+        assert_eq!(buf, *b"HELLO");
+
+        inbound.write_all(b"WORLD").await.unwrap();
+        outbound.read_exact(&mut buf).await.unwrap();
+        assert_eq!(buf, *b"WORLD");
+    }
+}
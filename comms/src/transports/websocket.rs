@@ -0,0 +1,391 @@
+// Copyright 2022, The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{
+    borrow::Cow,
+    cmp,
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use bytes::Bytes;
+use futures::{future::BoxFuture, ready, stream::FuturesUnordered, FutureExt, Sink, StreamExt};
+use log::*;
+use multiaddr::{Multiaddr, Protocol};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    net::TcpStream,
+    time,
+};
+use tokio_stream::Stream;
+use tokio_tungstenite::{
+    accept_async,
+    client_async,
+    tungstenite::{self, Message},
+    WebSocketStream,
+};
+
+use super::{tcp::TcpInbound, TcpTransport, Transport};
+
+const LOG_TARGET: &str = "comms::transports::websocket";
+
+type PendingHandshake = BoxFuture<'static, io::Result<(WsStream<TcpStream>, Multiaddr)>>;
+
+/// Transport implementation for WebSockets. Addresses are TCP/IP or DNS addresses followed by `/ws`, for example
+/// `/ip4/127.0.0.1/tcp/18189/ws`. The WebSocket connection itself is unencrypted, the usual noise upgrade is applied on
+/// top of it. Peers that only have a plain TCP/IP or DNS address are dialed over TCP, so a WebSocket node can still
+/// connect to the rest of the network.
+#[derive(Clone)]
+pub struct WebSocketTransport {
+    tcp_transport: TcpTransport,
+    handshake_timeout: Duration,
+}
+
+impl WebSocketTransport {
+    // #[doc("Sets the time allowed for the WebSocket upgrade of a new connection.")]
+    setter_mut!(set_handshake_timeout, handshake_timeout, Duration);
+
+    /// Create a new WebSocketTransport
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn tcp_transport_mut(&mut self) -> &mut TcpTransport {
+        &mut self.tcp_transport
+    }
+}
+
+impl Default for WebSocketTransport {
+    fn default() -> Self {
+        Self {
+            tcp_transport: TcpTransport::new(),
+            handshake_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+#[crate::async_trait]
+impl Transport for WebSocketTransport {
+    type Error = io::Error;
+    type Listener = WebSocketInbound;
+    type Output = WsStream<TcpStream>;
+
+    async fn listen(&self, addr: Multiaddr) -> Result<(Self::Listener, Multiaddr), Self::Error> {
+        // Connections are accepted on any path
+        let (tcp_addr, path) = split_ws_address(&addr)?;
+        let (listener, local_addr) = self.tcp_transport.listen(tcp_addr).await?;
+        let local_addr = local_addr.with(Protocol::Ws(path));
+        Ok((WebSocketInbound::new(listener, self.handshake_timeout), local_addr))
+    }
+
+    async fn dial(&self, addr: Multiaddr) -> Result<Self::Output, Self::Error> {
+        if !is_ws_address(&addr) {
+            let socket = self.tcp_transport.dial(addr).await?;
+            return Ok(WsStream::plain(socket));
+        }
+        let (tcp_addr, path) = split_ws_address(&addr)?;
+        let url = format!("ws://{}{}", host_and_port(&tcp_addr)?, path);
+        let socket = self.tcp_transport.dial(tcp_addr).await?;
+        let (stream, _) = time::timeout(self.handshake_timeout, client_async(url, socket))
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "WebSocket handshake timed out"))?
+            .map_err(into_io_error)?;
+        Ok(WsStream::new(stream))
+    }
+}
+
+/// Accepts TCP connections and performs the WebSocket upgrade for each of them. Upgrades happen concurrently so that
+/// a slow client does not hold up other connections.
+pub struct WebSocketInbound {
+    listener: TcpInbound,
+    handshake_timeout: Duration,
+    pending_handshakes: FuturesUnordered<PendingHandshake>,
+}
+
+impl WebSocketInbound {
+    fn new(listener: TcpInbound, handshake_timeout: Duration) -> Self {
+        Self {
+            listener,
+            handshake_timeout,
+            pending_handshakes: FuturesUnordered::new(),
+        }
+    }
+}
+
+impl Stream for WebSocketInbound {
+    type Item = io::Result<(WsStream<TcpStream>, Multiaddr)>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match Pin::new(&mut self.listener).poll_next(cx) {
+                Poll::Ready(Some(Ok((socket, peer_addr)))) => {
+                    let handshake_timeout = self.handshake_timeout;
+                    self.pending_handshakes
+                        .push(accept_upgrade(socket, peer_addr, handshake_timeout).boxed());
+                },
+                Poll::Ready(Some(Err(err))) => return Poll::Ready(Some(Err(err))),
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => break,
+            }
+        }
+
+        match self.pending_handshakes.poll_next_unpin(cx) {
+            Poll::Ready(Some(result)) => Poll::Ready(Some(result)),
+            // No upgrades are in progress, the listener will wake this task on the next connection
+            Poll::Ready(None) | Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+async fn accept_upgrade(
+    socket: TcpStream,
+    peer_addr: Multiaddr,
+    handshake_timeout: Duration,
+) -> io::Result<(WsStream<TcpStream>, Multiaddr)> {
+    let stream = time::timeout(handshake_timeout, accept_async(socket))
+        .await
+        .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "WebSocket handshake timed out"))?
+        .map_err(|err| {
+            debug!(
+                target: LOG_TARGET,
+                "WebSocket upgrade from '{}' failed: {}", peer_addr, err
+            );
+            into_io_error(err)
+        })?;
+    // The peer address is left as a TCP/IP address so that it can be checked against the liveness allowlist
+    Ok((WsStream::new(stream), peer_addr))
+}
+
+/// Adapts a WebSocket connection to a byte stream. Each write is sent as a binary message and the payloads of received
+/// binary messages are read back in order. Connections dialed to peers without a WebSocket address are passed through
+/// unchanged.
+pub struct WsStream<S> {
+    inner: WsInner<S>,
+    read_buf: Bytes,
+}
+
+enum WsInner<S> {
+    WebSocket(WebSocketStream<S>),
+    Plain(S),
+}
+
+impl<S> WsStream<S> {
+    fn new(inner: WebSocketStream<S>) -> Self {
+        Self {
+            inner: WsInner::WebSocket(inner),
+            read_buf: Bytes::new(),
+        }
+    }
+
+    fn plain(inner: S) -> Self {
+        Self {
+            inner: WsInner::Plain(inner),
+            read_buf: Bytes::new(),
+        }
+    }
+}
+
+impl<S> AsyncRead for WsStream<S>
+where S: AsyncRead + AsyncWrite + Unpin
+{
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let this = &mut *self;
+        let inner = match &mut this.inner {
+            WsInner::WebSocket(inner) => inner,
+            WsInner::Plain(inner) => return Pin::new(inner).poll_read(cx, buf),
+        };
+        loop {
+            if !this.read_buf.is_empty() {
+                let n = cmp::min(buf.remaining(), this.read_buf.len());
+                let chunk = this.read_buf.split_to(n);
+                buf.put_slice(&chunk);
+                return Poll::Ready(Ok(()));
+            }
+
+            match ready!(Pin::new(&mut *inner).poll_next(cx)) {
+                Some(Ok(Message::Binary(data))) => {
+                    this.read_buf = Bytes::from(data);
+                },
+                Some(Ok(Message::Close(_))) |
+                None |
+                Some(Err(tungstenite::Error::ConnectionClosed)) |
+                Some(Err(tungstenite::Error::AlreadyClosed)) => {
+                    // End of stream
+                    return Poll::Ready(Ok(()));
+                },
+                Some(Ok(Message::Text(_))) => {
+                    return Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "Unexpected text message on WebSocket connection",
+                    )));
+                },
+                // Pings are answered by the WebSocket implementation
+                Some(Ok(_)) => {},
+                Some(Err(err)) => return Poll::Ready(Err(into_io_error(err))),
+            }
+        }
+    }
+}
+
+impl<S> AsyncWrite for WsStream<S>
+where S: AsyncRead + AsyncWrite + Unpin
+{
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        match &mut self.inner {
+            WsInner::WebSocket(inner) => {
+                ready!(Pin::new(&mut *inner).poll_ready(cx)).map_err(into_io_error)?;
+                Pin::new(inner)
+                    .start_send(Message::Binary(buf.to_vec()))
+                    .map_err(into_io_error)?;
+                Poll::Ready(Ok(buf.len()))
+            },
+            WsInner::Plain(inner) => Pin::new(inner).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match &mut self.inner {
+            WsInner::WebSocket(inner) => Pin::new(inner).poll_flush(cx).map_err(into_io_error),
+            WsInner::Plain(inner) => Pin::new(inner).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match &mut self.inner {
+            WsInner::WebSocket(inner) => Pin::new(inner).poll_close(cx).map_err(into_io_error),
+            WsInner::Plain(inner) => Pin::new(inner).poll_shutdown(cx),
+        }
+    }
+}
+
+fn into_io_error(err: tungstenite::Error) -> io::Error {
+    match err {
+        tungstenite::Error::Io(err) => err,
+        tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed => {
+            io::Error::new(io::ErrorKind::BrokenPipe, err)
+        },
+        err => io::Error::new(io::ErrorKind::Other, err),
+    }
+}
+
+fn is_ws_address(addr: &Multiaddr) -> bool {
+    matches!(addr.iter().last(), Some(Protocol::Ws(_)))
+}
+
+/// Splits a WebSocket address into the TCP address to connect to and the WebSocket path
+fn split_ws_address(addr: &Multiaddr) -> io::Result<(Multiaddr, Cow<'static, str>)> {
+    let mut tcp_addr = addr.clone();
+    match tcp_addr.pop() {
+        Some(Protocol::Ws(path)) => Ok((tcp_addr, path)),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Expected a WebSocket address ending in /ws but got '{}'", addr),
+        )),
+    }
+}
+
+/// Returns the `host:port` of a TCP/IP or DNS address, for use in the WebSocket URL
+fn host_and_port(addr: &Multiaddr) -> io::Result<String> {
+    let mut iter = addr.iter();
+    match (iter.next(), iter.next()) {
+        (Some(Protocol::Ip4(ip)), Some(Protocol::Tcp(port))) => Ok(format!("{}:{}", ip, port)),
+        (Some(Protocol::Ip6(ip)), Some(Protocol::Tcp(port))) => Ok(format!("[{}]:{}", ip, port)),
+        (Some(Protocol::Dns(host)), Some(Protocol::Tcp(port))) |
+        (Some(Protocol::Dns4(host)), Some(Protocol::Tcp(port))) |
+        (Some(Protocol::Dns6(host)), Some(Protocol::Tcp(port))) => Ok(format!("{}:{}", host, port)),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Unsupported WebSocket address '{}'", addr),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use super::*;
+    use crate::runtime;
+
+    #[test]
+    fn split_address() {
+        let addr = "/ip4/127.0.0.1/tcp/8080/ws".parse().unwrap();
+        let (tcp_addr, path) = split_ws_address(&addr).unwrap();
+        assert_eq!(tcp_addr, "/ip4/127.0.0.1/tcp/8080".parse().unwrap());
+        assert_eq!(path, "/");
+        assert_eq!(host_and_port(&tcp_addr).unwrap(), "127.0.0.1:8080");
+
+        let addr = "/ip4/127.0.0.1/tcp/8080".parse().unwrap();
+        split_ws_address(&addr).unwrap_err();
+    }
+
+    #[runtime::test]
+    async fn listen_and_dial() {
+        let transport = WebSocketTransport::new();
+        let (mut listener, addr) = transport
+            .listen("/ip4/127.0.0.1/tcp/0/ws".parse().unwrap())
+            .await
+            .unwrap();
+        assert!(matches!(addr.iter().last(), Some(Protocol::Ws(_))));
+
+        // The listener performs the upgrade so it must be polled while dialing
+        let (outbound, inbound) = tokio::join!(transport.dial(addr), listener.next());
+        let mut outbound = outbound.unwrap();
+        let (mut inbound, _) = inbound.unwrap().unwrap();
+
+        outbound.write_all(b"HELLO").await.unwrap();
+        outbound.flush().await.unwrap();
+        let mut buf = [0u8; 5];
+        inbound.read_exact(&mut buf).await.unwrap();
+        assert_eq!(buf, *b"HELLO");
+
+        outbound.shutdown().await.unwrap();
+        assert_eq!(inbound.read(&mut buf).await.unwrap(), 0);
+    }
+
+    #[runtime::test]
+    async fn dial_tcp_peer() {
+        let tcp_transport = TcpTransport::new();
+        let (mut listener, addr) = tcp_transport
+            .listen("/ip4/127.0.0.1/tcp/0".parse().unwrap())
+            .await
+            .unwrap();
+
+        // A peer without a WebSocket address is dialed over plain TCP
+        let transport = WebSocketTransport::new();
+        let (outbound, inbound) = tokio::join!(transport.dial(addr), listener.next());
+        let mut outbound = outbound.unwrap();
+        let (mut inbound, _) = inbound.unwrap().unwrap();
+
+        outbound.write_all(b"HELLO").await.unwrap();
+        outbound.flush().await.unwrap();
+        let mut buf = [0u8; 5];
+        inbound.read_exact(&mut buf).await.unwrap();
+        assert_eq!(buf, *b"HELLO");
+
+        inbound.write_all(b"WORLD").await.unwrap();
+        outbound.read_exact(&mut buf).await.unwrap();
+        assert_eq!(buf, *b"WORLD");
+    }
+}
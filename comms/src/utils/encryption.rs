@@ -0,0 +1,195 @@
+// Copyright 2022, The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Passphrase-based authenticated encryption for data stored at rest.
+//!
+//! The passphrase is hashed with Argon2 so that it can be verified when the database is opened, and a separate
+//! Argon2 derivation (using its own salt) produces the AES-256-GCM key. Each record is encrypted with a random nonce
+//! which is prepended to the ciphertext. Callers supply associated data (typically the record key) so that
+//! ciphertexts cannot be swapped between records without detection.
+
+use aes_gcm::{
+    aead::{generic_array::GenericArray, Aead, Payload},
+    Aes256Gcm,
+    NewAead,
+};
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use rand::RngCore;
+use serde_derive::{Deserialize, Serialize};
+use thiserror::Error;
+
+pub const AES_NONCE_BYTES: usize = 12;
+pub const AES_KEY_BYTES: usize = 32;
+
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum DatabaseEncryptionError {
+    #[error("The passphrase is incorrect")]
+    InvalidPassphrase,
+    #[error("Failed to hash passphrase: {0}")]
+    PassphraseHashError(String),
+    #[error("Failed to encrypt record")]
+    EncryptionFailed,
+    #[error("Failed to decrypt record. The record is corrupt or has been tampered with")]
+    DecryptionFailed,
+}
+
+/// The parameters needed to verify a passphrase and derive the encryption key from it. These are stored alongside
+/// the encrypted data and are not secret.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncryptionParams {
+    pub passphrase_hash: String,
+    pub encryption_salt: String,
+}
+
+impl EncryptionParams {
+    /// Generate new random salts for the given passphrase, returning the parameters to store and the derived cipher.
+    pub fn generate(passphrase: &str) -> Result<(Self, DatabaseCipher), DatabaseEncryptionError> {
+        let argon2 = Argon2::default();
+        let passphrase_salt = SaltString::generate(&mut OsRng);
+        let passphrase_hash = argon2
+            .hash_password_simple(passphrase.as_bytes(), &passphrase_salt)
+            .map_err(|e| DatabaseEncryptionError::PassphraseHashError(e.to_string()))?
+            .to_string();
+        let encryption_salt = SaltString::generate(&mut OsRng);
+
+        let params = Self {
+            passphrase_hash,
+            encryption_salt: encryption_salt.as_str().to_string(),
+        };
+        let cipher = params.derive_cipher(&argon2, passphrase)?;
+        Ok((params, cipher))
+    }
+
+    /// Verify the passphrase against the stored hash and derive the cipher. Returns
+    /// `DatabaseEncryptionError::InvalidPassphrase` if the passphrase does not match.
+    pub fn unlock(&self, passphrase: &str) -> Result<DatabaseCipher, DatabaseEncryptionError> {
+        let argon2 = Argon2::default();
+        let stored_hash = PasswordHash::new(&self.passphrase_hash)
+            .map_err(|e| DatabaseEncryptionError::PassphraseHashError(e.to_string()))?;
+        argon2
+            .verify_password(passphrase.as_bytes(), &stored_hash)
+            .map_err(|_| DatabaseEncryptionError::InvalidPassphrase)?;
+        self.derive_cipher(&argon2, passphrase)
+    }
+
+    fn derive_cipher(&self, argon2: &Argon2, passphrase: &str) -> Result<DatabaseCipher, DatabaseEncryptionError> {
+        let derived_encryption_key = argon2
+            .hash_password_simple(passphrase.as_bytes(), self.encryption_salt.as_str())
+            .map_err(|e| DatabaseEncryptionError::PassphraseHashError(e.to_string()))?
+            .hash
+            .ok_or_else(|| {
+                DatabaseEncryptionError::PassphraseHashError("Problem generating encryption key hash".to_string())
+            })?;
+        if derived_encryption_key.as_bytes().len() != AES_KEY_BYTES {
+            return Err(DatabaseEncryptionError::PassphraseHashError(format!(
+                "Derived key is {} bytes, expected {}",
+                derived_encryption_key.as_bytes().len(),
+                AES_KEY_BYTES
+            )));
+        }
+        let key = GenericArray::from_slice(derived_encryption_key.as_bytes());
+        Ok(DatabaseCipher {
+            cipher: Aes256Gcm::new(key),
+        })
+    }
+}
+
+/// AES-256-GCM cipher used to encrypt and authenticate database records.
+#[derive(Clone)]
+pub struct DatabaseCipher {
+    cipher: Aes256Gcm,
+}
+
+impl DatabaseCipher {
+    /// Encrypt `plaintext`, binding it to `aad`. The returned bytes contain the nonce followed by the ciphertext.
+    pub fn encrypt(&self, aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, DatabaseEncryptionError> {
+        let mut nonce = [0u8; AES_NONCE_BYTES];
+        OsRng.fill_bytes(&mut nonce);
+        let nonce_ga = GenericArray::from_slice(&nonce);
+        let mut ciphertext = self
+            .cipher
+            .encrypt(nonce_ga, Payload { msg: plaintext, aad })
+            .map_err(|_| DatabaseEncryptionError::EncryptionFailed)?;
+        let mut ciphertext_integral_nonce = nonce.to_vec();
+        ciphertext_integral_nonce.append(&mut ciphertext);
+        Ok(ciphertext_integral_nonce)
+    }
+
+    /// Decrypt and authenticate bytes previously returned from `encrypt` with the same `aad`.
+    pub fn decrypt(&self, aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, DatabaseEncryptionError> {
+        if ciphertext.len() < AES_NONCE_BYTES {
+            return Err(DatabaseEncryptionError::DecryptionFailed);
+        }
+        let (nonce, ciphertext) = ciphertext.split_at(AES_NONCE_BYTES);
+        let nonce = GenericArray::from_slice(nonce);
+        self.cipher
+            .decrypt(nonce, Payload { msg: ciphertext, aad })
+            .map_err(|_| DatabaseEncryptionError::DecryptionFailed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn encrypt_decrypt() {
+        let (params, cipher) = EncryptionParams::generate("correct horse").unwrap();
+        let ciphertext = cipher.encrypt(b"key", b"The quick brown fox").unwrap();
+        assert_ne!(&ciphertext[AES_NONCE_BYTES..], b"The quick brown fox");
+
+        let cipher = params.unlock("correct horse").unwrap();
+        let plaintext = cipher.decrypt(b"key", &ciphertext).unwrap();
+        assert_eq!(plaintext, b"The quick brown fox");
+    }
+
+    #[test]
+    fn it_rejects_an_incorrect_passphrase() {
+        let (params, _) = EncryptionParams::generate("correct horse").unwrap();
+        let err = params.unlock("battery staple").err().unwrap();
+        assert_eq!(err, DatabaseEncryptionError::InvalidPassphrase);
+    }
+
+    #[test]
+    fn it_detects_tampering() {
+        let (_, cipher) = EncryptionParams::generate("correct horse").unwrap();
+        let mut ciphertext = cipher.encrypt(b"key", b"The quick brown fox").unwrap();
+        assert_eq!(
+            cipher.decrypt(b"other key", &ciphertext).unwrap_err(),
+            DatabaseEncryptionError::DecryptionFailed
+        );
+
+        let last = ciphertext.len() - 1;
+        ciphertext[last] ^= 0x01;
+        assert_eq!(
+            cipher.decrypt(b"key", &ciphertext).unwrap_err(),
+            DatabaseEncryptionError::DecryptionFailed
+        );
+        assert_eq!(
+            cipher.decrypt(b"key", &[1, 2, 3]).unwrap_err(),
+            DatabaseEncryptionError::DecryptionFailed
+        );
+    }
+}
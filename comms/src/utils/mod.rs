@@ -23,6 +23,7 @@
 pub mod atomic_ref_counter;
 pub mod cidr;
 pub mod datetime;
+pub mod encryption;
 pub mod mpsc;
 pub mod multiaddr;
 pub mod signature;
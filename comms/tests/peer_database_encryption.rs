@@ -0,0 +1,163 @@
+//  Copyright 2022, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::sync::Arc;
+
+use rand::rngs::OsRng;
+use tari_comms::{
+    peer_manager::{Peer, PeerFeatures, PeerManagerError},
+    types::CommsDatabase,
+    CommsBuilder,
+    CommsBuilderError,
+    NodeIdentity,
+    UnspawnedCommsNode,
+};
+use tari_shutdown::Shutdown;
+use tari_storage::{
+    lmdb_store::{LMDBBuilder, LMDBConfig},
+    IterationResult,
+    LMDBWrapper,
+};
+use tari_test_utils::{paths::create_temporary_data_path, random};
+
+const MIGRATION_VERSION_KEY: u64 = u64::MAX;
+
+fn create_peer_storage() -> CommsDatabase {
+    let database_name = random::string(8);
+    let datastore = LMDBBuilder::new()
+        .set_path(create_temporary_data_path())
+        .set_env_config(LMDBConfig::default())
+        .set_max_number_of_databases(1)
+        .add_database(&database_name, lmdb_zero::db::CREATE)
+        .build()
+        .unwrap();
+
+    let peer_database = datastore.get_handle(&database_name).unwrap();
+    LMDBWrapper::new(Arc::new(peer_database))
+}
+
+fn build_comms(
+    shutdown: &Shutdown,
+    storage: &CommsDatabase,
+    passphrase: Option<&str>,
+) -> Result<UnspawnedCommsNode, CommsBuilderError> {
+    let node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        "/ip4/127.0.0.1/tcp/0".parse().unwrap(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+
+    let builder = CommsBuilder::new()
+        .allow_test_addresses()
+        .with_listener_address("/ip4/127.0.0.1/tcp/0".parse().unwrap())
+        .with_node_identity(node_identity)
+        .with_peer_storage(LMDBWrapper::new(storage.inner()), None)
+        .with_shutdown_signal(shutdown.to_signal());
+    match passphrase {
+        Some(passphrase) => builder.with_peer_storage_passphrase(passphrase.to_string()).build(),
+        None => builder.build(),
+    }
+}
+
+fn random_peer_identity() -> NodeIdentity {
+    NodeIdentity::random(
+        &mut OsRng,
+        "/ip4/127.0.0.1/tcp/9000".parse().unwrap(),
+        PeerFeatures::COMMUNICATION_NODE,
+    )
+}
+
+fn assert_no_plaintext_peers(storage: &CommsDatabase) {
+    let database = storage.inner();
+    database
+        .for_each::<u64, Vec<u8>, _>(|result| {
+            if let Ok((key, bytes)) = result {
+                if key < MIGRATION_VERSION_KEY - 1 {
+                    assert!(bincode::deserialize::<Peer>(&bytes).is_err());
+                }
+            }
+            IterationResult::Continue
+        })
+        .unwrap();
+}
+
+#[tokio::test]
+async fn it_opens_an_encrypted_database_with_the_passphrase() {
+    let shutdown = Shutdown::new();
+    let storage = create_peer_storage();
+    let peer_identity = random_peer_identity();
+
+    let comms = build_comms(&shutdown, &storage, Some("correct horse")).unwrap();
+    comms.peer_manager().add_peer(peer_identity.to_peer()).await.unwrap();
+    drop(comms);
+    assert_no_plaintext_peers(&storage);
+
+    let result = build_comms(&shutdown, &storage, Some("battery staple"));
+    assert!(matches!(
+        result,
+        Err(CommsBuilderError::PeerManagerError(PeerManagerError::EncryptionError(
+            _
+        )))
+    ));
+
+    let result = build_comms(&shutdown, &storage, None);
+    assert!(matches!(
+        result,
+        Err(CommsBuilderError::PeerManagerError(
+            PeerManagerError::PeerDatabaseEncrypted
+        ))
+    ));
+
+    let comms = build_comms(&shutdown, &storage, Some("correct horse")).unwrap();
+    assert!(comms.peer_manager().exists(peer_identity.public_key()).await);
+}
+
+#[tokio::test]
+async fn it_migrates_an_encrypted_database() {
+    let shutdown = Shutdown::new();
+    let storage = create_peer_storage();
+    let peer_identity = random_peer_identity();
+
+    let comms = build_comms(&shutdown, &storage, Some("correct horse")).unwrap();
+    comms.peer_manager().add_peer(peer_identity.to_peer()).await.unwrap();
+    drop(comms);
+
+    let latest_version = storage.inner().get::<_, u32>(&MIGRATION_VERSION_KEY).unwrap().unwrap();
+    storage
+        .inner()
+        .insert(&MIGRATION_VERSION_KEY, &(latest_version - 1))
+        .unwrap();
+
+    let result = build_comms(&shutdown, &storage, None);
+    assert!(matches!(
+        result,
+        Err(CommsBuilderError::PeerManagerError(
+            PeerManagerError::PeerDatabaseEncrypted
+        ))
+    ));
+
+    let comms = build_comms(&shutdown, &storage, Some("correct horse")).unwrap();
+    assert!(comms.peer_manager().exists(peer_identity.public_key()).await);
+    let version = storage.inner().get::<_, u32>(&MIGRATION_VERSION_KEY).unwrap();
+    assert_eq!(version, Some(latest_version));
+    assert_no_plaintext_peers(&storage);
+}